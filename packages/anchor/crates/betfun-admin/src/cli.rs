@@ -0,0 +1,644 @@
+//! Command-line arguments and what each command sends
+
+use std::path::PathBuf;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use betfun_client::instructions::{self as ix, CreateArenaArgs, CreateTournamentArgs};
+use betfun_client::pda;
+use betfun_client::accounts::BetFunAccount;
+use betfun_client::allowlist::Allowlist;
+use betfun_client::state::{pause, Arena, CurveType, Dispute, Duel, ScalarRange, StakeWeighting};
+use betfun_keeper::{Cluster, Error, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+
+use crate::inspect::{fetch, list_arenas, show};
+use crate::units::parse_sol;
+
+#[derive(Parser)]
+#[command(name = "betfun-admin", version, about = "Operate BetFun arenas and their markets")]
+pub struct Cli {
+    /// JSON-RPC endpoint
+    #[arg(long, global = true, default_value = "http://127.0.0.1:8899")]
+    pub url: String,
+
+    /// Signer and fee payer; the arena creator or oracle for most commands
+    #[arg(long, global = true)]
+    pub keypair: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+// Parsed once per run, so the size of `CreateArena` does not matter
+#[allow(clippy::large_enum_variant)]
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create the program config with the keypair, the program's upgrade authority, as its admin
+    InitConfig,
+
+    /// Hand the program config to a new admin (admin)
+    TransferAdmin {
+        new_admin: Pubkey,
+    },
+
+    /// Pause activities everywhere (admin) or in one arena (creator or admin)
+    Pause {
+        /// Pause only this arena
+        #[arg(long)]
+        arena: Option<Pubkey>,
+
+        #[arg(value_enum, required = true)]
+        activities: Vec<Activity>,
+    },
+
+    /// Resume paused activities everywhere or in one arena
+    Unpause {
+        /// Resume only this arena
+        #[arg(long)]
+        arena: Option<Pubkey>,
+
+        #[arg(value_enum, required = true)]
+        activities: Vec<Activity>,
+    },
+
+    /// Set the share of creator and protocol fees paid to referrers (admin)
+    ReferralShare {
+        /// Share in basis points, at most 5000
+        share_bps: u16,
+    },
+
+    /// Set the bond creators lock in each new arena, in SOL or in a token (admin)
+    CreatorBond {
+        /// Bond in SOL, at most 100; 0 disables bonds
+        #[arg(value_parser = parse_sol, required_unless_present = "mint")]
+        bond: Option<u64>,
+
+        /// SPL Token mint to bond in instead of SOL
+        #[arg(long, requires = "amount")]
+        mint: Option<Pubkey>,
+
+        /// Bond in base units of the mint
+        #[arg(long, requires = "mint", conflicts_with = "bond")]
+        amount: Option<u64>,
+    },
+
+    /// Set who receives protocol fees and the order book fee per side (admin)
+    ProtocolFees {
+        /// Receives swap and order book protocol fees
+        fee_recipient: Pubkey,
+
+        /// Order book fee charged to each side of a match, at most 100
+        #[arg(long, default_value_t = 30)]
+        order_book_fee_bps: u16,
+    },
+
+    /// Add house liquidity backing parlay payouts (admin)
+    FundParlayVault {
+        /// Amount in SOL
+        #[arg(value_parser = parse_sol)]
+        amount: u64,
+    },
+
+    /// Withdraw parlay vault liquidity not owed to open parlays (admin)
+    WithdrawParlayVault {
+        /// Amount in SOL
+        #[arg(value_parser = parse_sol)]
+        amount: u64,
+    },
+
+    /// Allow an arena to be picked as a parlay leg (admin)
+    ParlayApproval {
+        arena: Pubkey,
+
+        /// Stop new parlays picking the arena instead
+        #[arg(long)]
+        revoke: bool,
+    },
+
+    /// Create an arena owned by the keypair
+    CreateArena {
+        #[arg(long)]
+        title: String,
+
+        #[arg(long, default_value = "")]
+        description: String,
+
+        #[arg(long)]
+        question: String,
+
+        /// Outcome name; repeat for each outcome (2-6)
+        #[arg(long = "outcome", required_unless_present = "lower", conflicts_with = "lower")]
+        outcomes: Vec<String>,
+
+        /// Lower bound of a scalar arena, which trades LONG and SHORT shares
+        #[arg(long, requires = "upper", allow_hyphen_values = true)]
+        lower: Option<i64>,
+
+        /// Upper bound of a scalar arena
+        #[arg(long, requires = "lower", allow_hyphen_values = true)]
+        upper: Option<i64>,
+
+        /// Tag; repeat for each tag
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Entry fee in SOL
+        #[arg(long, value_parser = parse_sol)]
+        entry_fee: u64,
+
+        /// Unix timestamp betting and trading close (defaults to `--hours` from now)
+        #[arg(long, conflicts_with = "hours")]
+        lock_time: Option<i64>,
+
+        /// Hours from now betting and trading close
+        #[arg(long, default_value_t = 24)]
+        hours: i64,
+
+        /// Unix timestamp the arena can be resolved from (defaults to the lock time)
+        #[arg(long)]
+        resolve_after: Option<i64>,
+
+        /// Resolved by hand, with no fixed schedule for the result
+        #[arg(long)]
+        manual_resolve: bool,
+
+        #[arg(long)]
+        oracle: Option<Pubkey>,
+
+        #[arg(long)]
+        token_mint: Option<Pubkey>,
+
+        /// Creator's cut of the pot, in basis points
+        #[arg(long, default_value_t = Arena::DEFAULT_CREATOR_FEE_BPS)]
+        creator_fee_bps: u16,
+
+        /// Creator's cut of share trading volume, in basis points
+        #[arg(long, default_value_t = 0)]
+        trading_fee_bps: u16,
+
+        /// Extra weight of the earliest bets, in basis points, decaying linearly until the lock time
+        #[arg(long, conflicts_with = "lower")]
+        early_bonus_bps: Option<u16>,
+
+        /// Create a duel, staking the entry fee on this outcome as it is created
+        #[arg(long, conflicts_with = "lower")]
+        challenge: Option<u8>,
+
+        /// Only wallet that may accept the duel (anyone, if omitted)
+        #[arg(long, requires = "challenge")]
+        opponent: Option<Pubkey>,
+
+        /// Wallet allowed to join and buy shares; repeat for each member of a private arena
+        #[arg(long = "member")]
+        members: Vec<Pubkey>,
+    },
+
+    /// Replace a private arena's members, or open it to everyone, before it ends (creator)
+    Allowlist {
+        #[arg(long)]
+        arena: Pubkey,
+
+        /// Wallet allowed to join and buy shares; repeat for each member
+        #[arg(long = "member", required_unless_present = "public", conflicts_with = "public")]
+        members: Vec<Pubkey>,
+
+        /// Remove the allowlist
+        #[arg(long)]
+        public: bool,
+    },
+
+    /// Create an outcome's share token and issuance curve
+    CreateShareTokens {
+        #[arg(long)]
+        arena: Pubkey,
+
+        #[arg(long)]
+        outcome_index: u8,
+
+        /// Price at zero supply, in SOL per share
+        #[arg(long, value_parser = parse_sol)]
+        initial_price: u64,
+
+        #[arg(long, value_enum, default_value_t = Curve::Linear)]
+        curve: Curve,
+
+        /// Slope (linear) or doubling supply (exponential); 0 for a fixed price
+        #[arg(long, default_value_t = 0)]
+        curve_param: u64,
+    },
+
+    /// Create an outcome's AMM pool
+    InitPool {
+        #[arg(long)]
+        arena: Pubkey,
+
+        #[arg(long)]
+        outcome_index: u8,
+
+        #[arg(long, default_value_t = 30)]
+        fee_bps: u16,
+
+        #[arg(long, default_value_t = 10)]
+        protocol_fee_bps: u16,
+    },
+
+    /// Create an outcome's limit order book
+    InitOrderBook {
+        #[arg(long)]
+        arena: Pubkey,
+
+        #[arg(long)]
+        outcome_index: u8,
+    },
+
+    /// Resolve an arena as the creator or oracle, by winner or scalar value
+    Resolve {
+        #[arg(long)]
+        arena: Pubkey,
+
+        /// Winning outcome index
+        #[arg(long, required_unless_present = "value", conflicts_with = "value")]
+        winner: Option<u8>,
+
+        /// Reported value of a scalar arena
+        #[arg(long, allow_hyphen_values = true)]
+        value: Option<i64>,
+    },
+
+    /// Void an arena left unresolved past its resolution window, slashing the creator bond
+    Void {
+        #[arg(long)]
+        arena: Pubkey,
+    },
+
+    /// Dispute a bonded arena's resolution within two days of it, depositing one entry fee
+    Dispute {
+        #[arg(long)]
+        arena: Pubkey,
+    },
+
+    /// Uphold an arena's open dispute, slashing the creator bond to bettors (admin)
+    SettleDispute {
+        #[arg(long)]
+        arena: Pubkey,
+
+        /// Reject the dispute instead, paying the deposit to the creator
+        #[arg(long)]
+        reject: bool,
+    },
+
+    /// Withdraw a duel nobody has accepted, taking back the stake and bond (creator)
+    CancelDuel {
+        #[arg(long)]
+        arena: Pubkey,
+    },
+
+    /// Create a tournament organized by the keypair and open its first-round matches
+    CreateTournament {
+        #[arg(long)]
+        name: String,
+
+        /// Competitor in first-round order; repeat for each (4, 8 or 16)
+        #[arg(long = "entrant", required = true)]
+        entrants: Vec<String>,
+
+        /// Fee each entry pays into the tournament pot, in SOL
+        #[arg(long, value_parser = parse_sol)]
+        entry_fee: u64,
+
+        /// Entry fee of every match arena, in SOL
+        #[arg(long, value_parser = parse_sol)]
+        match_entry_fee: u64,
+
+        /// Unix timestamp entries close and first-round matches lock (defaults to `--hours` from now)
+        #[arg(long, conflicts_with = "hours")]
+        lock_time: Option<i64>,
+
+        /// Hours from now entries close
+        #[arg(long, default_value_t = 24)]
+        hours: i64,
+
+        /// Hours each later match stays open after the previous round decides it
+        #[arg(long, default_value_t = 24)]
+        round_hours: i64,
+    },
+
+    /// Decide a tournament match as its organizer, opening the next match once both its sides are known
+    ResolveMatch {
+        #[arg(long)]
+        tournament: Pubkey,
+
+        /// Bracket slot of the match; 0 is the final
+        #[arg(long)]
+        slot: u8,
+
+        /// Winning outcome index, 0 or 1
+        #[arg(long)]
+        winner: u8,
+    },
+
+    /// Cancel one of the keypair's limit orders and refund its escrow
+    CancelOrder {
+        #[arg(long)]
+        arena: Pubkey,
+
+        #[arg(long)]
+        outcome_index: u8,
+
+        #[arg(long)]
+        order_id: u64,
+    },
+
+    /// Upgrade an account written by an older program version
+    Migrate { address: Pubkey },
+
+    /// Summarize any BetFun account
+    Show { address: Pubkey },
+
+    /// List every arena
+    Arenas,
+}
+
+/// Issuance curve shape
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Curve {
+    Linear,
+    Exponential,
+}
+
+impl From<Curve> for CurveType {
+    fn from(curve: Curve) -> Self {
+        match curve {
+            Curve::Linear => CurveType::Linear,
+            Curve::Exponential => CurveType::Exponential,
+        }
+    }
+}
+
+/// Activity that can be paused
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Activity {
+    Betting,
+    Shares,
+    Amm,
+    OrderBook,
+    Claims,
+    All,
+}
+
+impl Activity {
+    fn flags(activities: &[Activity]) -> u8 {
+        activities.iter().fold(0, |flags, activity| {
+            flags
+                | match activity {
+                    Activity::Betting => pause::BETTING,
+                    Activity::Shares => pause::SHARES,
+                    Activity::Amm => pause::AMM,
+                    Activity::OrderBook => pause::ORDER_BOOK,
+                    Activity::Claims => pause::CLAIMS,
+                    Activity::All => pause::ALL,
+                }
+        })
+    }
+}
+
+impl Command {
+    /// Whether the command sends a transaction
+    pub fn signs(&self) -> bool {
+        !matches!(self, Command::Show { .. } | Command::Arenas)
+    }
+}
+
+/// Run `command` with `signer` as the cluster's payer, returning its output
+pub fn run(command: Command, cluster: &mut impl Cluster, signer: &Pubkey) -> Result<String> {
+    let (verb, instruction, address) = match command {
+        Command::Show { address } => return show(cluster, &address),
+        Command::Arenas => return list_arenas(cluster),
+        Command::InitConfig => ("Initialized", ix::initialize_config(signer), pda::config().0),
+        Command::TransferAdmin { new_admin } => {
+            ("Updated", ix::transfer_admin(signer, &new_admin), pda::config().0)
+        }
+        Command::Pause { arena, activities } => {
+            let flags = Activity::flags(&activities);
+            let (instruction, address) = set_pause(cluster, signer, arena, |paused| paused | flags)?;
+            ("Paused", instruction, address)
+        }
+        Command::Unpause { arena, activities } => {
+            let flags = Activity::flags(&activities);
+            let (instruction, address) = set_pause(cluster, signer, arena, |paused| paused & !flags)?;
+            ("Resumed", instruction, address)
+        }
+        Command::ReferralShare { share_bps } => {
+            ("Updated", ix::set_referral_share(signer, share_bps), pda::config().0)
+        }
+        Command::CreatorBond { bond, mint, amount } => {
+            let bond = amount.or(bond).unwrap_or_default();
+            ("Updated", ix::set_creator_bond(signer, bond, mint.as_ref()), pda::config().0)
+        }
+        Command::ProtocolFees { fee_recipient, order_book_fee_bps } => {
+            ("Updated", ix::set_protocol_fees(signer, &fee_recipient, order_book_fee_bps), pda::config().0)
+        }
+        Command::FundParlayVault { amount } => {
+            ("Funded", ix::fund_parlay_vault(signer, amount), pda::parlay_vault().0)
+        }
+        Command::WithdrawParlayVault { amount } => {
+            ("Withdrew from", ix::withdraw_parlay_vault(signer, amount), pda::parlay_vault().0)
+        }
+        Command::ParlayApproval { arena, revoke } => {
+            let verb = if revoke { "Revoked" } else { "Approved" };
+            (verb, ix::set_parlay_approval(&arena, signer, !revoke), arena)
+        }
+        Command::CreateArena {
+            title,
+            description,
+            question,
+            outcomes,
+            lower,
+            upper,
+            tags,
+            entry_fee,
+            lock_time,
+            hours,
+            resolve_after,
+            manual_resolve,
+            oracle,
+            token_mint,
+            creator_fee_bps,
+            trading_fee_bps,
+            early_bonus_bps,
+            challenge,
+            opponent,
+            members,
+        } => {
+            let lock_time = match lock_time {
+                Some(lock_time) => lock_time,
+                None => cluster.unix_timestamp()? + hours * 60 * 60,
+            };
+            let arena = pda::arena(signer, &title).0;
+            let args = CreateArenaArgs {
+                title,
+                description,
+                question,
+                outcomes,
+                tags,
+                entry_fee,
+                lock_time,
+                resolve_after: resolve_after.unwrap_or(lock_time),
+                manual_resolve,
+                oracle,
+                token_mint,
+                creator_fee_bps,
+                creator_trading_fee_bps: trading_fee_bps,
+                scalar: lower.zip(upper).map(|(lower, upper)| ScalarRange { lower, upper }),
+                allowlist_root: allowlist_root(members),
+                stake_weighting: early_bonus_bps
+                    .map_or(StakeWeighting::Flat, |bonus_bps| StakeWeighting::LinearDecay { bonus_bps }),
+                duel: challenge.map(|side| Duel { opponent, side }),
+            };
+            let bond_mint = bond_mint(cluster)?;
+            ("Created", ix::create_arena(signer, args, bond_mint.as_ref()), arena)
+        }
+        Command::Allowlist { arena, members, public: _ } => {
+            ("Updated", ix::set_allowlist(&arena, signer, allowlist_root(members)), arena)
+        }
+        Command::CreateShareTokens { arena, outcome_index, initial_price, curve, curve_param } => (
+            "Created",
+            ix::create_share_tokens(&arena, signer, outcome_index, initial_price, curve.into(), curve_param),
+            pda::outcome_share(&arena, outcome_index).0,
+        ),
+        Command::InitPool { arena, outcome_index, fee_bps, protocol_fee_bps } => (
+            "Initialized",
+            ix::initialize_pool(&arena, signer, outcome_index, fee_bps, protocol_fee_bps),
+            pda::amm_pool(&arena, outcome_index).0,
+        ),
+        Command::InitOrderBook { arena, outcome_index } => (
+            "Initialized",
+            ix::initialize_order_book(&arena, signer, outcome_index),
+            pda::order_book(&arena, outcome_index).0,
+        ),
+        Command::Resolve { arena, winner, value } => {
+            let instruction = match (winner, value) {
+                (Some(winner), _) => ix::resolve_arena(&arena, signer, winner),
+                (None, value) => ix::resolve_scalar_arena(&arena, signer, value.unwrap_or_default()),
+            };
+            ("Resolved", instruction, arena)
+        }
+        Command::Void { arena } => ("Voided", ix::void_arena(&arena, signer), arena),
+        Command::Dispute { arena } => ("Disputed", ix::dispute_arena(&arena, signer), arena),
+        Command::SettleDispute { arena, reject } => {
+            let (disputer, creator) = open_dispute(cluster, &arena)?;
+            let verb = if reject { "Rejected" } else { "Upheld" };
+            (verb, ix::settle_dispute(&arena, signer, &disputer, &creator, !reject), arena)
+        }
+        Command::CancelDuel { arena } => ("Cancelled", ix::cancel_duel(&arena, signer), arena),
+        Command::CreateTournament { name, entrants, entry_fee, match_entry_fee, lock_time, hours, round_hours } => {
+            let lock_time = match lock_time {
+                Some(lock_time) => lock_time,
+                None => cluster.unix_timestamp()? + hours * 60 * 60,
+            };
+            let tournament = pda::tournament(signer, &name).0;
+            let matches = entrants.len().saturating_sub(1);
+            let args = CreateTournamentArgs {
+                name,
+                entrants,
+                entry_fee,
+                match_entry_fee,
+                lock_time,
+                round_duration: round_hours * 60 * 60,
+            };
+            cluster.send(&[ix::create_tournament(signer, args)])?;
+            // First-round matches take the last half of the slots, one transaction each
+            for slot in matches / 2..matches {
+                cluster.send(&[ix::advance_tournament(&tournament, signer, slot as u8)])?;
+            }
+            return Ok(format!("Created {}", show(cluster, &tournament)?));
+        }
+        Command::ResolveMatch { tournament, slot, winner } => {
+            let mut instructions = vec![ix::resolve_tournament_match(&tournament, signer, slot, winner)];
+            if let Some(next) = next_match(cluster, &tournament, slot, winner)? {
+                instructions.push(ix::advance_tournament(&tournament, signer, next));
+            }
+            cluster.send(&instructions)?;
+            return Ok(format!("Resolved {}", show(cluster, &tournament)?));
+        }
+        Command::CancelOrder { arena, outcome_index, order_id } => (
+            "Cancelled",
+            ix::cancel_order(&arena, signer, outcome_index, order_id),
+            pda::limit_order(&arena, outcome_index, order_id).0,
+        ),
+        Command::Migrate { address } => ("Migrated", ix::migrate_account(&address, signer), address),
+    };
+    cluster.send(&[instruction])?;
+    Ok(format!("{verb} {}", show(cluster, &address)?))
+}
+
+/// Root of the allowlist of `members`, or `None` for a public arena
+fn allowlist_root(members: Vec<Pubkey>) -> Option<[u8; 32]> {
+    (!members.is_empty()).then(|| Allowlist::new(members).root())
+}
+
+/// Mint new arenas lock their creator bond in, if the config bonds in a token
+fn bond_mint(cluster: &impl Cluster) -> Result<Option<Pubkey>> {
+    let address = pda::config().0;
+    match fetch(cluster, &address)? {
+        BetFunAccount::GlobalConfig(config) => Ok(config.bond_mint.filter(|_| config.creator_bond > 0)),
+        account => {
+            let reason = format!("expected GlobalConfig, found {}", account.name());
+            Err(Error::Account { address, reason })
+        }
+    }
+}
+
+/// Disputer and creator of `arena`'s open dispute
+fn open_dispute(cluster: &impl Cluster, address: &Pubkey) -> Result<(Pubkey, Pubkey)> {
+    let reason = match fetch(cluster, address)? {
+        BetFunAccount::Arena(arena) => match arena.dispute {
+            Dispute::Open { disputer } => return Ok((disputer, arena.creator)),
+            _ => "arena has no open dispute".to_string(),
+        },
+        account => format!("expected Arena, found {}", account.name()),
+    };
+    Err(Error::Account { address: *address, reason })
+}
+
+/// Slot whose match `winner` of `slot` completes, if it can be created right after
+fn next_match(cluster: &impl Cluster, tournament: &Pubkey, slot: u8, winner: u8) -> Result<Option<u8>> {
+    let mut tournament = match fetch(cluster, tournament)? {
+        BetFunAccount::Tournament(tournament) => tournament,
+        account => {
+            let reason = format!("expected Tournament, found {}", account.name());
+            return Err(Error::Account { address: *tournament, reason });
+        }
+    };
+    let Some(result) = tournament.results.get_mut(slot as usize) else {
+        return Ok(None);
+    };
+    *result = winner.saturating_add(1);
+    let next = (slot as usize).checked_sub(1).map(|slot| slot / 2);
+    Ok(next.filter(|next| tournament.competitors(*next).is_some()).map(|next| next as u8))
+}
+
+/// Instruction replacing the pause flags of the config, or of `arena` if given
+fn set_pause(
+    cluster: &impl Cluster,
+    signer: &Pubkey,
+    arena: Option<Pubkey>,
+    update: impl Fn(u8) -> u8,
+) -> Result<(Instruction, Pubkey)> {
+    let address = arena.unwrap_or_else(|| pda::config().0);
+    let paused = match (arena, fetch(cluster, &address)?) {
+        (None, BetFunAccount::GlobalConfig(config)) => config.paused,
+        (Some(_), BetFunAccount::Arena(arena)) => arena.paused,
+        (_, account) => {
+            let expected = if arena.is_some() { "Arena" } else { "GlobalConfig" };
+            let reason = format!("expected {expected}, found {}", account.name());
+            return Err(Error::Account { address, reason });
+        }
+    };
+    let instruction = match arena {
+        Some(arena) => ix::set_arena_pause(&arena, signer, update(paused)),
+        None => ix::set_global_pause(signer, update(paused)),
+    };
+    Ok((instruction, address))
+}
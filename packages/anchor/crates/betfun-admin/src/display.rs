@@ -0,0 +1,431 @@
+//! Human-readable account summaries
+
+use std::fmt::{Display, Write};
+
+use anchor_lang::prelude::Pubkey;
+use betfun_client::accounts::BetFunAccount;
+use betfun_client::state::{
+    pause, AMMPool, Arena, Dispute, GlobalConfig, LimitOrder, LiquidityPosition, OrderBook, OrderStatus, OutcomeShare,
+    Parlay, ParlayStatus, ParlayVault, Participant, PriceHistory, Referral, ShareBalance, StakeWeighting, Tournament,
+    TournamentEntry, Trade, UserProfile,
+};
+use betfun_client::math::payout::ODDS_ONE;
+
+use crate::units::{format_bps, format_sol, format_timestamp};
+
+/// Labelled rows under an `<Kind> <address>` heading
+struct Summary {
+    text: String,
+}
+
+impl Summary {
+    fn new(kind: &str, address: &Pubkey) -> Self {
+        Self { text: format!("{kind} {address}\n") }
+    }
+
+    fn row(mut self, label: &str, value: impl Display) -> Self {
+        let _ = writeln!(self.text, "  {:<18}{value}", format!("{label}:"));
+        self
+    }
+
+    fn line(mut self, line: impl Display) -> Self {
+        let _ = writeln!(self.text, "    {line}");
+        self
+    }
+}
+
+/// Summary of any BetFun account, with times relative to `now`
+pub fn describe(address: &Pubkey, account: &BetFunAccount, now: i64) -> String {
+    let mut text = describe_fields(address, account, now);
+    let migration = if account.is_current() { "" } else { ", run migrate" };
+    let version = format!("  {:<18}{}{migration}\n", "version:", account.version());
+    let heading_end = text.find('\n').map_or(text.len(), |end| end + 1);
+    text.insert_str(heading_end, &version);
+    text
+}
+
+fn describe_fields(address: &Pubkey, account: &BetFunAccount, now: i64) -> String {
+    match account {
+        BetFunAccount::GlobalConfig(config) => describe_config(address, config),
+        BetFunAccount::Arena(arena) => describe_arena(address, arena, now),
+        BetFunAccount::Participant(participant) => describe_participant(address, participant),
+        BetFunAccount::Referral(referral) => describe_referral(address, referral),
+        BetFunAccount::UserProfile(profile) => describe_user_profile(address, profile),
+        BetFunAccount::Parlay(parlay) => describe_parlay(address, parlay),
+        BetFunAccount::ParlayVault(vault) => describe_parlay_vault(address, vault),
+        BetFunAccount::Tournament(tournament) => describe_tournament(address, tournament, now),
+        BetFunAccount::TournamentEntry(entry) => describe_tournament_entry(address, entry),
+        BetFunAccount::OutcomeShare(share) => describe_outcome_share(address, share),
+        BetFunAccount::ShareBalance(balance) => describe_share_balance(address, balance),
+        BetFunAccount::PriceHistory(history) => describe_price_history(address, history),
+        BetFunAccount::AMMPool(pool) => describe_pool(address, pool),
+        BetFunAccount::LiquidityPosition(position) => describe_liquidity_position(address, position),
+        BetFunAccount::OrderBook(book) => describe_order_book(address, book),
+        BetFunAccount::LimitOrder(order) => describe_limit_order(address, order, now),
+        BetFunAccount::Trade(trade) => describe_trade(address, trade),
+    }
+}
+
+/// `open until ...`, `locked until ...`, `locked ..., awaiting resolution`,
+/// `resolved: 1 "Away"`, `voided` or, for scalar arenas, `resolved at 42`
+pub fn arena_status(arena: &Arena, now: i64) -> String {
+    if arena.voided {
+        return "voided, refunding bets".to_string();
+    }
+    if let (true, Some(value)) = (arena.resolved, arena.resolved_value) {
+        return format!("resolved at {value}");
+    }
+    match arena.winner_outcome {
+        Some(winner) if arena.resolved => format!("resolved: {winner} {:?}", outcome_name(arena, winner)),
+        _ if !arena.is_locked(now) => format!("open until {}", format_timestamp(arena.lock_time)),
+        _ if arena.is_unaccepted_duel() && arena.can_void(now) => "challenge unaccepted, can be voided".to_string(),
+        _ if !arena.can_resolve(now) => format!("locked until {}", format_timestamp(arena.resolvable_from())),
+        _ => format!("locked {}, awaiting resolution", format_timestamp(arena.lock_time)),
+    }
+}
+
+/// Paused activities by name, e.g. `amm, order book`
+pub fn paused_activities(paused: u8) -> String {
+    let names = [
+        (pause::BETTING, "betting"),
+        (pause::SHARES, "shares"),
+        (pause::AMM, "amm"),
+        (pause::ORDER_BOOK, "order book"),
+        (pause::CLAIMS, "claims"),
+    ];
+    let paused: Vec<_> = names.iter().filter(|(flag, _)| paused & flag != 0).map(|(_, name)| *name).collect();
+    if paused.is_empty() {
+        return "nothing".to_string();
+    }
+    paused.join(", ")
+}
+
+fn outcome_name(arena: &Arena, index: u8) -> &str {
+    arena.outcomes.get(index as usize).map_or("?", String::as_str)
+}
+
+fn optional(key: Option<Pubkey>) -> String {
+    key.map_or_else(|| "none".to_string(), |key| key.to_string())
+}
+
+/// A creator bond in SOL, or in base units of its token
+fn format_bond(bond: u64, bond_mint: Option<Pubkey>) -> String {
+    match bond_mint {
+        Some(mint) => format!("{bond} base units of {mint}"),
+        None => format_sol(bond),
+    }
+}
+
+fn describe_config(address: &Pubkey, config: &GlobalConfig) -> String {
+    Summary::new("GlobalConfig", address)
+        .row("admin", config.admin)
+        .row("paused", paused_activities(config.paused))
+        .row("referral share", format_bps(config.referral_share_bps))
+        .row("creator bond", format_bond(config.creator_bond, config.bond_mint))
+        .row("fee recipient", config.protocol_fee_recipient())
+        .row("order book fee", format_bps(config.order_book_fee_bps))
+        .text
+}
+
+fn describe_arena(address: &Pubkey, arena: &Arena, now: i64) -> String {
+    let width = arena.outcomes.iter().map(String::len).max().unwrap_or(0);
+    let mut summary = Summary::new("Arena", address)
+        .row("title", &arena.title)
+        .row("question", &arena.question)
+        .row("creator", arena.creator)
+        .row("status", arena_status(arena, now))
+        .row("paused", paused_activities(arena.paused))
+        .row("entry fee", format_sol(arena.entry_fee))
+        .row("pot", format!("{} from {} participants", format_sol(arena.pot), arena.participants_count))
+        .row("paid out", format_sol(arena.total_paid_out))
+        .row("creator fee", format_bps(arena.creator_fee_bps))
+        .row("trading fee", format_bps(arena.creator_trading_fee_bps))
+        .row("referral fees", format_sol(arena.referral_fees))
+        .row("oracle", optional(arena.oracle))
+        .row("created", format_timestamp(arena.created_at));
+    if arena.creator_bond > 0 {
+        summary = summary.row("creator bond", format_bond(arena.creator_bond, arena.bond_mint));
+    }
+    match arena.dispute {
+        Dispute::Undisputed => {}
+        Dispute::Open { disputer } => summary = summary.row("dispute", format!("open, raised by {disputer}")),
+        Dispute::Rejected => summary = summary.row("dispute", "rejected"),
+        Dispute::Upheld => summary = summary.row("dispute", "upheld, bond slashed to bettors"),
+    }
+    if arena.parlay_approved {
+        summary = summary.row("parlay legs", "approved");
+    }
+    if let Some(duel) = arena.duel {
+        let terms = match duel.opponent {
+            Some(opponent) => format!("challenge to {opponent}"),
+            None => "open challenge".to_string(),
+        };
+        let state = if arena.is_unaccepted_duel() { "awaiting a match" } else { "accepted" };
+        summary = summary.row("duel", format!("{terms}, {state}"));
+    }
+    if let StakeWeighting::LinearDecay { bonus_bps } = arena.stake_weighting {
+        summary = summary.row("early bonus", format!("up to {}, decaying until lock", format_bps(bonus_bps)));
+    }
+    if let Some(range) = arena.scalar {
+        summary = summary.row("range", format!("{} to {}", range.lower, range.upper));
+    }
+    if let Some(root) = arena.allowlist_root {
+        let root: String = root.iter().map(|byte| format!("{byte:02x}")).collect();
+        summary = summary.row("allowlist", root);
+    }
+    summary = summary.row("outcomes", "");
+    for (index, name) in arena.outcomes.iter().enumerate() {
+        let count = arena.outcome_counts.get(index).copied().unwrap_or(0);
+        let pot = arena.outcome_pots.get(index).copied().unwrap_or(0);
+        let winner = if arena.resolved && arena.winner_outcome == Some(index as u8) { "  (winner)" } else { "" };
+        summary = summary.line(format!("{index}  {name:<width$}  {count:>4} bets  {}{winner}", format_sol(pot)));
+    }
+    summary.text
+}
+
+fn describe_participant(address: &Pubkey, participant: &Participant) -> String {
+    Summary::new("Participant", address)
+        .row("arena", participant.arena)
+        .row("wallet", participant.wallet)
+        .row("outcome", participant.outcome_chosen)
+        .row("stake", format_sol(participant.amount))
+        .row("claimed", participant.claimed)
+        .row("joined", format_timestamp(participant.joined_at))
+        .row("trophy mint", optional(participant.trophy_mint))
+        .text
+}
+
+fn describe_referral(address: &Pubkey, referral: &Referral) -> String {
+    Summary::new("Referral", address)
+        .row("user", referral.user)
+        .row("referrer", referral.referrer)
+        .row("pending rewards", format_sol(referral.pending_rewards))
+        .row("total earned", format_sol(referral.total_earned))
+        .row("since", format_timestamp(referral.created_at))
+        .text
+}
+
+fn describe_user_profile(address: &Pubkey, profile: &UserProfile) -> String {
+    Summary::new("UserProfile", address)
+        .row("user", profile.user)
+        .row("arenas", format!("{} joined, {} won", profile.arenas_joined, profile.arenas_won))
+        .row("staked", format_sol(profile.total_staked))
+        .row("won", format_sol(profile.total_won))
+        .row("realized pnl", format!("{} lamports", profile.realized_pnl))
+        .row("trophies", profile.trophies)
+        .row("since", format_timestamp(profile.created_at))
+        .row("last active", format_timestamp(profile.last_active_at))
+        .text
+}
+
+fn describe_parlay(address: &Pubkey, parlay: &Parlay) -> String {
+    let status = match parlay.status {
+        ParlayStatus::Open => "open".to_string(),
+        ParlayStatus::Won => format!("won {}", format_timestamp(parlay.settled_at)),
+        ParlayStatus::Lost => format!("lost {}", format_timestamp(parlay.settled_at)),
+    };
+    let mut summary = Summary::new("Parlay", address)
+        .row("owner", parlay.owner)
+        .row("parlay id", parlay.parlay_id)
+        .row("stake", format_sol(parlay.stake))
+        .row("payout", format_sol(parlay.payout))
+        .row("status", status)
+        .row("placed", format_timestamp(parlay.created_at))
+        .row("legs", "");
+    for leg in &parlay.legs {
+        let odds = leg.odds as f64 / ODDS_ONE as f64;
+        summary = summary.line(format!("{}  outcome {} at {odds:.2}x", leg.arena, leg.outcome));
+    }
+    summary.text
+}
+
+fn describe_parlay_vault(address: &Pubkey, vault: &ParlayVault) -> String {
+    Summary::new("ParlayVault", address)
+        .row("liability", format!("{} on {} open parlays", format_sol(vault.total_liability), vault.open_parlays))
+        .row("staked", format_sol(vault.total_staked))
+        .row("paid out", format_sol(vault.total_paid_out))
+        .text
+}
+
+fn describe_tournament(address: &Pubkey, tournament: &Tournament, now: i64) -> String {
+    let status = if tournament.cancelled {
+        "cancelled, refunding entries".to_string()
+    } else if !tournament.is_decided() {
+        let pending = tournament.results.iter().filter(|result| **result == 0).count();
+        format!("{pending} of {} matches undecided", tournament.results.len())
+    } else if now < tournament.scoring_ends_at {
+        format!("decided, scores accepted until {}", format_timestamp(tournament.scoring_ends_at))
+    } else {
+        format!("finished, best score {} shared by {}", tournament.best_score, tournament.best_count)
+    };
+    let mut summary = Summary::new("Tournament", address)
+        .row("name", &tournament.name)
+        .row("organizer", tournament.organizer)
+        .row("entry fee", format_sol(tournament.entry_fee))
+        .row("match entry fee", format_sol(tournament.match_entry_fee))
+        .row("pot", format!("{} from {} entries", format_sol(tournament.pot), tournament.entries_count))
+        .row("entries close", format_timestamp(tournament.lock_time))
+        .row("status", status)
+        .row("matches", "");
+    for slot in 0..tournament.arenas.len() {
+        let name = |entrant: Option<usize>| entrant.map_or("?", |entrant| &tournament.entrants[entrant]);
+        let competitors = tournament.competitors(slot);
+        let arena = match tournament.arenas[slot] {
+            arena if arena == Pubkey::default() => "not created".to_string(),
+            arena => arena.to_string(),
+        };
+        let mut line = format!(
+            "{}  {} vs {}  {arena}",
+            Tournament::match_title(slot as u8),
+            name(competitors.map(|competitors| competitors[0])),
+            name(competitors.map(|competitors| competitors[1])),
+        );
+        if let Some(winner) = tournament.winner(slot) {
+            line.push_str(&format!(", won by {}", tournament.entrants[winner]));
+        }
+        summary = summary.line(line);
+    }
+    summary.text
+}
+
+fn describe_tournament_entry(address: &Pubkey, entry: &TournamentEntry) -> String {
+    let score = if entry.submitted { entry.score.to_string() } else { "not submitted".to_string() };
+    Summary::new("TournamentEntry", address)
+        .row("tournament", entry.tournament)
+        .row("wallet", entry.wallet)
+        .row("score", score)
+        .row("claimed", entry.claimed)
+        .row("entered", format_timestamp(entry.entered_at))
+        .text
+}
+
+fn describe_outcome_share(address: &Pubkey, share: &OutcomeShare) -> String {
+    Summary::new("OutcomeShare", address)
+        .row("arena", share.arena)
+        .row("outcome", share.outcome_index)
+        .row("mint", share.token_mint)
+        .row("supply", share.total_supply)
+        .row("price", format!("{} per share", format_sol(share.current_price)))
+        .row(
+            "curve",
+            format!(
+                "{:?} from {}, param {}",
+                share.curve.curve_type,
+                format_sol(share.curve.base_price),
+                share.curve.param
+            ),
+        )
+        .row("trades", share.trade_count)
+        .text
+}
+
+fn describe_share_balance(address: &Pubkey, balance: &ShareBalance) -> String {
+    Summary::new("ShareBalance", address)
+        .row("owner", balance.owner)
+        .row("outcome share", balance.outcome_share)
+        .row("balance", balance.balance)
+        .row("cost basis", format!("{} per share", format_sol(balance.avg_cost_basis)))
+        .row("invested", format_sol(balance.total_invested))
+        .row("realized pnl", format!("{} lamports", balance.realized_pnl))
+        .text
+}
+
+fn describe_price_history(address: &Pubkey, history: &PriceHistory) -> String {
+    let latest = history.candles.get(history.head as usize).filter(|candle| candle.trades > 0);
+    let summary = Summary::new("PriceHistory", address)
+        .row("arena", history.arena)
+        .row("outcome", history.outcome_index)
+        .row("candles", history.candles.iter().filter(|candle| candle.trades > 0).count());
+    match latest {
+        Some(candle) => summary
+            .row("latest hour", format_timestamp(candle.start_time))
+            .row(
+                "ohlc",
+                format!("{} / {} / {} / {}", candle.open, candle.high, candle.low, candle.close),
+            )
+            .row("volume", format!("{} in {} trades", format_sol(candle.volume), candle.trades))
+            .text,
+        None => summary.text,
+    }
+}
+
+fn describe_pool(address: &Pubkey, pool: &AMMPool) -> String {
+    Summary::new("AMMPool", address)
+        .row("arena", pool.arena)
+        .row("outcome", pool.outcome_index)
+        .row("share mint", pool.share_mint)
+        .row("reserves", format!("{} shares / {}", pool.token_reserve, format_sol(pool.sol_reserve)))
+        .row("lp tokens", pool.total_lp_tokens)
+        .row("fees", format!("{} (protocol {})", format_bps(pool.fee_bps), format_bps(pool.protocol_fee_bps)))
+        .row("fees collected", format_sol(pool.fees_collected))
+        .row("swaps", pool.swap_count)
+        .text
+}
+
+fn describe_liquidity_position(address: &Pubkey, position: &LiquidityPosition) -> String {
+    Summary::new("LiquidityPosition", address)
+        .row("pool", position.pool)
+        .row("provider", position.provider)
+        .row("lp tokens", position.lp_tokens)
+        .row(
+            "deposited",
+            format!("{} shares / {}", position.tokens_deposited, format_sol(position.sol_deposited)),
+        )
+        .row("fees earned", format_sol(position.fees_earned))
+        .text
+}
+
+fn describe_order_book(address: &Pubkey, book: &OrderBook) -> String {
+    Summary::new("OrderBook", address)
+        .row("arena", book.arena)
+        .row("outcome", book.outcome_index)
+        .row(
+            "orders",
+            format!(
+                "{} active ({} buys, {} sells placed)",
+                book.active_orders, book.total_buy_orders, book.total_sell_orders
+            ),
+        )
+        .row("next order id", book.next_order_id)
+        .row("best bid/ask", format!("{} / {}", book.best_bid, book.best_ask))
+        .row("last trade", format!("{} ({} trades)", book.last_trade_price, book.trade_count))
+        .text
+}
+
+fn order_status(status: OrderStatus) -> &'static str {
+    match status {
+        OrderStatus::Open => "open",
+        OrderStatus::PartiallyFilled => "partially filled",
+        OrderStatus::Filled => "filled",
+        OrderStatus::Cancelled => "cancelled",
+        OrderStatus::Expired => "expired",
+    }
+}
+
+fn describe_limit_order(address: &Pubkey, order: &LimitOrder, now: i64) -> String {
+    let status = if order.is_active() && order.is_expired(now) { "expired" } else { order_status(order.status) };
+    Summary::new("LimitOrder", address)
+        .row("arena", order.arena)
+        .row("outcome", order.outcome_index)
+        .row("order id", order.order_id)
+        .row("owner", order.owner)
+        .row("order", format!("{:?} {:?} {} @ {}", order.order_type, order.side, order.size, order.price))
+        .row("filled", format!("{} ({} remaining)", order.filled_size, order.remaining_size))
+        .row("status", status)
+        .row("created", format_timestamp(order.created_at))
+        .text
+}
+
+fn describe_trade(address: &Pubkey, trade: &Trade) -> String {
+    Summary::new("Trade", address)
+        .row("arena", trade.arena)
+        .row("outcome", trade.outcome_index)
+        .row("trade id", trade.trade_id)
+        .row("orders", format!("buy #{} x sell #{}", trade.buy_order_id, trade.sell_order_id))
+        .row("buyer", trade.buyer)
+        .row("seller", trade.seller)
+        .row("fill", format!("{} @ {}", trade.size, trade.price))
+        .row("fees", format!("{} buyer / {} seller", trade.buyer_fee, trade.seller_fee))
+        .row("executed", format_timestamp(trade.executed_at))
+        .text
+}
@@ -0,0 +1,93 @@
+//! Reading and summarizing program accounts
+
+use std::fmt::Write;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Discriminator;
+use betfun_client::accounts::{decode, BetFunAccount};
+use betfun_client::pda;
+use betfun_client::state::Arena;
+use betfun_keeper::{Cluster, Error, Memcmp, Result};
+
+use crate::display::{arena_status, describe};
+use crate::units::format_sol;
+
+/// Decode the BetFun account at `address`
+pub fn fetch(cluster: &impl Cluster, address: &Pubkey) -> Result<BetFunAccount> {
+    let account = cluster.account(address)?.ok_or_else(|| Error::Account {
+        address: *address,
+        reason: "not found".to_string(),
+    })?;
+    BetFunAccount::decode(&account.data).map_err(|error| Error::Account {
+        address: *address,
+        reason: format!("not a BetFun account: {error}"),
+    })
+}
+
+/// Summary of the account at `address`
+/// Arenas also list the share, pool and order book accounts of each outcome.
+pub fn show(cluster: &impl Cluster, address: &Pubkey) -> Result<String> {
+    let account = fetch(cluster, address)?;
+    let mut text = describe(address, &account, cluster.unix_timestamp()?);
+    if let BetFunAccount::Arena(arena) = &account {
+        text.push_str(&markets(cluster, address, arena)?);
+    }
+    Ok(text)
+}
+
+/// Per-outcome market accounts that exist for an arena
+fn markets(cluster: &impl Cluster, arena_address: &Pubkey, arena: &Arena) -> Result<String> {
+    let mut text = String::new();
+    for index in 0..arena.outcomes.len() as u8 {
+        let accounts = [
+            ("shares", pda::outcome_share(arena_address, index).0),
+            ("pool", pda::amm_pool(arena_address, index).0),
+            ("order book", pda::order_book(arena_address, index).0),
+        ];
+        for (kind, address) in accounts {
+            if cluster.account(&address)?.is_some() {
+                let _ = writeln!(text, "    {index}  {kind:<10}  {address}");
+            }
+        }
+    }
+    if text.is_empty() {
+        return Ok(text);
+    }
+    Ok(format!("  markets:\n{text}"))
+}
+
+/// Every arena, oldest first
+pub fn arenas(cluster: &impl Cluster) -> Result<Vec<(Pubkey, Arena)>> {
+    let filter = Memcmp { offset: 0, bytes: Arena::DISCRIMINATOR.to_vec() };
+    let mut arenas = cluster
+        .program_accounts(&[filter])?
+        .into_iter()
+        .map(|(address, account)| {
+            decode::<Arena>(&account.data)
+                .map(|arena| (address, arena))
+                .map_err(|error| Error::Account { address, reason: error.to_string() })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    arenas.sort_by_key(|(address, arena)| (arena.created_at, *address));
+    Ok(arenas)
+}
+
+/// One line per arena: address, pot, participants, status and title
+pub fn list_arenas(cluster: &impl Cluster) -> Result<String> {
+    let now = cluster.unix_timestamp()?;
+    let mut text = String::new();
+    for (address, arena) in arenas(cluster)? {
+        let _ = writeln!(
+            text,
+            "{address}  {:>14}  {:>4} joined  {:<48}  {}",
+            format_sol(arena.pot),
+            arena.participants_count,
+            arena_status(&arena, now),
+            arena.title
+        );
+    }
+    if text.is_empty() {
+        text.push_str("no arenas\n");
+    }
+    Ok(text)
+}
@@ -0,0 +1,17 @@
+//! Operator CLI for the BetFun program
+//!
+//! Replaces one-off scripts for running arenas against any cluster,
+//! including `solana-test-validator`:
+//! - [`cli`]: `init-config`, `create-arena`, `create-share-tokens`, `init-pool`,
+//!   `init-order-book`, `resolve`, `pause`, `unpause`, `referral-share`,
+//!   `protocol-fees`, `cancel-order`, `migrate`, `show` and `arenas`
+//! - [`inspect`]: account lookups through a [`betfun_keeper::Cluster`]
+//! - [`display`]: human-readable summaries of every account type
+//! - [`units`]: SOL amounts, basis points and UTC timestamps
+
+pub mod cli;
+pub mod display;
+pub mod inspect;
+pub mod units;
+
+pub use cli::{run, Cli, Command};
@@ -0,0 +1,46 @@
+//! `betfun-admin`: create, resolve and inspect BetFun arenas
+//!
+//! ```text
+//! betfun-admin --keypair ~/.config/solana/id.json create-arena --title "Cup Final" \
+//!     --question "Who wins?" --outcome Home --outcome Away --entry-fee 0.1
+//! betfun-admin show <ARENA>
+//! ```
+
+use std::process::ExitCode;
+
+use betfun_admin::{run, Cli};
+use betfun_keeper::rpc::RpcCluster;
+use clap::Parser;
+use solana_keypair::Keypair;
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let keypair = match (&cli.keypair, cli.command.signs()) {
+        (Some(path), _) => match solana_keypair::read_keypair_file(path) {
+            Ok(keypair) => keypair,
+            Err(error) => {
+                eprintln!("cannot read keypair {}: {error}", path.display());
+                return ExitCode::FAILURE;
+            }
+        },
+        (None, true) => {
+            eprintln!("--keypair is required for this command");
+            return ExitCode::FAILURE;
+        }
+        // Read-only commands never sign
+        (None, false) => Keypair::new(),
+    };
+    let mut cluster = RpcCluster::new(cli.url, keypair);
+    let signer = cluster.payer();
+
+    match run(cli.command, &mut cluster, &signer) {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("{error}");
+            ExitCode::FAILURE
+        }
+    }
+}
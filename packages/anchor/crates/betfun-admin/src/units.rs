@@ -0,0 +1,66 @@
+//! Human units for lamports, basis points and timestamps
+
+const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+const SOL_DECIMALS: usize = 9;
+
+/// Parse a SOL amount such as `0.25` into lamports
+pub fn parse_sol(text: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid SOL amount {text:?}");
+    let (whole, fraction) = text.split_once('.').unwrap_or((text, ""));
+    if (whole.is_empty() && fraction.is_empty()) || fraction.len() > SOL_DECIMALS {
+        return Err(invalid());
+    }
+    let digits = |part: &str| part.is_empty() || part.bytes().all(|byte| byte.is_ascii_digit());
+    if !digits(whole) || !digits(fraction) {
+        return Err(invalid());
+    }
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().map_err(|_| invalid())? };
+    let fraction: u64 = format!("{fraction:0<SOL_DECIMALS$}").parse().map_err(|_| invalid())?;
+    whole
+        .checked_mul(LAMPORTS_PER_SOL)
+        .and_then(|lamports| lamports.checked_add(fraction))
+        .ok_or_else(invalid)
+}
+
+/// `1.5 SOL`, without trailing zeros
+pub fn format_sol(lamports: u64) -> String {
+    let whole = lamports / LAMPORTS_PER_SOL;
+    let fraction = lamports % LAMPORTS_PER_SOL;
+    if fraction == 0 {
+        return format!("{whole} SOL");
+    }
+    let fraction = format!("{fraction:0>SOL_DECIMALS$}");
+    format!("{whole}.{} SOL", fraction.trim_end_matches('0'))
+}
+
+/// `5.00%`
+pub fn format_bps(bps: u16) -> String {
+    format!("{}.{:02}%", bps / 100, bps % 100)
+}
+
+/// `2026-10-18 12:00:00 UTC`
+pub fn format_timestamp(unix_timestamp: i64) -> String {
+    let days = unix_timestamp.div_euclid(86_400);
+    let seconds = unix_timestamp.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Gregorian date of a day count since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,736 @@
+//! Admin commands run against the in-process runtime
+//!
+//! Commands are parsed from argument lists exactly as the binary parses them.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::solana_program::system_instruction;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction::create_associated_token_account;
+use anchor_spl::token::{self, spl_token};
+use betfun_admin::units::{format_bps, format_sol, format_timestamp, parse_sol};
+use betfun_admin::{run, Cli};
+use betfun_client::accounts::decode;
+use betfun_client::allowlist::Allowlist;
+use betfun_client::instructions as ix;
+use betfun_client::state::{
+    pause, AMMPool, Arena, CurveType, GlobalConfig, OrderBook, OrderSide, OrderType, OutcomeShare, StakeWeighting,
+};
+use betfun_client::{pda, PlaceOrderParams};
+use betfun_keeper::{AccountData, Cluster, Error, Memcmp, Result};
+use betfun_testkit::Svm;
+use clap::Parser;
+
+const SOL: u64 = 1_000_000_000;
+
+/// `Cluster` over the in-process runtime, signing as `payer`
+struct LocalCluster {
+    svm: Svm,
+    payer: Pubkey,
+}
+
+impl Cluster for LocalCluster {
+    fn program_accounts(&self, filters: &[Memcmp]) -> Result<Vec<(Pubkey, AccountData)>> {
+        Ok(self
+            .svm
+            .accounts_owned_by(&betfun_client::ID)
+            .filter(|(_, account)| {
+                filters.iter().all(|filter| {
+                    account.data.get(filter.offset..filter.offset + filter.bytes.len()) == Some(&filter.bytes[..])
+                })
+            })
+            .map(|(address, account)| (*address, AccountData { lamports: account.lamports, data: account.data.clone() }))
+            .collect())
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<AccountData>> {
+        Ok(self
+            .svm
+            .account(address)
+            .filter(|account| account.lamports > 0)
+            .map(|account| AccountData { lamports: account.lamports, data: account.data.clone() }))
+    }
+
+    fn unix_timestamp(&self) -> Result<i64> {
+        Ok(self.svm.clock().unix_timestamp)
+    }
+
+    fn send(&mut self, instructions: &[Instruction]) -> Result<()> {
+        self.svm
+            .send_transaction(instructions, &[self.payer])
+            .map(|_| ())
+            .map_err(|failure| Error::Cluster(failure.to_string()))
+    }
+}
+
+impl LocalCluster {
+    fn new() -> Self {
+        let mut svm = Svm::new();
+        svm.add_program(betfun_client::ID, betfun::entry);
+        let payer = Pubkey::new_unique();
+        svm.airdrop(&payer, 100 * SOL);
+        svm.set_upgrade_authority(&betfun_client::ID, Some(payer));
+        let mut cluster = Self { svm, payer };
+        cluster.admin(&["init-config"]).unwrap();
+        cluster
+    }
+
+    /// Parse and run `betfun-admin <args>` as the payer
+    fn admin(&mut self, args: &[&str]) -> Result<String> {
+        let cli = Cli::try_parse_from(std::iter::once("betfun-admin").chain(args.iter().copied()))
+            .unwrap_or_else(|error| panic!("{error}"));
+        let payer = self.payer;
+        run(cli.command, self, &payer)
+    }
+
+    fn fetch<T: anchor_lang::AccountDeserialize>(&self, address: &Pubkey) -> T {
+        decode(&self.svm.account(address).expect("account exists").data).unwrap()
+    }
+
+    fn wallet(&mut self) -> Pubkey {
+        let wallet = Pubkey::new_unique();
+        self.svm.airdrop(&wallet, 100 * SOL);
+        wallet
+    }
+
+    fn send_as(&mut self, instruction: Instruction, signer: Pubkey) {
+        self.svm.send_transaction(&[instruction], &[signer]).unwrap_or_else(|failure| panic!("{failure}"));
+    }
+}
+
+/// `create-arena` for a two-outcome arena, returning its address
+fn create_arena(cluster: &mut LocalCluster, title: &str) -> Pubkey {
+    let output = cluster
+        .admin(&[
+            "create-arena",
+            "--title",
+            title,
+            "--question",
+            "Who lifts the cup?",
+            "--outcome",
+            "Home",
+            "--outcome",
+            "Away",
+            "--tag",
+            "football",
+            "--entry-fee",
+            "0.25",
+            "--hours",
+            "2",
+        ])
+        .unwrap();
+    let arena = pda::arena(&cluster.payer, title).0;
+    assert!(output.starts_with(&format!("Created Arena {arena}\n")), "{output}");
+    arena
+}
+
+// ========== ARENAS ==========
+
+#[test]
+fn creates_and_resolves_an_arena() {
+    let mut cluster = LocalCluster::new();
+    let now = cluster.svm.clock().unix_timestamp;
+    let arena = create_arena(&mut cluster, "Cup Final");
+
+    let state: Arena = cluster.fetch(&arena);
+    assert_eq!(state.creator, cluster.payer);
+    assert_eq!(state.outcomes, ["Home", "Away"]);
+    assert_eq!(state.tags, ["football"]);
+    assert_eq!(state.entry_fee, SOL / 4);
+    assert_eq!((state.lock_time, state.resolve_after), (now + 2 * 60 * 60, now + 2 * 60 * 60));
+
+    let mut user = Pubkey::default();
+    for outcome in [0, 1, 1] {
+        user = cluster.wallet();
+        cluster.send_as(ix::join_arena(&arena, &user, outcome, None, &[]), user);
+    }
+    let shown = cluster.admin(&["show", &pda::user_profile(&user).0.to_string()]).unwrap();
+    assert!(shown.contains(&format!("user:             {user}")), "{shown}");
+    assert!(shown.contains("arenas:           1 joined, 0 won"), "{shown}");
+    assert!(shown.contains("staked:           0.25 SOL"), "{shown}");
+    let shown = cluster.admin(&["show", &arena.to_string()]).unwrap();
+    assert!(shown.contains("title:            Cup Final"), "{shown}");
+    assert!(shown.contains(&format!("status:           open until {}", format_timestamp(state.lock_time))));
+    assert!(shown.contains("pot:              0.75 SOL from 3 participants"), "{shown}");
+    assert!(shown.contains("0  Home     1 bets  0.25 SOL"), "{shown}");
+    assert!(shown.contains("1  Away     2 bets  0.5 SOL"), "{shown}");
+
+    cluster.svm.advance_clock(3 * 60 * 60);
+    let shown = cluster.admin(&["show", &arena.to_string()]).unwrap();
+    assert!(shown.contains("awaiting resolution"), "{shown}");
+
+    let output = cluster.admin(&["resolve", "--arena", &arena.to_string(), "--winner", "1"]).unwrap();
+    assert!(output.starts_with(&format!("Resolved Arena {arena}\n")), "{output}");
+    assert!(output.contains("status:           resolved: 1 \"Away\""), "{output}");
+    assert!(output.contains("1  Away     2 bets  0.5 SOL  (winner)"), "{output}");
+    let state: Arena = cluster.fetch(&arena);
+    assert_eq!((state.resolved, state.winner_outcome), (true, Some(1)));
+}
+
+#[test]
+fn creates_an_arena_with_creator_fees() {
+    let mut cluster = LocalCluster::new();
+    let output = cluster
+        .admin(&[
+            "create-arena",
+            "--title",
+            "Fee Cup",
+            "--question",
+            "Who lifts the cup?",
+            "--outcome",
+            "Home",
+            "--outcome",
+            "Away",
+            "--entry-fee",
+            "0.25",
+            "--creator-fee-bps",
+            "250",
+            "--trading-fee-bps",
+            "100",
+        ])
+        .unwrap();
+    assert!(output.contains("creator fee:      2.50%"), "{output}");
+    assert!(output.contains("trading fee:      1.00%"), "{output}");
+    let state: Arena = cluster.fetch(&pda::arena(&cluster.payer, "Fee Cup").0);
+    assert_eq!((state.creator_fee_bps, state.creator_trading_fee_bps), (250, 100));
+
+    let result = cluster.admin(&[
+        "create-arena",
+        "--title",
+        "Greedy Cup",
+        "--question",
+        "Who lifts the cup?",
+        "--outcome",
+        "Home",
+        "--outcome",
+        "Away",
+        "--entry-fee",
+        "0.25",
+        "--trading-fee-bps",
+        "201",
+    ]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn creates_an_arena_with_an_early_bonus() {
+    let mut cluster = LocalCluster::new();
+    let output = cluster
+        .admin(&[
+            "create-arena",
+            "--title",
+            "Early Cup",
+            "--question",
+            "Who lifts the cup?",
+            "--outcome",
+            "Home",
+            "--outcome",
+            "Away",
+            "--entry-fee",
+            "0.25",
+            "--early-bonus-bps",
+            "2500",
+        ])
+        .unwrap();
+    assert!(output.contains("early bonus:      up to 25.00%, decaying until lock"), "{output}");
+    let state: Arena = cluster.fetch(&pda::arena(&cluster.payer, "Early Cup").0);
+    assert_eq!(state.stake_weighting, StakeWeighting::LinearDecay { bonus_bps: 2500 });
+    assert_eq!(state.outcome_weights, vec![0, 0]);
+}
+
+#[test]
+fn challenges_a_duel_and_voids_it_unaccepted() {
+    let mut cluster = LocalCluster::new();
+    let opponent = cluster.wallet();
+    let output = cluster
+        .admin(&[
+            "create-arena",
+            "--title",
+            "Chess Duel",
+            "--question",
+            "Who wins the game?",
+            "--outcome",
+            "White",
+            "--outcome",
+            "Black",
+            "--entry-fee",
+            "0.25",
+            "--challenge",
+            "0",
+            "--opponent",
+            &opponent.to_string(),
+        ])
+        .unwrap();
+    assert!(output.contains(&format!("duel:             challenge to {opponent}, awaiting a match")), "{output}");
+    assert!(output.contains("0  White     1 bets  0.25 SOL"), "{output}");
+    let arena = pda::arena(&cluster.payer, "Chess Duel").0;
+
+    cluster.svm.advance_clock(24 * 60 * 60);
+    let output = cluster.admin(&["show", &arena.to_string()]).unwrap();
+    assert!(output.contains("status:           challenge unaccepted, can be voided"), "{output}");
+    let output = cluster.admin(&["void", "--arena", &arena.to_string()]).unwrap();
+    assert!(output.contains("status:           voided, refunding bets"), "{output}");
+}
+
+#[test]
+fn cancels_an_unaccepted_duel() {
+    let mut cluster = LocalCluster::new();
+    cluster
+        .admin(&[
+            "create-arena",
+            "--title",
+            "Open Duel",
+            "--question",
+            "Who wins the game?",
+            "--outcome",
+            "White",
+            "--outcome",
+            "Black",
+            "--entry-fee",
+            "0.25",
+            "--challenge",
+            "1",
+        ])
+        .unwrap();
+    let arena = pda::arena(&cluster.payer, "Open Duel").0;
+
+    let output = cluster.admin(&["cancel-duel", "--arena", &arena.to_string()]).unwrap();
+    assert!(output.starts_with("Cancelled"), "{output}");
+    assert!(output.contains("status:           voided, refunding bets"), "{output}");
+    assert!(cluster.admin(&["cancel-duel", "--arena", &arena.to_string()]).is_err());
+}
+
+#[test]
+fn creates_and_resolves_a_scalar_arena() {
+    let mut cluster = LocalCluster::new();
+    let output = cluster
+        .admin(&[
+            "create-arena",
+            "--title",
+            "Points Total",
+            "--question",
+            "How many points will the home side score?",
+            "--lower",
+            "-10",
+            "--upper",
+            "90",
+            "--entry-fee",
+            "0.25",
+            "--manual-resolve",
+        ])
+        .unwrap();
+    assert!(output.contains("range:            -10 to 90"), "{output}");
+    assert!(output.contains("0  LONG"), "{output}");
+    let arena = pda::arena(&cluster.payer, "Points Total").0;
+
+    // Even manual arenas resolve only after betting locks
+    assert!(cluster.admin(&["resolve", "--arena", &arena.to_string(), "--value", "40"]).is_err());
+    cluster.svm.advance_clock(24 * 60 * 60);
+    let output = cluster.admin(&["resolve", "--arena", &arena.to_string(), "--value", "40"]).unwrap();
+    assert!(output.contains("status:           resolved at 40"), "{output}");
+    let state: Arena = cluster.fetch(&arena);
+    assert_eq!(state.resolved_value, Some(40));
+}
+
+#[test]
+fn creates_a_private_arena_and_opens_it() {
+    let mut cluster = LocalCluster::new();
+    let members = [cluster.wallet(), cluster.wallet()];
+    let (first, second) = (members[0].to_string(), members[1].to_string());
+    let output = cluster
+        .admin(&[
+            "create-arena",
+            "--title",
+            "Club Cup",
+            "--question",
+            "Who wins the club cup?",
+            "--outcome",
+            "Red",
+            "--outcome",
+            "Blue",
+            "--entry-fee",
+            "0.1",
+            "--member",
+            &first,
+            "--member",
+            &second,
+        ])
+        .unwrap();
+    let arena = pda::arena(&cluster.payer, "Club Cup").0;
+    let allowlist = Allowlist::new(members);
+    let root: String = allowlist.root().iter().map(|byte| format!("{byte:02x}")).collect();
+    assert!(output.contains(&format!("allowlist:        {root}")), "{output}");
+
+    let proof = allowlist.proof(&members[1]).unwrap();
+    cluster.send_as(ix::join_arena(&arena, &members[1], 0, None, &proof), members[1]);
+
+    let output = cluster.admin(&["allowlist", "--arena", &arena.to_string(), "--public"]).unwrap();
+    assert!(output.starts_with(&format!("Updated Arena {arena}\n")), "{output}");
+    assert!(!output.contains("allowlist:"), "{output}");
+    assert_eq!(cluster.fetch::<Arena>(&arena).allowlist_root, None);
+}
+
+#[test]
+fn lists_arenas() {
+    let mut cluster = LocalCluster::new();
+    assert_eq!(cluster.admin(&["arenas"]).unwrap(), "no arenas\n");
+
+    let first = create_arena(&mut cluster, "Opening Match");
+    cluster.svm.advance_clock(60);
+    let second = create_arena(&mut cluster, "Semi Final");
+
+    let listed = cluster.admin(&["arenas"]).unwrap();
+    let lines: Vec<_> = listed.lines().collect();
+    assert_eq!(lines.len(), 2, "{listed}");
+    assert!(lines[0].starts_with(&first.to_string()) && lines[0].ends_with("Opening Match"), "{listed}");
+    assert!(lines[1].starts_with(&second.to_string()) && lines[1].ends_with("Semi Final"), "{listed}");
+}
+
+// ========== MARKETS ==========
+
+#[test]
+fn creates_an_outcome_market() {
+    let mut cluster = LocalCluster::new();
+    let arena = create_arena(&mut cluster, "Market Day");
+    let arena_arg = arena.to_string();
+
+    let output = cluster
+        .admin(&[
+            "create-share-tokens",
+            "--arena",
+            &arena_arg,
+            "--outcome-index",
+            "0",
+            "--initial-price",
+            "0.1",
+            "--curve",
+            "exponential",
+            "--curve-param",
+            "5000000000",
+        ])
+        .unwrap();
+    let (outcome_share, _) = pda::outcome_share(&arena, 0);
+    assert!(output.starts_with(&format!("Created OutcomeShare {outcome_share}\n")), "{output}");
+    let share: OutcomeShare = cluster.fetch(&outcome_share);
+    assert_eq!(share.curve.curve_type, CurveType::Exponential);
+    assert_eq!((share.curve.base_price, share.curve.param), (SOL / 10, 5 * SOL));
+
+    // Pools need shares to exist
+    let provider = cluster.payer;
+    cluster.send_as(ix::buy_shares(&arena, &provider, &provider, 0, 10 * SOL, None, None, None, &[]), provider);
+    let output = cluster
+        .admin(&["init-pool", "--arena", &arena_arg, "--outcome-index", "0", "--fee-bps", "50"])
+        .unwrap();
+    let (pool, _) = pda::amm_pool(&arena, 0);
+    assert!(output.starts_with(&format!("Initialized AMMPool {pool}\n")), "{output}");
+    assert!(output.contains("fees:             0.50% (protocol 0.10%)"), "{output}");
+    let state: AMMPool = cluster.fetch(&pool);
+    assert_eq!((state.fee_bps, state.protocol_fee_bps), (50, 10));
+
+    let output = cluster.admin(&["init-order-book", "--arena", &arena_arg, "--outcome-index", "0"]).unwrap();
+    let (order_book, _) = pda::order_book(&arena, 0);
+    assert!(output.starts_with(&format!("Initialized OrderBook {order_book}\n")), "{output}");
+    assert_eq!(cluster.fetch::<OrderBook>(&order_book).outcome_index, 0);
+
+    let shown = cluster.admin(&["show", &arena_arg]).unwrap();
+    assert!(shown.contains(&format!("0  shares      {outcome_share}")), "{shown}");
+    assert!(shown.contains(&format!("0  pool        {pool}")), "{shown}");
+    assert!(shown.contains(&format!("0  order book  {order_book}")), "{shown}");
+}
+
+#[test]
+fn cancels_an_order() {
+    let mut cluster = LocalCluster::new();
+    let arena = create_arena(&mut cluster, "Order Day");
+    let arena_arg = arena.to_string();
+    cluster
+        .admin(&["create-share-tokens", "--arena", &arena_arg, "--outcome-index", "0", "--initial-price", "0.1"])
+        .unwrap();
+    cluster.admin(&["init-order-book", "--arena", &arena_arg, "--outcome-index", "0"]).unwrap();
+    let owner = cluster.payer;
+    cluster.send_as(ix::create_share_token_account(&owner, &owner, &arena, 0), owner);
+    let params = PlaceOrderParams {
+        order_type: OrderType::Limit,
+        side: OrderSide::Buy,
+        price: 100_000,
+        size: 1_000,
+        expires_at: 0,
+        stop_price: None,
+        visible_size: None,
+        twap_interval: None,
+    };
+    cluster.send_as(ix::place_limit_order(&arena, &owner, 0, 0, params), owner);
+
+    let output = cluster
+        .admin(&["cancel-order", "--arena", &arena_arg, "--outcome-index", "0", "--order-id", "0"])
+        .unwrap();
+    let (order, _) = pda::limit_order(&arena, 0, 0);
+    assert!(output.starts_with(&format!("Cancelled LimitOrder {order}\n")), "{output}");
+    assert!(output.contains("order:            Limit Buy 1000 @ 100000"), "{output}");
+    assert!(output.contains("status:           cancelled"), "{output}");
+}
+
+#[test]
+fn pauses_and_resumes_activities() {
+    let mut cluster = LocalCluster::new();
+    let arena = create_arena(&mut cluster, "Pause Cup");
+    let arena_arg = arena.to_string();
+
+    let output = cluster.admin(&["pause", "amm", "order-book"]).unwrap();
+    let config = pda::config().0;
+    assert!(output.starts_with(&format!("Paused GlobalConfig {config}\n")), "{output}");
+    assert!(output.contains("paused:           amm, order book"), "{output}");
+    let output = cluster.admin(&["unpause", "amm"]).unwrap();
+    assert!(output.contains("paused:           order book"), "{output}");
+    let state: GlobalConfig = cluster.fetch(&config);
+    assert_eq!(state.paused, pause::ORDER_BOOK);
+
+    let output = cluster.admin(&["pause", "--arena", &arena_arg, "all"]).unwrap();
+    assert!(output.contains("paused:           betting, shares, amm, order book, claims"), "{output}");
+    let output = cluster.admin(&["unpause", "--arena", &arena_arg, "all"]).unwrap();
+    assert!(output.starts_with(&format!("Resumed Arena {arena}\n")), "{output}");
+    assert!(output.contains("paused:           nothing"), "{output}");
+
+    let error = cluster.admin(&["pause", "--arena", &config.to_string(), "all"]).unwrap_err();
+    assert_eq!(error.to_string(), format!("account {config}: expected Arena, found GlobalConfig"));
+}
+
+#[test]
+fn sets_referral_share() {
+    let mut cluster = LocalCluster::new();
+    let output = cluster.admin(&["show", &pda::config().0.to_string()]).unwrap();
+    assert!(output.contains("referral share:   10.00%"), "{output}");
+
+    let output = cluster.admin(&["referral-share", "250"]).unwrap();
+    assert!(output.starts_with("Updated GlobalConfig"), "{output}");
+    assert!(output.contains("referral share:   2.50%"), "{output}");
+    assert_eq!(cluster.fetch::<GlobalConfig>(&pda::config().0).referral_share_bps, 250);
+    assert!(cluster.admin(&["referral-share", "5001"]).is_err());
+}
+
+#[test]
+fn sets_protocol_fees() {
+    let mut cluster = LocalCluster::new();
+    let recipient = cluster.wallet();
+    let output = cluster.admin(&["protocol-fees", &recipient.to_string(), "--order-book-fee-bps", "50"]).unwrap();
+    assert!(output.starts_with("Updated GlobalConfig"), "{output}");
+    assert!(output.contains(&format!("fee recipient:    {recipient}")), "{output}");
+    assert!(output.contains("order book fee:   0.50%"), "{output}");
+    let config = cluster.fetch::<GlobalConfig>(&pda::config().0);
+    assert_eq!((config.fee_recipient, config.order_book_fee_bps), (recipient, 50));
+    assert!(cluster.admin(&["protocol-fees", &recipient.to_string(), "--order-book-fee-bps", "101"]).is_err());
+}
+
+#[test]
+fn transfers_admin() {
+    let mut cluster = LocalCluster::new();
+    let new_admin = cluster.wallet();
+    let output = cluster.admin(&["transfer-admin", &new_admin.to_string()]).unwrap();
+    assert!(output.starts_with("Updated GlobalConfig"), "{output}");
+    assert!(output.contains(&format!("admin:            {new_admin}")), "{output}");
+    assert_eq!(cluster.fetch::<GlobalConfig>(&pda::config().0).admin, new_admin);
+    assert!(cluster.admin(&["transfer-admin", &new_admin.to_string()]).is_err());
+}
+
+#[test]
+fn bonds_arenas_and_voids_unresolved_ones() {
+    let mut cluster = LocalCluster::new();
+    let output = cluster.admin(&["creator-bond", "0.5"]).unwrap();
+    assert!(output.starts_with("Updated GlobalConfig"), "{output}");
+    assert!(output.contains("creator bond:     0.5 SOL"), "{output}");
+    assert!(cluster.admin(&["creator-bond", "101"]).is_err());
+
+    let arena = create_arena(&mut cluster, "Bonded Final");
+    let shown = cluster.admin(&["show", &arena.to_string()]).unwrap();
+    assert!(shown.contains("creator bond:     0.5 SOL"), "{shown}");
+    let user = cluster.wallet();
+    cluster.send_as(ix::join_arena(&arena, &user, 0, None, &[]), user);
+
+    assert!(cluster.admin(&["void", "--arena", &arena.to_string()]).is_err());
+    cluster.svm.advance_clock(2 * 60 * 60 + Arena::RESOLUTION_WINDOW);
+    let output = cluster.admin(&["void", "--arena", &arena.to_string()]).unwrap();
+    assert!(output.starts_with(&format!("Voided Arena {arena}\n")), "{output}");
+    assert!(output.contains("status:           voided, refunding bets"), "{output}");
+
+    let before = cluster.svm.lamports(&user);
+    cluster.send_as(ix::claim_winnings(&arena, &user, &cluster.payer), user);
+    assert_eq!(cluster.svm.lamports(&user) - before, SOL / 4 + SOL / 2);
+}
+
+#[test]
+fn settles_disputes_of_bonded_resolutions() {
+    let mut cluster = LocalCluster::new();
+    cluster.admin(&["creator-bond", "0.5"]).unwrap();
+    let arena = create_arena(&mut cluster, "Disputed Final");
+    let user = cluster.wallet();
+    cluster.send_as(ix::join_arena(&arena, &user, 0, None, &[]), user);
+    cluster.svm.advance_clock(3 * 60 * 60);
+    cluster.admin(&["resolve", "--arena", &arena.to_string(), "--winner", "0"]).unwrap();
+
+    // The creator can't dispute their own resolution, nor can an undisputed arena be settled
+    assert!(cluster.admin(&["dispute", "--arena", &arena.to_string()]).is_err());
+    assert!(cluster.admin(&["settle-dispute", "--arena", &arena.to_string()]).is_err());
+    cluster.send_as(ix::dispute_arena(&arena, &user), user);
+    let shown = cluster.admin(&["show", &arena.to_string()]).unwrap();
+    assert!(shown.contains(&format!("dispute:          open, raised by {user}")), "{shown}");
+
+    let before = cluster.svm.lamports(&user);
+    let output = cluster.admin(&["settle-dispute", "--arena", &arena.to_string()]).unwrap();
+    assert!(output.starts_with(&format!("Upheld Arena {arena}\n")), "{output}");
+    assert!(output.contains("dispute:          upheld, bond slashed to bettors"), "{output}");
+    assert_eq!(cluster.svm.lamports(&user) - before, SOL / 4);
+    assert!(cluster.admin(&["settle-dispute", "--arena", &arena.to_string(), "--reject"]).is_err());
+}
+
+#[test]
+fn bonds_arenas_in_a_token() {
+    let mut cluster = LocalCluster::new();
+    let payer = cluster.payer;
+    let mint = Pubkey::new_unique();
+    let space = spl_token::state::Mint::LEN;
+    let rent = cluster.svm.rent().minimum_balance(space);
+    let account = get_associated_token_address(&payer, &mint);
+    let instructions = [
+        system_instruction::create_account(&payer, &mint, rent, space as u64, &token::ID),
+        spl_token::instruction::initialize_mint2(&token::ID, &mint, &payer, None, 6).unwrap(),
+        create_associated_token_account(&payer, &payer, &mint, &token::ID),
+        spl_token::instruction::mint_to(&token::ID, &mint, &account, &payer, &[], 500).unwrap(),
+    ];
+    cluster.svm.send_transaction(&instructions, &[payer, mint]).unwrap_or_else(|failure| panic!("{failure}"));
+
+    let output = cluster.admin(&["creator-bond", "--mint", &mint.to_string(), "--amount", "500"]).unwrap();
+    assert!(output.contains(&format!("creator bond:     500 base units of {mint}")), "{output}");
+
+    let arena = create_arena(&mut cluster, "Token Final");
+    let shown = cluster.admin(&["show", &arena.to_string()]).unwrap();
+    assert!(shown.contains(&format!("creator bond:     500 base units of {mint}")), "{shown}");
+    assert_eq!(cluster.fetch::<Arena>(&arena).bond_mint, Some(mint));
+    let vault = cluster.svm.account(&pda::bond_vault(&arena).0).expect("bond vault exists");
+    assert_eq!(spl_token::state::Account::unpack(&vault.data).unwrap().amount, 500);
+}
+
+#[test]
+fn funds_and_withdraws_the_parlay_vault() {
+    let mut cluster = LocalCluster::new();
+    let vault = pda::parlay_vault().0;
+    let output = cluster.admin(&["fund-parlay-vault", "2.5"]).unwrap();
+    assert!(output.starts_with(&format!("Funded ParlayVault {vault}\n")), "{output}");
+    assert!(output.contains("liability:        0 SOL on 0 open parlays"), "{output}");
+
+    let before = cluster.svm.lamports(&vault);
+    let output = cluster.admin(&["withdraw-parlay-vault", "1"]).unwrap();
+    assert!(output.starts_with("Withdrew from ParlayVault"), "{output}");
+    assert_eq!(before - cluster.svm.lamports(&vault), SOL);
+    assert!(cluster.admin(&["withdraw-parlay-vault", "2"]).is_err());
+}
+
+#[test]
+fn approves_and_revokes_parlay_legs() {
+    let mut cluster = LocalCluster::new();
+    let arena = create_arena(&mut cluster, "Parlay Final");
+    let output = cluster.admin(&["parlay-approval", &arena.to_string()]).unwrap();
+    assert!(output.starts_with(&format!("Approved Arena {arena}\n")), "{output}");
+    assert!(output.contains("parlay legs:      approved"), "{output}");
+
+    let output = cluster.admin(&["parlay-approval", &arena.to_string(), "--revoke"]).unwrap();
+    assert!(output.starts_with(&format!("Revoked Arena {arena}\n")), "{output}");
+    assert!(!output.contains("parlay legs"), "{output}");
+}
+
+// ========== TOURNAMENTS ==========
+
+#[test]
+fn runs_a_tournament_bracket() {
+    let mut cluster = LocalCluster::new();
+    let mut args = vec!["create-tournament", "--name", "Spring Cup", "--entry-fee", "0.5", "--match-entry-fee", "0.1"];
+    for entrant in ["Ada", "Bo", "Cy", "Di"] {
+        args.extend(["--entrant", entrant]);
+    }
+    let output = cluster.admin(&args).unwrap();
+    let tournament = pda::tournament(&cluster.payer, "Spring Cup").0;
+    let semi_final = pda::tournament_match(&tournament, 1).0;
+    let final_match = pda::tournament_match(&tournament, 0).0;
+    assert!(output.starts_with(&format!("Created Tournament {tournament}\n")), "{output}");
+    assert!(output.contains("status:           3 of 3 matches undecided"), "{output}");
+    assert!(output.contains(&format!("Match 1  Ada vs Bo  {semi_final}")), "{output}");
+    assert!(output.contains("Match 0  ? vs ?  not created"), "{output}");
+
+    // The second semi-final decides who meets Ada, so the final opens with it
+    cluster.svm.advance_clock(25 * 60 * 60);
+    let tournament_arg = tournament.to_string();
+    let resolve = |slot, winner| ["resolve-match", "--tournament", &tournament_arg, "--slot", slot, "--winner", winner];
+    let output = cluster.admin(&resolve("1", "0")).unwrap();
+    assert!(output.contains(&format!("Match 1  Ada vs Bo  {semi_final}, won by Ada")), "{output}");
+    assert!(cluster.svm.account(&final_match).is_none());
+    let output = cluster.admin(&resolve("2", "1")).unwrap();
+    assert!(output.contains(&format!("Match 0  Ada vs Di  {final_match}")), "{output}");
+    let state: Arena = cluster.fetch(&final_match);
+    assert_eq!((state.creator, state.outcomes), (tournament, vec!["Ada".to_string(), "Di".to_string()]));
+}
+
+// ========== ERRORS ==========
+
+#[test]
+fn reports_missing_and_foreign_accounts() {
+    let mut cluster = LocalCluster::new();
+    let missing = Pubkey::new_unique();
+    let error = cluster.admin(&["show", &missing.to_string()]).unwrap_err();
+    assert_eq!(error.to_string(), format!("account {missing}: not found"));
+
+    let payer = cluster.payer.to_string();
+    let error = cluster.admin(&["show", &payer]).unwrap_err();
+    assert!(error.to_string().starts_with(&format!("account {payer}: not a BetFun account")), "{error}");
+
+    // The program rejects the resolution, and the error says why
+    let arena = create_arena(&mut cluster, "Too Early");
+    let error = cluster.admin(&["resolve", "--arena", &arena.to_string(), "--winner", "0"]).unwrap_err();
+    assert!(matches!(error, Error::Cluster(_)), "{error}");
+}
+
+#[test]
+fn rejects_bad_arguments() {
+    let parse = |args: &[&str]| Cli::try_parse_from(std::iter::once("betfun-admin").chain(args.iter().copied()));
+    let create = |extra: &[&str]| {
+        let mut args = vec!["create-arena", "--title", "t", "--question", "q"];
+        args.extend_from_slice(extra);
+        parse(&args)
+    };
+    assert!(create(&["--outcome", "A", "--outcome", "B", "--entry-fee", "1"]).is_ok());
+    assert!(create(&["--outcome", "A", "--entry-fee", "abc"]).is_err());
+    assert!(create(&["--entry-fee", "1"]).is_err());
+    assert!(create(&["--outcome", "A", "--entry-fee", "1", "--end-time", "5", "--hours", "3"]).is_err());
+    assert!(parse(&["create-share-tokens", "--arena", "nope", "--outcome-index", "0", "--initial-price", "1"]).is_err());
+
+    let cli = parse(&["show", &Pubkey::new_unique().to_string()]).unwrap();
+    assert!(!cli.command.signs());
+    let arena = Pubkey::new_unique().to_string();
+    let cli = parse(&["--keypair", "id.json", "resolve", "--arena", &arena, "--winner", "0"]).unwrap();
+    assert!(cli.command.signs());
+}
+
+// ========== UNITS ==========
+
+#[test]
+fn formats_and_parses_units() {
+    assert_eq!(parse_sol("1"), Ok(SOL));
+    assert_eq!(parse_sol("0.25"), Ok(SOL / 4));
+    assert_eq!(parse_sol(".5"), Ok(SOL / 2));
+    assert_eq!(parse_sol("2."), Ok(2 * SOL));
+    assert_eq!(parse_sol("0.000000001"), Ok(1));
+    for invalid in ["", ".", "-1", "1.0000000001", "1e9", "0x10", "18446744074"] {
+        assert!(parse_sol(invalid).is_err(), "{invalid:?}");
+    }
+
+    assert_eq!(format_sol(0), "0 SOL");
+    assert_eq!(format_sol(3 * SOL), "3 SOL");
+    assert_eq!(format_sol(SOL + SOL / 10), "1.1 SOL");
+    assert_eq!(format_sol(1), "0.000000001 SOL");
+    assert_eq!(format_bps(500), "5.00%");
+    assert_eq!(format_bps(5), "0.05%");
+
+    assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00:00 UTC");
+    assert_eq!(format_timestamp(1_792_324_800), "2026-10-18 12:00:00 UTC");
+    assert_eq!(format_timestamp(-1), "1969-12-31 23:59:59 UTC");
+}
@@ -0,0 +1,137 @@
+//! Account decoders
+//!
+//! [`decode`] reads one known account type; [`BetFunAccount::decode`]
+//! dispatches on the 8-byte discriminator for callers that scan every account
+//! the program owns, and also reads accounts still at an older layout version.
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::{AccountDeserialize, Discriminator, Result};
+use betfun::state::{
+    AMMPool, Arena, GlobalConfig, LimitOrder, LiquidityPosition, OrderBook, OutcomeShare, Parlay, ParlayVault,
+    Participant, PriceHistory, Referral, ShareBalance, Tournament, TournamentEntry, Trade, UserProfile, Versioned,
+};
+
+/// Decode raw account data as `T`, checking its discriminator
+pub fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..])
+}
+
+/// Any account owned by the BetFun program
+#[derive(Clone)]
+pub enum BetFunAccount {
+    GlobalConfig(GlobalConfig),
+    Arena(Box<Arena>),
+    Participant(Participant),
+    Referral(Referral),
+    UserProfile(UserProfile),
+    Parlay(Parlay),
+    ParlayVault(ParlayVault),
+    Tournament(Box<Tournament>),
+    TournamentEntry(TournamentEntry),
+    OutcomeShare(OutcomeShare),
+    ShareBalance(ShareBalance),
+    PriceHistory(Box<PriceHistory>),
+    AMMPool(AMMPool),
+    LiquidityPosition(LiquidityPosition),
+    OrderBook(OrderBook),
+    LimitOrder(LimitOrder),
+    Trade(Trade),
+}
+
+impl BetFunAccount {
+    /// Decode raw account data by its discriminator, at any layout version
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let discriminator = data.get(..8).ok_or(ErrorCode::AccountDiscriminatorNotFound)?;
+        let account = match discriminator {
+            d if d == GlobalConfig::DISCRIMINATOR => Self::GlobalConfig(GlobalConfig::read(data)?),
+            d if d == Arena::DISCRIMINATOR => Self::Arena(Box::new(Arena::read(data)?)),
+            d if d == Participant::DISCRIMINATOR => Self::Participant(Participant::read(data)?),
+            d if d == Referral::DISCRIMINATOR => Self::Referral(Referral::read(data)?),
+            d if d == UserProfile::DISCRIMINATOR => Self::UserProfile(UserProfile::read(data)?),
+            d if d == Parlay::DISCRIMINATOR => Self::Parlay(Parlay::read(data)?),
+            d if d == ParlayVault::DISCRIMINATOR => Self::ParlayVault(ParlayVault::read(data)?),
+            d if d == Tournament::DISCRIMINATOR => Self::Tournament(Box::new(Tournament::read(data)?)),
+            d if d == TournamentEntry::DISCRIMINATOR => Self::TournamentEntry(TournamentEntry::read(data)?),
+            d if d == OutcomeShare::DISCRIMINATOR => Self::OutcomeShare(OutcomeShare::read(data)?),
+            d if d == ShareBalance::DISCRIMINATOR => Self::ShareBalance(ShareBalance::read(data)?),
+            d if d == PriceHistory::DISCRIMINATOR => Self::PriceHistory(Box::new(PriceHistory::read(data)?)),
+            d if d == AMMPool::DISCRIMINATOR => Self::AMMPool(AMMPool::read(data)?),
+            d if d == LiquidityPosition::DISCRIMINATOR => Self::LiquidityPosition(LiquidityPosition::read(data)?),
+            d if d == OrderBook::DISCRIMINATOR => Self::OrderBook(OrderBook::read(data)?),
+            d if d == LimitOrder::DISCRIMINATOR => Self::LimitOrder(LimitOrder::read(data)?),
+            d if d == Trade::DISCRIMINATOR => Self::Trade(Trade::read(data)?),
+            _ => return Err(ErrorCode::AccountDiscriminatorMismatch.into()),
+        };
+        Ok(account)
+    }
+
+    /// Account type name, as in the program's IDL
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::GlobalConfig(_) => "GlobalConfig",
+            Self::Arena(_) => "Arena",
+            Self::Participant(_) => "Participant",
+            Self::Referral(_) => "Referral",
+            Self::UserProfile(_) => "UserProfile",
+            Self::Parlay(_) => "Parlay",
+            Self::ParlayVault(_) => "ParlayVault",
+            Self::Tournament(_) => "Tournament",
+            Self::TournamentEntry(_) => "TournamentEntry",
+            Self::OutcomeShare(_) => "OutcomeShare",
+            Self::ShareBalance(_) => "ShareBalance",
+            Self::PriceHistory(_) => "PriceHistory",
+            Self::AMMPool(_) => "AMMPool",
+            Self::LiquidityPosition(_) => "LiquidityPosition",
+            Self::OrderBook(_) => "OrderBook",
+            Self::LimitOrder(_) => "LimitOrder",
+            Self::Trade(_) => "Trade",
+        }
+    }
+
+    /// Layout version the account was written at
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::GlobalConfig(account) => account.version(),
+            Self::Arena(account) => account.version(),
+            Self::Participant(account) => account.version(),
+            Self::Referral(account) => account.version(),
+            Self::UserProfile(account) => account.version(),
+            Self::Parlay(account) => account.version(),
+            Self::ParlayVault(account) => account.version(),
+            Self::Tournament(account) => account.version(),
+            Self::TournamentEntry(account) => account.version(),
+            Self::OutcomeShare(account) => account.version(),
+            Self::ShareBalance(account) => account.version(),
+            Self::PriceHistory(account) => account.version(),
+            Self::AMMPool(account) => account.version(),
+            Self::LiquidityPosition(account) => account.version(),
+            Self::OrderBook(account) => account.version(),
+            Self::LimitOrder(account) => account.version(),
+            Self::Trade(account) => account.version(),
+        }
+    }
+
+    /// Whether the program accepts the account without `migrate_account`
+    pub fn is_current(&self) -> bool {
+        let current = match self {
+            Self::GlobalConfig(_) => GlobalConfig::VERSION,
+            Self::Arena(_) => Arena::VERSION,
+            Self::Participant(_) => Participant::VERSION,
+            Self::Referral(_) => Referral::VERSION,
+            Self::UserProfile(_) => UserProfile::VERSION,
+            Self::Parlay(_) => Parlay::VERSION,
+            Self::ParlayVault(_) => ParlayVault::VERSION,
+            Self::Tournament(_) => Tournament::VERSION,
+            Self::TournamentEntry(_) => TournamentEntry::VERSION,
+            Self::OutcomeShare(_) => OutcomeShare::VERSION,
+            Self::ShareBalance(_) => ShareBalance::VERSION,
+            Self::PriceHistory(_) => PriceHistory::VERSION,
+            Self::AMMPool(_) => AMMPool::VERSION,
+            Self::LiquidityPosition(_) => LiquidityPosition::VERSION,
+            Self::OrderBook(_) => OrderBook::VERSION,
+            Self::LimitOrder(_) => LimitOrder::VERSION,
+            Self::Trade(_) => Trade::VERSION,
+        };
+        self.version() == current
+    }
+}
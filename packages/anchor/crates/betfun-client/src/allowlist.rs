@@ -0,0 +1,62 @@
+//! Merkle allowlists for private arenas
+//!
+//! Build an [`Allowlist`] from the members' wallets, pass its [`root`] to
+//! `create_arena` or `set_allowlist`, and hand each member their [`proof`]
+//! for `join_arena` and `buy_shares`. Hashing matches
+//! `betfun::state::allowlist`.
+//!
+//! [`root`]: Allowlist::root
+//! [`proof`]: Allowlist::proof
+
+use anchor_lang::prelude::Pubkey;
+use betfun::state::allowlist::{leaf, parent};
+
+/// Merkle tree over a set of wallets
+pub struct Allowlist {
+    /// Sorted leaves first, the root's layer last
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl Allowlist {
+    /// Tree over `wallets`; duplicates are ignored
+    pub fn new(wallets: impl IntoIterator<Item = Pubkey>) -> Self {
+        let mut leaves: Vec<[u8; 32]> = wallets.into_iter().map(|wallet| leaf(&wallet)).collect();
+        leaves.sort_unstable();
+        leaves.dedup();
+
+        let mut layers = vec![leaves];
+        while layers.last().is_some_and(|layer| layer.len() > 1) {
+            // A node without a sibling moves up unchanged
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => parent(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+        Self { layers }
+    }
+
+    /// Root to store on the arena; all zeroes, which nothing proves, for an empty list
+    pub fn root(&self) -> [u8; 32] {
+        self.layers.last().and_then(|layer| layer.first()).copied().unwrap_or_default()
+    }
+
+    /// Sibling hashes from `wallet`'s leaf up, or `None` if it is not a member
+    pub fn proof(&self, wallet: &Pubkey) -> Option<Vec<[u8; 32]>> {
+        let mut index = self.layers[0].binary_search(&leaf(wallet)).ok()?;
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
@@ -0,0 +1,235 @@
+//! Event decoders
+//!
+//! `emit!` logs each event as `Program data: <base64>`, where the payload is
+//! the event's 8-byte discriminator followed by its Borsh body.
+//! [`parse_logs`] walks a transaction's log messages, tracks the invoke stack
+//! so only data logged by the BetFun program itself is read, and decodes it
+//! with [`BetFunEvent::decode`].
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+pub use betfun::instructions::{
+    AccountMigrated, AdminTransferred, AllowlistUpdated, ArenaCreated, ArenaDisputed, ArenaJoined, ArenaPauseUpdated,
+    ArenaResolved, ArenaVoided, BatchClaimed, BondShareClaimed, CompleteSetsMinted, ConfigInitialized,
+    CreatorBondReleased, CreatorBondUpdated, DisputeSettled, DuelAccepted, DuelCancelled, GlobalPauseUpdated,
+    LimitOrderCancelled, LimitOrderPlaced, LiquidityAdded, LiquidityRemoved, OrderBookInitialized,
+    ParlayApprovalUpdated, ParlayPlaced, ParlaySettled, ParlayVaultFunded, ParlayVaultWithdrawn, PoolInitialized,
+    ProfileSettled, ProtocolFeesUpdated, ReferralRewardsClaimed, ReferralShareUpdated, RefundClaimed,
+    ScalarArenaResolved, ShareTokensCreated, SharesPurchased, SharesRedeemed, SharesSold, SwapExecuted,
+    TournamentAdvanced, TournamentCancelled, TournamentCreated, TournamentEntered, TournamentMatchResolved,
+    TournamentPrizeClaimed, TournamentScoreSubmitted, TradeSettled, TrophyMinted, WinningsClaimed,
+};
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Any event the BetFun program emits
+pub enum BetFunEvent {
+    ArenaCreated(ArenaCreated),
+    ArenaJoined(ArenaJoined),
+    DuelAccepted(DuelAccepted),
+    DuelCancelled(DuelCancelled),
+    ArenaResolved(ArenaResolved),
+    ScalarArenaResolved(ScalarArenaResolved),
+    ArenaVoided(ArenaVoided),
+    WinningsClaimed(WinningsClaimed),
+    RefundClaimed(RefundClaimed),
+    BatchClaimed(BatchClaimed),
+    ProfileSettled(ProfileSettled),
+    CreatorBondReleased(CreatorBondReleased),
+    BondShareClaimed(BondShareClaimed),
+    ArenaDisputed(ArenaDisputed),
+    DisputeSettled(DisputeSettled),
+    TrophyMinted(TrophyMinted),
+    ShareTokensCreated(ShareTokensCreated),
+    SharesPurchased(SharesPurchased),
+    SharesSold(SharesSold),
+    SharesRedeemed(SharesRedeemed),
+    CompleteSetsMinted(CompleteSetsMinted),
+    PoolInitialized(PoolInitialized),
+    LiquidityAdded(LiquidityAdded),
+    LiquidityRemoved(LiquidityRemoved),
+    SwapExecuted(SwapExecuted),
+    OrderBookInitialized(OrderBookInitialized),
+    LimitOrderPlaced(LimitOrderPlaced),
+    LimitOrderCancelled(LimitOrderCancelled),
+    TradeSettled(TradeSettled),
+    AccountMigrated(AccountMigrated),
+    ConfigInitialized(ConfigInitialized),
+    GlobalPauseUpdated(GlobalPauseUpdated),
+    ArenaPauseUpdated(ArenaPauseUpdated),
+    AllowlistUpdated(AllowlistUpdated),
+    ReferralShareUpdated(ReferralShareUpdated),
+    CreatorBondUpdated(CreatorBondUpdated),
+    ProtocolFeesUpdated(ProtocolFeesUpdated),
+    AdminTransferred(AdminTransferred),
+    ReferralRewardsClaimed(ReferralRewardsClaimed),
+    ParlayVaultFunded(ParlayVaultFunded),
+    ParlayVaultWithdrawn(ParlayVaultWithdrawn),
+    ParlayApprovalUpdated(ParlayApprovalUpdated),
+    ParlayPlaced(ParlayPlaced),
+    ParlaySettled(ParlaySettled),
+    TournamentCreated(TournamentCreated),
+    TournamentEntered(TournamentEntered),
+    TournamentAdvanced(TournamentAdvanced),
+    TournamentMatchResolved(TournamentMatchResolved),
+    TournamentCancelled(TournamentCancelled),
+    TournamentScoreSubmitted(TournamentScoreSubmitted),
+    TournamentPrizeClaimed(TournamentPrizeClaimed),
+}
+
+impl BetFunEvent {
+    /// Decode an event from its discriminator and Borsh body
+    /// Returns `None` for data that is not a well-formed BetFun event.
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let discriminator = data.get(..8)?;
+        let mut body = &data[8..];
+        let event = match discriminator {
+            d if d == ArenaCreated::DISCRIMINATOR => Self::ArenaCreated(decode(&mut body)?),
+            d if d == ArenaJoined::DISCRIMINATOR => Self::ArenaJoined(decode(&mut body)?),
+            d if d == DuelAccepted::DISCRIMINATOR => Self::DuelAccepted(decode(&mut body)?),
+            d if d == DuelCancelled::DISCRIMINATOR => Self::DuelCancelled(decode(&mut body)?),
+            d if d == ArenaResolved::DISCRIMINATOR => Self::ArenaResolved(decode(&mut body)?),
+            d if d == ScalarArenaResolved::DISCRIMINATOR => Self::ScalarArenaResolved(decode(&mut body)?),
+            d if d == ArenaVoided::DISCRIMINATOR => Self::ArenaVoided(decode(&mut body)?),
+            d if d == WinningsClaimed::DISCRIMINATOR => Self::WinningsClaimed(decode(&mut body)?),
+            d if d == RefundClaimed::DISCRIMINATOR => Self::RefundClaimed(decode(&mut body)?),
+            d if d == BatchClaimed::DISCRIMINATOR => Self::BatchClaimed(decode(&mut body)?),
+            d if d == ProfileSettled::DISCRIMINATOR => Self::ProfileSettled(decode(&mut body)?),
+            d if d == CreatorBondReleased::DISCRIMINATOR => Self::CreatorBondReleased(decode(&mut body)?),
+            d if d == BondShareClaimed::DISCRIMINATOR => Self::BondShareClaimed(decode(&mut body)?),
+            d if d == ArenaDisputed::DISCRIMINATOR => Self::ArenaDisputed(decode(&mut body)?),
+            d if d == DisputeSettled::DISCRIMINATOR => Self::DisputeSettled(decode(&mut body)?),
+            d if d == TrophyMinted::DISCRIMINATOR => Self::TrophyMinted(decode(&mut body)?),
+            d if d == ShareTokensCreated::DISCRIMINATOR => Self::ShareTokensCreated(decode(&mut body)?),
+            d if d == SharesPurchased::DISCRIMINATOR => Self::SharesPurchased(decode(&mut body)?),
+            d if d == SharesSold::DISCRIMINATOR => Self::SharesSold(decode(&mut body)?),
+            d if d == SharesRedeemed::DISCRIMINATOR => Self::SharesRedeemed(decode(&mut body)?),
+            d if d == CompleteSetsMinted::DISCRIMINATOR => Self::CompleteSetsMinted(decode(&mut body)?),
+            d if d == PoolInitialized::DISCRIMINATOR => Self::PoolInitialized(decode(&mut body)?),
+            d if d == LiquidityAdded::DISCRIMINATOR => Self::LiquidityAdded(decode(&mut body)?),
+            d if d == LiquidityRemoved::DISCRIMINATOR => Self::LiquidityRemoved(decode(&mut body)?),
+            d if d == SwapExecuted::DISCRIMINATOR => Self::SwapExecuted(decode(&mut body)?),
+            d if d == OrderBookInitialized::DISCRIMINATOR => Self::OrderBookInitialized(decode(&mut body)?),
+            d if d == LimitOrderPlaced::DISCRIMINATOR => Self::LimitOrderPlaced(decode(&mut body)?),
+            d if d == LimitOrderCancelled::DISCRIMINATOR => Self::LimitOrderCancelled(decode(&mut body)?),
+            d if d == TradeSettled::DISCRIMINATOR => Self::TradeSettled(decode(&mut body)?),
+            d if d == AccountMigrated::DISCRIMINATOR => Self::AccountMigrated(decode(&mut body)?),
+            d if d == ConfigInitialized::DISCRIMINATOR => Self::ConfigInitialized(decode(&mut body)?),
+            d if d == GlobalPauseUpdated::DISCRIMINATOR => Self::GlobalPauseUpdated(decode(&mut body)?),
+            d if d == ArenaPauseUpdated::DISCRIMINATOR => Self::ArenaPauseUpdated(decode(&mut body)?),
+            d if d == AllowlistUpdated::DISCRIMINATOR => Self::AllowlistUpdated(decode(&mut body)?),
+            d if d == ReferralShareUpdated::DISCRIMINATOR => Self::ReferralShareUpdated(decode(&mut body)?),
+            d if d == CreatorBondUpdated::DISCRIMINATOR => Self::CreatorBondUpdated(decode(&mut body)?),
+            d if d == ProtocolFeesUpdated::DISCRIMINATOR => Self::ProtocolFeesUpdated(decode(&mut body)?),
+            d if d == AdminTransferred::DISCRIMINATOR => Self::AdminTransferred(decode(&mut body)?),
+            d if d == ReferralRewardsClaimed::DISCRIMINATOR => Self::ReferralRewardsClaimed(decode(&mut body)?),
+            d if d == ParlayVaultFunded::DISCRIMINATOR => Self::ParlayVaultFunded(decode(&mut body)?),
+            d if d == ParlayVaultWithdrawn::DISCRIMINATOR => Self::ParlayVaultWithdrawn(decode(&mut body)?),
+            d if d == ParlayApprovalUpdated::DISCRIMINATOR => Self::ParlayApprovalUpdated(decode(&mut body)?),
+            d if d == ParlayPlaced::DISCRIMINATOR => Self::ParlayPlaced(decode(&mut body)?),
+            d if d == ParlaySettled::DISCRIMINATOR => Self::ParlaySettled(decode(&mut body)?),
+            d if d == TournamentCreated::DISCRIMINATOR => Self::TournamentCreated(decode(&mut body)?),
+            d if d == TournamentEntered::DISCRIMINATOR => Self::TournamentEntered(decode(&mut body)?),
+            d if d == TournamentAdvanced::DISCRIMINATOR => Self::TournamentAdvanced(decode(&mut body)?),
+            d if d == TournamentMatchResolved::DISCRIMINATOR => Self::TournamentMatchResolved(decode(&mut body)?),
+            d if d == TournamentCancelled::DISCRIMINATOR => Self::TournamentCancelled(decode(&mut body)?),
+            d if d == TournamentScoreSubmitted::DISCRIMINATOR => Self::TournamentScoreSubmitted(decode(&mut body)?),
+            d if d == TournamentPrizeClaimed::DISCRIMINATOR => Self::TournamentPrizeClaimed(decode(&mut body)?),
+            _ => return None,
+        };
+        body.is_empty().then_some(event)
+    }
+
+    /// Event name, as in the program's IDL
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::ArenaCreated(_) => "ArenaCreated",
+            Self::ArenaJoined(_) => "ArenaJoined",
+            Self::DuelAccepted(_) => "DuelAccepted",
+            Self::DuelCancelled(_) => "DuelCancelled",
+            Self::ArenaResolved(_) => "ArenaResolved",
+            Self::ScalarArenaResolved(_) => "ScalarArenaResolved",
+            Self::ArenaVoided(_) => "ArenaVoided",
+            Self::WinningsClaimed(_) => "WinningsClaimed",
+            Self::RefundClaimed(_) => "RefundClaimed",
+            Self::BatchClaimed(_) => "BatchClaimed",
+            Self::ProfileSettled(_) => "ProfileSettled",
+            Self::CreatorBondReleased(_) => "CreatorBondReleased",
+            Self::BondShareClaimed(_) => "BondShareClaimed",
+            Self::ArenaDisputed(_) => "ArenaDisputed",
+            Self::DisputeSettled(_) => "DisputeSettled",
+            Self::TrophyMinted(_) => "TrophyMinted",
+            Self::ShareTokensCreated(_) => "ShareTokensCreated",
+            Self::SharesPurchased(_) => "SharesPurchased",
+            Self::SharesSold(_) => "SharesSold",
+            Self::SharesRedeemed(_) => "SharesRedeemed",
+            Self::CompleteSetsMinted(_) => "CompleteSetsMinted",
+            Self::PoolInitialized(_) => "PoolInitialized",
+            Self::LiquidityAdded(_) => "LiquidityAdded",
+            Self::LiquidityRemoved(_) => "LiquidityRemoved",
+            Self::SwapExecuted(_) => "SwapExecuted",
+            Self::OrderBookInitialized(_) => "OrderBookInitialized",
+            Self::LimitOrderPlaced(_) => "LimitOrderPlaced",
+            Self::LimitOrderCancelled(_) => "LimitOrderCancelled",
+            Self::TradeSettled(_) => "TradeSettled",
+            Self::AccountMigrated(_) => "AccountMigrated",
+            Self::ConfigInitialized(_) => "ConfigInitialized",
+            Self::GlobalPauseUpdated(_) => "GlobalPauseUpdated",
+            Self::ArenaPauseUpdated(_) => "ArenaPauseUpdated",
+            Self::AllowlistUpdated(_) => "AllowlistUpdated",
+            Self::ReferralShareUpdated(_) => "ReferralShareUpdated",
+            Self::CreatorBondUpdated(_) => "CreatorBondUpdated",
+            Self::ProtocolFeesUpdated(_) => "ProtocolFeesUpdated",
+            Self::AdminTransferred(_) => "AdminTransferred",
+            Self::ReferralRewardsClaimed(_) => "ReferralRewardsClaimed",
+            Self::ParlayVaultFunded(_) => "ParlayVaultFunded",
+            Self::ParlayVaultWithdrawn(_) => "ParlayVaultWithdrawn",
+            Self::ParlayApprovalUpdated(_) => "ParlayApprovalUpdated",
+            Self::ParlayPlaced(_) => "ParlayPlaced",
+            Self::ParlaySettled(_) => "ParlaySettled",
+            Self::TournamentCreated(_) => "TournamentCreated",
+            Self::TournamentEntered(_) => "TournamentEntered",
+            Self::TournamentAdvanced(_) => "TournamentAdvanced",
+            Self::TournamentMatchResolved(_) => "TournamentMatchResolved",
+            Self::TournamentCancelled(_) => "TournamentCancelled",
+            Self::TournamentScoreSubmitted(_) => "TournamentScoreSubmitted",
+            Self::TournamentPrizeClaimed(_) => "TournamentPrizeClaimed",
+        }
+    }
+}
+
+fn decode<T: AnchorDeserialize>(body: &mut &[u8]) -> Option<T> {
+    T::deserialize(body).ok()
+}
+
+/// Events the BetFun program emitted, in log order
+/// Data logged by other programs (including ones BetFun invokes) is ignored,
+/// as is anything that does not decode as a BetFun event. Logs of a failed
+/// transaction still parse; callers decide whether to keep them.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<BetFunEvent> {
+    let program_id = crate::ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+    for line in logs.iter().map(AsRef::as_ref) {
+        if let Some(data) = line.strip_prefix(PROGRAM_DATA) {
+            if stack.last() == Some(&program_id.as_str()) {
+                let event = BASE64.decode(data).ok().and_then(|data| BetFunEvent::decode(&data));
+                events.extend(event);
+            }
+            continue;
+        }
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        let mut words = rest.split(' ');
+        match (words.next(), words.next()) {
+            (Some(program), Some("invoke")) => stack.push(program),
+            (Some(program), Some("success" | "failed:")) if stack.last() == Some(&program) => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    events
+}
@@ -0,0 +1,1060 @@
+//! Instruction builders, one per program entrypoint
+//!
+//! Account lists come from the program's `accounts` structs and data from its
+//! `instruction` structs, with every PDA derived from the arguments. Share and
+//! LP token accounts are the owner's associated token accounts.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar;
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::{associated_token, token, token_2022};
+use betfun::instructions::PlaceOrderParams;
+use betfun::state::{Arena, CurveType};
+
+use crate::pda;
+
+/// Arguments of `create_arena`
+pub use betfun::instruction::CreateArena as CreateArenaArgs;
+
+/// Arguments of `create_tournament`
+pub use betfun::instruction::CreateTournament as CreateTournamentArgs;
+
+fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Optional `referrer` and `referral` accounts of a referred instruction
+fn referral_accounts(user: &Pubkey, referrer: Option<&Pubkey>) -> (Option<Pubkey>, Option<Pubkey>) {
+    match referrer {
+        Some(referrer) => (Some(*referrer), Some(pda::referral(user).0)),
+        None => (None, None),
+    }
+}
+
+// ========== CONFIG ==========
+
+/// `admin`, the program's upgrade authority, becomes the config admin; only
+/// the first call succeeds
+pub fn initialize_config(admin: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::InitializeConfig {
+            config: pda::config().0,
+            admin: *admin,
+            program: betfun::ID,
+            program_data: pda::program_data(),
+            system_program: system_program::ID,
+        },
+        betfun::instruction::InitializeConfig {},
+    )
+}
+
+/// Replace the global pause flags (see `betfun::state::pause`)
+pub fn set_global_pause(admin: &Pubkey, paused: u8) -> Instruction {
+    instruction(
+        betfun::accounts::SetGlobalPause {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        betfun::instruction::SetGlobalPause { paused },
+    )
+}
+
+/// Replace an arena's pause flags; `authority` is the creator or config admin
+pub fn set_arena_pause(arena: &Pubkey, authority: &Pubkey, paused: u8) -> Instruction {
+    instruction(
+        betfun::accounts::SetArenaPause {
+            arena: *arena,
+            config: pda::config().0,
+            authority: *authority,
+        },
+        betfun::instruction::SetArenaPause { paused },
+    )
+}
+
+/// Set the referrers' share of creator and protocol fees, in basis points
+pub fn set_referral_share(admin: &Pubkey, share_bps: u16) -> Instruction {
+    instruction(
+        betfun::accounts::SetReferralShare {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        betfun::instruction::SetReferralShare { share_bps },
+    )
+}
+
+/// `bond` is in lamports, or in base units of `bond_mint`, and applies to
+/// arenas created from now on
+pub fn set_creator_bond(admin: &Pubkey, bond: u64, bond_mint: Option<&Pubkey>) -> Instruction {
+    instruction(
+        betfun::accounts::SetCreatorBond {
+            config: pda::config().0,
+            admin: *admin,
+            bond_mint: bond_mint.copied(),
+        },
+        betfun::instruction::SetCreatorBond { bond },
+    )
+}
+
+/// `swap` and `settle_match` must then pay protocol fees to `fee_recipient`
+pub fn set_protocol_fees(admin: &Pubkey, fee_recipient: &Pubkey, order_book_fee_bps: u16) -> Instruction {
+    instruction(
+        betfun::accounts::SetProtocolFees {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        betfun::instruction::SetProtocolFees {
+            fee_recipient: *fee_recipient,
+            order_book_fee_bps,
+        },
+    )
+}
+
+/// Hand the config to `new_admin`
+pub fn transfer_admin(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::TransferAdmin {
+            config: pda::config().0,
+            admin: *admin,
+        },
+        betfun::instruction::TransferAdmin { new_admin: *new_admin },
+    )
+}
+
+// ========== ARENA ==========
+
+/// A duel also stakes the creator's bet on `duel.side`; `bond_mint` is the
+/// config's, when it bonds creators in a token, and the bond is then taken out
+/// of the creator's associated token account
+pub fn create_arena(creator: &Pubkey, args: CreateArenaArgs, bond_mint: Option<&Pubkey>) -> Instruction {
+    let arena = pda::arena(creator, &args.title).0;
+    let duel = args.duel.is_some();
+    instruction(
+        betfun::accounts::CreateArena {
+            arena,
+            config: pda::config().0,
+            creator: *creator,
+            participant: duel.then(|| pda::participant(&arena, creator).0),
+            user_profile: duel.then(|| pda::user_profile(creator).0),
+            bond_mint: bond_mint.copied(),
+            creator_bond_account: bond_mint.map(|mint| pda::bond_token_account(creator, mint)),
+            bond_vault: bond_mint.map(|_| pda::bond_vault(&arena).0),
+            token_program: bond_mint.map(|_| token::ID),
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+/// `referrer` is recorded on the user's first referred action and earns a
+/// share of the creator fee; `proof` is empty unless the arena is private
+pub fn join_arena(
+    arena: &Pubkey,
+    user: &Pubkey,
+    outcome_chosen: u8,
+    referrer: Option<&Pubkey>,
+    proof: &[[u8; 32]],
+) -> Instruction {
+    let (referrer, referral) = referral_accounts(user, referrer);
+    instruction(
+        betfun::accounts::JoinArena {
+            arena: *arena,
+            config: pda::config().0,
+            participant: pda::participant(arena, user).0,
+            user: *user,
+            user_profile: pda::user_profile(user).0,
+            referrer,
+            referral,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::JoinArena { outcome_chosen, proof: proof.to_vec() },
+    )
+}
+
+/// Replace an arena's allowlist root (see [`crate::allowlist`]); `None` makes it public
+pub fn set_allowlist(arena: &Pubkey, creator: &Pubkey, allowlist_root: Option<[u8; 32]>) -> Instruction {
+    instruction(
+        betfun::accounts::SetAllowlist {
+            arena: *arena,
+            creator: *creator,
+        },
+        betfun::instruction::SetAllowlist { allowlist_root },
+    )
+}
+
+/// `resolver` must be the arena creator or its oracle
+pub fn resolve_arena(arena: &Pubkey, resolver: &Pubkey, winner_outcome: u8) -> Instruction {
+    instruction(
+        betfun::accounts::ResolveArena {
+            arena: *arena,
+            resolver: *resolver,
+        },
+        betfun::instruction::ResolveArena { winner_outcome },
+    )
+}
+
+/// `resolver` must be the arena creator or its oracle; `value` is clamped to
+/// the arena's scalar range
+pub fn resolve_scalar_arena(arena: &Pubkey, resolver: &Pubkey, value: i64) -> Instruction {
+    instruction(
+        betfun::accounts::ResolveScalarArena {
+            arena: *arena,
+            resolver: *resolver,
+        },
+        betfun::instruction::ResolveScalarArena { value },
+    )
+}
+
+/// `creator` receives the creator fee on the first claim
+pub fn claim_winnings(arena: &Pubkey, user: &Pubkey, creator: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::ClaimWinnings {
+            arena: *arena,
+            config: pda::config().0,
+            participant: pda::participant(arena, user).0,
+            user: *user,
+            user_profile: pda::user_profile(user).0,
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::ClaimWinnings {},
+    )
+}
+
+/// One position settled by [`claim_many`]
+pub enum ClaimEntry {
+    /// A bet in `arena`, whose `creator` takes the creator fee on a win
+    /// Passed as 3 accounts: `arena`, the participant and `creator`.
+    Bet { arena: Pubkey, creator: Pubkey },
+    /// The whole share balance of `outcome_index` in a resolved or voided `arena`
+    /// Passed as 5 accounts: `arena`, the share balance, the outcome share,
+    /// its mint and `user`'s token account.
+    Shares { arena: Pubkey, outcome_index: u8 },
+}
+
+/// Claim every entry of `user` in one transaction; the accounts of each entry
+/// follow the named accounts in order, so entries may be mixed freely
+/// Entries with nothing to claim yet, such as losing positions or arenas
+/// still unresolved, are skipped rather than failing the batch.
+pub fn claim_many(user: &Pubkey, entries: &[ClaimEntry]) -> Instruction {
+    let mut instruction = instruction(
+        betfun::accounts::ClaimMany {
+            config: pda::config().0,
+            user: *user,
+            user_profile: pda::user_profile(user).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::ClaimMany {},
+    );
+    for entry in entries {
+        match entry {
+            ClaimEntry::Bet { arena, creator } => instruction.accounts.extend([
+                AccountMeta::new(*arena, false),
+                AccountMeta::new(pda::participant(arena, user).0, false),
+                AccountMeta::new(*creator, false),
+            ]),
+            ClaimEntry::Shares { arena, outcome_index } => {
+                let outcome_share = pda::outcome_share(arena, *outcome_index).0;
+                instruction.accounts.extend([
+                    AccountMeta::new(*arena, false),
+                    AccountMeta::new(pda::share_balance(&outcome_share, user).0, false),
+                    AccountMeta::new(outcome_share, false),
+                    AccountMeta::new(pda::share_mint(arena, *outcome_index).0, false),
+                    AccountMeta::new(pda::share_token_account(user, arena, *outcome_index), false),
+                ]);
+            }
+        }
+    }
+    instruction
+}
+
+/// Anyone may book `owner`'s losses in a resolved `arena`: their bet if `bet`
+/// is set, and their whole balance of the losing `shares` outcome if given
+pub fn settle_profile(arena: &Pubkey, owner: &Pubkey, caller: &Pubkey, bet: bool, shares: Option<u8>) -> Instruction {
+    let outcome_share = shares.map(|outcome_index| pda::outcome_share(arena, outcome_index).0);
+    instruction(
+        betfun::accounts::SettleProfile {
+            arena: *arena,
+            owner: *owner,
+            user_profile: pda::user_profile(owner).0,
+            participant: bet.then(|| pda::participant(arena, owner).0),
+            outcome_share,
+            share_balance: outcome_share.map(|outcome_share| pda::share_balance(&outcome_share, owner).0),
+            caller: *caller,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::SettleProfile {},
+    )
+}
+
+/// Anyone may void an arena left unresolved past its resolution window
+pub fn void_arena(arena: &Pubkey, caller: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::VoidArena {
+            arena: *arena,
+            caller: *caller,
+        },
+        betfun::instruction::VoidArena {},
+    )
+}
+
+/// The creator may withdraw a duel nobody has accepted, taking back their stake and bond
+pub fn cancel_duel(arena: &Pubkey, creator: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::CancelDuel {
+            arena: *arena,
+            participant: pda::participant(arena, creator).0,
+            creator: *creator,
+        },
+        betfun::instruction::CancelDuel {},
+    )
+}
+
+/// Anyone may return the bond of a resolved arena to its `creator`; a bond in
+/// `bond_mint` goes to the creator's associated token account
+pub fn release_creator_bond(
+    arena: &Pubkey,
+    creator: &Pubkey,
+    caller: &Pubkey,
+    bond_mint: Option<&Pubkey>,
+) -> Instruction {
+    instruction(
+        betfun::accounts::ReleaseCreatorBond {
+            arena: *arena,
+            creator: *creator,
+            caller: *caller,
+            bond_vault: bond_mint.map(|_| pda::bond_vault(arena).0),
+            creator_bond_account: bond_mint.map(|mint| pda::bond_token_account(creator, mint)),
+            token_program: bond_mint.map(|_| token::ID),
+        },
+        betfun::instruction::ReleaseCreatorBond {},
+    )
+}
+
+/// Claim `claimant`'s part of a slashed bond; a bond in `bond_mint` goes to
+/// their associated token account. `bettor` is false for the fee recipient,
+/// who claims the whole bond when nobody bet
+pub fn claim_bond_share(
+    arena: &Pubkey,
+    claimant: &Pubkey,
+    bond_mint: Option<&Pubkey>,
+    bettor: bool,
+) -> Instruction {
+    instruction(
+        betfun::accounts::ClaimBondShare {
+            arena: *arena,
+            config: pda::config().0,
+            participant: bettor.then(|| pda::participant(arena, claimant).0),
+            claimant: *claimant,
+            bond_vault: bond_mint.map(|_| pda::bond_vault(arena).0),
+            claimant_bond_account: bond_mint.map(|mint| pda::bond_token_account(claimant, mint)),
+            token_program: bond_mint.map(|_| token::ID),
+        },
+        betfun::instruction::ClaimBondShare {},
+    )
+}
+
+/// Anyone but the creator may dispute a bonded arena's resolution within the
+/// dispute window, depositing one entry fee
+pub fn dispute_arena(arena: &Pubkey, disputer: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::DisputeArena {
+            arena: *arena,
+            disputer: *disputer,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::DisputeArena {},
+    )
+}
+
+/// `admin` upholds or rejects the open dispute `disputer` raised; the deposit
+/// goes back to the disputer if upheld, else to `creator`
+pub fn settle_dispute(
+    arena: &Pubkey,
+    admin: &Pubkey,
+    disputer: &Pubkey,
+    creator: &Pubkey,
+    upheld: bool,
+) -> Instruction {
+    instruction(
+        betfun::accounts::SettleDispute {
+            arena: *arena,
+            config: pda::config().0,
+            admin: *admin,
+            disputer: *disputer,
+            creator: *creator,
+        },
+        betfun::instruction::SettleDispute { upheld },
+    )
+}
+
+/// Mint `user`'s Token-2022 trophy for a claimed win
+pub fn mint_trophy(arena: &Pubkey, user: &Pubkey) -> Instruction {
+    let participant = pda::participant(arena, user).0;
+    let trophy_mint = pda::trophy_mint(&participant).0;
+    instruction(
+        betfun::accounts::MintTrophy {
+            arena: *arena,
+            config: pda::config().0,
+            participant,
+            user_profile: pda::user_profile(user).0,
+            trophy_mint,
+            trophy_authority: pda::trophy_authority().0,
+            user_trophy_account: pda::trophy_token_account(user, &trophy_mint),
+            user: *user,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::MintTrophy {},
+    )
+}
+
+// ========== SHARES ==========
+
+pub fn create_share_tokens(
+    arena: &Pubkey,
+    creator: &Pubkey,
+    outcome_index: u8,
+    initial_price: u64,
+    curve_type: CurveType,
+    curve_param: u64,
+) -> Instruction {
+    instruction(
+        betfun::accounts::CreateShareTokens {
+            arena: *arena,
+            config: pda::config().0,
+            creator: *creator,
+            outcome_share: pda::outcome_share(arena, outcome_index).0,
+            share_mint: pda::share_mint(arena, outcome_index).0,
+            price_history: pda::price_history(arena, outcome_index).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        betfun::instruction::CreateShareTokens {
+            outcome_index,
+            initial_price,
+            curve_type,
+            curve_param,
+        },
+    )
+}
+
+/// `creator` receives the arena's trading fee; `referrer` earns a share of it;
+/// `proof` is empty unless the arena is private
+#[allow(clippy::too_many_arguments)]
+pub fn buy_shares(
+    arena: &Pubkey,
+    buyer: &Pubkey,
+    creator: &Pubkey,
+    outcome_index: u8,
+    amount: u64,
+    max_total_cost: Option<u64>,
+    deadline_ts: Option<i64>,
+    referrer: Option<&Pubkey>,
+    proof: &[[u8; 32]],
+) -> Instruction {
+    let outcome_share = pda::outcome_share(arena, outcome_index).0;
+    let (referrer, referral) = referral_accounts(buyer, referrer);
+    instruction(
+        betfun::accounts::BuyShares {
+            arena: *arena,
+            config: pda::config().0,
+            outcome_share,
+            share_mint: pda::share_mint(arena, outcome_index).0,
+            price_history: pda::price_history(arena, outcome_index).0,
+            share_balance: pda::share_balance(&outcome_share, buyer).0,
+            buyer_token_account: pda::share_token_account(buyer, arena, outcome_index),
+            buyer: *buyer,
+            user_profile: pda::user_profile(buyer).0,
+            arena_escrow: *arena,
+            creator: *creator,
+            referrer,
+            referral,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        betfun::instruction::BuyShares {
+            _outcome_index: outcome_index,
+            amount,
+            max_total_cost,
+            deadline_ts,
+            proof: proof.to_vec(),
+        },
+    )
+}
+
+pub fn sell_shares(
+    arena: &Pubkey,
+    seller: &Pubkey,
+    outcome_index: u8,
+    shares_to_sell: u64,
+    min_total_proceeds: Option<u64>,
+    deadline_ts: Option<i64>,
+) -> Instruction {
+    let outcome_share = pda::outcome_share(arena, outcome_index).0;
+    instruction(
+        betfun::accounts::SellShares {
+            arena: *arena,
+            config: pda::config().0,
+            outcome_share,
+            share_mint: pda::share_mint(arena, outcome_index).0,
+            price_history: pda::price_history(arena, outcome_index).0,
+            share_balance: pda::share_balance(&outcome_share, seller).0,
+            seller_token_account: pda::share_token_account(seller, arena, outcome_index),
+            seller: *seller,
+            user_profile: pda::user_profile(seller).0,
+            arena_escrow: *arena,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::SellShares {
+            _outcome_index: outcome_index,
+            shares_to_sell,
+            min_total_proceeds,
+            deadline_ts,
+        },
+    )
+}
+
+pub fn redeem_shares(
+    arena: &Pubkey,
+    redeemer: &Pubkey,
+    outcome_index: u8,
+    amount: u64,
+    min_total_proceeds: Option<u64>,
+    deadline_ts: Option<i64>,
+) -> Instruction {
+    let outcome_share = pda::outcome_share(arena, outcome_index).0;
+    instruction(
+        betfun::accounts::RedeemShares {
+            arena: *arena,
+            config: pda::config().0,
+            outcome_share,
+            share_mint: pda::share_mint(arena, outcome_index).0,
+            share_balance: pda::share_balance(&outcome_share, redeemer).0,
+            redeemer_token_account: pda::share_token_account(redeemer, arena, outcome_index),
+            redeemer: *redeemer,
+            user_profile: pda::user_profile(redeemer).0,
+            arena_escrow: *arena,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::RedeemShares {
+            _outcome_index: outcome_index,
+            amount,
+            min_total_proceeds,
+            deadline_ts,
+        },
+    )
+}
+
+/// Mint `amount` LONG/SHORT pairs of a scalar arena; `max_cost` covers the
+/// 1 SOL collateral per pair and the creator trading fee
+pub fn mint_complete_sets(
+    arena: &Pubkey,
+    buyer: &Pubkey,
+    creator: &Pubkey,
+    amount: u64,
+    max_cost: u64,
+    deadline_ts: Option<i64>,
+    proof: &[[u8; 32]],
+) -> Instruction {
+    let long_share = pda::outcome_share(arena, Arena::LONG).0;
+    let short_share = pda::outcome_share(arena, Arena::SHORT).0;
+    instruction(
+        betfun::accounts::MintCompleteSets {
+            arena: *arena,
+            config: pda::config().0,
+            long_share,
+            short_share,
+            long_mint: pda::share_mint(arena, Arena::LONG).0,
+            short_mint: pda::share_mint(arena, Arena::SHORT).0,
+            long_balance: pda::share_balance(&long_share, buyer).0,
+            short_balance: pda::share_balance(&short_share, buyer).0,
+            long_token_account: pda::share_token_account(buyer, arena, Arena::LONG),
+            short_token_account: pda::share_token_account(buyer, arena, Arena::SHORT),
+            buyer: *buyer,
+            user_profile: pda::user_profile(buyer).0,
+            creator: *creator,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::MintCompleteSets { amount, max_cost, deadline_ts, proof: proof.to_vec() },
+    )
+}
+
+/// Create `owner`'s share token account through the associated token program
+pub fn create_share_token_account(payer: &Pubkey, owner: &Pubkey, arena: &Pubkey, outcome_index: u8) -> Instruction {
+    associated_token::spl_associated_token_account::instruction::create_associated_token_account(
+        payer,
+        owner,
+        &pda::share_mint(arena, outcome_index).0,
+        &token::ID,
+    )
+}
+
+// ========== AMM ==========
+
+pub fn initialize_pool(
+    arena: &Pubkey,
+    creator: &Pubkey,
+    outcome_index: u8,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+) -> Instruction {
+    let pool = pda::amm_pool(arena, outcome_index).0;
+    instruction(
+        betfun::accounts::InitializePool {
+            arena: *arena,
+            config: pda::config().0,
+            outcome_share: pda::outcome_share(arena, outcome_index).0,
+            pool,
+            lp_token_mint: pda::lp_token(&pool).0,
+            share_mint: pda::share_mint(arena, outcome_index).0,
+            pool_token_vault: pda::pool_token_vault(&pool).0,
+            creator: *creator,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        betfun::instruction::InitializePool {
+            outcome_index,
+            fee_bps,
+            protocol_fee_bps,
+        },
+    )
+}
+
+pub fn add_liquidity(
+    arena: &Pubkey,
+    provider: &Pubkey,
+    outcome_index: u8,
+    token_amount: u64,
+    sol_amount: u64,
+    min_lp_tokens: u64,
+) -> Instruction {
+    let pool = pda::amm_pool(arena, outcome_index).0;
+    instruction(
+        betfun::accounts::AddLiquidity {
+            arena: *arena,
+            config: pda::config().0,
+            pool,
+            lp_token_mint: pda::lp_token(&pool).0,
+            pool_token_vault: pda::pool_token_vault(&pool).0,
+            provider_token_account: pda::share_token_account(provider, arena, outcome_index),
+            provider_lp_token_account: pda::lp_token_account(provider, &pool),
+            liquidity_position: pda::liquidity_position(&pool, provider).0,
+            provider: *provider,
+            pool_sol_vault: pda::pool_sol_vault(&pool).0,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        betfun::instruction::AddLiquidity {
+            token_amount,
+            sol_amount,
+            min_lp_tokens,
+        },
+    )
+}
+
+pub fn remove_liquidity(
+    arena: &Pubkey,
+    provider: &Pubkey,
+    outcome_index: u8,
+    lp_tokens_to_burn: u64,
+    min_token_amount: u64,
+    min_sol_amount: u64,
+) -> Instruction {
+    let pool = pda::amm_pool(arena, outcome_index).0;
+    instruction(
+        betfun::accounts::RemoveLiquidity {
+            arena: *arena,
+            pool,
+            lp_token_mint: pda::lp_token(&pool).0,
+            pool_token_vault: pda::pool_token_vault(&pool).0,
+            provider_token_account: pda::share_token_account(provider, arena, outcome_index),
+            provider_lp_token_account: pda::lp_token_account(provider, &pool),
+            liquidity_position: pda::liquidity_position(&pool, provider).0,
+            provider: *provider,
+            pool_sol_vault: pda::pool_sol_vault(&pool).0,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::RemoveLiquidity {
+            lp_tokens_to_burn,
+            min_token_amount,
+            min_sol_amount,
+        },
+    )
+}
+
+/// Swap through the outcome's pool using the user's share token account
+/// `creator` receives the arena's trading fee; `referrer` earns a share of it
+/// and of the protocol fee.
+#[allow(clippy::too_many_arguments)]
+pub fn swap(
+    arena: &Pubkey,
+    user: &Pubkey,
+    creator: &Pubkey,
+    protocol_fee_recipient: &Pubkey,
+    outcome_index: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_token_to_sol: bool,
+    referrer: Option<&Pubkey>,
+) -> Instruction {
+    let pool = pda::amm_pool(arena, outcome_index).0;
+    let (referrer, referral) = referral_accounts(user, referrer);
+    instruction(
+        betfun::accounts::Swap {
+            arena: *arena,
+            config: pda::config().0,
+            pool,
+            price_history: pda::price_history(arena, outcome_index).0,
+            pool_token_vault: pda::pool_token_vault(&pool).0,
+            user_token_account: pda::share_token_account(user, arena, outcome_index),
+            user: *user,
+            pool_sol_vault: pda::pool_sol_vault(&pool).0,
+            protocol_fee_recipient: *protocol_fee_recipient,
+            creator: *creator,
+            referrer,
+            referral,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::Swap {
+            amount_in,
+            min_amount_out,
+            is_token_to_sol,
+        },
+    )
+}
+
+// ========== ORDER BOOK ==========
+
+pub fn initialize_order_book(arena: &Pubkey, creator: &Pubkey, outcome_index: u8) -> Instruction {
+    instruction(
+        betfun::accounts::InitializeOrderBook {
+            arena: *arena,
+            config: pda::config().0,
+            outcome_share: pda::outcome_share(arena, outcome_index).0,
+            order_book: pda::order_book(arena, outcome_index).0,
+            creator: *creator,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::InitializeOrderBook { outcome_index },
+    )
+}
+
+/// `order_id` must be the order book's current `next_order_id`
+pub fn place_limit_order(
+    arena: &Pubkey,
+    owner: &Pubkey,
+    outcome_index: u8,
+    order_id: u64,
+    params: PlaceOrderParams,
+) -> Instruction {
+    let limit_order = pda::limit_order(arena, outcome_index, order_id).0;
+    instruction(
+        betfun::accounts::PlaceLimitOrder {
+            arena: *arena,
+            config: pda::config().0,
+            order_book: pda::order_book(arena, outcome_index).0,
+            limit_order,
+            share_mint: pda::share_mint(arena, outcome_index).0,
+            owner_token_account: pda::share_token_account(owner, arena, outcome_index),
+            order_escrow: pda::order_escrow(&limit_order).0,
+            owner: *owner,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        betfun::instruction::PlaceLimitOrder {
+            outcome_index,
+            params,
+        },
+    )
+}
+
+pub fn cancel_order(arena: &Pubkey, owner: &Pubkey, outcome_index: u8, order_id: u64) -> Instruction {
+    let limit_order = pda::limit_order(arena, outcome_index, order_id).0;
+    instruction(
+        betfun::accounts::CancelOrder {
+            arena: *arena,
+            order_book: pda::order_book(arena, outcome_index).0,
+            limit_order,
+            owner_token_account: pda::share_token_account(owner, arena, outcome_index),
+            order_escrow: pda::order_escrow(&limit_order).0,
+            owner: *owner,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::CancelOrder {},
+    )
+}
+
+/// Orders and parties of a `settle_match` call
+#[derive(Clone, Copy, Debug)]
+pub struct MatchedOrders {
+    pub outcome_index: u8,
+    pub buy_order_id: u64,
+    pub sell_order_id: u64,
+    /// Must be the order book's current `trade_count`
+    pub trade_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub protocol_fee_recipient: Pubkey,
+    /// Arena creator, paid the arena's trading fee
+    pub creator: Pubkey,
+    pub matcher: Pubkey,
+}
+
+pub fn settle_match(
+    arena: &Pubkey,
+    orders: &MatchedOrders,
+    match_size: u64,
+    match_price: u64,
+) -> Instruction {
+    let outcome_index = orders.outcome_index;
+    let buy_order = pda::limit_order(arena, outcome_index, orders.buy_order_id).0;
+    let sell_order = pda::limit_order(arena, outcome_index, orders.sell_order_id).0;
+    instruction(
+        betfun::accounts::SettleMatch {
+            arena: *arena,
+            config: pda::config().0,
+            order_book: pda::order_book(arena, outcome_index).0,
+            price_history: pda::price_history(arena, outcome_index).0,
+            buy_order,
+            sell_order,
+            trade: pda::trade(arena, outcome_index, orders.trade_id).0,
+            buy_order_escrow: pda::order_escrow(&buy_order).0,
+            sell_order_escrow: pda::order_escrow(&sell_order).0,
+            buyer_token_account: pda::share_token_account(&orders.buyer, arena, outcome_index),
+            seller: orders.seller,
+            buyer: orders.buyer,
+            protocol_fee_recipient: orders.protocol_fee_recipient,
+            creator: orders.creator,
+            matcher: orders.matcher,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        },
+        betfun::instruction::SettleMatch {
+            match_size,
+            match_price,
+        },
+    )
+}
+
+// ========== REFERRALS ==========
+
+/// `referrer` withdraws the rewards `user`'s referral has accrued
+pub fn claim_referral_rewards(user: &Pubkey, referrer: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::ClaimReferralRewards {
+            referral: pda::referral(user).0,
+            referrer: *referrer,
+        },
+        betfun::instruction::ClaimReferralRewards {},
+    )
+}
+
+// ========== PARLAYS ==========
+
+/// Deposit house liquidity into the parlay vault, creating it on first use
+pub fn fund_parlay_vault(admin: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        betfun::accounts::FundParlayVault {
+            config: pda::config().0,
+            parlay_vault: pda::parlay_vault().0,
+            admin: *admin,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::FundParlayVault { amount },
+    )
+}
+
+pub fn withdraw_parlay_vault(admin: &Pubkey, amount: u64) -> Instruction {
+    instruction(
+        betfun::accounts::WithdrawParlayVault {
+            config: pda::config().0,
+            parlay_vault: pda::parlay_vault().0,
+            admin: *admin,
+        },
+        betfun::instruction::WithdrawParlayVault { amount },
+    )
+}
+
+/// `approved` allows `arena` to be picked as a parlay leg; `false` revokes it
+pub fn set_parlay_approval(arena: &Pubkey, admin: &Pubkey, approved: bool) -> Instruction {
+    instruction(
+        betfun::accounts::SetParlayApproval {
+            arena: *arena,
+            config: pda::config().0,
+            admin: *admin,
+        },
+        betfun::instruction::SetParlayApproval { approved },
+    )
+}
+
+/// `legs` pairs each arena with the outcome picked in it; the arenas follow
+/// the named accounts in the same order
+pub fn place_parlay(owner: &Pubkey, parlay_id: u64, legs: &[(Pubkey, u8)], stake: u64, min_payout: u64) -> Instruction {
+    let mut instruction = instruction(
+        betfun::accounts::PlaceParlay {
+            config: pda::config().0,
+            parlay_vault: pda::parlay_vault().0,
+            parlay: pda::parlay(owner, parlay_id).0,
+            owner: *owner,
+            user_profile: pda::user_profile(owner).0,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::PlaceParlay {
+            parlay_id,
+            picks: legs.iter().map(|(_, outcome)| *outcome).collect(),
+            stake,
+            min_payout,
+        },
+    );
+    instruction.accounts.extend(legs.iter().map(|(arena, _)| AccountMeta::new_readonly(*arena, false)));
+    instruction
+}
+
+/// `arenas` are the parlay's leg arenas in leg order; anyone may settle
+pub fn settle_parlay(settler: &Pubkey, owner: &Pubkey, parlay_id: u64, arenas: &[Pubkey]) -> Instruction {
+    let mut instruction = instruction(
+        betfun::accounts::SettleParlay {
+            config: pda::config().0,
+            parlay_vault: pda::parlay_vault().0,
+            parlay: pda::parlay(owner, parlay_id).0,
+            owner: *owner,
+            user_profile: pda::user_profile(owner).0,
+            settler: *settler,
+        },
+        betfun::instruction::SettleParlay {},
+    );
+    instruction.accounts.extend(arenas.iter().map(|arena| AccountMeta::new_readonly(*arena, false)));
+    instruction
+}
+
+// ========== TOURNAMENTS ==========
+
+pub fn create_tournament(organizer: &Pubkey, args: CreateTournamentArgs) -> Instruction {
+    instruction(
+        betfun::accounts::CreateTournament {
+            tournament: pda::tournament(organizer, &args.name).0,
+            config: pda::config().0,
+            organizer: *organizer,
+            system_program: system_program::ID,
+        },
+        args,
+    )
+}
+
+pub fn enter_tournament(tournament: &Pubkey, user: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::EnterTournament {
+            tournament: *tournament,
+            config: pda::config().0,
+            entry: pda::tournament_entry(tournament, user).0,
+            user: *user,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::EnterTournament {},
+    )
+}
+
+/// Create the match arena of `slot`; anyone may pay for it
+pub fn advance_tournament(tournament: &Pubkey, payer: &Pubkey, slot: u8) -> Instruction {
+    instruction(
+        betfun::accounts::AdvanceTournament {
+            tournament: *tournament,
+            arena: pda::tournament_match(tournament, slot).0,
+            config: pda::config().0,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::AdvanceTournament { slot },
+    )
+}
+
+pub fn resolve_tournament_match(tournament: &Pubkey, organizer: &Pubkey, slot: u8, winner_outcome: u8) -> Instruction {
+    instruction(
+        betfun::accounts::ResolveTournamentMatch {
+            tournament: *tournament,
+            arena: pda::tournament_match(tournament, slot).0,
+            organizer: *organizer,
+        },
+        betfun::instruction::ResolveTournamentMatch { slot, winner_outcome },
+    )
+}
+
+/// Cancel the tournament over its voided, undecided match in `slot`
+pub fn cancel_tournament(tournament: &Pubkey, caller: &Pubkey, slot: u8) -> Instruction {
+    instruction(
+        betfun::accounts::CancelTournament {
+            tournament: *tournament,
+            arena: pda::tournament_match(tournament, slot).0,
+            caller: *caller,
+        },
+        betfun::instruction::CancelTournament { slot },
+    )
+}
+
+/// `picked` are the slots `user` bet in; their participant accounts follow
+/// the named accounts
+pub fn submit_tournament_score(tournament: &Pubkey, user: &Pubkey, picked: &[u8]) -> Instruction {
+    let mut instruction = instruction(
+        betfun::accounts::SubmitTournamentScore {
+            tournament: *tournament,
+            entry: pda::tournament_entry(tournament, user).0,
+            user: *user,
+        },
+        betfun::instruction::SubmitTournamentScore {},
+    );
+    instruction.accounts.extend(picked.iter().map(|slot| {
+        let arena = pda::tournament_match(tournament, *slot).0;
+        AccountMeta::new_readonly(pda::participant(&arena, user).0, false)
+    }));
+    instruction
+}
+
+pub fn claim_tournament_prize(tournament: &Pubkey, user: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::ClaimTournamentPrize {
+            tournament: *tournament,
+            config: pda::config().0,
+            entry: pda::tournament_entry(tournament, user).0,
+            user: *user,
+        },
+        betfun::instruction::ClaimTournamentPrize {},
+    )
+}
+
+// ========== MIGRATION ==========
+
+/// Upgrade `account` to its current layout; `payer` covers any extra rent
+pub fn migrate_account(account: &Pubkey, payer: &Pubkey) -> Instruction {
+    instruction(
+        betfun::accounts::MigrateAccount {
+            account: *account,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        betfun::instruction::MigrateAccount {},
+    )
+}
@@ -0,0 +1,24 @@
+//! Native Rust client for the BetFun program
+//!
+//! Built on the program crate itself (`cpi` feature), so PDA seeds, account
+//! lists, instruction data and account layouts are checked by the compiler
+//! against the program instead of being maintained by hand:
+//! - [`pda`]: address derivation for every program-derived account
+//! - [`instructions`]: one builder per `lib.rs` entrypoint
+//! - [`accounts`]: decoders for every account type the program owns
+//! - [`events`]: decoders for the events the program emits
+//! - [`quote`]: swap, liquidity, share and payout quotes that match execution
+//! - [`allowlist`]: Merkle roots and proofs for private arenas
+
+pub mod accounts;
+pub mod allowlist;
+pub mod events;
+pub mod instructions;
+pub mod pda;
+pub mod quote;
+
+pub use betfun::error::BetFunError;
+pub use betfun::instructions::PlaceOrderParams;
+pub use betfun::state;
+pub use betfun::ID;
+pub use betfun_math as math;
@@ -0,0 +1,178 @@
+//! Program-derived addresses
+//!
+//! Every function returns `(address, bump)`, matching the seeds the program
+//! checks in its account constraints.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_spl::associated_token::{get_associated_token_address, get_associated_token_address_with_program_id};
+
+fn find(seeds: &[&[u8]]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(seeds, &betfun::ID)
+}
+
+// ========== CONFIG ==========
+
+/// Program-wide config holding the admin and global pause flags
+pub fn config() -> (Pubkey, u8) {
+    find(&[b"config"])
+}
+
+/// ProgramData account holding the program's upgrade authority
+pub fn program_data() -> Pubkey {
+    bpf_loader_upgradeable::get_program_data_address(&betfun::ID)
+}
+
+// ========== ARENA ==========
+
+/// Arena escrow and state, keyed by creator and title
+///
+/// Titles over the seed limit are cut to it, as `create_arena` does, so such
+/// a request reaches the program and fails with `TitleTooLong`.
+pub fn arena(creator: &Pubkey, title: &str) -> (Pubkey, u8) {
+    let seed = &title.as_bytes()[..title.len().min(betfun::state::Arena::MAX_TITLE_LEN)];
+    find(&[b"arena", creator.as_ref(), seed])
+}
+
+/// A user's bet in an arena
+pub fn participant(arena: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"participant", arena.as_ref(), user.as_ref()])
+}
+
+/// Token account escrowing an arena's creator bond, when the bond is in a token
+pub fn bond_vault(arena: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"bond_vault", arena.as_ref()])
+}
+
+/// Owner's SPL Token account for the config's bond mint
+pub fn bond_token_account(owner: &Pubkey, bond_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, bond_mint)
+}
+
+/// Who referred a user, holding the referrer's unclaimed rewards
+pub fn referral(user: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"referral", user.as_ref()])
+}
+
+/// Token-2022 trophy NFT minted for a winning participant
+pub fn trophy_mint(participant: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"trophy_mint", participant.as_ref()])
+}
+
+/// Mint authority of every genuine trophy
+pub fn trophy_authority() -> (Pubkey, u8) {
+    find(&[b"trophy_authority"])
+}
+
+/// Owner's Token-2022 token account for a trophy mint
+pub fn trophy_token_account(owner: &Pubkey, trophy_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, trophy_mint, &anchor_spl::token_2022::ID)
+}
+
+/// Lifetime statistics of a wallet
+pub fn user_profile(user: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"user_profile", user.as_ref()])
+}
+
+// ========== PARLAYS ==========
+
+/// House liquidity backing parlay payouts
+pub fn parlay_vault() -> (Pubkey, u8) {
+    find(&[b"parlay_vault"])
+}
+
+/// A parlay, keyed by owner and an owner-chosen id
+pub fn parlay(owner: &Pubkey, parlay_id: u64) -> (Pubkey, u8) {
+    find(&[b"parlay", owner.as_ref(), &parlay_id.to_le_bytes()])
+}
+
+// ========== TOURNAMENTS ==========
+
+/// Tournament bracket and pot, keyed by organizer and name
+pub fn tournament(organizer: &Pubkey, name: &str) -> (Pubkey, u8) {
+    find(&[b"tournament", organizer.as_ref(), name.as_bytes()])
+}
+
+/// A wallet's entry into a tournament
+pub fn tournament_entry(tournament: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"tournament_entry", tournament.as_ref(), user.as_ref()])
+}
+
+/// Match arena deciding a bracket slot
+pub fn tournament_match(tournament: &Pubkey, slot: u8) -> (Pubkey, u8) {
+    arena(tournament, &betfun::state::Tournament::match_title(slot))
+}
+
+// ========== SHARES ==========
+
+pub fn outcome_share(arena: &Pubkey, outcome_index: u8) -> (Pubkey, u8) {
+    find(&[b"outcome_share", arena.as_ref(), &[outcome_index]])
+}
+
+pub fn share_mint(arena: &Pubkey, outcome_index: u8) -> (Pubkey, u8) {
+    find(&[b"share_mint", arena.as_ref(), &[outcome_index]])
+}
+
+/// Hourly candles for an outcome
+pub fn price_history(arena: &Pubkey, outcome_index: u8) -> (Pubkey, u8) {
+    find(&betfun::state::PriceHistory::seeds(arena, &outcome_index))
+}
+
+/// Cost basis and P&L for one owner of an outcome share
+pub fn share_balance(outcome_share: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"share_balance", outcome_share.as_ref(), owner.as_ref()])
+}
+
+/// Owner's associated token account for an outcome's share mint
+pub fn share_token_account(owner: &Pubkey, arena: &Pubkey, outcome_index: u8) -> Pubkey {
+    get_associated_token_address(owner, &share_mint(arena, outcome_index).0)
+}
+
+// ========== AMM ==========
+
+pub fn amm_pool(arena: &Pubkey, outcome_index: u8) -> (Pubkey, u8) {
+    find(&[b"amm_pool", arena.as_ref(), &[outcome_index]])
+}
+
+/// LP token mint of a pool
+pub fn lp_token(pool: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"lp_token", pool.as_ref()])
+}
+
+/// Share token vault of a pool
+pub fn pool_token_vault(pool: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"pool_token_vault", pool.as_ref()])
+}
+
+/// System account holding a pool's SOL reserve
+pub fn pool_sol_vault(pool: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"pool_sol_vault", pool.as_ref()])
+}
+
+pub fn liquidity_position(pool: &Pubkey, provider: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"liquidity_position", pool.as_ref(), provider.as_ref()])
+}
+
+/// Provider's associated token account for a pool's LP mint
+pub fn lp_token_account(provider: &Pubkey, pool: &Pubkey) -> Pubkey {
+    get_associated_token_address(provider, &lp_token(pool).0)
+}
+
+// ========== ORDER BOOK ==========
+
+pub fn order_book(arena: &Pubkey, outcome_index: u8) -> (Pubkey, u8) {
+    find(&[b"order_book", arena.as_ref(), &[outcome_index]])
+}
+
+pub fn limit_order(arena: &Pubkey, outcome_index: u8, order_id: u64) -> (Pubkey, u8) {
+    find(&[b"limit_order", arena.as_ref(), &[outcome_index], &order_id.to_le_bytes()])
+}
+
+/// Escrow of a limit order (SOL for bids, a share token account for asks)
+pub fn order_escrow(limit_order: &Pubkey) -> (Pubkey, u8) {
+    find(&[b"order_escrow", limit_order.as_ref()])
+}
+
+pub fn trade(arena: &Pubkey, outcome_index: u8, trade_id: u64) -> (Pubkey, u8) {
+    find(&[b"trade", arena.as_ref(), &[outcome_index], &trade_id.to_le_bytes()])
+}
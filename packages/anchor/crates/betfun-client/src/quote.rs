@@ -0,0 +1,93 @@
+//! Quotes from decoded accounts
+//!
+//! Each function runs the same `betfun-math` code the program executes, so a
+//! quote taken from fresh account state matches the transaction to the
+//! lamport. Use the quote's `min_*` helpers to turn it into a slippage bound.
+
+use anchor_lang::{require, Result};
+use betfun::error::BetFunError;
+use betfun::state::{AMMPool, Arena, OutcomeShare, Participant};
+use betfun_math::amm::{self, DepositQuote, SwapQuote, WithdrawQuote};
+use betfun_math::curve::{Curve, TradeQuote};
+use betfun_math::payout::{self, ClaimQuote};
+
+// ========== AMM ==========
+
+/// Quote `swap`: `amount_in` share base units (`is_token_to_sol`) or lamports
+/// The arena's creator trading fee is charged like the protocol fee.
+pub fn swap(arena: &Arena, pool: &AMMPool, amount_in: u64, is_token_to_sol: bool) -> Result<SwapQuote> {
+    let pool = amm::Pool { creator_fee_bps: arena.creator_trading_fee_bps, ..pool.math() };
+    Ok(pool.quote_swap(amount_in, is_token_to_sol).map_err(BetFunError::from)?)
+}
+
+/// Quote `add_liquidity`
+pub fn add_liquidity(pool: &AMMPool, token_amount: u64, sol_amount: u64) -> Result<DepositQuote> {
+    Ok(pool.math().quote_add_liquidity(token_amount, sol_amount).map_err(BetFunError::from)?)
+}
+
+/// Quote `remove_liquidity`
+pub fn remove_liquidity(pool: &AMMPool, lp_tokens: u64) -> Result<WithdrawQuote> {
+    Ok(pool.math().quote_remove_liquidity(lp_tokens).map_err(BetFunError::from)?)
+}
+
+// ========== SHARES ==========
+
+/// Quote `buy_shares`; `lamports` plus its [`trading_fee`] is the cost to
+/// pass as `max_total_cost`
+pub fn buy_shares(share: &OutcomeShare, amount: u64) -> Result<TradeQuote> {
+    Ok(Curve::from(share.curve)
+        .quote_buy(share.total_supply, amount)
+        .map_err(BetFunError::from)?)
+}
+
+/// Quote `sell_shares`; `lamports` is the proceeds to pass as `min_total_proceeds`
+pub fn sell_shares(share: &OutcomeShare, amount: u64) -> Result<TradeQuote> {
+    Ok(Curve::from(share.curve)
+        .quote_sell(share.total_supply, amount)
+        .map_err(BetFunError::from)?)
+}
+
+/// Creator trading fee `buy_shares` and `settle_match` charge on `lamports`
+pub fn trading_fee(arena: &Arena, lamports: u64) -> Result<u64> {
+    Ok(betfun_math::bps_of(lamports, arena.creator_trading_fee_bps).map_err(BetFunError::from)?)
+}
+
+/// Quote `redeem_shares`: lamports paid for `amount` winning share base units,
+/// LONG/SHORT share base units of a scalar arena, or any shares of a voided arena
+/// `escrow_lamports` is the arena account's balance and `rent_exempt_min` its
+/// rent-exempt minimum; winning shares split what is left after the unclaimed pot.
+pub fn redeem_shares(
+    arena: &Arena,
+    escrow_lamports: u64,
+    rent_exempt_min: u64,
+    share: &OutcomeShare,
+    amount: u64,
+) -> Result<u64> {
+    let reserve = arena.share_reserve(escrow_lamports, rent_exempt_min);
+    arena.redemption_value(share, amount, reserve)
+}
+
+// ========== ARENA ==========
+
+/// Quote `claim_winnings` for a participant
+/// `creator_fee` is only non-zero while the fee is still owed, since the
+/// first claim pays it.
+pub fn claim_winnings(arena: &Arena, participant: &Participant) -> Result<ClaimQuote> {
+    require!(arena.resolved, BetFunError::NotResolved);
+    require!(arena.winner_outcome == Some(participant.outcome_chosen), BetFunError::NotWinner);
+    require!(!participant.claimed, BetFunError::AlreadyClaimed);
+
+    let mut quote = payout::quote_claim(
+        arena.participant_weight(participant)?,
+        arena.pot,
+        arena.outcome_weight(participant.outcome_chosen),
+        arena.creator_fee_bps,
+    )
+    .map_err(BetFunError::from)?;
+    // Profit is over the stake itself, whatever its weight
+    quote.profit = quote.payout.saturating_sub(participant.amount);
+    if arena.creator_fee_paid {
+        quote.creator_fee = 0;
+    }
+    Ok(quote)
+}
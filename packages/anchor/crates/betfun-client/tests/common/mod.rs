@@ -0,0 +1,98 @@
+//! Shared fixtures for the client tests
+//!
+//! Transactions are built with the client's own builders and run against the
+//! real program in `betfun_testkit::Svm`.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountDeserialize, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token;
+use betfun_client::accounts::decode;
+use betfun_client::instructions::{self as ix, CreateArenaArgs};
+use betfun_client::pda;
+use betfun_client::state::{Arena, CurveType, GlobalConfig, StakeWeighting};
+use betfun_testkit::{Svm, TransactionMetadata};
+
+pub const SOL: u64 = 1_000_000_000;
+pub const SHARE: u64 = 1_000_000_000;
+pub const DAY: i64 = 24 * 60 * 60;
+
+/// Runtime with the program loaded and its config initialized
+pub fn setup() -> Svm {
+    let mut svm = Svm::new();
+    svm.add_program(betfun_client::ID, betfun::entry);
+    let admin = wallet(&mut svm);
+    svm.set_upgrade_authority(&betfun_client::ID, Some(admin));
+    send(&mut svm, ix::initialize_config(&admin), &admin);
+    svm
+}
+
+/// Wallet the config requires protocol fees to be paid to
+pub fn fee_recipient(svm: &Svm) -> Pubkey {
+    fetch::<GlobalConfig>(svm, &pda::config().0).protocol_fee_recipient()
+}
+
+/// Fresh wallet holding 100 SOL
+pub fn wallet(svm: &mut Svm) -> Pubkey {
+    let wallet = Pubkey::new_unique();
+    svm.airdrop(&wallet, 100 * SOL);
+    wallet
+}
+
+#[track_caller]
+pub fn send(svm: &mut Svm, instruction: Instruction, signer: &Pubkey) -> TransactionMetadata {
+    svm.send_instruction(instruction, &[*signer])
+        .unwrap_or_else(|failure| panic!("transaction failed: {failure}\n{:#?}", failure.logs))
+}
+
+pub fn fetch<T: AccountDeserialize>(svm: &Svm, key: &Pubkey) -> T {
+    decode(&svm.account(key).expect("account exists").data).expect("account decodes")
+}
+
+pub fn token_balance(svm: &Svm, key: &Pubkey) -> u64 {
+    spl_token::state::Account::unpack(&svm.account(key).expect("token account exists").data)
+        .unwrap()
+        .amount
+}
+
+pub fn arena_args(svm: &Svm) -> CreateArenaArgs {
+    CreateArenaArgs {
+        title: "Client Cup".to_string(),
+        description: "Built with betfun-client".to_string(),
+        question: "Who wins the cup?".to_string(),
+        outcomes: vec!["Home".to_string(), "Away".to_string()],
+        tags: vec![],
+        entry_fee: SOL / 10,
+        lock_time: svm.clock().unix_timestamp + DAY,
+        resolve_after: svm.clock().unix_timestamp + DAY,
+        manual_resolve: false,
+        oracle: None,
+        token_mint: None,
+        creator_fee_bps: Arena::DEFAULT_CREATOR_FEE_BPS,
+        creator_trading_fee_bps: 0,
+        scalar: None,
+        allowlist_root: None,
+        stake_weighting: StakeWeighting::Flat,
+        duel: None,
+    }
+}
+
+/// Arena with a linear share market on outcome 0; returns `(creator, arena)`
+pub fn share_market(svm: &mut Svm) -> (Pubkey, Pubkey) {
+    let args = arena_args(svm);
+    share_market_with(svm, args)
+}
+
+pub fn share_market_with(svm: &mut Svm, args: CreateArenaArgs) -> (Pubkey, Pubkey) {
+    let creator = wallet(svm);
+    let (arena, _) = pda::arena(&creator, &args.title);
+    send(svm, ix::create_arena(&creator, args, None), &creator);
+    send(
+        svm,
+        ix::create_share_tokens(&arena, &creator, 0, SOL / 10, CurveType::Linear, SOL / 100),
+        &creator,
+    );
+    (creator, arena)
+}
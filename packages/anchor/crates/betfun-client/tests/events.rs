@@ -0,0 +1,155 @@
+//! Events decode from transaction logs the way `emit!` writes them
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use betfun_client::events::{parse_logs, ArenaCreated, ArenaJoined, BetFunEvent, TradeSettled};
+use betfun_client::state::StakeWeighting;
+
+fn program_data(event: &impl Event) -> String {
+    format!("Program data: {}", BASE64.encode(event.data()))
+}
+
+fn invoke(program: &Pubkey, depth: usize) -> String {
+    format!("Program {program} invoke [{depth}]")
+}
+
+fn success(program: &Pubkey) -> String {
+    format!("Program {program} success")
+}
+
+fn joined(amount: u64) -> ArenaJoined {
+    ArenaJoined {
+        arena: Pubkey::new_unique(),
+        participant: Pubkey::new_unique(),
+        outcome_chosen: 1,
+        amount,
+        weight: amount,
+        total_pot: 3 * amount,
+        participants_count: 3,
+    }
+}
+
+// ========== DECODING ==========
+
+#[test]
+fn decodes_events_in_log_order() {
+    let created = ArenaCreated {
+        arena: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        title: "Who wins the final?".to_string(),
+        entry_fee: 100_000_000,
+        outcomes_count: 2,
+        creator_fee_bps: 500,
+        creator_trading_fee_bps: 100,
+        scalar: None,
+        allowlist_root: None,
+        creator_bond: 0,
+        bond_mint: None,
+        lock_time: 0,
+        resolve_after: 0,
+        stake_weighting: StakeWeighting::Flat,
+        duel: None,
+    };
+    let joined = joined(100_000_000);
+    let logs = vec![
+        invoke(&betfun_client::ID, 1),
+        "Program log: Instruction: CreateArena".to_string(),
+        program_data(&created),
+        program_data(&joined),
+        "Program log: Arena created".to_string(),
+        success(&betfun_client::ID),
+    ];
+
+    let events = parse_logs(&logs);
+    assert_eq!(events.len(), 2);
+    match &events[0] {
+        BetFunEvent::ArenaCreated(event) => {
+            assert_eq!((event.arena, event.creator), (created.arena, created.creator));
+            assert_eq!(event.title, created.title);
+            assert_eq!((event.entry_fee, event.outcomes_count), (created.entry_fee, created.outcomes_count));
+        }
+        other => panic!("expected ArenaCreated, got {}", other.name()),
+    }
+    match &events[1] {
+        BetFunEvent::ArenaJoined(event) => {
+            assert_eq!((event.arena, event.participant), (joined.arena, joined.participant));
+            assert_eq!((event.amount, event.total_pot), (joined.amount, joined.total_pot));
+        }
+        other => panic!("expected ArenaJoined, got {}", other.name()),
+    }
+}
+
+#[test]
+fn rejects_malformed_data() {
+    let mut truncated = joined(1).data();
+    truncated.pop();
+    let mut padded = joined(1).data();
+    padded.push(0);
+
+    assert!(BetFunEvent::decode(&joined(1).data()).is_some());
+    assert!(BetFunEvent::decode(&truncated).is_none());
+    assert!(BetFunEvent::decode(&padded).is_none());
+    assert!(BetFunEvent::decode(&[0; 4]).is_none());
+    assert!(BetFunEvent::decode(&[0xff; 64]).is_none());
+
+    let logs = vec![
+        invoke(&betfun_client::ID, 1),
+        "Program data: not base64!".to_string(),
+        format!("Program data: {}", BASE64.encode(&truncated)),
+        program_data(&joined(7)),
+        success(&betfun_client::ID),
+    ];
+    let events = parse_logs(&logs);
+    assert_eq!(events.len(), 1);
+    assert!(matches!(&events[0], BetFunEvent::ArenaJoined(event) if event.amount == 7));
+}
+
+// ========== INVOKE STACK ==========
+
+#[test]
+fn ignores_data_logged_by_other_programs() {
+    let other = Pubkey::new_unique();
+    let trade = TradeSettled {
+        order_book: Pubkey::new_unique(),
+        trade_id: 4,
+        buy_order_id: 1,
+        sell_order_id: 2,
+        buyer: Pubkey::new_unique(),
+        seller: Pubkey::new_unique(),
+        price: 600_000,
+        size: 10,
+        buyer_fee: 18,
+        seller_fee: 18,
+        creator_fee: 60,
+    };
+    let logs = vec![
+        // Another program logging BetFun-shaped data at the top level
+        invoke(&other, 1),
+        program_data(&joined(1)),
+        success(&other),
+        // BetFun invoking a program that logs data
+        invoke(&betfun_client::ID, 1),
+        invoke(&other, 2),
+        program_data(&joined(2)),
+        format!("Program {other} consumed 1200 of 190000 compute units"),
+        success(&other),
+        program_data(&trade),
+        success(&betfun_client::ID),
+        // A program invoked after BetFun returned
+        invoke(&other, 1),
+        program_data(&joined(3)),
+        format!("Program {other} failed: custom program error: 0x1"),
+    ];
+
+    let events = parse_logs(&logs);
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+        BetFunEvent::TradeSettled(event) => {
+            assert_eq!((event.trade_id, event.buyer, event.seller), (4, trade.buyer, trade.seller));
+            assert_eq!((event.price, event.size), (600_000, 10));
+        }
+        other => panic!("expected TradeSettled, got {}", other.name()),
+    }
+}
@@ -0,0 +1,285 @@
+//! Every builder, PDA and decoder exercised against the real program
+
+mod common;
+
+use betfun_client::accounts::{decode, BetFunAccount};
+use betfun_client::allowlist::Allowlist;
+use betfun_client::instructions::{self as ix, ClaimEntry, CreateArenaArgs, MatchedOrders};
+use betfun_client::pda;
+use betfun_client::state::{
+    AMMPool, Arena, LimitOrder, LiquidityPosition, OrderBook, OrderSide, OrderType, OutcomeShare, Participant,
+    PriceHistory, Referral, ShareBalance, Trade, UserProfile,
+};
+use betfun_client::PlaceOrderParams;
+use common::*;
+
+fn limit(side: OrderSide, price: u64, size: u64) -> PlaceOrderParams {
+    PlaceOrderParams {
+        order_type: OrderType::Limit,
+        side,
+        price,
+        size,
+        expires_at: 0,
+        stop_price: None,
+        visible_size: None,
+        twap_interval: None,
+    }
+}
+
+// ========== ARENA ==========
+
+#[test]
+fn arena_lifecycle() {
+    let mut svm = setup();
+    let creator = wallet(&mut svm);
+    let args = arena_args(&svm);
+    let (arena, arena_bump) = pda::arena(&creator, &args.title);
+    send(&mut svm, ix::create_arena(&creator, args, None), &creator);
+
+    let winner = wallet(&mut svm);
+    let loser = wallet(&mut svm);
+    send(&mut svm, ix::join_arena(&arena, &winner, 0, None, &[]), &winner);
+    send(&mut svm, ix::join_arena(&arena, &loser, 1, None, &[]), &loser);
+
+    let (participant, participant_bump) = pda::participant(&arena, &winner);
+    let state: Participant = fetch(&svm, &participant);
+    assert_eq!(state.wallet, winner);
+    assert_eq!(state.bump, participant_bump);
+
+    svm.advance_clock(DAY);
+    send(&mut svm, ix::resolve_arena(&arena, &creator, 0), &creator);
+
+    let before = svm.lamports(&winner);
+    send(&mut svm, ix::claim_winnings(&arena, &winner, &creator), &winner);
+    assert!(svm.lamports(&winner) > before);
+
+    send(&mut svm, ix::mint_trophy(&arena, &winner), &winner);
+    let (trophy_mint, _) = pda::trophy_mint(&participant);
+    let mint = svm.account(&trophy_mint).expect("trophy minted");
+    assert_eq!(mint.owner, anchor_spl::token_2022::ID);
+    assert!(svm.account(&pda::trophy_token_account(&winner, &trophy_mint)).is_some());
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.bump, arena_bump);
+    assert_eq!(state.winner_outcome, Some(0));
+    assert_eq!(state.total_paid_out, state.pot);
+
+    let (profile, profile_bump) = pda::user_profile(&winner);
+    let profile: UserProfile = fetch(&svm, &profile);
+    assert_eq!(profile.bump, profile_bump);
+    assert_eq!((profile.arenas_joined, profile.arenas_won, profile.trophies), (1, 1, 1));
+}
+
+// ========== SHARES AND AMM ==========
+
+#[test]
+fn share_and_pool_flow() {
+    let mut svm = setup();
+    let (creator, arena) = share_market(&mut svm);
+    let (outcome_share, outcome_share_bump) = pda::outcome_share(&arena, 0);
+    let (price_history, price_history_bump) = pda::price_history(&arena, 0);
+
+    let trader = wallet(&mut svm);
+    send(&mut svm, ix::buy_shares(&arena, &trader, &creator, 0, 10 * SHARE, None, None, None, &[]), &trader);
+    send(&mut svm, ix::sell_shares(&arena, &trader, 0, SHARE, None, None), &trader);
+    let trader_tokens = pda::share_token_account(&trader, &arena, 0);
+    assert_eq!(token_balance(&svm, &trader_tokens), 9 * SHARE);
+
+    let (share_balance, share_balance_bump) = pda::share_balance(&outcome_share, &trader);
+    let balance: ShareBalance = fetch(&svm, &share_balance);
+    assert_eq!(balance.balance, 9 * SHARE);
+    assert_eq!(balance.bump, share_balance_bump);
+    let share: OutcomeShare = fetch(&svm, &outcome_share);
+    assert_eq!(share.token_mint, pda::share_mint(&arena, 0).0);
+    assert_eq!(share.bump, outcome_share_bump);
+    let history: PriceHistory = fetch(&svm, &price_history);
+    assert_eq!(history.bump, price_history_bump);
+
+    send(&mut svm, ix::initialize_pool(&arena, &creator, 0, 30, 10), &creator);
+    send(&mut svm, ix::add_liquidity(&arena, &trader, 0, 4 * SHARE, SOL, 0), &trader);
+
+    let (pool, pool_bump) = pda::amm_pool(&arena, 0);
+    let state: AMMPool = fetch(&svm, &pool);
+    assert_eq!(state.bump, pool_bump);
+    assert_eq!(state.lp_token_mint, pda::lp_token(&pool).0);
+    assert_eq!(svm.lamports(&pda::pool_sol_vault(&pool).0), SOL);
+    assert_eq!(token_balance(&svm, &pda::pool_token_vault(&pool).0), 4 * SHARE);
+
+    let fee_recipient = fee_recipient(&svm);
+    send(&mut svm, ix::swap(&arena, &trader, &creator, &fee_recipient, 0, SOL / 10, 1, false, None), &trader);
+    send(&mut svm, ix::swap(&arena, &trader, &creator, &fee_recipient, 0, SHARE, 1, true, None), &trader);
+    assert_eq!(fetch::<AMMPool>(&svm, &pool).swap_count, 2);
+
+    let (position, position_bump) = pda::liquidity_position(&pool, &trader);
+    let position: LiquidityPosition = fetch(&svm, &position);
+    assert_eq!(position.bump, position_bump);
+    assert_eq!(token_balance(&svm, &pda::lp_token_account(&trader, &pool)), position.lp_tokens);
+    send(
+        &mut svm,
+        ix::remove_liquidity(&arena, &trader, 0, position.lp_tokens / 2, 0, 0),
+        &trader,
+    );
+
+    // Resolve on outcome 0 and redeem a tenth of a share
+    let bettor = wallet(&mut svm);
+    send(&mut svm, ix::join_arena(&arena, &bettor, 0, None, &[]), &bettor);
+    send(&mut svm, ix::buy_shares(&arena, &bettor, &creator, 0, SHARE / 10, None, None, None, &[]), &bettor);
+    svm.advance_clock(DAY);
+    send(&mut svm, ix::resolve_arena(&arena, &creator, 0), &creator);
+    let before = token_balance(&svm, &trader_tokens);
+    send(&mut svm, ix::redeem_shares(&arena, &trader, 0, SHARE / 10, None, None), &trader);
+    assert_eq!(token_balance(&svm, &trader_tokens), before - SHARE / 10);
+
+    // The bettor claims their bet and shares through the batch instruction; a repeat is skipped
+    let entries = [ClaimEntry::Bet { arena, creator }, ClaimEntry::Shares { arena, outcome_index: 0 }];
+    send(&mut svm, ix::claim_many(&bettor, &entries), &bettor);
+    send(&mut svm, ix::claim_many(&bettor, &entries), &bettor);
+    assert!(fetch::<Participant>(&svm, &pda::participant(&arena, &bettor).0).claimed);
+    assert_eq!(token_balance(&svm, &pda::share_token_account(&bettor, &arena, 0)), 0);
+}
+
+// ========== ORDER BOOK ==========
+
+#[test]
+fn order_book_flow() {
+    let mut svm = setup();
+    let (creator, arena) = share_market(&mut svm);
+    send(&mut svm, ix::initialize_order_book(&arena, &creator, 0), &creator);
+    let (order_book, order_book_bump) = pda::order_book(&arena, 0);
+    assert_eq!(fetch::<OrderBook>(&svm, &order_book).bump, order_book_bump);
+
+    let seller = wallet(&mut svm);
+    send(&mut svm, ix::buy_shares(&arena, &seller, &creator, 0, SHARE, None, None, None, &[]), &seller);
+    let buyer = wallet(&mut svm);
+    send(&mut svm, ix::create_share_token_account(&buyer, &buyer, &arena, 0), &buyer);
+
+    send(&mut svm, ix::place_limit_order(&arena, &buyer, 0, 0, limit(OrderSide::Buy, 110_000, 100)), &buyer);
+    send(&mut svm, ix::place_limit_order(&arena, &seller, 0, 1, limit(OrderSide::Sell, 100_000, 100)), &seller);
+    send(&mut svm, ix::place_limit_order(&arena, &seller, 0, 2, limit(OrderSide::Sell, 200_000, 50)), &seller);
+
+    let (sell_order, sell_order_bump) = pda::limit_order(&arena, 0, 1);
+    assert_eq!(fetch::<LimitOrder>(&svm, &sell_order).bump, sell_order_bump);
+    assert_eq!(token_balance(&svm, &pda::order_escrow(&sell_order).0), 100);
+
+    let orders = MatchedOrders {
+        outcome_index: 0,
+        buy_order_id: 0,
+        sell_order_id: 1,
+        trade_id: 0,
+        buyer,
+        seller,
+        protocol_fee_recipient: fee_recipient(&svm),
+        creator,
+        matcher: wallet(&mut svm),
+    };
+    send(&mut svm, ix::settle_match(&arena, &orders, 100, 100_000), &orders.matcher);
+    assert_eq!(token_balance(&svm, &pda::share_token_account(&buyer, &arena, 0)), 100);
+
+    let (trade, trade_bump) = pda::trade(&arena, 0, 0);
+    let trade: Trade = fetch(&svm, &trade);
+    assert_eq!(trade.bump, trade_bump);
+    assert_eq!((trade.buyer, trade.seller, trade.size), (buyer, seller, 100));
+
+    send(&mut svm, ix::cancel_order(&arena, &seller, 0, 2), &seller);
+    assert_eq!(fetch::<OrderBook>(&svm, &order_book).active_orders, 0);
+}
+
+// ========== REFERRALS ==========
+
+#[test]
+fn referral_flow() {
+    let mut svm = setup();
+    let (creator, arena) = share_market(&mut svm);
+    send(&mut svm, ix::initialize_pool(&arena, &creator, 0, 30, 10), &creator);
+    let provider = wallet(&mut svm);
+    send(&mut svm, ix::buy_shares(&arena, &provider, &creator, 0, 4 * SHARE, None, None, None, &[]), &provider);
+    send(&mut svm, ix::add_liquidity(&arena, &provider, 0, 4 * SHARE, SOL, 0), &provider);
+
+    let referrer = wallet(&mut svm);
+    let trader = wallet(&mut svm);
+    let fee_recipient = fee_recipient(&svm);
+    send(&mut svm, ix::join_arena(&arena, &trader, 0, Some(&referrer), &[]), &trader);
+    send(&mut svm, ix::buy_shares(&arena, &trader, &creator, 0, SHARE, None, None, Some(&referrer), &[]), &trader);
+    send(&mut svm, ix::swap(&arena, &trader, &creator, &fee_recipient, 0, SOL / 10, 1, false, Some(&referrer)), &trader);
+
+    let (referral, referral_bump) = pda::referral(&trader);
+    let state: Referral = fetch(&svm, &referral);
+    assert_eq!(state.referrer, referrer);
+    assert_eq!(state.bump, referral_bump);
+    assert!(state.pending_rewards > 0);
+
+    let before = svm.lamports(&referrer);
+    send(&mut svm, ix::claim_referral_rewards(&trader, &referrer), &referrer);
+    assert_eq!(svm.lamports(&referrer), before + state.pending_rewards);
+    assert_eq!(fetch::<Referral>(&svm, &referral).pending_rewards, 0);
+}
+
+// ========== ALLOWLISTS ==========
+
+#[test]
+fn allowlist_flow() {
+    let mut svm = setup();
+    let members: Vec<_> = (0..5).map(|_| wallet(&mut svm)).collect();
+    let allowlist = Allowlist::new(members.iter().copied());
+    let args = CreateArenaArgs { allowlist_root: Some(allowlist.root()), ..arena_args(&svm) };
+    let (creator, arena) = share_market_with(&mut svm, args);
+
+    for member in &members {
+        let proof = allowlist.proof(member).expect("member has a proof");
+        send(&mut svm, ix::join_arena(&arena, member, 0, None, &proof), member);
+    }
+    let proof = allowlist.proof(&members[4]).unwrap();
+    send(&mut svm, ix::buy_shares(&arena, &members[4], &creator, 0, SHARE, None, None, None, &proof), &members[4]);
+
+    let stranger = wallet(&mut svm);
+    assert_eq!(allowlist.proof(&stranger), None);
+    assert!(svm.send_instruction(ix::join_arena(&arena, &stranger, 0, None, &[]), &[stranger]).is_err());
+
+    send(&mut svm, ix::set_allowlist(&arena, &creator, None), &creator);
+    send(&mut svm, ix::join_arena(&arena, &stranger, 0, None, &[]), &stranger);
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!((state.allowlist_root, state.participants_count), (None, 6));
+}
+
+// ========== DECODERS ==========
+
+#[test]
+fn decode_dispatches_on_discriminator() {
+    let mut svm = setup();
+    let (creator, arena) = share_market(&mut svm);
+    let bettor = wallet(&mut svm);
+    let referrer = wallet(&mut svm);
+    send(&mut svm, ix::join_arena(&arena, &bettor, 0, Some(&referrer), &[]), &bettor);
+    send(&mut svm, ix::buy_shares(&arena, &bettor, &creator, 0, SHARE, None, None, None, &[]), &bettor);
+    send(&mut svm, ix::initialize_order_book(&arena, &creator, 0), &creator);
+    send(&mut svm, ix::initialize_pool(&arena, &creator, 0, 30, 10), &creator);
+
+    let outcome_share = pda::outcome_share(&arena, 0).0;
+    let expected = [
+        (arena, "Arena"),
+        (pda::participant(&arena, &bettor).0, "Participant"),
+        (pda::referral(&bettor).0, "Referral"),
+        (pda::user_profile(&bettor).0, "UserProfile"),
+        (outcome_share, "OutcomeShare"),
+        (pda::share_balance(&outcome_share, &bettor).0, "ShareBalance"),
+        (pda::price_history(&arena, 0).0, "PriceHistory"),
+        (pda::amm_pool(&arena, 0).0, "AMMPool"),
+        (pda::order_book(&arena, 0).0, "OrderBook"),
+    ];
+    for (address, name) in expected {
+        let account = svm.account(&address).expect("account exists");
+        assert_eq!(account.owner, betfun_client::ID);
+        assert_eq!(BetFunAccount::decode(&account.data).unwrap().name(), name);
+    }
+
+    match BetFunAccount::decode(&svm.account(&arena).unwrap().data).unwrap() {
+        BetFunAccount::Arena(state) => assert_eq!(state.creator, creator),
+        other => panic!("decoded as {}", other.name()),
+    }
+
+    // Token accounts and truncated data are rejected
+    let share_mint = svm.account(&pda::share_mint(&arena, 0).0).unwrap();
+    assert!(BetFunAccount::decode(&share_mint.data).is_err());
+    assert!(BetFunAccount::decode(&[0; 4]).is_err());
+    assert!(decode::<Participant>(&svm.account(&arena).unwrap().data).is_err());
+}
@@ -0,0 +1,260 @@
+//! Quotes taken from account state match what the program then executes
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun_client::instructions::{self as ix, CreateArenaArgs};
+use betfun_client::math::MathError;
+use betfun_client::state::{
+    AMMPool, Arena, CurveType, LiquidityPosition, OutcomeShare, Participant, ScalarRange, ShareBalance,
+};
+use betfun_client::{pda, quote, BetFunError};
+use common::*;
+
+/// Share market whose creator takes 1% of trading volume
+fn fee_market(svm: &mut betfun_testkit::Svm) -> (Pubkey, Pubkey) {
+    let args = CreateArenaArgs { creator_trading_fee_bps: 100, ..arena_args(svm) };
+    share_market_with(svm, args)
+}
+
+fn expect_error<T>(result: anchor_lang::Result<T>, expected: BetFunError) {
+    match result {
+        Ok(_) => panic!("expected {expected:?}"),
+        Err(error) => assert_eq!(error, expected.into()),
+    }
+}
+
+// ========== AMM ==========
+
+#[test]
+fn pool_quotes_match_execution() {
+    let mut svm = setup();
+    let (creator, arena) = fee_market(&mut svm);
+    let arena_state: Arena = fetch(&svm, &arena);
+    let provider = wallet(&mut svm);
+    send(&mut svm, ix::buy_shares(&arena, &provider, &creator, 0, 10 * SHARE, None, None, None, &[]), &provider);
+    send(&mut svm, ix::initialize_pool(&arena, &creator, 0, 30, 10), &creator);
+    let (pool, _) = pda::amm_pool(&arena, 0);
+    let (position, _) = pda::liquidity_position(&pool, &provider);
+
+    // First deposit sets the price
+    let deposit = quote::add_liquidity(&fetch(&svm, &pool), 4 * SHARE, SOL).unwrap();
+    send(
+        &mut svm,
+        ix::add_liquidity(&arena, &provider, 0, 4 * SHARE, SOL, deposit.lp_tokens),
+        &provider,
+    );
+    let state: AMMPool = fetch(&svm, &pool);
+    assert_eq!(fetch::<LiquidityPosition>(&svm, &position).lp_tokens, deposit.lp_tokens);
+    assert_eq!((state.token_reserve, state.sol_reserve), (deposit.token_reserve_after, deposit.sol_reserve_after));
+
+    // Later deposits pair at the pool ratio
+    let sol_amount = state.math().paired_sol_amount(2 * SHARE).unwrap();
+    let deposit = quote::add_liquidity(&state, 2 * SHARE, sol_amount).unwrap();
+    expect_error(quote::add_liquidity(&state, 2 * SHARE, sol_amount * 2), BetFunError::SlippageToleranceExceeded);
+    let lp_before = state.total_lp_tokens;
+    send(
+        &mut svm,
+        ix::add_liquidity(&arena, &provider, 0, 2 * SHARE, sol_amount, deposit.lp_tokens),
+        &provider,
+    );
+    assert_eq!(fetch::<AMMPool>(&svm, &pool).total_lp_tokens, lp_before + deposit.lp_tokens);
+
+    // SOL -> shares
+    let trader = wallet(&mut svm);
+    let fee_recipient = fee_recipient(&svm);
+    send(&mut svm, ix::create_share_token_account(&trader, &trader, &arena, 0), &trader);
+    let trader_tokens = pda::share_token_account(&trader, &arena, 0);
+    let buy = quote::swap(&arena_state, &fetch(&svm, &pool), SOL / 10, false).unwrap();
+    let creator_before = svm.lamports(&creator);
+    let recipient_before = svm.lamports(&fee_recipient);
+    send(
+        &mut svm,
+        ix::swap(&arena, &trader, &creator, &fee_recipient, 0, SOL / 10, buy.amount_out, false, None),
+        &trader,
+    );
+    assert_eq!(token_balance(&svm, &trader_tokens), buy.amount_out);
+    assert_eq!(svm.lamports(&fee_recipient), recipient_before + buy.protocol_fee);
+    assert_eq!(svm.lamports(&creator), creator_before + buy.creator_fee);
+    let state: AMMPool = fetch(&svm, &pool);
+    assert_eq!((state.token_reserve, state.sol_reserve), (buy.token_reserve_after, buy.sol_reserve_after));
+    assert_eq!(state.last_price, buy.price_after);
+    assert_eq!(state.fees_collected, buy.fee);
+    assert!(buy.price_after > buy.price_before && buy.price_impact_bps > 0);
+
+    // Shares -> SOL, paid net of the protocol and creator fees
+    let sell = quote::swap(&arena_state, &state, buy.amount_out, true).unwrap();
+    let result = svm.send_instruction(
+        ix::swap(&arena, &trader, &creator, &fee_recipient, 0, buy.amount_out, sell.amount_out + 1, true, None),
+        &[trader],
+    );
+    assert_eq!(result.unwrap_err().custom_code(), Some(u32::from(BetFunError::SlippageToleranceExceeded)));
+    let trader_before = svm.lamports(&trader);
+    let recipient_before = svm.lamports(&fee_recipient);
+    send(
+        &mut svm,
+        ix::swap(&arena, &trader, &creator, &fee_recipient, 0, buy.amount_out, sell.min_amount_out(50), true, None),
+        &trader,
+    );
+    assert_eq!(svm.lamports(&trader), trader_before + sell.amount_out);
+    assert_eq!(svm.lamports(&fee_recipient), recipient_before + sell.protocol_fee);
+    let state: AMMPool = fetch(&svm, &pool);
+    assert_eq!((state.token_reserve, state.sol_reserve), (sell.token_reserve_after, sell.sol_reserve_after));
+
+    // Withdrawal
+    let lp_tokens = fetch::<LiquidityPosition>(&svm, &position).lp_tokens / 3;
+    let withdraw = quote::remove_liquidity(&state, lp_tokens).unwrap();
+    let provider_tokens = pda::share_token_account(&provider, &arena, 0);
+    let (tokens_before, lamports_before) = (token_balance(&svm, &provider_tokens), svm.lamports(&provider));
+    send(
+        &mut svm,
+        ix::remove_liquidity(&arena, &provider, 0, lp_tokens, withdraw.token_amount, withdraw.sol_amount),
+        &provider,
+    );
+    assert_eq!(token_balance(&svm, &provider_tokens), tokens_before + withdraw.token_amount);
+    assert_eq!(svm.lamports(&provider), lamports_before + withdraw.sol_amount);
+}
+
+#[test]
+fn pool_quotes_reject_what_the_program_rejects() {
+    let mut svm = setup();
+    let (creator, arena) = share_market(&mut svm);
+    send(&mut svm, ix::initialize_pool(&arena, &creator, 0, 30, 10), &creator);
+    let state: AMMPool = fetch(&svm, &pda::amm_pool(&arena, 0).0);
+
+    expect_error(quote::swap(&fetch(&svm, &arena), &state, SOL, false), BetFunError::InsufficientLiquidity);
+    expect_error(quote::add_liquidity(&state, 0, SOL), BetFunError::InvalidAmount);
+    expect_error(quote::remove_liquidity(&state, 1), BetFunError::InsufficientLiquidity);
+    assert_eq!(state.math().quote_swap(0, true), Err(MathError::InvalidAmount));
+}
+
+// ========== SHARES ==========
+
+#[test]
+fn share_quotes_match_execution() {
+    let mut svm = setup();
+    let (creator, arena) = fee_market(&mut svm);
+    let (outcome_share, _) = pda::outcome_share(&arena, 0);
+    let trader = wallet(&mut svm);
+
+    let (share_balance, _) = pda::share_balance(&outcome_share, &trader);
+    let mut invested = 0;
+    for amount in [3 * SHARE, SHARE / 7, 11 * SHARE] {
+        let buy = quote::buy_shares(&fetch(&svm, &outcome_share), amount).unwrap();
+        let fee = quote::trading_fee(&fetch(&svm, &arena), buy.lamports).unwrap();
+        let (escrow_before, creator_before) = (svm.lamports(&arena), svm.lamports(&creator));
+        let max_cost = buy.lamports + fee;
+        let result = svm.send_instruction(
+            ix::buy_shares(&arena, &trader, &creator, 0, amount, Some(max_cost - 1), None, None, &[]),
+            &[trader],
+        );
+        assert_eq!(result.unwrap_err().custom_code(), Some(u32::from(BetFunError::SlippageToleranceExceeded)));
+        send(&mut svm, ix::buy_shares(&arena, &trader, &creator, 0, amount, Some(max_cost), None, None, &[]), &trader);
+        assert_eq!(svm.lamports(&arena), escrow_before + buy.lamports);
+        assert_eq!(svm.lamports(&creator), creator_before + fee);
+        let share: OutcomeShare = fetch(&svm, &outcome_share);
+        assert_eq!((share.total_supply, share.current_price), (buy.supply_after, buy.price_after));
+        invested += amount as u128 * buy.average_price as u128 / SHARE as u128;
+    }
+    assert_eq!(fetch::<ShareBalance>(&svm, &share_balance).total_invested as u128, invested);
+
+    let sell = quote::sell_shares(&fetch(&svm, &outcome_share), 5 * SHARE).unwrap();
+    let trader_before = svm.lamports(&trader);
+    send(&mut svm, ix::sell_shares(&arena, &trader, 0, 5 * SHARE, Some(sell.lamports), None), &trader);
+    assert_eq!(svm.lamports(&trader), trader_before + sell.lamports);
+    assert_eq!(fetch::<OutcomeShare>(&svm, &outcome_share).current_price, sell.price_after);
+
+    // Redemption needs a resolved arena with this outcome winning
+    let share: OutcomeShare = fetch(&svm, &outcome_share);
+    let redeem = |svm: &betfun_testkit::Svm, share: &OutcomeShare| {
+        let escrow = svm.account(&arena).unwrap();
+        let rent_exempt_min = svm.rent().minimum_balance(escrow.data.len());
+        quote::redeem_shares(&fetch(svm, &arena), escrow.lamports, rent_exempt_min, share, SHARE)
+    };
+    expect_error(redeem(&svm, &share), BetFunError::NotResolved);
+    let bettor = wallet(&mut svm);
+    send(&mut svm, ix::join_arena(&arena, &bettor, 0, None, &[]), &bettor);
+    svm.advance_clock(DAY);
+    send(&mut svm, ix::resolve_arena(&arena, &creator, 0), &creator);
+
+    let value = redeem(&svm, &share).unwrap();
+    let trader_before = svm.lamports(&trader);
+    send(&mut svm, ix::redeem_shares(&arena, &trader, 0, SHARE, Some(value), None), &trader);
+    assert_eq!(svm.lamports(&trader), trader_before + value);
+}
+
+#[test]
+fn scalar_redeem_quotes_match_execution() {
+    let mut svm = setup();
+    let scalar = Some(ScalarRange { lower: 0, upper: 1_000 });
+    let args = CreateArenaArgs { outcomes: vec![], scalar, ..arena_args(&svm) };
+    let (creator, arena) = share_market_with(&mut svm, args);
+    let short_tokens = ix::create_share_tokens(&arena, &creator, Arena::SHORT, SOL / 10, CurveType::Linear, SOL / 100);
+    send(&mut svm, short_tokens, &creator);
+    let trader = wallet(&mut svm);
+    send(&mut svm, ix::mint_complete_sets(&arena, &trader, &creator, SHARE, SOL, None, &[]), &trader);
+    svm.advance_clock(DAY);
+    send(&mut svm, ix::resolve_scalar_arena(&arena, &creator, 60), &creator);
+
+    let share: OutcomeShare = fetch(&svm, &pda::outcome_share(&arena, Arena::LONG).0);
+    let value = quote::redeem_shares(&fetch(&svm, &arena), 0, 0, &share, SHARE).unwrap();
+    assert_eq!(value, 6 * SOL / 100);
+    let trader_before = svm.lamports(&trader);
+    send(&mut svm, ix::redeem_shares(&arena, &trader, Arena::LONG, SHARE, Some(value), None), &trader);
+    assert_eq!(svm.lamports(&trader), trader_before + value);
+}
+
+// ========== ARENA ==========
+
+#[test]
+fn claim_quotes_match_payouts() {
+    let mut svm = setup();
+    let creator = wallet(&mut svm);
+    let args = arena_args(&svm);
+    let (arena, _) = pda::arena(&creator, &args.title);
+    send(&mut svm, ix::create_arena(&creator, args, None), &creator);
+
+    let winners = [wallet(&mut svm), wallet(&mut svm)];
+    let losers = [wallet(&mut svm), wallet(&mut svm), wallet(&mut svm)];
+    for user in winners {
+        send(&mut svm, ix::join_arena(&arena, &user, 0, None, &[]), &user);
+    }
+    for user in losers {
+        send(&mut svm, ix::join_arena(&arena, &user, 1, None, &[]), &user);
+    }
+
+    let participant = |svm: &betfun_testkit::Svm, user: &Pubkey| {
+        fetch::<Participant>(svm, &pda::participant(&arena, user).0)
+    };
+    expect_error(
+        quote::claim_winnings(&fetch(&svm, &arena), &participant(&svm, &winners[0])),
+        BetFunError::NotResolved,
+    );
+
+    svm.advance_clock(DAY);
+    send(&mut svm, ix::resolve_arena(&arena, &creator, 0), &creator);
+    expect_error(
+        quote::claim_winnings(&fetch(&svm, &arena), &participant(&svm, &losers[0])),
+        BetFunError::NotWinner,
+    );
+
+    let mut fee_paid = 0;
+    for user in winners {
+        let claim = quote::claim_winnings(&fetch(&svm, &arena), &participant(&svm, &user)).unwrap();
+        let (user_before, creator_before) = (svm.lamports(&user), svm.lamports(&creator));
+        send(&mut svm, ix::claim_winnings(&arena, &user, &creator), &user);
+        assert_eq!(svm.lamports(&user), user_before + claim.payout);
+        assert_eq!(svm.lamports(&creator), creator_before + claim.creator_fee);
+        assert_eq!(claim.profit, claim.payout - SOL / 10);
+        fee_paid += claim.creator_fee;
+
+        expect_error(
+            quote::claim_winnings(&fetch(&svm, &arena), &participant(&svm, &user)),
+            BetFunError::AlreadyClaimed,
+        );
+    }
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(fee_paid, state.calculate_creator_fee());
+    assert!(fee_paid > 0);
+}
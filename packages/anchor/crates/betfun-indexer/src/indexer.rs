@@ -0,0 +1,83 @@
+//! Catch-up loop from the [`Source`] into the [`Store`]
+
+use betfun_client::events::parse_logs;
+
+use crate::source::Source;
+use crate::store::{Cursor, IndexedTransaction, Store};
+use crate::Result;
+
+/// What one [`Indexer::sync`] pass stored
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub transactions: usize,
+    pub events: usize,
+}
+
+pub struct Indexer<S> {
+    source: S,
+    store: Store,
+}
+
+impl<S: Source> Indexer<S> {
+    pub fn new(source: S, store: Store) -> Self {
+        Self { source, store }
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// Forget everything from `slot` on and index it again
+    pub fn replay_from(&mut self, slot: u64) -> Result<SyncReport> {
+        self.store.rewind(slot)?;
+        self.sync()
+    }
+
+    /// Index every program transaction after the cursor, oldest first
+    /// Failed transactions are skipped: their events never happened. The
+    /// cursor moves after each transaction, so an interrupted pass resumes
+    /// where it stopped.
+    pub fn sync(&mut self) -> Result<SyncReport> {
+        let cursor = self.store.cursor()?.unwrap_or(Cursor { slot: 0, signature: None });
+        let until = cursor.signature.as_deref();
+
+        let mut pending = Vec::new();
+        let mut before: Option<String> = None;
+        'pages: loop {
+            let page = self.source.signatures(before.as_deref(), until)?;
+            if page.is_empty() {
+                break;
+            }
+            for info in page {
+                if info.slot < cursor.slot {
+                    break 'pages;
+                }
+                before = Some(info.signature.clone());
+                pending.push(info);
+            }
+        }
+
+        let mut report = SyncReport::default();
+        for info in pending.into_iter().rev() {
+            let (block_time, events) = if info.failed {
+                (None, Vec::new())
+            } else {
+                // Not served yet; the next pass picks it up
+                let Some(transaction) = self.source.transaction(&info.signature)? else {
+                    break;
+                };
+                let events = if transaction.failed { Vec::new() } else { parse_logs(&transaction.logs) };
+                (transaction.block_time, events)
+            };
+            report.transactions += 1;
+            report.events += events.len();
+            self.store.insert(&IndexedTransaction {
+                signature: info.signature,
+                slot: info.slot,
+                block_time,
+                events,
+            })?;
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,337 @@
+//! Events as JSON objects for the `events.data` column
+//!
+//! Field names match the Rust structs (and the IDL). Pubkeys are base58,
+//! hashes are hex, enums are their variant name (keying an object of its
+//! fields if it has any), `None` is `null`, and
+//! integers stay integers so SQLite's `json_extract` can sum and compare them.
+
+use anchor_lang::prelude::Pubkey;
+use betfun_client::events::BetFunEvent;
+use betfun_client::state::{CurveType, Duel, OrderSide, OrderType, ScalarRange, StakeWeighting};
+use serde_json::{Map, Value};
+
+trait Field {
+    fn value(&self) -> Value;
+}
+
+macro_rules! plain_fields {
+    ($($ty:ty),*) => {
+        $(impl Field for $ty {
+            fn value(&self) -> Value {
+                Value::from(*self)
+            }
+        })*
+    };
+}
+
+plain_fields!(bool, u8, u16, u32, u64, i64, f64);
+
+impl Field for String {
+    fn value(&self) -> Value {
+        Value::from(self.as_str())
+    }
+}
+
+impl Field for Pubkey {
+    fn value(&self) -> Value {
+        Value::from(self.to_string())
+    }
+}
+
+impl Field for [u8; 32] {
+    fn value(&self) -> Value {
+        Value::from(self.iter().map(|byte| format!("{byte:02x}")).collect::<String>())
+    }
+}
+
+macro_rules! variant_fields {
+    ($($ty:ty),*) => {
+        $(impl Field for $ty {
+            fn value(&self) -> Value {
+                Value::from(format!("{self:?}"))
+            }
+        })*
+    };
+}
+
+variant_fields!(OrderSide, OrderType, CurveType);
+
+impl<T: Field> Field for Option<T> {
+    fn value(&self) -> Value {
+        self.as_ref().map_or(Value::Null, Field::value)
+    }
+}
+
+impl Field for ScalarRange {
+    fn value(&self) -> Value {
+        serde_json::json!({ "lower": self.lower, "upper": self.upper })
+    }
+}
+
+impl Field for Duel {
+    fn value(&self) -> Value {
+        serde_json::json!({ "opponent": self.opponent.value(), "side": self.side })
+    }
+}
+
+impl Field for StakeWeighting {
+    fn value(&self) -> Value {
+        match self {
+            StakeWeighting::Flat => Value::from("Flat"),
+            StakeWeighting::LinearDecay { bonus_bps } => {
+                serde_json::json!({ "LinearDecay": { "bonus_bps": bonus_bps } })
+            }
+        }
+    }
+}
+
+/// Destructure every field (so a new event field fails to compile here) into an object
+macro_rules! object {
+    ($event:expr, $ty:ident { $($field:ident),* $(,)? }) => {{
+        let betfun_client::events::$ty { $($field),* } = $event;
+        let mut object = Map::new();
+        $(object.insert(stringify!($field).to_string(), $field.value());)*
+        Value::Object(object)
+    }};
+}
+
+/// The event's fields as a JSON object
+pub fn event_json(event: &BetFunEvent) -> Value {
+    match event {
+        BetFunEvent::ArenaCreated(event) => object!(
+            event,
+            ArenaCreated {
+                arena,
+                creator,
+                title,
+                entry_fee,
+                outcomes_count,
+                creator_fee_bps,
+                creator_trading_fee_bps,
+                scalar,
+                allowlist_root,
+                creator_bond,
+                bond_mint,
+                lock_time,
+                resolve_after,
+                stake_weighting,
+                duel,
+            }
+        ),
+        BetFunEvent::ArenaJoined(event) => object!(
+            event,
+            ArenaJoined { arena, participant, outcome_chosen, amount, weight, total_pot, participants_count }
+        ),
+        BetFunEvent::DuelAccepted(event) => object!(event, DuelAccepted { arena, challenger, opponent, stake }),
+        BetFunEvent::DuelCancelled(event) => object!(event, DuelCancelled { arena, challenger, refund }),
+        BetFunEvent::ArenaResolved(event) => object!(
+            event,
+            ArenaResolved {
+                arena,
+                resolver,
+                winner_outcome,
+                total_pot,
+                winner_pot,
+                winners_count,
+                creator_fee,
+                distributable_pot,
+            }
+        ),
+        BetFunEvent::ScalarArenaResolved(event) => object!(
+            event,
+            ScalarArenaResolved { arena, resolver, reported_value, resolved_value, long_payout, short_payout }
+        ),
+        BetFunEvent::ArenaVoided(event) => {
+            object!(event, ArenaVoided { arena, creator, caller, pot, slashed_bond })
+        }
+        BetFunEvent::WinningsClaimed(event) => object!(
+            event,
+            WinningsClaimed { arena, participant, original_bet, payout, profit, roi_percentage }
+        ),
+        BetFunEvent::RefundClaimed(event) => object!(event, RefundClaimed { arena, participant, stake, refund }),
+        BetFunEvent::BatchClaimed(event) => object!(event, BatchClaimed { user, claimed, skipped, total }),
+        BetFunEvent::ProfileSettled(event) => {
+            object!(event, ProfileSettled { arena, owner, bet_loss, share_loss, realized_pnl })
+        }
+        BetFunEvent::CreatorBondReleased(event) => object!(event, CreatorBondReleased { arena, creator, amount }),
+        BetFunEvent::BondShareClaimed(event) => object!(event, BondShareClaimed { arena, claimant, stake, amount }),
+        BetFunEvent::ArenaDisputed(event) => object!(event, ArenaDisputed { arena, disputer, deposit }),
+        BetFunEvent::DisputeSettled(event) => {
+            object!(event, DisputeSettled { arena, disputer, upheld, deposit, slashed_bond })
+        }
+        BetFunEvent::TrophyMinted(event) => {
+            object!(event, TrophyMinted { arena, participant, trophy_mint, outcome_won, amount_won })
+        }
+        BetFunEvent::ShareTokensCreated(event) => object!(
+            event,
+            ShareTokensCreated {
+                arena,
+                outcome_index,
+                token_mint,
+                initial_price,
+                outcome_name,
+                curve_type,
+                curve_param,
+            }
+        ),
+        BetFunEvent::SharesPurchased(event) => object!(
+            event,
+            SharesPurchased { arena, buyer, outcome_index, amount, price, total_cost, creator_fee, new_balance }
+        ),
+        BetFunEvent::SharesSold(event) => object!(
+            event,
+            SharesSold { arena, seller, outcome_index, amount, price, total_proceeds, new_balance, realized_pnl }
+        ),
+        BetFunEvent::SharesRedeemed(event) => object!(
+            event,
+            SharesRedeemed {
+                arena,
+                redeemer,
+                outcome_index,
+                amount,
+                redemption_value,
+                final_pnl,
+                total_realized_pnl,
+            }
+        ),
+        BetFunEvent::CompleteSetsMinted(event) => object!(
+            event,
+            CompleteSetsMinted { arena, buyer, amount, collateral, creator_fee }
+        ),
+        BetFunEvent::PoolInitialized(event) => object!(
+            event,
+            PoolInitialized { pool, arena, outcome_index, share_mint, lp_token_mint, fee_bps, protocol_fee_bps }
+        ),
+        BetFunEvent::LiquidityAdded(event) => object!(
+            event,
+            LiquidityAdded { pool, provider, token_amount, sol_amount, lp_tokens_minted, total_lp_tokens }
+        ),
+        BetFunEvent::LiquidityRemoved(event) => object!(
+            event,
+            LiquidityRemoved { pool, provider, lp_tokens_burned, token_amount, sol_amount, fees_earned }
+        ),
+        BetFunEvent::SwapExecuted(event) => object!(
+            event,
+            SwapExecuted {
+                pool,
+                user,
+                is_token_to_sol,
+                amount_in,
+                amount_out,
+                fee_amount,
+                protocol_fee,
+                creator_fee,
+                price_impact,
+                price_before,
+                price_after,
+            }
+        ),
+        BetFunEvent::OrderBookInitialized(event) => {
+            object!(event, OrderBookInitialized { order_book, arena, outcome_index })
+        }
+        BetFunEvent::LimitOrderPlaced(event) => object!(
+            event,
+            LimitOrderPlaced { order_book, order_id, owner, outcome_index, order_type, side, price, size, expires_at }
+        ),
+        BetFunEvent::LimitOrderCancelled(event) => object!(
+            event,
+            LimitOrderCancelled {
+                order_book,
+                order_id,
+                owner,
+                outcome_index,
+                side,
+                refund_amount,
+                filled_size,
+                total_size,
+            }
+        ),
+        BetFunEvent::TradeSettled(event) => object!(
+            event,
+            TradeSettled {
+                order_book,
+                trade_id,
+                buy_order_id,
+                sell_order_id,
+                buyer,
+                seller,
+                price,
+                size,
+                buyer_fee,
+                seller_fee,
+                creator_fee,
+            }
+        ),
+        BetFunEvent::AccountMigrated(event) => {
+            object!(event, AccountMigrated { account, from_version, to_version })
+        }
+        BetFunEvent::ConfigInitialized(event) => object!(event, ConfigInitialized { config, admin }),
+        BetFunEvent::GlobalPauseUpdated(event) => {
+            object!(event, GlobalPauseUpdated { config, admin, previous, paused })
+        }
+        BetFunEvent::ArenaPauseUpdated(event) => {
+            object!(event, ArenaPauseUpdated { arena, authority, previous, paused })
+        }
+        BetFunEvent::AllowlistUpdated(event) => {
+            object!(event, AllowlistUpdated { arena, creator, previous, allowlist_root })
+        }
+        BetFunEvent::ReferralShareUpdated(event) => {
+            object!(event, ReferralShareUpdated { config, admin, previous, share_bps })
+        }
+        BetFunEvent::CreatorBondUpdated(event) => object!(
+            event,
+            CreatorBondUpdated { config, admin, previous, bond, bond_mint }
+        ),
+        BetFunEvent::ProtocolFeesUpdated(event) => {
+            object!(event, ProtocolFeesUpdated { config, admin, fee_recipient, order_book_fee_bps })
+        }
+        BetFunEvent::AdminTransferred(event) => object!(event, AdminTransferred { config, previous, admin }),
+        BetFunEvent::ReferralRewardsClaimed(event) => {
+            object!(event, ReferralRewardsClaimed { referral, referrer, user, amount, total_earned })
+        }
+        BetFunEvent::ParlayVaultFunded(event) => {
+            object!(event, ParlayVaultFunded { vault, admin, amount, free_liquidity })
+        }
+        BetFunEvent::ParlayVaultWithdrawn(event) => {
+            object!(event, ParlayVaultWithdrawn { vault, admin, amount, free_liquidity })
+        }
+        BetFunEvent::ParlayApprovalUpdated(event) => {
+            object!(event, ParlayApprovalUpdated { arena, admin, approved })
+        }
+        BetFunEvent::ParlayPlaced(event) => {
+            object!(event, ParlayPlaced { parlay, owner, parlay_id, legs, stake, payout })
+        }
+        BetFunEvent::ParlaySettled(event) => object!(event, ParlaySettled { parlay, owner, won, stake, paid }),
+        BetFunEvent::TournamentCreated(event) => object!(
+            event,
+            TournamentCreated {
+                tournament,
+                organizer,
+                name,
+                entrants,
+                entry_fee,
+                match_entry_fee,
+                lock_time,
+                round_duration,
+            }
+        ),
+        BetFunEvent::TournamentEntered(event) => {
+            object!(event, TournamentEntered { tournament, wallet, entry_fee, pot, entries_count })
+        }
+        BetFunEvent::TournamentAdvanced(event) => {
+            object!(event, TournamentAdvanced { tournament, arena, slot, lock_time })
+        }
+        BetFunEvent::TournamentMatchResolved(event) => {
+            object!(event, TournamentMatchResolved { tournament, arena, slot, winner_outcome, decided })
+        }
+        BetFunEvent::TournamentCancelled(event) => {
+            object!(event, TournamentCancelled { tournament, arena, slot, caller, pot })
+        }
+        BetFunEvent::TournamentScoreSubmitted(event) => {
+            object!(event, TournamentScoreSubmitted { tournament, wallet, score, best_score, best_count })
+        }
+        BetFunEvent::TournamentPrizeClaimed(event) => {
+            object!(event, TournamentPrizeClaimed { tournament, wallet, amount, refund })
+        }
+    }
+}
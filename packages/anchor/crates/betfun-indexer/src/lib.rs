@@ -0,0 +1,46 @@
+//! Local indexer for BetFun program events
+//!
+//! Events are decoded from `Program data:` log lines with
+//! [`betfun_client::events`], never from `msg!` text:
+//! - [`source`]: the program's transaction history ([`source::RpcSource`] over JSON-RPC)
+//! - [`store`]: SQLite `events` table plus a resumable cursor
+//! - [`indexer`]: catch-up and replay-from-slot passes
+//! - [`json`]: event fields as the JSON stored in `events.data`
+
+use std::fmt;
+
+pub mod indexer;
+pub mod json;
+pub mod source;
+pub mod store;
+
+pub use indexer::{Indexer, SyncReport};
+pub use source::{RpcSource, Source};
+pub use store::Store;
+
+/// Indexer error
+#[derive(Debug)]
+pub enum Error {
+    /// The RPC node could not be reached or returned an error
+    Rpc(String),
+    Store(rusqlite::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Rpc(reason) => write!(f, "rpc error: {reason}"),
+            Error::Store(error) => write!(f, "store error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<rusqlite::Error> for Error {
+    fn from(error: rusqlite::Error) -> Self {
+        Error::Store(error)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,72 @@
+//! `betfun-indexer`: keep a SQLite database of BetFun program events
+//!
+//! ```text
+//! betfun-indexer --db betfun-events.sqlite --from-slot 250000000
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use betfun_indexer::{Indexer, RpcSource, Store};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(version, about = "Index BetFun program events into SQLite")]
+struct Args {
+    /// JSON-RPC endpoint
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// SQLite database, created if missing
+    #[arg(long, default_value = "betfun-events.sqlite")]
+    db: PathBuf,
+
+    /// Drop stored events from this slot on and index them again
+    #[arg(long)]
+    from_slot: Option<u64>,
+
+    /// Milliseconds between passes
+    #[arg(long, default_value_t = 2_000)]
+    interval_ms: u64,
+
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let store = match Store::open(&args.db) {
+        Ok(store) => store,
+        Err(error) => {
+            eprintln!("cannot open {}: {error}", args.db.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut indexer = Indexer::new(RpcSource::new(args.url), store);
+    let mut replay = args.from_slot;
+
+    loop {
+        let pass = match replay.take() {
+            Some(slot) => indexer.replay_from(slot),
+            None => indexer.sync(),
+        };
+        match pass {
+            Ok(report) if report.transactions > 0 => {
+                println!("indexed {} events from {} transactions", report.events, report.transactions)
+            }
+            Ok(_) => {}
+            Err(error) if args.once => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
+            }
+            Err(error) => eprintln!("{error}"),
+        }
+        if args.once {
+            return ExitCode::SUCCESS;
+        }
+        thread::sleep(Duration::from_millis(args.interval_ms));
+    }
+}
@@ -0,0 +1,116 @@
+//! Where the indexer reads the program's transaction history
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::{Error, Result};
+
+/// A program transaction as listed by `getSignaturesForAddress`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: u64,
+    pub failed: bool,
+}
+
+/// A confirmed transaction's log messages
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransactionLogs {
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+/// Transaction history of the BetFun program
+pub trait Source {
+    /// Program transactions newest first, starting below `before` and
+    /// stopping short of `until`; an empty page means there are no more
+    fn signatures(&self, before: Option<&str>, until: Option<&str>) -> Result<Vec<SignatureInfo>>;
+
+    /// `None` while the transaction is not yet available
+    fn transaction(&self, signature: &str) -> Result<Option<TransactionLogs>>;
+}
+
+/// [`Source`] over HTTP JSON-RPC at `confirmed` commitment
+pub struct RpcSource {
+    agent: ureq::Agent,
+    url: String,
+    page_size: usize,
+}
+
+impl RpcSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+            url: url.into(),
+            page_size: 1_000,
+        }
+    }
+
+    /// `result` of a JSON-RPC call
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .map_err(|error| Error::Rpc(format!("{method}: {error}")))?
+            .into_json()
+            .map_err(|error| Error::Rpc(format!("{method}: {error}")))?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Rpc(format!("{method}: {error}")));
+        }
+        Ok(response["result"].take())
+    }
+}
+
+impl Source for RpcSource {
+    fn signatures(&self, before: Option<&str>, until: Option<&str>) -> Result<Vec<SignatureInfo>> {
+        let result = self.call(
+            "getSignaturesForAddress",
+            json!([
+                betfun_client::ID.to_string(),
+                { "limit": self.page_size, "before": before, "until": until, "commitment": "confirmed" },
+            ]),
+        )?;
+        Ok(result
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| SignatureInfo {
+                signature: entry["signature"].as_str().unwrap_or_default().to_string(),
+                slot: entry["slot"].as_u64().unwrap_or_default(),
+                failed: !entry["err"].is_null(),
+            })
+            .collect())
+    }
+
+    fn transaction(&self, signature: &str) -> Result<Option<TransactionLogs>> {
+        let result = self.call(
+            "getTransaction",
+            json!([
+                signature,
+                { "encoding": "json", "commitment": "confirmed", "maxSupportedTransactionVersion": 0 },
+            ]),
+        )?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        let meta = &result["meta"];
+        Ok(Some(TransactionLogs {
+            slot: result["slot"].as_u64().unwrap_or_default(),
+            block_time: result["blockTime"].as_i64(),
+            failed: !meta["err"].is_null(),
+            logs: meta["logMessages"]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|line| line.as_str().map(str::to_string))
+                .collect(),
+        }))
+    }
+}
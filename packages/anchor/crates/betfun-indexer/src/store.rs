@@ -0,0 +1,164 @@
+//! SQLite event store
+//!
+//! One `events` row per decoded event, keyed by transaction signature and
+//! the event's position in that transaction. The event body is a JSON object
+//! (see [`crate::json`]), so analytics query it with `json_extract`:
+//!
+//! ```sql
+//! SELECT json_extract(data, '$.arena'), SUM(json_extract(data, '$.amount'))
+//! FROM events WHERE name = 'ArenaJoined' GROUP BY 1;
+//! ```
+//!
+//! The `cursor` row records how far indexing got, so a restart resumes there.
+
+use std::path::Path;
+
+use betfun_client::events::BetFunEvent;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::json::event_json;
+use crate::Result;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS events (
+    signature   TEXT    NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        INTEGER NOT NULL,
+    block_time  INTEGER,
+    name        TEXT    NOT NULL,
+    data        TEXT    NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_by_slot ON events (slot);
+CREATE INDEX IF NOT EXISTS events_by_name ON events (name, slot);
+CREATE TABLE IF NOT EXISTS cursor (
+    id        INTEGER PRIMARY KEY CHECK (id = 0),
+    slot      INTEGER NOT NULL,
+    signature TEXT
+);
+";
+
+/// Where indexing resumes
+/// `signature` is the newest indexed transaction; without one, indexing
+/// starts from the first transaction at or after `slot`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cursor {
+    pub slot: u64,
+    pub signature: Option<String>,
+}
+
+/// A confirmed transaction's events
+pub struct IndexedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub events: Vec<BetFunEvent>,
+}
+
+/// An `events` row
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredEvent {
+    pub signature: String,
+    pub event_index: u32,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub name: String,
+    pub data: serde_json::Value,
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    /// Open (or create) the database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Underlying connection, for ad-hoc queries
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    pub fn cursor(&self) -> Result<Option<Cursor>> {
+        let cursor = self
+            .connection
+            .query_row("SELECT slot, signature FROM cursor WHERE id = 0", [], |row| {
+                Ok(Cursor {
+                    slot: row.get::<_, i64>(0)? as u64,
+                    signature: row.get(1)?,
+                })
+            })
+            .optional()?;
+        Ok(cursor)
+    }
+
+    /// Store a transaction's events and move the cursor past it, atomically
+    pub fn insert(&mut self, transaction: &IndexedTransaction) -> Result<()> {
+        let tx = self.connection.transaction()?;
+        for (index, event) in transaction.events.iter().enumerate() {
+            tx.execute(
+                "INSERT OR REPLACE INTO events (signature, event_index, slot, block_time, name, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    transaction.signature,
+                    index as i64,
+                    transaction.slot as i64,
+                    transaction.block_time,
+                    event.name(),
+                    event_json(event).to_string(),
+                ],
+            )?;
+        }
+        write_cursor(&tx, transaction.slot, Some(&transaction.signature))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Drop events from `slot` on and restart indexing there
+    pub fn rewind(&mut self, slot: u64) -> Result<usize> {
+        let tx = self.connection.transaction()?;
+        let removed = tx.execute("DELETE FROM events WHERE slot >= ?1", params![slot as i64])?;
+        write_cursor(&tx, slot, None)?;
+        tx.commit()?;
+        Ok(removed)
+    }
+
+    /// Events named `name` (all events for `None`), oldest first
+    pub fn events(&self, name: Option<&str>) -> Result<Vec<StoredEvent>> {
+        let mut statement = self.connection.prepare(
+            "SELECT signature, event_index, slot, block_time, name, data FROM events
+             WHERE ?1 IS NULL OR name = ?1 ORDER BY slot, rowid",
+        )?;
+        let rows = statement.query_map(params![name], |row| {
+            let data: String = row.get(5)?;
+            Ok(StoredEvent {
+                signature: row.get(0)?,
+                event_index: row.get(1)?,
+                slot: row.get::<_, i64>(2)? as u64,
+                block_time: row.get(3)?,
+                name: row.get(4)?,
+                data: serde_json::from_str(&data).unwrap_or_default(),
+            })
+        })?;
+        Ok(rows.collect::<rusqlite::Result<_>>()?)
+    }
+}
+
+fn write_cursor(connection: &Connection, slot: u64, signature: Option<&str>) -> Result<()> {
+    connection.execute(
+        "INSERT OR REPLACE INTO cursor (id, slot, signature) VALUES (0, ?1, ?2)",
+        params![slot as i64, signature],
+    )?;
+    Ok(())
+}
@@ -0,0 +1,260 @@
+//! Indexing a synthetic program history into SQLite
+//!
+//! Logs are built the way `emit!` writes them on-chain.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::Event;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use betfun_client::events::{ArenaCreated, ArenaJoined};
+use betfun_client::state::StakeWeighting;
+use betfun_indexer::source::{SignatureInfo, TransactionLogs};
+use betfun_indexer::store::Cursor;
+use betfun_indexer::{Indexer, Result, Source, Store, SyncReport};
+
+struct Transaction {
+    signature: String,
+    slot: u64,
+    failed: bool,
+    logs: Vec<String>,
+    available: bool,
+}
+
+/// In-memory chain, oldest transaction first, shared with the test
+#[derive(Clone, Default)]
+struct Chain(Rc<RefCell<Vec<Transaction>>>);
+
+impl Chain {
+    fn push(&self, slot: u64, failed: bool, events: &[&dyn EventData]) -> String {
+        let mut transactions = self.0.borrow_mut();
+        let signature = format!("sig-{}", transactions.len());
+        let program = betfun_client::ID;
+        let mut logs = vec![format!("Program {program} invoke [1]")];
+        logs.extend(events.iter().map(|event| format!("Program data: {}", BASE64.encode(event.bytes()))));
+        logs.push(if failed {
+            format!("Program {program} failed: custom program error: 0x1770")
+        } else {
+            format!("Program {program} success")
+        });
+        transactions.push(Transaction { signature: signature.clone(), slot, failed, logs, available: true });
+        signature
+    }
+
+    fn set_available(&self, signature: &str, available: bool) {
+        let mut transactions = self.0.borrow_mut();
+        let transaction = transactions.iter_mut().find(|tx| tx.signature == signature).unwrap();
+        transaction.available = available;
+    }
+}
+
+impl Source for Chain {
+    /// Two per page, so catch-up has to paginate
+    fn signatures(&self, before: Option<&str>, until: Option<&str>) -> Result<Vec<SignatureInfo>> {
+        let transactions = self.0.borrow();
+        Ok(transactions
+            .iter()
+            .rev()
+            .skip_while(|tx| before.is_some_and(|before| tx.signature != before))
+            .skip(usize::from(before.is_some()))
+            .take_while(|tx| until != Some(tx.signature.as_str()))
+            .take(2)
+            .map(|tx| SignatureInfo { signature: tx.signature.clone(), slot: tx.slot, failed: tx.failed })
+            .collect())
+    }
+
+    fn transaction(&self, signature: &str) -> Result<Option<TransactionLogs>> {
+        let transactions = self.0.borrow();
+        let transaction = transactions.iter().find(|tx| tx.signature == signature && tx.available);
+        Ok(transaction.map(|tx| TransactionLogs {
+            slot: tx.slot,
+            block_time: Some(1_700_000_000 + tx.slot as i64),
+            failed: tx.failed,
+            logs: tx.logs.clone(),
+        }))
+    }
+}
+
+trait EventData {
+    fn bytes(&self) -> Vec<u8>;
+}
+
+impl<T: Event> EventData for T {
+    fn bytes(&self) -> Vec<u8> {
+        self.data()
+    }
+}
+
+fn created(arena: Pubkey) -> ArenaCreated {
+    ArenaCreated {
+        arena,
+        creator: Pubkey::new_unique(),
+        title: "Who wins the final?".to_string(),
+        entry_fee: 100,
+        outcomes_count: 2,
+        creator_fee_bps: 500,
+        creator_trading_fee_bps: 0,
+        scalar: None,
+        allowlist_root: None,
+        creator_bond: 0,
+        bond_mint: None,
+        lock_time: 0,
+        resolve_after: 0,
+        stake_weighting: StakeWeighting::Flat,
+        duel: None,
+    }
+}
+
+fn joined(arena: Pubkey, amount: u64) -> ArenaJoined {
+    ArenaJoined {
+        arena,
+        participant: Pubkey::new_unique(),
+        outcome_chosen: 0,
+        amount,
+        weight: amount,
+        total_pot: amount,
+        participants_count: 1,
+    }
+}
+
+fn amounts(store: &Store) -> Vec<u64> {
+    store
+        .events(Some("ArenaJoined"))
+        .unwrap()
+        .iter()
+        .map(|event| event.data["amount"].as_u64().unwrap())
+        .collect()
+}
+
+// ========== SYNC ==========
+
+#[test]
+fn stores_events_oldest_first_as_queryable_json() {
+    let chain = Chain::default();
+    let arena = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let create = chain.push(10, false, &[&created(arena)]);
+    chain.push(11, false, &[&joined(arena, 100), &joined(other, 5)]);
+    chain.push(11, false, &[&joined(arena, 250)]);
+    chain.push(12, false, &[]);
+    let mut indexer = Indexer::new(chain.clone(), Store::in_memory().unwrap());
+
+    let report = indexer.sync().unwrap();
+    assert_eq!(report, SyncReport { transactions: 4, events: 4 });
+
+    let events = indexer.store().events(None).unwrap();
+    let names: Vec<_> = events.iter().map(|event| event.name.as_str()).collect();
+    assert_eq!(names, ["ArenaCreated", "ArenaJoined", "ArenaJoined", "ArenaJoined"]);
+    assert_eq!((events[0].signature.as_str(), events[0].slot), (create.as_str(), 10));
+    assert_eq!(events[0].block_time, Some(1_700_000_010));
+    assert_eq!(events[0].data["arena"], arena.to_string());
+    assert_eq!(events[0].data["title"], "Who wins the final?");
+    assert_eq!((events[1].event_index, events[2].event_index), (0, 1));
+    assert_eq!(amounts(indexer.store()), [100, 5, 250]);
+
+    let volume: i64 = indexer
+        .store()
+        .connection()
+        .query_row(
+            "SELECT SUM(json_extract(data, '$.amount')) FROM events
+             WHERE name = 'ArenaJoined' AND json_extract(data, '$.arena') = ?1",
+            [arena.to_string()],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(volume, 350);
+    assert_eq!(
+        indexer.store().cursor().unwrap(),
+        Some(Cursor { slot: 12, signature: Some("sig-3".to_string()) })
+    );
+}
+
+#[test]
+fn skips_failed_transactions() {
+    let chain = Chain::default();
+    let arena = Pubkey::new_unique();
+    chain.push(10, false, &[&joined(arena, 1)]);
+    chain.push(11, true, &[&joined(arena, 2)]);
+    chain.push(12, false, &[&joined(arena, 3)]);
+    let mut indexer = Indexer::new(chain, Store::in_memory().unwrap());
+
+    assert_eq!(indexer.sync().unwrap(), SyncReport { transactions: 3, events: 2 });
+    assert_eq!(amounts(indexer.store()), [1, 3]);
+}
+
+#[test]
+fn later_passes_pick_up_only_new_transactions() {
+    let chain = Chain::default();
+    let arena = Pubkey::new_unique();
+    chain.push(10, false, &[&joined(arena, 1)]);
+    chain.push(11, false, &[&joined(arena, 2)]);
+    let mut indexer = Indexer::new(chain.clone(), Store::in_memory().unwrap());
+    indexer.sync().unwrap();
+
+    assert_eq!(indexer.sync().unwrap(), SyncReport::default());
+
+    chain.push(11, false, &[&joined(arena, 3)]);
+    chain.push(12, false, &[&joined(arena, 4)]);
+    chain.push(13, false, &[&joined(arena, 5)]);
+    assert_eq!(indexer.sync().unwrap(), SyncReport { transactions: 3, events: 3 });
+    assert_eq!(amounts(indexer.store()), [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn stops_at_transactions_not_yet_served() {
+    let chain = Chain::default();
+    let arena = Pubkey::new_unique();
+    chain.push(10, false, &[&joined(arena, 1)]);
+    let pending = chain.push(11, false, &[&joined(arena, 2)]);
+    chain.push(12, false, &[&joined(arena, 3)]);
+    chain.set_available(&pending, false);
+    let mut indexer = Indexer::new(chain.clone(), Store::in_memory().unwrap());
+
+    assert_eq!(indexer.sync().unwrap(), SyncReport { transactions: 1, events: 1 });
+    assert_eq!(amounts(indexer.store()), [1]);
+
+    chain.set_available(&pending, true);
+    assert_eq!(indexer.sync().unwrap(), SyncReport { transactions: 2, events: 2 });
+    assert_eq!(amounts(indexer.store()), [1, 2, 3]);
+}
+
+// ========== REPLAY ==========
+
+#[test]
+fn replay_reindexes_from_a_slot() {
+    let chain = Chain::default();
+    let arena = Pubkey::new_unique();
+    for (slot, amount) in [(10, 1), (11, 2), (12, 3), (13, 4)] {
+        chain.push(slot, false, &[&joined(arena, amount)]);
+    }
+    let mut indexer = Indexer::new(chain, Store::in_memory().unwrap());
+    indexer.sync().unwrap();
+    // Simulate a row lost or corrupted by an older indexer version
+    indexer.store().connection().execute("DELETE FROM events WHERE slot = 12", []).unwrap();
+    assert_eq!(amounts(indexer.store()), [1, 2, 4]);
+
+    assert_eq!(indexer.replay_from(12).unwrap(), SyncReport { transactions: 2, events: 2 });
+    assert_eq!(amounts(indexer.store()), [1, 2, 3, 4]);
+    assert_eq!(
+        indexer.store().cursor().unwrap(),
+        Some(Cursor { slot: 13, signature: Some("sig-3".to_string()) })
+    );
+}
+
+#[test]
+fn reopened_database_resumes_from_its_cursor() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("events.sqlite");
+    let chain = Chain::default();
+    let arena = Pubkey::new_unique();
+    chain.push(10, false, &[&joined(arena, 1)]);
+    chain.push(11, false, &[&joined(arena, 2)]);
+    Indexer::new(chain.clone(), Store::open(&path).unwrap()).sync().unwrap();
+
+    chain.push(12, false, &[&joined(arena, 3)]);
+    let mut indexer = Indexer::new(chain, Store::open(&path).unwrap());
+    assert_eq!(indexer.sync().unwrap(), SyncReport { transactions: 1, events: 1 });
+    assert_eq!(amounts(indexer.store()), [1, 2, 3]);
+}
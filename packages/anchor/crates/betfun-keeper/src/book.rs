@@ -0,0 +1,187 @@
+//! In-memory price-time order book
+//!
+//! Bids rank by highest price, asks by lowest, and ties go to the order that
+//! has been resting longest. Only the displayed slice of an iceberg order is
+//! matchable; once a slice is filled the next one is shown and the order
+//! moves to the back of its price level.
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use anchor_lang::prelude::Pubkey;
+use betfun_client::state::{LimitOrder, OrderSide, OrderType};
+
+/// An order the book can match
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestingOrder {
+    pub address: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub price: u64,
+    /// Unfilled size on chain
+    pub remaining_size: u64,
+    /// Size currently shown to the book (the iceberg slice)
+    pub displayed_size: u64,
+    /// Iceberg slice size (`None` shows the whole order)
+    pub visible_size: Option<u64>,
+    /// Time priority: lower rests longer
+    pub sequence: u64,
+    /// Order of arrival in the book; unlike `sequence`, kept across iceberg refills
+    pub arrival: u64,
+}
+
+impl RestingOrder {
+    /// Book priority against another order on the same side (`Less` ranks first)
+    fn priority(&self, other: &Self) -> Ordering {
+        let by_price = match self.side {
+            OrderSide::Buy => other.price.cmp(&self.price),
+            OrderSide::Sell => self.price.cmp(&other.price),
+        };
+        by_price.then(self.sequence.cmp(&other.sequence))
+    }
+
+    fn slice(&self) -> u64 {
+        self.visible_size.map_or(self.remaining_size, |visible| visible.min(self.remaining_size))
+    }
+}
+
+/// A bid and an ask that trade with each other
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cross {
+    pub buy: RestingOrder,
+    pub sell: RestingOrder,
+    /// Price of the order that arrived first
+    pub price: u64,
+    /// Smaller of the two displayed sizes
+    pub size: u64,
+}
+
+/// Price-time book for one outcome
+#[derive(Clone, Debug, Default)]
+pub struct Book {
+    orders: Vec<RestingOrder>,
+    next_sequence: u64,
+}
+
+impl Book {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the book's contents with the matchable subset of `orders`
+    /// Orders already in the book keep their time priority and iceberg slice;
+    /// new orders queue behind them by creation time. Inactive, expired and
+    /// untriggered stop-loss orders are dropped, and TWAP orders wait until
+    /// their interval has elapsed. `last_trade_price` triggers stops.
+    pub fn sync(&mut self, orders: impl IntoIterator<Item = (Pubkey, LimitOrder)>, now: i64, last_trade_price: u64) {
+        let mut fresh: Vec<(Pubkey, LimitOrder)> = orders
+            .into_iter()
+            .filter(|(_, order)| is_matchable(order, now, last_trade_price))
+            .collect();
+        let keep: HashSet<Pubkey> = fresh.iter().map(|(address, _)| *address).collect();
+        self.orders.retain(|order| keep.contains(&order.address));
+
+        fresh.sort_by_key(|(_, order)| (order.created_at, order.order_id));
+        for (address, order) in fresh {
+            match self.orders.iter_mut().find(|resting| resting.address == address) {
+                Some(resting) => {
+                    resting.remaining_size = order.remaining_size;
+                    resting.displayed_size = resting.displayed_size.min(order.remaining_size);
+                    if resting.displayed_size == 0 {
+                        resting.displayed_size = resting.slice();
+                    }
+                }
+                None => {
+                    let sequence = self.take_sequence();
+                    let mut resting = RestingOrder {
+                        address,
+                        order_id: order.order_id,
+                        owner: order.owner,
+                        side: order.side,
+                        price: order.price,
+                        remaining_size: order.remaining_size,
+                        displayed_size: 0,
+                        visible_size: order.visible_size,
+                        sequence,
+                        arrival: sequence,
+                    };
+                    resting.displayed_size = resting.slice();
+                    self.orders.push(resting);
+                }
+            }
+        }
+    }
+
+    /// Orders on one side, best first
+    pub fn side(&self, side: OrderSide) -> Vec<&RestingOrder> {
+        let mut orders: Vec<&RestingOrder> = self.orders.iter().filter(|order| order.side == side).collect();
+        orders.sort_by(|a, b| a.priority(b));
+        orders
+    }
+
+    /// Best bid and ask among the orders `eligible` accepts, if they cross
+    pub fn next_cross(&self, eligible: impl Fn(&RestingOrder) -> bool) -> Option<Cross> {
+        let best = |side| {
+            self.orders
+                .iter()
+                .filter(|order| order.side == side && eligible(order))
+                .min_by(|a, b| a.priority(b))
+        };
+        let (buy, sell) = (best(OrderSide::Buy)?, best(OrderSide::Sell)?);
+        if buy.price < sell.price {
+            return None;
+        }
+        let price = if buy.arrival < sell.arrival { buy.price } else { sell.price };
+        Some(Cross {
+            buy: buy.clone(),
+            sell: sell.clone(),
+            price,
+            size: buy.displayed_size.min(sell.displayed_size),
+        })
+    }
+
+    /// Record `size` filled on both sides of a settled cross
+    pub fn fill(&mut self, cross: &Cross, size: u64) {
+        for address in [cross.buy.address, cross.sell.address] {
+            let Some(index) = self.orders.iter().position(|order| order.address == address) else {
+                continue;
+            };
+            let order = &mut self.orders[index];
+            order.remaining_size = order.remaining_size.saturating_sub(size);
+            order.displayed_size = order.displayed_size.saturating_sub(size);
+            if order.remaining_size == 0 {
+                self.orders.remove(index);
+            } else if order.displayed_size == 0 {
+                // Iceberg refill loses time priority
+                order.displayed_size = order.slice();
+                order.sequence = self.next_sequence;
+                self.next_sequence += 1;
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    fn take_sequence(&mut self) -> u64 {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        sequence
+    }
+}
+
+/// Whether the program would settle `order` at `now`
+pub fn is_matchable(order: &LimitOrder, now: i64, last_trade_price: u64) -> bool {
+    let triggered = match order.order_type {
+        OrderType::StopLoss => last_trade_price > 0 && order.is_stop_triggered(last_trade_price),
+        OrderType::TWAP => order.should_execute_twap(now),
+        OrderType::Limit | OrderType::Iceberg => true,
+    };
+    order.is_active() && !order.is_expired(now) && order.remaining_size > 0 && triggered
+}
@@ -0,0 +1,223 @@
+//! Matching loop for one arena outcome
+//!
+//! Each pass reloads the outcome's `LimitOrder` accounts into the [`Book`],
+//! settles every cross, and numbers trades from
+//! the order book's `trade_count` so each `settle_match` creates the `Trade`
+//! PDA the program expects.
+
+use std::collections::HashSet;
+use std::fmt;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::Discriminator;
+use betfun_client::accounts::decode;
+use betfun_client::instructions::{self as ix, MatchedOrders};
+use betfun_client::pda;
+use betfun_client::state::{pause, Arena, GlobalConfig, LimitOrder, OrderBook};
+
+use crate::book::{Book, Cross};
+
+/// `LimitOrder` layout offsets used to filter program accounts
+const ARENA_OFFSET: usize = 8 + 8;
+const OUTCOME_INDEX_OFFSET: usize = ARENA_OFFSET + 32;
+
+/// Keeper error
+#[derive(Debug)]
+pub enum Error {
+    /// The cluster could not be read or rejected a transaction
+    Cluster(String),
+    /// An account the keeper needs is missing or malformed
+    Account { address: Pubkey, reason: String },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Cluster(reason) => write!(f, "cluster error: {reason}"),
+            Error::Account { address, reason } => write!(f, "account {address}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Account data and balance as read from the cluster
+#[derive(Clone, Debug, Default)]
+pub struct AccountData {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+/// Match program accounts whose data holds `bytes` at `offset`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memcmp {
+    pub offset: usize,
+    pub bytes: Vec<u8>,
+}
+
+/// Where the keeper reads accounts and sends transactions
+/// Implemented over JSON-RPC by [`crate::rpc::RpcCluster`]; tests implement it
+/// over the in-process runtime.
+pub trait Cluster {
+    /// BetFun accounts matching every filter
+    fn program_accounts(&self, filters: &[Memcmp]) -> Result<Vec<(Pubkey, AccountData)>>;
+
+    fn account(&self, address: &Pubkey) -> Result<Option<AccountData>>;
+
+    /// Cluster clock time
+    fn unix_timestamp(&self) -> Result<i64>;
+
+    /// Send and confirm one transaction signed and paid for by the cluster's payer
+    fn send(&mut self, instructions: &[Instruction]) -> Result<()>;
+}
+
+/// What to match and who signs for it
+#[derive(Clone, Copy, Debug)]
+pub struct KeeperConfig {
+    pub arena: Pubkey,
+    pub outcome_index: u8,
+    /// Signs and pays rent for every `Trade` account
+    pub matcher: Pubkey,
+}
+
+/// A trade the keeper settled
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Settlement {
+    pub trade_id: u64,
+    pub trade: Pubkey,
+    pub buy_order_id: u64,
+    pub sell_order_id: u64,
+    pub price: u64,
+    pub size: u64,
+}
+
+/// Matching engine for one order book
+pub struct Keeper {
+    config: KeeperConfig,
+    book: Book,
+}
+
+impl Keeper {
+    pub fn new(config: KeeperConfig) -> Self {
+        Self { config, book: Book::new() }
+    }
+
+    pub fn config(&self) -> &KeeperConfig {
+        &self.config
+    }
+
+    pub fn book(&self) -> &Book {
+        &self.book
+    }
+
+    /// `LimitOrder` accounts for the configured arena and outcome
+    pub fn load_orders(&self, cluster: &impl Cluster) -> Result<Vec<(Pubkey, LimitOrder)>> {
+        let filters = [
+            Memcmp { offset: 0, bytes: LimitOrder::DISCRIMINATOR.to_vec() },
+            Memcmp { offset: ARENA_OFFSET, bytes: self.config.arena.to_bytes().to_vec() },
+            Memcmp { offset: OUTCOME_INDEX_OFFSET, bytes: vec![self.config.outcome_index] },
+        ];
+        cluster
+            .program_accounts(&filters)?
+            .into_iter()
+            .map(|(address, account)| Ok((address, decode_account(&address, &account.data)?)))
+            .collect()
+    }
+
+    /// Resync the book from the cluster and settle every payable cross
+    /// Stops at the first rejected transaction and settles nothing once the
+    /// arena locks or while order book trading is paused; the next pass resyncs.
+    pub fn run_once(&mut self, cluster: &mut impl Cluster) -> Result<Vec<Settlement>> {
+        let KeeperConfig { arena, outcome_index, .. } = self.config;
+        let arena_state: Arena = fetch(cluster, &arena)?;
+        let now = cluster.unix_timestamp()?;
+        if arena_state.resolved || arena_state.is_locked(now) {
+            self.book = Book::new();
+            return Ok(Vec::new());
+        }
+        let config: GlobalConfig = fetch(cluster, &pda::config().0)?;
+        if arena_state.is_paused(pause::ORDER_BOOK) || config.is_paused(pause::ORDER_BOOK) {
+            return Ok(Vec::new());
+        }
+        let order_book: OrderBook = fetch(cluster, &pda::order_book(&arena, outcome_index).0)?;
+        let orders = self.load_orders(cluster)?;
+        self.book.sync(orders, now, order_book.last_trade_price);
+
+        let mut ready_accounts: HashSet<Pubkey> = HashSet::new();
+        let mut trade_id = order_book.trade_count;
+        let mut settled = Vec::new();
+
+        // Buy escrows hold the limit price and the fee on it, so every cross is payable
+        while let Some(cross) = self.book.next_cross(|_| true) {
+            let mut instructions = Vec::with_capacity(2);
+            let buyer_tokens = pda::share_token_account(&cross.buy.owner, &arena, outcome_index);
+            if !ready_accounts.contains(&buyer_tokens) && cluster.account(&buyer_tokens)?.is_none() {
+                instructions.push(ix::create_share_token_account(
+                    &self.config.matcher,
+                    &cross.buy.owner,
+                    &arena,
+                    outcome_index,
+                ));
+            }
+            let fee_recipient = config.protocol_fee_recipient();
+            instructions.push(self.settle_match(&arena_state.creator, &fee_recipient, &cross, trade_id, cross.size));
+            cluster.send(&instructions)?;
+
+            ready_accounts.insert(buyer_tokens);
+            self.book.fill(&cross, cross.size);
+            settled.push(Settlement {
+                trade_id,
+                trade: pda::trade(&arena, outcome_index, trade_id).0,
+                buy_order_id: cross.buy.order_id,
+                sell_order_id: cross.sell.order_id,
+                price: cross.price,
+                size: cross.size,
+            });
+            trade_id += 1;
+        }
+        Ok(settled)
+    }
+
+    /// `settle_match` for `size` of `cross`, recorded as trade `trade_id`
+    /// `creator` is the arena creator, who receives its trading fee, and
+    /// `fee_recipient` the config's protocol fee recipient.
+    pub fn settle_match(
+        &self,
+        creator: &Pubkey,
+        fee_recipient: &Pubkey,
+        cross: &Cross,
+        trade_id: u64,
+        size: u64,
+    ) -> Instruction {
+        let orders = MatchedOrders {
+            outcome_index: self.config.outcome_index,
+            buy_order_id: cross.buy.order_id,
+            sell_order_id: cross.sell.order_id,
+            trade_id,
+            buyer: cross.buy.owner,
+            seller: cross.sell.owner,
+            protocol_fee_recipient: *fee_recipient,
+            creator: *creator,
+            matcher: self.config.matcher,
+        };
+        ix::settle_match(&self.config.arena, &orders, size, cross.price)
+    }
+}
+
+fn fetch<T: anchor_lang::AccountDeserialize>(cluster: &impl Cluster, address: &Pubkey) -> Result<T> {
+    let account = cluster.account(address)?.ok_or_else(|| Error::Account {
+        address: *address,
+        reason: "not found".to_string(),
+    })?;
+    decode_account(address, &account.data)
+}
+
+fn decode_account<T: anchor_lang::AccountDeserialize>(address: &Pubkey, data: &[u8]) -> Result<T> {
+    decode(data).map_err(|error| Error::Account {
+        address: *address,
+        reason: error.to_string(),
+    })
+}
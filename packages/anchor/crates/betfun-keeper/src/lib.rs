@@ -0,0 +1,14 @@
+//! Matching engine keeper for the BetFun limit order book
+//!
+//! The program escrows limit orders but never matches them; `settle_match`
+//! is called by an off-chain matcher. This crate is that matcher:
+//! - [`book`]: in-memory price-time book with expiry, stop, TWAP and iceberg rules
+//! - [`keeper`]: loads an outcome's orders and settles crosses through a [`Cluster`]
+//! - [`rpc`]: [`Cluster`] over JSON-RPC, used by the `betfun-keeper` binary
+
+pub mod book;
+pub mod keeper;
+pub mod rpc;
+
+pub use book::{Book, Cross, RestingOrder};
+pub use keeper::{AccountData, Cluster, Error, Keeper, KeeperConfig, Memcmp, Result, Settlement};
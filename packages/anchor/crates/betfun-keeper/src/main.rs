@@ -0,0 +1,80 @@
+//! `betfun-keeper`: settle crossing limit orders for one arena outcome
+//!
+//! ```text
+//! betfun-keeper --arena <ARENA> --outcome-index 0 --keypair ~/.config/solana/id.json
+//! ```
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+use anchor_lang::prelude::Pubkey;
+use betfun_keeper::rpc::RpcCluster;
+use betfun_keeper::{Keeper, KeeperConfig};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(version, about = "Matching engine for the BetFun limit order book")]
+struct Args {
+    /// JSON-RPC endpoint
+    #[arg(long, default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Matcher keypair; signs and pays rent for trade accounts
+    #[arg(long)]
+    keypair: PathBuf,
+
+    #[arg(long)]
+    arena: Pubkey,
+
+    #[arg(long, default_value_t = 0)]
+    outcome_index: u8,
+
+    /// Milliseconds between passes
+    #[arg(long, default_value_t = 2_000)]
+    interval_ms: u64,
+
+    /// Run a single pass and exit
+    #[arg(long)]
+    once: bool,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    let matcher = match solana_keypair::read_keypair_file(&args.keypair) {
+        Ok(keypair) => keypair,
+        Err(error) => {
+            eprintln!("cannot read keypair {}: {error}", args.keypair.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut cluster = RpcCluster::new(args.url, matcher);
+    let mut keeper = Keeper::new(KeeperConfig {
+        arena: args.arena,
+        outcome_index: args.outcome_index,
+        matcher: cluster.payer(),
+    });
+
+    loop {
+        match keeper.run_once(&mut cluster) {
+            Ok(settled) => {
+                for trade in settled {
+                    println!(
+                        "trade {} ({}): buy #{} x sell #{}, {} @ {}",
+                        trade.trade_id, trade.trade, trade.buy_order_id, trade.sell_order_id, trade.size, trade.price
+                    );
+                }
+            }
+            Err(error) if args.once => {
+                eprintln!("{error}");
+                return ExitCode::FAILURE;
+            }
+            Err(error) => eprintln!("{error}"),
+        }
+        if args.once {
+            return ExitCode::SUCCESS;
+        }
+        thread::sleep(Duration::from_millis(args.interval_ms));
+    }
+}
@@ -0,0 +1,170 @@
+//! JSON-RPC cluster for running against a validator
+
+use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::sysvar;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_hash::Hash;
+use solana_keypair::Keypair;
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+use crate::keeper::{AccountData, Cluster, Error, Memcmp, Result};
+
+/// How long `send` waits for a transaction to confirm
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+const CONFIRM_POLL: Duration = Duration::from_millis(500);
+
+/// Cluster reached over HTTP JSON-RPC, signing with the payer keypair
+pub struct RpcCluster {
+    agent: ureq::Agent,
+    url: String,
+    payer: Keypair,
+    commitment: &'static str,
+}
+
+impl RpcCluster {
+    /// Reads and confirms at `confirmed` commitment
+    pub fn new(url: impl Into<String>, payer: Keypair) -> Self {
+        Self {
+            agent: ureq::AgentBuilder::new().timeout(Duration::from_secs(30)).build(),
+            url: url.into(),
+            payer,
+            commitment: "confirmed",
+        }
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// `result` of a JSON-RPC call
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = self
+            .agent
+            .post(&self.url)
+            .send_json(request)
+            .map_err(|error| Error::Cluster(format!("{method}: {error}")))?
+            .into_json()
+            .map_err(|error| Error::Cluster(format!("{method}: {error}")))?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Cluster(format!("{method}: {error}")));
+        }
+        Ok(response["result"].take())
+    }
+
+    fn latest_blockhash(&self) -> Result<Hash> {
+        let result = self.call("getLatestBlockhash", json!([{ "commitment": self.commitment }]))?;
+        let blockhash = result["value"]["blockhash"].as_str().unwrap_or_default();
+        Hash::from_str(blockhash).map_err(|error| Error::Cluster(format!("blockhash {blockhash:?}: {error}")))
+    }
+
+    fn confirm(&self, signature: &str) -> Result<()> {
+        let started = Instant::now();
+        while started.elapsed() < CONFIRM_TIMEOUT {
+            let result = self.call("getSignatureStatuses", json!([[signature]]))?;
+            let status = &result["value"][0];
+            if !status.is_null() {
+                if !status["err"].is_null() {
+                    return Err(Error::Cluster(format!("transaction {signature} failed: {}", status["err"])));
+                }
+                if matches!(status["confirmationStatus"].as_str(), Some("confirmed" | "finalized")) {
+                    return Ok(());
+                }
+            }
+            thread::sleep(CONFIRM_POLL);
+        }
+        Err(Error::Cluster(format!("transaction {signature} not confirmed")))
+    }
+}
+
+impl Cluster for RpcCluster {
+    fn program_accounts(&self, filters: &[Memcmp]) -> Result<Vec<(Pubkey, AccountData)>> {
+        let filters: Vec<Value> = filters
+            .iter()
+            .map(|filter| {
+                json!({ "memcmp": {
+                    "offset": filter.offset,
+                    "bytes": BASE64.encode(&filter.bytes),
+                    "encoding": "base64",
+                } })
+            })
+            .collect();
+        let result = self.call(
+            "getProgramAccounts",
+            json!([
+                betfun_client::ID.to_string(),
+                { "encoding": "base64", "commitment": self.commitment, "filters": filters },
+            ]),
+        )?;
+        result
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+            .iter()
+            .map(|entry| {
+                let address = parse_pubkey(&entry["pubkey"])?;
+                Ok((address, parse_account(&entry["account"])?))
+            })
+            .collect()
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<AccountData>> {
+        let result = self.call(
+            "getAccountInfo",
+            json!([address.to_string(), { "encoding": "base64", "commitment": self.commitment }]),
+        )?;
+        match &result["value"] {
+            Value::Null => Ok(None),
+            account => parse_account(account).map(Some),
+        }
+    }
+
+    fn unix_timestamp(&self) -> Result<i64> {
+        let clock = self.account(&sysvar::clock::ID)?.unwrap_or_default();
+        // slot, epoch_start_timestamp, epoch, leader_schedule_epoch, unix_timestamp
+        clock
+            .data
+            .get(32..40)
+            .map(|bytes| i64::from_le_bytes(bytes.try_into().unwrap()))
+            .ok_or_else(|| Error::Cluster("malformed clock sysvar".to_string()))
+    }
+
+    fn send(&mut self, instructions: &[Instruction]) -> Result<()> {
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            self.latest_blockhash()?,
+        );
+        let wire = bincode::serialize(&transaction).map_err(|error| Error::Cluster(error.to_string()))?;
+        let signature = self.call(
+            "sendTransaction",
+            json!([BASE64.encode(wire), { "encoding": "base64", "preflightCommitment": self.commitment }]),
+        )?;
+        self.confirm(signature.as_str().unwrap_or_default())
+    }
+}
+
+fn parse_pubkey(value: &Value) -> Result<Pubkey> {
+    let text = value.as_str().unwrap_or_default();
+    Pubkey::from_str(text).map_err(|error| Error::Cluster(format!("pubkey {text:?}: {error}")))
+}
+
+/// `{ lamports, data: [base64, "base64"] }` as returned with base64 encoding
+fn parse_account(value: &Value) -> Result<AccountData> {
+    let encoded = value["data"][0].as_str().unwrap_or_default();
+    Ok(AccountData {
+        lamports: value["lamports"].as_u64().unwrap_or_default(),
+        data: BASE64
+            .decode(encoded)
+            .map_err(|error| Error::Cluster(format!("account data: {error}")))?,
+    })
+}
@@ -0,0 +1,126 @@
+//! Book priority, eligibility and escrow sizing without a runtime
+
+use anchor_lang::prelude::Pubkey;
+use betfun_client::state::{LimitOrder, OrderSide, OrderStatus, OrderType};
+use betfun_keeper::book::is_matchable;
+use betfun_keeper::Book;
+
+const NOW: i64 = 1_700_000_000;
+
+fn order(order_id: u64, side: OrderSide, price: u64, size: u64) -> LimitOrder {
+    LimitOrder {
+        order_id,
+        arena: Pubkey::default(),
+        outcome_index: 0,
+        owner: Pubkey::new_unique(),
+        order_type: OrderType::Limit,
+        side,
+        price,
+        size,
+        remaining_size: size,
+        filled_size: 0,
+        stop_price: None,
+        visible_size: None,
+        twap_interval: None,
+        twap_last_execution: None,
+        status: OrderStatus::Open,
+        created_at: NOW - 100 + order_id as i64,
+        expires_at: 0,
+        updated_at: NOW,
+        fees_paid: 0,
+        avg_fill_price: 0,
+        bump: 255,
+        version: 1,
+    }
+}
+
+fn keyed(orders: Vec<LimitOrder>) -> Vec<(Pubkey, LimitOrder)> {
+    orders.into_iter().map(|order| (Pubkey::new_unique(), order)).collect()
+}
+
+// ========== PRIORITY ==========
+
+#[test]
+fn best_price_then_oldest_order_wins() {
+    let mut orders = keyed(vec![
+        order(0, OrderSide::Buy, 90, 10),
+        order(1, OrderSide::Buy, 100, 10),
+        order(2, OrderSide::Buy, 100, 10),
+        order(3, OrderSide::Sell, 120, 10),
+        order(4, OrderSide::Sell, 110, 10),
+    ]);
+    let mut book = Book::new();
+    book.sync(orders.clone(), NOW, 0);
+    let ids = |book: &Book, side| book.side(side).iter().map(|order| order.order_id).collect::<Vec<_>>();
+    assert_eq!(ids(&book, OrderSide::Buy), vec![1, 2, 0]);
+    assert_eq!(ids(&book, OrderSide::Sell), vec![4, 3]);
+    assert_eq!(book.next_cross(|_| true), None);
+
+    // A crossing ask trades at the resting bid's price
+    orders.extend(keyed(vec![order(5, OrderSide::Sell, 95, 4)]));
+    book.sync(orders, NOW, 0);
+    let cross = book.next_cross(|_| true).unwrap();
+    assert_eq!((cross.buy.order_id, cross.sell.order_id, cross.price, cross.size), (1, 5, 100, 4));
+
+    // Excluded orders step aside for the next best
+    let cross = book.next_cross(|order| order.order_id != 1).unwrap();
+    assert_eq!(cross.buy.order_id, 2);
+}
+
+#[test]
+fn iceberg_refills_at_the_back_of_its_level() {
+    let mut iceberg = order(0, OrderSide::Sell, 100, 25);
+    iceberg.order_type = OrderType::Iceberg;
+    iceberg.visible_size = Some(10);
+    let mut book = Book::new();
+    book.sync(
+        keyed(vec![iceberg, order(1, OrderSide::Sell, 100, 5), order(2, OrderSide::Buy, 100, 100)]),
+        NOW,
+        0,
+    );
+
+    let mut fills = Vec::new();
+    while let Some(cross) = book.next_cross(|_| true) {
+        fills.push((cross.sell.order_id, cross.size));
+        book.fill(&cross, cross.size);
+    }
+    assert_eq!(fills, vec![(0, 10), (1, 5), (0, 10), (0, 5)]);
+    assert_eq!(book.side(OrderSide::Sell).len(), 0);
+    assert_eq!(book.side(OrderSide::Buy)[0].remaining_size, 70);
+}
+
+// ========== ELIGIBILITY ==========
+
+#[test]
+fn only_orders_the_program_would_settle_are_matchable() {
+    let live = order(0, OrderSide::Buy, 100, 10);
+    assert!(is_matchable(&live, NOW, 0));
+
+    let expired = LimitOrder { expires_at: NOW, ..order(0, OrderSide::Buy, 100, 10) };
+    assert!(!is_matchable(&expired, NOW, 0));
+    assert!(is_matchable(&expired, NOW - 1, 0));
+
+    for status in [OrderStatus::Filled, OrderStatus::Cancelled, OrderStatus::Expired] {
+        assert!(!is_matchable(&LimitOrder { status, ..order(0, OrderSide::Buy, 100, 10) }, NOW, 0));
+    }
+
+    // Stops wait for the last trade to reach them
+    let stop = LimitOrder {
+        order_type: OrderType::StopLoss,
+        stop_price: Some(80),
+        ..order(0, OrderSide::Sell, 75, 10)
+    };
+    assert!(!is_matchable(&stop, NOW, 0));
+    assert!(!is_matchable(&stop, NOW, 90));
+    assert!(is_matchable(&stop, NOW, 80));
+
+    // TWAP orders wait out their interval
+    let twap = LimitOrder {
+        order_type: OrderType::TWAP,
+        twap_interval: Some(60),
+        twap_last_execution: Some(NOW - 30),
+        ..order(0, OrderSide::Buy, 100, 10)
+    };
+    assert!(!is_matchable(&twap, NOW, 0));
+    assert!(is_matchable(&twap, NOW + 30, 0));
+}
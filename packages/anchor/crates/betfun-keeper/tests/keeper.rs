@@ -0,0 +1,322 @@
+//! The keeper matching real orders in the in-process runtime
+//!
+//! `LocalCluster` serves the keeper's reads and transactions from
+//! `betfun_testkit::Svm`, so every pass runs the program's own
+//! `settle_match`.
+
+use anchor_lang::prelude::{AccountDeserialize, Pubkey};
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token::spl_token;
+use betfun_client::accounts::decode;
+use betfun_client::instructions::{self as ix, CreateArenaArgs};
+use betfun_client::state::{
+    pause, Arena, CurveType, LimitOrder, OrderBook, OrderSide, OrderStatus, OrderType, StakeWeighting, Trade,
+};
+use betfun_client::{pda, PlaceOrderParams};
+use betfun_keeper::{AccountData, Cluster, Error, Keeper, KeeperConfig, Memcmp, Result, Settlement};
+use betfun_testkit::Svm;
+
+const SOL: u64 = 1_000_000_000;
+const SHARE: u64 = 1_000_000_000;
+const BID: u64 = 110_000;
+const ASK: u64 = 100_000;
+const SIZE: u64 = 100;
+const FEE_BPS: u16 = 30;
+
+/// `Cluster` over the in-process runtime, signing as the matcher
+struct LocalCluster {
+    svm: Svm,
+    matcher: Pubkey,
+}
+
+impl Cluster for LocalCluster {
+    fn program_accounts(&self, filters: &[Memcmp]) -> Result<Vec<(Pubkey, AccountData)>> {
+        Ok(self
+            .svm
+            .accounts_owned_by(&betfun_client::ID)
+            .filter(|(_, account)| {
+                filters.iter().all(|filter| {
+                    account.data.get(filter.offset..filter.offset + filter.bytes.len()) == Some(&filter.bytes[..])
+                })
+            })
+            .map(|(address, account)| (*address, AccountData { lamports: account.lamports, data: account.data.clone() }))
+            .collect())
+    }
+
+    fn account(&self, address: &Pubkey) -> Result<Option<AccountData>> {
+        Ok(self
+            .svm
+            .account(address)
+            .filter(|account| account.lamports > 0)
+            .map(|account| AccountData { lamports: account.lamports, data: account.data.clone() }))
+    }
+
+    fn unix_timestamp(&self) -> Result<i64> {
+        Ok(self.svm.clock().unix_timestamp)
+    }
+
+    fn send(&mut self, instructions: &[Instruction]) -> Result<()> {
+        self.svm
+            .send_transaction(instructions, &[self.matcher])
+            .map(|_| ())
+            .map_err(|failure| Error::Cluster(failure.to_string()))
+    }
+}
+
+/// Order book on outcome 0 with a seller holding shares and a buyer with an
+/// empty share account
+struct Market {
+    cluster: LocalCluster,
+    arena: Pubkey,
+    creator: Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+    next_order_id: u64,
+}
+
+impl Market {
+    fn new() -> Self {
+        let mut svm = Svm::new();
+        svm.add_program(betfun_client::ID, betfun::entry);
+        let [creator, buyer, seller, matcher] = [(); 4].map(|_| {
+            let wallet = Pubkey::new_unique();
+            svm.airdrop(&wallet, 100 * SOL);
+            wallet
+        });
+
+        let args = CreateArenaArgs {
+            title: "Keeper Cup".to_string(),
+            description: "Matched by betfun-keeper".to_string(),
+            question: "Who wins the cup?".to_string(),
+            outcomes: vec!["Home".to_string(), "Away".to_string()],
+            tags: vec![],
+            entry_fee: SOL / 10,
+            lock_time: svm.clock().unix_timestamp + 24 * 60 * 60,
+            resolve_after: svm.clock().unix_timestamp + 24 * 60 * 60,
+            manual_resolve: false,
+            oracle: None,
+            token_mint: None,
+            creator_fee_bps: Arena::DEFAULT_CREATOR_FEE_BPS,
+            creator_trading_fee_bps: 100,
+            scalar: None,
+            allowlist_root: None,
+            stake_weighting: StakeWeighting::Flat,
+            duel: None,
+        };
+        let (arena, _) = pda::arena(&creator, &args.title);
+        svm.set_upgrade_authority(&betfun_client::ID, Some(creator));
+        let mut cluster = LocalCluster { svm, matcher };
+        cluster.run(ix::initialize_config(&creator), &creator);
+        cluster.run(ix::set_protocol_fees(&creator, &matcher, FEE_BPS), &creator);
+        cluster.run(ix::create_arena(&creator, args, None), &creator);
+        cluster.run(ix::create_share_tokens(&arena, &creator, 0, SOL / 10, CurveType::Linear, SOL / 100), &creator);
+        cluster.run(ix::initialize_order_book(&arena, &creator, 0), &creator);
+        cluster.run(ix::buy_shares(&arena, &seller, &creator, 0, SHARE, None, None, None, &[]), &seller);
+        cluster.run(ix::create_share_token_account(&buyer, &buyer, &arena, 0), &buyer);
+
+        Self { cluster, arena, creator, buyer, seller, next_order_id: 0 }
+    }
+
+    fn keeper(&self) -> Keeper {
+        Keeper::new(KeeperConfig {
+            arena: self.arena,
+            outcome_index: 0,
+            matcher: self.cluster.matcher,
+        })
+    }
+
+    /// Place an order and return its id
+    fn place(&mut self, side: OrderSide, price: u64, size: u64, tweak: impl FnOnce(&mut PlaceOrderParams)) -> u64 {
+        let owner = match side {
+            OrderSide::Buy => self.buyer,
+            OrderSide::Sell => self.seller,
+        };
+        let mut params = PlaceOrderParams {
+            order_type: OrderType::Limit,
+            side,
+            price,
+            size,
+            expires_at: 0,
+            stop_price: None,
+            visible_size: None,
+            twap_interval: None,
+        };
+        tweak(&mut params);
+        let order_id = self.next_order_id;
+        self.cluster.run(ix::place_limit_order(&self.arena, &owner, 0, order_id, params), &owner);
+        self.next_order_id += 1;
+        order_id
+    }
+
+    fn order(&self, order_id: u64) -> LimitOrder {
+        self.cluster.fetch(&pda::limit_order(&self.arena, 0, order_id).0)
+    }
+}
+
+impl LocalCluster {
+    #[track_caller]
+    fn run(&mut self, instruction: Instruction, signer: &Pubkey) {
+        if let Err(failure) = self.svm.send_instruction(instruction, &[*signer]) {
+            panic!("transaction failed: {failure}\n{:#?}", failure.logs);
+        }
+    }
+
+    fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> T {
+        decode(&self.svm.account(address).expect("account exists").data).expect("account decodes")
+    }
+}
+
+fn trades(settled: &[Settlement]) -> Vec<(u64, u64, u64, u64)> {
+    settled
+        .iter()
+        .map(|trade| (trade.trade_id, trade.buy_order_id, trade.sell_order_id, trade.size))
+        .collect()
+}
+
+// ========== MATCHING ==========
+
+#[test]
+fn settles_crosses_at_the_resting_price() {
+    let mut market = Market::new();
+    let ask = market.place(OrderSide::Sell, ASK, SIZE, |_| {});
+    let bid = market.place(OrderSide::Buy, BID, SIZE, |_| {});
+    let mut keeper = market.keeper();
+
+    // The buyer closes the empty share account their bid was placed with
+    let buyer_tokens = pda::share_token_account(&market.buyer, &market.arena, 0);
+    let close = spl_token::instruction::close_account(&spl_token::ID, &buyer_tokens, &market.buyer, &market.buyer, &[])
+        .unwrap();
+    let buyer = market.buyer;
+    market.cluster.run(close, &buyer);
+
+    let settled = keeper.run_once(&mut market.cluster).unwrap();
+    assert_eq!(trades(&settled), vec![(0, bid, ask, SIZE)]);
+    assert_eq!(settled[0].price, ASK);
+
+    // The trade landed on the PDA numbered by the book's trade count
+    let trade: Trade = market.cluster.fetch(&pda::trade(&market.arena, 0, 0).0);
+    assert_eq!(settled[0].trade, pda::trade(&market.arena, 0, 0).0);
+    assert_eq!((trade.trade_id, trade.price, trade.size), (0, ASK, SIZE));
+    assert_eq!((trade.buy_order_id, trade.sell_order_id), (bid, ask));
+    let order_book: OrderBook = market.cluster.fetch(&pda::order_book(&market.arena, 0).0);
+    assert_eq!(order_book.trade_count, 1);
+
+    // The keeper reopened the buyer's share account on the way
+    let tokens = spl_token::state::Account::unpack(&market.cluster.svm.account(&buyer_tokens).unwrap().data).unwrap();
+    assert_eq!(tokens.amount, SIZE);
+    assert!(market.order(bid).status == OrderStatus::Filled);
+    assert!(market.order(ask).status == OrderStatus::Filled);
+
+    assert!(keeper.run_once(&mut market.cluster).unwrap().is_empty());
+    assert!(keeper.book().is_empty());
+}
+
+#[test]
+fn trade_ids_continue_from_the_order_book() {
+    let mut market = Market::new();
+    let mut keeper = market.keeper();
+    for round in 0..3 {
+        let ask = market.place(OrderSide::Sell, ASK, SIZE, |_| {});
+        let bid = market.place(OrderSide::Buy, BID, SIZE, |_| {});
+        // A fresh keeper reads the count from chain rather than its own history
+        if round == 2 {
+            keeper = market.keeper();
+        }
+        let settled = keeper.run_once(&mut market.cluster).unwrap();
+        assert_eq!(trades(&settled), vec![(round, bid, ask, SIZE)]);
+    }
+    let order_book: OrderBook = market.cluster.fetch(&pda::order_book(&market.arena, 0).0);
+    assert_eq!(order_book.trade_count, 3);
+}
+
+#[test]
+fn resting_bid_sets_the_price() {
+    let mut market = Market::new();
+    let bid = market.place(OrderSide::Buy, BID, SIZE, |_| {});
+    let ask = market.place(OrderSide::Sell, ASK, SIZE, |_| {});
+    let mut keeper = market.keeper();
+
+    // The bid escrowed the fee on top of its price, so it fills whole at its
+    // own price and the emptied escrow closes
+    let escrow = pda::order_escrow(&pda::limit_order(&market.arena, 0, bid).0).0;
+    let settled = keeper.run_once(&mut market.cluster).unwrap();
+    assert_eq!(trades(&settled), vec![(0, bid, ask, SIZE)]);
+    assert_eq!(settled[0].price, BID);
+    assert_eq!(market.cluster.svm.lamports(&escrow), 0);
+    assert!(market.order(bid).status == OrderStatus::Filled);
+    assert!(market.order(ask).status == OrderStatus::Filled);
+}
+
+#[test]
+fn paused_order_books_are_left_alone() {
+    let mut market = Market::new();
+    let ask = market.place(OrderSide::Sell, ASK, SIZE, |_| {});
+    let bid = market.place(OrderSide::Buy, BID, SIZE, |_| {});
+    let mut keeper = market.keeper();
+    let (arena, creator) = (market.arena, market.creator);
+
+    market.cluster.run(ix::set_arena_pause(&arena, &creator, pause::ORDER_BOOK), &creator);
+    assert!(keeper.run_once(&mut market.cluster).unwrap().is_empty());
+    market.cluster.run(ix::set_arena_pause(&arena, &creator, 0), &creator);
+    market.cluster.run(ix::set_global_pause(&creator, pause::ORDER_BOOK), &creator);
+    assert!(keeper.run_once(&mut market.cluster).unwrap().is_empty());
+
+    market.cluster.run(ix::set_global_pause(&creator, 0), &creator);
+    let settled = keeper.run_once(&mut market.cluster).unwrap();
+    assert_eq!(trades(&settled), vec![(0, bid, ask, SIZE)]);
+}
+
+#[test]
+fn locked_arenas_are_left_alone() {
+    let mut market = Market::new();
+    market.place(OrderSide::Sell, ASK, SIZE, |_| {});
+    market.place(OrderSide::Buy, BID, SIZE, |_| {});
+    let mut keeper = market.keeper();
+
+    market.cluster.svm.advance_clock(24 * 60 * 60);
+    assert!(keeper.run_once(&mut market.cluster).unwrap().is_empty());
+}
+
+// ========== ORDER RULES ==========
+
+#[test]
+fn expired_orders_are_not_matched() {
+    let mut market = Market::new();
+    let now = market.cluster.svm.clock().unix_timestamp;
+    let expiring = market.place(OrderSide::Sell, ASK - 10_000, SIZE, |params| params.expires_at = now + 60);
+    let live = market.place(OrderSide::Sell, ASK, SIZE, |_| {});
+    market.cluster.svm.advance_clock(120);
+    let bid = market.place(OrderSide::Buy, BID, SIZE, |_| {});
+    let mut keeper = market.keeper();
+
+    let settled = keeper.run_once(&mut market.cluster).unwrap();
+    assert_eq!(trades(&settled), vec![(0, bid, live, SIZE)]);
+    assert!(market.order(expiring).status == OrderStatus::Open);
+    assert_eq!(market.order(expiring).remaining_size, SIZE);
+}
+
+#[test]
+fn icebergs_trade_one_slice_at_a_time() {
+    let mut market = Market::new();
+    let iceberg = market.place(OrderSide::Sell, ASK, SIZE, |params| {
+        params.order_type = OrderType::Iceberg;
+        params.visible_size = Some(40);
+    });
+    let plain = market.place(OrderSide::Sell, ASK, 30, |_| {});
+    let bid = market.place(OrderSide::Buy, BID, SIZE, |_| {});
+    let mut keeper = market.keeper();
+
+    // After its first slice the iceberg queues behind the plain ask
+    let settled = keeper.run_once(&mut market.cluster).unwrap();
+    assert_eq!(
+        trades(&settled),
+        vec![(0, bid, iceberg, 40), (1, bid, plain, 30), (2, bid, iceberg, 30)]
+    );
+    for trade in &settled {
+        let record: Trade = market.cluster.fetch(&trade.trade);
+        assert_eq!((record.trade_id, record.size), (trade.trade_id, trade.size));
+    }
+    assert_eq!(market.order(iceberg).remaining_size, 30);
+    assert!(market.order(bid).status == OrderStatus::Filled);
+}
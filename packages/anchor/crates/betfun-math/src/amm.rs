@@ -0,0 +1,258 @@
+//! Constant-product (x * y = k) pool math
+//!
+//! Reserves are share base units on the token side and lamports on the SOL
+//! side. The LP fee stays in the pool; the protocol and arena creator fees are
+//! taken from the SOL side of every swap so they never come out of the
+//! reserves backing `k`.
+
+use crate::{bps_of, isqrt, mul_div, price_impact_bps, MathError, Result, BPS_DENOMINATOR, SHARE_UNIT};
+
+/// Reserves and fee settings of a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pool {
+    pub token_reserve: u64,
+    pub sol_reserve: u64,
+    pub total_lp_tokens: u64,
+    /// LP fee in basis points, charged on the input
+    pub fee_bps: u16,
+    /// Protocol fee in basis points, charged on the SOL side
+    pub protocol_fee_bps: u16,
+    /// Arena creator's trading fee in basis points, charged on the SOL side
+    /// before the trade reaches the pool
+    pub creator_fee_bps: u16,
+}
+
+/// Result of a swap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SwapQuote {
+    pub amount_in: u64,
+    /// What the trader receives, net of the protocol and creator fees
+    pub amount_out: u64,
+    /// LP fee, in units of the input
+    pub fee: u64,
+    /// Protocol fee in lamports
+    pub protocol_fee: u64,
+    /// Arena creator's fee in lamports
+    pub creator_fee: u64,
+    /// Spot price before the swap (lamports per whole share)
+    pub price_before: u64,
+    /// Spot price after the swap (lamports per whole share)
+    pub price_after: u64,
+    /// Move from `price_before` to `price_after` in basis points
+    pub price_impact_bps: u16,
+    pub token_reserve_after: u64,
+    pub sol_reserve_after: u64,
+}
+
+impl SwapQuote {
+    /// Lowest `amount_out` still acceptable within `slippage_bps` of this quote
+    pub fn min_amount_out(&self, slippage_bps: u16) -> u64 {
+        with_slippage(self.amount_out, slippage_bps)
+    }
+}
+
+/// Result of a deposit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositQuote {
+    pub lp_tokens: u64,
+    pub token_reserve_after: u64,
+    pub sol_reserve_after: u64,
+}
+
+impl DepositQuote {
+    /// Lowest LP mint still acceptable within `slippage_bps` of this quote
+    pub fn min_lp_tokens(&self, slippage_bps: u16) -> u64 {
+        with_slippage(self.lp_tokens, slippage_bps)
+    }
+}
+
+/// Result of a withdrawal
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawQuote {
+    pub token_amount: u64,
+    pub sol_amount: u64,
+}
+
+/// Deposits may be off the pool ratio by at most this many basis points
+pub const DEPOSIT_RATIO_TOLERANCE_BPS: u16 = 100;
+
+/// LP tokens the first deposit must mint at least
+/// A dust-sized opening deposit would leave each LP token worth so much that
+/// later deposits round down to nothing and are kept by the first provider.
+pub const MIN_INITIAL_LP_TOKENS: u64 = 1_000;
+
+/// `amount` less `slippage_bps`, rounded down
+pub fn with_slippage(amount: u64, slippage_bps: u16) -> u64 {
+    let kept = BPS_DENOMINATOR.saturating_sub(slippage_bps as u64);
+    mul_div(amount, kept, BPS_DENOMINATOR).unwrap_or(0)
+}
+
+/// Spot price of a pool (lamports per whole share)
+pub fn spot_price(token_reserve: u64, sol_reserve: u64) -> u64 {
+    if token_reserve == 0 {
+        return 0;
+    }
+    mul_div(sol_reserve, SHARE_UNIT, token_reserve).unwrap_or(u64::MAX)
+}
+
+/// Gross output of a swap, before the protocol fee
+/// Uses dy = (y * dx') / (x + dx'), where dx' is the input less the LP fee
+pub fn amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64, fee_bps: u16) -> Result<u64> {
+    if amount_in == 0 {
+        return Err(MathError::InvalidAmount);
+    }
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(MathError::InsufficientLiquidity);
+    }
+
+    let kept_bps = BPS_DENOMINATOR
+        .checked_sub(fee_bps as u64)
+        .ok_or(MathError::ArithmeticOverflow)?;
+    let amount_in_with_fee = amount_in as u128 * kept_bps as u128 / BPS_DENOMINATOR as u128;
+
+    let numerator = amount_in_with_fee * reserve_out as u128;
+    let denominator = reserve_in as u128 + amount_in_with_fee;
+    let amount_out = u64::try_from(numerator / denominator).map_err(|_| MathError::ArithmeticOverflow)?;
+
+    if amount_out == 0 {
+        return Err(MathError::InsufficientOutputAmount);
+    }
+    Ok(amount_out)
+}
+
+impl Pool {
+    pub fn spot_price(&self) -> u64 {
+        spot_price(self.token_reserve, self.sol_reserve)
+    }
+
+    /// Quote a swap of `amount_in` tokens (`is_token_to_sol`) or lamports
+    pub fn quote_swap(&self, amount_in: u64, is_token_to_sol: bool) -> Result<SwapQuote> {
+        let (reserve_in, reserve_out) = if is_token_to_sol {
+            (self.token_reserve, self.sol_reserve)
+        } else {
+            (self.sol_reserve, self.token_reserve)
+        };
+
+        // Buyers pay the creator fee out of their SOL before it is swapped
+        let creator_fee_in = if is_token_to_sol { 0 } else { bps_of(amount_in, self.creator_fee_bps)? };
+        let swap_in = amount_in - creator_fee_in;
+        let gross_out = amount_out(swap_in, reserve_in, reserve_out, self.fee_bps)?;
+        if gross_out >= reserve_out {
+            return Err(MathError::InsufficientLiquidity);
+        }
+
+        let fee = bps_of(swap_in, self.fee_bps)?;
+        let sol_amount = if is_token_to_sol { gross_out } else { swap_in };
+        let protocol_fee = bps_of(sol_amount, self.protocol_fee_bps)?;
+        let creator_fee = if is_token_to_sol { bps_of(gross_out, self.creator_fee_bps)? } else { creator_fee_in };
+
+        // Sellers receive the SOL net of both fees; buyers pay them on top of
+        // what reaches the reserve
+        let (amount_out, token_reserve_after, sol_reserve_after) = if is_token_to_sol {
+            (
+                gross_out
+                    .checked_sub(protocol_fee)
+                    .and_then(|net_out| net_out.checked_sub(creator_fee))
+                    .ok_or(MathError::ArithmeticOverflow)?,
+                self.token_reserve.checked_add(amount_in).ok_or(MathError::ArithmeticOverflow)?,
+                self.sol_reserve - gross_out,
+            )
+        } else {
+            (
+                gross_out,
+                self.token_reserve - gross_out,
+                swap_in
+                    .checked_sub(protocol_fee)
+                    .and_then(|net_in| net_in.checked_add(self.sol_reserve))
+                    .ok_or(MathError::ArithmeticOverflow)?,
+            )
+        };
+        if amount_out == 0 {
+            return Err(MathError::InsufficientOutputAmount);
+        }
+
+        let price_before = self.spot_price();
+        let price_after = spot_price(token_reserve_after, sol_reserve_after);
+        Ok(SwapQuote {
+            amount_in,
+            amount_out,
+            fee,
+            protocol_fee,
+            creator_fee,
+            price_before,
+            price_after,
+            price_impact_bps: price_impact_bps(price_before, price_after),
+            token_reserve_after,
+            sol_reserve_after,
+        })
+    }
+
+    /// LP tokens minted for a deposit, without the pool-ratio check
+    /// First deposit mints sqrt(tokens * sol), at least `MIN_INITIAL_LP_TOKENS`;
+    /// later ones mint pro rata on the scarcer side
+    pub fn lp_tokens_for(&self, token_amount: u64, sol_amount: u64) -> Result<u64> {
+        let lp_tokens = if self.total_lp_tokens == 0 {
+            let lp_tokens = isqrt(token_amount as u128 * sol_amount as u128) as u64;
+            if lp_tokens < MIN_INITIAL_LP_TOKENS {
+                return Err(MathError::InsufficientLiquidityMinted);
+            }
+            lp_tokens
+        } else {
+            let lp_from_tokens = mul_div(self.total_lp_tokens, token_amount, self.token_reserve)?;
+            let lp_from_sol = mul_div(self.total_lp_tokens, sol_amount, self.sol_reserve)?;
+            lp_from_tokens.min(lp_from_sol)
+        };
+        if lp_tokens == 0 {
+            return Err(MathError::InsufficientLiquidityMinted);
+        }
+        Ok(lp_tokens)
+    }
+
+    /// SOL to pair with `token_amount` at the pool ratio
+    pub fn paired_sol_amount(&self, token_amount: u64) -> Result<u64> {
+        mul_div(token_amount, self.sol_reserve, self.token_reserve)
+    }
+
+    /// Quote a deposit of both sides
+    /// Into a funded pool, each side must be within
+    /// [`DEPOSIT_RATIO_TOLERANCE_BPS`] of the amount the other side implies.
+    pub fn quote_add_liquidity(&self, token_amount: u64, sol_amount: u64) -> Result<DepositQuote> {
+        if token_amount == 0 || sol_amount == 0 {
+            return Err(MathError::InvalidAmount);
+        }
+
+        if self.total_lp_tokens > 0 {
+            let expected_sol = mul_div(token_amount, self.sol_reserve, self.token_reserve)?;
+            let expected_tokens = mul_div(sol_amount, self.token_reserve, self.sol_reserve)?;
+            if !within_tolerance(sol_amount, expected_sol) || !within_tolerance(token_amount, expected_tokens) {
+                return Err(MathError::SlippageToleranceExceeded);
+            }
+        }
+
+        let lp_tokens = self.lp_tokens_for(token_amount, sol_amount)?;
+        Ok(DepositQuote {
+            lp_tokens,
+            token_reserve_after: self.token_reserve.checked_add(token_amount).ok_or(MathError::ArithmeticOverflow)?,
+            sol_reserve_after: self.sol_reserve.checked_add(sol_amount).ok_or(MathError::ArithmeticOverflow)?,
+        })
+    }
+
+    /// Quote burning `lp_tokens` for a pro-rata share of both reserves
+    pub fn quote_remove_liquidity(&self, lp_tokens: u64) -> Result<WithdrawQuote> {
+        if lp_tokens == 0 {
+            return Err(MathError::InvalidAmount);
+        }
+        if lp_tokens > self.total_lp_tokens {
+            return Err(MathError::InsufficientLiquidity);
+        }
+        Ok(WithdrawQuote {
+            token_amount: mul_div(self.token_reserve, lp_tokens, self.total_lp_tokens)?,
+            sol_amount: mul_div(self.sol_reserve, lp_tokens, self.total_lp_tokens)?,
+        })
+    }
+}
+
+fn within_tolerance(amount: u64, expected: u64) -> bool {
+    let tolerance = mul_div(expected, DEPOSIT_RATIO_TOLERANCE_BPS as u64, BPS_DENOMINATOR).unwrap_or(0);
+    amount >= expected.saturating_sub(tolerance) && amount <= expected.saturating_add(tolerance)
+}
@@ -0,0 +1,198 @@
+//! Primary issuance bonding curves
+//!
+//! Costs are taken from the integral of the price over supply. The integral
+//! ([`Curve::reserve_at`]) is evaluated in integer lamports, so buying and
+//! selling the same shares always telescopes back to the same escrow balance.
+
+use crate::{price_impact_bps, MathError, Result, SHARE_UNIT};
+
+/// Shape of the curve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shape {
+    /// price = base_price + param * supply (param in lamports per whole share minted)
+    /// A zero slope gives a fixed price
+    Linear,
+    /// price = base_price * 2^(supply / param) (param is the doubling supply in base units)
+    Exponential,
+}
+
+/// Bonding curve parameters
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Curve {
+    pub shape: Shape,
+    /// Price at zero supply (lamports per whole share)
+    pub base_price: u64,
+    /// Slope (linear) or doubling supply (exponential)
+    pub param: u64,
+}
+
+/// Result of minting or burning shares along the curve
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TradeQuote {
+    /// Shares minted or burned (base units)
+    pub amount: u64,
+    /// Lamports paid for a buy, or received for a sell
+    pub lamports: u64,
+    /// Average execution price (lamports per whole share)
+    pub average_price: u64,
+    /// Spot price before the trade
+    pub price_before: u64,
+    /// Spot price at the new supply
+    pub price_after: u64,
+    /// Move from `price_before` to `price_after` in basis points
+    pub price_impact_bps: u16,
+    pub supply_after: u64,
+}
+
+const UNIT: u128 = SHARE_UNIT as u128;
+
+/// Fractional bits used by the fixed-point exponent
+const FRAC_BITS: u32 = 48;
+const ONE: u128 = 1 << FRAC_BITS;
+
+/// 1 / ln(2) in Q48
+const LOG2_E: u128 = 406_082_553_034_799;
+
+/// 2^(2^-i) in Q48, for i = 1..=48
+const EXP2_TABLE: [u128; 48] = [
+    398_065_729_532_860, 334_732_044_999_537, 306_950_638_654_743, 293_936_938_588_304,
+    287_638_476_118_103, 284_540_038_248_454, 283_003_357_999_923, 282_238_132_792_267,
+    281_856_296_460_736, 281_665_572_056_716, 281_570_258_256_901, 281_522_613_452_764,
+    281_498_794_074_041, 281_486_885_140_442, 281_480_930_862_574, 281_477_953_770_871,
+    281_476_465_236_827, 281_475_720_972_757, 281_475_348_841_460, 281_475_162_775_996,
+    281_475_069_743_311, 281_475_023_226_979, 281_474_999_968_816, 281_474_988_339_736,
+    281_474_982_525_196, 281_474_979_617_926, 281_474_978_164_290, 281_474_977_437_473,
+    281_474_977_074_064, 281_474_976_892_360, 281_474_976_801_508, 281_474_976_756_082,
+    281_474_976_733_369, 281_474_976_722_012, 281_474_976_716_334, 281_474_976_713_495,
+    281_474_976_712_075, 281_474_976_711_365, 281_474_976_711_010, 281_474_976_710_833,
+    281_474_976_710_744, 281_474_976_710_700, 281_474_976_710_678, 281_474_976_710_667,
+    281_474_976_710_661, 281_474_976_710_658, 281_474_976_710_657, 281_474_976_710_656,
+];
+
+/// 2^(numerator / denominator) in Q48
+fn exp2_ratio(numerator: u64, denominator: u64) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+
+    let whole = numerator / denominator;
+    // Keep the result (< 2^(whole + 49)) inside u128 headroom for later multiplies
+    if whole > 64 {
+        return None;
+    }
+
+    let remainder = (numerator % denominator) as u128;
+    let frac = (remainder << FRAC_BITS) / denominator as u128;
+
+    let mut result = ONE;
+    for (i, factor) in EXP2_TABLE.iter().enumerate() {
+        if frac & (1 << (FRAC_BITS - 1 - i as u32)) != 0 {
+            result = (result * factor) >> FRAC_BITS;
+        }
+    }
+
+    result.checked_shl(whole as u32)
+}
+
+/// Average execution price for a trade (lamports per whole share)
+pub fn average_price(amount: u64, total: u64) -> u64 {
+    if amount == 0 {
+        return 0;
+    }
+    ((total as u128 * UNIT) / amount as u128).min(u64::MAX as u128) as u64
+}
+
+impl Curve {
+    /// Spot price at a given supply (lamports per whole share)
+    pub fn price_at(&self, supply: u64) -> Option<u64> {
+        let price = match self.shape {
+            Shape::Linear => (self.param as u128)
+                .checked_mul(supply as u128)?
+                .checked_div(UNIT)?
+                .checked_add(self.base_price as u128)?,
+            Shape::Exponential => (self.base_price as u128)
+                .checked_mul(exp2_ratio(supply, self.param)?)?
+                >> FRAC_BITS,
+        };
+        u64::try_from(price).ok()
+    }
+
+    /// Lamports held against the curve when `supply` shares are outstanding
+    /// (integral of the price from 0 to `supply`)
+    pub fn reserve_at(&self, supply: u64) -> Option<u64> {
+        let supply_u = supply as u128;
+        let reserve = match self.shape {
+            Shape::Linear => {
+                // base * s + slope * s^2 / 2, scaled down from whole shares
+                let flat = (self.base_price as u128)
+                    .checked_mul(supply_u)?
+                    / UNIT;
+                let ramp = (self.param as u128)
+                    .checked_mul(supply_u)?
+                    / UNIT;
+                let ramp = ramp.checked_mul(supply_u)? / (2 * UNIT);
+                flat.checked_add(ramp)?
+            }
+            Shape::Exponential => {
+                // base * d * (2^(s/d) - 1) / (ln 2 * unit)
+                // The price gain keeps 16 fractional bits until the final shift
+                let growth = exp2_ratio(supply, self.param)?.checked_sub(ONE)?;
+                let gain = (self.base_price as u128).checked_mul(growth)? >> (FRAC_BITS - 16);
+                let scaled = gain.checked_mul(self.param as u128)? / UNIT;
+                scaled.checked_mul(LOG2_E)? >> (FRAC_BITS + 16)
+            }
+        };
+        u64::try_from(reserve).ok()
+    }
+
+    /// Cost of minting `amount` shares on top of `supply`
+    pub fn buy_cost(&self, supply: u64, amount: u64) -> Option<u64> {
+        let new_supply = supply.checked_add(amount)?;
+        self.reserve_at(new_supply)?
+            .checked_sub(self.reserve_at(supply)?)
+    }
+
+    /// Proceeds from burning `amount` shares out of `supply`
+    pub fn sell_proceeds(&self, supply: u64, amount: u64) -> Option<u64> {
+        let new_supply = supply.checked_sub(amount)?;
+        Some(
+            self.reserve_at(supply)?
+                .saturating_sub(self.reserve_at(new_supply)?),
+        )
+    }
+
+    /// Quote minting `amount` shares on top of `supply`
+    pub fn quote_buy(&self, supply: u64, amount: u64) -> Result<TradeQuote> {
+        if amount == 0 {
+            return Err(MathError::InvalidAmount);
+        }
+        let cost = self.buy_cost(supply, amount).ok_or(MathError::ArithmeticOverflow)?;
+        if cost == 0 {
+            return Err(MathError::InvalidAmount);
+        }
+        self.quote(supply, amount, cost, supply + amount)
+    }
+
+    /// Quote burning `amount` shares out of `supply`
+    pub fn quote_sell(&self, supply: u64, amount: u64) -> Result<TradeQuote> {
+        if amount == 0 {
+            return Err(MathError::InvalidAmount);
+        }
+        let proceeds = self.sell_proceeds(supply, amount).ok_or(MathError::ArithmeticOverflow)?;
+        self.quote(supply, amount, proceeds, supply - amount)
+    }
+
+    fn quote(&self, supply: u64, amount: u64, lamports: u64, supply_after: u64) -> Result<TradeQuote> {
+        let price_before = self.price_at(supply).ok_or(MathError::ArithmeticOverflow)?;
+        let price_after = self.price_at(supply_after).ok_or(MathError::ArithmeticOverflow)?;
+        Ok(TradeQuote {
+            amount,
+            lamports,
+            average_price: average_price(amount, lamports),
+            price_before,
+            price_after,
+            price_impact_bps: price_impact_bps(price_before, price_after),
+            supply_after,
+        })
+    }
+}
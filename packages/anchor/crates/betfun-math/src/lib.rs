@@ -0,0 +1,89 @@
+//! Pricing and payout math for BetFun
+//!
+//! Pure integer arithmetic with no Solana dependencies, shared by the program
+//! and off-chain clients so a quote matches execution to the lamport:
+//! - [`amm`]: constant-product swaps and pool liquidity
+//! - [`curve`]: primary issuance bonding curves
+//! - [`payout`]: arena pot payouts and share redemption
+
+#![no_std]
+
+use core::fmt;
+
+pub mod amm;
+pub mod curve;
+pub mod payout;
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Base units per whole share (share mints use 9 decimals); prices are
+/// lamports per whole share
+pub const SHARE_UNIT: u64 = 1_000_000_000;
+
+/// Why a quote could not be produced
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MathError {
+    InvalidAmount,
+    InsufficientLiquidity,
+    InsufficientOutputAmount,
+    InsufficientLiquidityMinted,
+    SlippageToleranceExceeded,
+    ArithmeticOverflow,
+}
+
+impl fmt::Display for MathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::InvalidAmount => "Invalid amount",
+            Self::InsufficientLiquidity => "Insufficient liquidity",
+            Self::InsufficientOutputAmount => "Insufficient output amount",
+            Self::InsufficientLiquidityMinted => "Insufficient liquidity minted",
+            Self::SlippageToleranceExceeded => "Slippage tolerance exceeded",
+            Self::ArithmeticOverflow => "Arithmetic overflow",
+        })
+    }
+}
+
+pub type Result<T> = core::result::Result<T, MathError>;
+
+/// `value * numerator / denominator`, rounded down
+pub fn mul_div(value: u64, numerator: u64, denominator: u64) -> Result<u64> {
+    if denominator == 0 {
+        return Err(MathError::ArithmeticOverflow);
+    }
+    u64::try_from(value as u128 * numerator as u128 / denominator as u128)
+        .map_err(|_| MathError::ArithmeticOverflow)
+}
+
+/// Basis-point share of `amount`, rounded down
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    mul_div(amount, bps as u64, BPS_DENOMINATOR)
+}
+
+/// Relative move from `before` to `after` in basis points, capped at 100%
+pub fn price_impact_bps(before: u64, after: u64) -> u16 {
+    if before == 0 {
+        return if after == 0 { 0 } else { BPS_DENOMINATOR as u16 };
+    }
+    let impact = before.abs_diff(after) as u128 * BPS_DENOMINATOR as u128 / before as u128;
+    impact.min(BPS_DENOMINATOR as u128) as u16
+}
+
+/// Integer square root (floor)
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    // Newton's method from an initial guess above the root
+    let mut x = 1u128 << ((128 - value.leading_zeros()).div_ceil(2));
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
@@ -0,0 +1,127 @@
+//! Arena pot payouts and winning share redemption
+//!
+//! Winners split the pot less the creator fee in proportion to their stake in
+//! the winning outcome. Winning shares split the share reserve pro rata;
+//! scalar LONG and SHORT shares split a fixed price by where the resolved
+//! value landed.
+//! Parlays multiply the odds each arena's pot implies for its pick. Voided
+//! arenas refund stakes pro rata, topped up by the creator's slashed bond.
+//! Arenas with an early-bettor bonus split the pot by weighted stake instead,
+//! each stake's weight decaying linearly from creation to lock.
+
+use crate::{bps_of, mul_div, MathError, Result, SHARE_UNIT};
+
+/// Lamports paid per whole scalar LONG/SHORT pair on redemption
+pub const REDEMPTION_PRICE: u64 = 1_000_000_000;
+
+/// Fixed-point scale of decimal odds: `ODDS_ONE` pays back exactly the stake
+pub const ODDS_ONE: u64 = 1_000_000_000;
+
+/// Result of a winning claim
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClaimQuote {
+    /// Lamports sent to the winner
+    pub payout: u64,
+    /// Payout above the original stake
+    pub profit: u64,
+    /// Creator fee taken from the pot (paid once, on the first claim)
+    pub creator_fee: u64,
+}
+
+/// Creator's cut of the pot
+pub fn creator_fee(pot: u64, creator_fee_bps: u16) -> u64 {
+    bps_of(pot, creator_fee_bps).unwrap_or(0)
+}
+
+/// Payout for `stake` lamports on the winning outcome
+/// If nobody staked the winner, the stake is returned as-is.
+pub fn payout(stake: u64, pot: u64, winner_pot: u64, creator_fee_bps: u16) -> Result<u64> {
+    if winner_pot == 0 {
+        return Ok(stake);
+    }
+    let distributable_pot = pot
+        .checked_sub(creator_fee(pot, creator_fee_bps))
+        .ok_or(MathError::ArithmeticOverflow)?;
+    mul_div(stake, distributable_pot, winner_pot)
+}
+
+/// Quote a winning claim for `stake`
+pub fn quote_claim(stake: u64, pot: u64, winner_pot: u64, creator_fee_bps: u16) -> Result<ClaimQuote> {
+    let payout = payout(stake, pot, winner_pot, creator_fee_bps)?;
+    Ok(ClaimQuote {
+        payout,
+        profit: payout.saturating_sub(stake),
+        creator_fee: creator_fee(pot, creator_fee_bps),
+    })
+}
+
+/// Weight of a `stake` placed at `joined_at` in an arena open from `created_at`
+/// until `lock_time`: the stake plus up to `bonus_bps` of it, the bonus
+/// decaying linearly from full at creation to nothing at lock
+pub fn stake_weight(stake: u64, bonus_bps: u16, joined_at: i64, created_at: i64, lock_time: i64) -> Result<u64> {
+    if lock_time <= created_at {
+        return Err(MathError::InvalidAmount);
+    }
+    let remaining = lock_time.abs_diff(joined_at.clamp(created_at, lock_time));
+    let bonus = mul_div(bps_of(stake, bonus_bps)?, remaining, lock_time.abs_diff(created_at))?;
+    stake.checked_add(bonus).ok_or(MathError::ArithmeticOverflow)
+}
+
+/// Lamports paid for redeeming `amount` scalar LONG/SHORT pair base units
+pub fn redemption_value(amount: u64) -> Result<u64> {
+    mul_div(amount, REDEMPTION_PRICE, SHARE_UNIT)
+}
+
+/// Lamports paid for redeeming `amount` of the `supply` winning share base
+/// units still outstanding, out of a `reserve` that backs them all
+/// Rounds down, so later redeemers never get less per share than earlier ones.
+pub fn pro_rata_redemption(amount: u64, supply: u64, reserve: u64) -> Result<u64> {
+    if amount > supply {
+        return Err(MathError::InvalidAmount);
+    }
+    mul_div(amount, reserve, supply)
+}
+
+/// Lamports paid for redeeming `amount` scalar share base units of a market
+/// that resolved at `value` within `[lower, upper]`
+/// LONG pays `(value - lower) / (upper - lower)` of the redemption price and
+/// SHORT the rest; values outside the range are clamped to it.
+pub fn scalar_redemption_value(amount: u64, long: bool, value: i64, lower: i64, upper: i64) -> Result<u64> {
+    if lower >= upper {
+        return Err(MathError::InvalidAmount);
+    }
+    let value = value.clamp(lower, upper);
+    let settled = if long { value.abs_diff(lower) } else { upper.abs_diff(value) };
+    mul_div(redemption_value(amount)?, settled, upper.abs_diff(lower))
+}
+
+/// Decimal odds the pot implies for an outcome, in [`ODDS_ONE`] units: what
+/// each staked lamport would collect if the outcome won right now
+pub fn implied_odds(pot: u64, outcome_pot: u64, creator_fee_bps: u16) -> Result<u64> {
+    if outcome_pot == 0 {
+        return Err(MathError::InvalidAmount);
+    }
+    payout(ODDS_ONE, pot, outcome_pot, creator_fee_bps)
+}
+
+/// Payout of `stake` on a parlay whose legs pay `odds` each, rounded down
+pub fn parlay_payout(stake: u64, odds: impl IntoIterator<Item = u64>) -> Result<u64> {
+    odds.into_iter().try_fold(stake, |payout, odds| mul_div(payout, odds, ODDS_ONE))
+}
+
+/// Payout of a parlay once a leg locked at `odds` is voided: that leg pays back evenly
+pub fn parlay_without_leg(payout: u64, odds: u64) -> Result<u64> {
+    if odds == 0 {
+        return Err(MathError::InvalidAmount);
+    }
+    mul_div(payout, ODDS_ONE, odds)
+}
+
+/// Refund of `stake` from a voided arena that split `refundable` lamports
+/// (the pot less fees already paid, plus the creator's bond) across a `pot` of stakes
+pub fn void_refund(stake: u64, pot: u64, refundable: u64) -> Result<u64> {
+    if pot == 0 {
+        return Err(MathError::InvalidAmount);
+    }
+    mul_div(stake, refundable, pot)
+}
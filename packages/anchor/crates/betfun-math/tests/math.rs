@@ -0,0 +1,340 @@
+//! Quote consistency: reserves, fees and payouts add up for any input
+
+use betfun_math::amm::{self, Pool};
+use betfun_math::curve::{Curve, Shape};
+use betfun_math::payout;
+use betfun_math::{isqrt, mul_div, price_impact_bps, MathError, SHARE_UNIT};
+use proptest::prelude::*;
+
+const SOL: u64 = 1_000_000_000;
+
+fn pool(token_reserve: u64, sol_reserve: u64) -> Pool {
+    Pool {
+        token_reserve,
+        sol_reserve,
+        total_lp_tokens: isqrt(token_reserve as u128 * sol_reserve as u128) as u64,
+        fee_bps: 30,
+        protocol_fee_bps: 10,
+        creator_fee_bps: 0,
+    }
+}
+
+// ========== HELPERS ==========
+
+#[test]
+fn isqrt_is_floor() {
+    for value in [0u128, 1, 2, 3, 4, 15, 16, 17, u64::MAX as u128, u128::MAX] {
+        let root = isqrt(value);
+        assert!(root * root <= value);
+        assert!((root + 1).checked_mul(root + 1).is_none_or(|next| next > value));
+    }
+}
+
+#[test]
+fn price_impact_is_symmetric_and_capped() {
+    assert_eq!(price_impact_bps(100, 110), 1_000);
+    assert_eq!(price_impact_bps(100, 90), 1_000);
+    assert_eq!(price_impact_bps(100, 1_000), 10_000);
+    assert_eq!(price_impact_bps(0, 0), 0);
+    assert_eq!(price_impact_bps(0, 1), 10_000);
+}
+
+#[test]
+fn mul_div_rejects_overflow() {
+    assert_eq!(mul_div(u64::MAX, 2, 2), Ok(u64::MAX));
+    assert_eq!(mul_div(u64::MAX, 2, 1), Err(MathError::ArithmeticOverflow));
+    assert_eq!(mul_div(1, 1, 0), Err(MathError::ArithmeticOverflow));
+}
+
+// ========== AMM ==========
+
+#[test]
+fn swap_quote_breaks_down_fees() {
+    // 4 shares against 1 SOL: buy with 0.1 SOL
+    let quote = pool(4 * SHARE_UNIT, SOL).quote_swap(SOL / 10, false).unwrap();
+    let net_in = SOL / 10 * 9_970 / 10_000;
+    assert_eq!(quote.amount_out, net_in * 4 * SHARE_UNIT / (SOL + net_in));
+    assert_eq!(quote.fee, SOL / 10 * 30 / 10_000);
+    assert_eq!(quote.protocol_fee, SOL / 10 * 10 / 10_000);
+    assert_eq!(quote.sol_reserve_after, SOL + SOL / 10 - quote.protocol_fee);
+    assert_eq!(quote.price_before, SOL / 4);
+    assert_eq!(quote.price_impact_bps, price_impact_bps(quote.price_before, quote.price_after));
+    assert_eq!(quote.min_amount_out(100), quote.amount_out * 99 / 100);
+}
+
+#[test]
+fn swap_quote_takes_creator_fee_from_sol_side() {
+    let with_fee = Pool { creator_fee_bps: 100, ..pool(4 * SHARE_UNIT, SOL) };
+
+    // Buys swap what is left after the creator fee
+    let buy = with_fee.quote_swap(SOL / 10, false).unwrap();
+    let net = Pool { creator_fee_bps: 0, ..with_fee }.quote_swap(SOL / 10 - SOL / 1_000, false).unwrap();
+    assert_eq!(buy.creator_fee, SOL / 1_000);
+    assert_eq!(buy.amount_out, net.amount_out);
+    assert_eq!(buy.sol_reserve_after, net.sol_reserve_after);
+
+    // Sells pay it out of the SOL leg, like the protocol fee
+    let sell = with_fee.quote_swap(SHARE_UNIT, true).unwrap();
+    let gross = sell.amount_out + sell.protocol_fee + sell.creator_fee;
+    assert_eq!(sell.creator_fee, gross / 100);
+    assert_eq!(sell.sol_reserve_after, SOL - gross);
+}
+
+#[test]
+fn swap_quote_rejects_what_the_program_rejects() {
+    let funded = pool(4 * SHARE_UNIT, SOL);
+    assert_eq!(funded.quote_swap(0, false), Err(MathError::InvalidAmount));
+    // One lamport is all fee, so nothing comes out
+    assert_eq!(funded.quote_swap(1, false), Err(MathError::InsufficientOutputAmount));
+    assert_eq!(pool(0, 0).quote_swap(SOL, false), Err(MathError::InsufficientLiquidity));
+}
+
+#[test]
+fn deposits_must_match_the_pool_ratio() {
+    let funded = pool(4 * SHARE_UNIT, SOL);
+    let sol = funded.paired_sol_amount(SHARE_UNIT).unwrap();
+    assert_eq!(sol, SOL / 4);
+    assert!(funded.quote_add_liquidity(SHARE_UNIT, sol * 101 / 100).is_ok());
+    assert_eq!(
+        funded.quote_add_liquidity(SHARE_UNIT, sol * 102 / 100),
+        Err(MathError::SlippageToleranceExceeded)
+    );
+
+    // An empty pool takes any ratio
+    let empty = Pool { total_lp_tokens: 0, ..pool(0, 0) };
+    let quote = empty.quote_add_liquidity(4 * SHARE_UNIT, SOL).unwrap();
+    assert_eq!(quote.lp_tokens as u128, isqrt(4 * SHARE_UNIT as u128 * SOL as u128));
+}
+
+#[test]
+fn opening_deposits_must_mint_a_minimum() {
+    let empty = Pool { total_lp_tokens: 0, ..pool(0, 0) };
+    let root = amm::MIN_INITIAL_LP_TOKENS;
+    assert_eq!(empty.quote_add_liquidity(root, root).unwrap().lp_tokens, root);
+    assert_eq!(
+        empty.quote_add_liquidity(root - 1, root),
+        Err(MathError::InsufficientLiquidityMinted)
+    );
+}
+
+proptest! {
+    #[test]
+    fn swap_reserves_account_for_every_lamport(
+        token_reserve in 1_000u64..1_000_000 * SHARE_UNIT,
+        sol_reserve in 1_000u64..1_000_000 * SOL,
+        amount_in in 1u64..1_000_000 * SOL,
+        creator_fee_bps in 0u16..=200,
+        is_token_to_sol: bool,
+    ) {
+        let pool = Pool { creator_fee_bps, ..pool(token_reserve, sol_reserve) };
+        if let Ok(quote) = pool.quote_swap(amount_in, is_token_to_sol) {
+            let k_before = token_reserve as u128 * sol_reserve as u128;
+            let k_after = quote.token_reserve_after as u128 * quote.sol_reserve_after as u128;
+            prop_assert!(k_after >= k_before);
+
+            if is_token_to_sol {
+                prop_assert_eq!(quote.token_reserve_after, token_reserve + amount_in);
+                prop_assert_eq!(
+                    sol_reserve - quote.sol_reserve_after,
+                    quote.amount_out + quote.protocol_fee + quote.creator_fee
+                );
+            } else {
+                prop_assert_eq!(token_reserve - quote.token_reserve_after, quote.amount_out);
+                prop_assert_eq!(
+                    quote.sol_reserve_after - sol_reserve,
+                    amount_in - quote.protocol_fee - quote.creator_fee
+                );
+            }
+            prop_assert!(quote.amount_out > 0);
+            prop_assert_eq!(quote.price_after, amm::spot_price(quote.token_reserve_after, quote.sol_reserve_after));
+        }
+    }
+
+    #[test]
+    fn withdrawals_never_exceed_reserves(
+        token_reserve in 1u64..u64::MAX / 2,
+        sol_reserve in 1u64..u64::MAX / 2,
+        total_lp_tokens in 1u64..u64::MAX,
+        lp_tokens in 1u64..u64::MAX,
+    ) {
+        let pool = Pool { total_lp_tokens, ..pool(token_reserve, sol_reserve) };
+        match pool.quote_remove_liquidity(lp_tokens) {
+            Ok(quote) => {
+                prop_assert!(quote.token_amount <= token_reserve);
+                prop_assert!(quote.sol_amount <= sol_reserve);
+            }
+            Err(error) => {
+                prop_assert!(lp_tokens > total_lp_tokens);
+                prop_assert_eq!(error, MathError::InsufficientLiquidity);
+            }
+        }
+    }
+}
+
+// ========== CURVE ==========
+
+proptest! {
+    #[test]
+    fn buy_then_sell_round_trips(
+        linear: bool,
+        base_price in 1u64..10 * SOL,
+        param in 1u64..1_000 * SHARE_UNIT,
+        supply in 0u64..100 * SHARE_UNIT,
+        amount in 1u64..100 * SHARE_UNIT,
+    ) {
+        let shape = if linear { Shape::Linear } else { Shape::Exponential };
+        let curve = Curve { shape, base_price, param };
+        if let Ok(buy) = curve.quote_buy(supply, amount) {
+            let sell = curve.quote_sell(buy.supply_after, amount).unwrap();
+            prop_assert_eq!(sell.lamports, buy.lamports);
+            prop_assert_eq!(sell.supply_after, supply);
+            prop_assert!(buy.price_after >= buy.price_before);
+            prop_assert!(buy.average_price >= buy.price_before.saturating_sub(1));
+        }
+    }
+}
+
+// ========== PAYOUT ==========
+
+proptest! {
+    #[test]
+    fn claims_split_the_pot_less_the_fee(
+        stakes in prop::collection::vec(1u64..1_000 * SOL, 1..20),
+        losing_pot in 0u64..10_000 * SOL,
+        creator_fee_bps in 0u16..=10_000,
+    ) {
+        let winner_pot: u64 = stakes.iter().sum();
+        let pot = winner_pot + losing_pot;
+        let fee = payout::creator_fee(pot, creator_fee_bps);
+
+        let mut paid = 0u64;
+        for stake in &stakes {
+            let quote = payout::quote_claim(*stake, pot, winner_pot, creator_fee_bps).unwrap();
+            prop_assert_eq!(quote.creator_fee, fee);
+            prop_assert_eq!(quote.profit, quote.payout.saturating_sub(*stake));
+            paid += quote.payout;
+        }
+        prop_assert!(paid + fee <= pot);
+        // Rounding loses at most one lamport per winner
+        prop_assert!(pot - fee - paid < stakes.len() as u64);
+    }
+}
+
+#[test]
+fn unbacked_winner_gets_the_stake_back() {
+    assert_eq!(payout::payout(SOL, 5 * SOL, 0, 500), Ok(SOL));
+    assert_eq!(payout::redemption_value(SHARE_UNIT / 2), Ok(SOL / 2));
+}
+
+#[test]
+fn redemption_is_priced_per_whole_share() {
+    assert_eq!(payout::redemption_value(SHARE_UNIT), Ok(payout::REDEMPTION_PRICE));
+    assert_eq!(payout::redemption_value(1), Ok(payout::REDEMPTION_PRICE / SHARE_UNIT));
+    // Any supply of base units fits, unlike a price per base unit
+    assert_eq!(payout::redemption_value(u64::MAX), Ok(u64::MAX));
+}
+
+proptest! {
+    #[test]
+    fn scalar_sides_split_the_redemption_price(
+        amount in 0u64..1_000_000 * SHARE_UNIT,
+        lower in -1_000_000i64..1_000_000,
+        width in 1i64..1_000_000,
+        value in -3_000_000i64..3_000_000,
+    ) {
+        let upper = lower + width;
+        let full = payout::redemption_value(amount).unwrap();
+        let long = payout::scalar_redemption_value(amount, true, value, lower, upper).unwrap();
+        let short = payout::scalar_redemption_value(amount, false, value, lower, upper).unwrap();
+        prop_assert!(long + short <= full);
+        prop_assert!(full - long - short <= 1);
+    }
+}
+
+proptest! {
+    #[test]
+    fn pro_rata_redemptions_drain_the_reserve_exactly(
+        positions in prop::collection::vec(1u64..1_000 * SHARE_UNIT, 1..20),
+        reserve in 0u64..1_000_000 * SOL,
+    ) {
+        let mut supply: u64 = positions.iter().sum();
+        let mut left = reserve;
+        let fair = |amount: u64| amount as u128 * reserve as u128 / positions.iter().sum::<u64>() as u128;
+        for amount in &positions {
+            let value = payout::pro_rata_redemption(*amount, supply, left).unwrap();
+            // Never below the holder's share of the original reserve
+            prop_assert!(value as u128 + 1 >= fair(*amount));
+            left -= value;
+            supply -= amount;
+        }
+        // The last redeemer takes whatever rounding left behind
+        prop_assert_eq!(left, 0);
+    }
+}
+
+#[test]
+fn pro_rata_redemption_splits_the_reserve() {
+    assert_eq!(payout::pro_rata_redemption(SHARE_UNIT, 4 * SHARE_UNIT, 10 * SOL), Ok(5 * SOL / 2));
+    assert_eq!(payout::pro_rata_redemption(SHARE_UNIT, SHARE_UNIT, 0), Ok(0));
+    assert_eq!(payout::pro_rata_redemption(2, 1, SOL), Err(MathError::InvalidAmount));
+}
+
+#[test]
+fn early_stakes_earn_more_weight() {
+    let weight = |joined_at| payout::stake_weight(SOL, 5_000, joined_at, 1_000, 2_000);
+    assert_eq!(weight(1_000), Ok(3 * SOL / 2));
+    assert_eq!(weight(1_750), Ok(SOL + SOL / 8));
+    assert_eq!(weight(2_000), Ok(SOL));
+    // Joins outside the betting window are clamped to it
+    assert_eq!((weight(0), weight(5_000)), (Ok(3 * SOL / 2), Ok(SOL)));
+    assert_eq!(payout::stake_weight(SOL, 0, 1_000, 1_000, 2_000), Ok(SOL));
+    assert_eq!(payout::stake_weight(SOL, 5_000, 1_000, 2_000, 2_000), Err(MathError::InvalidAmount));
+
+    // Weighted stakes split the pot: the early bettor's 1.5 SOL weight against 1 SOL
+    let (early, late) = (weight(1_000).unwrap(), weight(2_000).unwrap());
+    assert_eq!(payout::payout(early, 3 * SOL, early + late, 0), Ok(9 * SOL / 5));
+    assert_eq!(payout::payout(late, 3 * SOL, early + late, 0), Ok(6 * SOL / 5));
+}
+
+#[test]
+fn scalar_redemption_follows_the_resolved_value() {
+    let value = |long, value| payout::scalar_redemption_value(SHARE_UNIT, long, value, 100, 200);
+    assert_eq!(value(true, 125), Ok(SOL / 4));
+    assert_eq!(value(false, 125), Ok(3 * SOL / 4));
+    // Out-of-range values settle at the nearest bound
+    assert_eq!((value(true, 500), value(false, 500)), (Ok(SOL), Ok(0)));
+    assert_eq!((value(true, -5), value(false, -5)), (Ok(0), Ok(SOL)));
+    assert_eq!(
+        payout::scalar_redemption_value(SHARE_UNIT, true, 0, 10, 10),
+        Err(MathError::InvalidAmount)
+    );
+}
+
+#[test]
+fn parlay_odds_multiply_across_legs() {
+    // 10 SOL pot, 2.5 SOL on the pick and no fee: 4x
+    assert_eq!(payout::implied_odds(10 * SOL, 5 * SOL / 2, 0), Ok(4 * payout::ODDS_ONE));
+    // A 5% fee takes its cut of every leg
+    assert_eq!(payout::implied_odds(10 * SOL, 5 * SOL, 500), Ok(19 * payout::ODDS_ONE / 10));
+    assert_eq!(payout::implied_odds(10 * SOL, 0, 0), Err(MathError::InvalidAmount));
+
+    let odds = [4 * payout::ODDS_ONE, 3 * payout::ODDS_ONE / 2];
+    assert_eq!(payout::parlay_payout(SOL, odds), Ok(6 * SOL));
+    assert_eq!(payout::parlay_payout(SOL, []), Ok(SOL));
+}
+
+#[test]
+fn voided_parlay_leg_pays_back_evenly() {
+    let payout = payout::parlay_payout(SOL, [4 * payout::ODDS_ONE, 3 * payout::ODDS_ONE / 2]).unwrap();
+    assert_eq!(payout::parlay_without_leg(payout, 3 * payout::ODDS_ONE / 2), Ok(4 * SOL));
+    assert_eq!(payout::parlay_without_leg(payout, 0), Err(MathError::InvalidAmount));
+}
+
+#[test]
+fn void_refund_shares_the_bond_pro_rata() {
+    // 4 SOL pot, 0.1 SOL already paid to referrers, 1 SOL bond slashed
+    let refundable = 4 * SOL - SOL / 10 + SOL;
+    assert_eq!(payout::void_refund(SOL, 4 * SOL, refundable), Ok(SOL + SOL / 4 - SOL / 40));
+    assert_eq!(payout::void_refund(SOL, 4 * SOL, 4 * SOL), Ok(SOL));
+    assert_eq!(payout::void_refund(SOL, 0, SOL), Err(MathError::InvalidAmount));
+}
@@ -0,0 +1,520 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    syscalls::{MAX_CPI_ACCOUNT_INFOS, MAX_CPI_INSTRUCTION_DATA_LEN},
+};
+
+use crate::{Entrypoint, StoredAccount, SvmError};
+
+/// Deepest CPI chain the runtime allows (the top-level instruction counts as 1)
+const MAX_INVOKE_DEPTH: usize = 5;
+
+/// Account state compared before and after each program invocation
+#[derive(Clone, PartialEq, Eq)]
+struct Snapshot {
+    lamports: u64,
+    data: Vec<u8>,
+    owner: Pubkey,
+}
+
+impl Snapshot {
+    fn of(info: &AccountInfo) -> Result<Self, SvmError> {
+        let data = info
+            .try_borrow_data()
+            .map_err(|_| SvmError::AccountBorrowFailed(*info.key))?
+            .to_vec();
+        Ok(Self {
+            lamports: info.lamports(),
+            data,
+            owner: *info.owner,
+        })
+    }
+}
+
+/// One program on the invocation stack
+struct Frame {
+    program_id: Pubkey,
+    /// Account state as of the last point this frame's changes were checked
+    pre: HashMap<Pubkey, Snapshot>,
+}
+
+/// Per-transaction state reachable from the syscall stubs
+pub(crate) struct InvokeContext {
+    programs: HashMap<Pubkey, Entrypoint>,
+    clock: Clock,
+    rent: Rent,
+    logs: Vec<String>,
+    return_data: Option<(Pubkey, Vec<u8>)>,
+    frames: Vec<Frame>,
+    /// First runtime or callee error; CPI failures abort the whole transaction
+    fatal: Option<SvmError>,
+}
+
+impl InvokeContext {
+    pub(crate) fn new(programs: HashMap<Pubkey, Entrypoint>, clock: Clock, rent: Rent) -> Self {
+        Self {
+            programs,
+            clock,
+            rent,
+            logs: Vec::new(),
+            return_data: None,
+            frames: Vec::new(),
+            fatal: None,
+        }
+    }
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Option<InvokeContext>> = const { RefCell::new(None) };
+}
+
+/// Install the context for a transaction on this thread
+pub(crate) fn begin(context: InvokeContext) {
+    install_stubs();
+    CONTEXT.with(|c| *c.borrow_mut() = Some(context));
+}
+
+/// Tear down the transaction context, returning its logs
+pub(crate) fn end() -> Vec<String> {
+    CONTEXT
+        .with(|c| c.borrow_mut().take())
+        .map(|context| context.logs)
+        .unwrap_or_default()
+}
+
+fn try_with_context<R>(f: impl FnOnce(&mut InvokeContext) -> R) -> Option<R> {
+    CONTEXT.with(|c| c.borrow_mut().as_mut().map(f))
+}
+
+fn with_context<R>(f: impl FnOnce(&mut InvokeContext) -> R) -> R {
+    try_with_context(f).expect("no transaction is executing on this thread")
+}
+
+fn log(message: String) {
+    if try_with_context(|ctx| ctx.logs.push(message.clone())).is_none() {
+        println!("{message}");
+    }
+}
+
+/// Serialized account region laid out like the BPF loader's input buffer
+///
+/// `AccountInfo::resize` reads the original length 4 bytes before the key and
+/// writes the new length 8 bytes before the data, so both are kept in place.
+struct SerializedAccount {
+    buffer: Vec<u64>,
+    executable: bool,
+}
+
+impl SerializedAccount {
+    const ORIGINAL_LEN_OFFSET: usize = 4;
+    const KEY_OFFSET: usize = 8;
+    const OWNER_OFFSET: usize = 40;
+    const LAMPORTS_OFFSET: usize = 72;
+    const DATA_LEN_OFFSET: usize = 80;
+    const DATA_OFFSET: usize = 88;
+
+    fn new(key: &Pubkey, account: &StoredAccount) -> Self {
+        let len = Self::DATA_OFFSET + account.data.len() + MAX_PERMITTED_DATA_INCREASE;
+        let mut serialized = Self {
+            buffer: vec![0u64; len.div_ceil(8)],
+            executable: account.executable,
+        };
+
+        let data_len = account.data.len();
+        let bytes = serialized.bytes_mut();
+        bytes[Self::ORIGINAL_LEN_OFFSET..Self::KEY_OFFSET]
+            .copy_from_slice(&(data_len as u32).to_le_bytes());
+        bytes[Self::KEY_OFFSET..Self::OWNER_OFFSET].copy_from_slice(key.as_ref());
+        bytes[Self::OWNER_OFFSET..Self::LAMPORTS_OFFSET].copy_from_slice(account.owner.as_ref());
+        bytes[Self::LAMPORTS_OFFSET..Self::DATA_LEN_OFFSET]
+            .copy_from_slice(&account.lamports.to_le_bytes());
+        bytes[Self::DATA_LEN_OFFSET..Self::DATA_OFFSET]
+            .copy_from_slice(&(data_len as u64).to_le_bytes());
+        bytes[Self::DATA_OFFSET..Self::DATA_OFFSET + data_len].copy_from_slice(&account.data);
+        serialized
+    }
+
+    fn bytes(&self) -> &[u8] {
+        // SAFETY: a u64 buffer is always valid to view as bytes
+        unsafe { std::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, self.buffer.len() * 8) }
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        // SAFETY: a u64 buffer is always valid to view as bytes
+        unsafe {
+            std::slice::from_raw_parts_mut(self.buffer.as_mut_ptr() as *mut u8, self.buffer.len() * 8)
+        }
+    }
+
+    /// Build an `AccountInfo` pointing into the buffer
+    ///
+    /// # Safety
+    /// The buffer must outlive the returned info and every clone of it, and
+    /// must not be accessed through `self` while any of them is alive.
+    unsafe fn info<'a>(&mut self, is_signer: bool, is_writable: bool) -> AccountInfo<'a> {
+        let base = self.buffer.as_mut_ptr() as *mut u8;
+        let key = &*(base.add(Self::KEY_OFFSET) as *const Pubkey);
+        let owner = &*(base.add(Self::OWNER_OFFSET) as *const Pubkey);
+        let lamports = &mut *(base.add(Self::LAMPORTS_OFFSET) as *mut u64);
+        let data_len = *(base.add(Self::DATA_LEN_OFFSET) as *const u64) as usize;
+        let data = std::slice::from_raw_parts_mut(base.add(Self::DATA_OFFSET), data_len);
+        AccountInfo::new(key, is_signer, is_writable, lamports, data, owner, self.executable, u64::MAX)
+    }
+
+    fn read(&self) -> StoredAccount {
+        let bytes = self.bytes();
+        let field = |start: usize| u64::from_le_bytes(bytes[start..start + 8].try_into().unwrap());
+        let data_len = field(Self::DATA_LEN_OFFSET) as usize;
+        StoredAccount {
+            lamports: field(Self::LAMPORTS_OFFSET),
+            data: bytes[Self::DATA_OFFSET..Self::DATA_OFFSET + data_len].to_vec(),
+            owner: Pubkey::try_from(&bytes[Self::OWNER_OFFSET..Self::LAMPORTS_OFFSET]).unwrap(),
+            executable: self.executable,
+        }
+    }
+}
+
+/// Check the changes a program made to one account against the runtime's rules
+fn verify_account(
+    key: &Pubkey,
+    pre: &Snapshot,
+    post: &Snapshot,
+    program_id: &Pubkey,
+    is_writable: bool,
+) -> Result<(), SvmError> {
+    let owned = pre.owner == *program_id;
+
+    if pre.owner != post.owner {
+        let zeroed = post.data.iter().all(|b| *b == 0);
+        if !owned || !is_writable || !zeroed {
+            return Err(SvmError::ModifiedProgramId(*key));
+        }
+    }
+
+    if pre.lamports != post.lamports {
+        if !is_writable {
+            return Err(SvmError::ReadonlyLamportChange(*key));
+        }
+        if post.lamports < pre.lamports && !owned {
+            return Err(SvmError::ExternalAccountLamportSpend(*key));
+        }
+    }
+
+    if pre.data != post.data {
+        if !is_writable {
+            return Err(SvmError::ReadonlyDataModified(*key));
+        }
+        if !owned {
+            return Err(SvmError::ExternalAccountDataModified(*key));
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge signer/writable flags of repeated account metas
+fn merged_privileges(ix: &Instruction) -> (Vec<Pubkey>, HashMap<Pubkey, (bool, bool)>) {
+    let mut keys = Vec::new();
+    let mut privileges: HashMap<Pubkey, (bool, bool)> = HashMap::new();
+    for meta in &ix.accounts {
+        let entry = privileges.entry(meta.pubkey).or_insert_with(|| {
+            keys.push(meta.pubkey);
+            (false, false)
+        });
+        entry.0 |= meta.is_signer;
+        entry.1 |= meta.is_writable;
+    }
+    (keys, privileges)
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Run one top-level instruction against `accounts`
+///
+/// On success the modified accounts are written back; on failure `accounts`
+/// is left in an unspecified state and the caller discards it.
+pub(crate) fn process_instruction(
+    ix: &Instruction,
+    accounts: &mut HashMap<Pubkey, StoredAccount>,
+    signers: &HashSet<Pubkey>,
+) -> Result<(), SvmError> {
+    let entrypoint = with_context(|ctx| ctx.programs.get(&ix.program_id).copied())
+        .ok_or(SvmError::UnsupportedProgramId(ix.program_id))?;
+
+    let (keys, privileges) = merged_privileges(ix);
+    for key in &keys {
+        if privileges[key].0 && !signers.contains(key) {
+            return Err(SvmError::MissingRequiredSignature(*key));
+        }
+    }
+
+    let mut serialized: Vec<SerializedAccount> = keys
+        .iter()
+        .map(|key| SerializedAccount::new(key, &accounts.get(key).cloned().unwrap_or_default()))
+        .collect();
+
+    let outcome = {
+        // SAFETY: `serialized` is not touched again until every info is dropped
+        // at the end of this block
+        let unique: Vec<AccountInfo> = serialized
+            .iter_mut()
+            .zip(&keys)
+            .map(|(account, key)| {
+                let (is_signer, is_writable) = privileges[key];
+                unsafe { account.info(is_signer, is_writable) }
+            })
+            .collect();
+        let infos: Vec<AccountInfo> = ix
+            .accounts
+            .iter()
+            .map(|meta| unique[keys.iter().position(|k| *k == meta.pubkey).unwrap()].clone())
+            .collect();
+
+        let mut pre = HashMap::new();
+        for info in &unique {
+            pre.insert(*info.key, Snapshot::of(info)?);
+        }
+        let lamports_before: u128 = unique.iter().map(|info| info.lamports() as u128).sum();
+
+        with_context(|ctx| {
+            ctx.return_data = None;
+            ctx.frames.push(Frame { program_id: ix.program_id, pre });
+        });
+        log(format!("Program {} invoke [1]", ix.program_id));
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            entrypoint(&ix.program_id, &infos, &ix.data)
+        }));
+
+        let (frame, fatal) = with_context(|ctx| (ctx.frames.pop(), ctx.fatal.take()));
+        let frame = frame.expect("invocation stack underflow");
+
+        let outcome = match (result, fatal) {
+            (_, Some(error)) => Err(error),
+            (Err(payload), None) => Err(SvmError::ProgramPanicked(panic_message(payload))),
+            (Ok(Err(error)), None) => Err(SvmError::Program(error)),
+            (Ok(Ok(())), None) => {
+                let mut outcome = Ok(());
+                for info in &unique {
+                    let post = Snapshot::of(info)?;
+                    let checked = verify_account(
+                        info.key,
+                        &frame.pre[info.key],
+                        &post,
+                        &ix.program_id,
+                        info.is_writable,
+                    );
+                    if checked.is_err() {
+                        outcome = checked;
+                        break;
+                    }
+                }
+                let lamports_after: u128 = unique.iter().map(|info| info.lamports() as u128).sum();
+                if outcome.is_ok() && lamports_before != lamports_after {
+                    outcome = Err(SvmError::UnbalancedInstruction);
+                }
+                outcome
+            }
+        };
+
+        match &outcome {
+            Ok(()) => log(format!("Program {} success", ix.program_id)),
+            Err(error) => log(format!("Program {} failed: {error}", ix.program_id)),
+        }
+        outcome
+    };
+    outcome?;
+
+    for (key, account) in keys.iter().zip(&serialized) {
+        accounts.insert(*key, account.read());
+    }
+    Ok(())
+}
+
+/// Cross-program invocation issued by a running program
+fn invoke_signed(
+    ix: &Instruction,
+    account_infos: &[AccountInfo],
+    signers_seeds: &[&[&[u8]]],
+) -> Result<(), SvmError> {
+    let (caller_id, entrypoint, depth) = with_context(|ctx| {
+        let caller = ctx.frames.last().expect("CPI outside of a program").program_id;
+        (caller, ctx.programs.get(&ix.program_id).copied(), ctx.frames.len())
+    });
+    let entrypoint = entrypoint.ok_or(SvmError::UnsupportedProgramId(ix.program_id))?;
+    if depth >= MAX_INVOKE_DEPTH {
+        return Err(SvmError::CallDepth);
+    }
+    if ix.data.len() as u64 > MAX_CPI_INSTRUCTION_DATA_LEN {
+        return Err(SvmError::MaxInstructionDataLenExceeded);
+    }
+    if account_infos.len() > MAX_CPI_ACCOUNT_INFOS {
+        return Err(SvmError::MaxAccountInfosExceeded);
+    }
+
+    let pda_signers = signers_seeds
+        .iter()
+        .map(|seeds| Pubkey::create_program_address(seeds, &caller_id))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| SvmError::InvalidSeeds)?;
+
+    // Callee accounts share their data and lamports with the caller's infos
+    let (keys, privileges) = merged_privileges(ix);
+    let mut caller_infos = Vec::with_capacity(keys.len());
+    for key in &keys {
+        let caller_info = account_infos
+            .iter()
+            .find(|info| info.key == key)
+            .ok_or(SvmError::MissingAccount(*key))?;
+        let (is_signer, is_writable) = privileges[key];
+        if is_signer && !caller_info.is_signer && !pda_signers.contains(key) {
+            return Err(SvmError::PrivilegeEscalation(*key));
+        }
+        if is_writable && !caller_info.is_writable {
+            return Err(SvmError::PrivilegeEscalation(*key));
+        }
+        caller_infos.push(caller_info);
+    }
+
+    let infos: Vec<AccountInfo> = ix
+        .accounts
+        .iter()
+        .map(|meta| {
+            let mut info = caller_infos[keys.iter().position(|k| *k == meta.pubkey).unwrap()].clone();
+            (info.is_signer, info.is_writable) = privileges[&meta.pubkey];
+            info
+        })
+        .collect();
+
+    // The caller's own changes so far must be legal before the callee sees them
+    let mut callee_pre = HashMap::new();
+    for info in &caller_infos {
+        let current = Snapshot::of(info)?;
+        with_context(|ctx| {
+            let frame = ctx.frames.last_mut().unwrap();
+            match frame.pre.get(info.key) {
+                Some(pre) => verify_account(info.key, pre, &current, &frame.program_id, info.is_writable),
+                None => Ok(()),
+            }
+        })?;
+        with_context(|ctx| ctx.frames.last_mut().unwrap().pre.insert(*info.key, current.clone()));
+        callee_pre.insert(*info.key, current);
+    }
+    let lamports_before: u128 = callee_pre.values().map(|s| s.lamports as u128).sum();
+
+    with_context(|ctx| ctx.frames.push(Frame { program_id: ix.program_id, pre: callee_pre }));
+    log(format!("Program {} invoke [{}]", ix.program_id, depth + 1));
+
+    // SAFETY: the infos only borrow buffers owned by the top-level instruction,
+    // which outlive this call; the lifetime is narrowed to fit the entrypoint
+    let callee_infos: &[AccountInfo] = unsafe { std::mem::transmute(infos.as_slice()) };
+    let result = entrypoint(&ix.program_id, callee_infos, &ix.data);
+
+    let frame = with_context(|ctx| ctx.frames.pop()).expect("invocation stack underflow");
+    if let Err(error) = result {
+        log(format!("Program {} failed: {error}", ix.program_id));
+        return Err(SvmError::Program(error));
+    }
+
+    // Check the callee's changes, then fold them into the caller's baseline
+    let mut lamports_after: u128 = 0;
+    for (key, info) in keys.iter().zip(&caller_infos) {
+        let post = Snapshot::of(info)?;
+        verify_account(key, &frame.pre[key], &post, &ix.program_id, privileges[key].1)?;
+        lamports_after += post.lamports as u128;
+        with_context(|ctx| ctx.frames.last_mut().unwrap().pre.insert(*key, post));
+    }
+    if lamports_before != lamports_after {
+        return Err(SvmError::UnbalancedInstruction);
+    }
+
+    log(format!("Program {} success", ix.program_id));
+    Ok(())
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, message: &str) {
+        log(format!("Program log: {message}"));
+    }
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        let encoded: Vec<String> = fields.iter().map(|f| BASE64_STANDARD.encode(f)).collect();
+        log(format!("Program data: {}", encoded.join(" ")));
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        invoke_signed(instruction, account_infos, signers_seeds).map_err(|error| {
+            let returned = match &error {
+                SvmError::Program(program_error) => program_error.clone(),
+                _ => ProgramError::InvalidArgument,
+            };
+            with_context(|ctx| {
+                ctx.fatal.get_or_insert(error);
+            });
+            returned
+        })
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = with_context(|ctx| ctx.clock.clone());
+        // SAFETY: the sysvar getter passes a pointer to a `Clock`
+        unsafe { std::ptr::write_unaligned(var_addr as *mut Clock, clock) };
+        SUCCESS
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let rent = with_context(|ctx| ctx.rent.clone());
+        // SAFETY: the sysvar getter passes a pointer to a `Rent`
+        unsafe { std::ptr::write_unaligned(var_addr as *mut Rent, rent) };
+        SUCCESS
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        with_context(|ctx| ctx.return_data.clone())
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        with_context(|ctx| {
+            let program_id = ctx.frames.last().expect("return data outside of a program").program_id;
+            ctx.return_data = if data.is_empty() { None } else { Some((program_id, data.to_vec())) };
+        });
+    }
+
+    fn sol_get_stack_height(&self) -> u64 {
+        try_with_context(|ctx| ctx.frames.len() as u64).unwrap_or(0)
+    }
+}
+
+fn install_stubs() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        set_syscall_stubs(Box::new(Stubs));
+    });
+}
@@ -0,0 +1,459 @@
+//! In-process Solana runtime for offline program tests
+//!
+//! Programs run natively through their Rust entrypoints. Cross-program
+//! invocations, sysvars, logs and return data are routed through
+//! `solana_program`'s syscall stubs, and every invocation is checked against
+//! the runtime's account rules (signers, PDA signers, ownership of lamport and
+//! data changes, lamport conservation, rent exemption). Transactions are held
+//! to the packet size and account lock limits, and CPIs to the call depth,
+//! data length and account info limits. The System, SPL Token, Token-2022 and
+//! Associated Token Account programs are loaded by default.
+//!
+//! Native execution is not the SBF VM, so compute units and the 4 KiB stack
+//! frame are not metered, and nothing here stands in for a run of the built
+//! program on a validator before a deploy.
+
+mod invoke;
+mod system;
+mod token;
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    message::Message,
+    program_error::ProgramError,
+    pubkey,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar,
+};
+
+/// Native entrypoint of a program loaded into the runtime
+pub type Entrypoint = for<'a> fn(&Pubkey, &'a [AccountInfo<'a>], &[u8]) -> ProgramResult;
+
+/// Owner of the native (builtin) programs
+const NATIVE_LOADER_ID: Pubkey = pubkey!("NativeLoader1111111111111111111111111111111");
+
+/// Owner of deployed programs
+const BPF_LOADER_UPGRADEABLE_ID: Pubkey = pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+/// Size of a ProgramData account before the program's bytes
+const PROGRAM_DATA_HEADER_SIZE: usize = 45;
+
+/// System program
+pub(crate) const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+
+/// Default cluster time for a fresh runtime (2024-01-01T00:00:00Z)
+const GENESIS_TIMESTAMP: i64 = 1_704_067_200;
+
+/// Largest serialized transaction a validator accepts (`PACKET_DATA_SIZE`)
+const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Most accounts one transaction may lock (`MAX_TX_ACCOUNT_LOCKS`)
+const MAX_TX_ACCOUNT_LOCKS: usize = 64;
+
+/// Size of one transaction signature
+const SIGNATURE_SIZE: usize = 64;
+
+/// Account as stored by the runtime
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StoredAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+/// Why a transaction was rejected
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SvmError {
+    /// A program returned an error (including errors from CPI callees)
+    Program(ProgramError),
+    /// A program panicked
+    ProgramPanicked(String),
+    /// A signer account was not signed for
+    MissingRequiredSignature(Pubkey),
+    /// A CPI asked for signer or writable access the caller did not have
+    PrivilegeEscalation(Pubkey),
+    /// PDA signer seeds did not produce a valid program address
+    InvalidSeeds,
+    /// An instruction targeted a program that is not loaded
+    UnsupportedProgramId(Pubkey),
+    /// A CPI referenced an account the caller did not pass in
+    MissingAccount(Pubkey),
+    /// An account was already borrowed when a CPI needed it
+    AccountBorrowFailed(Pubkey),
+    /// CPIs nested deeper than the runtime allows
+    CallDepth,
+    /// A CPI carried more instruction data than the runtime allows
+    MaxInstructionDataLenExceeded,
+    /// A CPI passed more account infos than the runtime allows
+    MaxAccountInfosExceeded,
+    /// The serialized transaction does not fit in a packet
+    TransactionTooLarge(usize),
+    /// The transaction locks more accounts than the runtime allows
+    TooManyAccountLocks(usize),
+    /// A program debited lamports from an account it does not own
+    ExternalAccountLamportSpend(Pubkey),
+    /// Lamports changed on a read-only account
+    ReadonlyLamportChange(Pubkey),
+    /// A program modified data of an account it does not own
+    ExternalAccountDataModified(Pubkey),
+    /// Data changed on a read-only account
+    ReadonlyDataModified(Pubkey),
+    /// An account owner was changed illegally
+    ModifiedProgramId(Pubkey),
+    /// Total lamports changed across an instruction
+    UnbalancedInstruction,
+    /// A writable account was left below the rent-exempt minimum
+    InsufficientFundsForRent(Pubkey),
+}
+
+impl fmt::Display for SvmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvmError::Program(error) => write!(f, "program error: {error}"),
+            SvmError::ProgramPanicked(message) => write!(f, "program panicked: {message}"),
+            SvmError::MissingRequiredSignature(key) => write!(f, "missing signature for {key}"),
+            SvmError::PrivilegeEscalation(key) => write!(f, "privilege escalation on {key}"),
+            SvmError::InvalidSeeds => write!(f, "invalid PDA signer seeds"),
+            SvmError::UnsupportedProgramId(key) => write!(f, "program {key} is not loaded"),
+            SvmError::MissingAccount(key) => write!(f, "account {key} missing from CPI"),
+            SvmError::AccountBorrowFailed(key) => write!(f, "account {key} already borrowed"),
+            SvmError::CallDepth => write!(f, "CPI call depth exceeded"),
+            SvmError::MaxInstructionDataLenExceeded => write!(f, "CPI instruction data too long"),
+            SvmError::MaxAccountInfosExceeded => write!(f, "too many CPI account infos"),
+            SvmError::TransactionTooLarge(size) => {
+                write!(f, "transaction of {size} bytes exceeds {MAX_TRANSACTION_SIZE}")
+            }
+            SvmError::TooManyAccountLocks(count) => {
+                write!(f, "transaction locks {count} accounts, more than {MAX_TX_ACCOUNT_LOCKS}")
+            }
+            SvmError::ExternalAccountLamportSpend(key) => {
+                write!(f, "lamports debited from {key} by a program that does not own it")
+            }
+            SvmError::ReadonlyLamportChange(key) => write!(f, "lamports changed on read-only {key}"),
+            SvmError::ExternalAccountDataModified(key) => {
+                write!(f, "data of {key} modified by a program that does not own it")
+            }
+            SvmError::ReadonlyDataModified(key) => write!(f, "data changed on read-only {key}"),
+            SvmError::ModifiedProgramId(key) => write!(f, "owner of {key} changed illegally"),
+            SvmError::UnbalancedInstruction => write!(f, "instruction changed total lamports"),
+            SvmError::InsufficientFundsForRent(key) => write!(f, "{key} is not rent exempt"),
+        }
+    }
+}
+
+impl std::error::Error for SvmError {}
+
+/// A rejected transaction
+#[derive(Clone, Debug)]
+pub struct FailedTransaction {
+    /// Index of the failing instruction (0 when the transaction as a whole is rejected)
+    pub index: usize,
+    pub error: SvmError,
+    pub logs: Vec<String>,
+}
+
+impl FailedTransaction {
+    /// Custom program error code, if the failure was one
+    pub fn custom_code(&self) -> Option<u32> {
+        match self.error {
+            SvmError::Program(ProgramError::Custom(code)) => Some(code),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FailedTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "instruction {} failed: {}", self.index, self.error)
+    }
+}
+
+impl std::error::Error for FailedTransaction {}
+
+/// A committed transaction
+#[derive(Clone, Debug, Default)]
+pub struct TransactionMetadata {
+    pub logs: Vec<String>,
+}
+
+/// Check a transaction against the packet size and account lock limits
+///
+/// The first signer pays the fee, as it would sign first on a validator.
+fn check_transaction_limits(instructions: &[Instruction], signers: &[Pubkey]) -> Result<(), SvmError> {
+    let message = Message::new(instructions, signers.first());
+    if message.account_keys.len() > MAX_TX_ACCOUNT_LOCKS {
+        return Err(SvmError::TooManyAccountLocks(message.account_keys.len()));
+    }
+
+    // A compact-u16 signature count, the signatures, then the message
+    let signatures = usize::from(message.header.num_required_signatures);
+    let count_len = if signatures < 0x80 { 1 } else { 2 };
+    let size = count_len + signatures * SIGNATURE_SIZE + message.serialize().len();
+    if size > MAX_TRANSACTION_SIZE {
+        return Err(SvmError::TransactionTooLarge(size));
+    }
+    Ok(())
+}
+
+/// Whether an account is exempt from rent (`None` for closed accounts)
+fn rent_exempt(rent: &Rent, account: Option<&StoredAccount>) -> Option<bool> {
+    account
+        .filter(|account| account.lamports > 0)
+        .map(|account| rent.is_exempt(account.lamports, account.data.len()))
+}
+
+/// Local runtime holding accounts, loaded programs and the cluster clock
+pub struct Svm {
+    accounts: HashMap<Pubkey, StoredAccount>,
+    programs: HashMap<Pubkey, Entrypoint>,
+    clock: Clock,
+    rent: Rent,
+}
+
+impl Default for Svm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Svm {
+    /// Runtime with the System, SPL Token, Token-2022 and Associated Token Account programs
+    pub fn new() -> Self {
+        let mut svm = Self {
+            accounts: HashMap::new(),
+            programs: HashMap::new(),
+            clock: Clock {
+                slot: 1,
+                unix_timestamp: GENESIS_TIMESTAMP,
+                epoch_start_timestamp: GENESIS_TIMESTAMP,
+                ..Clock::default()
+            },
+            rent: Rent::default(),
+        };
+
+        svm.add_builtin(SYSTEM_PROGRAM_ID, system::process_instruction);
+        svm.add_program(spl_token::ID, token::process_instruction);
+        svm.add_program(spl_token_2022::ID, token::process_instruction_2022);
+        svm.add_program(
+            spl_associated_token_account::ID,
+            spl_associated_token_account::processor::process_instruction,
+        );
+        svm.sync_sysvars();
+        svm
+    }
+
+    /// Load a program from its native entrypoint
+    /// It is deployed as an upgradeable program with a ProgramData account,
+    /// immutable until [`Svm::set_upgrade_authority`] gives it an authority.
+    pub fn add_program(&mut self, program_id: Pubkey, entrypoint: Entrypoint) {
+        self.load(program_id, entrypoint, BPF_LOADER_UPGRADEABLE_ID);
+
+        // Bincode layout of `UpgradeableLoaderState::Program`
+        let program_data = Self::program_data_address(&program_id);
+        let mut data = Vec::with_capacity(36);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(program_data.as_ref());
+        self.accounts.get_mut(&program_id).unwrap().data = data;
+        self.set_upgrade_authority(&program_id, None);
+    }
+
+    /// ProgramData account of an upgradeable program
+    pub fn program_data_address(program_id: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[program_id.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+    }
+
+    /// Rewrite the upgrade authority recorded in a program's ProgramData account
+    pub fn set_upgrade_authority(&mut self, program_id: &Pubkey, authority: Option<Pubkey>) {
+        // Bincode layout of the `UpgradeableLoaderState::ProgramData` header
+        let mut data = Vec::with_capacity(PROGRAM_DATA_HEADER_SIZE);
+        data.extend_from_slice(&3u32.to_le_bytes());
+        data.extend_from_slice(&self.clock.slot.to_le_bytes());
+        data.push(u8::from(authority.is_some()));
+        data.extend_from_slice(authority.unwrap_or_default().as_ref());
+        self.accounts.insert(
+            Self::program_data_address(program_id),
+            StoredAccount {
+                lamports: self.rent.minimum_balance(data.len()),
+                data,
+                owner: BPF_LOADER_UPGRADEABLE_ID,
+                executable: false,
+            },
+        );
+    }
+
+    fn add_builtin(&mut self, program_id: Pubkey, entrypoint: Entrypoint) {
+        self.load(program_id, entrypoint, NATIVE_LOADER_ID);
+    }
+
+    fn load(&mut self, program_id: Pubkey, entrypoint: Entrypoint, loader: Pubkey) {
+        self.programs.insert(program_id, entrypoint);
+        self.accounts.insert(
+            program_id,
+            StoredAccount {
+                lamports: 1,
+                data: Vec::new(),
+                owner: loader,
+                executable: true,
+            },
+        );
+    }
+
+    /// Write the Clock and Rent sysvar accounts from the runtime's values
+    fn sync_sysvars(&mut self) {
+        // Bincode layout, as read by `Sysvar::from_account_info`
+        let clock = [
+            self.clock.slot.to_le_bytes(),
+            self.clock.epoch_start_timestamp.to_le_bytes(),
+            self.clock.epoch.to_le_bytes(),
+            self.clock.leader_schedule_epoch.to_le_bytes(),
+            self.clock.unix_timestamp.to_le_bytes(),
+        ]
+        .concat();
+        let mut rent = Vec::with_capacity(17);
+        rent.extend_from_slice(&self.rent.lamports_per_byte_year.to_le_bytes());
+        rent.extend_from_slice(&self.rent.exemption_threshold.to_le_bytes());
+        rent.push(self.rent.burn_percent);
+
+        for (id, data) in [(sysvar::clock::ID, clock), (sysvar::rent::ID, rent)] {
+            let lamports = self.rent.minimum_balance(data.len());
+            self.accounts.insert(
+                id,
+                StoredAccount {
+                    lamports,
+                    data,
+                    owner: sysvar::ID,
+                    executable: false,
+                },
+            );
+        }
+    }
+
+    /// Account stored at `key`
+    pub fn account(&self, key: &Pubkey) -> Option<&StoredAccount> {
+        self.accounts.get(key)
+    }
+
+    /// Every account owned by `owner`, as `getProgramAccounts` returns them
+    pub fn accounts_owned_by<'a>(&'a self, owner: &'a Pubkey) -> impl Iterator<Item = (&'a Pubkey, &'a StoredAccount)> {
+        self.accounts.iter().filter(move |(_, account)| account.owner == *owner)
+    }
+
+    /// Overwrite (or create) the account at `key`
+    pub fn set_account(&mut self, key: Pubkey, account: StoredAccount) {
+        self.accounts.insert(key, account);
+    }
+
+    /// Lamport balance of `key` (0 if the account does not exist)
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts.get(key).map(|account| account.lamports).unwrap_or(0)
+    }
+
+    /// Credit lamports to `key`, creating a system account if needed
+    pub fn airdrop(&mut self, key: &Pubkey, lamports: u64) {
+        let account = self.accounts.entry(*key).or_default();
+        account.lamports += lamports;
+    }
+
+    /// Rent parameters used for exemption checks
+    pub fn rent(&self) -> &Rent {
+        &self.rent
+    }
+
+    /// Current cluster clock
+    pub fn clock(&self) -> Clock {
+        self.clock.clone()
+    }
+
+    /// Replace the cluster clock
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.clock = clock;
+        self.sync_sysvars();
+    }
+
+    /// Move cluster time to `unix_timestamp`
+    pub fn warp_to_timestamp(&mut self, unix_timestamp: i64) {
+        let mut clock = self.clock();
+        let elapsed = unix_timestamp.saturating_sub(clock.unix_timestamp).max(0);
+        // Roughly 400ms slots
+        clock.slot += (elapsed as u64) * 5 / 2;
+        clock.unix_timestamp = unix_timestamp;
+        self.set_clock(clock);
+    }
+
+    /// Move cluster time forward by `seconds`
+    pub fn advance_clock(&mut self, seconds: i64) {
+        self.warp_to_timestamp(self.clock.unix_timestamp + seconds);
+    }
+
+    /// Execute a single instruction as its own transaction
+    pub fn send_instruction(
+        &mut self,
+        instruction: Instruction,
+        signers: &[Pubkey],
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        self.send_transaction(&[instruction], signers)
+    }
+
+    /// Execute instructions atomically, committing account changes only if all succeed
+    pub fn send_transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[Pubkey],
+    ) -> Result<TransactionMetadata, FailedTransaction> {
+        check_transaction_limits(instructions, signers).map_err(|error| FailedTransaction {
+            index: 0,
+            error,
+            logs: Vec::new(),
+        })?;
+        let signers: HashSet<Pubkey> = signers.iter().copied().collect();
+        let mut working = self.accounts.clone();
+
+        invoke::begin(invoke::InvokeContext::new(
+            self.programs.clone(),
+            self.clock.clone(),
+            self.rent.clone(),
+        ));
+        let mut result = Ok(());
+        for (index, instruction) in instructions.iter().enumerate() {
+            if let Err(error) = invoke::process_instruction(instruction, &mut working, &signers) {
+                result = Err((index, error));
+                break;
+            }
+        }
+        let logs = invoke::end();
+
+        // Writable accounts may not end the transaction newly below rent exemption
+        if result.is_ok() {
+            for (index, instruction) in instructions.iter().enumerate() {
+                for meta in instruction.accounts.iter().filter(|meta| meta.is_writable) {
+                    let before = rent_exempt(&self.rent, self.accounts.get(&meta.pubkey));
+                    let after = rent_exempt(&self.rent, working.get(&meta.pubkey));
+                    let resized = self.accounts.get(&meta.pubkey).map(|a| a.data.len())
+                        != working.get(&meta.pubkey).map(|a| a.data.len());
+                    if after == Some(false) && (before != Some(false) || resized) {
+                        result = Err((index, SvmError::InsufficientFundsForRent(meta.pubkey)));
+                    }
+                }
+            }
+        }
+
+        match result {
+            Ok(()) => {
+                working.retain(|_, account| account.lamports > 0);
+                self.accounts = working;
+                Ok(TransactionMetadata { logs })
+            }
+            Err((index, error)) => Err(FailedTransaction { index, error, logs }),
+        }
+    }
+}
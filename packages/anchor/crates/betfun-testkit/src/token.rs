@@ -0,0 +1,55 @@
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program::set_return_data,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token::{instruction::TokenInstruction, processor::Processor, state::Account};
+use spl_token_2022::{
+    extension::{BaseStateWithExtensions, ExtensionType, PodStateWithExtensions},
+    instruction::TokenInstruction as Token2022Instruction,
+    pod::PodMint,
+    state::Account as Token2022Account,
+};
+
+/// SPL Token program
+///
+/// The processor publishes return data through `solana_cpi`, which does
+/// nothing off-chain, so `GetAccountDataSize` results are re-published here
+/// through the syscall stubs for callers like the Associated Token Account
+/// program.
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    Processor::process(program_id, accounts, data)?;
+
+    if let Ok(TokenInstruction::GetAccountDataSize) = TokenInstruction::unpack(data) {
+        set_return_data(&(Account::LEN as u64).to_le_bytes());
+    }
+    Ok(())
+}
+
+/// SPL Token-2022 program
+///
+/// Re-publishes `GetAccountDataSize` results the same way, sized for the
+/// extensions the mint requires of its token accounts plus those requested.
+pub fn process_instruction_2022(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    spl_token_2022::processor::Processor::process(program_id, accounts, data)?;
+
+    if let Ok(Token2022Instruction::GetAccountDataSize { extension_types }) = Token2022Instruction::unpack(data) {
+        let mint_data = accounts[0].try_borrow_data()?;
+        let mint = PodStateWithExtensions::<PodMint>::unpack(&mint_data)?;
+        let mut account_extensions = ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+        account_extensions.extend_from_slice(&extension_types);
+        let account_len = ExtensionType::try_calculate_account_len::<Token2022Account>(&account_extensions)?;
+        set_return_data(&(account_len as u64).to_le_bytes());
+    }
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use anchor_lang::prelude::*;
+use betfun_math::MathError;
 
 #[error_code]
 pub enum BetFunError {
-    #[msg("Arena has already ended")]
+    #[msg("Arena has locked: betting and trading are closed")]
     ArenaEnded,
     
-    #[msg("Arena has not ended yet")]
+    #[msg("Arena cannot be resolved yet")]
     ArenaNotEnded,
     
     #[msg("Invalid outcome index")]
@@ -44,7 +45,7 @@ pub enum BetFunError {
     #[msg("Too few outcomes (min 2)")]
     TooFewOutcomes,
     
-    #[msg("Arena title too long (max 80 chars)")]
+    #[msg("Arena title too long (max 32 bytes)")]
     TitleTooLong,
     
     #[msg("Arena description too long (max 280 chars)")]
@@ -76,5 +77,86 @@ pub enum BetFunError {
     
     #[msg("Insufficient liquidity minted")]
     InsufficientLiquidityMinted,
+    
+    #[msg("Transaction deadline exceeded")]
+    DeadlineExceeded,
+    
+    #[msg("Account version not supported; run migrate_account")]
+    UnsupportedAccountVersion,
+    
+    #[msg("This action is paused")]
+    Paused,
+    
+    #[msg("Referrer missing or does not match the user's referral")]
+    InvalidReferrer,
+    
+    #[msg("No referral rewards to claim")]
+    NoReferralRewards,
+    
+    #[msg("Not supported for this arena's market type")]
+    WrongMarketType,
+    
+    #[msg("Wallet is not on this arena's allowlist")]
+    NotAllowlisted,
+    
+    #[msg("Arena was voided for non-resolution")]
+    ArenaVoided,
+    
+    #[msg("Arena is still within its resolution window")]
+    ResolutionWindowOpen,
+    
+    #[msg("Only the named opponent can accept this duel")]
+    NotDuelOpponent,
+    
+    #[msg("This side of the duel is already taken")]
+    DuelSideTaken,
+    
+    #[msg("The challenge has not been accepted")]
+    DuelNotAccepted,
+    
+    #[msg("The matches feeding this bracket slot are not resolved yet")]
+    BracketNotReady,
+    
+    #[msg("Tournament was cancelled; entries are refunded")]
+    TournamentCancelled,
+    
+    #[msg("Tournament scores can no longer be submitted")]
+    ScoringClosed,
+    
+    #[msg("Tournament scores are still being submitted")]
+    ScoringOpen,
+    
+    #[msg("Only losing positions can be settled; claim or redeem winning ones")]
+    PositionNotLost,
+    
+    #[msg("Parlay legs must be admin-approved arenas the bettor did not create or resolve")]
+    ParlayLegNotAllowed,
+    
+    #[msg("Parlay leg's arena pot is too small")]
+    ParlayLegPotTooSmall,
+    
+    #[msg("Parlay leg's odds are above the limit")]
+    ParlayLegOddsTooHigh,
+    
+    #[msg("The challenge was already accepted")]
+    DuelAlreadyAccepted,
+    
+    #[msg("The resolution can no longer be disputed")]
+    DisputeClosed,
+    
+    #[msg("The arena has no open dispute")]
+    NoOpenDispute,
 }
 
+impl From<MathError> for BetFunError {
+    fn from(error: MathError) -> Self {
+        match error {
+            MathError::InvalidAmount => Self::InvalidAmount,
+            MathError::InsufficientLiquidity => Self::InsufficientLiquidity,
+            MathError::InsufficientOutputAmount => Self::InsufficientOutputAmount,
+            MathError::InsufficientLiquidityMinted => Self::InsufficientLiquidityMinted,
+            MathError::SlippageToleranceExceeded => Self::SlippageToleranceExceeded,
+            MathError::ArithmeticOverflow => Self::ArithmeticOverflow,
+        }
+    }
+}
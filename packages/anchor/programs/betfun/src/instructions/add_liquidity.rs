@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, MintTo};
 use anchor_spl::associated_token::AssociatedToken;
-use crate::state::{Arena, AMMPool, LiquidityPosition};
+use crate::state::{pause, Arena, AMMPool, LiquidityPosition, GlobalConfig, Versioned};
 use crate::error::BetFunError;
 
 /// Add liquidity to an AMM pool
@@ -15,9 +15,19 @@ pub struct AddLiquidity<'info> {
         ],
         bump = arena.bump,
         constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::AMM) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::AMM) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
     #[account(
         mut,
         seeds = [
@@ -27,14 +37,24 @@ pub struct AddLiquidity<'info> {
         ],
         bump = pool.bump,
         constraint = pool.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = pool.version == AMMPool::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub pool: Account<'info, AMMPool>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = lp_token_mint.key() == pool.lp_token_mint @ BetFunError::InvalidConfiguration,
+    )]
     pub lp_token_mint: Account<'info, Mint>,
 
     #[account(
         mut,
+        seeds = [
+            b"pool_token_vault",
+            pool.key().as_ref()
+        ],
+        bump,
+        token::authority = pool,
         constraint = pool_token_vault.mint == pool.share_mint @ BetFunError::InvalidConfiguration,
     )]
     pub pool_token_vault: Account<'info, TokenAccount>,
@@ -63,7 +83,9 @@ pub struct AddLiquidity<'info> {
             pool.key().as_ref(),
             provider.key().as_ref()
         ],
-        bump
+        bump,
+        constraint = liquidity_position.lp_tokens == 0
+            || liquidity_position.version == LiquidityPosition::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub liquidity_position: Account<'info, LiquidityPosition>,
 
@@ -116,81 +138,19 @@ pub fn handler(
     );
 
     // Calculate LP tokens to mint
-    let lp_tokens = if pool.total_lp_tokens == 0 {
-        // First liquidity provider
-        let product = (token_amount as u128)
-            .checked_mul(sol_amount as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-        
-        let lp = (product as f64).sqrt() as u64;
-        require!(lp > 0, BetFunError::InsufficientLiquidityMinted);
-        
-        // Set initial reserves
-        pool.token_reserve = token_amount;
-        pool.sol_reserve = sol_amount;
-        pool.k = product;
-        
-        lp
-    } else {
-        // Subsequent liquidity providers
-        // Must maintain price ratio
-        let expected_sol = (token_amount as u128)
-            .checked_mul(pool.sol_reserve as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)?
-            .checked_div(pool.token_reserve as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)? as u64;
-
-        let expected_tokens = (sol_amount as u128)
-            .checked_mul(pool.token_reserve as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)?
-            .checked_div(pool.sol_reserve as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)? as u64;
-
-        // Allow 1% slippage
-        let sol_tolerance = expected_sol / 100;
-        let token_tolerance = expected_tokens / 100;
-
-        require!(
-            sol_amount >= expected_sol.saturating_sub(sol_tolerance) &&
-            sol_amount <= expected_sol.saturating_add(sol_tolerance),
-            BetFunError::SlippageToleranceExceeded
-        );
-
-        require!(
-            token_amount >= expected_tokens.saturating_sub(token_tolerance) &&
-            token_amount <= expected_tokens.saturating_add(token_tolerance),
-            BetFunError::SlippageToleranceExceeded
-        );
-
-        // Calculate LP tokens
-        let lp_from_tokens = (pool.total_lp_tokens as u128)
-            .checked_mul(token_amount as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)?
-            .checked_div(pool.token_reserve as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)? as u64;
-
-        let lp_from_sol = (pool.total_lp_tokens as u128)
-            .checked_mul(sol_amount as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)?
-            .checked_div(pool.sol_reserve as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)? as u64;
-
-        let lp = lp_from_tokens.min(lp_from_sol);
-        require!(lp > 0, BetFunError::InsufficientLiquidityMinted);
-
-        // Update reserves
-        pool.token_reserve = pool.token_reserve
-            .checked_add(token_amount)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-        pool.sol_reserve = pool.sol_reserve
-            .checked_add(sol_amount)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-        pool.k = (pool.token_reserve as u128)
-            .checked_mul(pool.sol_reserve as u128)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-
-        lp
-    };
+    // The first deposit sets the price; later ones must match the pool ratio
+    // within 1%
+    let quote = pool.math()
+        .quote_add_liquidity(token_amount, sol_amount)
+        .map_err(BetFunError::from)?;
+    let lp_tokens = quote.lp_tokens;
+
+    // Update reserves
+    pool.token_reserve = quote.token_reserve_after;
+    pool.sol_reserve = quote.sol_reserve_after;
+    pool.k = (pool.token_reserve as u128)
+        .checked_mul(pool.sol_reserve as u128)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
 
     // Check minimum LP tokens
     require!(lp_tokens >= min_lp_tokens, BetFunError::SlippageToleranceExceeded);
@@ -258,6 +218,7 @@ pub fn handler(
         liquidity_position.created_at = current_time;
         liquidity_position.fees_earned = 0;
         liquidity_position.bump = ctx.bumps.liquidity_position;
+        liquidity_position.version = LiquidityPosition::VERSION;
     } else {
         // Add to existing position
         liquidity_position.lp_tokens = liquidity_position.lp_tokens
@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use crate::state::{pause, Arena, Dispute, GlobalConfig, StakeWeighting, Tournament, Versioned};
+use crate::error::BetFunError;
+use crate::instructions::ArenaCreated;
+
+/// Create the match arena of a bracket slot once both its competitors are known
+/// First-round matches can be created right away, later ones once the matches
+/// feeding them are resolved. Anyone may call it, paying the rent.
+#[derive(Accounts)]
+#[instruction(slot: u8)]
+pub struct AdvanceTournament<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tournament",
+            tournament.organizer.as_ref(),
+            tournament.name.as_bytes()
+        ],
+        bump = tournament.bump,
+        constraint = tournament.version == Tournament::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !tournament.cancelled @ BetFunError::TournamentCancelled,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = Arena::space(
+            Tournament::match_title(slot).len(),
+            Tournament::MAX_NAME_LEN,
+            Arena::MAX_QUESTION_LEN,
+            2,
+            0,
+        ),
+        seeds = [
+            b"arena",
+            tournament.key().as_ref(),
+            Tournament::match_title(slot).as_bytes()
+        ],
+        bump
+    )]
+    pub arena: Box<Account<'info, Arena>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::BETTING) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<AdvanceTournament>, slot: u8) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let current_time = Clock::get()?.unix_timestamp;
+    let index = slot as usize;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        index < tournament.arenas.len(),
+        BetFunError::InvalidConfiguration
+    );
+    let competitors = tournament.competitors(index)
+        .ok_or(BetFunError::BracketNotReady)?;
+    
+    // First-round matches lock when entries close, later ones a round after they open;
+    // a first-round match nobody opened in time is created already locked
+    let lock_time = match tournament.feeders(index) {
+        None => tournament.lock_time,
+        Some(_) => current_time
+            .checked_add(tournament.round_duration)
+            .ok_or(BetFunError::ArithmeticOverflow)?,
+    };
+    
+    // ========== CREATE MATCH ==========
+    
+    let outcomes: Vec<String> = competitors.iter().map(|entrant| tournament.entrants[*entrant].clone()).collect();
+    let question = format!("{}: {} vs {}, who advances?", tournament.name, outcomes[0], outcomes[1]);
+    
+    // Created by the tournament PDA with no oracle, so only the organizer
+    // resolves it, through `resolve_tournament_match`
+    let arena = &mut ctx.accounts.arena;
+    arena.set_inner(Arena {
+        creator: tournament.key(),
+        title: Tournament::match_title(slot),
+        description: tournament.name.clone(),
+        question,
+        outcomes,
+        tags: vec![],
+        entry_fee: tournament.match_entry_fee,
+        pot: 0,
+        participants_count: 0,
+        outcome_counts: vec![0; 2],
+        outcome_pots: vec![0; 2],
+        resolved: false,
+        winner_outcome: None,
+        lock_time,
+        manual_resolve: false,
+        oracle: None,
+        token_mint: None,
+        creator_fee_bps: 0,
+        created_at: current_time,
+        bump: ctx.bumps.arena,
+        creator_fee_paid: false,
+        total_paid_out: 0,
+        version: Arena::VERSION,
+        paused: 0,
+        referral_fees: 0,
+        creator_trading_fee_bps: 0,
+        scalar: None,
+        resolved_value: None,
+        allowlist_root: None,
+        creator_bond: 0,
+        voided: false,
+        resolve_after: lock_time,
+        stake_weighting: StakeWeighting::Flat,
+        outcome_weights: vec![],
+        duel: None,
+        shares_outstanding: 0,
+        parlay_approved: false,
+        bond_mint: None,
+        resolved_at: 0,
+        dispute: Dispute::Undisputed,
+    });
+    tournament.arenas[index] = arena.key();
+    
+    // ========== LOGGING ==========
+    
+    msg!("Tournament match activated: {} slot {}", tournament.name, slot);
+    msg!("Arena: {}", arena.key());
+    msg!("Question: {}", arena.question);
+    msg!("Lock time: {} (Unix timestamp)", lock_time);
+    
+    emit!(ArenaCreated {
+        arena: arena.key(),
+        creator: arena.creator,
+        title: arena.title.clone(),
+        entry_fee: arena.entry_fee,
+        outcomes_count: 2,
+        creator_fee_bps: 0,
+        creator_trading_fee_bps: 0,
+        scalar: None,
+        allowlist_root: None,
+        creator_bond: 0,
+        bond_mint: None,
+        lock_time,
+        resolve_after: lock_time,
+        stake_weighting: StakeWeighting::Flat,
+        duel: None,
+    });
+    emit!(TournamentAdvanced {
+        tournament: tournament.key(),
+        arena: arena.key(),
+        slot,
+        lock_time,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct TournamentAdvanced {
+    pub tournament: Pubkey,
+    pub arena: Pubkey,
+    pub slot: u8,
+    pub lock_time: i64,
+}
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
-use crate::state::{Arena, OutcomeShare, ShareBalance};
+use betfun_math::bps_of;
+use crate::state::{pause, Arena, OutcomeShare, PriceHistory, ShareBalance, GlobalConfig, Referral, UserProfile, Versioned};
 use crate::error::BetFunError;
 
 /// Buy outcome shares with SOL
@@ -8,15 +10,26 @@ use crate::error::BetFunError;
 #[derive(Accounts)]
 pub struct BuyShares<'info> {
     #[account(
+        mut,
         seeds = [
             b"arena",
             arena.creator.as_ref(),
             arena.title.as_bytes()
         ],
-        bump = arena.bump
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::SHARES) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::SHARES) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
     #[account(
         mut,
         seeds = [
@@ -25,7 +38,8 @@ pub struct BuyShares<'info> {
             &[outcome_share.outcome_index]
         ],
         bump = outcome_share.bump,
-        constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration
+        constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = outcome_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub outcome_share: Account<'info, OutcomeShare>,
     
@@ -35,6 +49,19 @@ pub struct BuyShares<'info> {
     )]
     pub share_mint: Account<'info, Mint>,
     
+    /// Hourly candle history for this outcome
+    #[account(
+        mut,
+        seeds = [
+            PriceHistory::SEED,
+            arena.key().as_ref(),
+            &[outcome_share.outcome_index]
+        ],
+        bump = price_history.bump,
+        constraint = price_history.version == PriceHistory::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+    
     /// User's share balance account
     #[account(
         init_if_needed,
@@ -45,7 +72,9 @@ pub struct BuyShares<'info> {
             outcome_share.key().as_ref(),
             buyer.key().as_ref()
         ],
-        bump
+        bump,
+        constraint = share_balance.owner == Pubkey::default()
+            || share_balance.version == ShareBalance::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub share_balance: Account<'info, ShareBalance>,
     
@@ -62,6 +91,19 @@ pub struct BuyShares<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
     
+    /// Lifetime statistics of `buyer`, created on first use
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
     /// Arena escrow receives SOL
     /// CHECK: Arena PDA validated by seeds
     #[account(
@@ -75,6 +117,31 @@ pub struct BuyShares<'info> {
     )]
     pub arena_escrow: UncheckedAccount<'info>,
     
+    /// Arena creator, paid the arena's trading fee
+    #[account(
+        mut,
+        constraint = creator.key() == arena.creator @ BetFunError::InvalidConfiguration,
+    )]
+    /// CHECK: Arena creator
+    pub creator: AccountInfo<'info>,
+    
+    /// Wallet that referred `buyer`, earning a share of the creator fee
+    /// CHECK: Only recorded in `referral`, which must be passed with it
+    pub referrer: Option<UncheckedAccount<'info>>,
+    
+    /// Records the referral on first use and holds the referrer's rewards
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = Referral::SIZE,
+        seeds = [
+            b"referral",
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub referral: Option<Box<Account<'info, Referral>>>,
+    
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -84,23 +151,34 @@ pub struct BuyShares<'info> {
 pub fn handler(
     ctx: Context<BuyShares>,
     amount: u64, // Number of shares to buy
+    max_total_cost: Option<u64>, // Slippage bound on the total cost, creator fee included
+    deadline_ts: Option<i64>, // Latest acceptable execution time
+    proof: Vec<[u8; 32]>, // Allowlist membership proof; empty for a public arena
 ) -> Result<()> {
-    let arena = &ctx.accounts.arena;
+    let arena = &mut ctx.accounts.arena;
     let outcome_share = &mut ctx.accounts.outcome_share;
     let share_balance = &mut ctx.accounts.share_balance;
     let current_time = Clock::get()?.unix_timestamp;
     
     // ========== VALIDATION ==========
     
+    // Reject stale transactions
+    if let Some(deadline) = deadline_ts {
+        require!(
+            current_time <= deadline,
+            BetFunError::DeadlineExceeded
+        );
+    }
+    
     // Arena must not be resolved
     require!(
         !arena.resolved,
         BetFunError::AlreadyResolved
     );
     
-    // Arena must not have ended
+    // Trading closes at the lock time
     require!(
-        !arena.has_ended(current_time),
+        !arena.is_locked(current_time),
         BetFunError::ArenaEnded
     );
     
@@ -110,28 +188,105 @@ pub fn handler(
         BetFunError::InvalidConfiguration
     );
     
-    // Calculate cost in SOL
-    let price = outcome_share.current_price;
-    let cost = (amount as u128 * price as u128) / 1_000_000_000;
+    // Scalar shares are only issued in LONG/SHORT pairs by mint_complete_sets
+    require!(
+        !arena.is_scalar(),
+        BetFunError::WrongMarketType
+    );
+    
+    // Private arenas only sell to wallets on their allowlist
     require!(
-        cost <= u64::MAX as u128,
-        BetFunError::ArithmeticOverflow
+        arena.is_allowed(&ctx.accounts.buyer.key(), &proof),
+        BetFunError::NotAllowlisted
     );
-    let cost = cost as u64;
+    
+    // Calculate cost along the bonding curve
+    let quote = outcome_share.curve
+        .quote_buy(outcome_share.total_supply, amount)
+        .map_err(BetFunError::from)?;
+    let cost = quote.lamports;
+    
+    // The creator's trading fee is paid on top of the curve cost
+    let creator_fee = bps_of(cost, arena.creator_trading_fee_bps).map_err(BetFunError::from)?;
+    let total_cost = cost
+        .checked_add(creator_fee)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    // Check slippage
+    if let Some(max_total_cost) = max_total_cost {
+        require!(
+            total_cost <= max_total_cost,
+            BetFunError::SlippageToleranceExceeded
+        );
+    }
+    
+    let price = quote.average_price;
+    let new_supply = quote.supply_after;
+    let spot_price = quote.price_after;
     
     // Check buyer has sufficient balance
     let buyer_balance = ctx.accounts.buyer.lamports();
     let rent_exempt = Rent::get()?.minimum_balance(0);
     require!(
-        buyer_balance >= cost + rent_exempt,
+        buyer_balance >= total_cost + rent_exempt,
         BetFunError::InsufficientEntryFee
     );
     
+    // ========== REFERRAL ==========
+    
+    // The referrer's share comes out of the creator fee
+    let referral_reward = match (ctx.accounts.referral.as_mut(), ctx.accounts.referrer.as_ref()) {
+        (Some(referral), Some(referrer)) => {
+            let bump = ctx.bumps.referral.ok_or(BetFunError::InvalidReferrer)?;
+            referral.record(ctx.accounts.buyer.key(), referrer.key(), bump, current_time)?;
+            bps_of(creator_fee, ctx.accounts.config.referral_share_bps).map_err(BetFunError::from)?
+        }
+        (None, None) => 0,
+        _ => return err!(BetFunError::InvalidReferrer),
+    };
+    let creator_payment = creator_fee
+        .checked_sub(referral_reward)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
     // ========== TRANSFER SOL TO ESCROW ==========
     
     // Transfer SOL from buyer to arena escrow
-    **ctx.accounts.buyer.to_account_info().try_borrow_mut_lamports()? -= cost;
-    **ctx.accounts.arena_escrow.to_account_info().try_borrow_mut_lamports()? += cost;
+    let transfer_ix = system_program::Transfer {
+        from: ctx.accounts.buyer.to_account_info(),
+        to: ctx.accounts.arena_escrow.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        transfer_ix,
+    );
+    system_program::transfer(cpi_ctx, cost)?;
+    
+    // ========== PAY CREATOR FEE ==========
+    
+    if creator_payment > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.creator.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, creator_payment)?;
+    }
+    
+    if let Some(referral) = ctx.accounts.referral.as_mut() {
+        if referral_reward > 0 {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: referral.to_account_info(),
+                },
+            );
+            system_program::transfer(cpi_ctx, referral_reward)?;
+            referral.accrue(referral_reward)?;
+        }
+    }
     
     // ========== MINT SHARE TOKENS ==========
     
@@ -159,12 +314,14 @@ pub fn handler(
     // ========== UPDATE STATISTICS ==========
     
     // Update outcome share stats
-    outcome_share.total_supply = outcome_share.total_supply
+    outcome_share.total_supply = new_supply;
+    arena.shares_outstanding = arena.shares_outstanding
         .checked_add(amount)
         .ok_or(BetFunError::ArithmeticOverflow)?;
     
-    outcome_share.update_price_stats(price, current_time);
+    outcome_share.update_price_stats(spot_price, current_time);
     outcome_share.add_volume(cost);
+    ctx.accounts.price_history.record_trade(price, cost, current_time);
     
     // Initialize share balance if needed
     if share_balance.owner == Pubkey::default() {
@@ -175,11 +332,16 @@ pub fn handler(
         share_balance.total_invested = 0;
         share_balance.realized_pnl = 0;
         share_balance.bump = ctx.bumps.share_balance;
+        share_balance.version = ShareBalance::VERSION;
     }
     
     // Update user's share balance
     share_balance.buy_shares(amount, price);
     
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(ctx.accounts.buyer.key(), ctx.bumps.user_profile, current_time)?;
+    user_profile.record_stake(cost)?;
+    
     // ========== LOGGING ==========
     
     msg!("Shares purchased successfully");
@@ -189,8 +351,10 @@ pub fn handler(
         arena.outcomes[outcome_share.outcome_index as usize]
     );
     msg!("Amount: {} shares", amount);
-    msg!("Price: {} lamports ({:.4} SOL) per share", price, price as f64 / 1e9);
+    msg!("Average price: {} lamports ({:.4} SOL) per share", price, price as f64 / 1e9);
     msg!("Total cost: {} lamports ({:.4} SOL)", cost, cost as f64 / 1e9);
+    msg!("Creator fee: {} lamports", creator_fee);
+    msg!("Spot price: {} lamports ({:.4} SOL) per share", spot_price, spot_price as f64 / 1e9);
     msg!("New balance: {} shares", share_balance.balance);
     msg!("Total supply: {} shares", outcome_share.total_supply);
     
@@ -203,6 +367,7 @@ pub fn handler(
         amount,
         price,
         total_cost: cost,
+        creator_fee,
         new_balance: share_balance.balance,
     });
     
@@ -217,6 +382,7 @@ pub struct SharesPurchased {
     pub amount: u64,
     pub price: u64,
     pub total_cost: u64,
+    pub creator_fee: u64,
     pub new_balance: u64,
 }
 
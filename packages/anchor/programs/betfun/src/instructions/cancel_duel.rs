@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, Participant, Versioned};
+use crate::error::BetFunError;
+use crate::instructions::{claim_winnings, void_arena};
+
+/// Withdraw a duel before anyone accepts it
+/// The arena is voided and the challenger gets their stake and bond straight
+/// back, as `void_arena` and `claim_winnings` would once the duel locked.
+#[derive(Accounts)]
+pub struct CancelDuel<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = !arena.voided @ BetFunError::ArenaVoided,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = arena.creator == creator.key() @ BetFunError::Unauthorized,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"participant",
+            arena.key().as_ref(),
+            creator.key().as_ref()
+        ],
+        bump = participant.bump,
+        constraint = !participant.claimed @ BetFunError::AlreadyClaimed,
+        constraint = participant.version == Participant::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub participant: Account<'info, Participant>,
+    
+    #[account(mut)]
+    pub creator: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelDuel>) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    let participant = &mut ctx.accounts.participant;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        arena.duel.is_some(),
+        BetFunError::InvalidConfiguration
+    );
+    require!(
+        arena.is_unaccepted_duel(),
+        BetFunError::DuelAlreadyAccepted
+    );
+    
+    // ========== VOID AND REFUND ==========
+    
+    // Betting and trading stop at the lock, so the cancelled duel locks now
+    arena.lock_time = arena.lock_time.min(Clock::get()?.unix_timestamp);
+    void_arena::void(arena, ctx.accounts.creator.key())?;
+    let refund = claim_winnings::refund(arena, participant)?;
+    claim_winnings::credit(&ctx.accounts.creator.to_account_info(), refund)?;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Duel cancelled: {}", arena.key());
+    
+    emit!(DuelCancelled {
+        arena: arena.key(),
+        challenger: arena.creator,
+        refund,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct DuelCancelled {
+    pub arena: Pubkey,
+    pub challenger: Pubkey,
+    pub refund: u64,
+}
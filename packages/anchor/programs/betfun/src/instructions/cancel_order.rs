@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{Arena, OrderBook, LimitOrder, OrderSide, OrderStatus};
+use crate::state::{Arena, OrderBook, LimitOrder, OrderSide, OrderStatus, Versioned};
 use crate::error::BetFunError;
 
 /// Cancel a limit order
@@ -13,6 +13,7 @@ pub struct CancelOrder<'info> {
             arena.title.as_bytes()
         ],
         bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub arena: Account<'info, Arena>,
 
@@ -25,6 +26,7 @@ pub struct CancelOrder<'info> {
         ],
         bump = order_book.bump,
         constraint = order_book.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = order_book.version == OrderBook::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub order_book: Account<'info, OrderBook>,
 
@@ -40,6 +42,7 @@ pub struct CancelOrder<'info> {
         constraint = limit_order.arena == arena.key() @ BetFunError::InvalidConfiguration,
         constraint = limit_order.owner == owner.key() @ BetFunError::Unauthorized,
         constraint = limit_order.is_active() @ BetFunError::InvalidConfiguration,
+        constraint = limit_order.version == LimitOrder::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub limit_order: Account<'info, LimitOrder>,
 
@@ -77,10 +80,10 @@ pub fn handler(ctx: Context<CancelOrder>) -> Result<()> {
     // Calculate refund amount (remaining unfilled size)
     let refund_amount = match limit_order.side {
         OrderSide::Buy => {
-            // Refund SOL (price * remaining_size)
-            limit_order.price
-                .checked_mul(limit_order.remaining_size)
-                .ok_or(BetFunError::ArithmeticOverflow)?
+            // Refund the whole SOL escrow: price * remaining_size plus any price
+            // improvement left by fills below the limit, which would otherwise
+            // strand a balance under rent exemption and fail the transfer
+            ctx.accounts.order_escrow.lamports()
         }
         OrderSide::Sell => {
             // Refund tokens (remaining_size)
@@ -93,13 +96,25 @@ pub fn handler(ctx: Context<CancelOrder>) -> Result<()> {
         match limit_order.side {
             OrderSide::Buy => {
                 // Return SOL from escrow to owner
-                **ctx.accounts.order_escrow.try_borrow_mut_lamports()? = ctx.accounts.order_escrow.lamports()
-                    .checked_sub(refund_amount)
-                    .ok_or(BetFunError::ArithmeticOverflow)?;
+                // The escrow is a system account, so this goes through a signed transfer
+                let limit_order_key = limit_order.key();
+                let escrow_seeds = &[
+                    b"order_escrow",
+                    limit_order_key.as_ref(),
+                    &[ctx.bumps.order_escrow],
+                ];
 
-                **owner.to_account_info().try_borrow_mut_lamports()? = owner.lamports()
-                    .checked_add(refund_amount)
-                    .ok_or(BetFunError::ArithmeticOverflow)?;
+                anchor_lang::system_program::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.order_escrow.to_account_info(),
+                            to: owner.to_account_info(),
+                        },
+                        &[&escrow_seeds[..]],
+                    ),
+                    refund_amount,
+                )?;
             }
             OrderSide::Sell => {
                 // Return tokens from escrow to owner
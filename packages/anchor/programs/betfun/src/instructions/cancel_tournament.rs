@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, Tournament, Versioned};
+use crate::error::BetFunError;
+
+/// Cancel a tournament whose organizer let a match be voided before deciding it (anyone)
+/// Every entry then claims its entry fee back through `claim_tournament_prize`.
+#[derive(Accounts)]
+#[instruction(slot: u8)]
+pub struct CancelTournament<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tournament",
+            tournament.organizer.as_ref(),
+            tournament.name.as_bytes()
+        ],
+        bump = tournament.bump,
+        constraint = tournament.version == Tournament::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !tournament.cancelled @ BetFunError::TournamentCancelled,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    #[account(
+        constraint = tournament.arenas.get(slot as usize) == Some(&arena.key()) @ BetFunError::BracketNotReady,
+    )]
+    pub arena: Box<Account<'info, Arena>>,
+    
+    pub caller: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<CancelTournament>, slot: u8) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let arena = &ctx.accounts.arena;
+    
+    // ========== VALIDATION ==========
+    
+    // Matches the organizer voided themselves still have a result
+    require!(
+        arena.voided && tournament.results[slot as usize] == 0,
+        BetFunError::ResolutionWindowOpen
+    );
+    
+    // ========== CANCEL ==========
+    
+    tournament.cancelled = true;
+    
+    msg!("Tournament cancelled: {}", tournament.name);
+    msg!("Voided match: {} (slot {})", arena.key(), slot);
+    msg!("Refundable pot: {} lamports from {} entries", tournament.pot, tournament.entries_count);
+    
+    emit!(TournamentCancelled {
+        tournament: tournament.key(),
+        arena: arena.key(),
+        slot,
+        caller: ctx.accounts.caller.key(),
+        pot: tournament.pot,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct TournamentCancelled {
+    pub tournament: Pubkey,
+    pub arena: Pubkey,
+    pub slot: u8,
+    pub caller: Pubkey,
+    pub pot: u64,
+}
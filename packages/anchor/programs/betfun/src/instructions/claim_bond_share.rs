@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::state::{Arena, GlobalConfig, Participant, Versioned};
+use crate::error::BetFunError;
+use crate::instructions::release_creator_bond::{self, BondTokens};
+
+/// Claim a bettor's part of a creator bond slashed by an upheld dispute, or
+/// slashed in a token by a void
+/// A voided arena's bond in lamports is paid out with the refunds of
+/// `claim_winnings`; every other slashed bond is split over the bettors by
+/// stake here, winners and losers alike. With no bets to split it over, the
+/// protocol fee recipient claims it whole, passing no participant.
+#[derive(Accounts)]
+pub struct ClaimBondShare<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.has_bond_claims() @ BetFunError::NotResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    /// The claimant's bet (omitted by the fee recipient when nobody bet)
+    #[account(
+        mut,
+        seeds = [
+            b"participant",
+            arena.key().as_ref(),
+            claimant.key().as_ref()
+        ],
+        bump = participant.bump,
+        constraint = !participant.bond_claimed @ BetFunError::AlreadyClaimed,
+        constraint = participant.version == Participant::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub participant: Option<Account<'info, Participant>>,
+    
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+    
+    /// Holds the slashed bond (token bonds only)
+    #[account(
+        mut,
+        seeds = [
+            b"bond_vault",
+            arena.key().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// Claimant's token account for the bond's mint (token bonds only)
+    #[account(mut)]
+    pub claimant_bond_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+pub fn handler(ctx: Context<ClaimBondShare>) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    let claimant = ctx.accounts.claimant.to_account_info();
+    
+    // ========== VALIDATION ==========
+    
+    let amount = match ctx.accounts.participant.as_mut() {
+        Some(participant) => {
+            let share = arena.bond_share(participant.amount)?;
+            participant.bond_claimed = true;
+            share
+        }
+        None => {
+            require!(arena.pot == 0, BetFunError::NotParticipant);
+            require_keys_eq!(
+                claimant.key(),
+                ctx.accounts.config.protocol_fee_recipient(),
+                BetFunError::Unauthorized
+            );
+            arena.creator_bond
+        }
+    };
+    
+    // ========== PAYOUT ==========
+    
+    let tokens = BondTokens::from_accounts(
+        ctx.accounts.bond_vault.as_ref(),
+        ctx.accounts.claimant_bond_account.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    );
+    release_creator_bond::pay_bond(arena, amount, &claimant, tokens)?;
+    if ctx.accounts.participant.is_none() {
+        arena.creator_bond = 0;
+    } else if arena.bond_mint.is_none() {
+        // Bond lamports count towards the arena's payouts until the bond is gone
+        arena.total_paid_out = arena.total_paid_out
+            .checked_add(amount)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+    }
+    
+    // ========== LOGGING ==========
+    
+    msg!("Bond share claimed: {}", claimant.key());
+    match arena.bond_mint {
+        Some(mint) => msg!("Share: {} base units of {}", amount, mint),
+        None => msg!("Share: {} lamports ({:.4} SOL)", amount, amount as f64 / 1e9),
+    }
+    
+    emit!(BondShareClaimed {
+        arena: arena.key(),
+        claimant: claimant.key(),
+        stake: ctx.accounts.participant.as_ref().map_or(0, |participant| participant.amount),
+        amount,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct BondShareClaimed {
+    pub arena: Pubkey,
+    pub claimant: Pubkey,
+    pub stake: u64,
+    pub amount: u64,
+}
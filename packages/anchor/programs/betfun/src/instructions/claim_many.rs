@@ -0,0 +1,288 @@
+use anchor_lang::prelude::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
+use crate::state::{pause, Arena, OutcomeShare, Participant, GlobalConfig, ShareBalance, UserProfile, Versioned};
+use crate::error::BetFunError;
+use crate::instructions::{claim_winnings, SharesRedeemed};
+
+/// Claim winnings, refunds and winning shares across many arenas in one transaction
+/// Remaining accounts are entries of variable length, each starting with an
+/// arena and a position whose discriminator tells the two kinds apart. A bet
+/// takes 3 accounts, `(arena, participant, creator)`, and is paid like
+/// `claim_winnings`. A share position takes 5 accounts, `(arena,
+/// share_balance, outcome_share, share_mint, token_account)`, and its whole
+/// balance is redeemed like `redeem_shares`. Voided arenas refund both kinds.
+/// Entries in unresolved arenas, losing positions, bets already claimed and
+/// positions with no shares left are skipped, so a wallet can list everything
+/// it holds. Every payout reaches the user as a single credit.
+#[derive(Accounts)]
+pub struct ClaimMany<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    /// Lifetime statistics of `user`, created here for positions older than profiles
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimMany<'info>>) -> Result<()> {
+    let user = ctx.accounts.user.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(user, ctx.bumps.user_profile, Clock::get()?.unix_timestamp)?;
+    
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== SETTLE ENTRIES ==========
+    
+    // Each entry reloads and writes back its accounts, so an arena listed
+    // twice always sees what the earlier entry left
+    let mut accounts = ctx.remaining_accounts.iter();
+    let mut total: u64 = 0;
+    let mut claimed: u16 = 0;
+    let mut skipped: u16 = 0;
+    while let Some(arena_info) = accounts.next() {
+        let position = accounts.next().ok_or(BetFunError::InvalidConfiguration)?;
+        let mut arena = Account::<Arena>::try_from(arena_info)?;
+        require!(
+            arena.version == Arena::VERSION,
+            BetFunError::UnsupportedAccountVersion
+        );
+        // Refunds out of a voided arena are never paused
+        require!(
+            arena.voided || !(arena.is_paused(pause::CLAIMS) || ctx.accounts.config.is_paused(pause::CLAIMS)),
+            BetFunError::Paused
+        );
+    
+        let discriminator: [u8; 8] = position.try_borrow_data()?
+            .get(..8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ErrorCode::AccountDiscriminatorNotFound)?;
+        let paid = if discriminator == Participant::DISCRIMINATOR {
+            let creator = accounts.next().ok_or(BetFunError::InvalidConfiguration)?;
+            claim_bet(&mut arena, position, creator, user, user_profile)?
+        } else if discriminator == ShareBalance::DISCRIMINATOR {
+            let shares = ShareAccounts {
+                share_balance: position,
+                outcome_share: accounts.next().ok_or(BetFunError::InvalidConfiguration)?,
+                share_mint: accounts.next().ok_or(BetFunError::InvalidConfiguration)?,
+                token_account: accounts.next().ok_or(BetFunError::InvalidConfiguration)?,
+            };
+            let token_program = ctx.accounts.token_program.to_account_info();
+            let authority = ctx.accounts.user.to_account_info();
+            redeem_all(&mut arena, shares, token_program, authority, user_profile)?
+        } else {
+            return err!(ErrorCode::AccountDiscriminatorMismatch);
+        };
+        arena.exit(&crate::ID)?;
+    
+        match paid {
+            Some(amount) => {
+                total = total
+                    .checked_add(amount)
+                    .ok_or(BetFunError::ArithmeticOverflow)?;
+                claimed += 1;
+            }
+            None => skipped += 1,
+        }
+    }
+    
+    // ========== TRANSFER ==========
+    
+    claim_winnings::credit(&ctx.accounts.user.to_account_info(), total)?;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Batch claimed");
+    msg!("User: {}", user);
+    msg!("Entries: {} claimed, {} skipped", claimed, skipped);
+    msg!("Total: {} lamports ({:.4} SOL)", total, total as f64 / 1e9);
+    
+    emit!(BatchClaimed {
+        user,
+        claimed,
+        skipped,
+        total,
+    });
+    
+    Ok(())
+}
+
+/// Winnings or refund of one bet, or `None` if it has nothing to claim
+fn claim_bet<'info>(
+    arena: &mut Account<'info, Arena>,
+    position: &'info AccountInfo<'info>,
+    creator: &'info AccountInfo<'info>,
+    user: Pubkey,
+    user_profile: &mut UserProfile,
+) -> Result<Option<u64>> {
+    let mut participant = Account::<Participant>::try_from(position)?;
+    require!(
+        participant.version == Participant::VERSION,
+        BetFunError::UnsupportedAccountVersion
+    );
+    require_keys_eq!(participant.arena, arena.key(), BetFunError::InvalidConfiguration);
+    require_keys_eq!(participant.wallet, user, BetFunError::NotParticipant);
+    if !(arena.resolved || arena.voided) {
+        msg!("Skipped bet in unresolved arena {}", arena.key());
+        return Ok(None);
+    }
+    if participant.claimed {
+        msg!("Skipped claimed bet in arena {}", arena.key());
+        return Ok(None);
+    }
+    if !arena.voided && arena.winner_outcome != Some(participant.outcome_chosen) {
+        msg!("Skipped losing bet in arena {}", arena.key());
+        return Ok(None);
+    }
+    
+    let amount = if arena.voided {
+        let refund = claim_winnings::refund(arena, &mut participant)?;
+        user_profile.record_pnl(refund as i64 - participant.amount as i64);
+        refund
+    } else {
+        let payout = claim_winnings::pay_out(arena, &mut participant, creator)?;
+        user_profile.record_win(participant.amount, payout)?;
+        payout
+    };
+    participant.exit(&crate::ID)?;
+    
+    Ok(Some(amount))
+}
+
+/// Accounts of a share position after its arena
+struct ShareAccounts<'info> {
+    share_balance: &'info AccountInfo<'info>,
+    outcome_share: &'info AccountInfo<'info>,
+    share_mint: &'info AccountInfo<'info>,
+    token_account: &'info AccountInfo<'info>,
+}
+
+/// Value or refund of a position's whole share balance, burned here, or
+/// `None` if it has nothing to redeem
+fn redeem_all<'info>(
+    arena: &mut Account<'info, Arena>,
+    shares: ShareAccounts<'info>,
+    token_program: AccountInfo<'info>,
+    authority: AccountInfo<'info>,
+    user_profile: &mut UserProfile,
+) -> Result<Option<u64>> {
+    let mut share_balance = Account::<ShareBalance>::try_from(shares.share_balance)?;
+    let mut outcome_share = Account::<OutcomeShare>::try_from(shares.outcome_share)?;
+    let share_mint = Account::<Mint>::try_from(shares.share_mint)?;
+    let token_account = Account::<TokenAccount>::try_from(shares.token_account)?;
+    require!(
+        share_balance.version == ShareBalance::VERSION && outcome_share.version == OutcomeShare::VERSION,
+        BetFunError::UnsupportedAccountVersion
+    );
+    require_keys_eq!(share_balance.owner, authority.key(), BetFunError::NotParticipant);
+    require_keys_eq!(share_balance.outcome_share, outcome_share.key(), BetFunError::InvalidConfiguration);
+    require_keys_eq!(outcome_share.arena, arena.key(), BetFunError::InvalidConfiguration);
+    require_keys_eq!(share_mint.key(), outcome_share.token_mint, BetFunError::InvalidConfiguration);
+    require_keys_eq!(token_account.mint, share_mint.key(), BetFunError::InvalidConfiguration);
+    require_keys_eq!(token_account.owner, authority.key(), BetFunError::InvalidConfiguration);
+    if !(arena.resolved || arena.voided) {
+        msg!("Skipped shares in unresolved arena {}", arena.key());
+        return Ok(None);
+    }
+    // Both sides of a scalar pair pay out, and a voided arena refunds every outcome
+    if !arena.voided && !arena.is_scalar() && arena.winner_outcome != Some(outcome_share.outcome_index) {
+        msg!("Skipped losing shares in arena {}", arena.key());
+        return Ok(None);
+    }
+    
+    // Shares moved out of the token account stay on the balance
+    let amount = share_balance.balance.min(token_account.amount);
+    if amount == 0 {
+        msg!("Skipped empty share position in arena {}", arena.key());
+        return Ok(None);
+    }
+    
+    let arena_info = arena.to_account_info();
+    let rent_exempt_min = Rent::get()?.minimum_balance(arena_info.data_len());
+    let reserve = arena.share_reserve(arena_info.lamports(), rent_exempt_min);
+    let share_value = arena.redemption_value(&outcome_share, amount, reserve)?;
+    require!(
+        reserve >= share_value,
+        BetFunError::InsufficientEntryFee
+    );
+    // Share holders of a voided arena nobody bet in split the slashed bond
+    let bond_share = arena.void_bond_share(amount)?;
+    let redemption_value = share_value
+        .checked_add(bond_share)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    let cpi_accounts = Burn {
+        mint: share_mint.to_account_info(),
+        from: token_account.to_account_info(),
+        authority,
+    };
+    token::burn(CpiContext::new(token_program, cpi_accounts), amount)?;
+    
+    **arena_info.try_borrow_mut_lamports()? = arena_info.lamports()
+        .checked_sub(redemption_value)
+        .ok_or(BetFunError::InsufficientFunds)?;
+    
+    outcome_share.total_supply = outcome_share.total_supply
+        .checked_sub(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    arena.shares_outstanding = arena.shares_outstanding.saturating_sub(amount);
+    arena.creator_bond = arena.creator_bond
+        .checked_sub(bond_share)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    share_balance.balance = share_balance.balance
+        .checked_sub(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    let cost = (amount as u128 * share_balance.avg_cost_basis as u128) / 1_000_000_000;
+    let final_pnl = redemption_value as i64 - cost as i64;
+    share_balance.realized_pnl = share_balance.realized_pnl.saturating_add(final_pnl);
+    user_profile.record_payout(redemption_value)?;
+    user_profile.record_pnl(final_pnl);
+    
+    msg!("Redeemed {} shares of outcome {} for {} lamports", amount, outcome_share.outcome_index, redemption_value);
+    
+    emit!(SharesRedeemed {
+        arena: arena.key(),
+        redeemer: share_balance.owner,
+        outcome_index: outcome_share.outcome_index,
+        amount,
+        redemption_value,
+        final_pnl,
+        total_realized_pnl: share_balance.realized_pnl,
+    });
+    
+    outcome_share.exit(&crate::ID)?;
+    share_balance.exit(&crate::ID)?;
+    
+    Ok(Some(redemption_value))
+}
+
+#[event]
+pub struct BatchClaimed {
+    pub user: Pubkey,
+    pub claimed: u16,
+    pub skipped: u16,
+    pub total: u64,
+}
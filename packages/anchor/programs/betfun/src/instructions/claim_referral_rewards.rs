@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::state::{Referral, Versioned};
+use crate::error::BetFunError;
+
+/// Withdraw the fees a referral has accrued to its referrer
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"referral",
+            referral.user.as_ref()
+        ],
+        bump = referral.bump,
+        constraint = referral.referrer == referrer.key() @ BetFunError::Unauthorized,
+        constraint = referral.version == Referral::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub referral: Account<'info, Referral>,
+    
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    let referral = &mut ctx.accounts.referral;
+    let amount = referral.pending_rewards;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        amount > 0,
+        BetFunError::NoReferralRewards
+    );
+    
+    // Rewards sit on top of the account's rent-exempt balance
+    let referral_info = referral.to_account_info();
+    let rent_exempt_min = Rent::get()?.minimum_balance(referral_info.data_len());
+    require!(
+        referral_info.lamports() >= rent_exempt_min + amount,
+        BetFunError::InsufficientFunds
+    );
+    
+    // ========== TRANSFER REWARDS ==========
+    
+    **referral_info.try_borrow_mut_lamports()? = referral_info.lamports()
+        .checked_sub(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    **ctx.accounts.referrer.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.referrer.lamports()
+        .checked_add(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    referral.pending_rewards = 0;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Referral rewards claimed");
+    msg!("Referrer: {}", referral.referrer);
+    msg!("Referred user: {}", referral.user);
+    msg!("Amount: {} lamports ({:.4} SOL)", amount, amount as f64 / 1e9);
+    
+    emit!(ReferralRewardsClaimed {
+        referral: referral.key(),
+        referrer: referral.referrer,
+        user: referral.user,
+        amount,
+        total_earned: referral.total_earned,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referral: Pubkey,
+    pub referrer: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_earned: u64,
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use crate::state::{pause, GlobalConfig, Tournament, TournamentEntry, Versioned};
+use crate::error::BetFunError;
+
+/// Claim an equal share of the pot for the best score once scoring closes,
+/// or the entry fee back from a cancelled tournament nobody could win
+#[derive(Accounts)]
+pub struct ClaimTournamentPrize<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tournament",
+            tournament.organizer.as_ref(),
+            tournament.name.as_bytes()
+        ],
+        bump = tournament.bump,
+        constraint = tournament.version == Tournament::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::CLAIMS) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"tournament_entry",
+            tournament.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump = entry.bump,
+        constraint = !entry.claimed @ BetFunError::AlreadyClaimed,
+        constraint = entry.version == TournamentEntry::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub entry: Account<'info, TournamentEntry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<ClaimTournamentPrize>) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let entry = &mut ctx.accounts.entry;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== CALCULATE AMOUNT ==========
+    
+    // Entries are refunded when a match was voided, or when nobody submitted a score
+    let refund = tournament.cancelled || tournament.best_count == 0;
+    if !tournament.cancelled {
+        require!(
+            tournament.scoring_ends_at != 0 && current_time >= tournament.scoring_ends_at,
+            BetFunError::ScoringOpen
+        );
+    }
+    let amount = if refund {
+        tournament.entry_fee
+    } else {
+        require!(
+            entry.submitted && entry.score == tournament.best_score,
+            BetFunError::NotWinner
+        );
+        tournament.pot / tournament.best_count as u64
+    };
+    
+    // ========== TRANSFER ==========
+    
+    let tournament_info = tournament.to_account_info();
+    **tournament_info.try_borrow_mut_lamports()? = tournament_info.lamports()
+        .checked_sub(amount)
+        .ok_or(BetFunError::InsufficientFunds)?;
+    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.user.lamports()
+        .checked_add(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    entry.claimed = true;
+    tournament.total_paid_out = tournament.total_paid_out
+        .checked_add(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Tournament {} claimed: {}", if refund { "refund" } else { "prize" }, tournament.name);
+    msg!("User: {}", entry.wallet);
+    msg!("Amount: {} lamports ({:.4} SOL)", amount, amount as f64 / 1e9);
+    
+    emit!(TournamentPrizeClaimed {
+        tournament: tournament.key(),
+        wallet: entry.wallet,
+        amount,
+        refund,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct TournamentPrizeClaimed {
+    pub tournament: Pubkey,
+    pub wallet: Pubkey,
+    pub amount: u64,
+    pub refund: bool,
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
-use crate::state::{Arena, Participant};
+use crate::state::{pause, Arena, Participant, GlobalConfig, UserProfile, Versioned};
 use crate::error::BetFunError;
+use crate::instructions::release_creator_bond;
 
+/// Claim winnings from a resolved arena, or a refund from a voided one
 #[derive(Accounts)]
 pub struct ClaimWinnings<'info> {
     #[account(
@@ -12,10 +14,21 @@ pub struct ClaimWinnings<'info> {
             arena.title.as_bytes()
         ],
         bump = arena.bump,
-        constraint = arena.resolved @ BetFunError::NotResolved
+        constraint = arena.resolved || arena.voided @ BetFunError::NotResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        // Refunds out of a voided arena are never paused
+        constraint = arena.voided || !arena.is_paused(pause::CLAIMS) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = arena.voided || !config.is_paused(pause::CLAIMS) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
     #[account(
         mut,
         seeds = [
@@ -25,14 +38,28 @@ pub struct ClaimWinnings<'info> {
         ],
         bump = participant.bump,
         constraint = !participant.claimed @ BetFunError::AlreadyClaimed,
-        constraint = participant.wallet == user.key() @ BetFunError::NotParticipant
+        constraint = participant.wallet == user.key() @ BetFunError::NotParticipant,
+        constraint = participant.version == Participant::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub participant: Account<'info, Participant>,
     
     #[account(mut)]
     pub user: Signer<'info>,
     
-    /// Creator receives fees (only transferred once, on first claim)
+    /// Lifetime statistics of `user`, created here for bets older than profiles
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
+    /// Creator receives fees and the bond back (only transferred once, on first claim)
     #[account(
         mut,
         constraint = creator.key() == arena.creator @ BetFunError::InvalidConfiguration
@@ -44,9 +71,72 @@ pub struct ClaimWinnings<'info> {
 }
 
 pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
-    let arena = &ctx.accounts.arena;
+    let arena = &mut ctx.accounts.arena;
     let participant = &mut ctx.accounts.participant;
+    let user = ctx.accounts.user.to_account_info();
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(participant.wallet, ctx.bumps.user_profile, Clock::get()?.unix_timestamp)?;
+    
+    // ========== VOIDED ARENA ==========
+    
+    // Every bettor gets their stake back, topped up by the slashed creator bond
+    if arena.voided {
+        let refund = refund(arena, participant)?;
+        credit(&user, refund)?;
+        user_profile.record_pnl(refund as i64 - participant.amount as i64);
+        return Ok(());
+    }
+    
+    // ========== RESOLVED ARENA ==========
+    
+    let payout = pay_out(arena, participant, &ctx.accounts.creator.to_account_info())?;
+    credit(&user, payout)?;
+    user_profile.record_win(participant.amount, payout)?;
+    
+    Ok(())
+}
+
+/// Add `lamports` taken out of arenas to the claimant's balance
+pub(crate) fn credit(user: &AccountInfo, lamports: u64) -> Result<()> {
+    **user.try_borrow_mut_lamports()? = user.lamports()
+        .checked_add(lamports)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    Ok(())
+}
+
+/// Take a participant's refund out of a voided arena and mark it claimed
+/// Returns the refund, which the caller credits to the participant.
+pub(crate) fn refund(arena: &mut Account<Arena>, participant: &mut Participant) -> Result<u64> {
+    let refund = arena.void_refund(participant.amount)?;
+    
+    let arena_info = arena.to_account_info();
+    **arena_info.try_borrow_mut_lamports()? = arena_info.lamports()
+        .checked_sub(refund)
+        .ok_or(BetFunError::InsufficientFunds)?;
+    
+    participant.claimed = true;
+    arena.total_paid_out = arena.total_paid_out
+        .checked_add(refund)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
     
+    msg!("Refund claimed from voided arena");
+    msg!("User: {}", participant.wallet);
+    msg!("Stake: {} lamports, refund: {} lamports", participant.amount, refund);
+    
+    emit!(RefundClaimed {
+        arena: arena.key(),
+        participant: participant.wallet,
+        stake: participant.amount,
+        refund,
+    });
+    
+    Ok(refund)
+}
+
+/// Take a winner's payout out of a resolved arena, paying the creator their
+/// fee and bond on the first claim, and mark it claimed
+/// Returns the payout, which the caller credits to the participant.
+pub(crate) fn pay_out(arena: &mut Account<Arena>, participant: &mut Participant, creator: &AccountInfo) -> Result<u64> {
     // ========== VALIDATION ==========
     
     // Check if user won
@@ -63,18 +153,16 @@ pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
         !participant.claimed,
         BetFunError::AlreadyClaimed
     );
+    require_keys_eq!(creator.key(), arena.creator, BetFunError::InvalidConfiguration);
     
     // ========== CALCULATE PAYOUT ==========
     
-    let payout = arena.calculate_payout(participant.amount)?;
-    let creator_fee = arena.calculate_creator_fee();
+    let payout = arena.calculate_payout(arena.participant_weight(participant)?)?;
+    // Referrers were already paid their share of the fee as bets came in
+    let creator_fee = arena.creator_fee_due();
     
-    // Verify arena has sufficient funds for both payout and creator fee
+    // Verify arena has sufficient funds for the payout
     let arena_lamports = arena.to_account_info().lamports();
-    let _total_needed = payout
-        .checked_add(creator_fee)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
-    
     require!(
         arena_lamports >= payout,
         BetFunError::InsufficientEntryFee
@@ -83,11 +171,8 @@ pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
     // ========== TRANSFER CREATOR FEE FIRST (ONCE) ==========
     
     // Creator fee is transferred only once, when the first winner claims
-    // We check if there's enough for the fee and if it hasn't been paid yet
-    // (simplified: if remaining >= fee, assume not paid yet)
-    // In production, add a flag to arena account to track fee payment
     
-    let should_pay_creator_fee = arena_lamports >= creator_fee && creator_fee > 0;
+    let should_pay_creator_fee = !arena.creator_fee_paid && creator_fee > 0;
     let remaining_after_fee = if should_pay_creator_fee {
         arena_lamports
             .checked_sub(creator_fee)
@@ -102,15 +187,10 @@ pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
         BetFunError::InsufficientEntryFee
     );
     
-    // Calculate final arena balance
-    let _final_arena_balance = remaining_after_fee
-        .checked_sub(payout)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
-    
     // ========== PERFORM ALL TRANSFERS ==========
     
     // Get current lamports (will be updated after each transfer)
-    let mut current_arena_lamports = arena.to_account_info().lamports();
+    let mut current_arena_lamports = arena_lamports;
     
     // Transfer creator fee (if needed)
     if should_pay_creator_fee {
@@ -120,7 +200,11 @@ pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
         
         **arena.to_account_info().try_borrow_mut_lamports()? = current_arena_lamports;
         
-        **ctx.accounts.creator.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.creator.lamports()
+        **creator.try_borrow_mut_lamports()? = creator.lamports()
+            .checked_add(creator_fee)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        arena.creator_fee_paid = true;
+        arena.total_paid_out = arena.total_paid_out
             .checked_add(creator_fee)
             .ok_or(BetFunError::ArithmeticOverflow)?;
         
@@ -130,20 +214,26 @@ pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
         );
     }
     
-    // Transfer payout to winner
+    // Take the payout out of the arena
     current_arena_lamports = current_arena_lamports
         .checked_sub(payout)
         .ok_or(BetFunError::ArithmeticOverflow)?;
     
     **arena.to_account_info().try_borrow_mut_lamports()? = current_arena_lamports;
     
-    **ctx.accounts.user.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.user.lamports()
-        .checked_add(payout)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    // Resolving cleanly earns the creator their bond back once the dispute
+    // window closes; a token bond needs token accounts, so it is returned
+    // with `release_creator_bond`
+    if arena.bond_mint.is_none() && arena.can_release_bond(Clock::get()?.unix_timestamp) {
+        release_creator_bond::release(arena, creator, None)?;
+    }
     
     // ========== MARK AS CLAIMED ==========
     
     participant.claimed = true;
+    arena.total_paid_out = arena.total_paid_out
+        .checked_add(payout)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
     
     // ========== CALCULATE PROFIT ==========
     
@@ -183,7 +273,7 @@ pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
         roi_percentage,
     });
     
-    Ok(())
+    Ok(payout)
 }
 
 #[event]
@@ -195,3 +285,11 @@ pub struct WinningsClaimed {
     pub profit: u64,
     pub roi_percentage: f64,
 }
+
+#[event]
+pub struct RefundClaimed {
+    pub arena: Pubkey,
+    pub participant: Pubkey,
+    pub stake: u64,
+    pub refund: u64,
+}
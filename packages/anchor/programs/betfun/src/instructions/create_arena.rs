@@ -1,29 +1,109 @@
 use anchor_lang::prelude::*;
-use crate::state::Arena;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use crate::state::{pause, Arena, Duel, GlobalConfig, Participant, ScalarRange, StakeWeighting, UserProfile, Versioned};
 use crate::error::BetFunError;
+use crate::instructions::join_arena::{self, ArenaJoined};
 
 #[derive(Accounts)]
-#[instruction(title: String)]
+#[instruction(
+    title: String,
+    description: String,
+    question: String,
+    outcomes: Vec<String>,
+    tags: Vec<String>,
+)]
 pub struct CreateArena<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + 32 + 4 + 80 + 4 + 280 + 4 + 200 + 4 + (6 * 44) + 4 + (5 * 24) + 8 + 8 + 4 + (6 * 4) + (6 * 8) + 1 + 1 + 1 + 8 + 1 + 1 + 32 + 1 + 32 + 2 + 8 + 1 + 128,
+        // Scalar arenas pass no outcomes and get LONG and SHORT
+        space = Arena::space(
+            title.len(),
+            description.len(),
+            question.len(),
+            outcomes.len().max(Arena::MIN_OUTCOMES),
+            tags.len(),
+        ),
+        // The title is a seed, so an overlong one is cut to a derivable
+        // address and rejected by the handler rather than by the derivation
         seeds = [
             b"arena",
             creator.key().as_ref(),
-            title.as_bytes()
+            &title.as_bytes()[..title.len().min(Arena::MAX_TITLE_LEN)]
         ],
         bump
     )]
     pub arena: Account<'info, Arena>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::BETTING) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
     #[account(mut)]
     pub creator: Signer<'info>,
     
+    /// The challenger's bet, staked as a duel is created (duels only)
+    #[account(
+        init,
+        payer = creator,
+        space = Participant::SIZE,
+        seeds = [
+            b"participant",
+            arena.key().as_ref(),
+            creator.key().as_ref()
+        ],
+        bump
+    )]
+    pub participant: Option<Account<'info, Participant>>,
+    
+    /// Lifetime statistics of the challenger, created on first use (duels only)
+    #[account(
+        init_if_needed,
+        payer = creator,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            creator.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Option<Box<Account<'info, UserProfile>>>,
+    
+    /// Mint of the config's token bond (token bonds only)
+    #[account(
+        constraint = config.bond_mint == Some(bond_mint.key()) @ BetFunError::InvalidConfiguration,
+    )]
+    pub bond_mint: Option<Box<Account<'info, Mint>>>,
+    
+    /// Creator's token account the bond is taken from (token bonds only)
+    #[account(mut)]
+    pub creator_bond_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    /// Holds the token bond until it is released or slashed (token bonds only)
+    #[account(
+        init,
+        payer = creator,
+        token::mint = bond_mint,
+        token::authority = arena,
+        seeds = [
+            b"bond_vault",
+            arena.key().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: Option<Box<Account<'info, TokenAccount>>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+    
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateArena>,
     title: String,
@@ -32,10 +112,17 @@ pub fn handler(
     outcomes: Vec<String>,
     tags: Vec<String>,
     entry_fee: u64,
-    end_time: i64,
+    lock_time: i64,
+    resolve_after: i64,
     manual_resolve: bool,
     oracle: Option<Pubkey>,
     token_mint: Option<Pubkey>,
+    creator_fee_bps: u16,
+    creator_trading_fee_bps: u16,
+    scalar: Option<ScalarRange>,
+    allowlist_root: Option<[u8; 32]>,
+    stake_weighting: StakeWeighting,
+    duel: Option<Duel>,
 ) -> Result<()> {
     let arena = &mut ctx.accounts.arena;
     let current_time = Clock::get()?.unix_timestamp;
@@ -66,7 +153,7 @@ pub fn handler(
         BetFunError::InvalidConfiguration
     );
     require!(
-        question.trim().len() > 0,
+        !question.trim().is_empty(),
         BetFunError::InvalidConfiguration
     );
     require!(
@@ -74,6 +161,22 @@ pub fn handler(
         BetFunError::InvalidConfiguration
     );
     
+    // Scalar arenas trade fixed LONG and SHORT outcomes
+    let outcomes = match scalar {
+        Some(range) => {
+            require!(
+                outcomes.is_empty(),
+                BetFunError::InvalidConfiguration
+            );
+            require!(
+                range.lower < range.upper,
+                BetFunError::InvalidConfiguration
+            );
+            Arena::SCALAR_OUTCOMES.iter().map(|name| name.to_string()).collect()
+        }
+        None => outcomes,
+    };
+    
     // Outcomes validation
     require!(
         outcomes.len() >= Arena::MIN_OUTCOMES,
@@ -95,14 +198,14 @@ pub fn handler(
             BetFunError::InvalidConfiguration
         );
         require!(
-            outcome.trim().len() >= 1, // Minimum 1 character
+            !outcome.trim().is_empty(), // Minimum 1 character
             BetFunError::InvalidConfiguration
         );
         
         // Check for duplicates (case-insensitive)
-        for other_idx in (idx + 1)..outcomes.len() {
+        for other in outcomes.iter().skip(idx + 1) {
             require!(
-                outcome.trim().to_lowercase() != outcomes[other_idx].trim().to_lowercase(),
+                outcome.trim().to_lowercase() != other.trim().to_lowercase(),
                 BetFunError::InvalidConfiguration
             );
         }
@@ -126,37 +229,96 @@ pub fn handler(
     }
     
     // Entry fee validation (minimum 0.001 SOL = 1_000_000 lamports)
-    const MIN_ENTRY_FEE: u64 = 1_000_000; // 0.001 SOL
-    const MAX_ENTRY_FEE: u64 = 10_000_000_000; // 10 SOL
     require!(
-        entry_fee >= MIN_ENTRY_FEE,
+        entry_fee >= Arena::MIN_ENTRY_FEE,
         BetFunError::InsufficientEntryFee
     );
     require!(
-        entry_fee <= MAX_ENTRY_FEE,
+        entry_fee <= Arena::MAX_ENTRY_FEE,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // Creator fees validation
+    require!(
+        creator_fee_bps <= Arena::MAX_CREATOR_FEE_BPS,
+        BetFunError::InvalidConfiguration
+    );
+    require!(
+        creator_trading_fee_bps <= Arena::MAX_CREATOR_TRADING_FEE_BPS,
         BetFunError::InvalidConfiguration
     );
     
-    // End time validation
-    let final_end_time = if !manual_resolve {
+    // Early-bettor bonus validation: scalar arenas take no pari-mutuel bets to weight
+    if let StakeWeighting::LinearDecay { bonus_bps } = stake_weighting {
         require!(
-            end_time > current_time,
-            BetFunError::InvalidEndTime
+            scalar.is_none(),
+            BetFunError::InvalidConfiguration
         );
-        
-        // Maximum arena duration: 1 year
-        const MAX_DURATION: i64 = 365 * 24 * 60 * 60;
         require!(
-            end_time <= current_time + MAX_DURATION,
-            BetFunError::InvalidEndTime
+            bonus_bps > 0 && bonus_bps <= Arena::MAX_EARLY_BONUS_BPS,
+            BetFunError::InvalidConfiguration
         );
-        
-        end_time
-    } else {
-        // For manual resolve, set end_time to far future (1 year)
-        // This allows creator to resolve at any time
-        current_time + (365 * 24 * 60 * 60)
-    };
+    }
+    
+    // Duels pit the creator against one opponent on a two-outcome question,
+    // and only duels take the creator's bet
+    if let Some(duel) = duel {
+        require!(
+            scalar.is_none() && outcomes.len() == 2,
+            BetFunError::InvalidConfiguration
+        );
+        require!(
+            duel.opponent != Some(ctx.accounts.creator.key()),
+            BetFunError::InvalidConfiguration
+        );
+        require!(
+            (duel.side as usize) < outcomes.len(),
+            BetFunError::InvalidOutcome
+        );
+    }
+    require!(
+        ctx.accounts.participant.is_some() == duel.is_some() && ctx.accounts.user_profile.is_some() == duel.is_some(),
+        BetFunError::InvalidConfiguration
+    );
+    
+    // A token bond is escrowed in the bond vault, out of the creator's account for the config's mint
+    let config = &ctx.accounts.config;
+    let bond_mint = config.bond_mint.filter(|_| config.creator_bond > 0);
+    let token_bond = (
+        ctx.accounts.bond_mint.is_some(),
+        ctx.accounts.creator_bond_account.as_ref(),
+        ctx.accounts.bond_vault.is_some(),
+        ctx.accounts.token_program.is_some(),
+    );
+    match (bond_mint, token_bond) {
+        (Some(mint), (true, Some(source), true, true)) => {
+            require_keys_eq!(source.mint, mint, BetFunError::InvalidConfiguration);
+            require_keys_eq!(source.owner, ctx.accounts.creator.key(), BetFunError::InvalidConfiguration);
+        }
+        (None, (false, None, false, false)) => {}
+        _ => return err!(BetFunError::InvalidConfiguration),
+    }
+    
+    // Lock and resolution time validation, manual arenas included: betting
+    // must close before anyone can know the result
+    // Maximum arena duration: 1 year, for betting and again for resolution
+    const MAX_DURATION: i64 = 365 * 24 * 60 * 60;
+    require!(
+        lock_time > current_time,
+        BetFunError::InvalidEndTime
+    );
+    require!(
+        lock_time <= current_time + MAX_DURATION,
+        BetFunError::InvalidEndTime
+    );
+    require!(
+        resolve_after >= lock_time,
+        BetFunError::InvalidEndTime
+    );
+    require!(
+        resolve_after <= lock_time + MAX_DURATION,
+        BetFunError::InvalidEndTime
+    );
     
     // Oracle validation (if provided)
     if let Some(oracle_pubkey) = oracle {
@@ -172,6 +334,10 @@ pub fn handler(
     let outcomes_count = outcomes.len();
     let outcome_counts = vec![0u32; outcomes_count];
     let outcome_pots = vec![0u64; outcomes_count];
+    let outcome_weights = match stake_weighting {
+        StakeWeighting::Flat => Vec::new(),
+        StakeWeighting::LinearDecay { .. } => vec![0u64; outcomes_count],
+    };
     
     // Set arena data
     arena.creator = ctx.accounts.creator.key();
@@ -185,15 +351,91 @@ pub fn handler(
     arena.participants_count = 0;
     arena.outcome_counts = outcome_counts;
     arena.outcome_pots = outcome_pots;
+    arena.stake_weighting = stake_weighting;
+    arena.outcome_weights = outcome_weights;
+    arena.duel = duel;
     arena.resolved = false;
     arena.winner_outcome = None;
-    arena.end_time = final_end_time;
+    arena.lock_time = lock_time;
+    arena.resolve_after = resolve_after;
     arena.manual_resolve = manual_resolve;
     arena.oracle = oracle;
     arena.token_mint = token_mint;
-    arena.creator_fee_bps = Arena::DEFAULT_CREATOR_FEE_BPS;
+    arena.creator_fee_bps = creator_fee_bps;
+    arena.creator_trading_fee_bps = creator_trading_fee_bps;
+    arena.scalar = scalar;
+    arena.resolved_value = None;
+    arena.allowlist_root = allowlist_root;
     arena.created_at = current_time;
     arena.bump = ctx.bumps.arena;
+    arena.version = Arena::VERSION;
+    arena.paused = 0;
+    arena.creator_fee_paid = false;
+    arena.total_paid_out = 0;
+    arena.creator_bond = ctx.accounts.config.creator_bond;
+    arena.bond_mint = bond_mint;
+    arena.voided = false;
+    arena.shares_outstanding = 0;
+    arena.parlay_approved = false;
+    
+    // ========== LOCK CREATOR BOND ==========
+    
+    // Held by the arena, or its bond vault, until it resolves, or refunded to
+    // bettors if it is voided
+    if let (Some(source), Some(vault), Some(token_program)) = (
+        ctx.accounts.creator_bond_account.as_ref(),
+        ctx.accounts.bond_vault.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    ) {
+        let transfer_ix = Transfer {
+            from: source.to_account_info(),
+            to: vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            token_program.to_account_info(),
+            transfer_ix,
+        );
+        token::transfer(cpi_ctx, arena.creator_bond)?;
+    } else if arena.creator_bond > 0 {
+        let transfer_ix = system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: arena.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            transfer_ix,
+        );
+        system_program::transfer(cpi_ctx, arena.creator_bond)?;
+    }
+    
+    // ========== STAKE THE CHALLENGE ==========
+    
+    // A duel opens with the challenger's bet, so it can't be created without one
+    let mut challenge = None;
+    if let (Some(duel), Some(participant), Some(user_profile)) = (
+        arena.duel,
+        ctx.accounts.participant.as_mut(),
+        ctx.accounts.user_profile.as_mut(),
+    ) {
+        let transfer_ix = system_program::Transfer {
+            from: ctx.accounts.creator.to_account_info(),
+            to: arena.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            transfer_ix,
+        );
+        system_program::transfer(cpi_ctx, arena.entry_fee)?;
+        
+        let bump = ctx.bumps.participant.ok_or(BetFunError::InvalidConfiguration)?;
+        let weight = join_arena::place_bet(arena, participant, arena.creator, duel.side, bump, current_time)?;
+        
+        let profile_bump = ctx.bumps.user_profile.ok_or(BetFunError::InvalidConfiguration)?;
+        user_profile.touch(arena.creator, profile_bump, current_time)?;
+        user_profile.record_join(arena.entry_fee)?;
+        challenge = Some(weight);
+    }
     
     // ========== LOGGING ==========
     msg!("Arena created successfully");
@@ -204,14 +446,39 @@ pub fn handler(
     msg!("Entry fee: {} lamports ({:.4} SOL)", arena.entry_fee, arena.entry_fee as f64 / 1e9);
     msg!("Outcomes: {:?}", arena.outcomes);
     msg!("Tags: {:?}", arena.tags);
+    msg!("Creator fee: {} bps, trading fee: {} bps", arena.creator_fee_bps, arena.creator_trading_fee_bps);
     msg!("Manual resolve: {}", arena.manual_resolve);
-    msg!("End time: {} (Unix timestamp)", arena.end_time);
+    msg!("Lock time: {} (Unix timestamp)", arena.lock_time);
+    msg!("Resolve after: {} (Unix timestamp)", arena.resolve_after);
     if let Some(oracle) = arena.oracle {
         msg!("Oracle: {}", oracle);
     }
     if let Some(token_mint) = arena.token_mint {
         msg!("Token mint: {}", token_mint);
     }
+    if let Some(range) = arena.scalar {
+        msg!("Scalar range: {} to {}", range.lower, range.upper);
+    }
+    if arena.allowlist_root.is_some() {
+        msg!("Private: members join with an allowlist proof");
+    }
+    if let StakeWeighting::LinearDecay { bonus_bps } = arena.stake_weighting {
+        msg!("Early-bettor bonus: up to {} bps, decaying until lock", bonus_bps);
+    }
+    if let Some(duel) = arena.duel {
+        match duel.opponent {
+            Some(opponent) => msg!("Duel: challenging {}", opponent),
+            None => msg!("Duel: open challenge"),
+        }
+        msg!("Challenger backs: {} ({})", duel.side, arena.outcomes[duel.side as usize]);
+    }
+    match arena.bond_mint {
+        Some(mint) => msg!("Creator bond: {} base units of {}", arena.creator_bond, mint),
+        None if arena.creator_bond > 0 => {
+            msg!("Creator bond: {} lamports ({:.4} SOL)", arena.creator_bond, arena.creator_bond as f64 / 1e9);
+        }
+        None => {}
+    }
     
     // Emit event (if events are set up)
     emit!(ArenaCreated {
@@ -220,7 +487,28 @@ pub fn handler(
         title: arena.title.clone(),
         entry_fee: arena.entry_fee,
         outcomes_count: outcomes_count as u8,
+        creator_fee_bps: arena.creator_fee_bps,
+        creator_trading_fee_bps: arena.creator_trading_fee_bps,
+        scalar: arena.scalar,
+        allowlist_root: arena.allowlist_root,
+        creator_bond: arena.creator_bond,
+        bond_mint: arena.bond_mint,
+        lock_time: arena.lock_time,
+        resolve_after: arena.resolve_after,
+        stake_weighting: arena.stake_weighting,
+        duel: arena.duel,
     });
+    if let (Some(duel), Some(weight)) = (arena.duel, challenge) {
+        emit!(ArenaJoined {
+            arena: arena.key(),
+            participant: arena.creator,
+            outcome_chosen: duel.side,
+            amount: arena.entry_fee,
+            weight,
+            total_pot: arena.pot,
+            participants_count: arena.participants_count,
+        });
+    }
     
     Ok(())
 }
@@ -232,4 +520,14 @@ pub struct ArenaCreated {
     pub title: String,
     pub entry_fee: u64,
     pub outcomes_count: u8,
+    pub creator_fee_bps: u16,
+    pub creator_trading_fee_bps: u16,
+    pub scalar: Option<ScalarRange>,
+    pub allowlist_root: Option<[u8; 32]>,
+    pub creator_bond: u64,
+    pub bond_mint: Option<Pubkey>,
+    pub lock_time: i64,
+    pub resolve_after: i64,
+    pub stake_weighting: StakeWeighting,
+    pub duel: Option<Duel>,
 }
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use crate::state::{Arena, OutcomeShare};
+use anchor_spl::token::{Mint, Token};
+use crate::state::{pause, Arena, BondingCurve, CurveType, OutcomeShare, PriceHistory, GlobalConfig, Versioned};
 use crate::error::BetFunError;
 
 /// Create SPL token mints for each outcome in an arena
@@ -14,10 +14,20 @@ pub struct CreateShareTokens<'info> {
             arena.creator.as_ref(),
             arena.title.as_bytes()
         ],
-        bump = arena.bump
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::SHARES) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::SHARES) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
     /// Creator must sign to create share tokens
     #[account(
         mut,
@@ -54,6 +64,20 @@ pub struct CreateShareTokens<'info> {
     )]
     pub share_mint: Account<'info, Mint>,
     
+    /// Hourly candle history for this outcome
+    #[account(
+        init,
+        payer = creator,
+        space = PriceHistory::SIZE,
+        seeds = [
+            PriceHistory::SEED,
+            arena.key().as_ref(),
+            &[outcome_index]
+        ],
+        bump
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+    
     /// Token program
     pub token_program: Program<'info, Token>,
     
@@ -68,6 +92,8 @@ pub fn handler(
     ctx: Context<CreateShareTokens>,
     outcome_index: u8,
     initial_price: u64,
+    curve_type: CurveType,
+    curve_param: u64,
 ) -> Result<()> {
     let arena = &ctx.accounts.arena;
     let outcome_share = &mut ctx.accounts.outcome_share;
@@ -85,10 +111,15 @@ pub fn handler(
     const MIN_PRICE: u64 = 10_000_000; // 0.01 SOL
     const MAX_PRICE: u64 = 990_000_000; // 0.99 SOL
     require!(
-        initial_price >= MIN_PRICE && initial_price <= MAX_PRICE,
+        (MIN_PRICE..=MAX_PRICE).contains(&initial_price),
         BetFunError::InvalidConfiguration
     );
     
+    // Exponential curves need a non-zero doubling supply
+    if curve_type == CurveType::Exponential {
+        require!(curve_param > 0, BetFunError::InvalidConfiguration);
+    }
+    
     // Arena must not be resolved
     require!(
         !arena.resolved,
@@ -102,6 +133,11 @@ pub fn handler(
     outcome_share.token_mint = ctx.accounts.share_mint.key();
     outcome_share.total_supply = 0;
     outcome_share.current_price = initial_price;
+    outcome_share.curve = BondingCurve {
+        curve_type,
+        base_price: initial_price,
+        param: curve_param,
+    };
     outcome_share.volume_24h = 0;
     outcome_share.trade_count = 0;
     outcome_share.last_trade_at = current_time;
@@ -109,6 +145,16 @@ pub fn handler(
     outcome_share.low_24h = initial_price;
     outcome_share.price_24h_ago = initial_price;
     outcome_share.bump = ctx.bumps.outcome_share;
+    outcome_share.version = OutcomeShare::VERSION;
+    
+    // ========== INITIALIZE PRICE HISTORY ==========
+    
+    let price_history = &mut ctx.accounts.price_history;
+    price_history.arena = arena.key();
+    price_history.outcome_index = outcome_index;
+    price_history.head = 0;
+    price_history.bump = ctx.bumps.price_history;
+    price_history.version = PriceHistory::VERSION;
     
     // ========== LOGGING ==========
     
@@ -120,6 +166,7 @@ pub fn handler(
         initial_price, 
         initial_price as f64 / 1e9
     );
+    msg!("Curve: {:?} (param {})", curve_type, curve_param);
     
     // ========== EMIT EVENT ==========
     
@@ -129,6 +176,8 @@ pub fn handler(
         token_mint: ctx.accounts.share_mint.key(),
         initial_price,
         outcome_name: arena.outcomes[outcome_index as usize].clone(),
+        curve_type,
+        curve_param,
     });
     
     Ok(())
@@ -141,5 +190,7 @@ pub struct ShareTokensCreated {
     pub token_mint: Pubkey,
     pub initial_price: u64,
     pub outcome_name: String,
+    pub curve_type: CurveType,
+    pub curve_param: u64,
 }
 
@@ -0,0 +1,148 @@
+use anchor_lang::prelude::*;
+use crate::state::{pause, Arena, GlobalConfig, Tournament, Versioned};
+use crate::error::BetFunError;
+
+/// Open a single-elimination bracket; `advance_tournament` then creates its matches
+#[derive(Accounts)]
+#[instruction(name: String, entrants: Vec<String>)]
+pub struct CreateTournament<'info> {
+    #[account(
+        init,
+        payer = organizer,
+        space = Tournament::space(name.len(), entrants.len().max(Tournament::MIN_ENTRANTS)),
+        seeds = [
+            b"tournament",
+            organizer.key().as_ref(),
+            name.as_bytes()
+        ],
+        bump
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::BETTING) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(mut)]
+    pub organizer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreateTournament>,
+    name: String,
+    entrants: Vec<String>,
+    entry_fee: u64,
+    match_entry_fee: u64,
+    lock_time: i64,
+    round_duration: i64,
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        name.len() >= 3 && name.len() <= Tournament::MAX_NAME_LEN,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // A full bracket: 4, 8 or 16 distinct, named entrants
+    require!(
+        entrants.len().is_power_of_two()
+            && (Tournament::MIN_ENTRANTS..=Tournament::MAX_ENTRANTS).contains(&entrants.len()),
+        BetFunError::InvalidConfiguration
+    );
+    for (idx, entrant) in entrants.iter().enumerate() {
+        require!(
+            !entrant.trim().is_empty() && entrant.len() <= Arena::MAX_OUTCOME_LEN,
+            BetFunError::InvalidConfiguration
+        );
+        require!(
+            entrants.iter().skip(idx + 1).all(|other| other.trim().to_lowercase() != entrant.trim().to_lowercase()),
+            BetFunError::InvalidConfiguration
+        );
+    }
+    
+    // Match arenas take the same entry fees as any arena
+    require!(
+        entry_fee > 0,
+        BetFunError::InvalidAmount
+    );
+    require!(
+        (Arena::MIN_ENTRY_FEE..=Arena::MAX_ENTRY_FEE).contains(&match_entry_fee),
+        BetFunError::InvalidAmount
+    );
+    
+    // Entries close and the first round locks within a year; later rounds last up to 30 days each
+    const MAX_DURATION: i64 = 365 * 24 * 60 * 60;
+    const MAX_ROUND_DURATION: i64 = 30 * 24 * 60 * 60;
+    require!(
+        lock_time > current_time && lock_time <= current_time + MAX_DURATION,
+        BetFunError::InvalidEndTime
+    );
+    require!(
+        round_duration > 0 && round_duration <= MAX_ROUND_DURATION,
+        BetFunError::InvalidEndTime
+    );
+    
+    // ========== INITIALIZATION ==========
+    
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.organizer = ctx.accounts.organizer.key();
+    tournament.name = name;
+    tournament.arenas = vec![Pubkey::default(); entrants.len() - 1];
+    tournament.results = vec![0; entrants.len() - 1];
+    tournament.entrants = entrants.iter().map(|entrant| entrant.trim().to_string()).collect();
+    tournament.entry_fee = entry_fee;
+    tournament.match_entry_fee = match_entry_fee;
+    tournament.lock_time = lock_time;
+    tournament.round_duration = round_duration;
+    tournament.pot = 0;
+    tournament.entries_count = 0;
+    tournament.best_score = 0;
+    tournament.best_count = 0;
+    tournament.scoring_ends_at = 0;
+    tournament.total_paid_out = 0;
+    tournament.cancelled = false;
+    tournament.created_at = current_time;
+    tournament.bump = ctx.bumps.tournament;
+    tournament.version = Tournament::VERSION;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Tournament created: {}", tournament.name);
+    msg!("Organizer: {}", tournament.organizer);
+    msg!("Entrants: {:?}", tournament.entrants);
+    msg!("Entry fee: {} lamports, match entry fee: {} lamports", entry_fee, match_entry_fee);
+    msg!("Entries close: {} (Unix timestamp)", lock_time);
+    
+    emit!(TournamentCreated {
+        tournament: tournament.key(),
+        organizer: tournament.organizer,
+        name: tournament.name.clone(),
+        entrants: tournament.entrants.len() as u8,
+        entry_fee,
+        match_entry_fee,
+        lock_time,
+        round_duration,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct TournamentCreated {
+    pub tournament: Pubkey,
+    pub organizer: Pubkey,
+    pub name: String,
+    pub entrants: u8,
+    pub entry_fee: u64,
+    pub match_entry_fee: u64,
+    pub lock_time: i64,
+    pub round_duration: i64,
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{Arena, Dispute, Versioned};
+use crate::error::BetFunError;
+
+/// Dispute a bonded arena's resolution within `Arena::DISPUTE_WINDOW` of it
+/// Anyone but the creator may dispute, depositing one entry fee; the config
+/// admin then upholds or rejects it with `settle_dispute`. The creator's bond
+/// stays locked until then.
+#[derive(Accounts)]
+pub struct DisputeArena<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.resolved @ BetFunError::NotResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        mut,
+        constraint = disputer.key() != arena.creator @ BetFunError::Unauthorized,
+    )]
+    pub disputer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<DisputeArena>) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        arena.can_dispute(current_time),
+        BetFunError::DisputeClosed
+    );
+    
+    // ========== DEPOSIT ==========
+    
+    let deposit = arena.entry_fee;
+    let transfer_ix = system_program::Transfer {
+        from: ctx.accounts.disputer.to_account_info(),
+        to: arena.to_account_info(),
+    };
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        transfer_ix,
+    );
+    system_program::transfer(cpi_ctx, deposit)?;
+    
+    let disputer = ctx.accounts.disputer.key();
+    arena.dispute = Dispute::Open { disputer };
+    
+    // ========== LOGGING ==========
+    
+    msg!("Arena disputed: {}", arena.key());
+    msg!("Disputer: {}", disputer);
+    msg!("Deposit: {} lamports ({:.4} SOL)", deposit, deposit as f64 / 1e9);
+    
+    emit!(ArenaDisputed {
+        arena: arena.key(),
+        disputer,
+        deposit,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ArenaDisputed {
+    pub arena: Pubkey,
+    pub disputer: Pubkey,
+    pub deposit: u64,
+}
@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{pause, GlobalConfig, Tournament, TournamentEntry, Versioned};
+use crate::error::BetFunError;
+
+/// Pay the entry fee into a tournament's pot before its first round locks
+/// Picks are ordinary bets in the match arenas, scored with `submit_tournament_score`.
+#[derive(Accounts)]
+pub struct EnterTournament<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tournament",
+            tournament.organizer.as_ref(),
+            tournament.name.as_bytes()
+        ],
+        bump = tournament.bump,
+        constraint = tournament.version == Tournament::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !tournament.cancelled @ BetFunError::TournamentCancelled,
+    )]
+    pub tournament: Account<'info, Tournament>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::BETTING) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init,
+        payer = user,
+        space = TournamentEntry::SIZE,
+        seeds = [
+            b"tournament_entry",
+            tournament.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub entry: Account<'info, TournamentEntry>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<EnterTournament>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        current_time < ctx.accounts.tournament.lock_time,
+        BetFunError::ArenaEnded
+    );
+    
+    // ========== TRANSFER ENTRY FEE ==========
+    
+    let entry_fee = ctx.accounts.tournament.entry_fee;
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.user.to_account_info(),
+            to: ctx.accounts.tournament.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_ctx, entry_fee)?;
+    
+    let tournament = &mut ctx.accounts.tournament;
+    tournament.pot = tournament.pot
+        .checked_add(entry_fee)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    tournament.entries_count = tournament.entries_count
+        .checked_add(1)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    // ========== RECORD ENTRY ==========
+    
+    let entry = &mut ctx.accounts.entry;
+    entry.tournament = tournament.key();
+    entry.wallet = ctx.accounts.user.key();
+    entry.score = 0;
+    entry.submitted = false;
+    entry.claimed = false;
+    entry.entered_at = current_time;
+    entry.bump = ctx.bumps.entry;
+    entry.version = TournamentEntry::VERSION;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Tournament entered: {}", tournament.name);
+    msg!("User: {}", entry.wallet);
+    msg!("Pot: {} lamports from {} entries", tournament.pot, tournament.entries_count);
+    
+    emit!(TournamentEntered {
+        tournament: tournament.key(),
+        wallet: entry.wallet,
+        entry_fee,
+        pot: tournament.pot,
+        entries_count: tournament.entries_count,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct TournamentEntered {
+    pub tournament: Pubkey,
+    pub wallet: Pubkey,
+    pub entry_fee: u64,
+    pub pot: u64,
+    pub entries_count: u32,
+}
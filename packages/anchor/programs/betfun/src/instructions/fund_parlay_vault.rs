@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::{GlobalConfig, ParlayVault, Versioned};
+use crate::error::BetFunError;
+
+/// Add house liquidity backing parlay payouts (admin only)
+#[derive(Accounts)]
+pub struct FundParlayVault<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    /// Created on the first deposit
+    #[account(
+        init_if_needed,
+        payer = admin,
+        space = ParlayVault::SIZE,
+        seeds = [b"parlay_vault"],
+        bump
+    )]
+    pub parlay_vault: Account<'info, ParlayVault>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<FundParlayVault>, amount: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.parlay_vault;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        amount > 0,
+        BetFunError::InvalidAmount
+    );
+    
+    // A new vault is all zeroes; no vault predates versioning
+    if vault.version == 0 {
+        vault.bump = ctx.bumps.parlay_vault;
+        vault.version = ParlayVault::VERSION;
+    }
+    require!(
+        vault.version == ParlayVault::VERSION,
+        BetFunError::UnsupportedAccountVersion
+    );
+    
+    // ========== DEPOSIT ==========
+    
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.admin.to_account_info(),
+            to: vault.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_ctx, amount)?;
+    
+    let vault_info = vault.to_account_info();
+    let rent_exempt_min = Rent::get()?.minimum_balance(vault_info.data_len());
+    let free_liquidity = vault.free_liquidity(vault_info.lamports(), rent_exempt_min);
+    
+    // ========== LOGGING ==========
+    
+    msg!("Parlay vault funded");
+    msg!("Amount: {} lamports ({:.4} SOL)", amount, amount as f64 / 1e9);
+    msg!("Free liquidity: {} lamports", free_liquidity);
+    
+    emit!(ParlayVaultFunded {
+        vault: vault.key(),
+        admin: ctx.accounts.admin.key(),
+        amount,
+        free_liquidity,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ParlayVaultFunded {
+    pub vault: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub free_liquidity: u64,
+}
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::program::Betfun;
+use crate::state::{GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Create the program-wide config (program upgrade authority only); the
+/// upgrade authority becomes its admin
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = GlobalConfig::SIZE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        constraint = program.programdata_address()? == Some(program_data.key()) @ BetFunError::InvalidConfiguration,
+    )]
+    pub program: Program<'info, Betfun>,
+    
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(admin.key()) @ BetFunError::Unauthorized,
+    )]
+    pub program_data: Account<'info, ProgramData>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeConfig>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    // ========== INITIALIZE CONFIG ==========
+    
+    config.admin = ctx.accounts.admin.key();
+    config.paused = 0;
+    config.bump = ctx.bumps.config;
+    config.version = GlobalConfig::VERSION;
+    config.referral_share_bps = GlobalConfig::DEFAULT_REFERRAL_SHARE_BPS;
+    config.creator_bond = 0;
+    config.fee_recipient = config.admin;
+    config.order_book_fee_bps = GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS;
+    config.bond_mint = None;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Config initialized");
+    msg!("Admin: {}", config.admin);
+    
+    emit!(ConfigInitialized {
+        config: config.key(),
+        admin: config.admin,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ConfigInitialized {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+}
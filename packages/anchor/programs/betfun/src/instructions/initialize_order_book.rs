@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use crate::state::{pause, Arena, OutcomeShare, OrderBook, GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Initialize the limit order book for an outcome
+/// Orders can only be placed once the book exists. Like an AMM pool, any
+/// wallet may open it and pays its rent.
+#[derive(Accounts)]
+#[instruction(outcome_index: u8)]
+pub struct InitializeOrderBook<'info> {
+    #[account(
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::ORDER_BOOK) @ BetFunError::Paused,
+    )]
+    pub arena: Account<'info, Arena>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::ORDER_BOOK) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
+    #[account(
+        seeds = [
+            b"outcome_share",
+            arena.key().as_ref(),
+            &[outcome_index]
+        ],
+        bump = outcome_share.bump,
+        constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = outcome_share.outcome_index == outcome_index @ BetFunError::InvalidConfiguration,
+        constraint = outcome_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub outcome_share: Account<'info, OutcomeShare>,
+
+    #[account(
+        init,
+        payer = creator,
+        space = OrderBook::SIZE,
+        seeds = [
+            b"order_book",
+            arena.key().as_ref(),
+            &[outcome_index]
+        ],
+        bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<InitializeOrderBook>,
+    outcome_index: u8,
+) -> Result<()> {
+    let order_book = &mut ctx.accounts.order_book;
+    let arena = &ctx.accounts.arena;
+    let current_time = Clock::get()?.unix_timestamp;
+
+    // Initialize order book
+    order_book.arena = arena.key();
+    order_book.outcome_index = outcome_index;
+    order_book.next_order_id = 0;
+    order_book.active_orders = 0;
+    order_book.total_buy_orders = 0;
+    order_book.total_sell_orders = 0;
+    order_book.best_bid = 0;
+    order_book.best_ask = 0;
+    order_book.spread = 0;
+    order_book.mid_price = 0;
+    order_book.last_trade_price = 0;
+    order_book.volume_24h = 0;
+    order_book.trade_count = 0;
+    order_book.last_trade_at = current_time;
+    order_book.high_24h = 0;
+    order_book.low_24h = 0;
+    order_book.price_24h_ago = 0;
+    order_book.bump = ctx.bumps.order_book;
+    order_book.version = OrderBook::VERSION;
+
+    msg!("Order book initialized for arena: {}", arena.key());
+    msg!("Outcome index: {}", outcome_index);
+    msg!("Share mint: {}", ctx.accounts.outcome_share.token_mint);
+
+    emit!(OrderBookInitialized {
+        order_book: order_book.key(),
+        arena: arena.key(),
+        outcome_index,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct OrderBookInitialized {
+    pub order_book: Pubkey,
+    pub arena: Pubkey,
+    pub outcome_index: u8,
+}
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount};
-use crate::state::{Arena, OutcomeShare, AMMPool};
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::{pause, Arena, OutcomeShare, AMMPool, GlobalConfig, Versioned};
 use crate::error::BetFunError;
 
 /// Initialize an AMM pool for an outcome
@@ -15,9 +15,19 @@ pub struct InitializePool<'info> {
         ],
         bump = arena.bump,
         constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::AMM) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::AMM) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
     #[account(
         seeds = [
             b"outcome_share",
@@ -27,6 +37,7 @@ pub struct InitializePool<'info> {
         bump = outcome_share.bump,
         constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration,
         constraint = outcome_share.outcome_index == outcome_index @ BetFunError::InvalidConfiguration,
+        constraint = outcome_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub outcome_share: Account<'info, OutcomeShare>,
 
@@ -117,6 +128,7 @@ pub fn handler(
     pool.last_price = 0;
     pool.price_24h_ago = 0;
     pool.bump = ctx.bumps.pool;
+    pool.version = AMMPool::VERSION;
 
     msg!("AMM Pool initialized for arena: {}", arena.key());
     msg!("Outcome index: {}", outcome_index);
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
-use crate::state::{Arena, Participant};
+use betfun_math::{bps_of, payout};
+use crate::state::{pause, Arena, Participant, GlobalConfig, Referral, UserProfile, Versioned};
 use crate::error::BetFunError;
 
 #[derive(Accounts)]
@@ -12,12 +13,23 @@ pub struct JoinArena<'info> {
             arena.creator.as_ref(),
             arena.title.as_bytes()
         ],
-        bump = arena.bump
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::BETTING) @ BetFunError::Paused,
+        constraint = !arena.is_scalar() @ BetFunError::WrongMarketType,
     )]
     pub arena: Account<'info, Arena>,
     
     #[account(
-        init,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::BETTING) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        init_if_needed,
         payer = user,
         space = Participant::SIZE,
         seeds = [
@@ -32,12 +44,43 @@ pub struct JoinArena<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
+    /// Lifetime statistics of `user`, created on first use
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
+    /// Wallet that referred `user`, earning a share of the creator fee
+    /// CHECK: Only recorded in `referral`, which must be passed with it
+    pub referrer: Option<UncheckedAccount<'info>>,
+    
+    /// Records the referral on first use and holds the referrer's rewards
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Referral::SIZE,
+        seeds = [
+            b"referral",
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub referral: Option<Account<'info, Referral>>,
+    
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(
     ctx: Context<JoinArena>,
     outcome_chosen: u8,
+    proof: Vec<[u8; 32]>, // Allowlist membership proof; empty for a public arena
 ) -> Result<()> {
     let arena = &mut ctx.accounts.arena;
     let participant = &mut ctx.accounts.participant;
@@ -51,12 +94,18 @@ pub fn handler(
         BetFunError::AlreadyResolved
     );
     
-    // Check if arena has ended (for non-manual arenas)
+    // Betting closes at the lock time, manual arenas included
     require!(
-        !arena.has_ended(current_time),
+        !arena.is_locked(current_time),
         BetFunError::ArenaEnded
     );
     
+    // Private arenas only admit wallets on their allowlist
+    require!(
+        arena.is_allowed(&ctx.accounts.user.key(), &proof),
+        BetFunError::NotAllowlisted
+    );
+    
     // Prevent creator from joining their own arena (optional but good practice)
     // Uncomment if you want to enforce this:
     // require!(
@@ -70,6 +119,20 @@ pub fn handler(
         BetFunError::InvalidOutcome
     );
     
+    // Duels open with the challenger's stake, leaving one opponent to match it on the other side
+    if let Some(duel) = arena.duel {
+        if let Some(opponent) = duel.opponent {
+            require!(
+                ctx.accounts.user.key() == opponent,
+                BetFunError::NotDuelOpponent
+            );
+        }
+        require!(
+            arena.outcome_counts[outcome_chosen as usize] == 0,
+            BetFunError::DuelSideTaken
+        );
+    }
+    
     // Check user has sufficient balance (including rent)
     let user_balance = ctx.accounts.user.lamports();
     let rent_exempt_min = Rent::get()?.minimum_balance(Participant::SIZE);
@@ -82,8 +145,11 @@ pub fn handler(
         BetFunError::InsufficientEntryFee
     );
     
-    // Check if user already participated (PDA init will fail if exists, but double-check)
-    // The init constraint handles this, but we add explicit check for better error message
+    // One bet per wallet: an existing participant account already has its wallet set
+    require!(
+        participant.wallet == Pubkey::default(),
+        BetFunError::AlreadyJoined
+    );
     
     // ========== TRANSFER ENTRY FEE ==========
     
@@ -100,6 +166,99 @@ pub fn handler(
     
     // ========== UPDATE ARENA STATISTICS ==========
     
+    let user = ctx.accounts.user.key();
+    let weight = place_bet(arena, participant, user, outcome_chosen, ctx.bumps.participant, current_time)?;
+    
+    // ========== REFERRAL ==========
+    
+    // The referrer's share of this entry's creator fee leaves the escrow now
+    // and is deducted from the creator fee paid on the first claim
+    let mut referral_reward = 0;
+    match (ctx.accounts.referral.as_mut(), ctx.accounts.referrer.as_ref()) {
+        (Some(referral), Some(referrer)) => {
+            let bump = ctx.bumps.referral.ok_or(BetFunError::InvalidReferrer)?;
+            referral.record(ctx.accounts.user.key(), referrer.key(), bump, current_time)?;
+            
+            let creator_fee = payout::creator_fee(arena.entry_fee, arena.creator_fee_bps);
+            referral_reward = bps_of(creator_fee, ctx.accounts.config.referral_share_bps)
+                .map_err(BetFunError::from)?;
+            if referral_reward > 0 {
+                **arena.to_account_info().try_borrow_mut_lamports()? = arena.to_account_info().lamports()
+                    .checked_sub(referral_reward)
+                    .ok_or(BetFunError::ArithmeticOverflow)?;
+                **referral.to_account_info().try_borrow_mut_lamports()? = referral.to_account_info().lamports()
+                    .checked_add(referral_reward)
+                    .ok_or(BetFunError::ArithmeticOverflow)?;
+                referral.accrue(referral_reward)?;
+                arena.referral_fees = arena.referral_fees
+                    .checked_add(referral_reward)
+                    .ok_or(BetFunError::ArithmeticOverflow)?;
+                arena.total_paid_out = arena.total_paid_out
+                    .checked_add(referral_reward)
+                    .ok_or(BetFunError::ArithmeticOverflow)?;
+            }
+        }
+        (None, None) => {}
+        _ => return err!(BetFunError::InvalidReferrer),
+    }
+    
+    // ========== UPDATE PROFILE ==========
+    
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(participant.wallet, ctx.bumps.user_profile, current_time)?;
+    user_profile.record_join(arena.entry_fee)?;
+    
+    // ========== LOGGING ==========
+    msg!("User joined arena successfully");
+    msg!("User: {}", participant.wallet);
+    msg!("Arena: {}", arena.key());
+    msg!("Outcome chosen: {} ({})", outcome_chosen, arena.outcomes[outcome_chosen as usize]);
+    msg!("Amount bet: {} lamports ({:.4} SOL)", participant.amount, participant.amount as f64 / 1e9);
+    if weight != participant.amount {
+        msg!("Stake weight: {}", weight);
+    }
+    msg!("Total pot: {} lamports ({:.4} SOL)", arena.pot, arena.pot as f64 / 1e9);
+    msg!("Participants: {}", arena.participants_count);
+    msg!("Outcome distribution: {:?}", arena.outcome_counts);
+    msg!("Outcome pots: {:?}", arena.outcome_pots);
+    if referral_reward > 0 {
+        msg!("Referral reward: {} lamports", referral_reward);
+    }
+    
+    // Emit event
+    if arena.duel.is_some() && arena.participants_count == 2 {
+        msg!("Challenge accepted");
+        emit!(DuelAccepted {
+            arena: arena.key(),
+            challenger: arena.creator,
+            opponent: participant.wallet,
+            stake: participant.amount,
+        });
+    }
+    emit!(ArenaJoined {
+        arena: arena.key(),
+        participant: participant.wallet,
+        outcome_chosen,
+        amount: participant.amount,
+        weight,
+        total_pot: arena.pot,
+        participants_count: arena.participants_count,
+    });
+    
+    Ok(())
+}
+
+/// Add a bet of one entry fee on `outcome_chosen` to the arena's pots and
+/// record it in `participant`, once the caller has taken the stake
+/// Returns the bet's stake weight.
+pub(crate) fn place_bet(
+    arena: &mut Account<Arena>,
+    participant: &mut Participant,
+    wallet: Pubkey,
+    outcome_chosen: u8,
+    bump: u8,
+    current_time: i64,
+) -> Result<u64> {
     // Update total pot
     arena.pot = arena.pot
         .checked_add(arena.entry_fee)
@@ -121,39 +280,27 @@ pub fn handler(
         .checked_add(arena.entry_fee)
         .ok_or(BetFunError::ArithmeticOverflow)?;
     
+    // Early bets weigh more in arenas with an early-bettor bonus
+    let weight = arena.stake_weight(arena.entry_fee, current_time)?;
+    if let Some(outcome_weight) = arena.outcome_weights.get_mut(outcome_idx) {
+        *outcome_weight = outcome_weight
+            .checked_add(weight)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+    }
+    
     // ========== INITIALIZE PARTICIPANT ==========
     
     participant.arena = arena.key();
-    participant.wallet = ctx.accounts.user.key();
+    participant.wallet = wallet;
     participant.outcome_chosen = outcome_chosen;
     participant.amount = arena.entry_fee;
     participant.claimed = false;
     participant.joined_at = current_time;
     participant.trophy_mint = None;
-    participant.bump = ctx.bumps.participant;
-    
-    // ========== LOGGING ==========
-    msg!("User joined arena successfully");
-    msg!("User: {}", participant.wallet);
-    msg!("Arena: {}", arena.key());
-    msg!("Outcome chosen: {} ({})", outcome_chosen, arena.outcomes[outcome_idx]);
-    msg!("Amount bet: {} lamports ({:.4} SOL)", participant.amount, participant.amount as f64 / 1e9);
-    msg!("Total pot: {} lamports ({:.4} SOL)", arena.pot, arena.pot as f64 / 1e9);
-    msg!("Participants: {}", arena.participants_count);
-    msg!("Outcome distribution: {:?}", arena.outcome_counts);
-    msg!("Outcome pots: {:?}", arena.outcome_pots);
-    
-    // Emit event
-    emit!(ArenaJoined {
-        arena: arena.key(),
-        participant: participant.wallet,
-        outcome_chosen,
-        amount: participant.amount,
-        total_pot: arena.pot,
-        participants_count: arena.participants_count,
-    });
+    participant.bump = bump;
+    participant.version = Participant::VERSION;
     
-    Ok(())
+    Ok(weight)
 }
 
 #[event]
@@ -162,6 +309,15 @@ pub struct ArenaJoined {
     pub participant: Pubkey,
     pub outcome_chosen: u8,
     pub amount: u64,
+    pub weight: u64,
     pub total_pot: u64,
     pub participants_count: u32,
 }
+
+#[event]
+pub struct DuelAccepted {
+    pub arena: Pubkey,
+    pub challenger: Pubkey,
+    pub opponent: Pubkey,
+    pub stake: u64,
+}
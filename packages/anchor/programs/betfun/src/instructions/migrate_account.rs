@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use anchor_lang::error::ErrorCode;
+use anchor_lang::system_program;
+use crate::state::{
+    AMMPool, Arena, GlobalConfig, LimitOrder, LiquidityPosition, OrderBook, OutcomeShare, Parlay, ParlayVault,
+    Participant, PriceHistory, Referral, ShareBalance, Tournament, TournamentEntry, Trade, UserProfile, Versioned,
+};
+use crate::error::BetFunError;
+
+#[derive(Accounts)]
+pub struct MigrateAccount<'info> {
+    /// CHECK: any account owned by this program; its discriminator selects the layout
+    #[account(mut, owner = crate::ID)]
+    pub account: UncheckedAccount<'info>,
+
+    /// Pays for any extra rent the new layout needs (anyone may migrate)
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateAccount>) -> Result<()> {
+    let discriminator: [u8; 8] = {
+        let data = ctx.accounts.account.try_borrow_data()?;
+        data.get(..8)
+            .ok_or(ErrorCode::AccountDiscriminatorNotFound)?
+            .try_into()
+            .unwrap()
+    };
+
+    match &discriminator[..] {
+        d if d == Arena::DISCRIMINATOR => migrate::<Arena>(&ctx),
+        d if d == GlobalConfig::DISCRIMINATOR => migrate::<GlobalConfig>(&ctx),
+        d if d == Participant::DISCRIMINATOR => migrate::<Participant>(&ctx),
+        d if d == OutcomeShare::DISCRIMINATOR => migrate::<OutcomeShare>(&ctx),
+        d if d == ShareBalance::DISCRIMINATOR => migrate::<ShareBalance>(&ctx),
+        d if d == PriceHistory::DISCRIMINATOR => migrate::<PriceHistory>(&ctx),
+        d if d == AMMPool::DISCRIMINATOR => migrate::<AMMPool>(&ctx),
+        d if d == LiquidityPosition::DISCRIMINATOR => migrate::<LiquidityPosition>(&ctx),
+        d if d == OrderBook::DISCRIMINATOR => migrate::<OrderBook>(&ctx),
+        d if d == LimitOrder::DISCRIMINATOR => migrate::<LimitOrder>(&ctx),
+        d if d == Trade::DISCRIMINATOR => migrate::<Trade>(&ctx),
+        d if d == Referral::DISCRIMINATOR => migrate::<Referral>(&ctx),
+        d if d == UserProfile::DISCRIMINATOR => migrate::<UserProfile>(&ctx),
+        d if d == Parlay::DISCRIMINATOR => migrate::<Parlay>(&ctx),
+        d if d == ParlayVault::DISCRIMINATOR => migrate::<ParlayVault>(&ctx),
+        d if d == Tournament::DISCRIMINATOR => migrate::<Tournament>(&ctx),
+        d if d == TournamentEntry::DISCRIMINATOR => migrate::<TournamentEntry>(&ctx),
+        _ => err!(ErrorCode::AccountDiscriminatorMismatch),
+    }
+}
+
+/// Upgrade the account to `T::VERSION`, growing it if the new layout needs room
+/// Accounts already at the current version are left untouched.
+fn migrate<T: Versioned>(ctx: &Context<MigrateAccount>) -> Result<()> {
+    let account = ctx.accounts.account.to_account_info();
+    let mut state = Box::new(T::read(&account.try_borrow_data()?)?);
+    let from_version = state.version();
+
+    // ========== VALIDATION ==========
+
+    require!(
+        from_version <= T::VERSION,
+        BetFunError::UnsupportedAccountVersion
+    );
+    if from_version == T::VERSION {
+        msg!("Account {} already at version {}", account.key(), from_version);
+        return Ok(());
+    }
+
+    // ========== UPGRADE ==========
+
+    state.upgrade();
+
+    let space = state.space().max(account.data_len());
+    if space > account.data_len() {
+        let rent_due = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(account.lamports());
+        if rent_due > 0 {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: account.clone(),
+                },
+            );
+            system_program::transfer(cpi_ctx, rent_due)?;
+        }
+        account.resize(space)?;
+    }
+
+    state.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+
+    // ========== LOGGING ==========
+
+    msg!("Account {} migrated from version {} to {}", account.key(), from_version, T::VERSION);
+
+    emit!(AccountMigrated {
+        account: account.key(),
+        from_version,
+        to_version: T::VERSION,
+    });
+
+    Ok(())
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub from_version: u8,
+    pub to_version: u8,
+}
@@ -0,0 +1,351 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
+use betfun_math::{bps_of, payout};
+use crate::state::{pause, Arena, OutcomeShare, ShareBalance, GlobalConfig, UserProfile, Versioned};
+use crate::error::BetFunError;
+
+/// Mint LONG/SHORT pairs of a scalar arena against 1 SOL of collateral each
+/// The collateral stays in the arena escrow, so however the resolved value
+/// splits a pair between its sides, every pair is paid out in full.
+#[derive(Accounts)]
+pub struct MintCompleteSets<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::SHARES) @ BetFunError::Paused,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::SHARES) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"outcome_share",
+            arena.key().as_ref(),
+            &[Arena::LONG]
+        ],
+        bump = long_share.bump,
+        constraint = long_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub long_share: Box<Account<'info, OutcomeShare>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"outcome_share",
+            arena.key().as_ref(),
+            &[Arena::SHORT]
+        ],
+        bump = short_share.bump,
+        constraint = short_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub short_share: Box<Account<'info, OutcomeShare>>,
+    
+    #[account(
+        mut,
+        constraint = long_mint.key() == long_share.token_mint @ BetFunError::InvalidConfiguration
+    )]
+    pub long_mint: Box<Account<'info, Mint>>,
+    
+    #[account(
+        mut,
+        constraint = short_mint.key() == short_share.token_mint @ BetFunError::InvalidConfiguration
+    )]
+    pub short_mint: Box<Account<'info, Mint>>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = ShareBalance::SIZE,
+        seeds = [
+            b"share_balance",
+            long_share.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump,
+        constraint = long_balance.owner == Pubkey::default()
+            || long_balance.version == ShareBalance::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub long_balance: Box<Account<'info, ShareBalance>>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = ShareBalance::SIZE,
+        seeds = [
+            b"share_balance",
+            short_share.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump,
+        constraint = short_balance.owner == Pubkey::default()
+            || short_balance.version == ShareBalance::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub short_balance: Box<Account<'info, ShareBalance>>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = long_mint,
+        associated_token::authority = buyer
+    )]
+    pub long_token_account: Box<Account<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = short_mint,
+        associated_token::authority = buyer
+    )]
+    pub short_token_account: Box<Account<'info, TokenAccount>>,
+    
+    /// Buyer pays the collateral
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    /// Lifetime statistics of `buyer`, created on first use
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
+    /// Arena creator, paid the arena's trading fee
+    #[account(
+        mut,
+        constraint = creator.key() == arena.creator @ BetFunError::InvalidConfiguration,
+    )]
+    /// CHECK: Arena creator
+    pub creator: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<MintCompleteSets>,
+    amount: u64, // Number of pairs to mint, in share base units
+    max_cost: u64, // Slippage bound on the total cost, creator fee included
+    deadline_ts: Option<i64>, // Latest acceptable execution time
+    proof: Vec<[u8; 32]>, // Allowlist membership proof; empty for a public arena
+) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    // Reject stale transactions
+    if let Some(deadline) = deadline_ts {
+        require!(
+            current_time <= deadline,
+            BetFunError::DeadlineExceeded
+        );
+    }
+    
+    require!(
+        arena.is_scalar(),
+        BetFunError::WrongMarketType
+    );
+    require!(
+        !arena.resolved,
+        BetFunError::AlreadyResolved
+    );
+    
+    // Issuance closes at the lock time
+    require!(
+        !arena.is_locked(current_time),
+        BetFunError::ArenaEnded
+    );
+    
+    require!(
+        amount > 0,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // Private arenas only sell to wallets on their allowlist
+    require!(
+        arena.is_allowed(&ctx.accounts.buyer.key(), &proof),
+        BetFunError::NotAllowlisted
+    );
+    
+    // One redemption price of collateral per pair, the creator's trading fee on top
+    let collateral = payout::redemption_value(amount).map_err(BetFunError::from)?;
+    let creator_fee = bps_of(collateral, arena.creator_trading_fee_bps).map_err(BetFunError::from)?;
+    let total_cost = collateral
+        .checked_add(creator_fee)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    require!(
+        total_cost <= max_cost,
+        BetFunError::SlippageToleranceExceeded
+    );
+    
+    // ========== TRANSFER SOL ==========
+    
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.buyer.to_account_info(),
+            to: arena.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_ctx, collateral)?;
+    
+    if creator_fee > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.creator.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, creator_fee)?;
+    }
+    
+    // ========== MINT BOTH SIDES ==========
+    
+    // Each side is booked at half the pair price
+    let price = payout::REDEMPTION_PRICE / 2;
+    let arena_key = arena.key();
+    let buyer = ctx.accounts.buyer.key();
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let accounts = &mut *ctx.accounts;
+    issue(
+        arena_key,
+        &mut accounts.long_share,
+        &accounts.long_mint,
+        &accounts.long_token_account,
+        &mut accounts.long_balance,
+        ctx.bumps.long_balance,
+        buyer,
+        token_program.clone(),
+        amount,
+        price,
+    )?;
+    issue(
+        arena_key,
+        &mut accounts.short_share,
+        &accounts.short_mint,
+        &accounts.short_token_account,
+        &mut accounts.short_balance,
+        ctx.bumps.short_balance,
+        buyer,
+        token_program,
+        amount,
+        price,
+    )?;
+    
+    // ========== UPDATE STATISTICS ==========
+    
+    let arena = &mut accounts.arena;
+    arena.shares_outstanding = amount
+        .checked_mul(2)
+        .and_then(|minted| arena.shares_outstanding.checked_add(minted))
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    let user_profile = &mut accounts.user_profile;
+    user_profile.touch(buyer, ctx.bumps.user_profile, current_time)?;
+    user_profile.record_stake(collateral)?;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Complete sets minted");
+    msg!("Buyer: {}", buyer);
+    msg!("Amount: {} LONG and {} SHORT", amount, amount);
+    msg!("Collateral: {} lamports ({:.4} SOL)", collateral, collateral as f64 / 1e9);
+    msg!("Creator fee: {} lamports", creator_fee);
+    
+    // ========== EMIT EVENT ==========
+    
+    emit!(CompleteSetsMinted {
+        arena: arena_key,
+        buyer,
+        amount,
+        collateral,
+        creator_fee,
+    });
+    
+    Ok(())
+}
+
+/// Mint `amount` of one side to the buyer and book it at `price` per whole share
+#[allow(clippy::too_many_arguments)]
+fn issue<'info>(
+    arena: Pubkey,
+    share: &mut Account<'info, OutcomeShare>,
+    mint: &Account<'info, Mint>,
+    token_account: &Account<'info, TokenAccount>,
+    share_balance: &mut Account<'info, ShareBalance>,
+    balance_bump: u8,
+    buyer: Pubkey,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    price: u64,
+) -> Result<()> {
+    require_keys_eq!(share.arena, arena, BetFunError::InvalidConfiguration);
+    
+    let outcome_index_bytes = [share.outcome_index];
+    let seeds = &[
+        b"outcome_share",
+        arena.as_ref(),
+        outcome_index_bytes.as_ref(),
+        &[share.bump],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = MintTo {
+        mint: mint.to_account_info(),
+        to: token_account.to_account_info(),
+        authority: share.to_account_info(),
+    };
+    token::mint_to(CpiContext::new_with_signer(token_program, cpi_accounts, signer), amount)?;
+    
+    share.total_supply = share.total_supply
+        .checked_add(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    share.add_volume(payout::redemption_value(amount).map_err(BetFunError::from)? / 2);
+    
+    if share_balance.owner == Pubkey::default() {
+        share_balance.owner = buyer;
+        share_balance.outcome_share = share.key();
+        share_balance.balance = 0;
+        share_balance.avg_cost_basis = 0;
+        share_balance.total_invested = 0;
+        share_balance.realized_pnl = 0;
+        share_balance.bump = balance_bump;
+        share_balance.version = ShareBalance::VERSION;
+    }
+    share_balance.buy_shares(amount, price);
+    
+    Ok(())
+}
+
+#[event]
+pub struct CompleteSetsMinted {
+    pub arena: Pubkey,
+    pub buyer: Pubkey,
+    /// Pairs minted, in share base units of each side
+    pub amount: u64,
+    /// Lamports locked in the arena escrow
+    pub collateral: u64,
+    pub creator_fee: u64,
+}
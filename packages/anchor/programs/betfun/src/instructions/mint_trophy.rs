@@ -1,10 +1,18 @@
 use anchor_lang::prelude::*;
-use crate::state::{Arena, Participant};
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    self, token_metadata_initialize, token_metadata_update_field, Mint, MintTo, Token2022, TokenAccount,
+    TokenMetadataInitialize, TokenMetadataUpdateField,
+};
+use spl_token_metadata_interface::state::{Field, TokenMetadata};
+use crate::state::{pause, Arena, Participant, GlobalConfig, UserProfile, Versioned};
 use crate::error::BetFunError;
 
-/// Record trophy NFT mint for winner
-/// Note: Actual cNFT minting happens off-chain via Metaplex Bubblegum
-/// This instruction records the mint address on-chain for verification
+/// Mint a trophy NFT to a winner
+/// The trophy is a Token-2022 mint with its metadata stored on the mint
+/// itself. Its mint and update authority is the `trophy_authority` PDA, so a
+/// trophy is genuine exactly when that PDA is its mint authority.
 #[derive(Accounts)]
 pub struct MintTrophy<'info> {
     #[account(
@@ -14,9 +22,19 @@ pub struct MintTrophy<'info> {
             arena.title.as_bytes()
         ],
         bump = arena.bump,
-        constraint = arena.resolved @ BetFunError::NotResolved
+        constraint = arena.resolved @ BetFunError::NotResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::CLAIMS) @ BetFunError::Paused,
     )]
-    pub arena: Account<'info, Arena>,
+    pub arena: Box<Account<'info, Arena>>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::CLAIMS) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
     
     #[account(
         mut,
@@ -27,29 +45,76 @@ pub struct MintTrophy<'info> {
         ],
         bump = participant.bump,
         constraint = participant.wallet == user.key() @ BetFunError::NotParticipant,
-        constraint = participant.claimed @ BetFunError::NotResolved
+        constraint = participant.claimed @ BetFunError::NotResolved,
+        constraint = participant.trophy_mint.is_none() @ BetFunError::AlreadyClaimed,
+        constraint = participant.version == Participant::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub participant: Account<'info, Participant>,
     
+    /// Lifetime statistics of `user`, created when the winnings were claimed
+    #[account(
+        mut,
+        seeds = [
+            b"user_profile",
+            user.key().as_ref()
+        ],
+        bump = user_profile.bump,
+        constraint = user_profile.version == UserProfile::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub user_profile: Account<'info, UserProfile>,
+    
+    /// Trophy NFT, one per winning participant
+    #[account(
+        init,
+        payer = user,
+        seeds = [
+            b"trophy_mint",
+            participant.key().as_ref()
+        ],
+        bump,
+        mint::decimals = 0,
+        mint::authority = trophy_authority,
+        mint::token_program = token_program,
+        extensions::metadata_pointer::authority = trophy_authority,
+        extensions::metadata_pointer::metadata_address = trophy_mint,
+    )]
+    pub trophy_mint: Box<InterfaceAccount<'info, Mint>>,
+    
+    /// Mint and metadata update authority of every trophy
+    /// CHECK: PDA that only signs
+    #[account(
+        seeds = [b"trophy_authority"],
+        bump
+    )]
+    pub trophy_authority: UncheckedAccount<'info>,
+    
+    /// User's token account receiving the trophy
+    #[account(
+        init,
+        payer = user,
+        associated_token::mint = trophy_mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_trophy_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    
+    /// Winner pays for the trophy accounts
+    #[account(mut)]
     pub user: Signer<'info>,
+    
+    pub token_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn handler(
-    ctx: Context<MintTrophy>,
-    trophy_mint: Pubkey,
-) -> Result<()> {
+pub fn handler(ctx: Context<MintTrophy>) -> Result<()> {
     let arena = &ctx.accounts.arena;
     let participant = &mut ctx.accounts.participant;
+    let current_time = Clock::get()?.unix_timestamp;
     
     // ========== VALIDATION ==========
     
-    // Verify arena is resolved
-    require!(
-        arena.resolved,
-        BetFunError::NotResolved
-    );
-    
-    // Verify user won
+    // Verify user won; the accounts already require a resolved arena and a claimed bet
     let winner_outcome = arena.winner_outcome
         .ok_or(BetFunError::InvalidOutcome)?;
     
@@ -58,47 +123,124 @@ pub fn handler(
         BetFunError::NotWinner
     );
     
-    // Verify winnings have been claimed (trophy is minted after claiming)
-    require!(
-        participant.claimed,
-        BetFunError::NotResolved
-    );
+    // ========== TROPHY METADATA ==========
     
-    // Verify trophy hasn't been minted yet
-    require!(
-        participant.trophy_mint.is_none(),
-        BetFunError::AlreadyClaimed
-    );
+    let trophy = TrophyMetadata {
+        arena: arena.key(),
+        arena_title: arena.title.clone(),
+        arena_question: arena.question.clone(),
+        outcome_won: arena.outcomes[participant.outcome_chosen as usize].clone(),
+        amount_won: participant.amount,
+        rarity: TrophyRarity::from_amount(participant.amount),
+        minted_at: current_time,
+    };
+    let fields = trophy.fields();
     
-    // Verify mint address is not zero/empty
-    require!(
-        trophy_mint != Pubkey::default(),
-        BetFunError::InvalidConfiguration
-    );
+    // Token-2022 grows the mint as metadata is written; fund the final size up front
+    let mint_info = ctx.accounts.trophy_mint.to_account_info();
+    let metadata = TokenMetadata {
+        name: trophy.name(),
+        symbol: TrophyMetadata::SYMBOL.to_string(),
+        mint: mint_info.key(),
+        additional_metadata: fields.clone(),
+        ..Default::default()
+    };
+    let metadata_len = metadata.tlv_size_of()?;
+    let rent_due = Rent::get()?
+        .minimum_balance(mint_info.data_len() + metadata_len)
+        .saturating_sub(mint_info.lamports());
+    if rent_due > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.user.to_account_info(),
+                to: mint_info.clone(),
+            },
+        );
+        system_program::transfer(cpi_ctx, rent_due)?;
+    }
+    
+    // ========== MINT TROPHY ==========
+    
+    let authority_seeds: &[&[u8]] = &[b"trophy_authority", &[ctx.bumps.trophy_authority]];
+    let signer = &[authority_seeds];
+    let token_program = ctx.accounts.token_program.to_account_info();
+    let authority = ctx.accounts.trophy_authority.to_account_info();
+    
+    token_metadata_initialize(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            TokenMetadataInitialize {
+                program_id: token_program.clone(),
+                metadata: mint_info.clone(),
+                update_authority: authority.clone(),
+                mint_authority: authority.clone(),
+                mint: mint_info.clone(),
+            },
+            signer,
+        ),
+        metadata.name,
+        metadata.symbol,
+        metadata.uri,
+    )?;
+    
+    for (key, value) in fields {
+        token_metadata_update_field(
+            CpiContext::new_with_signer(
+                token_program.clone(),
+                TokenMetadataUpdateField {
+                    program_id: token_program.clone(),
+                    metadata: mint_info.clone(),
+                    update_authority: authority.clone(),
+                },
+                signer,
+            ),
+            Field::Key(key),
+            value,
+        )?;
+    }
+    
+    token_interface::mint_to(
+        CpiContext::new_with_signer(
+            token_program,
+            MintTo {
+                mint: mint_info.clone(),
+                to: ctx.accounts.user_trophy_account.to_account_info(),
+                authority,
+            },
+            signer,
+        ),
+        1,
+    )?;
     
     // ========== STORE TROPHY MINT ==========
     
-    participant.trophy_mint = Some(trophy_mint);
+    participant.trophy_mint = Some(mint_info.key());
+    
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.record_trophy()?;
+    user_profile.last_active_at = current_time;
     
     // ========== LOGGING ==========
-    msg!("Trophy mint recorded successfully");
+    msg!("Trophy minted successfully");
     msg!("User: {}", participant.wallet);
     msg!("Arena: {}", arena.key());
-    msg!("Trophy mint: {}", trophy_mint);
-    msg!("Outcome won: {} ({})", 
+    msg!("Trophy mint: {}", mint_info.key());
+    msg!("Outcome won: {} ({})",
         participant.outcome_chosen,
-        arena.outcomes[participant.outcome_chosen as usize]
+        trophy.outcome_won
     );
-    msg!("Amount won: {} lamports ({:.4} SOL)", 
+    msg!("Amount won: {} lamports ({:.4} SOL)",
         participant.amount,
         participant.amount as f64 / 1e9
     );
+    msg!("Rarity: {}", trophy.rarity.name());
     
     // Emit event
     emit!(TrophyMinted {
         arena: arena.key(),
         participant: participant.wallet,
-        trophy_mint,
+        trophy_mint: mint_info.key(),
         outcome_won: participant.outcome_chosen,
         amount_won: participant.amount,
     });
@@ -115,9 +257,10 @@ pub struct TrophyMinted {
     pub amount_won: u64,
 }
 
-// Trophy metadata structure (for off-chain minting reference)
+// Trophy metadata, written to the mint's Token-2022 metadata extension
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct TrophyMetadata {
+    pub arena: Pubkey,
     pub arena_title: String,
     pub arena_question: String,
     pub outcome_won: String,
@@ -126,26 +269,65 @@ pub struct TrophyMetadata {
     pub minted_at: i64,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+impl TrophyMetadata {
+    pub const SYMBOL: &'static str = "TROPHY";
+    
+    /// Token name, e.g. `Grand Final Trophy`
+    pub fn name(&self) -> String {
+        format!("{} Trophy", self.arena_title)
+    }
+    
+    /// Additional metadata fields, in the order they are written
+    pub fn fields(&self) -> Vec<(String, String)> {
+        [
+            ("arena", self.arena.to_string()),
+            ("title", self.arena_title.clone()),
+            ("question", self.arena_question.clone()),
+            ("outcome", self.outcome_won.clone()),
+            ("amount_won", self.amount_won.to_string()),
+            ("rarity", self.rarity.name().to_string()),
+            ("minted_at", self.minted_at.to_string()),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq)]
 pub enum TrophyRarity {
-    Common,    // Regular win (< 1 SOL bet)
-    Rare,      // Medium win (1-10 SOL bet)
-    Epic,      // Large win (10-100 SOL bet)
-    Legendary, // Whale win (> 100 SOL bet)
+    Common,    // Regular win (< 0.1 SOL bet)
+    Rare,      // Medium win (0.1-1 SOL bet)
+    Epic,      // Large win (1-10 SOL bet)
+    Legendary, // Whale win (10 SOL bet, the largest entry fee)
 }
 
 impl TrophyRarity {
+    /// Smallest winning bet of each tier, a tenth of the next one up, counting
+    /// down from the largest entry fee an arena can charge
+    pub const LEGENDARY_AMOUNT: u64 = Arena::MAX_ENTRY_FEE;
+    pub const EPIC_AMOUNT: u64 = Self::LEGENDARY_AMOUNT / 10;
+    pub const RARE_AMOUNT: u64 = Self::EPIC_AMOUNT / 10;
+    
+    /// Rarity of a trophy for a winning bet of `amount` lamports
     pub fn from_amount(amount: u64) -> Self {
-        let sol_amount = amount as f64 / 1e9;
-        
-        if sol_amount >= 100.0 {
+        if amount >= Self::LEGENDARY_AMOUNT {
             TrophyRarity::Legendary
-        } else if sol_amount >= 10.0 {
+        } else if amount >= Self::EPIC_AMOUNT {
             TrophyRarity::Epic
-        } else if sol_amount >= 1.0 {
+        } else if amount >= Self::RARE_AMOUNT {
             TrophyRarity::Rare
         } else {
             TrophyRarity::Common
         }
     }
+    
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrophyRarity::Common => "Common",
+            TrophyRarity::Rare => "Rare",
+            TrophyRarity::Epic => "Epic",
+            TrophyRarity::Legendary => "Legendary",
+        }
+    }
 }
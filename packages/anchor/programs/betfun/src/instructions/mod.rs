@@ -1,33 +1,103 @@
+// Every instruction module exports its own `handler`; callers always use the
+// fully qualified path, so the glob re-exports only need the account structs.
+#![allow(ambiguous_glob_reexports)]
+
+pub mod initialize_config;
+pub mod set_global_pause;
+pub mod set_arena_pause;
+pub mod set_referral_share;
+pub mod set_creator_bond;
+pub mod set_protocol_fees;
+pub mod transfer_admin;
 pub mod create_arena;
 pub mod join_arena;
+pub mod set_allowlist;
 pub mod resolve_arena;
+pub mod resolve_scalar_arena;
+pub mod void_arena;
+pub mod cancel_duel;
 pub mod claim_winnings;
+pub mod claim_many;
+pub mod settle_profile;
+pub mod release_creator_bond;
+pub mod claim_bond_share;
+pub mod dispute_arena;
+pub mod settle_dispute;
 pub mod mint_trophy;
 pub mod create_share_tokens;
 pub mod buy_shares;
 pub mod sell_shares;
 pub mod redeem_shares;
+pub mod mint_complete_sets;
 pub mod initialize_pool;
 pub mod add_liquidity;
 pub mod remove_liquidity;
 pub mod swap;
+pub mod claim_referral_rewards;
+pub mod fund_parlay_vault;
+pub mod withdraw_parlay_vault;
+pub mod set_parlay_approval;
+pub mod place_parlay;
+pub mod settle_parlay;
+pub mod initialize_order_book;
 pub mod place_limit_order;
 pub mod cancel_order;
 pub mod settle_match;
+pub mod create_tournament;
+pub mod enter_tournament;
+pub mod advance_tournament;
+pub mod resolve_tournament_match;
+pub mod cancel_tournament;
+pub mod submit_tournament_score;
+pub mod claim_tournament_prize;
+pub mod migrate_account;
 
+pub use initialize_config::*;
+pub use set_global_pause::*;
+pub use set_arena_pause::*;
+pub use set_referral_share::*;
+pub use set_creator_bond::*;
+pub use set_protocol_fees::*;
+pub use transfer_admin::*;
 pub use create_arena::*;
 pub use join_arena::*;
+pub use set_allowlist::*;
 pub use resolve_arena::*;
+pub use resolve_scalar_arena::*;
+pub use void_arena::*;
+pub use cancel_duel::*;
 pub use claim_winnings::*;
+pub use claim_many::*;
+pub use settle_profile::*;
+pub use release_creator_bond::*;
+pub use claim_bond_share::*;
+pub use dispute_arena::*;
+pub use settle_dispute::*;
 pub use mint_trophy::*;
 pub use create_share_tokens::*;
 pub use buy_shares::*;
 pub use sell_shares::*;
 pub use redeem_shares::*;
+pub use mint_complete_sets::*;
 pub use initialize_pool::*;
 pub use add_liquidity::*;
 pub use remove_liquidity::*;
 pub use swap::*;
+pub use claim_referral_rewards::*;
+pub use fund_parlay_vault::*;
+pub use withdraw_parlay_vault::*;
+pub use set_parlay_approval::*;
+pub use place_parlay::*;
+pub use settle_parlay::*;
+pub use initialize_order_book::*;
 pub use place_limit_order::*;
 pub use cancel_order::*;
 pub use settle_match::*;
+pub use create_tournament::*;
+pub use enter_tournament::*;
+pub use advance_tournament::*;
+pub use resolve_tournament_match::*;
+pub use cancel_tournament::*;
+pub use submit_tournament_score::*;
+pub use claim_tournament_prize::*;
+pub use migrate_account::*;
@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{Arena, OrderBook, LimitOrder, OrderType, OrderSide, OrderStatus};
+use anchor_spl::token::{self, InitializeAccount3, Mint, Token, TokenAccount, Transfer};
+use crate::state::{pause, Arena, OrderBook, LimitOrder, OrderType, OrderSide, OrderStatus, GlobalConfig, Versioned};
 use crate::error::BetFunError;
 
 /// Place a limit order in the order book
@@ -15,9 +15,19 @@ pub struct PlaceLimitOrder<'info> {
         ],
         bump = arena.bump,
         constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::ORDER_BOOK) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::ORDER_BOOK) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
     #[account(
         mut,
         seeds = [
@@ -28,6 +38,7 @@ pub struct PlaceLimitOrder<'info> {
         bump = order_book.bump,
         constraint = order_book.arena == arena.key() @ BetFunError::InvalidConfiguration,
         constraint = order_book.outcome_index == outcome_index @ BetFunError::InvalidConfiguration,
+        constraint = order_book.version == OrderBook::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub order_book: Account<'info, OrderBook>,
 
@@ -45,13 +56,27 @@ pub struct PlaceLimitOrder<'info> {
     )]
     pub limit_order: Account<'info, LimitOrder>,
 
+    /// Share mint for this outcome
+    #[account(
+        seeds = [
+            b"share_mint",
+            arena.key().as_ref(),
+            &[outcome_index]
+        ],
+        bump
+    )]
+    pub share_mint: Account<'info, Mint>,
+
     #[account(
         mut,
         constraint = owner_token_account.owner == owner.key() @ BetFunError::Unauthorized,
+        constraint = owner_token_account.mint == share_mint.key() @ BetFunError::InvalidConfiguration,
     )]
     pub owner_token_account: Account<'info, TokenAccount>,
 
     /// Escrow account to hold tokens/SOL for the order
+    /// Buy orders escrow SOL in the bare PDA, sell orders get a token account
+    /// owned by the limit order
     #[account(
         mut,
         seeds = [
@@ -93,6 +118,9 @@ pub fn handler(
     let owner = &ctx.accounts.owner;
     let current_time = Clock::get()?.unix_timestamp;
 
+    // Trading closes at the lock time
+    require!(!ctx.accounts.arena.is_locked(current_time), BetFunError::ArenaEnded);
+
     // Validate params
     require!(params.price > 0, BetFunError::InvalidAmount);
     require!(params.size > 0, BetFunError::InvalidAmount);
@@ -108,9 +136,10 @@ pub fn handler(
     // Calculate escrow amount
     let escrow_amount = match params.side {
         OrderSide::Buy => {
-            // For buy orders, escrow SOL (price * size)
-            params.price
-                .checked_mul(params.size)
+            // For buy orders, escrow SOL for price * size and the buyer fee on
+            // it, plus rent so the escrow stays exempt between partial fills
+            LimitOrder::buy_reserve(params.price, params.size, ctx.accounts.config.order_book_fee_bps)?
+                .checked_add(Rent::get()?.minimum_balance(0))
                 .ok_or(BetFunError::ArithmeticOverflow)?
         }
         OrderSide::Sell => {
@@ -144,6 +173,37 @@ pub fn handler(
                 BetFunError::InsufficientFunds
             );
 
+            // Create the escrow token account, owned by the limit order PDA
+            let limit_order_key = limit_order.key();
+            let escrow_seeds = &[
+                b"order_escrow",
+                limit_order_key.as_ref(),
+                &[ctx.bumps.order_escrow],
+            ];
+
+            anchor_lang::system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::CreateAccount {
+                        from: owner.to_account_info(),
+                        to: ctx.accounts.order_escrow.to_account_info(),
+                    },
+                    &[&escrow_seeds[..]],
+                ),
+                Rent::get()?.minimum_balance(TokenAccount::LEN),
+                TokenAccount::LEN as u64,
+                &ctx.accounts.token_program.key(),
+            )?;
+
+            token::initialize_account3(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                InitializeAccount3 {
+                    account: ctx.accounts.order_escrow.to_account_info(),
+                    mint: ctx.accounts.share_mint.to_account_info(),
+                    authority: limit_order.to_account_info(),
+                },
+            ))?;
+
             token::transfer(
                 CpiContext::new(
                     ctx.accounts.token_program.to_account_info(),
@@ -187,6 +247,7 @@ pub fn handler(
     limit_order.fees_paid = 0;
     limit_order.avg_fill_price = 0;
     limit_order.bump = ctx.bumps.limit_order;
+    limit_order.version = LimitOrder::VERSION;
 
     // Update order book statistics
     order_book.active_orders += 1;
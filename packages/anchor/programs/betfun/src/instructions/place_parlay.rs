@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use betfun_math::{bps_of, payout};
+use crate::state::{pause, GlobalConfig, Parlay, ParlayLeg, ParlayStatus, ParlayVault, UserProfile, Versioned};
+use crate::error::BetFunError;
+
+/// Bet on one outcome in each of several arenas at once
+/// The legs' arenas are passed as `remaining_accounts`, in the order of
+/// `picks`. Each must be approved by the admin, not created or resolved by
+/// the bettor, and hold at least `Parlay::MIN_LEG_POT`, so nobody can lock
+/// odds on a pot they control. Odds are locked from each arena's pot at
+/// purchase, capped at `Parlay::MAX_LEG_ODDS`, and the payout may take at
+/// most `ParlayVault::MAX_PAYOUT_BPS` of the vault's free liquidity.
+#[derive(Accounts)]
+#[instruction(parlay_id: u64, picks: Vec<u8>)]
+pub struct PlaceParlay<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::BETTING) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"parlay_vault"],
+        bump = parlay_vault.bump,
+        constraint = parlay_vault.version == ParlayVault::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub parlay_vault: Account<'info, ParlayVault>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = Parlay::space(picks.len()),
+        seeds = [
+            b"parlay",
+            owner.key().as_ref(),
+            &parlay_id.to_le_bytes()
+        ],
+        bump
+    )]
+    pub parlay: Account<'info, Parlay>,
+    
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// Lifetime statistics of `owner`, created on first use
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            owner.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<PlaceParlay>,
+    parlay_id: u64,
+    picks: Vec<u8>,
+    stake: u64,
+    min_payout: u64, // Slippage bound on the locked payout
+) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        (Parlay::MIN_LEGS..=Parlay::MAX_LEGS).contains(&picks.len()),
+        BetFunError::InvalidConfiguration
+    );
+    require!(
+        ctx.remaining_accounts.len() == picks.len(),
+        BetFunError::InvalidConfiguration
+    );
+    require!(
+        stake > 0,
+        BetFunError::InvalidAmount
+    );
+    
+    // ========== PRICE LEGS ==========
+    
+    let owner = ctx.accounts.owner.key();
+    let mut legs: Vec<ParlayLeg> = Vec::with_capacity(picks.len());
+    for (info, outcome) in ctx.remaining_accounts.iter().zip(picks) {
+        let arena = ParlayLeg::load_arena(info)?;
+        
+        // Each arena at most once, and only open categorical arenas
+        require!(
+            legs.iter().all(|leg| leg.arena != info.key()),
+            BetFunError::InvalidConfiguration
+        );
+        require!(
+            !arena.is_scalar(),
+            BetFunError::WrongMarketType
+        );
+        require!(
+            !arena.is_paused(pause::BETTING),
+            BetFunError::Paused
+        );
+        require!(
+            !arena.resolved,
+            BetFunError::AlreadyResolved
+        );
+        require!(
+            !arena.is_locked(current_time),
+            BetFunError::ArenaEnded
+        );
+        
+        // House money only backs reviewed arenas the bettor cannot settle
+        require!(
+            arena.parlay_approved && arena.creator != owner && arena.oracle != Some(owner),
+            BetFunError::ParlayLegNotAllowed
+        );
+        require!(
+            arena.pot >= Parlay::MIN_LEG_POT,
+            BetFunError::ParlayLegPotTooSmall
+        );
+        
+        let leg = ParlayLeg::price(info.key(), &arena, outcome)?;
+        require!(
+            leg.odds <= Parlay::MAX_LEG_ODDS,
+            BetFunError::ParlayLegOddsTooHigh
+        );
+        legs.push(leg);
+    }
+    
+    let payout = payout::parlay_payout(stake, legs.iter().map(|leg| leg.odds))
+        .map_err(BetFunError::from)?;
+    require!(
+        payout >= min_payout,
+        BetFunError::SlippageToleranceExceeded
+    );
+    
+    // ========== ESCROW STAKE ==========
+    
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.owner.to_account_info(),
+            to: ctx.accounts.parlay_vault.to_account_info(),
+        },
+    );
+    system_program::transfer(cpi_ctx, stake)?;
+    
+    // The vault, stake included, must cover this payout on top of every open
+    // one, and no single parlay may claim more than a slice of what is free
+    let vault = &mut ctx.accounts.parlay_vault;
+    let vault_info = vault.to_account_info();
+    let rent_exempt_min = Rent::get()?.minimum_balance(vault_info.data_len());
+    let free_liquidity = vault.free_liquidity(vault_info.lamports(), rent_exempt_min);
+    let max_payout = bps_of(free_liquidity, ParlayVault::MAX_PAYOUT_BPS).map_err(BetFunError::from)?;
+    require!(
+        payout <= max_payout,
+        BetFunError::InsufficientLiquidity
+    );
+    
+    vault.total_liability = vault.total_liability
+        .checked_add(payout)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    vault.open_parlays = vault.open_parlays
+        .checked_add(1)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    vault.total_staked = vault.total_staked
+        .checked_add(stake)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    // ========== RECORD PARLAY ==========
+    
+    let parlay = &mut ctx.accounts.parlay;
+    parlay.owner = owner;
+    parlay.parlay_id = parlay_id;
+    parlay.stake = stake;
+    parlay.payout = payout;
+    parlay.legs = legs;
+    parlay.status = ParlayStatus::Open;
+    parlay.created_at = current_time;
+    parlay.settled_at = 0;
+    parlay.bump = ctx.bumps.parlay;
+    parlay.version = Parlay::VERSION;
+    
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(owner, ctx.bumps.user_profile, current_time)?;
+    user_profile.record_stake(stake)?;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Parlay placed");
+    msg!("Owner: {}", parlay.owner);
+    msg!("Legs: {}", parlay.legs.len());
+    for leg in parlay.legs.iter() {
+        msg!("  {} outcome {} at {:.4}x", leg.arena, leg.outcome, leg.odds as f64 / payout::ODDS_ONE as f64);
+    }
+    msg!("Stake: {} lamports ({:.4} SOL)", stake, stake as f64 / 1e9);
+    msg!("Payout: {} lamports ({:.4} SOL)", payout, payout as f64 / 1e9);
+    
+    emit!(ParlayPlaced {
+        parlay: parlay.key(),
+        owner: parlay.owner,
+        parlay_id,
+        legs: parlay.legs.len() as u8,
+        stake,
+        payout,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ParlayPlaced {
+    pub parlay: Pubkey,
+    pub owner: Pubkey,
+    pub parlay_id: u64,
+    pub legs: u8,
+    pub stake: u64,
+    pub payout: u64,
+}
@@ -1,24 +1,37 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
-use crate::state::{Arena, OutcomeShare, ShareBalance};
+use crate::state::{pause, Arena, OutcomeShare, ShareBalance, GlobalConfig, UserProfile, Versioned};
 use crate::error::BetFunError;
 
-/// Redeem winning shares for SOL after arena resolution
-/// Each winning share is worth 1 SOL
+/// Redeem winning shares for SOL after arena resolution, or any shares once it is voided
+/// Winning shares split the arena's share reserve pro rata; in scalar arenas a
+/// LONG and a SHORT share together are worth 1 SOL, split by the resolved value
 #[derive(Accounts)]
-#[instruction(amount: u64)]
+#[instruction(_outcome_index: u8, amount: u64)]
 pub struct RedeemShares<'info> {
     #[account(
+        mut,
         seeds = [
             b"arena",
             arena.creator.as_ref(),
             arena.title.as_bytes()
         ],
         bump = arena.bump,
-        constraint = arena.resolved @ BetFunError::NotResolved
+        constraint = arena.resolved || arena.voided @ BetFunError::NotResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        // Refunds out of a voided arena are never paused
+        constraint = arena.voided || !arena.is_paused(pause::CLAIMS) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = arena.voided || !config.is_paused(pause::CLAIMS) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
     #[account(
         mut,
         seeds = [
@@ -27,7 +40,8 @@ pub struct RedeemShares<'info> {
             &[outcome_share.outcome_index]
         ],
         bump = outcome_share.bump,
-        constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration
+        constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = outcome_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub outcome_share: Account<'info, OutcomeShare>,
     
@@ -46,7 +60,8 @@ pub struct RedeemShares<'info> {
             redeemer.key().as_ref()
         ],
         bump = share_balance.bump,
-        constraint = share_balance.owner == redeemer.key() @ BetFunError::NotParticipant
+        constraint = share_balance.owner == redeemer.key() @ BetFunError::NotParticipant,
+        constraint = share_balance.version == ShareBalance::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub share_balance: Account<'info, ShareBalance>,
     
@@ -63,6 +78,19 @@ pub struct RedeemShares<'info> {
     #[account(mut)]
     pub redeemer: Signer<'info>,
     
+    /// Lifetime statistics of `redeemer`, created here for positions older than profiles
+    #[account(
+        init_if_needed,
+        payer = redeemer,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            redeemer.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
     /// Arena escrow pays SOL
     /// CHECK: Arena PDA validated by seeds
     #[account(
@@ -84,26 +112,28 @@ pub struct RedeemShares<'info> {
 pub fn handler(
     ctx: Context<RedeemShares>,
     amount: u64, // Number of shares to redeem
+    min_total_proceeds: Option<u64>, // Slippage bound on the redemption value
+    deadline_ts: Option<i64>, // Latest acceptable execution time
 ) -> Result<()> {
-    let arena = &ctx.accounts.arena;
+    let arena = &mut ctx.accounts.arena;
     let outcome_share = &mut ctx.accounts.outcome_share;
     let share_balance = &mut ctx.accounts.share_balance;
+    let current_time = Clock::get()?.unix_timestamp;
     
     // ========== VALIDATION ==========
     
-    // Arena must be resolved
-    require!(
-        arena.resolved,
-        BetFunError::NotResolved
-    );
-    
-    // This must be the winning outcome
-    let winner_outcome = arena.winner_outcome
-        .ok_or(BetFunError::InvalidOutcome)?;
+    // Reject stale transactions
+    if let Some(deadline) = deadline_ts {
+        require!(
+            current_time <= deadline,
+            BetFunError::DeadlineExceeded
+        );
+    }
     
+    // Arena must be resolved, or voided to refund
     require!(
-        outcome_share.outcome_index == winner_outcome,
-        BetFunError::NotWinner
+        arena.resolved || arena.voided,
+        BetFunError::NotResolved
     );
     
     // Amount must be positive
@@ -118,19 +148,34 @@ pub fn handler(
         BetFunError::InsufficientEntryFee
     );
     
-    // Calculate redemption value (1 SOL per share)
-    const REDEMPTION_PRICE: u64 = 1_000_000_000; // 1 SOL
-    let redemption_value = amount
-        .checked_mul(REDEMPTION_PRICE)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    // Calculate redemption value: winning shares split everything in the
+    // escrow above rent and the unclaimed pot, scalar pairs are worth 1 SOL.
+    // Categorical arenas reject any outcome but the winner unless voided.
+    let escrow = &ctx.accounts.arena_escrow;
+    let rent_exempt_min = Rent::get()?.minimum_balance(escrow.data_len());
+    let reserve = arena.share_reserve(escrow.lamports(), rent_exempt_min);
+    let share_value = arena.redemption_value(outcome_share, amount, reserve)?;
     
-    // Check escrow has sufficient balance
-    let escrow_balance = ctx.accounts.arena_escrow.lamports();
+    // Check escrow has sufficient balance outside rent and the unclaimed pot
     require!(
-        escrow_balance >= redemption_value,
+        reserve >= share_value,
         BetFunError::InsufficientEntryFee
     );
     
+    // Share holders of a voided arena nobody bet in split the slashed bond
+    let bond_share = arena.void_bond_share(amount)?;
+    let redemption_value = share_value
+        .checked_add(bond_share)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    // Check slippage
+    if let Some(min_total_proceeds) = min_total_proceeds {
+        require!(
+            redemption_value >= min_total_proceeds,
+            BetFunError::SlippageToleranceExceeded
+        );
+    }
+    
     // ========== BURN SHARE TOKENS ==========
     
     // Burn shares from redeemer's token account
@@ -157,6 +202,11 @@ pub fn handler(
         .checked_sub(amount)
         .ok_or(BetFunError::ArithmeticOverflow)?;
     
+    arena.shares_outstanding = arena.shares_outstanding.saturating_sub(amount);
+    arena.creator_bond = arena.creator_bond
+        .checked_sub(bond_share)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
     // Update user's share balance
     share_balance.balance = share_balance.balance
         .checked_sub(amount)
@@ -165,7 +215,12 @@ pub fn handler(
     // Calculate final realized P&L
     let cost = (amount as u128 * share_balance.avg_cost_basis as u128) / 1_000_000_000;
     let final_pnl = redemption_value as i64 - cost as i64;
-    share_balance.realized_pnl += final_pnl;
+    share_balance.realized_pnl = share_balance.realized_pnl.saturating_add(final_pnl);
+    
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(ctx.accounts.redeemer.key(), ctx.bumps.user_profile, current_time)?;
+    user_profile.record_payout(redemption_value)?;
+    user_profile.record_pnl(final_pnl);
     
     // ========== LOGGING ==========
     
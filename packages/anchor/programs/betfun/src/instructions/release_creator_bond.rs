@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::{Arena, Versioned};
+use crate::error::BetFunError;
+
+/// Return the creator's bond from an arena that resolved, or that locked
+/// with neither bets nor shares outstanding
+/// Anyone may release; the first winning claim releases a bond in lamports
+/// as well, so this is only needed when nobody claims (e.g. scalar arenas)
+/// or the bond is in a token.
+#[derive(Accounts)]
+pub struct ReleaseCreatorBond<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = !arena.voided @ BetFunError::ArenaVoided,
+        constraint = arena.creator_bond > 0 @ BetFunError::InvalidAmount,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    /// Creator receives the bond
+    #[account(
+        mut,
+        constraint = creator.key() == arena.creator @ BetFunError::InvalidConfiguration
+    )]
+    /// CHECK: Creator account validated by constraint
+    pub creator: UncheckedAccount<'info>,
+    
+    pub caller: Signer<'info>,
+    
+    /// Holds a token bond (token bonds only)
+    #[account(
+        mut,
+        seeds = [
+            b"bond_vault",
+            arena.key().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: Option<Account<'info, TokenAccount>>,
+    
+    /// Creator's token account the bond goes back to (token bonds only)
+    #[account(mut)]
+    pub creator_bond_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Option<Program<'info, Token>>,
+}
+
+pub fn handler(ctx: Context<ReleaseCreatorBond>) -> Result<()> {
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        ctx.accounts.arena.can_release_bond(current_time),
+        BetFunError::NotResolved
+    );
+    
+    // ========== RELEASE ==========
+    
+    let tokens = BondTokens::from_accounts(
+        ctx.accounts.bond_vault.as_ref(),
+        ctx.accounts.creator_bond_account.as_ref(),
+        ctx.accounts.token_program.as_ref(),
+    );
+    release(&mut ctx.accounts.arena, &ctx.accounts.creator.to_account_info(), tokens)?;
+    
+    Ok(())
+}
+
+/// Token accounts a token bond is paid out through
+pub(crate) struct BondTokens<'a, 'info> {
+    pub vault: &'a Account<'info, TokenAccount>,
+    pub destination: &'a Account<'info, TokenAccount>,
+    pub token_program: &'a Program<'info, Token>,
+}
+
+impl<'a, 'info> BondTokens<'a, 'info> {
+    /// The accounts, if all of them were passed
+    pub fn from_accounts(
+        vault: Option<&'a Account<'info, TokenAccount>>,
+        destination: Option<&'a Account<'info, TokenAccount>>,
+        token_program: Option<&'a Program<'info, Token>>,
+    ) -> Option<Self> {
+        Some(Self {
+            vault: vault?,
+            destination: destination?,
+            token_program: token_program?,
+        })
+    }
+}
+
+/// Move the bond held by `arena` back to its creator
+pub(crate) fn release<'info>(
+    arena: &mut Account<'info, Arena>,
+    creator: &AccountInfo,
+    tokens: Option<BondTokens<'_, 'info>>,
+) -> Result<()> {
+    let amount = arena.creator_bond;
+    if amount == 0 {
+        return Ok(());
+    }
+    
+    pay_bond(arena, amount, creator, tokens)?;
+    arena.creator_bond = 0;
+    
+    match arena.bond_mint {
+        Some(mint) => msg!("Creator bond released: {} base units of {}", amount, mint),
+        None => msg!("Creator bond released: {} lamports ({:.4} SOL)", amount, amount as f64 / 1e9),
+    }
+    
+    emit!(CreatorBondReleased {
+        arena: arena.key(),
+        creator: arena.creator,
+        amount,
+    });
+    
+    Ok(())
+}
+
+/// Pay `amount` of the bond held by `arena` to `recipient`: out of the arena's
+/// lamports, or out of its bond vault into `recipient`'s token account
+pub(crate) fn pay_bond<'info>(
+    arena: &Account<'info, Arena>,
+    amount: u64,
+    recipient: &AccountInfo,
+    tokens: Option<BondTokens<'_, 'info>>,
+) -> Result<()> {
+    let mint = match arena.bond_mint {
+        Some(mint) => mint,
+        None => {
+            let arena_info = arena.to_account_info();
+            **arena_info.try_borrow_mut_lamports()? = arena_info.lamports()
+                .checked_sub(amount)
+                .ok_or(BetFunError::InsufficientFunds)?;
+            **recipient.try_borrow_mut_lamports()? = recipient.lamports()
+                .checked_add(amount)
+                .ok_or(BetFunError::ArithmeticOverflow)?;
+            return Ok(());
+        }
+    };
+    
+    let tokens = tokens.ok_or(BetFunError::InvalidConfiguration)?;
+    require_keys_eq!(tokens.destination.mint, mint, BetFunError::InvalidConfiguration);
+    require_keys_eq!(tokens.destination.owner, recipient.key(), BetFunError::InvalidConfiguration);
+    
+    let seeds = &[
+        b"arena",
+        arena.creator.as_ref(),
+        arena.title.as_bytes(),
+        &[arena.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            tokens.token_program.to_account_info(),
+            Transfer {
+                from: tokens.vault.to_account_info(),
+                to: tokens.destination.to_account_info(),
+                authority: arena.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )
+}
+
+#[event]
+pub struct CreatorBondReleased {
+    pub arena: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+}
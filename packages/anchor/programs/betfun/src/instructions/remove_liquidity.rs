@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
-use crate::state::{Arena, AMMPool, LiquidityPosition};
+use crate::state::{Arena, AMMPool, LiquidityPosition, Versioned};
 use crate::error::BetFunError;
 
 /// Remove liquidity from an AMM pool
@@ -14,6 +14,7 @@ pub struct RemoveLiquidity<'info> {
             arena.title.as_bytes()
         ],
         bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub arena: Account<'info, Arena>,
 
@@ -26,14 +27,24 @@ pub struct RemoveLiquidity<'info> {
         ],
         bump = pool.bump,
         constraint = pool.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = pool.version == AMMPool::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub pool: Account<'info, AMMPool>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = lp_token_mint.key() == pool.lp_token_mint @ BetFunError::InvalidConfiguration,
+    )]
     pub lp_token_mint: Account<'info, Mint>,
 
     #[account(
         mut,
+        seeds = [
+            b"pool_token_vault",
+            pool.key().as_ref()
+        ],
+        bump,
+        token::authority = pool,
         constraint = pool_token_vault.mint == pool.share_mint @ BetFunError::InvalidConfiguration,
     )]
     pub pool_token_vault: Account<'info, TokenAccount>,
@@ -64,6 +75,7 @@ pub struct RemoveLiquidity<'info> {
         constraint = liquidity_position.pool == pool.key() @ BetFunError::InvalidConfiguration,
         constraint = liquidity_position.provider == provider.key() @ BetFunError::Unauthorized,
         constraint = liquidity_position.lp_tokens >= lp_tokens_to_burn @ BetFunError::InsufficientFunds,
+        constraint = liquidity_position.version == LiquidityPosition::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub liquidity_position: Account<'info, LiquidityPosition>,
 
@@ -96,25 +108,12 @@ pub fn handler(
     let liquidity_position = &mut ctx.accounts.liquidity_position;
     let provider = &ctx.accounts.provider;
 
-    // Validate LP tokens
-    require!(lp_tokens_to_burn > 0, BetFunError::InvalidAmount);
-    require!(
-        lp_tokens_to_burn <= pool.total_lp_tokens,
-        BetFunError::InsufficientLiquidity
-    );
-
     // Calculate amounts to withdraw
-    let token_amount = (pool.token_reserve as u128)
-        .checked_mul(lp_tokens_to_burn as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)?
-        .checked_div(pool.total_lp_tokens as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)? as u64;
-
-    let sol_amount = (pool.sol_reserve as u128)
-        .checked_mul(lp_tokens_to_burn as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)?
-        .checked_div(pool.total_lp_tokens as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)? as u64;
+    let quote = pool.math()
+        .quote_remove_liquidity(lp_tokens_to_burn)
+        .map_err(BetFunError::from)?;
+    let token_amount = quote.token_amount;
+    let sol_amount = quote.sol_amount;
 
     // Check minimum amounts (slippage protection)
     require!(
@@ -150,7 +149,7 @@ pub fn handler(
     )?;
 
     // Transfer tokens from pool vault to provider
-    let _pool_key = pool.key();
+    let pool_key = pool.key();
     let seeds = &[
         b"amm_pool",
         pool.arena.as_ref(),
@@ -173,13 +172,24 @@ pub fn handler(
     )?;
 
     // Transfer SOL from pool vault to provider
-    **ctx.accounts.pool_sol_vault.try_borrow_mut_lamports()? = ctx.accounts.pool_sol_vault.lamports()
-        .checked_sub(sol_amount)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    // The SOL vault is a system account, so this goes through a signed transfer
+    let vault_seeds = &[
+        b"pool_sol_vault",
+        pool_key.as_ref(),
+        &[ctx.bumps.pool_sol_vault],
+    ];
 
-    **provider.to_account_info().try_borrow_mut_lamports()? = provider.lamports()
-        .checked_add(sol_amount)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.pool_sol_vault.to_account_info(),
+                to: provider.to_account_info(),
+            },
+            &[&vault_seeds[..]],
+        ),
+        sol_amount,
+    )?;
 
     // Update pool reserves
     pool.token_reserve = pool.token_reserve
@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::state::Arena;
+use crate::state::{Arena, Versioned};
 use crate::error::BetFunError;
 
 #[derive(Accounts)]
@@ -12,7 +12,10 @@ pub struct ResolveArena<'info> {
             arena.title.as_bytes()
         ],
         bump = arena.bump,
-        constraint = !arena.resolved @ BetFunError::AlreadyResolved
+        constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = !arena.voided @ BetFunError::ArenaVoided,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_scalar() @ BetFunError::WrongMarketType,
     )]
     pub arena: Account<'info, Arena>,
     
@@ -41,14 +44,11 @@ pub fn handler(
     
     // ========== RESOLUTION VALIDATION ==========
     
-    // For non-manual arenas, check if ended
-    if !arena.manual_resolve {
-        require!(
-            arena.has_ended(current_time),
-            BetFunError::ArenaNotEnded
-        );
-    }
-    // For manual arenas, creator can resolve at any time
+    // No arena resolves before its resolve_after time, manual ones included
+    require!(
+        arena.can_resolve(current_time),
+        BetFunError::ArenaNotEnded
+    );
     
     // Validate winner outcome index
     require!(
@@ -56,39 +56,50 @@ pub fn handler(
         BetFunError::InvalidOutcome
     );
     
-    // Check if there are participants
+    // Check if there are bets or shares to settle
     require!(
-        arena.participants_count > 0,
+        arena.has_positions(),
         BetFunError::InvalidConfiguration
     );
     
-    // Check if chosen outcome has participants
+    // An unaccepted challenge is voided and refunded instead
+    require!(
+        !arena.is_unaccepted_duel(),
+        BetFunError::DuelNotAccepted
+    );
+    
+    // Check if chosen outcome has participants; an arena traded only in
+    // shares pays its winners from the share reserve instead
     let winner_count = arena.outcome_counts[winner_outcome as usize];
     require!(
-        winner_count > 0,
+        winner_count > 0 || arena.participants_count == 0,
         BetFunError::InvalidOutcome
     );
     
     // ========== RESOLVE ARENA ==========
     
+    let resolver = if is_creator { "creator" } else { "oracle" };
+    resolve(arena, winner_outcome, ctx.accounts.resolver.key(), resolver)
+}
+
+/// Record `winner_outcome` as the arena's result, once the caller has
+/// checked the resolver and the outcome
+pub(crate) fn resolve(arena: &mut Account<Arena>, winner_outcome: u8, resolver: Pubkey, role: &str) -> Result<()> {
     arena.resolved = true;
+    arena.resolved_at = Clock::get()?.unix_timestamp;
     arena.winner_outcome = Some(winner_outcome);
     
     // ========== CALCULATE STATISTICS ==========
     
+    let winner_count = arena.outcome_counts[winner_outcome as usize];
     let winner_pot = arena.outcome_pots[winner_outcome as usize];
     let creator_fee = arena.calculate_creator_fee();
-    let distributable_pot = arena.pot
-        .checked_sub(creator_fee)
-        .unwrap_or(0);
+    let distributable_pot = arena.pot.saturating_sub(creator_fee);
     
     // ========== LOGGING ==========
     msg!("Arena resolved successfully");
     msg!("Arena: {}", arena.key());
-    msg!("Resolver: {} ({})", 
-        ctx.accounts.resolver.key(),
-        if is_creator { "creator" } else { "oracle" }
-    );
+    msg!("Resolver: {} ({})", resolver, role);
     msg!("Winner outcome: {} ({})", 
         winner_outcome, 
         arena.outcomes[winner_outcome as usize]
@@ -103,7 +114,7 @@ pub fn handler(
     // Emit event
     emit!(ArenaResolved {
         arena: arena.key(),
-        resolver: ctx.accounts.resolver.key(),
+        resolver,
         winner_outcome,
         total_pot: arena.pot,
         winner_pot,
@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use betfun_math::SHARE_UNIT;
+use crate::state::{Arena, Versioned};
+use crate::error::BetFunError;
+
+#[derive(Accounts)]
+pub struct ResolveScalarArena<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = !arena.voided @ BetFunError::ArenaVoided,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    /// Can be creator or oracle (for automated resolution)
+    pub resolver: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<ResolveScalarArena>,
+    value: i64,
+) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== AUTHORIZATION CHECK ==========
+    
+    let is_creator = ctx.accounts.resolver.key() == arena.creator;
+    let is_oracle = arena.oracle
+        .map(|oracle_pubkey| oracle_pubkey == ctx.accounts.resolver.key())
+        .unwrap_or(false);
+    
+    require!(
+        is_creator || is_oracle,
+        BetFunError::UnauthorizedResolver
+    );
+    
+    // ========== RESOLUTION VALIDATION ==========
+    
+    let range = arena.scalar.ok_or(BetFunError::WrongMarketType)?;
+    
+    // No arena resolves before its resolve_after time, manual ones included
+    require!(
+        arena.can_resolve(current_time),
+        BetFunError::ArenaNotEnded
+    );
+    
+    // ========== RESOLVE ARENA ==========
+    
+    // Values outside the range settle at the nearest bound
+    let settled_value = value.clamp(range.lower, range.upper);
+    arena.resolved = true;
+    arena.resolved_at = current_time;
+    arena.resolved_value = Some(settled_value);
+    
+    let long_payout = arena.scalar_redemption_value(Arena::LONG, SHARE_UNIT)?;
+    let short_payout = arena.scalar_redemption_value(Arena::SHORT, SHARE_UNIT)?;
+    
+    // ========== LOGGING ==========
+    msg!("Scalar arena resolved successfully");
+    msg!("Arena: {}", arena.key());
+    msg!("Resolver: {} ({})",
+        ctx.accounts.resolver.key(),
+        if is_creator { "creator" } else { "oracle" }
+    );
+    msg!("Reported value: {} (range {} to {})", value, range.lower, range.upper);
+    msg!("LONG pays {} lamports per share, SHORT pays {}", long_payout, short_payout);
+    
+    // Emit event
+    emit!(ScalarArenaResolved {
+        arena: arena.key(),
+        resolver: ctx.accounts.resolver.key(),
+        reported_value: value,
+        resolved_value: settled_value,
+        long_payout,
+        short_payout,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ScalarArenaResolved {
+    pub arena: Pubkey,
+    pub resolver: Pubkey,
+    pub reported_value: i64,
+    pub resolved_value: i64,
+    /// Lamports per whole LONG share
+    pub long_payout: u64,
+    /// Lamports per whole SHORT share
+    pub short_payout: u64,
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, Tournament, Versioned};
+use crate::error::BetFunError;
+use crate::instructions::{resolve_arena, void_arena};
+
+/// Record which competitor won a bracket slot and settle its match arena (organizer only)
+/// A match nobody backed the winner of is voided instead, so its bettors get
+/// their stakes back, but the result still advances the bracket.
+#[derive(Accounts)]
+#[instruction(slot: u8)]
+pub struct ResolveTournamentMatch<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tournament",
+            tournament.organizer.as_ref(),
+            tournament.name.as_bytes()
+        ],
+        bump = tournament.bump,
+        has_one = organizer @ BetFunError::UnauthorizedResolver,
+        constraint = tournament.version == Tournament::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !tournament.cancelled @ BetFunError::TournamentCancelled,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    #[account(
+        mut,
+        constraint = tournament.arenas.get(slot as usize) == Some(&arena.key()) @ BetFunError::BracketNotReady,
+        constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = !arena.voided @ BetFunError::ArenaVoided,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Box<Account<'info, Arena>>,
+    
+    pub organizer: Signer<'info>,
+}
+
+pub fn handler(
+    ctx: Context<ResolveTournamentMatch>,
+    slot: u8,
+    winner_outcome: u8,
+) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let arena = &mut ctx.accounts.arena;
+    let organizer = ctx.accounts.organizer.key();
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        arena.can_resolve(current_time),
+        BetFunError::ArenaNotEnded
+    );
+    require!(
+        (winner_outcome as usize) < arena.outcomes.len(),
+        BetFunError::InvalidOutcome
+    );
+    
+    // ========== RECORD RESULT ==========
+    
+    tournament.results[slot as usize] = winner_outcome + 1;
+    let winner = tournament.winner(slot as usize)
+        .ok_or(BetFunError::BracketNotReady)?;
+    
+    msg!("Tournament match decided: {} slot {}", tournament.name, slot);
+    msg!("Winner: {}", tournament.entrants[winner]);
+    
+    // The final opens score submissions
+    if tournament.is_decided() {
+        tournament.scoring_ends_at = current_time
+            .checked_add(Tournament::SCORING_WINDOW)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        msg!("Tournament decided, scores accepted until {}", tournament.scoring_ends_at);
+    }
+    
+    emit!(TournamentMatchResolved {
+        tournament: tournament.key(),
+        arena: arena.key(),
+        slot,
+        winner_outcome,
+        decided: tournament.is_decided(),
+    });
+    
+    // ========== SETTLE ARENA ==========
+    
+    let backed = arena.outcome_counts[winner_outcome as usize] > 0;
+    if backed || arena.participants_count == 0 {
+        resolve_arena::resolve(arena, winner_outcome, organizer, "organizer")
+    } else {
+        void_arena::void(arena, organizer)
+    }
+}
+
+#[event]
+pub struct TournamentMatchResolved {
+    pub tournament: Pubkey,
+    pub arena: Pubkey,
+    pub slot: u8,
+    pub winner_outcome: u8,
+    pub decided: bool,
+}
@@ -1,23 +1,34 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount};
-use crate::state::{Arena, OutcomeShare, ShareBalance};
+use crate::state::{pause, Arena, OutcomeShare, PriceHistory, ShareBalance, GlobalConfig, UserProfile, Versioned};
 use crate::error::BetFunError;
 
 /// Sell outcome shares for SOL
 /// Burns share tokens and returns SOL to seller
 #[derive(Accounts)]
-#[instruction(amount: u64)]
+#[instruction(_outcome_index: u8, amount: u64)]
 pub struct SellShares<'info> {
     #[account(
+        mut,
         seeds = [
             b"arena",
             arena.creator.as_ref(),
             arena.title.as_bytes()
         ],
-        bump = arena.bump
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::SHARES) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
     
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::SHARES) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
     #[account(
         mut,
         seeds = [
@@ -26,7 +37,8 @@ pub struct SellShares<'info> {
             &[outcome_share.outcome_index]
         ],
         bump = outcome_share.bump,
-        constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration
+        constraint = outcome_share.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = outcome_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub outcome_share: Account<'info, OutcomeShare>,
     
@@ -36,6 +48,19 @@ pub struct SellShares<'info> {
     )]
     pub share_mint: Account<'info, Mint>,
     
+    /// Hourly candle history for this outcome
+    #[account(
+        mut,
+        seeds = [
+            PriceHistory::SEED,
+            arena.key().as_ref(),
+            &[outcome_share.outcome_index]
+        ],
+        bump = price_history.bump,
+        constraint = price_history.version == PriceHistory::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+    
     /// User's share balance account
     #[account(
         mut,
@@ -45,7 +70,8 @@ pub struct SellShares<'info> {
             seller.key().as_ref()
         ],
         bump = share_balance.bump,
-        constraint = share_balance.owner == seller.key() @ BetFunError::NotParticipant
+        constraint = share_balance.owner == seller.key() @ BetFunError::NotParticipant,
+        constraint = share_balance.version == ShareBalance::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub share_balance: Account<'info, ShareBalance>,
     
@@ -62,6 +88,19 @@ pub struct SellShares<'info> {
     #[account(mut)]
     pub seller: Signer<'info>,
     
+    /// Lifetime statistics of `seller`, created here for positions older than profiles
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            seller.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
     /// Arena escrow pays SOL
     /// CHECK: Arena PDA validated by seeds
     #[account(
@@ -83,45 +122,77 @@ pub struct SellShares<'info> {
 pub fn handler(
     ctx: Context<SellShares>,
     amount: u64, // Number of shares to sell
+    min_total_proceeds: Option<u64>, // Slippage bound on the total proceeds
+    deadline_ts: Option<i64>, // Latest acceptable execution time
 ) -> Result<()> {
-    let arena = &ctx.accounts.arena;
+    let arena = &mut ctx.accounts.arena;
     let outcome_share = &mut ctx.accounts.outcome_share;
     let share_balance = &mut ctx.accounts.share_balance;
     let current_time = Clock::get()?.unix_timestamp;
     
     // ========== VALIDATION ==========
     
+    // Reject stale transactions
+    if let Some(deadline) = deadline_ts {
+        require!(
+            current_time <= deadline,
+            BetFunError::DeadlineExceeded
+        );
+    }
+    
     // Arena must not be resolved (can't sell after resolution)
     require!(
         !arena.resolved,
         BetFunError::AlreadyResolved
     );
     
+    // Trading closes at the lock time; holders in a voided arena can still sell back
+    require!(
+        !arena.is_locked(current_time) || arena.voided,
+        BetFunError::ArenaEnded
+    );
+    
     // Amount must be positive
     require!(
         amount > 0,
         BetFunError::InvalidConfiguration
     );
     
+    // Scalar shares are backed by LONG/SHORT pairs rather than a curve
+    require!(
+        !arena.is_scalar(),
+        BetFunError::WrongMarketType
+    );
+    
     // User must have sufficient shares
     require!(
         share_balance.balance >= amount,
         BetFunError::InsufficientEntryFee
     );
     
-    // Calculate proceeds in SOL
-    let price = outcome_share.current_price;
-    let proceeds = (amount as u128 * price as u128) / 1_000_000_000;
-    require!(
-        proceeds <= u64::MAX as u128,
-        BetFunError::ArithmeticOverflow
-    );
-    let proceeds = proceeds as u64;
+    // Calculate proceeds along the bonding curve
+    let quote = outcome_share.curve
+        .quote_sell(outcome_share.total_supply, amount)
+        .map_err(BetFunError::from)?;
+    let proceeds = quote.lamports;
     
-    // Check escrow has sufficient balance
-    let escrow_balance = ctx.accounts.arena_escrow.lamports();
+    // Check slippage
+    if let Some(min_total_proceeds) = min_total_proceeds {
+        require!(
+            proceeds >= min_total_proceeds,
+            BetFunError::SlippageToleranceExceeded
+        );
+    }
+    
+    let price = quote.average_price;
+    let new_supply = quote.supply_after;
+    let spot_price = quote.price_after;
+    
+    // Check escrow has sufficient balance outside rent and the unclaimed pot
+    let escrow = &ctx.accounts.arena_escrow;
+    let rent_exempt_min = Rent::get()?.minimum_balance(escrow.data_len());
     require!(
-        escrow_balance >= proceeds,
+        arena.share_reserve(escrow.lamports(), rent_exempt_min) >= proceeds,
         BetFunError::InsufficientEntryFee
     );
     
@@ -147,16 +218,21 @@ pub fn handler(
     // ========== UPDATE STATISTICS ==========
     
     // Update outcome share stats
-    outcome_share.total_supply = outcome_share.total_supply
-        .checked_sub(amount)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    outcome_share.total_supply = new_supply;
+    arena.shares_outstanding = arena.shares_outstanding.saturating_sub(amount);
     
-    outcome_share.update_price_stats(price, current_time);
+    outcome_share.update_price_stats(spot_price, current_time);
     outcome_share.add_volume(proceeds);
+    ctx.accounts.price_history.record_trade(price, proceeds, current_time);
     
     // Update user's share balance and P&L
+    let pnl_before = share_balance.realized_pnl;
     share_balance.sell_shares(amount, price)?;
     
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(ctx.accounts.seller.key(), ctx.bumps.user_profile, current_time)?;
+    user_profile.record_pnl(share_balance.realized_pnl.saturating_sub(pnl_before));
+    
     // ========== LOGGING ==========
     
     msg!("Shares sold successfully");
@@ -166,8 +242,9 @@ pub fn handler(
         arena.outcomes[outcome_share.outcome_index as usize]
     );
     msg!("Amount: {} shares", amount);
-    msg!("Price: {} lamports ({:.4} SOL) per share", price, price as f64 / 1e9);
+    msg!("Average price: {} lamports ({:.4} SOL) per share", price, price as f64 / 1e9);
     msg!("Total proceeds: {} lamports ({:.4} SOL)", proceeds, proceeds as f64 / 1e9);
+    msg!("Spot price: {} lamports ({:.4} SOL) per share", spot_price, spot_price as f64 / 1e9);
     msg!("New balance: {} shares", share_balance.balance);
     msg!("Realized P&L: {} lamports", share_balance.realized_pnl);
     msg!("Total supply: {} shares", outcome_share.total_supply);
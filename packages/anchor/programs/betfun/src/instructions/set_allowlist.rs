@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, Versioned};
+use crate::error::BetFunError;
+
+/// Replace an arena's allowlist root before it ends (creator only)
+/// `None` opens the arena to everyone; bets already placed are unaffected.
+#[derive(Accounts)]
+pub struct SetAllowlist<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        constraint = creator.key() == arena.creator @ BetFunError::Unauthorized,
+    )]
+    pub creator: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetAllowlist>, allowlist_root: Option<[u8; 32]>) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        !arena.resolved,
+        BetFunError::AlreadyResolved
+    );
+    require!(
+        !arena.is_locked(current_time),
+        BetFunError::ArenaEnded
+    );
+    
+    // ========== UPDATE ==========
+    
+    let previous = arena.allowlist_root;
+    arena.allowlist_root = allowlist_root;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Arena {} allowlist {}", arena.key(), if allowlist_root.is_some() { "updated" } else { "removed" });
+    
+    emit!(AllowlistUpdated {
+        arena: arena.key(),
+        creator: ctx.accounts.creator.key(),
+        previous,
+        allowlist_root,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct AllowlistUpdated {
+    pub arena: Pubkey,
+    pub creator: Pubkey,
+    pub previous: Option<[u8; 32]>,
+    pub allowlist_root: Option<[u8; 32]>,
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::{pause, Arena, GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Replace an arena's pause flags (arena creator or config admin)
+#[derive(Accounts)]
+pub struct SetArenaPause<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        constraint = authority.key() == arena.creator
+            || authority.key() == config.admin @ BetFunError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetArenaPause>, paused: u8) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        paused & !pause::ALL == 0,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== UPDATE ==========
+    
+    let previous = arena.paused;
+    arena.paused = paused;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Arena {} pause flags: {:#07b} -> {:#07b}", arena.key(), previous, paused);
+    
+    emit!(ArenaPauseUpdated {
+        arena: arena.key(),
+        authority: ctx.accounts.authority.key(),
+        previous,
+        paused,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ArenaPauseUpdated {
+    pub arena: Pubkey,
+    pub authority: Pubkey,
+    pub previous: u8,
+    pub paused: u8,
+}
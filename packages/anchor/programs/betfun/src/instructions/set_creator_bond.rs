@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+use crate::state::{GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Set the bond creators lock in each new arena, in lamports or in base units
+/// of an SPL Token mint (admin only)
+/// Existing arenas keep the bond they were created with.
+#[derive(Accounts)]
+pub struct SetCreatorBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub admin: Signer<'info>,
+    
+    /// Mint bonds are posted in; omitted for bonds in lamports
+    pub bond_mint: Option<Account<'info, Mint>>,
+}
+
+pub fn handler(ctx: Context<SetCreatorBond>, bond: u64) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    // ========== VALIDATION ==========
+    
+    // Token amounts depend on the mint's decimals, so only lamport bonds are capped
+    let bond_mint = ctx.accounts.bond_mint.as_ref().map(|mint| mint.key());
+    require!(
+        bond_mint.is_some() || bond <= GlobalConfig::MAX_CREATOR_BOND,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== UPDATE ==========
+    
+    let previous = config.creator_bond;
+    config.creator_bond = bond;
+    config.bond_mint = bond_mint;
+    
+    // ========== LOGGING ==========
+    
+    match bond_mint {
+        Some(mint) => msg!("Creator bond: {} -> {} base units of {}", previous, bond, mint),
+        None => msg!("Creator bond: {} lamports -> {} lamports", previous, bond),
+    }
+    
+    emit!(CreatorBondUpdated {
+        config: config.key(),
+        admin: config.admin,
+        previous,
+        bond,
+        bond_mint,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct CreatorBondUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub previous: u64,
+    pub bond: u64,
+    pub bond_mint: Option<Pubkey>,
+}
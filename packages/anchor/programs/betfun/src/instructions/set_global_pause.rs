@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::{pause, GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Replace the program-wide pause flags (admin only)
+#[derive(Accounts)]
+pub struct SetGlobalPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetGlobalPause>, paused: u8) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        paused & !pause::ALL == 0,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== UPDATE ==========
+    
+    let previous = config.paused;
+    config.paused = paused;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Global pause flags: {:#07b} -> {:#07b}", previous, paused);
+    
+    emit!(GlobalPauseUpdated {
+        config: config.key(),
+        admin: config.admin,
+        previous,
+        paused,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct GlobalPauseUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub previous: u8,
+    pub paused: u8,
+}
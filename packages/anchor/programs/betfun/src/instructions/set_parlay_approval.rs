@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Allow or stop an arena being picked as a parlay leg (admin only)
+/// Parlays are paid from house liquidity, so only arenas the admin has
+/// reviewed can back them. Open parlays keep the legs they were placed with.
+#[derive(Accounts)]
+pub struct SetParlayApproval<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetParlayApproval>, approved: bool) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    
+    // ========== UPDATE ==========
+    
+    arena.parlay_approved = approved;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Arena {} parlay legs: {}", arena.key(), if approved { "approved" } else { "not approved" });
+    
+    emit!(ParlayApprovalUpdated {
+        arena: arena.key(),
+        admin: ctx.accounts.admin.key(),
+        approved,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ParlayApprovalUpdated {
+    pub arena: Pubkey,
+    pub admin: Pubkey,
+    pub approved: bool,
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Set who receives protocol fees and the order book's fee per side (admin only)
+#[derive(Accounts)]
+pub struct SetProtocolFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetProtocolFees>, fee_recipient: Pubkey, order_book_fee_bps: u16) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        fee_recipient != Pubkey::default(),
+        BetFunError::InvalidConfiguration
+    );
+    require!(
+        order_book_fee_bps <= GlobalConfig::MAX_ORDER_BOOK_FEE_BPS,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== UPDATE ==========
+    
+    config.fee_recipient = fee_recipient;
+    config.order_book_fee_bps = order_book_fee_bps;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Protocol fee recipient: {}", fee_recipient);
+    msg!("Order book fee: {} bps", order_book_fee_bps);
+    
+    emit!(ProtocolFeesUpdated {
+        config: config.key(),
+        admin: config.admin,
+        fee_recipient,
+        order_book_fee_bps,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ProtocolFeesUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub fee_recipient: Pubkey,
+    pub order_book_fee_bps: u16,
+}
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Set the share of creator and protocol fees paid to referrers (admin only)
+#[derive(Accounts)]
+pub struct SetReferralShare<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SetReferralShare>, share_bps: u16) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        share_bps <= GlobalConfig::MAX_REFERRAL_SHARE_BPS,
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== UPDATE ==========
+    
+    let previous = config.referral_share_bps;
+    config.referral_share_bps = share_bps;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Referral share: {} bps -> {} bps", previous, share_bps);
+    
+    emit!(ReferralShareUpdated {
+        config: config.key(),
+        admin: config.admin,
+        previous,
+        share_bps,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ReferralShareUpdated {
+    pub config: Pubkey,
+    pub admin: Pubkey,
+    pub previous: u16,
+    pub share_bps: u16,
+}
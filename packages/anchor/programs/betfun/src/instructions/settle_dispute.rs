@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, Dispute, GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Rule on an arena's open dispute (admin only)
+/// Upholding it returns the deposit to the disputer and slashes the creator's
+/// bond to the bettors, who claim it pro rata by stake with `claim_bond_share`.
+/// Rejecting it pays the deposit to the creator and frees the bond for
+/// `release_creator_bond`. Payouts stand either way.
+#[derive(Accounts)]
+pub struct SettleDispute<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub admin: Signer<'info>,
+    
+    /// Disputer gets the deposit back if the dispute is upheld
+    /// CHECK: Checked against the open dispute in the handler
+    #[account(mut)]
+    pub disputer: UncheckedAccount<'info>,
+    
+    /// Creator gets the deposit if the dispute is rejected
+    #[account(
+        mut,
+        constraint = creator.key() == arena.creator @ BetFunError::InvalidConfiguration
+    )]
+    /// CHECK: Creator account validated by constraint
+    pub creator: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<SettleDispute>, upheld: bool) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    
+    // ========== VALIDATION ==========
+    
+    let disputer = match arena.dispute {
+        Dispute::Open { disputer } => disputer,
+        _ => return err!(BetFunError::NoOpenDispute),
+    };
+    require_keys_eq!(ctx.accounts.disputer.key(), disputer, BetFunError::InvalidConfiguration);
+    
+    // ========== SETTLE ==========
+    
+    let deposit = arena.dispute_deposit();
+    let recipient = if upheld {
+        ctx.accounts.disputer.to_account_info()
+    } else {
+        ctx.accounts.creator.to_account_info()
+    };
+    let arena_info = arena.to_account_info();
+    **arena_info.try_borrow_mut_lamports()? = arena_info.lamports()
+        .checked_sub(deposit)
+        .ok_or(BetFunError::InsufficientFunds)?;
+    **recipient.try_borrow_mut_lamports()? = recipient.lamports()
+        .checked_add(deposit)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    arena.dispute = if upheld { Dispute::Upheld } else { Dispute::Rejected };
+    
+    // ========== LOGGING ==========
+    
+    msg!("Dispute {}: {}", if upheld { "upheld" } else { "rejected" }, arena.key());
+    msg!("Deposit of {} lamports paid to {}", deposit, recipient.key());
+    if upheld {
+        msg!("Slashed bond: {} to bettors", arena.creator_bond);
+    }
+    
+    emit!(DisputeSettled {
+        arena: arena.key(),
+        disputer,
+        upheld,
+        deposit,
+        slashed_bond: if upheld { arena.creator_bond } else { 0 },
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct DisputeSettled {
+    pub arena: Pubkey,
+    pub disputer: Pubkey,
+    pub upheld: bool,
+    pub deposit: u64,
+    pub slashed_bond: u64,
+}
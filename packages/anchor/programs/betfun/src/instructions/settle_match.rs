@@ -1,9 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-use crate::state::{Arena, OrderBook, LimitOrder, Trade, OrderSide, OrderStatus};
+use betfun_math::bps_of;
+use crate::state::{
+    pause, Arena, OrderBook, LimitOrder, PriceHistory, Trade, OrderSide,
+    OrderStatus, GlobalConfig, Versioned,
+};
 use crate::error::BetFunError;
 
-/// Settle a matched order (called by matching engine)
+/// Settle a matched order (called by the matching engine in `crates/betfun-keeper`)
 #[derive(Accounts)]
 pub struct SettleMatch<'info> {
     #[account(
@@ -14,9 +18,19 @@ pub struct SettleMatch<'info> {
         ],
         bump = arena.bump,
         constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::ORDER_BOOK) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::ORDER_BOOK) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
     #[account(
         mut,
         seeds = [
@@ -26,14 +40,29 @@ pub struct SettleMatch<'info> {
         ],
         bump = order_book.bump,
         constraint = order_book.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = order_book.version == OrderBook::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub order_book: Account<'info, OrderBook>,
 
+    /// Hourly candle history for the order book's outcome
+    #[account(
+        mut,
+        seeds = [
+            PriceHistory::SEED,
+            arena.key().as_ref(),
+            &[order_book.outcome_index]
+        ],
+        bump = price_history.bump,
+        constraint = price_history.version == PriceHistory::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
     #[account(
         mut,
         constraint = buy_order.arena == arena.key() @ BetFunError::InvalidConfiguration,
         constraint = buy_order.side == OrderSide::Buy @ BetFunError::InvalidConfiguration,
         constraint = buy_order.is_active() @ BetFunError::InvalidConfiguration,
+        constraint = buy_order.version == LimitOrder::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub buy_order: Account<'info, LimitOrder>,
 
@@ -42,6 +71,7 @@ pub struct SettleMatch<'info> {
         constraint = sell_order.arena == arena.key() @ BetFunError::InvalidConfiguration,
         constraint = sell_order.side == OrderSide::Sell @ BetFunError::InvalidConfiguration,
         constraint = sell_order.is_active() @ BetFunError::InvalidConfiguration,
+        constraint = sell_order.version == LimitOrder::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub sell_order: Account<'info, LimitOrder>,
 
@@ -98,11 +128,30 @@ pub struct SettleMatch<'info> {
     /// CHECK: Seller account
     pub seller: AccountInfo<'info>,
 
-    /// Protocol fee recipient
-    #[account(mut)]
-    /// CHECK: Protocol fee recipient
+    /// Buyer (refunded whatever the buy escrow no longer needs to hold)
+    #[account(
+        mut,
+        constraint = buyer.key() == buy_order.owner @ BetFunError::Unauthorized,
+    )]
+    /// CHECK: Buyer account
+    pub buyer: AccountInfo<'info>,
+
+    /// Protocol fee recipient recorded in the config
+    #[account(
+        mut,
+        constraint = protocol_fee_recipient.key() == config.protocol_fee_recipient() @ BetFunError::InvalidConfiguration,
+    )]
+    /// CHECK: Protocol fee recipient validated by constraint
     pub protocol_fee_recipient: AccountInfo<'info>,
 
+    /// Arena creator, paid the arena's trading fee out of the seller's proceeds
+    #[account(
+        mut,
+        constraint = creator.key() == arena.creator @ BetFunError::InvalidConfiguration,
+    )]
+    /// CHECK: Arena creator
+    pub creator: AccountInfo<'info>,
+
     /// Matching engine authority (off-chain service)
     #[account(mut)]
     pub matcher: Signer<'info>,
@@ -116,7 +165,6 @@ pub fn handler(
     ctx: Context<SettleMatch>,
     match_size: u64,
     match_price: u64,
-    fee_bps: u16, // e.g., 30 = 0.3%
 ) -> Result<()> {
     let order_book = &mut ctx.accounts.order_book;
     let buy_order = &mut ctx.accounts.buy_order;
@@ -124,6 +172,9 @@ pub fn handler(
     let trade = &mut ctx.accounts.trade;
     let current_time = Clock::get()?.unix_timestamp;
 
+    // Trading closes at the lock time
+    require!(!ctx.accounts.arena.is_locked(current_time), BetFunError::ArenaEnded);
+
     // Validate match
     require!(match_size > 0, BetFunError::InvalidAmount);
     require!(match_size <= buy_order.remaining_size, BetFunError::InvalidAmount);
@@ -131,7 +182,8 @@ pub fn handler(
     require!(buy_order.price >= match_price, BetFunError::InvalidConfiguration);
     require!(sell_order.price <= match_price, BetFunError::InvalidConfiguration);
 
-    // Calculate amounts
+    // Calculate amounts; each side pays the config's order book fee
+    let fee_bps = ctx.accounts.config.order_book_fee_bps;
     let total_value = match_price
         .checked_mul(match_size)
         .ok_or(BetFunError::ArithmeticOverflow)?;
@@ -148,8 +200,12 @@ pub fn handler(
         .checked_div(10000)
         .ok_or(BetFunError::ArithmeticOverflow)? as u64;
 
+    let creator_fee = bps_of(total_value, ctx.accounts.arena.creator_trading_fee_bps)
+        .map_err(BetFunError::from)?;
+
     let seller_proceeds = total_value
         .checked_sub(seller_fee)
+        .and_then(|proceeds| proceeds.checked_sub(creator_fee))
         .ok_or(BetFunError::ArithmeticOverflow)?;
 
     // Transfer tokens from sell order escrow to buyer
@@ -177,13 +233,26 @@ pub fn handler(
     )?;
 
     // Transfer SOL from buy order escrow to seller
-    **ctx.accounts.buy_order_escrow.try_borrow_mut_lamports()? = ctx.accounts.buy_order_escrow.lamports()
-        .checked_sub(seller_proceeds)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    // The escrow is a system account, so payouts go through a signed transfer
+    let buy_order_key = buy_order.key();
+    let buy_escrow_seeds = &[
+        b"order_escrow",
+        buy_order_key.as_ref(),
+        &[ctx.bumps.buy_order_escrow],
+    ];
+    let buy_escrow_signer_seeds = &[&buy_escrow_seeds[..]];
 
-    **ctx.accounts.seller.try_borrow_mut_lamports()? = ctx.accounts.seller.lamports()
-        .checked_add(seller_proceeds)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.buy_order_escrow.to_account_info(),
+                to: ctx.accounts.seller.to_account_info(),
+            },
+            buy_escrow_signer_seeds,
+        ),
+        seller_proceeds,
+    )?;
 
     // Transfer fees to protocol
     let total_fees = buyer_fee
@@ -191,13 +260,59 @@ pub fn handler(
         .ok_or(BetFunError::ArithmeticOverflow)?;
 
     if total_fees > 0 {
-        **ctx.accounts.buy_order_escrow.try_borrow_mut_lamports()? = ctx.accounts.buy_order_escrow.lamports()
-            .checked_sub(total_fees)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.buy_order_escrow.to_account_info(),
+                    to: ctx.accounts.protocol_fee_recipient.to_account_info(),
+                },
+                buy_escrow_signer_seeds,
+            ),
+            total_fees,
+        )?;
+    }
+
+    // Transfer the arena creator's trading fee
+    if creator_fee > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.buy_order_escrow.to_account_info(),
+                    to: ctx.accounts.creator.to_account_info(),
+                },
+                buy_escrow_signer_seeds,
+            ),
+            creator_fee,
+        )?;
+    }
 
-        **ctx.accounts.protocol_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts.protocol_fee_recipient.lamports()
-            .checked_add(total_fees)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
+    // Release what the buy escrow no longer has to hold: price improvement and
+    // the unused fee reserve, keeping the rest of the order and rent funded, or
+    // everything once the order is filled
+    let remaining_size = buy_order.remaining_size - match_size;
+    let still_reserved = if remaining_size == 0 {
+        0
+    } else {
+        LimitOrder::buy_reserve(buy_order.price, remaining_size, fee_bps)?
+            .checked_add(Rent::get()?.minimum_balance(0))
+            .ok_or(BetFunError::ArithmeticOverflow)?
+    };
+    let buyer_refund = ctx.accounts.buy_order_escrow.lamports().saturating_sub(still_reserved);
+
+    if buyer_refund > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.buy_order_escrow.to_account_info(),
+                    to: ctx.accounts.buyer.to_account_info(),
+                },
+                buy_escrow_signer_seeds,
+            ),
+            buyer_refund,
+        )?;
     }
 
     // Update buy order
@@ -222,9 +337,11 @@ pub fn handler(
     trade.seller_fee = seller_fee;
     trade.executed_at = current_time;
     trade.bump = ctx.bumps.trade;
+    trade.version = Trade::VERSION;
 
     // Update order book statistics
     order_book.update_trade_stats(match_price, total_value, current_time);
+    ctx.accounts.price_history.record_trade(match_price, total_value, current_time);
 
     // Update active orders count if orders are filled
     if buy_order.status == OrderStatus::Filled {
@@ -239,7 +356,7 @@ pub fn handler(
     msg!("Buy order: {} ({}% filled)", buy_order.order_id, buy_order.fill_percentage());
     msg!("Sell order: {} ({}% filled)", sell_order.order_id, sell_order.fill_percentage());
     msg!("Match size: {}, Price: {}", match_size, match_price);
-    msg!("Buyer fee: {}, Seller fee: {}", buyer_fee, seller_fee);
+    msg!("Buyer fee: {}, Seller fee: {}, Creator fee: {}", buyer_fee, seller_fee, creator_fee);
 
     emit!(TradeSettled {
         order_book: order_book.key(),
@@ -252,6 +369,7 @@ pub fn handler(
         size: match_size,
         buyer_fee,
         seller_fee,
+        creator_fee,
     });
 
     Ok(())
@@ -269,5 +387,6 @@ pub struct TradeSettled {
     pub size: u64,
     pub buyer_fee: u64,
     pub seller_fee: u64,
+    pub creator_fee: u64,
 }
 
@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use betfun_math::payout;
+use crate::state::{pause, GlobalConfig, Parlay, ParlayLeg, ParlayStatus, ParlayVault, UserProfile, Versioned};
+use crate::error::BetFunError;
+
+/// Settle a parlay once it is decided: paid out when every pick has won, or
+/// marked lost as soon as one pick has lost
+/// A leg in a voided arena pays back evenly, dropping its odds from the payout.
+/// Anyone may settle, so lost parlays stop holding vault liquidity. The legs'
+/// arenas are passed as `remaining_accounts`, in leg order.
+#[derive(Accounts)]
+pub struct SettleParlay<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::CLAIMS) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"parlay_vault"],
+        bump = parlay_vault.bump,
+        constraint = parlay_vault.version == ParlayVault::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub parlay_vault: Account<'info, ParlayVault>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"parlay",
+            owner.key().as_ref(),
+            &parlay.parlay_id.to_le_bytes()
+        ],
+        bump = parlay.bump,
+        constraint = parlay.owner == owner.key() @ BetFunError::NotParticipant,
+        constraint = parlay.status == ParlayStatus::Open @ BetFunError::AlreadyClaimed,
+        constraint = parlay.version == Parlay::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub parlay: Account<'info, Parlay>,
+    
+    /// Parlay owner, receives the payout
+    /// CHECK: must be `parlay.owner`
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
+    
+    /// Lifetime statistics of `owner`, created when the parlay was placed
+    #[account(
+        mut,
+        seeds = [
+            b"user_profile",
+            owner.key().as_ref()
+        ],
+        bump = user_profile.bump,
+        constraint = user_profile.version == UserProfile::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
+    pub settler: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SettleParlay>) -> Result<()> {
+    let parlay = &mut ctx.accounts.parlay;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        ctx.remaining_accounts.len() == parlay.legs.len(),
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== CHECK LEGS ==========
+    
+    let mut lost = false;
+    let mut pending = false;
+    let mut owed = parlay.payout;
+    for (info, leg) in ctx.remaining_accounts.iter().zip(parlay.legs.iter()) {
+        require_keys_eq!(info.key(), leg.arena, BetFunError::InvalidConfiguration);
+        let arena = ParlayLeg::load_arena(info)?;
+        if arena.voided {
+            owed = payout::parlay_without_leg(owed, leg.odds).map_err(BetFunError::from)?;
+        } else if !arena.resolved {
+            pending = true;
+        } else if arena.winner_outcome != Some(leg.outcome) {
+            lost = true;
+        }
+    }
+    
+    // A single lost leg decides the parlay; otherwise every leg must be in
+    require!(
+        lost || !pending,
+        BetFunError::NotResolved
+    );
+    
+    // ========== SETTLE ==========
+    
+    let vault = &mut ctx.accounts.parlay_vault;
+    vault.total_liability = vault.total_liability
+        .checked_sub(parlay.payout)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    vault.open_parlays = vault.open_parlays.saturating_sub(1);
+    
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.last_active_at = current_time;
+    
+    let paid = if lost {
+        parlay.status = ParlayStatus::Lost;
+        user_profile.record_pnl(-(parlay.stake as i64));
+        0
+    } else {
+        let vault_info = vault.to_account_info();
+        **vault_info.try_borrow_mut_lamports()? = vault_info.lamports()
+            .checked_sub(owed)
+            .ok_or(BetFunError::InsufficientLiquidity)?;
+        
+        **ctx.accounts.owner.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.owner.lamports()
+            .checked_add(owed)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        
+        vault.total_paid_out = vault.total_paid_out
+            .checked_add(owed)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        
+        parlay.status = ParlayStatus::Won;
+        user_profile.record_payout(owed)?;
+        user_profile.record_pnl(owed as i64 - parlay.stake as i64);
+        owed
+    };
+    parlay.settled_at = current_time;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Parlay settled: {}", if lost { "lost" } else { "won" });
+    msg!("Owner: {}", parlay.owner);
+    msg!("Stake: {} lamports ({:.4} SOL)", parlay.stake, parlay.stake as f64 / 1e9);
+    msg!("Paid: {} lamports ({:.4} SOL)", paid, paid as f64 / 1e9);
+    
+    emit!(ParlaySettled {
+        parlay: parlay.key(),
+        owner: parlay.owner,
+        won: !lost,
+        stake: parlay.stake,
+        paid,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ParlaySettled {
+    pub parlay: Pubkey,
+    pub owner: Pubkey,
+    pub won: bool,
+    pub stake: u64,
+    pub paid: u64,
+}
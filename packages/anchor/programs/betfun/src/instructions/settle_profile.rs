@@ -0,0 +1,166 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, OutcomeShare, Participant, ShareBalance, UserProfile, Versioned};
+use crate::error::BetFunError;
+
+/// Book a wallet's losing positions in a resolved arena into its profile
+/// Winnings are booked when claimed, but a losing bet or share position has
+/// nothing to claim, so anyone may call this to record the loss: the bet's
+/// stake, and the cost basis of the losing shares. Each position is settled
+/// once; the bet is marked claimed and the share balance zeroed.
+#[derive(Accounts)]
+pub struct SettleProfile<'info> {
+    #[account(
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = arena.resolved @ BetFunError::NotResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    /// Wallet whose positions are settled
+    /// CHECK: Only used to derive its profile and positions
+    pub owner: UncheckedAccount<'info>,
+    
+    /// Lifetime statistics of `owner`, created here for positions older than profiles
+    #[account(
+        init_if_needed,
+        payer = caller,
+        space = UserProfile::SIZE,
+        seeds = [
+            b"user_profile",
+            owner.key().as_ref()
+        ],
+        bump
+    )]
+    pub user_profile: Box<Account<'info, UserProfile>>,
+    
+    /// Losing bet of `owner`
+    #[account(
+        mut,
+        seeds = [
+            b"participant",
+            arena.key().as_ref(),
+            owner.key().as_ref()
+        ],
+        bump = participant.bump,
+        constraint = !participant.claimed @ BetFunError::AlreadyClaimed,
+        constraint = participant.version == Participant::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub participant: Option<Account<'info, Participant>>,
+    
+    /// Losing outcome whose shares `owner` holds
+    #[account(
+        seeds = [
+            b"outcome_share",
+            arena.key().as_ref(),
+            &[outcome_share.outcome_index]
+        ],
+        bump = outcome_share.bump,
+        constraint = outcome_share.version == OutcomeShare::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub outcome_share: Option<Account<'info, OutcomeShare>>,
+    
+    /// Balance of `owner` in `outcome_share`, which must be passed with it
+    #[account(
+        mut,
+        constraint = share_balance.owner == owner.key() @ BetFunError::NotParticipant,
+        constraint = share_balance.version == ShareBalance::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub share_balance: Option<Account<'info, ShareBalance>>,
+    
+    /// Pays for a new profile
+    #[account(mut)]
+    pub caller: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<SettleProfile>) -> Result<()> {
+    let arena = &ctx.accounts.arena;
+    let owner = ctx.accounts.owner.key();
+    let user_profile = &mut ctx.accounts.user_profile;
+    user_profile.touch(owner, ctx.bumps.user_profile, Clock::get()?.unix_timestamp)?;
+    
+    let winner_outcome = arena.winner_outcome
+        .ok_or(BetFunError::InvalidOutcome)?;
+    
+    // ========== LOSING BET ==========
+    
+    let mut bet_loss: u64 = 0;
+    if let Some(participant) = ctx.accounts.participant.as_mut() {
+        require!(
+            participant.outcome_chosen != winner_outcome,
+            BetFunError::PositionNotLost
+        );
+        participant.claimed = true;
+        bet_loss = participant.amount;
+    }
+    
+    // ========== LOSING SHARES ==========
+    
+    let mut share_loss: u64 = 0;
+    match (ctx.accounts.outcome_share.as_ref(), ctx.accounts.share_balance.as_mut()) {
+        (Some(outcome_share), Some(share_balance)) => {
+            require_keys_eq!(share_balance.outcome_share, outcome_share.key(), BetFunError::InvalidConfiguration);
+            // Both sides of a scalar pair pay out, so neither is a loss
+            require!(
+                !arena.is_scalar() && outcome_share.outcome_index != winner_outcome,
+                BetFunError::PositionNotLost
+            );
+            require!(
+                share_balance.balance > 0,
+                BetFunError::InvalidAmount
+            );
+            let cost = (share_balance.balance as u128 * share_balance.avg_cost_basis as u128) / 1_000_000_000;
+            share_loss = u64::try_from(cost).map_err(|_| BetFunError::ArithmeticOverflow)?;
+            share_balance.balance = 0;
+            share_balance.realized_pnl = share_balance.realized_pnl.saturating_sub(share_loss as i64);
+        }
+        (None, None) => {}
+        _ => return err!(BetFunError::InvalidConfiguration),
+    }
+    
+    require!(
+        ctx.accounts.participant.is_some() || ctx.accounts.share_balance.is_some(),
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== UPDATE PROFILE ==========
+    
+    let loss = bet_loss
+        .checked_add(share_loss)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    user_profile.record_pnl(-(loss as i64));
+    
+    // ========== LOGGING ==========
+    
+    msg!("Profile settled");
+    msg!("Owner: {}", owner);
+    msg!("Bet loss: {} lamports, share loss: {} lamports", bet_loss, share_loss);
+    
+    emit!(ProfileSettled {
+        arena: arena.key(),
+        owner,
+        bet_loss,
+        share_loss,
+        realized_pnl: user_profile.realized_pnl,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ProfileSettled {
+    pub arena: Pubkey,
+    pub owner: Pubkey,
+    /// Stake of the losing bet, 0 if none was settled
+    pub bet_loss: u64,
+    /// Cost basis of the losing shares, 0 if none were settled
+    pub share_loss: u64,
+    /// Profile P&L after the losses
+    pub realized_pnl: i64,
+}
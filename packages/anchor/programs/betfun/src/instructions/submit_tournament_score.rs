@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::state::{Tournament, TournamentEntry, Versioned};
+use crate::error::BetFunError;
+
+/// Score an entry by its picks once the final is decided
+/// Remaining accounts are the entrant's `Participant` accounts in the match
+/// arenas, one per match at most; each pick on the winner scores a point.
+#[derive(Accounts)]
+pub struct SubmitTournamentScore<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"tournament",
+            tournament.organizer.as_ref(),
+            tournament.name.as_bytes()
+        ],
+        bump = tournament.bump,
+        constraint = tournament.version == Tournament::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !tournament.cancelled @ BetFunError::TournamentCancelled,
+    )]
+    pub tournament: Box<Account<'info, Tournament>>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"tournament_entry",
+            tournament.key().as_ref(),
+            user.key().as_ref()
+        ],
+        bump = entry.bump,
+        constraint = !entry.submitted @ BetFunError::AlreadyClaimed,
+        constraint = entry.version == TournamentEntry::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub entry: Account<'info, TournamentEntry>,
+    
+    pub user: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<SubmitTournamentScore>) -> Result<()> {
+    let tournament = &mut ctx.accounts.tournament;
+    let entry = &mut ctx.accounts.entry;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        tournament.is_decided(),
+        BetFunError::BracketNotReady
+    );
+    require!(
+        current_time < tournament.scoring_ends_at,
+        BetFunError::ScoringClosed
+    );
+    
+    // ========== SCORE PICKS ==========
+    
+    let mut scored_slots: u16 = 0;
+    let mut score: u8 = 0;
+    for info in ctx.remaining_accounts {
+        let pick = TournamentEntry::load_pick(info)?;
+        require!(
+            pick.wallet == entry.wallet,
+            BetFunError::NotParticipant
+        );
+    
+        // Each match counts once, and only matches of this tournament count
+        let slot = tournament.slot_of(&pick.arena)
+            .ok_or(BetFunError::InvalidConfiguration)?;
+        require!(
+            scored_slots & (1 << slot) == 0,
+            BetFunError::InvalidConfiguration
+        );
+        scored_slots |= 1 << slot;
+    
+        if tournament.results[slot] == pick.outcome_chosen + 1 {
+            score += 1;
+        }
+    }
+    
+    entry.score = score;
+    entry.submitted = true;
+    
+    // Entries on the best score split the pot
+    if score > tournament.best_score || tournament.best_count == 0 {
+        tournament.best_score = score;
+        tournament.best_count = 1;
+    } else if score == tournament.best_score {
+        tournament.best_count = tournament.best_count
+            .checked_add(1)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+    }
+    
+    // ========== LOGGING ==========
+    
+    msg!("Tournament score submitted: {}", tournament.name);
+    msg!("User: {}", entry.wallet);
+    msg!("Score: {} of {} (best {}, shared by {})",
+        score,
+        tournament.arenas.len(),
+        tournament.best_score,
+        tournament.best_count
+    );
+    
+    emit!(TournamentScoreSubmitted {
+        tournament: tournament.key(),
+        wallet: entry.wallet,
+        score,
+        best_score: tournament.best_score,
+        best_count: tournament.best_count,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct TournamentScoreSubmitted {
+    pub tournament: Pubkey,
+    pub wallet: Pubkey,
+    pub score: u8,
+    pub best_score: u8,
+    pub best_count: u32,
+}
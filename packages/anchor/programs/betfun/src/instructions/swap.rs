@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
-use crate::state::{Arena, AMMPool};
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use betfun_math::bps_of;
+use crate::state::{pause, Arena, AMMPool, PriceHistory, GlobalConfig, Referral, Versioned};
 use crate::error::BetFunError;
 
 /// Swap tokens using the AMM pool
@@ -14,9 +15,19 @@ pub struct Swap<'info> {
         ],
         bump = arena.bump,
         constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !arena.is_paused(pause::AMM) @ BetFunError::Paused,
     )]
     pub arena: Account<'info, Arena>,
 
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+        constraint = !config.is_paused(pause::AMM) @ BetFunError::Paused,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+
     #[account(
         mut,
         seeds = [
@@ -26,11 +37,31 @@ pub struct Swap<'info> {
         ],
         bump = pool.bump,
         constraint = pool.arena == arena.key() @ BetFunError::InvalidConfiguration,
+        constraint = pool.version == AMMPool::VERSION @ BetFunError::UnsupportedAccountVersion,
     )]
     pub pool: Account<'info, AMMPool>,
 
+    /// Hourly candle history for the pool's outcome
     #[account(
         mut,
+        seeds = [
+            PriceHistory::SEED,
+            arena.key().as_ref(),
+            &[pool.outcome_index]
+        ],
+        bump = price_history.bump,
+        constraint = price_history.version == PriceHistory::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub price_history: Box<Account<'info, PriceHistory>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"pool_token_vault",
+            pool.key().as_ref()
+        ],
+        bump,
+        token::authority = pool,
         constraint = pool_token_vault.mint == pool.share_mint @ BetFunError::InvalidConfiguration,
     )]
     pub pool_token_vault: Account<'info, TokenAccount>,
@@ -57,11 +88,39 @@ pub struct Swap<'info> {
     /// CHECK: PDA for holding SOL
     pub pool_sol_vault: AccountInfo<'info>,
 
-    /// Protocol fee recipient
-    #[account(mut)]
-    /// CHECK: Protocol fee recipient
+    /// Protocol fee recipient recorded in the config
+    #[account(
+        mut,
+        constraint = protocol_fee_recipient.key() == config.protocol_fee_recipient() @ BetFunError::InvalidConfiguration,
+    )]
+    /// CHECK: Protocol fee recipient validated by constraint
     pub protocol_fee_recipient: AccountInfo<'info>,
 
+    /// Arena creator, paid the arena's trading fee
+    #[account(
+        mut,
+        constraint = creator.key() == arena.creator @ BetFunError::InvalidConfiguration,
+    )]
+    /// CHECK: Arena creator
+    pub creator: AccountInfo<'info>,
+
+    /// Wallet that referred `user`, earning a share of the protocol and creator fees
+    /// CHECK: Only recorded in `referral`, which must be passed with it
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// Records the referral on first use and holds the referrer's rewards
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Referral::SIZE,
+        seeds = [
+            b"referral",
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub referral: Option<Box<Account<'info, Referral>>>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
@@ -79,62 +138,60 @@ pub fn handler(
     // Validate amount
     require!(amount_in > 0, BetFunError::InvalidAmount);
 
-    // Calculate output amount
-    let (reserve_in, reserve_out) = if is_token_to_sol {
-        (pool.token_reserve, pool.sol_reserve)
-    } else {
-        (pool.sol_reserve, pool.token_reserve)
-    };
-
-    require!(reserve_in > 0 && reserve_out > 0, BetFunError::InsufficientLiquidity);
-
-    // Calculate amount out with fee
-    let amount_in_with_fee = (amount_in as u128)
-        .checked_mul((10000 - pool.fee_bps) as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)?
-        .checked_div(10000)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
+    // Trading closes at the lock time
+    require!(!ctx.accounts.arena.is_locked(current_time), BetFunError::ArenaEnded);
 
-    let numerator = amount_in_with_fee
-        .checked_mul(reserve_out as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
-    
-    let denominator = (reserve_in as u128)
-        .checked_add(amount_in_with_fee)
-        .ok_or(BetFunError::ArithmeticOverflow)?;
-
-    let amount_out = numerator
-        .checked_div(denominator)
-        .ok_or(BetFunError::ArithmeticOverflow)? as u64;
+    // Quote the swap with the same math off-chain clients use. The protocol
+    // and creator fees are taken from the SOL side of the trade: the SOL paid
+    // in for buys, the SOL paid out for sells. Sellers receive the output net
+    // of them, so the pool's reserves (and k) never fund either.
+    let math = betfun_math::amm::Pool {
+        creator_fee_bps: ctx.accounts.arena.creator_trading_fee_bps,
+        ..pool.math()
+    };
+    let quote = math
+        .quote_swap(amount_in, is_token_to_sol)
+        .map_err(BetFunError::from)?;
+    let user_amount_out = quote.amount_out;
+    let fee_amount = quote.fee;
+    let protocol_fee = quote.protocol_fee;
+    let creator_fee = quote.creator_fee;
 
     // Check slippage
     require!(
-        amount_out >= min_amount_out,
+        user_amount_out >= min_amount_out,
         BetFunError::SlippageToleranceExceeded
     );
 
-    require!(amount_out > 0, BetFunError::InsufficientOutputAmount);
-    require!(amount_out < reserve_out, BetFunError::InsufficientLiquidity);
-
-    // Calculate fees
-    let fee_amount = (amount_in as u128)
-        .checked_mul(pool.fee_bps as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)?
-        .checked_div(10000)
-        .ok_or(BetFunError::ArithmeticOverflow)? as u64;
-
-    let protocol_fee = (fee_amount as u128)
-        .checked_mul(pool.protocol_fee_bps as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)?
-        .checked_div(pool.fee_bps as u128)
-        .ok_or(BetFunError::ArithmeticOverflow)? as u64;
-
-    // Calculate price impact
-    let price_before = pool.get_price();
-    let price_impact = pool.calculate_price_impact(amount_in, is_token_to_sol)?;
+    let price_before = quote.price_before;
+    let price_impact = quote.price_impact_bps;
+
+    // The referrer's share comes out of the protocol and creator fees
+    let share_bps = ctx.accounts.config.referral_share_bps;
+    let (protocol_reward, creator_reward) = match (ctx.accounts.referral.as_mut(), ctx.accounts.referrer.as_ref()) {
+        (Some(referral), Some(referrer)) => {
+            let bump = ctx.bumps.referral.ok_or(BetFunError::InvalidReferrer)?;
+            referral.record(user.key(), referrer.key(), bump, current_time)?;
+            (
+                bps_of(protocol_fee, share_bps).map_err(BetFunError::from)?,
+                bps_of(creator_fee, share_bps).map_err(BetFunError::from)?,
+            )
+        }
+        (None, None) => (0, 0),
+        _ => return err!(BetFunError::InvalidReferrer),
+    };
+    let referral_reward = protocol_reward
+        .checked_add(creator_reward)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    let recipient_fee = protocol_fee
+        .checked_sub(protocol_reward)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    let creator_payment = creator_fee
+        .checked_sub(creator_reward)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
 
     // Perform swap
-    let _pool_key = pool.key();
+    let pool_key = pool.key();
     let seeds = &[
         b"amm_pool",
         pool.arena.as_ref(),
@@ -143,6 +200,14 @@ pub fn handler(
     ];
     let signer_seeds = &[&seeds[..]];
 
+    // The SOL vault is a system account, so payouts go through a signed transfer
+    let vault_seeds = &[
+        b"pool_sol_vault",
+        pool_key.as_ref(),
+        &[ctx.bumps.pool_sol_vault],
+    ];
+    let vault_signer_seeds = &[&vault_seeds[..]];
+
     if is_token_to_sol {
         // Token -> SOL swap
         
@@ -166,35 +231,32 @@ pub fn handler(
         )?;
 
         // Transfer SOL from pool to user
-        **ctx.accounts.pool_sol_vault.try_borrow_mut_lamports()? = ctx.accounts.pool_sol_vault.lamports()
-            .checked_sub(amount_out)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-
-        **user.to_account_info().try_borrow_mut_lamports()? = user.lamports()
-            .checked_add(amount_out)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.pool_sol_vault.to_account_info(),
+                    to: user.to_account_info(),
+                },
+                vault_signer_seeds,
+            ),
+            user_amount_out,
+        )?;
 
         // Transfer protocol fee to recipient
-        if protocol_fee > 0 {
-            **ctx.accounts.pool_sol_vault.try_borrow_mut_lamports()? = ctx.accounts.pool_sol_vault.lamports()
-                .checked_sub(protocol_fee)
-                .ok_or(BetFunError::ArithmeticOverflow)?;
-
-            **ctx.accounts.protocol_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts.protocol_fee_recipient.lamports()
-                .checked_add(protocol_fee)
-                .ok_or(BetFunError::ArithmeticOverflow)?;
+        if recipient_fee > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.pool_sol_vault.to_account_info(),
+                        to: ctx.accounts.protocol_fee_recipient.to_account_info(),
+                    },
+                    vault_signer_seeds,
+                ),
+                recipient_fee,
+            )?;
         }
-
-        // Update reserves
-        pool.token_reserve = pool.token_reserve
-            .checked_add(amount_in)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-        pool.sol_reserve = pool.sol_reserve
-            .checked_sub(amount_out)
-            .ok_or(BetFunError::ArithmeticOverflow)?
-            .checked_sub(protocol_fee)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-
     } else {
         // SOL -> Token swap
         
@@ -225,52 +287,90 @@ pub fn handler(
                 },
                 signer_seeds,
             ),
-            amount_out,
+            user_amount_out,
         )?;
 
         // Transfer protocol fee to recipient
-        if protocol_fee > 0 {
-            **ctx.accounts.pool_sol_vault.try_borrow_mut_lamports()? = ctx.accounts.pool_sol_vault.lamports()
-                .checked_sub(protocol_fee)
-                .ok_or(BetFunError::ArithmeticOverflow)?;
-
-            **ctx.accounts.protocol_fee_recipient.try_borrow_mut_lamports()? = ctx.accounts.protocol_fee_recipient.lamports()
-                .checked_add(protocol_fee)
-                .ok_or(BetFunError::ArithmeticOverflow)?;
+        if recipient_fee > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.pool_sol_vault.to_account_info(),
+                        to: ctx.accounts.protocol_fee_recipient.to_account_info(),
+                    },
+                    vault_signer_seeds,
+                ),
+                recipient_fee,
+            )?;
         }
+    }
+
+    // Pay the arena creator's trading fee
+    if creator_payment > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.pool_sol_vault.to_account_info(),
+                    to: ctx.accounts.creator.to_account_info(),
+                },
+                vault_signer_seeds,
+            ),
+            creator_payment,
+        )?;
+    }
 
-        // Update reserves
-        pool.sol_reserve = pool.sol_reserve
-            .checked_add(amount_in)
-            .ok_or(BetFunError::ArithmeticOverflow)?
-            .checked_sub(protocol_fee)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
-        pool.token_reserve = pool.token_reserve
-            .checked_sub(amount_out)
-            .ok_or(BetFunError::ArithmeticOverflow)?;
+    // Pay the referrer's share into the referral account
+    if let Some(referral) = ctx.accounts.referral.as_mut() {
+        if referral_reward > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.pool_sol_vault.to_account_info(),
+                        to: referral.to_account_info(),
+                    },
+                    vault_signer_seeds,
+                ),
+                referral_reward,
+            )?;
+            referral.accrue(referral_reward)?;
+        }
     }
 
-    // Update k
+    // Update reserves and k
+    pool.token_reserve = quote.token_reserve_after;
+    pool.sol_reserve = quote.sol_reserve_after;
     pool.k = (pool.token_reserve as u128)
         .checked_mul(pool.sol_reserve as u128)
         .ok_or(BetFunError::ArithmeticOverflow)?;
 
     // Update statistics
-    let volume_sol = if is_token_to_sol { amount_out } else { amount_in };
+    let volume_sol = if is_token_to_sol {
+        user_amount_out + protocol_fee + creator_fee
+    } else {
+        amount_in
+    };
     pool.update_swap_stats(volume_sol, current_time);
     pool.fees_collected = pool.fees_collected
         .checked_add(fee_amount)
         .ok_or(BetFunError::ArithmeticOverflow)?;
 
     let price_after = pool.get_price();
+    ctx.accounts.price_history.record_trade(price_after, volume_sol, current_time);
 
     msg!("Swap executed in pool: {}", pool.key());
     msg!("User: {}", user.key());
     msg!("Direction: {}", if is_token_to_sol { "Token -> SOL" } else { "SOL -> Token" });
     msg!("Amount in: {}", amount_in);
-    msg!("Amount out: {}", amount_out);
+    msg!("Amount out: {}", user_amount_out);
     msg!("Fee: {} ({:.2}%)", fee_amount, pool.fee_bps as f64 / 100.0);
     msg!("Protocol fee: {}", protocol_fee);
+    msg!("Creator fee: {}", creator_fee);
+    if referral_reward > 0 {
+        msg!("Referral reward: {}", referral_reward);
+    }
     msg!("Price impact: {:.2}%", price_impact as f64 / 100.0);
     msg!("Price: {:.6} -> {:.6} SOL", price_before as f64 / 1e9, price_after as f64 / 1e9);
     msg!("New reserves: {} tokens, {} SOL", pool.token_reserve, pool.sol_reserve);
@@ -280,9 +380,10 @@ pub fn handler(
         user: user.key(),
         is_token_to_sol,
         amount_in,
-        amount_out,
+        amount_out: user_amount_out,
         fee_amount,
         protocol_fee,
+        creator_fee,
         price_impact,
         price_before,
         price_after,
@@ -300,6 +401,7 @@ pub struct SwapExecuted {
     pub amount_out: u64,
     pub fee_amount: u64,
     pub protocol_fee: u64,
+    pub creator_fee: u64,
     pub price_impact: u16,
     pub price_before: u64,
     pub price_after: u64,
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalConfig, Versioned};
+use crate::error::BetFunError;
+
+/// Hand the config to a new admin (admin only)
+#[derive(Accounts)]
+pub struct TransferAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<TransferAdmin>, new_admin: Pubkey) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        new_admin != Pubkey::default(),
+        BetFunError::InvalidConfiguration
+    );
+    
+    // ========== UPDATE ==========
+    
+    let previous = config.admin;
+    config.admin = new_admin;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Admin: {} -> {}", previous, new_admin);
+    
+    emit!(AdminTransferred {
+        config: config.key(),
+        previous,
+        admin: new_admin,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub config: Pubkey,
+    pub previous: Pubkey,
+    pub admin: Pubkey,
+}
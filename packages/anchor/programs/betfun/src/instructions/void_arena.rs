@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::state::{Arena, Versioned};
+use crate::error::BetFunError;
+
+/// Void an arena its resolver left unresolved past the resolution window, or
+/// a duel nobody accepted before it locked
+/// Anyone may void; bettors then claim their stakes back through
+/// `claim_winnings`, topped up by the creator's slashed bond (or claim a token
+/// bond with `claim_bond_share`), and share holders redeem their shares for a
+/// refund through `redeem_shares`.
+#[derive(Accounts)]
+pub struct VoidArena<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"arena",
+            arena.creator.as_ref(),
+            arena.title.as_bytes()
+        ],
+        bump = arena.bump,
+        constraint = !arena.resolved @ BetFunError::AlreadyResolved,
+        constraint = !arena.voided @ BetFunError::ArenaVoided,
+        constraint = arena.version == Arena::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub arena: Account<'info, Arena>,
+    
+    pub caller: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<VoidArena>) -> Result<()> {
+    let arena = &mut ctx.accounts.arena;
+    let current_time = Clock::get()?.unix_timestamp;
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        arena.has_positions(),
+        BetFunError::InvalidConfiguration
+    );
+    require!(
+        arena.can_void(current_time),
+        BetFunError::ResolutionWindowOpen
+    );
+    
+    // ========== VOID ==========
+    
+    void(arena, ctx.accounts.caller.key())
+}
+
+/// Void the arena so every bettor is refunded, once the caller has checked it may be
+pub(crate) fn void(arena: &mut Account<Arena>, caller: Pubkey) -> Result<()> {
+    arena.voided = true;
+    
+    // ========== LOGGING ==========
+    
+    msg!("Arena voided: {}", arena.key());
+    msg!("Voided by: {}", caller);
+    match arena.bond_mint {
+        Some(mint) => msg!("Slashed bond: {} base units of {}", arena.creator_bond, mint),
+        None => msg!("Slashed bond: {} lamports ({:.4} SOL)", arena.creator_bond, arena.creator_bond as f64 / 1e9),
+    }
+    
+    emit!(ArenaVoided {
+        arena: arena.key(),
+        creator: arena.creator,
+        caller,
+        pot: arena.pot,
+        slashed_bond: arena.creator_bond,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ArenaVoided {
+    pub arena: Pubkey,
+    pub creator: Pubkey,
+    pub caller: Pubkey,
+    pub pot: u64,
+    pub slashed_bond: u64,
+}
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::state::{GlobalConfig, ParlayVault, Versioned};
+use crate::error::BetFunError;
+
+/// Take house liquidity not reserved for open parlays out of the vault (admin only)
+#[derive(Accounts)]
+pub struct WithdrawParlayVault<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.admin == admin.key() @ BetFunError::Unauthorized,
+        constraint = config.version == GlobalConfig::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub config: Account<'info, GlobalConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"parlay_vault"],
+        bump = parlay_vault.bump,
+        constraint = parlay_vault.version == ParlayVault::VERSION @ BetFunError::UnsupportedAccountVersion,
+    )]
+    pub parlay_vault: Account<'info, ParlayVault>,
+    
+    #[account(mut)]
+    pub admin: Signer<'info>,
+}
+
+pub fn handler(ctx: Context<WithdrawParlayVault>, amount: u64) -> Result<()> {
+    let vault = &ctx.accounts.parlay_vault;
+    let vault_info = vault.to_account_info();
+    
+    // ========== VALIDATION ==========
+    
+    require!(
+        amount > 0,
+        BetFunError::InvalidAmount
+    );
+    
+    // Payouts owed to open parlays stay in the vault
+    let rent_exempt_min = Rent::get()?.minimum_balance(vault_info.data_len());
+    require!(
+        vault.free_liquidity(vault_info.lamports(), rent_exempt_min) >= amount,
+        BetFunError::InsufficientLiquidity
+    );
+    
+    // ========== WITHDRAW ==========
+    
+    **vault_info.try_borrow_mut_lamports()? = vault_info.lamports()
+        .checked_sub(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    **ctx.accounts.admin.to_account_info().try_borrow_mut_lamports()? = ctx.accounts.admin.lamports()
+        .checked_add(amount)
+        .ok_or(BetFunError::ArithmeticOverflow)?;
+    
+    let free_liquidity = vault.free_liquidity(vault_info.lamports(), rent_exempt_min);
+    
+    // ========== LOGGING ==========
+    
+    msg!("Parlay vault withdrawal");
+    msg!("Amount: {} lamports ({:.4} SOL)", amount, amount as f64 / 1e9);
+    msg!("Free liquidity: {} lamports", free_liquidity);
+    
+    emit!(ParlayVaultWithdrawn {
+        vault: vault.key(),
+        admin: ctx.accounts.admin.key(),
+        amount,
+        free_liquidity,
+    });
+    
+    Ok(())
+}
+
+#[event]
+pub struct ParlayVaultWithdrawn {
+    pub vault: Pubkey,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub free_liquidity: u64,
+}
@@ -1,3 +1,7 @@
+// `create_arena` takes its configuration as plain arguments, and Anchor mirrors
+// that list in the generated `cpi` module
+#![allow(clippy::too_many_arguments)]
+
 use anchor_lang::prelude::*;
 
 pub mod error;
@@ -5,6 +9,7 @@ pub mod instructions;
 pub mod state;
 
 use instructions::*;
+use state::{CurveType, Duel, ScalarRange, StakeWeighting};
 
 declare_id!("HrS1KpYRWfg9xUom8jnGqoRAayVCxHxukeb18C4WKAkE");
 
@@ -12,7 +17,63 @@ declare_id!("HrS1KpYRWfg9xUom8jnGqoRAayVCxHxukeb18C4WKAkE");
 pub mod betfun {
     use super::*;
 
-    /// Create a new prediction arena
+    /// Create the program-wide config; the program's upgrade authority becomes its admin
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+    ) -> Result<()> {
+        instructions::initialize_config::handler(ctx)
+    }
+
+    /// Pause or unpause activities across every arena (admin only)
+    pub fn set_global_pause(
+        ctx: Context<SetGlobalPause>,
+        paused: u8,
+    ) -> Result<()> {
+        instructions::set_global_pause::handler(ctx, paused)
+    }
+
+    /// Pause or unpause activities in one arena (creator or admin)
+    pub fn set_arena_pause(
+        ctx: Context<SetArenaPause>,
+        paused: u8,
+    ) -> Result<()> {
+        instructions::set_arena_pause::handler(ctx, paused)
+    }
+
+    /// Set the share of creator and protocol fees paid to referrers (admin only)
+    pub fn set_referral_share(
+        ctx: Context<SetReferralShare>,
+        share_bps: u16,
+    ) -> Result<()> {
+        instructions::set_referral_share::handler(ctx, share_bps)
+    }
+
+    /// Set the bond creators lock in each new arena (admin only)
+    pub fn set_creator_bond(
+        ctx: Context<SetCreatorBond>,
+        bond: u64,
+    ) -> Result<()> {
+        instructions::set_creator_bond::handler(ctx, bond)
+    }
+
+    /// Set who receives protocol fees and the order book's fee per side (admin only)
+    pub fn set_protocol_fees(
+        ctx: Context<SetProtocolFees>,
+        fee_recipient: Pubkey,
+        order_book_fee_bps: u16,
+    ) -> Result<()> {
+        instructions::set_protocol_fees::handler(ctx, fee_recipient, order_book_fee_bps)
+    }
+
+    /// Hand the config to a new admin (admin only)
+    pub fn transfer_admin(
+        ctx: Context<TransferAdmin>,
+        new_admin: Pubkey,
+    ) -> Result<()> {
+        instructions::transfer_admin::handler(ctx, new_admin)
+    }
+
+    /// Create a new prediction arena, or a duel opened with the creator's own stake
     pub fn create_arena(
         ctx: Context<CreateArena>,
         title: String,
@@ -21,10 +82,17 @@ pub mod betfun {
         outcomes: Vec<String>,
         tags: Vec<String>,
         entry_fee: u64,
-        end_time: i64,
+        lock_time: i64,
+        resolve_after: i64,
         manual_resolve: bool,
         oracle: Option<Pubkey>,
         token_mint: Option<Pubkey>,
+        creator_fee_bps: u16,
+        creator_trading_fee_bps: u16,
+        scalar: Option<ScalarRange>,
+        allowlist_root: Option<[u8; 32]>,
+        stake_weighting: StakeWeighting,
+        duel: Option<Duel>,
     ) -> Result<()> {
         instructions::create_arena::handler(
             ctx,
@@ -34,19 +102,35 @@ pub mod betfun {
             outcomes,
             tags,
             entry_fee,
-            end_time,
+            lock_time,
+            resolve_after,
             manual_resolve,
             oracle,
             token_mint,
+            creator_fee_bps,
+            creator_trading_fee_bps,
+            scalar,
+            allowlist_root,
+            stake_weighting,
+            duel,
         )
     }
 
-    /// Join an arena by betting on an outcome
+    /// Join an arena by betting on an outcome; private arenas need an allowlist proof
     pub fn join_arena(
         ctx: Context<JoinArena>,
         outcome_chosen: u8,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
-        instructions::join_arena::handler(ctx, outcome_chosen)
+        instructions::join_arena::handler(ctx, outcome_chosen, proof)
+    }
+    
+    /// Replace a private arena's allowlist, or make an arena public or private (creator only)
+    pub fn set_allowlist(
+        ctx: Context<SetAllowlist>,
+        allowlist_root: Option<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::set_allowlist::handler(ctx, allowlist_root)
     }
 
     /// Resolve an arena (creator or oracle only)
@@ -57,17 +141,64 @@ pub mod betfun {
         instructions::resolve_arena::handler(ctx, winner_outcome)
     }
 
-    /// Claim winnings after arena is resolved
+    /// Resolve a scalar arena at a reported value (creator or oracle only)
+    pub fn resolve_scalar_arena(
+        ctx: Context<ResolveScalarArena>,
+        value: i64,
+    ) -> Result<()> {
+        instructions::resolve_scalar_arena::handler(ctx, value)
+    }
+
+    /// Void an arena left unresolved past its resolution window (anyone)
+    pub fn void_arena(ctx: Context<VoidArena>) -> Result<()> {
+        instructions::void_arena::handler(ctx)
+    }
+
+    /// Withdraw a duel nobody has accepted yet, refunding the challenger's stake and bond (creator only)
+    pub fn cancel_duel(ctx: Context<CancelDuel>) -> Result<()> {
+        instructions::cancel_duel::handler(ctx)
+    }
+
+    /// Claim winnings after arena is resolved, or a refund after it is voided
     pub fn claim_winnings(ctx: Context<ClaimWinnings>) -> Result<()> {
         instructions::claim_winnings::handler(ctx)
     }
 
-    /// Record trophy mint for winner
-    pub fn mint_trophy(
-        ctx: Context<MintTrophy>,
-        trophy_mint: Pubkey,
-    ) -> Result<()> {
-        instructions::mint_trophy::handler(ctx, trophy_mint)
+    /// Claim winnings, refunds and winning shares from many arenas at once:
+    /// bets as 3 remaining accounts, share positions as 5, skipping entries
+    /// that have nothing to claim
+    pub fn claim_many<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimMany<'info>>) -> Result<()> {
+        instructions::claim_many::handler(ctx)
+    }
+
+    /// Book a wallet's losing bet and shares in a resolved arena into its profile
+    pub fn settle_profile(ctx: Context<SettleProfile>) -> Result<()> {
+        instructions::settle_profile::handler(ctx)
+    }
+
+    /// Return the creator's bond from a resolved arena nobody claimed from, or in a token (anyone)
+    pub fn release_creator_bond(ctx: Context<ReleaseCreatorBond>) -> Result<()> {
+        instructions::release_creator_bond::handler(ctx)
+    }
+
+    /// Claim a bettor's part of a creator bond slashed by a dispute, or in a token by a void
+    pub fn claim_bond_share(ctx: Context<ClaimBondShare>) -> Result<()> {
+        instructions::claim_bond_share::handler(ctx)
+    }
+
+    /// Dispute a bonded arena's resolution, depositing one entry fee (anyone but the creator)
+    pub fn dispute_arena(ctx: Context<DisputeArena>) -> Result<()> {
+        instructions::dispute_arena::handler(ctx)
+    }
+
+    /// Uphold an open dispute, slashing the creator bond, or reject it (admin only)
+    pub fn settle_dispute(ctx: Context<SettleDispute>, upheld: bool) -> Result<()> {
+        instructions::settle_dispute::handler(ctx, upheld)
+    }
+
+    /// Mint a Token-2022 trophy NFT to a winner who has claimed
+    pub fn mint_trophy(ctx: Context<MintTrophy>) -> Result<()> {
+        instructions::mint_trophy::handler(ctx)
     }
 
     /// Create SPL token mints for each outcome of an arena
@@ -75,34 +206,55 @@ pub mod betfun {
         ctx: Context<CreateShareTokens>,
         outcome_index: u8,
         initial_price: u64,
+        curve_type: CurveType,
+        curve_param: u64,
     ) -> Result<()> {
-        instructions::create_share_tokens::handler(ctx, outcome_index, initial_price)
+        instructions::create_share_tokens::handler(ctx, outcome_index, initial_price, curve_type, curve_param)
     }
 
-    /// Buy outcome shares using SOL
+    /// Buy outcome shares along the outcome's bonding curve; private arenas need an allowlist proof
     pub fn buy_shares(
         ctx: Context<BuyShares>,
         _outcome_index: u8,
-        amount_sol: u64,
+        amount: u64,
+        max_total_cost: Option<u64>,
+        deadline_ts: Option<i64>,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
-        instructions::buy_shares::handler(ctx, amount_sol)
+        instructions::buy_shares::handler(ctx, amount, max_total_cost, deadline_ts, proof)
     }
 
-    /// Sell outcome shares for SOL
+    /// Sell outcome shares back along the outcome's bonding curve
     pub fn sell_shares(
         ctx: Context<SellShares>,
         _outcome_index: u8,
         shares_to_sell: u64,
+        min_total_proceeds: Option<u64>,
+        deadline_ts: Option<i64>,
     ) -> Result<()> {
-        instructions::sell_shares::handler(ctx, shares_to_sell)
+        instructions::sell_shares::handler(ctx, shares_to_sell, min_total_proceeds, deadline_ts)
     }
 
-    /// Redeem winning outcome shares for SOL after arena resolution
+    /// Redeem winning outcome shares for SOL after arena resolution, or any shares of a voided arena
     pub fn redeem_shares(
         ctx: Context<RedeemShares>,
-        outcome_index: u8,
+        _outcome_index: u8,
+        amount: u64,
+        min_total_proceeds: Option<u64>,
+        deadline_ts: Option<i64>,
+    ) -> Result<()> {
+        instructions::redeem_shares::handler(ctx, amount, min_total_proceeds, deadline_ts)
+    }
+
+    /// Mint LONG/SHORT pairs of a scalar arena against 1 SOL of collateral each
+    pub fn mint_complete_sets(
+        ctx: Context<MintCompleteSets>,
+        amount: u64,
+        max_cost: u64,
+        deadline_ts: Option<i64>,
+        proof: Vec<[u8; 32]>,
     ) -> Result<()> {
-        instructions::redeem_shares::handler(ctx, outcome_index as u64)
+        instructions::mint_complete_sets::handler(ctx, amount, max_cost, deadline_ts, proof)
     }
 
     /// Initialize an AMM pool for an outcome
@@ -145,6 +297,61 @@ pub mod betfun {
         instructions::swap::handler(ctx, amount_in, min_amount_out, is_token_to_sol)
     }
 
+    /// Withdraw the fees a referred user has earned their referrer
+    pub fn claim_referral_rewards(
+        ctx: Context<ClaimReferralRewards>,
+    ) -> Result<()> {
+        instructions::claim_referral_rewards::handler(ctx)
+    }
+
+    /// Add house liquidity backing parlay payouts (admin only)
+    pub fn fund_parlay_vault(
+        ctx: Context<FundParlayVault>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_parlay_vault::handler(ctx, amount)
+    }
+
+    /// Withdraw parlay vault liquidity not owed to open parlays (admin only)
+    pub fn withdraw_parlay_vault(
+        ctx: Context<WithdrawParlayVault>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_parlay_vault::handler(ctx, amount)
+    }
+
+    /// Allow or stop an arena being picked as a parlay leg (admin only)
+    pub fn set_parlay_approval(
+        ctx: Context<SetParlayApproval>,
+        approved: bool,
+    ) -> Result<()> {
+        instructions::set_parlay_approval::handler(ctx, approved)
+    }
+
+    /// Bet on one outcome in each of several arenas, passed as remaining accounts
+    pub fn place_parlay(
+        ctx: Context<PlaceParlay>,
+        parlay_id: u64,
+        picks: Vec<u8>,
+        stake: u64,
+        min_payout: u64,
+    ) -> Result<()> {
+        instructions::place_parlay::handler(ctx, parlay_id, picks, stake, min_payout)
+    }
+
+    /// Pay out a winning parlay or close out a losing one (anyone may call)
+    pub fn settle_parlay(ctx: Context<SettleParlay>) -> Result<()> {
+        instructions::settle_parlay::handler(ctx)
+    }
+
+    /// Initialize the limit order book for an outcome
+    pub fn initialize_order_book(
+        ctx: Context<InitializeOrderBook>,
+        outcome_index: u8,
+    ) -> Result<()> {
+        instructions::initialize_order_book::handler(ctx, outcome_index)
+    }
+
     /// Place a limit order in the order book
     pub fn place_limit_order(
         ctx: Context<PlaceLimitOrder>,
@@ -166,9 +373,76 @@ pub mod betfun {
         ctx: Context<SettleMatch>,
         match_size: u64,
         match_price: u64,
-        fee_bps: u16,
     ) -> Result<()> {
-        instructions::settle_match::handler(ctx, match_size, match_price, fee_bps)
+        instructions::settle_match::handler(ctx, match_size, match_price)
+    }
+
+    /// Open a single-elimination tournament of linked match arenas
+    pub fn create_tournament(
+        ctx: Context<CreateTournament>,
+        name: String,
+        entrants: Vec<String>,
+        entry_fee: u64,
+        match_entry_fee: u64,
+        lock_time: i64,
+        round_duration: i64,
+    ) -> Result<()> {
+        instructions::create_tournament::handler(
+            ctx,
+            name,
+            entrants,
+            entry_fee,
+            match_entry_fee,
+            lock_time,
+            round_duration,
+        )
+    }
+
+    /// Pay into a tournament's pot before its first round locks
+    pub fn enter_tournament(ctx: Context<EnterTournament>) -> Result<()> {
+        instructions::enter_tournament::handler(ctx)
+    }
+
+    /// Create the match arena of a bracket slot once its competitors are known (anyone)
+    pub fn advance_tournament(
+        ctx: Context<AdvanceTournament>,
+        slot: u8,
+    ) -> Result<()> {
+        instructions::advance_tournament::handler(ctx, slot)
+    }
+
+    /// Decide a bracket slot and settle its match arena (organizer only)
+    pub fn resolve_tournament_match(
+        ctx: Context<ResolveTournamentMatch>,
+        slot: u8,
+        winner_outcome: u8,
+    ) -> Result<()> {
+        instructions::resolve_tournament_match::handler(ctx, slot, winner_outcome)
+    }
+
+    /// Cancel a tournament whose match was voided undecided (anyone)
+    pub fn cancel_tournament(
+        ctx: Context<CancelTournament>,
+        slot: u8,
+    ) -> Result<()> {
+        instructions::cancel_tournament::handler(ctx, slot)
+    }
+
+    /// Score an entry by its picks in the match arenas
+    pub fn submit_tournament_score(ctx: Context<SubmitTournamentScore>) -> Result<()> {
+        instructions::submit_tournament_score::handler(ctx)
+    }
+
+    /// Claim a share of the tournament pot, or a refund
+    pub fn claim_tournament_prize(ctx: Context<ClaimTournamentPrize>) -> Result<()> {
+        instructions::claim_tournament_prize::handler(ctx)
+    }
+
+    /// Upgrade any program account to its current layout version
+    pub fn migrate_account(
+        ctx: Context<MigrateAccount>,
+    ) -> Result<()> {
+        instructions::migrate_account::handler(ctx)
     }
 }
 
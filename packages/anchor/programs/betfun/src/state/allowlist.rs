@@ -0,0 +1,23 @@
+//! Merkle allowlists of the wallets that may bet in a private arena
+//!
+//! Leaves are `sha256(0x00 || wallet)` and parents `sha256(0x01 || a || b)`
+//! with `a <= b`, so a proof is the sibling hashes from the leaf up, in any
+//! order of siblings within a pair. The prefixes keep a parent from passing
+//! as a leaf.
+
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+pub fn leaf(wallet: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0x00][..], wallet.as_ref()]).to_bytes()
+}
+
+pub fn parent(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[&[0x01][..], low.as_ref(), high.as_ref()]).to_bytes()
+}
+
+/// Whether `proof` places `wallet` in the tree with `root`
+pub fn verify(root: &[u8; 32], wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    proof.iter().fold(leaf(wallet), |node, sibling| parent(&node, sibling)) == *root
+}
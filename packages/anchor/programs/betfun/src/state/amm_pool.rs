@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use betfun_math::amm;
+use crate::error::BetFunError;
 
 /// Automated Market Maker (AMM) pool for outcome shares
 /// Uses constant product formula: x * y = k
@@ -62,6 +64,9 @@ pub struct AMMPool {
     
     /// Bump seed
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
 }
 
 impl AMMPool {
@@ -82,18 +87,28 @@ impl AMMPool {
         8 +  // last_swap_at
         8 +  // last_price
         8 +  // price_24h_ago
-        1;   // bump
+        1 +  // bump
+        1;   // version
+    
+    /// Pricing view of this pool, shared with off-chain quoting
+    /// Swaps set `creator_fee_bps` from the arena; it is zero here.
+    pub fn math(&self) -> amm::Pool {
+        amm::Pool {
+            token_reserve: self.token_reserve,
+            sol_reserve: self.sol_reserve,
+            total_lp_tokens: self.total_lp_tokens,
+            fee_bps: self.fee_bps,
+            protocol_fee_bps: self.protocol_fee_bps,
+            creator_fee_bps: 0,
+        }
+    }
     
     /// Get current price (SOL per token)
     pub fn get_price(&self) -> u64 {
-        if self.token_reserve == 0 {
-            return 0;
-        }
-        // Price = sol_reserve / token_reserve
-        ((self.sol_reserve as u128 * 1_000_000_000) / self.token_reserve as u128) as u64
+        amm::spot_price(self.token_reserve, self.sol_reserve)
     }
     
-    /// Calculate output amount for a swap
+    /// Calculate output amount for a swap, before the protocol fee
     /// Uses formula: dy = (y * dx) / (x + dx)
     /// With fee: dy = (y * dx * (10000 - fee)) / ((x + dx) * 10000)
     pub fn get_amount_out(
@@ -102,22 +117,8 @@ impl AMMPool {
         reserve_in: u64,
         reserve_out: u64,
     ) -> Result<u64> {
-        require!(amount_in > 0, ErrorCode::InvalidAmount);
-        require!(reserve_in > 0 && reserve_out > 0, ErrorCode::InsufficientLiquidity);
-        
-        // Apply fee
-        let amount_in_with_fee = (amount_in as u128 * (10000 - self.fee_bps) as u128) / 10000;
-        
-        // Calculate output
-        let numerator = amount_in_with_fee * reserve_out as u128;
-        let denominator = (reserve_in as u128) + amount_in_with_fee;
-        
-        let amount_out = numerator / denominator;
-        
-        require!(amount_out > 0, ErrorCode::InsufficientOutputAmount);
-        require!(amount_out <= u64::MAX as u128, ErrorCode::ArithmeticOverflow);
-        
-        Ok(amount_out as u64)
+        Ok(amm::amount_out(amount_in, reserve_in, reserve_out, self.fee_bps)
+            .map_err(BetFunError::from)?)
     }
     
     /// Calculate price impact for a swap
@@ -127,35 +128,10 @@ impl AMMPool {
         amount_in: u64,
         is_token_to_sol: bool,
     ) -> Result<u16> {
-        let current_price = self.get_price();
-        
-        let (reserve_in, reserve_out) = if is_token_to_sol {
-            (self.token_reserve, self.sol_reserve)
-        } else {
-            (self.sol_reserve, self.token_reserve)
-        };
-        
-        let amount_out = self.get_amount_out(amount_in, reserve_in, reserve_out)?;
-        
-        // New price after swap
-        let new_price = if is_token_to_sol {
-            let new_sol = self.sol_reserve - amount_out;
-            let new_tokens = self.token_reserve + amount_in;
-            ((new_sol as u128 * 1_000_000_000) / new_tokens as u128) as u64
-        } else {
-            let new_sol = self.sol_reserve + amount_in;
-            let new_tokens = self.token_reserve - amount_out;
-            ((new_sol as u128 * 1_000_000_000) / new_tokens as u128) as u64
-        };
-        
-        // Calculate impact in basis points
-        let impact = if new_price > current_price {
-            ((new_price - current_price) as u128 * 10000) / current_price as u128
-        } else {
-            ((current_price - new_price) as u128 * 10000) / current_price as u128
-        };
-        
-        Ok(impact.min(10000) as u16)
+        let quote = self.math()
+            .quote_swap(amount_in, is_token_to_sol)
+            .map_err(BetFunError::from)?;
+        Ok(quote.price_impact_bps)
     }
     
     /// Calculate LP tokens to mint when adding liquidity
@@ -164,28 +140,9 @@ impl AMMPool {
         token_amount: u64,
         sol_amount: u64,
     ) -> Result<u64> {
-        if self.total_lp_tokens == 0 {
-            // First liquidity provider
-            // LP tokens = sqrt(token_amount * sol_amount)
-            let product = (token_amount as u128) * (sol_amount as u128);
-            let lp_tokens = (product as f64).sqrt() as u64;
-            require!(lp_tokens > 0, ErrorCode::InsufficientLiquidityMinted);
-            Ok(lp_tokens)
-        } else {
-            // Subsequent liquidity providers
-            // LP tokens = min(
-            //   total_lp * token_amount / token_reserve,
-            //   total_lp * sol_amount / sol_reserve
-            // )
-            let lp_from_tokens = ((self.total_lp_tokens as u128 * token_amount as u128) 
-                / self.token_reserve as u128) as u64;
-            let lp_from_sol = ((self.total_lp_tokens as u128 * sol_amount as u128) 
-                / self.sol_reserve as u128) as u64;
-            
-            let lp_tokens = lp_from_tokens.min(lp_from_sol);
-            require!(lp_tokens > 0, ErrorCode::InsufficientLiquidityMinted);
-            Ok(lp_tokens)
-        }
+        Ok(self.math()
+            .lp_tokens_for(token_amount, sol_amount)
+            .map_err(BetFunError::from)?)
     }
     
     /// Calculate amounts to withdraw when removing liquidity
@@ -193,32 +150,23 @@ impl AMMPool {
         &self,
         lp_tokens: u64,
     ) -> Result<(u64, u64)> {
-        require!(lp_tokens > 0, ErrorCode::InvalidAmount);
-        require!(lp_tokens <= self.total_lp_tokens, ErrorCode::InsufficientLiquidity);
-        
-        // token_amount = token_reserve * lp_tokens / total_lp_tokens
-        let token_amount = ((self.token_reserve as u128 * lp_tokens as u128) 
-            / self.total_lp_tokens as u128) as u64;
-        
-        // sol_amount = sol_reserve * lp_tokens / total_lp_tokens
-        let sol_amount = ((self.sol_reserve as u128 * lp_tokens as u128) 
-            / self.total_lp_tokens as u128) as u64;
-        
-        Ok((token_amount, sol_amount))
+        let quote = self.math()
+            .quote_remove_liquidity(lp_tokens)
+            .map_err(BetFunError::from)?;
+        Ok((quote.token_amount, quote.sol_amount))
     }
     
     /// Update pool statistics after a swap
     pub fn update_swap_stats(&mut self, volume: u64, current_time: i64) {
-        self.swap_count += 1;
-        self.volume_24h = self.volume_24h.saturating_add(volume);
-        self.last_price = self.get_price();
-        
-        // Reset 24h stats if more than 24 hours passed
+        // Reset 24h stats if more than 24 hours passed since the previous swap
         if current_time - self.last_swap_at > 86400 {
-            self.volume_24h = volume;
+            self.volume_24h = 0;
             self.price_24h_ago = self.last_price;
         }
         
+        self.swap_count += 1;
+        self.volume_24h = self.volume_24h.saturating_add(volume);
+        self.last_price = self.get_price();
         self.last_swap_at = current_time;
     }
     
@@ -233,6 +181,8 @@ impl AMMPool {
     }
 }
 
+pub use betfun_math::isqrt;
+
 /// Liquidity provider position
 #[account]
 pub struct LiquidityPosition {
@@ -259,6 +209,9 @@ pub struct LiquidityPosition {
     
     /// Bump seed
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
 }
 
 impl LiquidityPosition {
@@ -270,7 +223,8 @@ impl LiquidityPosition {
         8 +  // sol_deposited
         8 +  // created_at
         8 +  // fees_earned
-        1;   // bump
+        1 +  // bump
+        1;   // version
     
     /// Calculate current value of position
     pub fn calculate_value(&self, pool: &AMMPool) -> Result<(u64, u64)> {
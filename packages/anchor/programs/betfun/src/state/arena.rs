@@ -1,11 +1,13 @@
 use anchor_lang::prelude::*;
+use betfun_math::payout;
+use crate::state::{allowlist, OutcomeShare, Participant};
 
 #[account]
 pub struct Arena {
     /// Arena creator
     pub creator: Pubkey,
     
-    /// Arena title (max 80 chars)
+    /// Arena title (max 32 bytes, the PDA seed limit)
     pub title: String,
     
     /// Arena description (max 280 chars)
@@ -41,10 +43,10 @@ pub struct Arena {
     /// Winning outcome index (if resolved)
     pub winner_outcome: Option<u8>,
     
-    /// End timestamp (Unix)
-    pub end_time: i64,
+    /// Unix timestamp betting and share trading stop
+    pub lock_time: i64,
     
-    /// Manual resolution flag
+    /// Resolved by hand by the creator or oracle, with no fixed schedule for the result
     pub manual_resolve: bool,
     
     /// Oracle pubkey (for automated resolution)
@@ -61,10 +63,113 @@ pub struct Arena {
     
     /// Bump for PDA
     pub bump: u8,
+    
+    /// Whether the creator fee has been paid out (on the first claim)
+    pub creator_fee_paid: bool,
+    
+    /// Lamports paid out of the pot so far (winnings, creator and referral fees)
+    pub total_paid_out: u64,
+    
+    /// Account layout version
+    pub version: u8,
+    
+    /// `pause` flags in effect for this arena only
+    pub paused: u8,
+    
+    /// Creator fee already paid to referrers out of the escrow
+    pub referral_fees: u64,
+    
+    /// Creator fee on share trading (buy_shares, swap, settle_match), in basis points
+    pub creator_trading_fee_bps: u16,
+    
+    /// Bounds of a scalar arena, whose outcomes are LONG and SHORT
+    pub scalar: Option<ScalarRange>,
+    
+    /// Value a scalar arena resolved at, clamped to its range
+    pub resolved_value: Option<i64>,
+    
+    /// Merkle root of the wallets allowed to join and buy shares (`None` for a public arena)
+    pub allowlist_root: Option<[u8; 32]>,
+    
+    /// Bond the creator locked at creation, still held by the arena
+    pub creator_bond: u64,
+    
+    /// Voided for non-resolution: stakes and the slashed bond are refunded
+    pub voided: bool,
+    
+    /// Earliest Unix timestamp the arena can be resolved, at or after `lock_time`
+    /// (0 for arenas created before it existed, see `resolvable_from`)
+    pub resolve_after: i64,
+    
+    /// How winning stakes are weighted when the pot is split
+    pub stake_weighting: StakeWeighting,
+    
+    /// Weighted stake per outcome (empty for a flat arena)
+    pub outcome_weights: Vec<u64>,
+    
+    /// Head-to-head challenge between the creator and one opponent (`None` for an open pool)
+    pub duel: Option<Duel>,
+    
+    /// Share base units outstanding across all outcomes (LONG and SHORT count separately)
+    pub shares_outstanding: u64,
+    
+    /// Approved by the config admin as a parlay leg
+    pub parlay_approved: bool,
+    
+    /// Mint of a bond held in the arena's bond vault (`None` for a bond in lamports)
+    pub bond_mint: Option<Pubkey>,
+    
+    /// Unix timestamp of the resolution, which opens the dispute window (0 until resolved)
+    pub resolved_at: i64,
+    
+    /// Challenge to the resolution, settled by the config admin
+    pub dispute: Dispute,
+}
+
+/// Range a scalar arena's value is reported in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScalarRange {
+    pub lower: i64,
+    pub upper: i64,
+}
+
+/// Terms of a duel: the creator stakes one side as they create it, then a
+/// single opponent matches the stake on the other
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Duel {
+    /// Only wallet that may accept (`None` for a challenge open to anyone)
+    pub opponent: Option<Pubkey>,
+    /// Outcome the creator backs
+    pub side: u8,
+}
+
+/// Challenge to an arena's resolution
+/// `Undisputed` is tagged 0, so arenas created before disputes existed read as undisputed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Dispute {
+    #[default]
+    Undisputed,
+    /// Awaiting the admin's ruling, with `disputer`'s deposit of one entry fee held by the arena
+    Open { disputer: Pubkey },
+    /// The resolution stands and the deposit went to the creator
+    Rejected,
+    /// The resolution was wrong: the deposit went back and the bond is slashed to bettors
+    Upheld,
+}
+
+/// Weighting of pari-mutuel stakes
+/// `Flat` is tagged 0, so arenas created before weighting existed read as flat.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum StakeWeighting {
+    /// Every staked lamport weighs the same
+    #[default]
+    Flat,
+    /// Stakes weigh up to `bonus_bps` extra, decaying linearly from creation to lock
+    LinearDecay { bonus_bps: u16 },
 }
 
 impl Arena {
-    pub const MAX_TITLE_LEN: usize = 80;
+    pub const MAX_TITLE_LEN: usize = 32;
     pub const MAX_DESC_LEN: usize = 280;
     pub const MAX_QUESTION_LEN: usize = 200;
     pub const MAX_OUTCOME_LEN: usize = 40;
@@ -72,7 +177,24 @@ impl Arena {
     pub const MIN_OUTCOMES: usize = 2;
     pub const MAX_TAGS: usize = 5;
     pub const MAX_TAG_LEN: usize = 20;
+    pub const MIN_ENTRY_FEE: u64 = 1_000_000; // 0.001 SOL
+    pub const MAX_ENTRY_FEE: u64 = 10_000_000_000; // 10 SOL
     pub const DEFAULT_CREATOR_FEE_BPS: u16 = 500; // 5%
+    pub const MAX_CREATOR_FEE_BPS: u16 = 1_000; // 10%
+    pub const MAX_CREATOR_TRADING_FEE_BPS: u16 = 200; // 2%
+    pub const MAX_EARLY_BONUS_BPS: u16 = 10_000; // 100%
+    
+    /// Outcome index of a scalar arena's LONG shares
+    pub const LONG: u8 = 0;
+    /// Outcome index of a scalar arena's SHORT shares
+    pub const SHORT: u8 = 1;
+    pub const SCALAR_OUTCOMES: [&'static str; 2] = ["LONG", "SHORT"];
+    
+    /// Time the resolver has after `resolve_after` before anyone can void the arena
+    pub const RESOLUTION_WINDOW: i64 = 7 * 24 * 60 * 60;
+    
+    /// Time after the resolution during which a bonded arena can be disputed
+    pub const DISPUTE_WINDOW: i64 = 2 * 24 * 60 * 60;
     
     /// Calculate space needed for Arena account
     pub fn space(
@@ -96,54 +218,281 @@ impl Arena {
         4 + (outcomes_count * 8) + // outcome_pots Vec<u64>
         1 + // resolved bool
         1 + 1 + // winner_outcome Option<u8>
-        8 + // end_time
+        8 + // lock_time
         1 + // manual_resolve
         1 + 32 + // oracle Option<Pubkey>
         1 + 32 + // token_mint Option<Pubkey>
         2 + // creator_fee_bps
         8 + // created_at
         1 + // bump
-        128 // padding for future fields
+        1 + // creator_fee_paid
+        8 + // total_paid_out
+        1 + // version
+        1 + // paused
+        8 + // referral_fees
+        2 + // creator_trading_fee_bps
+        1 + 16 + // scalar Option<ScalarRange>
+        1 + 8 + // resolved_value Option<i64>
+        1 + 32 + // allowlist_root Option<[u8; 32]>
+        8 + // creator_bond
+        1 + // voided
+        8 + // resolve_after
+        1 + 2 + // stake_weighting StakeWeighting
+        4 + (outcomes_count * 8) + // outcome_weights Vec<u64>
+        1 + 1 + 32 + 1 + // duel Option<Duel>
+        8 + // shares_outstanding
+        1 + // parlay_approved
+        1 + 32 + // bond_mint Option<Pubkey>
+        8 + // resolved_at
+        1 + 32 + // dispute Dispute
+        32 // padding for future fields
+    }
+    
+    /// Whether any of `flags` is paused for this arena
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+    
+    /// Whether the arena is a scalar (numeric range) market
+    pub fn is_scalar(&self) -> bool {
+        self.scalar.is_some()
+    }
+    
+    /// Whether `wallet` may join and buy shares, proving membership of a private arena's allowlist
+    pub fn is_allowed(&self, wallet: &Pubkey, proof: &[[u8; 32]]) -> bool {
+        match &self.allowlist_root {
+            Some(root) => allowlist::verify(root, wallet, proof),
+            None => true,
+        }
     }
     
-    /// Check if arena has ended
-    pub fn has_ended(&self, current_time: i64) -> bool {
-        !self.manual_resolve && current_time >= self.end_time
+    /// Whether the arena is a duel still waiting for its opponent's stake
+    pub fn is_unaccepted_duel(&self) -> bool {
+        self.duel.is_some() && self.participants_count < 2
+    }
+    
+    /// Whether betting and share trading have stopped
+    pub fn is_locked(&self, current_time: i64) -> bool {
+        current_time >= self.lock_time
+    }
+    
+    /// Earliest time the arena can be resolved
+    /// Arenas created before `resolve_after` existed resolve as they used to:
+    /// manual ones at any time, others once betting locks.
+    pub fn resolvable_from(&self) -> i64 {
+        match self.resolve_after {
+            0 if self.manual_resolve => self.created_at,
+            0 => self.lock_time,
+            resolve_after => resolve_after,
+        }
+    }
+    
+    /// Whether the creator or oracle may resolve the arena now
+    pub fn can_resolve(&self, current_time: i64) -> bool {
+        current_time >= self.resolvable_from()
+    }
+    
+    /// Whether the resolver missed the resolution window, or a challenge went
+    /// unaccepted until the lock, so anyone may void the arena
+    /// Arenas with neither bets nor shares have nothing to refund and are never voided.
+    pub fn can_void(&self, current_time: i64) -> bool {
+        let deadline = if self.is_unaccepted_duel() {
+            self.lock_time
+        } else {
+            self.lock_time.max(self.resolve_after).saturating_add(Self::RESOLUTION_WINDOW)
+        };
+        !self.resolved && !self.voided && self.has_positions() && current_time >= deadline
+    }
+    
+    /// Whether anyone holds a bet or shares in the arena
+    pub fn has_positions(&self) -> bool {
+        self.participants_count > 0 || self.shares_outstanding > 0
+    }
+    
+    /// Bond lamports the arena holds (none when the bond is in a token vault)
+    pub fn lamport_bond(&self) -> u64 {
+        if self.bond_mint.is_some() {
+            0
+        } else {
+            self.creator_bond
+        }
+    }
+    
+    /// Whether bettors claim their part of a slashed bond with `claim_bond_share`:
+    /// a voided arena's token bond, which refunds only carrying lamports leave
+    /// out, or any bond of an arena whose dispute was upheld
+    pub fn has_bond_claims(&self) -> bool {
+        let slashed = if self.voided {
+            self.bond_mint.is_some()
+        } else {
+            self.dispute == Dispute::Upheld
+        };
+        slashed && self.creator_bond > 0
+    }
+    
+    /// Part of a slashed bond owed to a bettor who staked `stake`, split over the pot
+    pub fn bond_share(&self, stake: u64) -> Result<u64> {
+        require!(self.has_bond_claims(), crate::error::BetFunError::NotResolved);
+        Ok(payout::pro_rata_redemption(stake, self.pot, self.creator_bond).map_err(crate::error::BetFunError::from)?)
+    }
+    
+    /// Whether the creator's bond can go back to them: the arena resolved and
+    /// the dispute window closed without a dispute against it, or it locked
+    /// with neither a bet nor a share whose holder the bond protects
+    pub fn can_release_bond(&self, current_time: i64) -> bool {
+        if !self.resolved {
+            return !self.has_positions() && self.is_locked(current_time);
+        }
+        match self.dispute {
+            Dispute::Undisputed => current_time >= self.resolved_at.saturating_add(Self::DISPUTE_WINDOW),
+            Dispute::Rejected => true,
+            Dispute::Open { .. } | Dispute::Upheld => false,
+        }
+    }
+    
+    /// Whether anyone but the creator can still dispute the resolution: the
+    /// arena holds a bond to slash and resolved within the dispute window
+    pub fn can_dispute(&self, current_time: i64) -> bool {
+        self.resolved
+            && self.creator_bond > 0
+            && self.dispute == Dispute::Undisputed
+            && current_time < self.resolved_at.saturating_add(Self::DISPUTE_WINDOW)
+    }
+    
+    /// Lamports a disputer has deposited pending the admin's ruling
+    pub fn dispute_deposit(&self) -> u64 {
+        match self.dispute {
+            Dispute::Open { .. } => self.entry_fee,
+            _ => 0,
+        }
     }
     
     /// Calculate creator fee from pot
     pub fn calculate_creator_fee(&self) -> u64 {
-        (self.pot as u128)
-            .checked_mul(self.creator_fee_bps as u128)
-            .and_then(|v| v.checked_div(10000))
-            .and_then(|v| u64::try_from(v).ok())
-            .unwrap_or(0)
+        payout::creator_fee(self.pot, self.creator_fee_bps)
     }
     
-    /// Calculate winner's payout
-    pub fn calculate_payout(&self, participant_amount: u64) -> Result<u64> {
-        require!(self.resolved, crate::error::BetFunError::NotResolved);
+    /// Creator fee still owed to the creator, net of referral payouts
+    pub fn creator_fee_due(&self) -> u64 {
+        self.calculate_creator_fee().saturating_sub(self.referral_fees)
+    }
+    
+    /// Pot, bond and dispute deposit lamports still owed to winners, the creator and the disputer
+    pub fn unclaimed_pot(&self) -> u64 {
+        self.pot
+            .saturating_add(self.lamport_bond())
+            .saturating_add(self.dispute_deposit())
+            .saturating_sub(self.total_paid_out)
+    }
+    
+    /// Refund of a `stake` in a voided arena: the pot less referral fees
+    /// already paid, plus a slashed bond in lamports, split pro rata
+    pub fn void_refund(&self, stake: u64) -> Result<u64> {
+        require!(self.voided, crate::error::BetFunError::NotResolved);
         
-        let winner_outcome = self.winner_outcome
-            .ok_or(crate::error::BetFunError::InvalidOutcome)?;
+        let refundable = self.pot
+            .saturating_sub(self.referral_fees)
+            .saturating_add(self.lamport_bond());
+        Ok(payout::void_refund(stake, self.pot, refundable).map_err(crate::error::BetFunError::from)?)
+    }
+    
+    /// Escrow lamports available to share sales and redemptions
+    /// (everything above rent and the unclaimed pot)
+    pub fn share_reserve(&self, escrow_lamports: u64, rent_exempt_min: u64) -> u64 {
+        escrow_lamports
+            .saturating_sub(rent_exempt_min)
+            .saturating_sub(self.unclaimed_pot())
+    }
+    
+    /// Lamports paid for redeeming `amount` base units of an outcome's shares
+    /// Winning categorical shares split the share `reserve` pro rata over the
+    /// supply still outstanding; scalar arenas split the fixed pair price
+    /// between LONG and SHORT by the resolved value. Voided arenas refund
+    /// every outcome's shares.
+    pub fn redemption_value(&self, share: &OutcomeShare, amount: u64, reserve: u64) -> Result<u64> {
+        if self.voided {
+            return self.void_share_refund(share, amount);
+        }
+        require!(self.resolved, crate::error::BetFunError::NotResolved);
         
-        let winner_pot = self.outcome_pots[winner_outcome as usize];
+        if self.is_scalar() {
+            return self.scalar_redemption_value(share.outcome_index, amount);
+        }
+        require!(
+            self.winner_outcome == Some(share.outcome_index),
+            crate::error::BetFunError::NotWinner
+        );
+        Ok(payout::pro_rata_redemption(amount, share.total_supply, reserve).map_err(crate::error::BetFunError::from)?)
+    }
+    
+    /// Refund of `amount` base units of an outcome's shares in a voided arena:
+    /// half the pair price for a scalar share, or what a sale back along the
+    /// curve would pay for a categorical one
+    pub fn void_share_refund(&self, share: &OutcomeShare, amount: u64) -> Result<u64> {
+        require!(self.voided, crate::error::BetFunError::NotResolved);
         
-        if winner_pot == 0 {
-            return Ok(participant_amount); // Return original if no one else won
+        let refund = if self.is_scalar() {
+            payout::redemption_value(amount).map(|pair| pair / 2)
+        } else {
+            share.curve.quote_sell(share.total_supply, amount).map(|quote| quote.lamports)
+        };
+        Ok(refund.map_err(crate::error::BetFunError::from)?)
+    }
+    
+    /// Part of the slashed bond paid with a refund of `amount` share base units
+    /// Bettors split the bond when there are any; otherwise the share holders
+    /// of a voided arena split what is left of it over the shares outstanding.
+    pub fn void_bond_share(&self, amount: u64) -> Result<u64> {
+        if !self.voided || self.participants_count > 0 || self.shares_outstanding == 0 {
+            return Ok(0);
+        }
+        Ok(payout::pro_rata_redemption(amount, self.shares_outstanding, self.lamport_bond())
+            .map_err(crate::error::BetFunError::from)?)
+    }
+    
+    /// Lamports paid for redeeming `amount` LONG or SHORT base units of a resolved scalar arena
+    pub fn scalar_redemption_value(&self, outcome_index: u8, amount: u64) -> Result<u64> {
+        let (range, value) = self.scalar.zip(self.resolved_value).ok_or(crate::error::BetFunError::NotResolved)?;
+        Ok(payout::scalar_redemption_value(amount, outcome_index == Self::LONG, value, range.lower, range.upper)
+            .map_err(crate::error::BetFunError::from)?)
+    }
+    
+    /// Weight of a `stake` placed at `joined_at` (the stake itself in a flat arena)
+    pub fn stake_weight(&self, stake: u64, joined_at: i64) -> Result<u64> {
+        match self.stake_weighting {
+            StakeWeighting::Flat => Ok(stake),
+            StakeWeighting::LinearDecay { bonus_bps } => Ok(
+                payout::stake_weight(stake, bonus_bps, joined_at, self.created_at, self.lock_time)
+                    .map_err(crate::error::BetFunError::from)?,
+            ),
+        }
+    }
+    
+    /// Weight of a participant's stake
+    pub fn participant_weight(&self, participant: &Participant) -> Result<u64> {
+        self.stake_weight(participant.amount, participant.joined_at)
+    }
+    
+    /// Total weight staked on an outcome (its pot in a flat arena)
+    pub fn outcome_weight(&self, outcome_index: u8) -> u64 {
+        match self.stake_weighting {
+            StakeWeighting::Flat => self.outcome_pots[outcome_index as usize],
+            StakeWeighting::LinearDecay { .. } => self.outcome_weights[outcome_index as usize],
         }
+    }
+    
+    /// Calculate winner's payout for a stake of `weight` (its amount in a flat arena)
+    pub fn calculate_payout(&self, weight: u64) -> Result<u64> {
+        require!(self.resolved, crate::error::BetFunError::NotResolved);
+        
+        let winner_outcome = self.winner_outcome
+            .ok_or(crate::error::BetFunError::InvalidOutcome)?;
         
-        let creator_fee = self.calculate_creator_fee();
-        let distributable_pot = self.pot
-            .checked_sub(creator_fee)
-            .ok_or(crate::error::BetFunError::ArithmeticOverflow)?;
+        let winner_weight = self.outcome_weight(winner_outcome);
         
-        // Calculate proportional payout
-        let payout = (participant_amount as u128)
-            .checked_mul(distributable_pot as u128)
-            .and_then(|v| v.checked_div(winner_pot as u128))
-            .and_then(|v| u64::try_from(v).ok())
-            .ok_or(crate::error::BetFunError::ArithmeticOverflow)?;
+        // Share of the pot less the creator fee, by weight
+        let payout = payout::payout(weight, self.pot, winner_weight, self.creator_fee_bps)
+            .map_err(crate::error::BetFunError::from)?;
         
         Ok(payout)
     }
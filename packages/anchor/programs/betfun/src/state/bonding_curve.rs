@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use betfun_math::curve::{self, Curve, Shape, TradeQuote};
+
+/// Shape of the primary issuance curve
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CurveType {
+    /// price = base_price + param * supply (param in lamports per whole share minted)
+    /// A zero slope gives a fixed price
+    Linear,
+    /// price = base_price * 2^(supply / param) (param is the doubling supply in base units)
+    Exponential,
+}
+
+/// Bonding curve used by `buy_shares` / `sell_shares`
+///
+/// The pricing itself lives in `betfun_math::curve`, shared with off-chain
+/// quoting; this is its on-chain representation.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BondingCurve {
+    /// Curve shape
+    pub curve_type: CurveType,
+
+    /// Price at zero supply (lamports per whole share)
+    pub base_price: u64,
+
+    /// Slope (linear) or doubling supply (exponential)
+    pub param: u64,
+}
+
+impl From<BondingCurve> for Curve {
+    fn from(curve: BondingCurve) -> Self {
+        Curve {
+            shape: match curve.curve_type {
+                CurveType::Linear => Shape::Linear,
+                CurveType::Exponential => Shape::Exponential,
+            },
+            base_price: curve.base_price,
+            param: curve.param,
+        }
+    }
+}
+
+impl BondingCurve {
+    /// Spot price at a given supply (lamports per whole share)
+    pub fn price_at(&self, supply: u64) -> Option<u64> {
+        Curve::from(*self).price_at(supply)
+    }
+
+    /// Lamports held against the curve when `supply` shares are outstanding
+    /// (integral of the price from 0 to `supply`)
+    pub fn reserve_at(&self, supply: u64) -> Option<u64> {
+        Curve::from(*self).reserve_at(supply)
+    }
+
+    /// Cost of minting `amount` shares on top of `supply`
+    pub fn buy_cost(&self, supply: u64, amount: u64) -> Option<u64> {
+        Curve::from(*self).buy_cost(supply, amount)
+    }
+
+    /// Proceeds from burning `amount` shares out of `supply`
+    pub fn sell_proceeds(&self, supply: u64, amount: u64) -> Option<u64> {
+        Curve::from(*self).sell_proceeds(supply, amount)
+    }
+
+    /// Quote minting `amount` shares on top of `supply`
+    pub fn quote_buy(&self, supply: u64, amount: u64) -> betfun_math::Result<TradeQuote> {
+        Curve::from(*self).quote_buy(supply, amount)
+    }
+
+    /// Quote burning `amount` shares out of `supply`
+    pub fn quote_sell(&self, supply: u64, amount: u64) -> betfun_math::Result<TradeQuote> {
+        Curve::from(*self).quote_sell(supply, amount)
+    }
+
+    /// Average execution price for a trade (lamports per whole share)
+    pub fn average_price(amount: u64, total: u64) -> u64 {
+        curve::average_price(amount, total)
+    }
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+/// Pause flags, one bit per kind of activity
+/// Cancelling orders and removing liquidity are never paused, so funds can
+/// always be withdrawn.
+pub mod pause {
+    /// Creating and joining arenas
+    pub const BETTING: u8 = 1 << 0;
+
+    /// Creating, buying and selling outcome shares
+    pub const SHARES: u8 = 1 << 1;
+
+    /// Creating pools, adding liquidity and swapping
+    pub const AMM: u8 = 1 << 2;
+
+    /// Creating order books, placing orders and settling matches
+    pub const ORDER_BOOK: u8 = 1 << 3;
+
+    /// Claiming winnings, redeeming winning shares and minting trophies
+    pub const CLAIMS: u8 = 1 << 4;
+
+    pub const ALL: u8 = BETTING | SHARES | AMM | ORDER_BOOK | CLAIMS;
+}
+
+/// Program-wide settings, a single PDA
+#[account]
+pub struct GlobalConfig {
+    /// Can pause the whole program or any arena
+    pub admin: Pubkey,
+
+    /// `pause` flags in effect for every arena
+    pub paused: u8,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+
+    /// Share of creator and protocol fees paid to referrers (basis points)
+    pub referral_share_bps: u16,
+
+    /// Bond a creator locks in each new arena, returned once it resolves: in
+    /// lamports, or base units of `bond_mint`
+    pub creator_bond: u64,
+
+    /// Receives protocol fees from swaps and matched orders
+    pub fee_recipient: Pubkey,
+
+    /// Fee charged to each side of a matched order (basis points)
+    pub order_book_fee_bps: u16,
+
+    /// SPL Token mint bonds are posted in (`None` for bonds in lamports)
+    pub bond_mint: Option<Pubkey>,
+}
+
+impl GlobalConfig {
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // admin
+        1 +  // paused
+        1 +  // bump
+        1 +  // version
+        2 +  // referral_share_bps
+        8 +  // creator_bond
+        32 + // fee_recipient
+        2 +  // order_book_fee_bps
+        1 + 32 + // bond_mint Option<Pubkey>
+        20;  // padding
+
+    pub const DEFAULT_REFERRAL_SHARE_BPS: u16 = 1_000; // 10%
+    pub const MAX_REFERRAL_SHARE_BPS: u16 = 5_000; // 50%
+    pub const MAX_CREATOR_BOND: u64 = 100_000_000_000; // 100 SOL, for bonds in lamports
+    pub const DEFAULT_ORDER_BOOK_FEE_BPS: u16 = 30; // 0.3%
+    pub const MAX_ORDER_BOOK_FEE_BPS: u16 = 100; // 1%
+
+    /// Whether any of `flags` is paused program-wide
+    pub fn is_paused(&self, flags: u8) -> bool {
+        self.paused & flags != 0
+    }
+
+    /// Wallet protocol fees must be paid to; the admin on configs created
+    /// before the recipient was recorded
+    pub fn protocol_fee_recipient(&self) -> Pubkey {
+        if self.fee_recipient == Pubkey::default() {
+            self.admin
+        } else {
+            self.fee_recipient
+        }
+    }
+}
@@ -1,11 +1,31 @@
+// `outcome_share` and `amm_pool` each define a module-local `ErrorCode`.
+#![allow(ambiguous_glob_reexports)]
+
+pub mod allowlist;
 pub mod arena;
+pub mod config;
+pub mod bonding_curve;
 pub mod participant;
 pub mod outcome_share;
 pub mod amm_pool;
 pub mod order_book;
+pub mod price_history;
+pub mod referral;
+pub mod parlay;
+pub mod tournament;
+pub mod user_profile;
+pub mod version;
 
 pub use arena::*;
+pub use config::*;
+pub use bonding_curve::*;
 pub use participant::*;
 pub use outcome_share::*;
 pub use amm_pool::*;
 pub use order_book::*;
+pub use price_history::*;
+pub use referral::*;
+pub use parlay::*;
+pub use tournament::*;
+pub use user_profile::*;
+pub use version::*;
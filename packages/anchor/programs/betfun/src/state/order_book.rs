@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use betfun_math::bps_of;
+use crate::error::BetFunError;
 
 /// Order type
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
@@ -108,6 +110,9 @@ pub struct LimitOrder {
     
     /// Bump seed
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
 }
 
 impl LimitOrder {
@@ -132,7 +137,17 @@ impl LimitOrder {
         8 +  // updated_at
         8 +  // fees_paid
         8 +  // avg_fill_price
-        1;   // bump
+        1 +  // bump
+        1;   // version
+    
+    /// Lamports a buy order escrows for `size` shares at its limit `price`:
+    /// their cost plus the most a buyer fee of `fee_bps` takes from it, since
+    /// fills at or below the limit never charge more
+    pub fn buy_reserve(price: u64, size: u64, fee_bps: u16) -> Result<u64> {
+        let cost = price.checked_mul(size).ok_or(BetFunError::ArithmeticOverflow)?;
+        let fee = bps_of(cost, fee_bps).map_err(BetFunError::from)?;
+        Ok(cost.checked_add(fee).ok_or(BetFunError::ArithmeticOverflow)?)
+    }
     
     /// Check if order is active
     pub fn is_active(&self) -> bool {
@@ -264,6 +279,9 @@ pub struct OrderBook {
     
     /// Bump seed
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
 }
 
 impl OrderBook {
@@ -285,7 +303,8 @@ impl OrderBook {
         8 +  // high_24h
         8 +  // low_24h
         8 +  // price_24h_ago
-        1;   // bump
+        1 +  // bump
+        1;   // version
     
     /// Update best bid/ask and spread
     pub fn update_best_prices(&mut self, bid: u64, ask: u64) {
@@ -300,6 +319,14 @@ impl OrderBook {
     
     /// Update after a trade
     pub fn update_trade_stats(&mut self, price: u64, volume: u64, current_time: i64) {
+        // Reset 24h stats if more than 24 hours passed since the previous trade
+        if current_time - self.last_trade_at > 86400 {
+            self.volume_24h = 0;
+            self.high_24h = price;
+            self.low_24h = price;
+            self.price_24h_ago = if self.last_trade_price > 0 { self.last_trade_price } else { price };
+        }
+        
         self.last_trade_price = price;
         self.trade_count += 1;
         self.volume_24h = self.volume_24h.saturating_add(volume);
@@ -312,14 +339,6 @@ impl OrderBook {
         if self.low_24h == 0 || price < self.low_24h {
             self.low_24h = price;
         }
-        
-        // Reset 24h stats if more than 24 hours passed
-        if current_time - self.last_trade_at > 86400 {
-            self.volume_24h = volume;
-            self.high_24h = price;
-            self.low_24h = price;
-            self.price_24h_ago = price;
-        }
     }
     
     /// Calculate 24h price change percentage (in basis points)
@@ -381,6 +400,9 @@ pub struct Trade {
     
     /// Bump seed
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
 }
 
 impl Trade {
@@ -397,6 +419,7 @@ impl Trade {
         8 +  // buyer_fee
         8 +  // seller_fee
         8 +  // executed_at
-        1;   // bump
+        1 +  // bump
+        1;   // version
 }
 
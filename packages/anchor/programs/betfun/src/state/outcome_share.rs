@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::BondingCurve;
 
 /// Represents a tradable outcome share token
 /// Each outcome in an arena has its own SPL token that can be bought/sold
@@ -20,6 +21,9 @@ pub struct OutcomeShare {
     /// Updated by AMM and order book
     pub current_price: u64,
     
+    /// Primary issuance curve used by buy_shares / sell_shares
+    pub curve: BondingCurve,
+    
     /// 24-hour trading volume (in lamports)
     pub volume_24h: u64,
     
@@ -40,6 +44,9 @@ pub struct OutcomeShare {
     
     /// Bump seed for PDA
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
 }
 
 impl OutcomeShare {
@@ -50,13 +57,15 @@ impl OutcomeShare {
         32 + // token_mint
         8 +  // total_supply
         8 +  // current_price
+        1 + 8 + 8 + // curve (type, base_price, param)
         8 +  // volume_24h
         8 +  // trade_count
         8 +  // last_trade_at
         8 +  // high_24h
         8 +  // low_24h
         8 +  // price_24h_ago
-        1;   // bump
+        1 +  // bump
+        1;   // version
     
     /// Calculate 24h price change percentage
     pub fn price_change_24h(&self) -> i64 {
@@ -70,6 +79,14 @@ impl OutcomeShare {
     
     /// Update price statistics
     pub fn update_price_stats(&mut self, new_price: u64, current_time: i64) {
+        // Reset 24h stats if more than 24 hours passed since the previous trade
+        if current_time - self.last_trade_at > 86400 {
+            self.volume_24h = 0;
+            self.high_24h = new_price;
+            self.low_24h = new_price;
+            self.price_24h_ago = self.current_price;
+        }
+        
         self.current_price = new_price;
         self.last_trade_at = current_time;
         
@@ -80,14 +97,6 @@ impl OutcomeShare {
         if self.low_24h == 0 || new_price < self.low_24h {
             self.low_24h = new_price;
         }
-        
-        // Reset 24h stats if more than 24 hours passed
-        if current_time - self.last_trade_at > 86400 {
-            self.volume_24h = 0;
-            self.high_24h = new_price;
-            self.low_24h = new_price;
-            self.price_24h_ago = new_price;
-        }
     }
     
     /// Add volume to 24h tracking
@@ -120,6 +129,9 @@ pub struct ShareBalance {
     
     /// Bump seed
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
 }
 
 impl ShareBalance {
@@ -130,7 +142,8 @@ impl ShareBalance {
         8 +  // avg_cost_basis
         8 +  // total_invested
         8 +  // realized_pnl
-        1;   // bump
+        1 +  // bump
+        1;   // version
     
     /// Calculate unrealized P&L
     pub fn unrealized_pnl(&self, current_price: u64) -> i64 {
@@ -146,15 +159,19 @@ impl ShareBalance {
     
     /// Update cost basis when buying shares
     pub fn buy_shares(&mut self, amount: u64, price: u64) {
-        let new_total_cost = self.total_invested + (amount * price / 1_000_000_000);
-        let new_balance = self.balance + amount;
+        let cost = (amount as u128 * price as u128) / 1_000_000_000;
+        let new_balance = self.balance.saturating_add(amount);
         
+        // Weighted average of the existing basis and this purchase
         if new_balance > 0 {
-            self.avg_cost_basis = (new_total_cost * 1_000_000_000) / new_balance;
+            let weighted = (self.balance as u128 * self.avg_cost_basis as u128)
+                .saturating_add(amount as u128 * price as u128);
+            self.avg_cost_basis = (weighted / new_balance as u128).min(u64::MAX as u128) as u64;
         }
         
         self.balance = new_balance;
-        self.total_invested = new_total_cost;
+        self.total_invested = self.total_invested
+            .saturating_add(cost.min(u64::MAX as u128) as u64);
     }
     
     /// Update P&L when selling shares
@@ -164,11 +181,13 @@ impl ShareBalance {
         // Calculate realized P&L for this sale
         let cost = (amount as u128 * self.avg_cost_basis as u128) / 1_000_000_000;
         let proceeds = (amount as u128 * price as u128) / 1_000_000_000;
-        let pnl = proceeds as i64 - cost as i64;
+        let pnl = (proceeds as i128 - cost as i128)
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64;
         
-        self.realized_pnl += pnl;
+        self.realized_pnl = self.realized_pnl.saturating_add(pnl);
         self.balance -= amount;
-        self.total_invested = self.total_invested.saturating_sub(cost as u64);
+        self.total_invested = self.total_invested
+            .saturating_sub(cost.min(u64::MAX as u128) as u64);
         
         Ok(())
     }
@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+use anchor_lang::error::ErrorCode;
+use betfun_math::payout;
+use crate::error::BetFunError;
+use crate::state::{Arena, Versioned};
+
+/// One pick of a parlay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParlayLeg {
+    /// Arena the pick is in
+    pub arena: Pubkey,
+
+    /// Outcome picked
+    pub outcome: u8,
+
+    /// Decimal odds locked at purchase, in `payout::ODDS_ONE` units
+    pub odds: u64,
+}
+
+impl ParlayLeg {
+    pub const SIZE: usize = 32 + 1 + 8;
+
+    /// Decode a leg's arena, passed in `remaining_accounts`
+    pub fn load_arena(info: &AccountInfo) -> Result<Box<Arena>> {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let arena = Box::new(Arena::try_deserialize(&mut &info.try_borrow_data()?[..])?);
+        require!(
+            arena.version == Arena::VERSION,
+            BetFunError::UnsupportedAccountVersion
+        );
+        Ok(arena)
+    }
+
+    /// Pick `outcome` in `arena` at the odds its pot implies now
+    pub fn price(arena_key: Pubkey, arena: &Arena, outcome: u8) -> Result<Self> {
+        require!(
+            (outcome as usize) < arena.outcomes.len(),
+            BetFunError::InvalidOutcome
+        );
+        // An unbacked outcome has no odds to lock
+        let odds = payout::implied_odds(arena.pot, arena.outcome_pots[outcome as usize], arena.creator_fee_bps)
+            .map_err(|_| BetFunError::InvalidOutcome)?;
+        Ok(Self { arena: arena_key, outcome, odds })
+    }
+}
+
+/// Parlay status
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParlayStatus {
+    /// Waiting on at least one arena
+    Open,
+    /// Every pick won and the payout was sent
+    Won,
+    /// At least one pick lost; the stake stays with the vault
+    Lost,
+}
+
+/// A single bet combining one pick in each of several arenas
+/// Pays `payout` from the parlay vault if every pick wins.
+#[account]
+pub struct Parlay {
+    /// Bettor, who receives the payout
+    pub owner: Pubkey,
+
+    /// Owner-chosen id, part of the PDA seeds
+    pub parlay_id: u64,
+
+    /// Lamports staked
+    pub stake: u64,
+
+    /// Lamports paid if every leg wins, locked at purchase
+    pub payout: u64,
+
+    /// Picks, in the order their arenas are passed
+    pub legs: Vec<ParlayLeg>,
+
+    pub status: ParlayStatus,
+
+    /// Placement timestamp
+    pub created_at: i64,
+
+    /// Settlement timestamp (0 while open)
+    pub settled_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+}
+
+impl Parlay {
+    pub const MIN_LEGS: usize = 2;
+    pub const MAX_LEGS: usize = 8;
+    /// Smallest pot a leg's arena may have, so a few lamports cannot set its odds
+    pub const MIN_LEG_POT: u64 = 1_000_000_000; // 1 SOL
+    /// Highest odds a leg may lock, in `payout::ODDS_ONE` units
+    pub const MAX_LEG_ODDS: u64 = 10 * payout::ODDS_ONE; // 10x
+
+    /// Calculate space needed for a parlay with `legs` picks
+    pub fn space(legs: usize) -> usize {
+        8 +   // discriminator
+        32 +  // owner
+        8 +   // parlay_id
+        8 +   // stake
+        8 +   // payout
+        4 + legs * ParlayLeg::SIZE + // legs Vec
+        1 +   // status
+        8 +   // created_at
+        8 +   // settled_at
+        1 +   // bump
+        1 +   // version
+        32    // padding
+    }
+}
+
+/// House liquidity backing parlay payouts, a single PDA holding the lamports
+#[account]
+pub struct ParlayVault {
+    /// Payouts owed if every open parlay won
+    pub total_liability: u64,
+
+    /// Parlays not yet settled
+    pub open_parlays: u32,
+
+    /// Lamports staked on parlays over the vault's lifetime
+    pub total_staked: u64,
+
+    /// Lamports paid to winning parlays over the vault's lifetime
+    pub total_paid_out: u64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+}
+
+impl ParlayVault {
+    pub const SIZE: usize =
+        8 +   // discriminator
+        8 +   // total_liability
+        4 +   // open_parlays
+        8 +   // total_staked
+        8 +   // total_paid_out
+        1 +   // bump
+        1 +   // version
+        32;   // padding
+
+    /// Largest share of free liquidity one parlay's payout may take (basis points)
+    pub const MAX_PAYOUT_BPS: u16 = 1_000; // 10%
+
+    /// Lamports not reserved for rent or open parlays
+    pub fn free_liquidity(&self, vault_lamports: u64, rent_exempt_min: u64) -> u64 {
+        vault_lamports
+            .saturating_sub(rent_exempt_min)
+            .saturating_sub(self.total_liability)
+    }
+}
@@ -14,7 +14,7 @@ pub struct Participant {
     /// Amount staked in lamports
     pub amount: u64,
     
-    /// Whether winnings have been claimed
+    /// Whether winnings have been claimed, or the loss booked with `settle_profile`
     pub claimed: bool,
     
     /// Timestamp when joined
@@ -25,6 +25,12 @@ pub struct Participant {
     
     /// Bump for PDA
     pub bump: u8,
+    
+    /// Account layout version
+    pub version: u8,
+    
+    /// Whether the participant's part of a slashed bond was claimed with `claim_bond_share`
+    pub bond_claimed: bool,
 }
 
 impl Participant {
@@ -39,5 +45,7 @@ impl Participant {
         8 +   // joined_at
         1 + 32 + // trophy_mint Option<Pubkey>
         1 +   // bump
-        32;   // padding
+        1 +   // version
+        1 +   // bond_claimed
+        30;   // padding
 }
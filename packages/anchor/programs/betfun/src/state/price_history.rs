@@ -0,0 +1,194 @@
+use anchor_lang::prelude::*;
+
+/// One hourly OHLCV bucket
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Candle {
+    /// Bucket start timestamp (aligned to the hour, 0 = empty slot)
+    pub start_time: i64,
+
+    /// First trade price in the bucket (lamports per share)
+    pub open: u64,
+
+    /// Highest trade price in the bucket
+    pub high: u64,
+
+    /// Lowest trade price in the bucket
+    pub low: u64,
+
+    /// Last trade price in the bucket
+    pub close: u64,
+
+    /// Traded volume in the bucket (in lamports)
+    pub volume: u64,
+
+    /// Number of trades in the bucket
+    pub trades: u32,
+}
+
+impl Candle {
+    pub const SIZE: usize = 8 + // start_time
+        8 +  // open
+        8 +  // high
+        8 +  // low
+        8 +  // close
+        8 +  // volume
+        4;   // trades
+}
+
+/// Rolling candle history for an outcome
+/// Ring buffer of hourly buckets fed by primary sales, AMM swaps and order book fills
+#[account]
+pub struct PriceHistory {
+    /// Parent arena
+    pub arena: Pubkey,
+
+    /// Which outcome this history is for
+    pub outcome_index: u8,
+
+    /// Slot of the most recent candle in `candles`
+    pub head: u16,
+
+    /// Hourly candles, oldest entries are overwritten first
+    pub candles: [Candle; PriceHistory::CANDLE_COUNT],
+
+    /// Bump seed
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+}
+
+impl PriceHistory {
+    /// Bucket width in seconds
+    pub const BUCKET_SECONDS: i64 = 3600;
+
+    /// Length of the rolling stats window in seconds
+    pub const WINDOW_SECONDS: i64 = 86400;
+
+    /// Number of buckets kept (48h, so the price at the start of the window is always known)
+    pub const CANDLE_COUNT: usize = 48;
+
+    /// PDA seed prefix
+    pub const SEED: &[u8] = b"price_history";
+
+    pub const SIZE: usize = 8 + // discriminator
+        32 + // arena
+        1 +  // outcome_index
+        2 +  // head
+        Candle::SIZE * Self::CANDLE_COUNT + // candles
+        1 +  // bump
+        1;   // version
+
+    /// PDA seeds (without the bump) of an outcome's history
+    ///
+    /// Account constraints list the same three seeds inline, since `seeds = [..]`
+    /// only takes a literal list; the outcome index is always its single byte.
+    pub fn seeds<'a>(arena: &'a Pubkey, outcome_index: &'a u8) -> [&'a [u8]; 3] {
+        [Self::SEED, arena.as_ref(), std::slice::from_ref(outcome_index)]
+    }
+
+    /// Align a timestamp to the start of its bucket
+    pub fn bucket_start(timestamp: i64) -> i64 {
+        timestamp - timestamp.rem_euclid(Self::BUCKET_SECONDS)
+    }
+
+    /// Most recent candle, if any trade has been recorded
+    pub fn latest(&self) -> Option<&Candle> {
+        let candle = &self.candles[self.head as usize];
+        if candle.start_time == 0 {
+            None
+        } else {
+            Some(candle)
+        }
+    }
+
+    /// Record a trade into the current hourly bucket
+    pub fn record_trade(&mut self, price: u64, volume: u64, current_time: i64) {
+        let bucket_start = Self::bucket_start(current_time);
+        let head = self.head as usize;
+        let latest = self.candles[head];
+
+        // Same bucket (or a clock that has not advanced): extend the current candle
+        if latest.start_time != 0 && bucket_start <= latest.start_time {
+            let candle = &mut self.candles[head];
+            candle.high = candle.high.max(price);
+            candle.low = candle.low.min(price);
+            candle.close = price;
+            candle.volume = candle.volume.saturating_add(volume);
+            candle.trades = candle.trades.saturating_add(1);
+            return;
+        }
+
+        // New bucket: advance the ring (skip the advance for the very first candle)
+        let next = if latest.start_time == 0 {
+            head
+        } else {
+            (head + 1) % Self::CANDLE_COUNT
+        };
+
+        self.candles[next] = Candle {
+            start_time: bucket_start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+            trades: 1,
+        };
+        self.head = next as u16;
+    }
+
+    /// Candles inside the rolling window ending at `current_time`
+    fn window(&self, current_time: i64) -> impl Iterator<Item = &Candle> {
+        let window_start = Self::bucket_start(current_time) - Self::WINDOW_SECONDS + Self::BUCKET_SECONDS;
+        self.candles
+            .iter()
+            .filter(move |c| c.start_time != 0 && c.start_time >= window_start)
+    }
+
+    /// Traded volume over the last 24 hours
+    pub fn volume_24h(&self, current_time: i64) -> u64 {
+        self.window(current_time)
+            .fold(0u64, |acc, c| acc.saturating_add(c.volume))
+    }
+
+    /// Highest and lowest price over the last 24 hours (0, 0 if no trades)
+    pub fn high_low_24h(&self, current_time: i64) -> (u64, u64) {
+        self.window(current_time).fold((0, 0), |(high, low), c| {
+            let low = if low == 0 { c.low } else { low.min(c.low) };
+            (high.max(c.high), low)
+        })
+    }
+
+    /// Price at the start of the 24 hour window
+    /// Uses the last close before the window, or the first open inside it
+    pub fn price_24h_ago(&self, current_time: i64) -> u64 {
+        let window_start = Self::bucket_start(current_time) - Self::WINDOW_SECONDS + Self::BUCKET_SECONDS;
+
+        let before = self.candles
+            .iter()
+            .filter(|c| c.start_time != 0 && c.start_time < window_start)
+            .max_by_key(|c| c.start_time);
+
+        if let Some(candle) = before {
+            return candle.close;
+        }
+
+        self.window(current_time)
+            .min_by_key(|c| c.start_time)
+            .map(|c| c.open)
+            .unwrap_or(0)
+    }
+
+    /// 24h price change in basis points
+    pub fn price_change_24h(&self, current_time: i64) -> i64 {
+        let price_24h_ago = self.price_24h_ago(current_time);
+        if price_24h_ago == 0 {
+            return 0;
+        }
+
+        let current_price = self.latest().map(|c| c.close).unwrap_or(0);
+        let change = current_price as i128 - price_24h_ago as i128;
+        ((change * 10000) / price_24h_ago as i128) as i64
+    }
+}
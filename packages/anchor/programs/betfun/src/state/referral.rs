@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use crate::error::BetFunError;
+use crate::state::Versioned;
+
+/// Who referred a user, and the fee share held for that referrer
+/// Rewards are kept as lamports in this account until the referrer claims.
+#[account]
+pub struct Referral {
+    /// Referred user
+    pub user: Pubkey,
+
+    /// Wallet that referred the user, fixed on first use
+    pub referrer: Pubkey,
+
+    /// Lamports held for the referrer, not yet claimed
+    pub pending_rewards: u64,
+
+    /// Lamports accrued over the referral's lifetime
+    pub total_earned: u64,
+
+    /// Timestamp of the first referred action
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+}
+
+impl Referral {
+    pub const SIZE: usize =
+        8 +   // discriminator
+        32 +  // user
+        32 +  // referrer
+        8 +   // pending_rewards
+        8 +   // total_earned
+        8 +   // created_at
+        1 +   // bump
+        1 +   // version
+        32;   // padding
+
+    /// Record `referrer` on a new referral; an existing one keeps the
+    /// referrer it was bound to and ignores any later `referrer`
+    pub fn record(&mut self, user: Pubkey, referrer: Pubkey, bump: u8, current_time: i64) -> Result<()> {
+        if self.user == Pubkey::default() {
+            require!(referrer != user, BetFunError::InvalidReferrer);
+            self.user = user;
+            self.referrer = referrer;
+            self.pending_rewards = 0;
+            self.total_earned = 0;
+            self.created_at = current_time;
+            self.bump = bump;
+            self.version = Referral::VERSION;
+            return Ok(());
+        }
+        require!(
+            self.version == Referral::VERSION,
+            BetFunError::UnsupportedAccountVersion
+        );
+        if self.referrer != referrer {
+            msg!("Referral already bound to {}", self.referrer);
+        }
+        Ok(())
+    }
+    
+    /// Credit `amount` lamports, already moved into this account, to the referrer
+    pub fn accrue(&mut self, amount: u64) -> Result<()> {
+        self.pending_rewards = self.pending_rewards
+            .checked_add(amount)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        self.total_earned = self.total_earned
+            .checked_add(amount)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_lang::error::ErrorCode;
+use crate::error::BetFunError;
+use crate::state::{Participant, Versioned};
+
+/// Single-elimination bracket of two-outcome arenas
+///
+/// Matches live in `arenas` in heap order: the final is slot 0 and slot `s`
+/// is contested by the winners of slots `2s + 1` and `2s + 2`. First-round
+/// matches fill the last `entrants.len() / 2` slots. Each match is an
+/// ordinary arena created by the tournament PDA, so only
+/// `resolve_tournament_match` resolves it and records the result here.
+#[account]
+pub struct Tournament {
+    /// Resolves every match
+    pub organizer: Pubkey,
+
+    /// Tournament name (max 24 chars), part of the PDA seeds
+    pub name: String,
+
+    /// Competitors in first-round order: 0 meets 1, 2 meets 3, ...
+    pub entrants: Vec<String>,
+
+    /// Match arena of each bracket slot (default until activated)
+    pub arenas: Vec<Pubkey>,
+
+    /// Result of each slot: 0 while pending, else the winning outcome plus 1
+    pub results: Vec<u8>,
+
+    /// Lamports each entry pays into the tournament pot
+    pub entry_fee: u64,
+
+    /// Entry fee of every match arena
+    pub match_entry_fee: u64,
+
+    /// Entries close and first-round matches lock
+    pub lock_time: i64,
+
+    /// Later matches lock this long after they are activated
+    pub round_duration: i64,
+
+    /// Entry fees collected
+    pub pot: u64,
+
+    /// Number of entries
+    pub entries_count: u32,
+
+    /// Highest score submitted so far
+    pub best_score: u8,
+
+    /// Entries that submitted `best_score`, who split the pot
+    pub best_count: u32,
+
+    /// End of score submissions (0 until the final resolves)
+    pub scoring_ends_at: i64,
+
+    /// Lamports paid out of the pot so far
+    pub total_paid_out: u64,
+
+    /// A match was voided, so entries are refunded instead
+    pub cancelled: bool,
+
+    /// Created timestamp
+    pub created_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+}
+
+impl Tournament {
+    pub const MAX_NAME_LEN: usize = 24;
+    pub const MIN_ENTRANTS: usize = 4;
+    pub const MAX_ENTRANTS: usize = 16;
+
+    /// Time entries have to submit their scores once the final resolves
+    pub const SCORING_WINDOW: i64 = 3 * 24 * 60 * 60;
+
+    /// Calculate space needed for a tournament of `entrants`
+    pub fn space(name_len: usize, entrants: usize) -> usize {
+        8 +   // discriminator
+        32 +  // organizer
+        4 + name_len + // name
+        4 + entrants * (4 + crate::state::Arena::MAX_OUTCOME_LEN) + // entrants Vec
+        4 + (entrants - 1) * 32 + // arenas Vec
+        4 + (entrants - 1) + // results Vec
+        8 +   // entry_fee
+        8 +   // match_entry_fee
+        8 +   // lock_time
+        8 +   // round_duration
+        8 +   // pot
+        4 +   // entries_count
+        1 +   // best_score
+        4 +   // best_count
+        8 +   // scoring_ends_at
+        8 +   // total_paid_out
+        1 +   // cancelled
+        8 +   // created_at
+        1 +   // bump
+        1 +   // version
+        32    // padding
+    }
+
+    /// Title, and so PDA seed, of the arena deciding `slot`
+    pub fn match_title(slot: u8) -> String {
+        format!("Match {slot}")
+    }
+
+    /// Slot of the first first-round match
+    pub fn first_round_slot(&self) -> usize {
+        self.entrants.len() / 2 - 1
+    }
+
+    /// Slots whose winners meet in `slot`, or `None` for a first-round match
+    pub fn feeders(&self, slot: usize) -> Option<[usize; 2]> {
+        (slot < self.first_round_slot()).then_some([2 * slot + 1, 2 * slot + 2])
+    }
+
+    /// Entrant indices contesting `slot`, once both are known
+    pub fn competitors(&self, slot: usize) -> Option<[usize; 2]> {
+        match self.feeders(slot) {
+            None => {
+                let first = 2 * slot.checked_sub(self.first_round_slot())?;
+                (first + 1 < self.entrants.len()).then_some([first, first + 1])
+            }
+            Some([first, second]) => Some([self.winner(first)?, self.winner(second)?]),
+        }
+    }
+
+    /// Entrant index that won `slot`, once resolved
+    pub fn winner(&self, slot: usize) -> Option<usize> {
+        let outcome = self.results.get(slot)?.checked_sub(1)?;
+        self.competitors(slot).map(|competitors| competitors[outcome as usize])
+    }
+
+    /// Whether the final has been resolved
+    pub fn is_decided(&self) -> bool {
+        self.results.first().is_some_and(|result| *result > 0)
+    }
+
+    /// Bracket slot of a match arena
+    pub fn slot_of(&self, arena: &Pubkey) -> Option<usize> {
+        if *arena == Pubkey::default() {
+            return None;
+        }
+        self.arenas.iter().position(|key| key == arena)
+    }
+}
+
+/// One wallet's entry into a tournament, scored by its picks in the match arenas
+#[account]
+pub struct TournamentEntry {
+    /// Tournament entered
+    pub tournament: Pubkey,
+
+    /// Entrant's wallet, whose `Participant` accounts are its picks
+    pub wallet: Pubkey,
+
+    /// Correct picks, once submitted
+    pub score: u8,
+
+    /// Whether the score has been submitted
+    pub submitted: bool,
+
+    /// Whether the prize or refund has been claimed
+    pub claimed: bool,
+
+    /// Entry timestamp
+    pub entered_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+}
+
+impl TournamentEntry {
+    pub const SIZE: usize =
+        8 +   // discriminator
+        32 +  // tournament
+        32 +  // wallet
+        1 +   // score
+        1 +   // submitted
+        1 +   // claimed
+        8 +   // entered_at
+        1 +   // bump
+        1 +   // version
+        32;   // padding
+
+    /// Decode a pick, passed in `remaining_accounts`
+    pub fn load_pick(info: &AccountInfo) -> Result<Participant> {
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+        let pick = Participant::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require!(
+            pick.version == Participant::VERSION,
+            BetFunError::UnsupportedAccountVersion
+        );
+        Ok(pick)
+    }
+}
@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use crate::error::BetFunError;
+use crate::state::Versioned;
+
+/// Lifetime statistics of one wallet, for leaderboards
+/// Created on the wallet's first bet or share purchase.
+#[account]
+pub struct UserProfile {
+    /// Wallet these statistics belong to
+    pub user: Pubkey,
+
+    /// Arenas joined
+    pub arenas_joined: u32,
+
+    /// Arenas whose winnings were claimed
+    pub arenas_won: u32,
+
+    /// Lamports put in through entry fees and share purchases
+    pub total_staked: u64,
+
+    /// Lamports taken out through claimed winnings and share redemptions
+    pub total_won: u64,
+
+    /// Winnings over stake, plus share sales and redemptions over cost basis,
+    /// less losses booked with `settle_profile`
+    pub realized_pnl: i64,
+
+    /// Trophies recorded with `mint_trophy`
+    pub trophies: u32,
+
+    /// Timestamp of the first tracked action
+    pub created_at: i64,
+
+    /// Timestamp of the latest tracked action
+    pub last_active_at: i64,
+
+    /// Bump for PDA
+    pub bump: u8,
+
+    /// Account layout version
+    pub version: u8,
+}
+
+impl UserProfile {
+    pub const SIZE: usize =
+        8 +   // discriminator
+        32 +  // user
+        4 +   // arenas_joined
+        4 +   // arenas_won
+        8 +   // total_staked
+        8 +   // total_won
+        8 +   // realized_pnl
+        4 +   // trophies
+        8 +   // created_at
+        8 +   // last_active_at
+        1 +   // bump
+        1 +   // version
+        64;   // padding
+
+    /// Start a new profile for `user`, or check an existing one is current
+    pub fn touch(&mut self, user: Pubkey, bump: u8, current_time: i64) -> Result<()> {
+        if self.user == Pubkey::default() {
+            self.user = user;
+            self.arenas_joined = 0;
+            self.arenas_won = 0;
+            self.total_staked = 0;
+            self.total_won = 0;
+            self.realized_pnl = 0;
+            self.trophies = 0;
+            self.created_at = current_time;
+            self.bump = bump;
+            self.version = UserProfile::VERSION;
+        }
+        require!(
+            self.version == UserProfile::VERSION,
+            BetFunError::UnsupportedAccountVersion
+        );
+        self.last_active_at = current_time;
+        Ok(())
+    }
+
+    /// Count a joined arena and its entry fee
+    pub fn record_join(&mut self, entry_fee: u64) -> Result<()> {
+        self.arenas_joined = self.arenas_joined
+            .checked_add(1)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        self.record_stake(entry_fee)
+    }
+
+    /// Count lamports put at risk
+    pub fn record_stake(&mut self, lamports: u64) -> Result<()> {
+        self.total_staked = self.total_staked
+            .checked_add(lamports)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Count claimed winnings of `payout` on a bet of `stake`
+    pub fn record_win(&mut self, stake: u64, payout: u64) -> Result<()> {
+        self.arenas_won = self.arenas_won
+            .checked_add(1)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        self.record_payout(payout)?;
+        self.record_pnl(payout as i64 - stake as i64);
+        Ok(())
+    }
+
+    /// Count lamports paid out on a winning position
+    pub fn record_payout(&mut self, lamports: u64) -> Result<()> {
+        self.total_won = self.total_won
+            .checked_add(lamports)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    pub fn record_pnl(&mut self, pnl: i64) {
+        self.realized_pnl = self.realized_pnl.saturating_add(pnl);
+    }
+
+    pub fn record_trophy(&mut self) -> Result<()> {
+        self.trophies = self.trophies
+            .checked_add(1)
+            .ok_or(BetFunError::ArithmeticOverflow)?;
+        Ok(())
+    }
+}
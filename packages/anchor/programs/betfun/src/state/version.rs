@@ -0,0 +1,225 @@
+use anchor_lang::prelude::*;
+use crate::state::{
+    AMMPool, Arena, BondingCurve, CurveType, GlobalConfig, LimitOrder, LiquidityPosition, OrderBook, OutcomeShare,
+    Parlay, ParlayVault, Participant, PriceHistory, Referral, ShareBalance, Tournament, TournamentEntry, Trade,
+    UserProfile,
+};
+
+/// Account layout versioning
+///
+/// Version 0 is the layout an account had before versioning, and version 1
+/// added `version`, after the fields every such layout had. Where that is
+/// the only change, the older layout is a prefix of the newer one: `Arena`
+/// and `Participant` read version 0 from their zeroed padding, the others
+/// are one byte short and [`Versioned::read`] supplies it. `OutcomeShare`
+/// gained `curve` ahead of `version`, so it decodes version 0 through
+/// [`OutcomeShareV0`] instead. Fields added after versioning bump the
+/// version of their account and come after `version`, carved out of the
+/// zeroed padding older accounts were created with; [`Versioned::upgrade`]
+/// fills in whatever must not stay zero. Handlers only accept
+/// [`Versioned::VERSION`]; `migrate_account` upgrades anything older in place.
+pub trait Versioned: AccountSerialize + AccountDeserialize + Discriminator {
+    /// Layout version this program writes
+    const VERSION: u8;
+
+    fn version(&self) -> u8;
+
+    /// Rewrite fields read at an older version into the current layout
+    fn upgrade(&mut self);
+
+    /// Account size at the current version
+    fn space(&self) -> usize;
+
+    /// Decode account data written at any version up to the current one
+    fn read(data: &[u8]) -> Result<Self> {
+        // A layout without `version` ends where it starts; the extra zero
+        // reads as version 0 and is ignored by complete layouts
+        let mut padded = Vec::with_capacity(data.len() + 1);
+        padded.extend_from_slice(data);
+        padded.push(0);
+        Self::try_deserialize(&mut &padded[..])
+    }
+}
+
+/// Accounts whose only change is `version` itself, so upgrading from 0 only sets it
+macro_rules! versioned {
+    ($ty:ty, |$account:ident| $space:expr) => {
+        impl Versioned for $ty {
+            const VERSION: u8 = 1;
+
+            fn version(&self) -> u8 {
+                self.version
+            }
+
+            fn upgrade(&mut self) {
+                self.version = Self::VERSION;
+            }
+
+            fn space(&self) -> usize {
+                let $account = self;
+                $space
+            }
+        }
+    };
+}
+
+versioned!(ShareBalance, |_balance| ShareBalance::SIZE);
+versioned!(PriceHistory, |_history| PriceHistory::SIZE);
+versioned!(AMMPool, |_pool| AMMPool::SIZE);
+versioned!(LiquidityPosition, |_position| LiquidityPosition::SIZE);
+versioned!(OrderBook, |_book| OrderBook::SIZE);
+versioned!(LimitOrder, |_order| LimitOrder::SIZE);
+versioned!(Trade, |_trade| Trade::SIZE);
+versioned!(Referral, |_referral| Referral::SIZE);
+versioned!(UserProfile, |_profile| UserProfile::SIZE);
+versioned!(Parlay, |parlay| Parlay::space(parlay.legs.len()));
+versioned!(ParlayVault, |_vault| ParlayVault::SIZE);
+versioned!(Tournament, |tournament| Tournament::space(tournament.name.len(), tournament.entrants.len()));
+versioned!(TournamentEntry, |_entry| TournamentEntry::SIZE);
+
+/// Version 2 appended `bond_claimed`, false from the padding.
+impl Versioned for Participant {
+    const VERSION: u8 = 2;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn upgrade(&mut self) {
+        self.version = Self::VERSION;
+    }
+
+    fn space(&self) -> usize {
+        Self::SIZE
+    }
+}
+
+/// Version 2 appended pause flags, referral fees, trading fees, scalar
+/// ranges, allowlists, creator bonds, `resolve_after`, stake weighting, duels,
+/// shares outstanding and parlay approval, version 3 the bond mint, and
+/// version 4 the resolution time and dispute. Each reads as zero from the
+/// padding and means what it did before: unpaused, flat, bonded in lamports,
+/// undisputed and resolved too long ago to dispute, resolvable as before
+/// `resolve_after` existed (see [`Arena::resolvable_from`]).
+impl Versioned for Arena {
+    const VERSION: u8 = 4;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn upgrade(&mut self) {
+        self.version = Self::VERSION;
+    }
+
+    fn space(&self) -> usize {
+        Arena::space(
+            self.title.len(),
+            self.description.len(),
+            self.question.len(),
+            self.outcomes.len(),
+            self.tags.len(),
+        )
+    }
+}
+
+/// Version 2 appended the referral share, creator bond, fee recipient and
+/// order book fee; version 1 configs get the defaults `initialize_config` sets.
+/// Version 3 appended the bond mint, read as `None` (lamports) from the padding.
+impl Versioned for GlobalConfig {
+    const VERSION: u8 = 3;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn upgrade(&mut self) {
+        if self.version < 2 {
+            self.referral_share_bps = Self::DEFAULT_REFERRAL_SHARE_BPS;
+            self.fee_recipient = self.admin;
+            self.order_book_fee_bps = Self::DEFAULT_ORDER_BOOK_FEE_BPS;
+        }
+        self.version = Self::VERSION;
+    }
+
+    fn space(&self) -> usize {
+        Self::SIZE
+    }
+}
+
+/// `OutcomeShare` as written before versioning, with no `curve`
+#[derive(AnchorDeserialize)]
+pub struct OutcomeShareV0 {
+    pub arena: Pubkey,
+    pub outcome_index: u8,
+    pub token_mint: Pubkey,
+    pub total_supply: u64,
+    pub current_price: u64,
+    pub volume_24h: u64,
+    pub trade_count: u64,
+    pub last_trade_at: i64,
+    pub high_24h: u64,
+    pub low_24h: u64,
+    pub price_24h_ago: u64,
+    pub bump: u8,
+}
+
+impl OutcomeShareV0 {
+    pub const SIZE: usize = OutcomeShare::SIZE
+        - (1 + 8 + 8) // curve
+        - 1; // version
+}
+
+/// Shares issued before the bonding curve traded at a fixed `current_price`,
+/// which version 1 keeps as a flat curve.
+impl From<OutcomeShareV0> for OutcomeShare {
+    fn from(share: OutcomeShareV0) -> Self {
+        OutcomeShare {
+            arena: share.arena,
+            outcome_index: share.outcome_index,
+            token_mint: share.token_mint,
+            total_supply: share.total_supply,
+            current_price: share.current_price,
+            curve: BondingCurve {
+                curve_type: CurveType::Linear,
+                base_price: share.current_price,
+                param: 0,
+            },
+            volume_24h: share.volume_24h,
+            trade_count: share.trade_count,
+            last_trade_at: share.last_trade_at,
+            high_24h: share.high_24h,
+            low_24h: share.low_24h,
+            price_24h_ago: share.price_24h_ago,
+            bump: share.bump,
+            version: 0,
+        }
+    }
+}
+
+impl Versioned for OutcomeShare {
+    const VERSION: u8 = 1;
+
+    fn version(&self) -> u8 {
+        self.version
+    }
+
+    fn upgrade(&mut self) {
+        self.version = Self::VERSION;
+    }
+
+    fn space(&self) -> usize {
+        Self::SIZE
+    }
+
+    fn read(data: &[u8]) -> Result<Self> {
+        if data.len() != OutcomeShareV0::SIZE {
+            return Self::try_deserialize(&mut &data[..]);
+        }
+        let discriminator = data.get(..8).ok_or(ErrorCode::AccountDiscriminatorNotFound)?;
+        require!(discriminator == Self::DISCRIMINATOR, ErrorCode::AccountDiscriminatorMismatch);
+        let share = OutcomeShareV0::deserialize(&mut &data[8..])
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?;
+        Ok(share.into())
+    }
+}
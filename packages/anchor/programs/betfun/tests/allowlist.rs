@@ -0,0 +1,110 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::allowlist::{leaf, parent};
+use betfun::state::{Arena, CurveType};
+use betfun_testkit::Svm;
+use common::*;
+
+/// Three members, their root and each one's proof
+struct Members {
+    wallets: [Pubkey; 3],
+    root: [u8; 32],
+    proofs: [Vec<[u8; 32]>; 3],
+}
+
+fn members(svm: &mut Svm) -> Members {
+    let wallets = [wallet(svm), wallet(svm), wallet(svm)];
+    let [a, b, c] = wallets.map(|wallet| leaf(&wallet));
+    let ab = parent(&a, &b);
+    Members {
+        wallets,
+        root: parent(&ab, &c),
+        proofs: [vec![b, c], vec![a, c], vec![ab]],
+    }
+}
+
+fn private_arena(svm: &mut Svm, root: [u8; 32]) -> (Pubkey, Pubkey) {
+    let config = ArenaConfig { allowlist_root: Some(root), ..ArenaConfig::new(svm) };
+    new_arena_with(svm, &config)
+}
+
+fn join_with_proof(svm: &mut Svm, arena: &Pubkey, user: &Pubkey, proof: &[[u8; 32]]) -> TxResult {
+    svm.send_instruction(with_proof(join_arena_ix(arena, user, 0), proof), &[*user])
+}
+
+// ========== JOIN ==========
+
+#[test]
+fn private_arenas_admit_only_allowlisted_wallets() {
+    let mut svm = setup();
+    let members = members(&mut svm);
+    let (_, arena) = private_arena(&mut svm, members.root);
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.allowlist_root, Some(members.root));
+
+    let stranger = wallet(&mut svm);
+    assert_error(join_with_proof(&mut svm, &arena, &stranger, &[]), BetFunError::NotAllowlisted);
+    assert_error(join_with_proof(&mut svm, &arena, &stranger, &members.proofs[0]), BetFunError::NotAllowlisted);
+    assert_error(join_with_proof(&mut svm, &arena, &members.wallets[1], &[]), BetFunError::NotAllowlisted);
+
+    for (member, proof) in members.wallets.iter().zip(&members.proofs) {
+        assert_ok(join_with_proof(&mut svm, &arena, member, proof));
+    }
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.participants_count, 3);
+}
+
+// ========== SHARES ==========
+
+#[test]
+fn private_arenas_sell_shares_only_to_allowlisted_wallets() {
+    let mut svm = setup();
+    let members = members(&mut svm);
+    let (creator, arena) = private_arena(&mut svm, members.root);
+    assert_ok(svm.send_instruction(
+        create_share_tokens_ix(&arena, &creator, 0, BASE_PRICE, CurveType::Linear, SLOPE),
+        &[creator],
+    ));
+
+    let buy = |buyer: &Pubkey| buy_shares_ix(&arena, buyer, &creator, 0, SHARE, None, None);
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(buy(&stranger), &[stranger]);
+    assert_error(result, BetFunError::NotAllowlisted);
+
+    let member = members.wallets[2];
+    assert_ok(svm.send_instruction(with_proof(buy(&member), &members.proofs[2]), &[member]));
+    assert_eq!(token_balance(&svm, &share_ata(&member, &arena, 0)), SHARE);
+}
+
+// ========== ROTATE ==========
+
+#[test]
+fn creator_rotates_the_allowlist_until_the_arena_ends() {
+    let mut svm = setup();
+    let old = members(&mut svm);
+    let new = members(&mut svm);
+    let (creator, arena) = private_arena(&mut svm, old.root);
+
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(set_allowlist_ix(&arena, &stranger, Some(new.root)), &[stranger]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    // Rotating keeps existing bets but changes who can place new ones
+    assert_ok(join_with_proof(&mut svm, &arena, &old.wallets[0], &old.proofs[0]));
+    assert_ok(svm.send_instruction(set_allowlist_ix(&arena, &creator, Some(new.root)), &[creator]));
+    let result = join_with_proof(&mut svm, &arena, &old.wallets[1], &old.proofs[1]);
+    assert_error(result, BetFunError::NotAllowlisted);
+    assert_ok(join_with_proof(&mut svm, &arena, &new.wallets[1], &new.proofs[1]));
+
+    // Removing the root opens the arena to everyone
+    assert_ok(svm.send_instruction(set_allowlist_ix(&arena, &creator, None), &[creator]));
+    assert_ok(join_with_proof(&mut svm, &arena, &stranger, &[]));
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!((state.allowlist_root, state.participants_count), (None, 3));
+
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(set_allowlist_ix(&arena, &creator, Some(old.root)), &[creator]);
+    assert_error(result, BetFunError::ArenaEnded);
+}
@@ -0,0 +1,405 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::get_associated_token_address;
+use betfun::error::BetFunError;
+use betfun::state::{AMMPool, GlobalConfig, LiquidityPosition};
+use betfun_testkit::Svm;
+use common::*;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 10;
+
+/// Pool seeded with `tokens` shares and `sol` lamports by a fresh provider
+struct Market {
+    creator: Pubkey,
+    arena: Pubkey,
+    provider: Pubkey,
+    pool: Pubkey,
+    fee_recipient: Pubkey,
+}
+
+fn market(svm: &mut Svm, tokens: u64, sol: u64) -> Market {
+    let (creator, arena) = new_share_market(svm);
+    assert_ok(svm.send_instruction(
+        initialize_pool_ix(&arena, &creator, 0, FEE_BPS, PROTOCOL_FEE_BPS),
+        &[creator],
+    ));
+    let provider = shareholder(svm, &arena, 0, 10 * SHARE);
+    assert_ok(svm.send_instruction(add_liquidity_ix(&arena, &provider, 0, tokens, sol, 0), &[provider]));
+
+    // Fee recipients must already be rent exempt to accept small transfers
+    let fee_recipient = fee_recipient(svm, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+    Market {
+        creator,
+        arena,
+        provider,
+        pool: pool_pda(&arena, 0),
+        fee_recipient,
+    }
+}
+
+/// Protocol share of a trade's SOL leg
+fn protocol_fee_in(sol_amount: u64) -> u64 {
+    sol_amount * PROTOCOL_FEE_BPS as u64 / 10_000
+}
+
+/// Constant-product output after the LP fee
+fn amount_out(amount_in: u64, reserve_in: u64, reserve_out: u64) -> u64 {
+    let in_with_fee = amount_in as u128 * (10_000 - FEE_BPS as u128) / 10_000;
+    (in_with_fee * reserve_out as u128 / (reserve_in as u128 + in_with_fee)) as u64
+}
+
+// ========== INITIALIZE POOL ==========
+
+#[test]
+fn initialize_pool_creates_empty_pool() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    assert_ok(svm.send_instruction(
+        initialize_pool_ix(&arena, &creator, 0, FEE_BPS, PROTOCOL_FEE_BPS),
+        &[creator],
+    ));
+
+    let pool_key = pool_pda(&arena, 0);
+    let pool: AMMPool = fetch(&svm, &pool_key);
+    assert_eq!(pool.arena, arena);
+    assert_eq!(pool.share_mint, share_mint_pda(&arena, 0));
+    assert_eq!(pool.lp_token_mint, lp_mint_pda(&pool_key));
+    assert_eq!(pool.fee_bps, FEE_BPS);
+    assert_eq!(pool.protocol_fee_bps, PROTOCOL_FEE_BPS);
+    assert_eq!(pool.total_lp_tokens, 0);
+    assert_eq!(token_balance(&svm, &pool_token_vault_pda(&pool_key)), 0);
+}
+
+#[test]
+fn initialize_pool_rejects_excessive_fees() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+
+    for (fee_bps, protocol_fee_bps) in [(1_001, 10), (300, 101), (10, 30)] {
+        let result = svm.send_instruction(
+            initialize_pool_ix(&arena, &creator, 0, fee_bps, protocol_fee_bps),
+            &[creator],
+        );
+        assert_error(result, BetFunError::InvalidConfiguration);
+    }
+}
+
+// ========== LIQUIDITY ==========
+
+#[test]
+fn add_liquidity_mints_lp_tokens() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+
+    // sqrt(4e9 * 1e9)
+    let initial_lp = 2 * SOL;
+    let pool: AMMPool = fetch(&svm, &m.pool);
+    assert_eq!(pool.token_reserve, 4 * SHARE);
+    assert_eq!(pool.sol_reserve, SOL);
+    assert_eq!(pool.total_lp_tokens, initial_lp);
+    assert_eq!(svm.lamports(&pool_sol_vault_pda(&m.pool)), SOL);
+    assert_eq!(token_balance(&svm, &pool_token_vault_pda(&m.pool)), 4 * SHARE);
+
+    let lp_account = get_associated_token_address(&m.provider, &lp_mint_pda(&m.pool));
+    assert_eq!(token_balance(&svm, &lp_account), initial_lp);
+
+    // Deposits must match the pool ratio within 1%
+    let result = svm.send_instruction(add_liquidity_ix(&m.arena, &m.provider, 0, 2 * SHARE, SOL, 0), &[m.provider]);
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    let result = svm.send_instruction(
+        add_liquidity_ix(&m.arena, &m.provider, 0, 2 * SHARE, SOL / 2, initial_lp),
+        &[m.provider],
+    );
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    assert_ok(svm.send_instruction(add_liquidity_ix(&m.arena, &m.provider, 0, 2 * SHARE, SOL / 2, 0), &[m.provider]));
+    let position: LiquidityPosition = fetch(&svm, &liquidity_position_pda(&m.pool, &m.provider));
+    assert_eq!(position.lp_tokens, initial_lp + initial_lp / 2);
+    assert_eq!(position.tokens_deposited, 6 * SHARE);
+    assert_eq!(position.sol_deposited, SOL + SOL / 2);
+}
+
+#[test]
+fn add_liquidity_rejects_invalid_amounts() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+
+    let result = svm.send_instruction(add_liquidity_ix(&m.arena, &m.provider, 0, 0, SOL, 0), &[m.provider]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    let result = svm.send_instruction(add_liquidity_ix(&m.arena, &m.provider, 0, 20 * SHARE, 5 * SOL, 0), &[m.provider]);
+    assert_error(result, BetFunError::InsufficientFunds);
+
+    // Someone else's share account cannot fund the deposit
+    let holder = shareholder(&mut svm, &m.arena, 0, SHARE);
+    let mut ix = add_liquidity_ix(&m.arena, &m.provider, 0, SHARE, SOL / 4, 0);
+    ix.accounts[5].pubkey = share_ata(&holder, &m.arena, 0);
+    let result = svm.send_instruction(ix, &[m.provider]);
+    assert_error(result, BetFunError::Unauthorized);
+}
+
+#[test]
+fn opening_deposit_must_mint_the_minimum_lp() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    assert_ok(svm.send_instruction(
+        initialize_pool_ix(&arena, &creator, 0, FEE_BPS, PROTOCOL_FEE_BPS),
+        &[creator],
+    ));
+    let provider = shareholder(&mut svm, &arena, 0, SHARE);
+
+    // sqrt(1 * 999_999) rounds down to 999 LP tokens
+    let result = svm.send_instruction(add_liquidity_ix(&arena, &provider, 0, 1, 999_999, 0), &[provider]);
+    assert_error(result, BetFunError::InsufficientLiquidityMinted);
+
+    assert_ok(svm.send_instruction(add_liquidity_ix(&arena, &provider, 0, 1, 1_000_000, 0), &[provider]));
+    let pool: AMMPool = fetch(&svm, &pool_pda(&arena, 0));
+    assert_eq!(pool.total_lp_tokens, betfun_math::amm::MIN_INITIAL_LP_TOKENS);
+}
+
+#[test]
+fn remove_liquidity_returns_pro_rata_share() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+    let lp = 2 * SOL;
+
+    let result = svm.send_instruction(remove_liquidity_ix(&m.arena, &m.provider, 0, lp / 2, 0, SOL), &[m.provider]);
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    let provider_before = svm.lamports(&m.provider);
+    let tokens_before = token_balance(&svm, &share_ata(&m.provider, &m.arena, 0));
+    assert_ok(svm.send_instruction(
+        remove_liquidity_ix(&m.arena, &m.provider, 0, lp / 2, 2 * SHARE, SOL / 2),
+        &[m.provider],
+    ));
+    assert_eq!(svm.lamports(&m.provider), provider_before + SOL / 2);
+    assert_eq!(token_balance(&svm, &share_ata(&m.provider, &m.arena, 0)), tokens_before + 2 * SHARE);
+
+    let pool: AMMPool = fetch(&svm, &m.pool);
+    assert_eq!(pool.token_reserve, 2 * SHARE);
+    assert_eq!(pool.sol_reserve, SOL / 2);
+    assert_eq!(pool.total_lp_tokens, lp / 2);
+
+    // Draining the pool closes out the SOL vault entirely
+    assert_ok(svm.send_instruction(remove_liquidity_ix(&m.arena, &m.provider, 0, lp / 2, 0, 0), &[m.provider]));
+    assert_eq!(svm.lamports(&pool_sol_vault_pda(&m.pool)), 0);
+    assert_eq!(fetch::<AMMPool>(&svm, &m.pool).total_lp_tokens, 0);
+}
+
+#[test]
+fn sol_vault_pays_out_while_staying_system_owned() {
+    // The SOL vault is a system account, so only signed system transfers can
+    // move its lamports; writing them directly would fail the runtime's checks
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+    let vault = pool_sol_vault_pda(&m.pool);
+    let holder = shareholder(&mut svm, &m.arena, 0, SHARE);
+    let holder_tokens = share_ata(&holder, &m.arena, 0);
+
+    let before = svm.lamports(&vault);
+    assert_ok(svm.send_instruction(
+        swap_ix(&m.arena, &holder, &m.creator, &holder_tokens, &m.fee_recipient, 0, SHARE, 0, true),
+        &[holder],
+    ));
+    assert!(svm.lamports(&vault) < before);
+    assert_eq!(svm.account(&vault).unwrap().owner, system_program::ID);
+
+    let before = svm.lamports(&vault);
+    assert_ok(svm.send_instruction(remove_liquidity_ix(&m.arena, &m.provider, 0, SOL, 0, 0), &[m.provider]));
+    assert_eq!(svm.lamports(&vault), before - before / 2);
+    assert_eq!(svm.account(&vault).unwrap().owner, system_program::ID);
+}
+
+#[test]
+fn remove_liquidity_rejects_invalid_amounts() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+
+    let result = svm.send_instruction(remove_liquidity_ix(&m.arena, &m.provider, 0, 0, 0, 0), &[m.provider]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    let result = svm.send_instruction(remove_liquidity_ix(&m.arena, &m.provider, 0, 3 * SOL, 0, 0), &[m.provider]);
+    assert_error(result, BetFunError::InsufficientFunds);
+}
+
+// ========== SWAP ==========
+
+#[test]
+fn swap_sol_for_tokens_and_back() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+    let trader = wallet(&mut svm);
+    assert_ok(svm.send_instruction(create_share_ata_ix(&trader, &trader, &m.arena, 0), &[trader]));
+    let trader_tokens = share_ata(&trader, &m.arena, 0);
+
+    // SOL -> shares
+    let amount_in = SOL / 10;
+    let expected_out = amount_out(amount_in, SOL, 4 * SHARE);
+    let protocol_fee = protocol_fee_in(amount_in);
+    let recipient_before = svm.lamports(&m.fee_recipient);
+
+    let result = svm.send_instruction(
+        swap_ix(&m.arena, &trader, &m.creator, &trader_tokens, &m.fee_recipient, 0, amount_in, expected_out + 1, false),
+        &[trader],
+    );
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    assert_ok(svm.send_instruction(
+        swap_ix(&m.arena, &trader, &m.creator, &trader_tokens, &m.fee_recipient, 0, amount_in, expected_out, false),
+        &[trader],
+    ));
+    assert_eq!(token_balance(&svm, &trader_tokens), expected_out);
+    assert_eq!(svm.lamports(&m.fee_recipient), recipient_before + protocol_fee);
+
+    let pool: AMMPool = fetch(&svm, &m.pool);
+    assert_eq!(pool.sol_reserve, SOL + amount_in - protocol_fee);
+    assert_eq!(pool.token_reserve, 4 * SHARE - expected_out);
+    assert_eq!(pool.k, pool.token_reserve as u128 * pool.sol_reserve as u128);
+    assert_eq!(pool.swap_count, 1);
+    assert_eq!(svm.lamports(&pool_sol_vault_pda(&m.pool)), pool.sol_reserve);
+
+    // Shares -> SOL, with the protocol fee taken out of the SOL paid out
+    let sol_out = amount_out(expected_out, pool.token_reserve, pool.sol_reserve);
+    let protocol_fee = protocol_fee_in(sol_out);
+    let (sol_reserve, k_before) = (pool.sol_reserve, pool.k);
+    let trader_before = svm.lamports(&trader);
+    let recipient_before = svm.lamports(&m.fee_recipient);
+    let result = svm.send_instruction(
+        swap_ix(&m.arena, &trader, &m.creator, &trader_tokens, &m.fee_recipient, 0, expected_out, sol_out, true),
+        &[trader],
+    );
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+    let min_out = sol_out - protocol_fee;
+    assert_ok(svm.send_instruction(
+        swap_ix(&m.arena, &trader, &m.creator, &trader_tokens, &m.fee_recipient, 0, expected_out, min_out, true),
+        &[trader],
+    ));
+    assert_eq!(svm.lamports(&trader), trader_before + sol_out - protocol_fee);
+    assert_eq!(svm.lamports(&m.fee_recipient), recipient_before + protocol_fee);
+    assert_eq!(token_balance(&svm, &trader_tokens), 0);
+    let pool: AMMPool = fetch(&svm, &m.pool);
+    assert_eq!(pool.sol_reserve, sol_reserve - sol_out);
+    assert!(pool.k >= k_before);
+    assert_eq!(svm.lamports(&pool_sol_vault_pda(&m.pool)), pool.sol_reserve);
+}
+
+#[test]
+fn swap_rejects_invalid_requests() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+    let trader = wallet(&mut svm);
+    assert_ok(svm.send_instruction(create_share_ata_ix(&trader, &trader, &m.arena, 0), &[trader]));
+    let trader_tokens = share_ata(&trader, &m.arena, 0);
+    let swap = |amount_in, min_out, is_token_to_sol| {
+        swap_ix(&m.arena, &trader, &m.creator, &trader_tokens, &m.fee_recipient, 0, amount_in, min_out, is_token_to_sol)
+    };
+
+    let result = svm.send_instruction(swap(0, 0, false), &[trader]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    // One lamport is all fee, so nothing comes out
+    let result = svm.send_instruction(swap(1, 0, false), &[trader]);
+    assert_error(result, BetFunError::InsufficientOutputAmount);
+
+    let result = svm.send_instruction(swap(SHARE, 0, true), &[trader]);
+    assert_error(result, BetFunError::InsufficientFunds);
+
+    // Trading out of someone else's share account
+    let provider_tokens = share_ata(&m.provider, &m.arena, 0);
+    let result = svm.send_instruction(
+        swap_ix(&m.arena, &trader, &m.creator, &provider_tokens, &m.fee_recipient, 0, SHARE, 0, true),
+        &[trader],
+    );
+    assert_error(result, BetFunError::Unauthorized);
+
+    // Protocol fees only go to the config's recipient
+    let result = svm.send_instruction(
+        swap_ix(&m.arena, &trader, &m.creator, &trader_tokens, &trader, 0, SOL / 10, 0, false),
+        &[trader],
+    );
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn pool_vault_and_lp_mint_are_pinned_to_the_pool() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+    let holder = shareholder(&mut svm, &m.arena, 0, SHARE);
+    let holder_tokens = share_ata(&holder, &m.arena, 0);
+
+    // Selling shares into the seller's own account instead of the pool's vault
+    let mut swap = swap_ix(&m.arena, &holder, &m.creator, &holder_tokens, &m.fee_recipient, 0, SHARE, 0, true);
+    swap.accounts[4].pubkey = holder_tokens;
+    let failure = svm.send_instruction(swap, &[holder]).unwrap_err();
+    assert_eq!(failure.custom_code(), Some(u32::from(ErrorCode::ConstraintSeeds)), "{failure}");
+
+    let mut add = add_liquidity_ix(&m.arena, &holder, 0, SHARE, SOL / 4, 0);
+    add.accounts[4].pubkey = holder_tokens;
+    let failure = svm.send_instruction(add, &[holder]).unwrap_err();
+    assert_eq!(failure.custom_code(), Some(u32::from(ErrorCode::ConstraintSeeds)), "{failure}");
+
+    let provider_tokens = share_ata(&m.provider, &m.arena, 0);
+    let mut remove = remove_liquidity_ix(&m.arena, &m.provider, 0, SOL, 0, 0);
+    remove.accounts[3].pubkey = provider_tokens;
+    let failure = svm.send_instruction(remove, &[m.provider]).unwrap_err();
+    assert_eq!(failure.custom_code(), Some(u32::from(ErrorCode::ConstraintSeeds)), "{failure}");
+
+    // Burning LP tokens of a mint other than the pool's
+    let mut remove = remove_liquidity_ix(&m.arena, &m.provider, 0, SOL, 0, 0);
+    remove.accounts[2].pubkey = share_mint_pda(&m.arena, 0);
+    let result = svm.send_instruction(remove, &[m.provider]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn swap_requires_liquidity() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    assert_ok(svm.send_instruction(
+        initialize_pool_ix(&arena, &creator, 0, FEE_BPS, PROTOCOL_FEE_BPS),
+        &[creator],
+    ));
+    let trader = shareholder(&mut svm, &arena, 0, SHARE);
+    let fee_recipient = fee_recipient(&mut svm, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+
+    let result = svm.send_instruction(
+        swap_ix(&arena, &trader, &creator, &share_ata(&trader, &arena, 0), &fee_recipient, 0, SOL / 10, 0, false),
+        &[trader],
+    );
+    assert_error(result, BetFunError::InsufficientLiquidity);
+}
+
+#[test]
+fn swap_rejects_resolved_arena() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+    join(&mut svm, &m.arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&m.arena, &m.creator, 0), &[m.creator]));
+
+    let provider_tokens = share_ata(&m.provider, &m.arena, 0);
+    let result = svm.send_instruction(
+        swap_ix(&m.arena, &m.provider, &m.creator, &provider_tokens, &m.fee_recipient, 0, SHARE, 0, true),
+        &[m.provider],
+    );
+    assert_error(result, BetFunError::AlreadyResolved);
+}
+
+#[test]
+fn swap_rejects_locked_arena() {
+    let mut svm = setup();
+    let m = market(&mut svm, 4 * SHARE, SOL);
+    svm.advance_clock(DAY);
+
+    let provider_tokens = share_ata(&m.provider, &m.arena, 0);
+    let result = svm.send_instruction(
+        swap_ix(&m.arena, &m.provider, &m.creator, &provider_tokens, &m.fee_recipient, 0, SHARE, 0, true),
+        &[m.provider],
+    );
+    assert_error(result, BetFunError::ArenaEnded);
+}
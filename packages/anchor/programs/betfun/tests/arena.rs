@@ -0,0 +1,470 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_option::COption;
+use anchor_spl::token_2022::{self, spl_token_2022};
+use betfun::error::BetFunError;
+use betfun::instructions::TrophyRarity;
+use betfun::state::{Arena, Participant};
+use betfun_testkit::SvmError;
+use common::*;
+use spl_token_2022::extension::{metadata_pointer::MetadataPointer, BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+use spl_token_metadata_interface::state::TokenMetadata;
+
+/// Name, config change and the error it should trigger
+type ConfigCase = (&'static str, fn(&mut ArenaConfig), BetFunError);
+
+// ========== CREATE ARENA ==========
+
+#[test]
+fn create_arena_initializes_state() {
+    let mut svm = setup();
+    let config = ArenaConfig::new(&svm);
+    let (creator, arena) = new_arena_with(&mut svm, &config);
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.creator, creator);
+    assert_eq!(state.title, config.title);
+    assert_eq!(state.outcomes, config.outcomes);
+    assert_eq!(state.entry_fee, ENTRY_FEE);
+    assert_eq!((state.lock_time, state.resolve_after), (config.lock_time, config.resolve_after));
+    assert_eq!(state.outcome_counts, vec![0, 0]);
+    assert_eq!(state.outcome_pots, vec![0, 0]);
+    assert_eq!(state.pot, 0);
+    assert!(!state.resolved);
+    assert!(!state.creator_fee_paid);
+    assert_eq!(state.total_paid_out, 0);
+    assert_eq!(state.creator_fee_bps, Arena::DEFAULT_CREATOR_FEE_BPS);
+    assert_eq!(state.creator_trading_fee_bps, 0);
+
+    // The account is sized for its own strings, not the maximum lengths
+    let space = Arena::space(
+        config.title.len(),
+        config.description.len(),
+        config.question.len(),
+        config.outcomes.len(),
+        config.tags.len(),
+    );
+    assert_eq!(svm.account(&arena).unwrap().data.len(), space);
+}
+
+#[test]
+fn create_arena_rejects_invalid_configuration() {
+    let mut svm = setup();
+    let base = ArenaConfig::new(&svm);
+    let cases: [ConfigCase; 11] = [
+        ("short title", |c| c.title = "ab".to_string(), BetFunError::InvalidConfiguration),
+        ("short question", |c| c.question = "Who?".to_string(), BetFunError::InvalidConfiguration),
+        (
+            "duplicate outcomes",
+            |c| c.outcomes = vec!["Red".to_string(), " red ".to_string()],
+            BetFunError::InvalidConfiguration,
+        ),
+        ("empty tag", |c| c.tags = vec![String::new()], BetFunError::InvalidConfiguration),
+        ("entry fee above max", |c| c.entry_fee = 11 * SOL, BetFunError::InvalidConfiguration),
+        ("entry fee below min", |c| c.entry_fee = 999_999, BetFunError::InsufficientEntryFee),
+        (
+            "one outcome",
+            |c| c.outcomes = vec!["Red".to_string()],
+            BetFunError::TooFewOutcomes,
+        ),
+        (
+            "seven outcomes",
+            |c| c.outcomes = (0..7).map(|i| format!("Team {i}")).collect(),
+            BetFunError::TooManyOutcomes,
+        ),
+        ("long description", |c| c.description = "x".repeat(281), BetFunError::DescriptionTooLong),
+        (
+            "creator fee above max",
+            |c| c.creator_fee_bps = Arena::MAX_CREATOR_FEE_BPS + 1,
+            BetFunError::InvalidConfiguration,
+        ),
+        (
+            "trading fee above max",
+            |c| c.creator_trading_fee_bps = Arena::MAX_CREATOR_TRADING_FEE_BPS + 1,
+            BetFunError::InvalidConfiguration,
+        ),
+    ];
+
+    for (name, mutate, expected) in cases {
+        let creator = wallet(&mut svm);
+        let mut config = base.clone();
+        mutate(&mut config);
+        let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+        assert!(result.is_err(), "{name} was accepted");
+        assert_error(result, expected);
+    }
+}
+
+#[test]
+fn create_arena_rejects_oracle_equal_to_creator() {
+    let mut svm = setup();
+    let creator = wallet(&mut svm);
+    let mut config = ArenaConfig::new(&svm);
+    config.oracle = Some(creator);
+
+    let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn create_arena_rejects_times_outside_window() {
+    let mut svm = setup();
+    let creator = wallet(&mut svm);
+    let now = now(&svm);
+    let cases = [(now, now), (now + 366 * DAY, now + 366 * DAY), (now + DAY, now), (now + DAY, now + 367 * DAY)];
+
+    for (lock_time, resolve_after) in cases {
+        // Manual arenas lock on schedule too
+        for manual_resolve in [false, true] {
+            let config = ArenaConfig { lock_time, resolve_after, manual_resolve, ..ArenaConfig::new(&svm) };
+            let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+            assert_error(result, BetFunError::InvalidEndTime);
+        }
+    }
+}
+
+#[test]
+fn manual_arena_locks_betting_before_resolution() {
+    let mut svm = setup();
+    let config = ArenaConfig {
+        manual_resolve: true,
+        resolve_after: now(&svm) + 2 * DAY,
+        ..ArenaConfig::new(&svm)
+    };
+    let (creator, arena) = new_arena_with(&mut svm, &config);
+    join(&mut svm, &arena, 0);
+
+    // Betting closes at the lock even though the result isn't in yet
+    svm.advance_clock(DAY);
+    let user = wallet(&mut svm);
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 1), &[user]);
+    assert_error(result, BetFunError::ArenaEnded);
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]);
+    assert_error(result, BetFunError::ArenaNotEnded);
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+}
+
+#[test]
+fn create_arena_rejects_titles_longer_than_a_seed() {
+    let mut svm = setup();
+    let creator = wallet(&mut svm);
+    let mut config = ArenaConfig::new(&svm);
+    config.title = "t".repeat(Arena::MAX_TITLE_LEN + 1);
+
+    let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+    assert_error(result, BetFunError::TitleTooLong);
+
+    // The longest title still fits the seed
+    config.title.pop();
+    assert_ok(svm.send_instruction(create_arena_ix(&creator, &config), &[creator]));
+    assert_eq!(fetch::<Arena>(&svm, &arena_pda(&creator, &config.title)).title, config.title);
+}
+
+// ========== JOIN ARENA ==========
+
+#[test]
+fn join_arena_escrows_entry_fee() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let arena_lamports = svm.lamports(&arena);
+
+    let red = join(&mut svm, &arena, 0);
+    let blue = join(&mut svm, &arena, 1);
+    join(&mut svm, &arena, 1);
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.pot, 3 * ENTRY_FEE);
+    assert_eq!(state.participants_count, 3);
+    assert_eq!(state.outcome_counts, vec![1, 2]);
+    assert_eq!(state.outcome_pots, vec![ENTRY_FEE, 2 * ENTRY_FEE]);
+    assert_eq!(svm.lamports(&arena), arena_lamports + 3 * ENTRY_FEE);
+
+    let participant: Participant = fetch(&svm, &participant_pda(&arena, &red));
+    assert_eq!(participant.wallet, red);
+    assert_eq!(participant.outcome_chosen, 0);
+    assert_eq!(participant.amount, ENTRY_FEE);
+    assert!(!participant.claimed);
+
+    let participant: Participant = fetch(&svm, &participant_pda(&arena, &blue));
+    assert_eq!(participant.outcome_chosen, 1);
+}
+
+#[test]
+fn join_arena_rejects_invalid_outcome() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let user = wallet(&mut svm);
+
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 2), &[user]);
+    assert_error(result, BetFunError::InvalidOutcome);
+}
+
+#[test]
+fn join_arena_rejects_after_end_time() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let user = wallet(&mut svm);
+
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]);
+    assert_error(result, BetFunError::ArenaEnded);
+}
+
+#[test]
+fn join_arena_rejects_resolved_arena() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let user = wallet(&mut svm);
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]);
+    assert_error(result, BetFunError::AlreadyResolved);
+}
+
+#[test]
+fn join_arena_rejects_underfunded_user() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let user = Pubkey::new_unique();
+    svm.airdrop(&user, ENTRY_FEE / 2);
+
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]);
+    assert_error(result, BetFunError::InsufficientEntryFee);
+}
+
+#[test]
+fn join_arena_twice_fails() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let user = join(&mut svm, &arena, 0);
+
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 1), &[user]);
+    assert_error(result, BetFunError::AlreadyJoined);
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.participants_count, 1);
+}
+
+// ========== RESOLVE ARENA ==========
+
+#[test]
+fn resolve_arena_after_end_time() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    join(&mut svm, &arena, 0);
+    join(&mut svm, &arena, 1);
+
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, 1), &[creator]);
+    assert_error(result, BetFunError::ArenaNotEnded);
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 1), &[creator]));
+
+    let state: Arena = fetch(&svm, &arena);
+    assert!(state.resolved);
+    assert_eq!(state.winner_outcome, Some(1));
+
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]);
+    assert_error(result, BetFunError::AlreadyResolved);
+}
+
+#[test]
+fn oracle_can_resolve() {
+    let mut svm = setup();
+    let oracle = wallet(&mut svm);
+    let mut config = ArenaConfig::new(&svm);
+    config.oracle = Some(oracle);
+    let (_, arena) = new_arena_with(&mut svm, &config);
+    join(&mut svm, &arena, 0);
+
+    svm.advance_clock(DAY);
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &stranger, 0), &[stranger]);
+    assert_error(result, BetFunError::UnauthorizedResolver);
+
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &oracle, 0), &[oracle]));
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.winner_outcome, Some(0));
+}
+
+#[test]
+fn resolve_arena_requires_backed_winner() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    svm.advance_clock(DAY);
+
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let mut config = ArenaConfig::new(&svm);
+    config.title = "Semi Final".to_string();
+    let (creator, arena) = new_arena_with(&mut svm, &config);
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+
+    // Out of range, then an outcome nobody picked
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, 2), &[creator]);
+    assert_error(result, BetFunError::InvalidOutcome);
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, 1), &[creator]);
+    assert_error(result, BetFunError::InvalidOutcome);
+}
+
+// ========== CLAIM WINNINGS ==========
+
+#[test]
+fn claim_winnings_pays_winners_and_creator_fee_once() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let first = join(&mut svm, &arena, 0);
+    let second = join(&mut svm, &arena, 0);
+    let loser = join(&mut svm, &arena, 1);
+
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &first, &creator), &[first]);
+    assert_error(result, BetFunError::NotResolved);
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let pot = 3 * ENTRY_FEE;
+    let creator_fee = pot * Arena::DEFAULT_CREATOR_FEE_BPS as u64 / 10_000;
+    let payout = ENTRY_FEE * (pot - creator_fee) / (2 * ENTRY_FEE);
+    let creator_before = svm.lamports(&creator);
+    let arena_before = svm.lamports(&arena);
+
+    let first_before = svm.lamports(&first);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &first, &creator), &[first]));
+    assert_eq!(svm.lamports(&first), first_before + payout);
+    assert_eq!(svm.lamports(&creator), creator_before + creator_fee);
+    assert!(fetch::<Arena>(&svm, &arena).creator_fee_paid);
+    assert!(fetch::<Participant>(&svm, &participant_pda(&arena, &first)).claimed);
+
+    let second_before = svm.lamports(&second);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &second, &creator), &[second]));
+    assert_eq!(svm.lamports(&second), second_before + payout);
+    assert_eq!(svm.lamports(&creator), creator_before + creator_fee);
+
+    // The whole pot is paid out; only the arena's rent reserve is left
+    assert_eq!(svm.lamports(&arena), arena_before - pot);
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.total_paid_out, pot);
+    assert_eq!(state.unclaimed_pot(), 0);
+
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &first, &creator), &[first]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &loser, &creator), &[loser]);
+    assert_error(result, BetFunError::NotWinner);
+}
+
+#[test]
+fn claim_winnings_rejects_wrong_creator() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let impostor = wallet(&mut svm);
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &winner, &impostor), &[winner]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn claim_winnings_requires_participant_signature() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &winner, &creator), &[]);
+    let failure = result.expect_err("unsigned claim succeeded");
+    assert_eq!(failure.error, SvmError::MissingRequiredSignature(winner));
+}
+
+// ========== MINT TROPHY ==========
+
+#[test]
+fn mint_trophy_mints_once_after_claim() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    let loser = join(&mut svm, &arena, 1);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let result = svm.send_instruction(mint_trophy_ix(&arena, &winner), &[winner]);
+    assert_error(result, BetFunError::NotResolved);
+
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &winner, &creator), &[winner]));
+    assert_ok(svm.send_instruction(mint_trophy_ix(&arena, &winner), &[winner]));
+    let participant_key = participant_pda(&arena, &winner);
+    let participant: Participant = fetch(&svm, &participant_key);
+    assert_eq!(participant.trophy_mint, Some(trophy_mint_pda(&participant_key)));
+
+    // The trophy mint already exists, so its `init` fails before any constraint runs
+    assert!(svm.send_instruction(mint_trophy_ix(&arena, &winner), &[winner]).is_err());
+
+    // Losers never get `claimed` set, so the claim check fires before the winner check
+    let result = svm.send_instruction(mint_trophy_ix(&arena, &loser), &[loser]);
+    assert_error(result, BetFunError::NotResolved);
+}
+
+#[test]
+fn trophies_are_token_2022_nfts_with_arena_metadata() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 1);
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 1), &[creator]));
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &winner, &creator), &[winner]));
+    let minted_at = now(&svm);
+    assert_ok(svm.send_instruction(mint_trophy_ix(&arena, &winner), &[winner]));
+
+    // Genuine trophies are Token-2022 mints whose authority is the program's PDA
+    let trophy_mint = trophy_mint_pda(&participant_pda(&arena, &winner));
+    let account = svm.account(&trophy_mint).unwrap();
+    assert_eq!(account.owner, token_2022::ID);
+    let mint = StateWithExtensions::<Mint>::unpack(&account.data).unwrap();
+    assert_eq!(mint.base.mint_authority, COption::Some(trophy_authority_pda()));
+    assert_eq!((mint.base.supply, mint.base.decimals), (1, 0));
+    assert_eq!(mint.get_extension::<MetadataPointer>().unwrap().metadata_address.0, trophy_mint);
+
+    let metadata = mint.get_variable_len_extension::<TokenMetadata>().unwrap();
+    assert_eq!(metadata.name, "Grand Final Trophy");
+    assert_eq!(metadata.symbol, "TROPHY");
+    assert_eq!(metadata.update_authority.0, trophy_authority_pda());
+    let expected = [
+        ("arena", arena.to_string()),
+        ("title", "Grand Final".to_string()),
+        ("question", "Who takes the grand final?".to_string()),
+        ("outcome", "Blue".to_string()),
+        ("amount_won", ENTRY_FEE.to_string()),
+        ("rarity", "Rare".to_string()),
+        ("minted_at", minted_at.to_string()),
+    ];
+    let expected: Vec<_> = expected.into_iter().map(|(key, value)| (key.to_string(), value)).collect();
+    assert_eq!(metadata.additional_metadata, expected);
+
+    let holder = svm.account(&trophy_ata(&winner, &trophy_mint)).unwrap();
+    let holder = StateWithExtensions::<TokenAccount>::unpack(&holder.data).unwrap();
+    assert_eq!((holder.base.owner, holder.base.amount), (winner, 1));
+}
+
+#[test]
+fn trophy_rarity_spans_the_entry_fee_bounds() {
+    assert_eq!(TrophyRarity::from_amount(Arena::MIN_ENTRY_FEE), TrophyRarity::Common);
+    assert_eq!(TrophyRarity::from_amount(ENTRY_FEE), TrophyRarity::Rare);
+    assert_eq!(TrophyRarity::from_amount(TrophyRarity::RARE_AMOUNT - 1), TrophyRarity::Common);
+    assert_eq!(TrophyRarity::from_amount(TrophyRarity::EPIC_AMOUNT - 1), TrophyRarity::Rare);
+    assert_eq!(TrophyRarity::from_amount(TrophyRarity::EPIC_AMOUNT), TrophyRarity::Epic);
+    assert_eq!(TrophyRarity::from_amount(Arena::MAX_ENTRY_FEE - 1), TrophyRarity::Epic);
+    assert_eq!(TrophyRarity::from_amount(Arena::MAX_ENTRY_FEE), TrophyRarity::Legendary);
+}
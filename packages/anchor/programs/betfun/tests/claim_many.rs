@@ -0,0 +1,142 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{Arena, OutcomeShare, Participant, ShareBalance};
+use betfun_math::curve::Curve;
+use betfun_testkit::Svm;
+use common::*;
+
+#[test]
+fn claim_many_pays_every_entry_at_once() {
+    let mut svm = setup();
+    let user = wallet(&mut svm);
+
+    // A winning bet and winning shares in one arena, a bet in another that gets voided
+    let (creator, market) = new_share_market(&mut svm);
+    assert_ok(svm.send_instruction(join_arena_ix(&market, &user, 0), &[user]));
+    let shares = SHARE / 10;
+    assert_ok(svm.send_instruction(buy_shares_ix(&market, &user, &creator, 0, shares, None, None), &[user]));
+    join(&mut svm, &market, 1);
+    shareholder(&mut svm, &market, 1, 2 * SHARE);
+    let (voided_creator, voided) = new_arena(&mut svm);
+    assert_ok(svm.send_instruction(join_arena_ix(&voided, &user, 0), &[user]));
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&market, &creator, 0), &[creator]));
+    svm.advance_clock(Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&voided, &user), &[user]));
+
+    // The bet listed twice is paid once
+    let entries = [
+        bet_entry(&market, &user, &creator),
+        share_entry(&market, &user, 0),
+        bet_entry(&voided, &user, &voided_creator),
+        bet_entry(&market, &user, &creator),
+    ];
+    let pot = 2 * ENTRY_FEE;
+    let creator_fee = pot * Arena::DEFAULT_CREATOR_FEE_BPS as u64 / 10_000;
+    // The user holds every winning share, so the losing side's purchases go to them too
+    let redemption = share_reserve(&svm, &market);
+    let user_before = svm.lamports(&user);
+    let creator_before = svm.lamports(&creator);
+    assert_ok(svm.send_instruction(claim_many_ix(&user, &entries), &[user]));
+    assert_eq!(svm.lamports(&user), user_before + pot - creator_fee + redemption + ENTRY_FEE);
+    assert_eq!(svm.lamports(&creator), creator_before + creator_fee);
+    assert!(fetch::<Participant>(&svm, &participant_pda(&voided, &user)).claimed);
+    let balance: ShareBalance = fetch(&svm, &share_balance_pda(&outcome_share_pda(&market, 0), &user));
+    assert_eq!(balance.balance, 0);
+    assert_eq!(token_balance(&svm, &share_ata(&user, &market, 0)), 0);
+
+    // Settled entries are skipped rather than failing the batch
+    let user_before = svm.lamports(&user);
+    assert_ok(svm.send_instruction(claim_many_ix(&user, &entries), &[user]));
+    assert_eq!(svm.lamports(&user), user_before);
+    let result = svm.send_instruction(claim_winnings_ix(&market, &user, &creator), &[user]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+}
+
+#[test]
+fn claim_many_skips_entries_with_nothing_to_claim() {
+    let mut svm = setup();
+    let user = wallet(&mut svm);
+
+    // In one resolved market the user's bet and shares on 0 lose and their shares on 1 win
+    let (creator, market) = new_share_market(&mut svm);
+    assert_ok(svm.send_instruction(join_arena_ix(&market, &user, 0), &[user]));
+    join(&mut svm, &market, 1);
+    let shares = SHARE / 10;
+    for outcome_index in [0, 1] {
+        let buy = buy_shares_ix(&market, &user, &creator, outcome_index, shares, None, None);
+        assert_ok(svm.send_instruction(buy, &[user]));
+    }
+    // Shares in a market that gets voided, and a bet in one nobody resolves
+    let config = ArenaConfig { title: "Voided Final".to_string(), ..ArenaConfig::new(&svm) };
+    let (voided_creator, voided) = new_share_market_with(&mut svm, &config);
+    let buy = buy_shares_ix(&voided, &user, &voided_creator, 0, shares, None, None);
+    assert_ok(svm.send_instruction(buy, &[user]));
+    let config = ArenaConfig { title: "Open Final".to_string(), ..ArenaConfig::new(&svm) };
+    let (open_creator, open) = new_arena_with(&mut svm, &config);
+    assert_ok(svm.send_instruction(join_arena_ix(&open, &user, 0), &[user]));
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&market, &creator, 1), &[creator]));
+    svm.advance_clock(Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&voided, &user), &[user]));
+
+    let entries = [
+        bet_entry(&market, &user, &creator),
+        share_entry(&market, &user, 0),
+        share_entry(&market, &user, 1),
+        share_entry(&voided, &user, 0),
+        bet_entry(&open, &user, &open_creator),
+    ];
+    // The user holds every winning share, and the voided shares sell back down the curve
+    let redemption = share_reserve(&svm, &market);
+    let share: OutcomeShare = fetch(&svm, &outcome_share_pda(&voided, 0));
+    let refund = Curve::from(share.curve).sell_proceeds(share.total_supply, shares).unwrap();
+    let before = svm.lamports(&user);
+    assert_ok(svm.send_instruction(claim_many_ix(&user, &entries), &[user]));
+    assert_eq!(svm.lamports(&user), before + redemption + refund);
+
+    // Skipped positions are left as they were
+    for arena in [market, open] {
+        assert!(!fetch::<Participant>(&svm, &participant_pda(&arena, &user)).claimed);
+    }
+    let balance = |svm: &Svm, arena: &Pubkey, outcome_index: u8| {
+        fetch::<ShareBalance>(svm, &share_balance_pda(&outcome_share_pda(arena, outcome_index), &user)).balance
+    };
+    assert_eq!(balance(&svm, &market, 0), shares);
+    assert_eq!(balance(&svm, &market, 1), 0);
+    assert_eq!(balance(&svm, &voided, 0), 0);
+}
+
+#[test]
+fn claim_many_rejects_bad_entries() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    let loser = join(&mut svm, &arena, 1);
+    let (other_creator, other) = new_arena(&mut svm);
+    assert_ok(svm.send_instruction(join_arena_ix(&other, &winner, 0), &[winner]));
+
+    assert_error(svm.send_instruction(claim_many_ix(&winner, &[]), &[winner]), BetFunError::InvalidConfiguration);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    // One entry whose accounts do not match fails the whole batch
+    let mut mismatched = bet_entry(&arena, &winner, &creator);
+    mismatched[1] = bet_entry(&other, &winner, &other_creator)[1].clone();
+    let mut truncated = bet_entry(&arena, &winner, &creator);
+    truncated.pop();
+    let cases = [
+        (loser, vec![bet_entry(&arena, &winner, &creator)], BetFunError::NotParticipant),
+        (winner, vec![bet_entry(&arena, &winner, &other_creator)], BetFunError::InvalidConfiguration),
+        (winner, vec![mismatched], BetFunError::InvalidConfiguration),
+        (winner, vec![bet_entry(&other, &winner, &other_creator), truncated], BetFunError::InvalidConfiguration),
+    ];
+    for (user, entries, expected) in cases {
+        assert_error(svm.send_instruction(claim_many_ix(&user, &entries), &[user]), expected);
+    }
+    assert!(!fetch::<Participant>(&svm, &participant_pda(&arena, &winner)).claimed);
+}
@@ -0,0 +1,1519 @@
+//! Shared fixtures for the program integration tests
+//!
+//! Each test runs the real program entrypoint inside `betfun_testkit::Svm`
+//! next to the System, SPL Token, Token-2022 and Associated Token Account
+//! programs, so the whole suite works offline.
+//!
+//! Every `BetFunError` variant with a reachable failure path is exercised by at
+//! least one test.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{instruction::Instruction, program_pack::Pack, sysvar};
+use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{
+    self, get_associated_token_address, get_associated_token_address_with_program_id, spl_associated_token_account,
+};
+use anchor_spl::token::{self, spl_token};
+use anchor_spl::token_2022;
+use betfun::error::BetFunError;
+use betfun::instructions::PlaceOrderParams;
+use betfun::state::{Arena, CurveType, Duel, OrderSide, OrderType, ScalarRange, StakeWeighting, Tournament};
+use betfun_testkit::{FailedTransaction, Svm, TransactionMetadata};
+
+pub const SOL: u64 = 1_000_000_000;
+
+/// One whole share in base units (share mints use 9 decimals)
+pub const SHARE: u64 = 1_000_000_000;
+
+pub const ENTRY_FEE: u64 = 100_000_000;
+pub const DAY: i64 = 24 * 60 * 60;
+
+/// Linear curve used by the share fixtures: 0.1 SOL base, +0.01 SOL per share
+pub const BASE_PRICE: u64 = 100_000_000;
+pub const SLOPE: u64 = 10_000_000;
+
+/// Config admin created by `setup`
+pub const ADMIN: Pubkey = Pubkey::new_from_array([0xad; 32]);
+
+pub type TxResult = std::result::Result<TransactionMetadata, FailedTransaction>;
+
+/// Runtime with the BetFun program loaded and its config owned by `ADMIN`
+pub fn setup() -> Svm {
+    let mut svm = Svm::new();
+    svm.add_program(betfun::ID, betfun::entry);
+    svm.set_upgrade_authority(&betfun::ID, Some(ADMIN));
+    svm.airdrop(&ADMIN, 100 * SOL);
+    assert_ok(svm.send_instruction(initialize_config_ix(&ADMIN), &[ADMIN]));
+    svm
+}
+
+/// Fresh wallet holding 100 SOL
+pub fn wallet(svm: &mut Svm) -> Pubkey {
+    let wallet = Pubkey::new_unique();
+    svm.airdrop(&wallet, 100 * SOL);
+    wallet
+}
+
+pub fn now(svm: &Svm) -> i64 {
+    svm.clock().unix_timestamp
+}
+
+#[track_caller]
+pub fn assert_ok(result: TxResult) -> TransactionMetadata {
+    result.unwrap_or_else(|failure| panic!("transaction failed: {failure}\n{:#?}", failure.logs))
+}
+
+#[track_caller]
+pub fn assert_error(result: TxResult, expected: BetFunError) {
+    let code = u32::from(expected);
+    match result {
+        Ok(_) => panic!("expected {expected:?}, but the transaction succeeded"),
+        Err(failure) => assert_eq!(
+            failure.custom_code(),
+            Some(code),
+            "expected {expected:?} ({code}), got: {failure}"
+        ),
+    }
+}
+
+// ========== ACCOUNT STATE ==========
+
+pub fn fetch<T: AccountDeserialize>(svm: &Svm, key: &Pubkey) -> T {
+    let account = svm
+        .account(key)
+        .unwrap_or_else(|| panic!("account {key} does not exist"));
+    T::try_deserialize(&mut account.data.as_slice())
+        .unwrap_or_else(|error| panic!("account {key} failed to deserialize: {error}"))
+}
+
+pub fn token_balance(svm: &Svm, key: &Pubkey) -> u64 {
+    let account = svm
+        .account(key)
+        .unwrap_or_else(|| panic!("token account {key} does not exist"));
+    spl_token::state::Account::unpack(&account.data)
+        .expect("not a token account")
+        .amount
+}
+
+/// Escrow lamports backing an arena's share sales and redemptions
+pub fn share_reserve(svm: &Svm, arena: &Pubkey) -> u64 {
+    let escrow = svm.account(arena).unwrap_or_else(|| panic!("arena {arena} does not exist"));
+    let rent_exempt_min = svm.rent().minimum_balance(escrow.data.len());
+    fetch::<Arena>(svm, arena).share_reserve(escrow.lamports, rent_exempt_min)
+}
+
+// ========== PDAS ==========
+
+fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &betfun::ID).0
+}
+
+pub fn config_pda() -> Pubkey {
+    pda(&[b"config"])
+}
+
+pub fn arena_pda(creator: &Pubkey, title: &str) -> Pubkey {
+    pda(&[b"arena", creator.as_ref(), &title.as_bytes()[..title.len().min(Arena::MAX_TITLE_LEN)]])
+}
+
+pub fn participant_pda(arena: &Pubkey, user: &Pubkey) -> Pubkey {
+    pda(&[b"participant", arena.as_ref(), user.as_ref()])
+}
+
+pub fn outcome_share_pda(arena: &Pubkey, outcome_index: u8) -> Pubkey {
+    pda(&[b"outcome_share", arena.as_ref(), &[outcome_index]])
+}
+
+pub fn share_mint_pda(arena: &Pubkey, outcome_index: u8) -> Pubkey {
+    pda(&[b"share_mint", arena.as_ref(), &[outcome_index]])
+}
+
+pub fn price_history_pda(arena: &Pubkey, outcome_index: u8) -> Pubkey {
+    pda(&betfun::state::PriceHistory::seeds(arena, &outcome_index))
+}
+
+pub fn share_balance_pda(outcome_share: &Pubkey, owner: &Pubkey) -> Pubkey {
+    pda(&[b"share_balance", outcome_share.as_ref(), owner.as_ref()])
+}
+
+pub fn pool_pda(arena: &Pubkey, outcome_index: u8) -> Pubkey {
+    pda(&[b"amm_pool", arena.as_ref(), &[outcome_index]])
+}
+
+pub fn lp_mint_pda(pool: &Pubkey) -> Pubkey {
+    pda(&[b"lp_token", pool.as_ref()])
+}
+
+pub fn pool_token_vault_pda(pool: &Pubkey) -> Pubkey {
+    pda(&[b"pool_token_vault", pool.as_ref()])
+}
+
+pub fn pool_sol_vault_pda(pool: &Pubkey) -> Pubkey {
+    pda(&[b"pool_sol_vault", pool.as_ref()])
+}
+
+pub fn liquidity_position_pda(pool: &Pubkey, provider: &Pubkey) -> Pubkey {
+    pda(&[b"liquidity_position", pool.as_ref(), provider.as_ref()])
+}
+
+pub fn order_book_pda(arena: &Pubkey, outcome_index: u8) -> Pubkey {
+    pda(&[b"order_book", arena.as_ref(), &[outcome_index]])
+}
+
+pub fn limit_order_pda(arena: &Pubkey, outcome_index: u8, order_id: u64) -> Pubkey {
+    pda(&[b"limit_order", arena.as_ref(), &[outcome_index], &order_id.to_le_bytes()])
+}
+
+pub fn order_escrow_pda(limit_order: &Pubkey) -> Pubkey {
+    pda(&[b"order_escrow", limit_order.as_ref()])
+}
+
+pub fn trade_pda(arena: &Pubkey, outcome_index: u8, trade_id: u64) -> Pubkey {
+    pda(&[b"trade", arena.as_ref(), &[outcome_index], &trade_id.to_le_bytes()])
+}
+
+pub fn referral_pda(user: &Pubkey) -> Pubkey {
+    pda(&[b"referral", user.as_ref()])
+}
+
+pub fn user_profile_pda(user: &Pubkey) -> Pubkey {
+    pda(&[b"user_profile", user.as_ref()])
+}
+
+pub fn parlay_vault_pda() -> Pubkey {
+    pda(&[b"parlay_vault"])
+}
+
+pub fn parlay_pda(owner: &Pubkey, parlay_id: u64) -> Pubkey {
+    pda(&[b"parlay", owner.as_ref(), &parlay_id.to_le_bytes()])
+}
+
+pub fn tournament_pda(organizer: &Pubkey, name: &str) -> Pubkey {
+    pda(&[b"tournament", organizer.as_ref(), name.as_bytes()])
+}
+
+pub fn tournament_entry_pda(tournament: &Pubkey, user: &Pubkey) -> Pubkey {
+    pda(&[b"tournament_entry", tournament.as_ref(), user.as_ref()])
+}
+
+/// Match arena of a bracket slot
+pub fn tournament_match_pda(tournament: &Pubkey, slot: u8) -> Pubkey {
+    arena_pda(tournament, &Tournament::match_title(slot))
+}
+
+pub fn trophy_mint_pda(participant: &Pubkey) -> Pubkey {
+    pda(&[b"trophy_mint", participant.as_ref()])
+}
+
+pub fn trophy_authority_pda() -> Pubkey {
+    pda(&[b"trophy_authority"])
+}
+
+/// Owner's Token-2022 token account for a trophy mint
+pub fn trophy_ata(owner: &Pubkey, trophy_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address_with_program_id(owner, trophy_mint, &token_2022::ID)
+}
+
+pub fn bond_vault_pda(arena: &Pubkey) -> Pubkey {
+    pda(&[b"bond_vault", arena.as_ref()])
+}
+
+/// Owner's associated token account for a bond mint
+pub fn bond_ata(owner: &Pubkey, bond_mint: &Pubkey) -> Pubkey {
+    get_associated_token_address(owner, bond_mint)
+}
+
+/// Owner's associated token account for an outcome's share mint
+pub fn share_ata(owner: &Pubkey, arena: &Pubkey, outcome_index: u8) -> Pubkey {
+    get_associated_token_address(owner, &share_mint_pda(arena, outcome_index))
+}
+
+// ========== CONFIG INSTRUCTIONS ==========
+
+pub fn initialize_config_ix(admin: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::InitializeConfig {
+            config: config_pda(),
+            admin: *admin,
+            program: betfun::ID,
+            program_data: Svm::program_data_address(&betfun::ID),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::InitializeConfig {}.data(),
+    }
+}
+
+pub fn transfer_admin_ix(admin: &Pubkey, new_admin: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::TransferAdmin {
+            config: config_pda(),
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::TransferAdmin { new_admin: *new_admin }.data(),
+    }
+}
+
+pub fn set_global_pause_ix(admin: &Pubkey, paused: u8) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SetGlobalPause {
+            config: config_pda(),
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SetGlobalPause { paused }.data(),
+    }
+}
+
+pub fn set_arena_pause_ix(arena: &Pubkey, authority: &Pubkey, paused: u8) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SetArenaPause {
+            arena: *arena,
+            config: config_pda(),
+            authority: *authority,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SetArenaPause { paused }.data(),
+    }
+}
+
+pub fn set_creator_bond_ix(admin: &Pubkey, bond: u64) -> Instruction {
+    set_bond_ix(admin, bond, None)
+}
+
+/// Bond creators in `bond_mint` instead of lamports
+pub fn set_token_bond_ix(admin: &Pubkey, bond: u64, bond_mint: &Pubkey) -> Instruction {
+    set_bond_ix(admin, bond, Some(*bond_mint))
+}
+
+fn set_bond_ix(admin: &Pubkey, bond: u64, bond_mint: Option<Pubkey>) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SetCreatorBond {
+            config: config_pda(),
+            admin: *admin,
+            bond_mint,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SetCreatorBond { bond }.data(),
+    }
+}
+
+pub fn set_protocol_fees_ix(admin: &Pubkey, fee_recipient: &Pubkey, order_book_fee_bps: u16) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SetProtocolFees {
+            config: config_pda(),
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SetProtocolFees {
+            fee_recipient: *fee_recipient,
+            order_book_fee_bps,
+        }
+        .data(),
+    }
+}
+
+/// New wallet the config pays protocol fees to, charging `order_book_fee_bps` a side on matches
+pub fn fee_recipient(svm: &mut Svm, order_book_fee_bps: u16) -> Pubkey {
+    let recipient = wallet(svm);
+    assert_ok(svm.send_instruction(set_protocol_fees_ix(&ADMIN, &recipient, order_book_fee_bps), &[ADMIN]));
+    recipient
+}
+
+// ========== ARENA INSTRUCTIONS ==========
+
+/// Arguments for `create_arena`
+#[derive(Clone)]
+pub struct ArenaConfig {
+    pub title: String,
+    pub description: String,
+    pub question: String,
+    pub outcomes: Vec<String>,
+    pub tags: Vec<String>,
+    pub entry_fee: u64,
+    pub lock_time: i64,
+    pub resolve_after: i64,
+    pub manual_resolve: bool,
+    pub oracle: Option<Pubkey>,
+    pub creator_fee_bps: u16,
+    pub creator_trading_fee_bps: u16,
+    pub scalar: Option<ScalarRange>,
+    pub allowlist_root: Option<[u8; 32]>,
+    pub stake_weighting: StakeWeighting,
+    pub duel: Option<Duel>,
+    /// The config's bond mint, when creators are bonded in a token
+    pub bond_mint: Option<Pubkey>,
+}
+
+impl ArenaConfig {
+    /// Two-outcome arena locking and resolvable in a day
+    pub fn new(svm: &Svm) -> Self {
+        Self {
+            title: "Grand Final".to_string(),
+            description: "Best of five".to_string(),
+            question: "Who takes the grand final?".to_string(),
+            outcomes: vec!["Red".to_string(), "Blue".to_string()],
+            tags: vec!["esports".to_string()],
+            entry_fee: ENTRY_FEE,
+            lock_time: now(svm) + DAY,
+            resolve_after: now(svm) + DAY,
+            manual_resolve: false,
+            oracle: None,
+            creator_fee_bps: Arena::DEFAULT_CREATOR_FEE_BPS,
+            creator_trading_fee_bps: 0,
+            scalar: None,
+            allowlist_root: None,
+            stake_weighting: StakeWeighting::Flat,
+            duel: None,
+            bond_mint: None,
+        }
+    }
+}
+
+pub fn create_arena_ix(creator: &Pubkey, config: &ArenaConfig) -> Instruction {
+    let arena = arena_pda(creator, &config.title);
+    let duel = config.duel.is_some();
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::CreateArena {
+            arena,
+            config: config_pda(),
+            creator: *creator,
+            participant: duel.then(|| participant_pda(&arena, creator)),
+            user_profile: duel.then(|| user_profile_pda(creator)),
+            bond_mint: config.bond_mint,
+            creator_bond_account: config.bond_mint.map(|mint| bond_ata(creator, &mint)),
+            bond_vault: config.bond_mint.map(|_| bond_vault_pda(&arena)),
+            token_program: config.bond_mint.map(|_| token::ID),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::CreateArena {
+            title: config.title.clone(),
+            description: config.description.clone(),
+            question: config.question.clone(),
+            outcomes: config.outcomes.clone(),
+            tags: config.tags.clone(),
+            entry_fee: config.entry_fee,
+            lock_time: config.lock_time,
+            resolve_after: config.resolve_after,
+            manual_resolve: config.manual_resolve,
+            oracle: config.oracle,
+            token_mint: None,
+            creator_fee_bps: config.creator_fee_bps,
+            creator_trading_fee_bps: config.creator_trading_fee_bps,
+            scalar: config.scalar,
+            allowlist_root: config.allowlist_root,
+            stake_weighting: config.stake_weighting,
+            duel: config.duel,
+        }
+        .data(),
+    }
+}
+
+pub fn join_arena_ix(arena: &Pubkey, user: &Pubkey, outcome_chosen: u8) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::JoinArena {
+            arena: *arena,
+            config: config_pda(),
+            participant: participant_pda(arena, user),
+            user: *user,
+            user_profile: user_profile_pda(user),
+            referrer: None,
+            referral: None,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::JoinArena { outcome_chosen, proof: vec![] }.data(),
+    }
+}
+
+pub fn set_allowlist_ix(arena: &Pubkey, creator: &Pubkey, allowlist_root: Option<[u8; 32]>) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SetAllowlist {
+            arena: *arena,
+            creator: *creator,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SetAllowlist { allowlist_root }.data(),
+    }
+}
+
+/// `ix` (a join or share purchase) with `proof` in place of its empty allowlist proof
+/// The proof is the last argument, so the empty vector's length prefix ends the data.
+pub fn with_proof(mut ix: Instruction, proof: &[[u8; 32]]) -> Instruction {
+    ix.data.truncate(ix.data.len() - 4);
+    proof.to_vec().serialize(&mut ix.data).unwrap();
+    ix
+}
+
+pub fn resolve_arena_ix(arena: &Pubkey, resolver: &Pubkey, winner_outcome: u8) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ResolveArena {
+            arena: *arena,
+            resolver: *resolver,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ResolveArena { winner_outcome }.data(),
+    }
+}
+
+pub fn resolve_scalar_arena_ix(arena: &Pubkey, resolver: &Pubkey, value: i64) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ResolveScalarArena {
+            arena: *arena,
+            resolver: *resolver,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ResolveScalarArena { value }.data(),
+    }
+}
+
+pub fn claim_winnings_ix(arena: &Pubkey, user: &Pubkey, creator: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ClaimWinnings {
+            arena: *arena,
+            config: config_pda(),
+            participant: participant_pda(arena, user),
+            user: *user,
+            user_profile: user_profile_pda(user),
+            creator: *creator,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ClaimWinnings {}.data(),
+    }
+}
+
+/// Book `owner`'s losing bet if `bet`, and their shares of the `shares` outcome if given
+pub fn settle_profile_ix(
+    arena: &Pubkey,
+    owner: &Pubkey,
+    caller: &Pubkey,
+    bet: bool,
+    shares: Option<u8>,
+) -> Instruction {
+    let outcome_share = shares.map(|outcome_index| outcome_share_pda(arena, outcome_index));
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SettleProfile {
+            arena: *arena,
+            owner: *owner,
+            user_profile: user_profile_pda(owner),
+            participant: bet.then(|| participant_pda(arena, owner)),
+            outcome_share,
+            share_balance: outcome_share.map(|outcome_share| share_balance_pda(&outcome_share, owner)),
+            caller: *caller,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SettleProfile {}.data(),
+    }
+}
+
+pub fn cancel_duel_ix(arena: &Pubkey, creator: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::CancelDuel {
+            arena: *arena,
+            participant: participant_pda(arena, creator),
+            creator: *creator,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::CancelDuel {}.data(),
+    }
+}
+
+pub fn void_arena_ix(arena: &Pubkey, caller: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::VoidArena {
+            arena: *arena,
+            caller: *caller,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::VoidArena {}.data(),
+    }
+}
+
+pub fn release_creator_bond_ix(arena: &Pubkey, creator: &Pubkey, caller: &Pubkey) -> Instruction {
+    release_bond_ix(arena, creator, caller, None)
+}
+
+/// Return a bond in `bond_mint` to the creator's associated token account
+pub fn release_token_bond_ix(arena: &Pubkey, creator: &Pubkey, caller: &Pubkey, bond_mint: &Pubkey) -> Instruction {
+    release_bond_ix(arena, creator, caller, Some(bond_mint))
+}
+
+fn release_bond_ix(arena: &Pubkey, creator: &Pubkey, caller: &Pubkey, bond_mint: Option<&Pubkey>) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ReleaseCreatorBond {
+            arena: *arena,
+            creator: *creator,
+            caller: *caller,
+            bond_vault: bond_mint.map(|_| bond_vault_pda(arena)),
+            creator_bond_account: bond_mint.map(|mint| bond_ata(creator, mint)),
+            token_program: bond_mint.map(|_| token::ID),
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ReleaseCreatorBond {}.data(),
+    }
+}
+
+/// `bettor` is false for the fee recipient claiming a bond nobody bet against
+pub fn claim_bond_share_ix(
+    arena: &Pubkey,
+    claimant: &Pubkey,
+    bond_mint: Option<&Pubkey>,
+    bettor: bool,
+) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ClaimBondShare {
+            arena: *arena,
+            config: config_pda(),
+            participant: bettor.then(|| participant_pda(arena, claimant)),
+            claimant: *claimant,
+            bond_vault: bond_mint.map(|_| bond_vault_pda(arena)),
+            claimant_bond_account: bond_mint.map(|mint| bond_ata(claimant, mint)),
+            token_program: bond_mint.map(|_| token::ID),
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ClaimBondShare {}.data(),
+    }
+}
+
+pub fn dispute_arena_ix(arena: &Pubkey, disputer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::DisputeArena {
+            arena: *arena,
+            disputer: *disputer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::DisputeArena {}.data(),
+    }
+}
+
+pub fn settle_dispute_ix(
+    arena: &Pubkey,
+    admin: &Pubkey,
+    disputer: &Pubkey,
+    creator: &Pubkey,
+    upheld: bool,
+) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SettleDispute {
+            arena: *arena,
+            config: config_pda(),
+            admin: *admin,
+            disputer: *disputer,
+            creator: *creator,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SettleDispute { upheld }.data(),
+    }
+}
+
+pub fn mint_trophy_ix(arena: &Pubkey, user: &Pubkey) -> Instruction {
+    let participant = participant_pda(arena, user);
+    let trophy_mint = trophy_mint_pda(&participant);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::MintTrophy {
+            arena: *arena,
+            config: config_pda(),
+            participant,
+            user_profile: user_profile_pda(user),
+            trophy_mint,
+            trophy_authority: trophy_authority_pda(),
+            user_trophy_account: trophy_ata(user, &trophy_mint),
+            user: *user,
+            token_program: token_2022::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::MintTrophy {}.data(),
+    }
+}
+
+/// Create an arena from `ArenaConfig::new` and return `(creator, arena)`
+pub fn new_arena(svm: &mut Svm) -> (Pubkey, Pubkey) {
+    let config = ArenaConfig::new(svm);
+    new_arena_with(svm, &config)
+}
+
+pub fn new_arena_with(svm: &mut Svm, config: &ArenaConfig) -> (Pubkey, Pubkey) {
+    let creator = wallet(svm);
+    assert_ok(svm.send_instruction(create_arena_ix(&creator, config), &[creator]));
+    (creator, arena_pda(&creator, &config.title))
+}
+
+/// New wallet that joins `arena` on `outcome`
+pub fn join(svm: &mut Svm, arena: &Pubkey, outcome: u8) -> Pubkey {
+    let user = wallet(svm);
+    assert_ok(svm.send_instruction(join_arena_ix(arena, &user, outcome), &[user]));
+    user
+}
+
+// ========== SHARE INSTRUCTIONS ==========
+
+pub fn create_share_tokens_ix(
+    arena: &Pubkey,
+    creator: &Pubkey,
+    outcome_index: u8,
+    initial_price: u64,
+    curve_type: CurveType,
+    curve_param: u64,
+) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::CreateShareTokens {
+            arena: *arena,
+            config: config_pda(),
+            creator: *creator,
+            outcome_share: outcome_share_pda(arena, outcome_index),
+            share_mint: share_mint_pda(arena, outcome_index),
+            price_history: price_history_pda(arena, outcome_index),
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::CreateShareTokens {
+            outcome_index,
+            initial_price,
+            curve_type,
+            curve_param,
+        }
+        .data(),
+    }
+}
+
+pub fn buy_shares_ix(
+    arena: &Pubkey,
+    buyer: &Pubkey,
+    creator: &Pubkey,
+    outcome_index: u8,
+    amount: u64,
+    max_total_cost: Option<u64>,
+    deadline_ts: Option<i64>,
+) -> Instruction {
+    let outcome_share = outcome_share_pda(arena, outcome_index);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::BuyShares {
+            arena: *arena,
+            config: config_pda(),
+            outcome_share,
+            share_mint: share_mint_pda(arena, outcome_index),
+            price_history: price_history_pda(arena, outcome_index),
+            share_balance: share_balance_pda(&outcome_share, buyer),
+            buyer_token_account: share_ata(buyer, arena, outcome_index),
+            buyer: *buyer,
+            user_profile: user_profile_pda(buyer),
+            arena_escrow: *arena,
+            creator: *creator,
+            referrer: None,
+            referral: None,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::BuyShares {
+            _outcome_index: outcome_index,
+            amount,
+            max_total_cost,
+            deadline_ts,
+            proof: vec![],
+        }
+        .data(),
+    }
+}
+
+pub fn sell_shares_ix(
+    arena: &Pubkey,
+    seller: &Pubkey,
+    outcome_index: u8,
+    shares_to_sell: u64,
+    min_total_proceeds: Option<u64>,
+    deadline_ts: Option<i64>,
+) -> Instruction {
+    let outcome_share = outcome_share_pda(arena, outcome_index);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SellShares {
+            arena: *arena,
+            config: config_pda(),
+            outcome_share,
+            share_mint: share_mint_pda(arena, outcome_index),
+            price_history: price_history_pda(arena, outcome_index),
+            share_balance: share_balance_pda(&outcome_share, seller),
+            seller_token_account: share_ata(seller, arena, outcome_index),
+            seller: *seller,
+            user_profile: user_profile_pda(seller),
+            arena_escrow: *arena,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SellShares {
+            _outcome_index: outcome_index,
+            shares_to_sell,
+            min_total_proceeds,
+            deadline_ts,
+        }
+        .data(),
+    }
+}
+
+pub fn redeem_shares_ix(
+    arena: &Pubkey,
+    redeemer: &Pubkey,
+    outcome_index: u8,
+    amount: u64,
+    min_total_proceeds: Option<u64>,
+    deadline_ts: Option<i64>,
+) -> Instruction {
+    let outcome_share = outcome_share_pda(arena, outcome_index);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::RedeemShares {
+            arena: *arena,
+            config: config_pda(),
+            outcome_share,
+            share_mint: share_mint_pda(arena, outcome_index),
+            share_balance: share_balance_pda(&outcome_share, redeemer),
+            redeemer_token_account: share_ata(redeemer, arena, outcome_index),
+            redeemer: *redeemer,
+            user_profile: user_profile_pda(redeemer),
+            arena_escrow: *arena,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::RedeemShares {
+            _outcome_index: outcome_index,
+            amount,
+            min_total_proceeds,
+            deadline_ts,
+        }
+        .data(),
+    }
+}
+
+pub fn mint_complete_sets_ix(
+    arena: &Pubkey,
+    buyer: &Pubkey,
+    creator: &Pubkey,
+    amount: u64,
+    max_cost: u64,
+) -> Instruction {
+    let long_share = outcome_share_pda(arena, Arena::LONG);
+    let short_share = outcome_share_pda(arena, Arena::SHORT);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::MintCompleteSets {
+            arena: *arena,
+            config: config_pda(),
+            long_share,
+            short_share,
+            long_mint: share_mint_pda(arena, Arena::LONG),
+            short_mint: share_mint_pda(arena, Arena::SHORT),
+            long_balance: share_balance_pda(&long_share, buyer),
+            short_balance: share_balance_pda(&short_share, buyer),
+            long_token_account: share_ata(buyer, arena, Arena::LONG),
+            short_token_account: share_ata(buyer, arena, Arena::SHORT),
+            buyer: *buyer,
+            user_profile: user_profile_pda(buyer),
+            creator: *creator,
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::MintCompleteSets { amount, max_cost, deadline_ts: None, proof: vec![] }.data(),
+    }
+}
+
+/// Create an empty share token account for `owner`
+pub fn create_share_ata_ix(payer: &Pubkey, owner: &Pubkey, arena: &Pubkey, outcome_index: u8) -> Instruction {
+    spl_associated_token_account::instruction::create_associated_token_account(
+        payer,
+        owner,
+        &share_mint_pda(arena, outcome_index),
+        &token::ID,
+    )
+}
+
+/// New SPL Token mint for bonds, with `ADMIN` as mint authority
+pub fn bond_mint(svm: &mut Svm) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    let space = spl_token::state::Mint::LEN;
+    let create = anchor_lang::solana_program::system_instruction::create_account(
+        &ADMIN,
+        &mint,
+        svm.rent().minimum_balance(space),
+        space as u64,
+        &token::ID,
+    );
+    let initialize = spl_token::instruction::initialize_mint2(&token::ID, &mint, &ADMIN, None, 9).unwrap();
+    assert_ok(svm.send_transaction(&[create, initialize], &[ADMIN, mint]));
+    mint
+}
+
+/// Create `owner`'s associated token account for `bond_mint` holding `amount`
+pub fn fund_bond_ata(svm: &mut Svm, bond_mint: &Pubkey, owner: &Pubkey, amount: u64) -> Pubkey {
+    let ata = bond_ata(owner, bond_mint);
+    let create = spl_associated_token_account::instruction::create_associated_token_account(
+        &ADMIN,
+        owner,
+        bond_mint,
+        &token::ID,
+    );
+    let mint_to = spl_token::instruction::mint_to(&token::ID, bond_mint, &ata, &ADMIN, &[], amount).unwrap();
+    assert_ok(svm.send_transaction(&[create, mint_to], &[ADMIN]));
+    ata
+}
+
+/// Arena with a linear share market on every outcome; returns `(creator, arena)`
+pub fn new_share_market(svm: &mut Svm) -> (Pubkey, Pubkey) {
+    let config = ArenaConfig::new(svm);
+    new_share_market_with(svm, &config)
+}
+
+pub fn new_share_market_with(svm: &mut Svm, config: &ArenaConfig) -> (Pubkey, Pubkey) {
+    let (creator, arena) = new_arena_with(svm, config);
+    for outcome_index in 0..2 {
+        assert_ok(svm.send_instruction(
+            create_share_tokens_ix(&arena, &creator, outcome_index, BASE_PRICE, CurveType::Linear, SLOPE),
+            &[creator],
+        ));
+    }
+    (creator, arena)
+}
+
+/// New wallet holding `amount` shares of `outcome_index`
+pub fn shareholder(svm: &mut Svm, arena: &Pubkey, outcome_index: u8, amount: u64) -> Pubkey {
+    let holder = wallet(svm);
+    let creator = fetch::<Arena>(svm, arena).creator;
+    assert_ok(svm.send_instruction(
+        buy_shares_ix(arena, &holder, &creator, outcome_index, amount, None, None),
+        &[holder],
+    ));
+    holder
+}
+
+// ========== AMM INSTRUCTIONS ==========
+
+pub fn initialize_pool_ix(
+    arena: &Pubkey,
+    creator: &Pubkey,
+    outcome_index: u8,
+    fee_bps: u16,
+    protocol_fee_bps: u16,
+) -> Instruction {
+    let pool = pool_pda(arena, outcome_index);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::InitializePool {
+            arena: *arena,
+            config: config_pda(),
+            outcome_share: outcome_share_pda(arena, outcome_index),
+            pool,
+            lp_token_mint: lp_mint_pda(&pool),
+            share_mint: share_mint_pda(arena, outcome_index),
+            pool_token_vault: pool_token_vault_pda(&pool),
+            creator: *creator,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::InitializePool {
+            outcome_index,
+            fee_bps,
+            protocol_fee_bps,
+        }
+        .data(),
+    }
+}
+
+pub fn add_liquidity_ix(
+    arena: &Pubkey,
+    provider: &Pubkey,
+    outcome_index: u8,
+    token_amount: u64,
+    sol_amount: u64,
+    min_lp_tokens: u64,
+) -> Instruction {
+    let pool = pool_pda(arena, outcome_index);
+    let lp_token_mint = lp_mint_pda(&pool);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::AddLiquidity {
+            arena: *arena,
+            config: config_pda(),
+            pool,
+            lp_token_mint,
+            pool_token_vault: pool_token_vault_pda(&pool),
+            provider_token_account: share_ata(provider, arena, outcome_index),
+            provider_lp_token_account: get_associated_token_address(provider, &lp_token_mint),
+            liquidity_position: liquidity_position_pda(&pool, provider),
+            provider: *provider,
+            pool_sol_vault: pool_sol_vault_pda(&pool),
+            token_program: token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::AddLiquidity {
+            token_amount,
+            sol_amount,
+            min_lp_tokens,
+        }
+        .data(),
+    }
+}
+
+pub fn remove_liquidity_ix(
+    arena: &Pubkey,
+    provider: &Pubkey,
+    outcome_index: u8,
+    lp_tokens_to_burn: u64,
+    min_token_amount: u64,
+    min_sol_amount: u64,
+) -> Instruction {
+    let pool = pool_pda(arena, outcome_index);
+    let lp_token_mint = lp_mint_pda(&pool);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::RemoveLiquidity {
+            arena: *arena,
+            pool,
+            lp_token_mint,
+            pool_token_vault: pool_token_vault_pda(&pool),
+            provider_token_account: share_ata(provider, arena, outcome_index),
+            provider_lp_token_account: get_associated_token_address(provider, &lp_token_mint),
+            liquidity_position: liquidity_position_pda(&pool, provider),
+            provider: *provider,
+            pool_sol_vault: pool_sol_vault_pda(&pool),
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::RemoveLiquidity {
+            lp_tokens_to_burn,
+            min_token_amount,
+            min_sol_amount,
+        }
+        .data(),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn swap_ix(
+    arena: &Pubkey,
+    user: &Pubkey,
+    creator: &Pubkey,
+    user_token_account: &Pubkey,
+    protocol_fee_recipient: &Pubkey,
+    outcome_index: u8,
+    amount_in: u64,
+    min_amount_out: u64,
+    is_token_to_sol: bool,
+) -> Instruction {
+    let pool = pool_pda(arena, outcome_index);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::Swap {
+            arena: *arena,
+            config: config_pda(),
+            pool,
+            price_history: price_history_pda(arena, outcome_index),
+            pool_token_vault: pool_token_vault_pda(&pool),
+            user_token_account: *user_token_account,
+            user: *user,
+            pool_sol_vault: pool_sol_vault_pda(&pool),
+            protocol_fee_recipient: *protocol_fee_recipient,
+            creator: *creator,
+            referrer: None,
+            referral: None,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::Swap {
+            amount_in,
+            min_amount_out,
+            is_token_to_sol,
+        }
+        .data(),
+    }
+}
+
+// ========== ORDER BOOK INSTRUCTIONS ==========
+
+pub fn initialize_order_book_ix(arena: &Pubkey, creator: &Pubkey, outcome_index: u8) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::InitializeOrderBook {
+            arena: *arena,
+            config: config_pda(),
+            outcome_share: outcome_share_pda(arena, outcome_index),
+            order_book: order_book_pda(arena, outcome_index),
+            creator: *creator,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::InitializeOrderBook { outcome_index }.data(),
+    }
+}
+
+/// Plain limit order parameters
+pub fn limit_order(side: OrderSide, price: u64, size: u64) -> PlaceOrderParams {
+    PlaceOrderParams {
+        order_type: OrderType::Limit,
+        side,
+        price,
+        size,
+        expires_at: 0,
+        stop_price: None,
+        visible_size: None,
+        twap_interval: None,
+    }
+}
+
+pub fn place_limit_order_ix(
+    arena: &Pubkey,
+    owner: &Pubkey,
+    outcome_index: u8,
+    order_id: u64,
+    params: PlaceOrderParams,
+) -> Instruction {
+    let limit_order = limit_order_pda(arena, outcome_index, order_id);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::PlaceLimitOrder {
+            arena: *arena,
+            config: config_pda(),
+            order_book: order_book_pda(arena, outcome_index),
+            limit_order,
+            share_mint: share_mint_pda(arena, outcome_index),
+            owner_token_account: share_ata(owner, arena, outcome_index),
+            order_escrow: order_escrow_pda(&limit_order),
+            owner: *owner,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::PlaceLimitOrder {
+            outcome_index,
+            params,
+        }
+        .data(),
+    }
+}
+
+pub fn cancel_order_ix(
+    arena: &Pubkey,
+    owner: &Pubkey,
+    owner_token_account: &Pubkey,
+    outcome_index: u8,
+    order_id: u64,
+) -> Instruction {
+    let limit_order = limit_order_pda(arena, outcome_index, order_id);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::CancelOrder {
+            arena: *arena,
+            order_book: order_book_pda(arena, outcome_index),
+            limit_order,
+            owner_token_account: *owner_token_account,
+            order_escrow: order_escrow_pda(&limit_order),
+            owner: *owner,
+            token_program: token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::CancelOrder {}.data(),
+    }
+}
+
+/// Orders and participants of a `settle_match` call
+pub struct MatchAccounts {
+    pub outcome_index: u8,
+    pub buy_order_id: u64,
+    pub sell_order_id: u64,
+    pub trade_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub protocol_fee_recipient: Pubkey,
+    pub creator: Pubkey,
+    pub matcher: Pubkey,
+}
+
+pub fn settle_match_ix(
+    arena: &Pubkey,
+    accounts: &MatchAccounts,
+    match_size: u64,
+    match_price: u64,
+) -> Instruction {
+    let outcome_index = accounts.outcome_index;
+    let buy_order = limit_order_pda(arena, outcome_index, accounts.buy_order_id);
+    let sell_order = limit_order_pda(arena, outcome_index, accounts.sell_order_id);
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SettleMatch {
+            arena: *arena,
+            config: config_pda(),
+            order_book: order_book_pda(arena, outcome_index),
+            price_history: price_history_pda(arena, outcome_index),
+            buy_order,
+            sell_order,
+            trade: trade_pda(arena, outcome_index, accounts.trade_id),
+            buy_order_escrow: order_escrow_pda(&buy_order),
+            sell_order_escrow: order_escrow_pda(&sell_order),
+            buyer_token_account: share_ata(&accounts.buyer, arena, outcome_index),
+            seller: accounts.seller,
+            buyer: accounts.buyer,
+            protocol_fee_recipient: accounts.protocol_fee_recipient,
+            creator: accounts.creator,
+            matcher: accounts.matcher,
+            token_program: token::ID,
+            system_program: system_program::ID,
+            rent: sysvar::rent::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SettleMatch {
+            match_size,
+            match_price,
+        }
+        .data(),
+    }
+}
+
+// ========== REFERRALS ==========
+
+/// `ix` (a join, share purchase or swap) made on `referrer`'s referral
+/// Fills the optional referral accounts the builders leave as placeholders.
+pub fn with_referrer(mut ix: Instruction, user: &Pubkey, referrer: &Pubkey) -> Instruction {
+    let slot = ix.accounts.iter().position(|meta| meta.pubkey == betfun::ID).expect("no referral accounts");
+    ix.accounts[slot] = AccountMeta::new_readonly(*referrer, false);
+    ix.accounts[slot + 1] = AccountMeta::new(referral_pda(user), false);
+    ix
+}
+
+pub fn set_referral_share_ix(admin: &Pubkey, share_bps: u16) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SetReferralShare {
+            config: config_pda(),
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SetReferralShare { share_bps }.data(),
+    }
+}
+
+pub fn claim_referral_rewards_ix(user: &Pubkey, referrer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ClaimReferralRewards {
+            referral: referral_pda(user),
+            referrer: *referrer,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ClaimReferralRewards {}.data(),
+    }
+}
+
+// ========== PARLAYS ==========
+
+pub fn fund_parlay_vault_ix(admin: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::FundParlayVault {
+            config: config_pda(),
+            parlay_vault: parlay_vault_pda(),
+            admin: *admin,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::FundParlayVault { amount }.data(),
+    }
+}
+
+pub fn withdraw_parlay_vault_ix(admin: &Pubkey, amount: u64) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::WithdrawParlayVault {
+            config: config_pda(),
+            parlay_vault: parlay_vault_pda(),
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::WithdrawParlayVault { amount }.data(),
+    }
+}
+
+pub fn set_parlay_approval_ix(arena: &Pubkey, admin: &Pubkey, approved: bool) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::SetParlayApproval {
+            arena: *arena,
+            config: config_pda(),
+            admin: *admin,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::SetParlayApproval { approved }.data(),
+    }
+}
+
+/// Parlay picking `(arena, outcome)` legs, the arenas passed as remaining accounts
+pub fn place_parlay_ix(
+    owner: &Pubkey,
+    parlay_id: u64,
+    legs: &[(Pubkey, u8)],
+    stake: u64,
+    min_payout: u64,
+) -> Instruction {
+    let mut accounts = betfun::accounts::PlaceParlay {
+        config: config_pda(),
+        parlay_vault: parlay_vault_pda(),
+        parlay: parlay_pda(owner, parlay_id),
+        owner: *owner,
+        user_profile: user_profile_pda(owner),
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(legs.iter().map(|(arena, _)| AccountMeta::new_readonly(*arena, false)));
+    Instruction {
+        program_id: betfun::ID,
+        accounts,
+        data: betfun::instruction::PlaceParlay {
+            parlay_id,
+            picks: legs.iter().map(|(_, outcome)| *outcome).collect(),
+            stake,
+            min_payout,
+        }
+        .data(),
+    }
+}
+
+pub fn settle_parlay_ix(settler: &Pubkey, owner: &Pubkey, parlay_id: u64, arenas: &[Pubkey]) -> Instruction {
+    let mut accounts = betfun::accounts::SettleParlay {
+        config: config_pda(),
+        parlay_vault: parlay_vault_pda(),
+        parlay: parlay_pda(owner, parlay_id),
+        owner: *owner,
+        user_profile: user_profile_pda(owner),
+        settler: *settler,
+    }
+    .to_account_metas(None);
+    accounts.extend(arenas.iter().map(|arena| AccountMeta::new_readonly(*arena, false)));
+    Instruction {
+        program_id: betfun::ID,
+        accounts,
+        data: betfun::instruction::SettleParlay {}.data(),
+    }
+}
+
+// ========== TOURNAMENTS ==========
+
+pub fn create_tournament_ix(
+    organizer: &Pubkey,
+    name: &str,
+    entrants: &[&str],
+    lock_time: i64,
+) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::CreateTournament {
+            tournament: tournament_pda(organizer, name),
+            config: config_pda(),
+            organizer: *organizer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::CreateTournament {
+            name: name.to_string(),
+            entrants: entrants.iter().map(|entrant| entrant.to_string()).collect(),
+            entry_fee: ENTRY_FEE,
+            match_entry_fee: ENTRY_FEE,
+            lock_time,
+            round_duration: DAY,
+        }
+        .data(),
+    }
+}
+
+pub fn enter_tournament_ix(tournament: &Pubkey, user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::EnterTournament {
+            tournament: *tournament,
+            config: config_pda(),
+            entry: tournament_entry_pda(tournament, user),
+            user: *user,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::EnterTournament {}.data(),
+    }
+}
+
+pub fn advance_tournament_ix(tournament: &Pubkey, payer: &Pubkey, slot: u8) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::AdvanceTournament {
+            tournament: *tournament,
+            arena: tournament_match_pda(tournament, slot),
+            config: config_pda(),
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::AdvanceTournament { slot }.data(),
+    }
+}
+
+pub fn resolve_tournament_match_ix(
+    tournament: &Pubkey,
+    organizer: &Pubkey,
+    slot: u8,
+    winner_outcome: u8,
+) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ResolveTournamentMatch {
+            tournament: *tournament,
+            arena: tournament_match_pda(tournament, slot),
+            organizer: *organizer,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ResolveTournamentMatch { slot, winner_outcome }.data(),
+    }
+}
+
+pub fn cancel_tournament_ix(tournament: &Pubkey, caller: &Pubkey, slot: u8) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::CancelTournament {
+            tournament: *tournament,
+            arena: tournament_match_pda(tournament, slot),
+            caller: *caller,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::CancelTournament { slot }.data(),
+    }
+}
+
+/// Score `user`'s entry, its participant accounts passed as remaining accounts
+pub fn submit_tournament_score_ix(tournament: &Pubkey, user: &Pubkey, picks: &[Pubkey]) -> Instruction {
+    let mut accounts = betfun::accounts::SubmitTournamentScore {
+        tournament: *tournament,
+        entry: tournament_entry_pda(tournament, user),
+        user: *user,
+    }
+    .to_account_metas(None);
+    accounts.extend(picks.iter().map(|pick| AccountMeta::new_readonly(*pick, false)));
+    Instruction {
+        program_id: betfun::ID,
+        accounts,
+        data: betfun::instruction::SubmitTournamentScore {}.data(),
+    }
+}
+
+pub fn claim_tournament_prize_ix(tournament: &Pubkey, user: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::ClaimTournamentPrize {
+            tournament: *tournament,
+            config: config_pda(),
+            entry: tournament_entry_pda(tournament, user),
+            user: *user,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::ClaimTournamentPrize {}.data(),
+    }
+}
+
+// ========== BATCH CLAIMS ==========
+
+/// `claim_many` entry for `user`'s bet in `arena`
+pub fn bet_entry(arena: &Pubkey, user: &Pubkey, creator: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*arena, false),
+        AccountMeta::new(participant_pda(arena, user), false),
+        AccountMeta::new(*creator, false),
+    ]
+}
+
+/// `claim_many` entry for `user`'s shares of `outcome_index`
+pub fn share_entry(arena: &Pubkey, user: &Pubkey, outcome_index: u8) -> Vec<AccountMeta> {
+    let outcome_share = outcome_share_pda(arena, outcome_index);
+    vec![
+        AccountMeta::new(*arena, false),
+        AccountMeta::new(share_balance_pda(&outcome_share, user), false),
+        AccountMeta::new(outcome_share, false),
+        AccountMeta::new(share_mint_pda(arena, outcome_index), false),
+        AccountMeta::new(share_ata(user, arena, outcome_index), false),
+    ]
+}
+
+pub fn claim_many_ix(user: &Pubkey, entries: &[Vec<AccountMeta>]) -> Instruction {
+    let mut accounts = betfun::accounts::ClaimMany {
+        config: config_pda(),
+        user: *user,
+        user_profile: user_profile_pda(user),
+        token_program: token::ID,
+        system_program: system_program::ID,
+    }
+    .to_account_metas(None);
+    accounts.extend(entries.concat());
+    Instruction {
+        program_id: betfun::ID,
+        accounts,
+        data: betfun::instruction::ClaimMany {}.data(),
+    }
+}
+
+// ========== MIGRATION ==========
+
+pub fn migrate_account_ix(account: &Pubkey, payer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: betfun::ID,
+        accounts: betfun::accounts::MigrateAccount {
+            account: *account,
+            payer: *payer,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: betfun::instruction::MigrateAccount {}.data(),
+    }
+}
@@ -93,7 +93,7 @@ describe("create_arena", () => {
       program.programId
     );
 
-    const longTitle = "A".repeat(81); // Max 80 chars
+    const longTitle = "A".repeat(33); // Max 32 bytes
 
     try {
       await program.methods
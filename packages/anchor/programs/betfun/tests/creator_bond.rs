@@ -0,0 +1,468 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{Arena, CurveType, Dispute, GlobalConfig, OutcomeShare, UserProfile};
+use betfun_math::curve::Curve;
+use betfun_testkit::Svm;
+use common::*;
+
+const BOND: u64 = SOL;
+
+fn set_bond(svm: &mut Svm, bond: u64) {
+    assert_ok(svm.send_instruction(set_creator_bond_ix(&ADMIN, bond), &[ADMIN]));
+}
+
+fn rent(svm: &Svm, key: &Pubkey) -> u64 {
+    svm.rent().minimum_balance(svm.account(key).unwrap().data.len())
+}
+
+/// Bonded arena with bets of one entry fee on outcomes 0, 0 and 1
+fn bonded_arena(svm: &mut Svm) -> (Pubkey, Pubkey, [Pubkey; 3]) {
+    set_bond(svm, BOND);
+    let (creator, arena) = new_arena(svm);
+    let users = [0, 0, 1].map(|outcome| join(svm, &arena, outcome));
+    (creator, arena, users)
+}
+
+// ========== CONFIG ==========
+
+#[test]
+fn admin_sets_the_bond_new_arenas_lock() {
+    let mut svm = setup();
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(set_creator_bond_ix(&stranger, BOND), &[stranger]);
+    assert_error(result, BetFunError::Unauthorized);
+    let result = svm.send_instruction(
+        set_creator_bond_ix(&ADMIN, GlobalConfig::MAX_CREATOR_BOND + 1),
+        &[ADMIN],
+    );
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // Arenas created before the bond keep none
+    let (_, unbonded) = new_arena(&mut svm);
+    set_bond(&mut svm, BOND);
+    assert_eq!(fetch::<GlobalConfig>(&svm, &config_pda()).creator_bond, BOND);
+    assert_eq!(fetch::<Arena>(&svm, &unbonded).creator_bond, 0);
+
+    let creator = wallet(&mut svm);
+    let config = ArenaConfig::new(&svm);
+    assert_ok(svm.send_instruction(create_arena_ix(&creator, &config), &[creator]));
+    let arena = arena_pda(&creator, &config.title);
+    let rent = rent(&svm, &arena);
+    assert_eq!(svm.lamports(&arena), rent + BOND);
+    assert_eq!(svm.lamports(&creator), 100 * SOL - rent - BOND);
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, BOND);
+}
+
+// ========== RELEASE ==========
+
+#[test]
+fn first_winning_claim_after_the_dispute_window_returns_the_bond() {
+    let mut svm = setup();
+    let (creator, arena, [first, second, _]) = bonded_arena(&mut svm);
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &first), &[first]);
+    assert_error(result, BetFunError::NotResolved);
+
+    // The resolution can still be disputed, so the bond stays put
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &first), &[first]);
+    assert_error(result, BetFunError::NotResolved);
+
+    svm.advance_clock(Arena::DISPUTE_WINDOW);
+    let state: Arena = fetch(&svm, &arena);
+    let payout = state.calculate_payout(ENTRY_FEE).unwrap();
+
+    let creator_before = svm.lamports(&creator);
+    let first_before = svm.lamports(&first);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &first, &creator), &[first]));
+    assert_eq!(svm.lamports(&first), first_before + payout);
+    assert_eq!(svm.lamports(&creator), creator_before + state.creator_fee_due() + BOND);
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, 0);
+
+    // Later winners are paid as before, and the bond is gone for good
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &second, &creator), &[second]));
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &first), &[first]);
+    assert_error(result, BetFunError::InvalidAmount);
+}
+
+#[test]
+fn anyone_releases_the_bond_when_nobody_claims() {
+    let mut svm = setup();
+    set_bond(&mut svm, BOND);
+    let (creator, empty) = new_arena(&mut svm);
+    let config = ArenaConfig { title: "Second Leg".to_string(), ..ArenaConfig::new(&svm) };
+    let (_, arena) = new_arena_with(&mut svm, &config);
+    join(&mut svm, &arena, 0);
+    let caller = wallet(&mut svm);
+
+    // An arena with no bets can't resolve, so its bond is free once it ends
+    let result = svm.send_instruction(release_creator_bond_ix(&empty, &creator, &caller), &[caller]);
+    assert_error(result, BetFunError::NotResolved);
+    svm.advance_clock(DAY);
+    let before = svm.lamports(&creator);
+    assert_ok(svm.send_instruction(release_creator_bond_ix(&empty, &creator, &caller), &[caller]));
+    assert_eq!(svm.lamports(&creator), before + BOND);
+
+    let arena_creator = fetch::<Arena>(&svm, &arena).creator;
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &arena_creator, &caller), &[caller]);
+    assert_error(result, BetFunError::NotResolved);
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &caller), &[caller]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn outstanding_shares_keep_the_bond_locked() {
+    let mut svm = setup();
+    set_bond(&mut svm, BOND);
+    let (creator, arena) = new_share_market(&mut svm);
+    shareholder(&mut svm, &arena, 0, SHARE);
+    let caller = wallet(&mut svm);
+
+    // Nobody bet, but the share holder still waits on a resolution
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &caller), &[caller]);
+    assert_error(result, BetFunError::NotResolved);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    svm.advance_clock(Arena::DISPUTE_WINDOW);
+    assert_ok(svm.send_instruction(release_creator_bond_ix(&arena, &creator, &caller), &[caller]));
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, 0);
+}
+
+// ========== VOID ==========
+
+#[test]
+fn unresolved_arena_is_voided_and_refunds_the_bond_to_bettors() {
+    let mut svm = setup();
+    let (creator, arena, users) = bonded_arena(&mut svm);
+    let caller = wallet(&mut svm);
+
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]);
+    assert_error(result, BetFunError::ResolutionWindowOpen);
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &users[0], &creator), &[users[0]]);
+    assert_error(result, BetFunError::NotResolved);
+
+    svm.advance_clock(Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+    let state: Arena = fetch(&svm, &arena);
+    assert!(state.voided && !state.resolved);
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]);
+    assert_error(result, BetFunError::ArenaVoided);
+    let result = svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]);
+    assert_error(result, BetFunError::ArenaVoided);
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &caller), &[caller]);
+    assert_error(result, BetFunError::ArenaVoided);
+
+    // Winners and losers alike get their stake back plus a third of the bond
+    let refund = ENTRY_FEE + BOND / 3;
+    let creator_before = svm.lamports(&creator);
+    for user in users {
+        let before = svm.lamports(&user);
+        assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+        assert_eq!(svm.lamports(&user), before + refund);
+        let profile: UserProfile = fetch(&svm, &user_profile_pda(&user));
+        assert_eq!(profile.realized_pnl, (BOND / 3) as i64);
+    }
+    assert_eq!(svm.lamports(&creator), creator_before);
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &users[0], &creator), &[users[0]]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+
+    let rent = rent(&svm, &arena);
+    assert_eq!(svm.lamports(&arena), rent + 3 * ENTRY_FEE + BOND - 3 * refund);
+}
+
+#[test]
+fn arenas_without_bets_are_never_voided() {
+    let mut svm = setup();
+    set_bond(&mut svm, BOND);
+    let (creator, arena) = new_arena(&mut svm);
+    let caller = wallet(&mut svm);
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    let result = svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // A late resolution still counts as a clean one
+    let (creator_two, late) = {
+        let config = ArenaConfig { title: "Late Final".to_string(), ..ArenaConfig::new(&svm) };
+        new_arena_with(&mut svm, &config)
+    };
+    join(&mut svm, &late, 1);
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&late, &creator_two, 1), &[creator_two]));
+    let result = svm.send_instruction(void_arena_ix(&late, &caller), &[caller]);
+    assert_error(result, BetFunError::AlreadyResolved);
+    assert_ok(svm.send_instruction(release_creator_bond_ix(&arena, &creator, &caller), &[caller]));
+}
+
+#[test]
+fn share_holders_split_the_bond_of_an_arena_nobody_bet_in() {
+    let mut svm = setup();
+    set_bond(&mut svm, BOND);
+    let (creator, arena) = new_share_market(&mut svm);
+    let holders = [shareholder(&mut svm, &arena, 0, 3 * SHARE), shareholder(&mut svm, &arena, 1, SHARE)];
+    let caller = wallet(&mut svm);
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+
+    // Each holder gets the curve refund plus their part of the bond
+    let creator_before = svm.lamports(&creator);
+    let positions = [(holders[0], 0, 3 * SHARE, BOND * 3 / 4), (holders[1], 1, SHARE, BOND / 4)];
+    for (holder, outcome_index, amount, bond_share) in positions {
+        let share: OutcomeShare = fetch(&svm, &outcome_share_pda(&arena, outcome_index));
+        let refund = Curve::from(share.curve).sell_proceeds(share.total_supply, amount).unwrap();
+        let before = svm.lamports(&holder);
+        assert_ok(svm.send_instruction(
+            redeem_shares_ix(&arena, &holder, outcome_index, amount, None, None),
+            &[holder],
+        ));
+        assert_eq!(svm.lamports(&holder) - before, refund + bond_share);
+    }
+    assert_eq!(svm.lamports(&creator), creator_before);
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, 0);
+    assert_eq!(svm.lamports(&arena), rent(&svm, &arena));
+}
+
+// ========== TOKEN BONDS ==========
+
+/// Bond creators `BOND` base units of a new mint and create an arena with one;
+/// returns `(bond_mint, creator, arena)`
+fn token_bonded_arena(svm: &mut Svm) -> (Pubkey, Pubkey, Pubkey) {
+    let mint = bond_mint(svm);
+    assert_ok(svm.send_instruction(set_token_bond_ix(&ADMIN, BOND, &mint), &[ADMIN]));
+    let creator = wallet(svm);
+    fund_bond_ata(svm, &mint, &creator, BOND);
+    let config = ArenaConfig { bond_mint: Some(mint), ..ArenaConfig::new(svm) };
+    assert_ok(svm.send_instruction(create_arena_ix(&creator, &config), &[creator]));
+    (mint, creator, arena_pda(&creator, &config.title))
+}
+
+#[test]
+fn token_bond_is_escrowed_until_a_clean_resolution() {
+    let mut svm = setup();
+    let (mint, creator, arena) = token_bonded_arena(&mut svm);
+    assert_eq!(fetch::<GlobalConfig>(&svm, &config_pda()).bond_mint, Some(mint));
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!((state.creator_bond, state.bond_mint), (BOND, Some(mint)));
+    assert_eq!(token_balance(&svm, &bond_vault_pda(&arena)), BOND);
+    assert_eq!(token_balance(&svm, &bond_ata(&creator, &mint)), 0);
+    assert_eq!(svm.lamports(&arena), rent(&svm, &arena));
+
+    // The bond must come in the config's mint
+    let other = wallet(&mut svm);
+    let config = ArenaConfig { title: "Other Final".to_string(), ..ArenaConfig::new(&svm) };
+    let result = svm.send_instruction(create_arena_ix(&other, &config), &[other]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+    let foreign = bond_mint(&mut svm);
+    fund_bond_ata(&mut svm, &foreign, &other, BOND);
+    let config = ArenaConfig { bond_mint: Some(foreign), ..config };
+    let result = svm.send_instruction(create_arena_ix(&other, &config), &[other]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // Winning claims pay lamports only, leaving the bond for `release_creator_bond`
+    let user = join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, BOND);
+
+    let caller = wallet(&mut svm);
+    svm.advance_clock(Arena::DISPUTE_WINDOW);
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &caller), &[caller]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+    assert_ok(svm.send_instruction(release_token_bond_ix(&arena, &creator, &caller, &mint), &[caller]));
+    assert_eq!(token_balance(&svm, &bond_ata(&creator, &mint)), BOND);
+    assert_eq!(token_balance(&svm, &bond_vault_pda(&arena)), 0);
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, 0);
+}
+
+#[test]
+fn voided_arena_slashes_a_token_bond_to_bettors() {
+    let mut svm = setup();
+    let (mint, creator, arena) = token_bonded_arena(&mut svm);
+    let users = [0, 0, 1].map(|outcome| join(&mut svm, &arena, outcome));
+    let accounts = users.map(|user| fund_bond_ata(&mut svm, &mint, &user, 0));
+    let caller = wallet(&mut svm);
+    let result = svm.send_instruction(claim_bond_share_ix(&arena, &users[0], Some(&mint), true), &[users[0]]);
+    assert_error(result, BetFunError::NotResolved);
+
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+    let result = svm.send_instruction(release_token_bond_ix(&arena, &creator, &caller, &mint), &[caller]);
+    assert_error(result, BetFunError::ArenaVoided);
+
+    // Refunds carry the stakes, and each bettor claims a third of the bond in tokens
+    for (user, account) in users.into_iter().zip(accounts) {
+        let before = svm.lamports(&user);
+        assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+        assert_eq!(svm.lamports(&user), before + ENTRY_FEE);
+
+        assert_ok(svm.send_instruction(claim_bond_share_ix(&arena, &user, Some(&mint), true), &[user]));
+        assert_eq!(token_balance(&svm, &account), BOND / 3);
+    }
+    let result = svm.send_instruction(claim_bond_share_ix(&arena, &users[0], Some(&mint), true), &[users[0]]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+    assert_eq!(token_balance(&svm, &bond_vault_pda(&arena)), BOND - 3 * (BOND / 3));
+
+    // Only an arena nobody bet in pays its bond to the fee recipient
+    let recipient = fee_recipient(&mut svm, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+    fund_bond_ata(&mut svm, &mint, &recipient, 0);
+    let result = svm.send_instruction(claim_bond_share_ix(&arena, &recipient, Some(&mint), false), &[recipient]);
+    assert_error(result, BetFunError::NotParticipant);
+}
+
+#[test]
+fn fee_recipient_takes_the_token_bond_of_an_arena_nobody_bet_in() {
+    let mut svm = setup();
+    let (mint, creator, arena) = token_bonded_arena(&mut svm);
+    for outcome_index in 0..2 {
+        assert_ok(svm.send_instruction(
+            create_share_tokens_ix(&arena, &creator, outcome_index, BASE_PRICE, CurveType::Linear, SLOPE),
+            &[creator],
+        ));
+    }
+    shareholder(&mut svm, &arena, 0, SHARE);
+    let caller = wallet(&mut svm);
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+
+    let recipient = fee_recipient(&mut svm, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+    let stranger = wallet(&mut svm);
+    fund_bond_ata(&mut svm, &mint, &stranger, 0);
+    let result = svm.send_instruction(claim_bond_share_ix(&arena, &stranger, Some(&mint), false), &[stranger]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    let account = fund_bond_ata(&mut svm, &mint, &recipient, 0);
+    assert_ok(svm.send_instruction(claim_bond_share_ix(&arena, &recipient, Some(&mint), false), &[recipient]));
+    assert_eq!(token_balance(&svm, &account), BOND);
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, 0);
+    let result = svm.send_instruction(claim_bond_share_ix(&arena, &recipient, Some(&mint), false), &[recipient]);
+    assert_error(result, BetFunError::NotResolved);
+}
+
+// ========== DISPUTES ==========
+
+/// Bonded arena resolved on outcome 0 and disputed by a fresh wallet
+fn disputed_arena(svm: &mut Svm) -> (Pubkey, Pubkey, [Pubkey; 3], Pubkey) {
+    let (creator, arena, users) = bonded_arena(svm);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let disputer = wallet(svm);
+    assert_ok(svm.send_instruction(dispute_arena_ix(&arena, &disputer), &[disputer]));
+    (creator, arena, users, disputer)
+}
+
+#[test]
+fn bonded_resolutions_are_disputable_within_the_window() {
+    let mut svm = setup();
+    let config = ArenaConfig { title: "Unbonded Final".to_string(), ..ArenaConfig::new(&svm) };
+    let (unbonded_creator, unbonded) = new_arena_with(&mut svm, &config);
+    join(&mut svm, &unbonded, 0);
+    let (late_creator, late, _) = bonded_arena(&mut svm);
+    let (creator, arena, [winner, ..]) = bonded_arena(&mut svm);
+    let disputer = wallet(&mut svm);
+    let result = svm.send_instruction(dispute_arena_ix(&arena, &disputer), &[disputer]);
+    assert_error(result, BetFunError::NotResolved);
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&unbonded, &unbonded_creator, 0), &[unbonded_creator]));
+    let result = svm.send_instruction(dispute_arena_ix(&unbonded, &disputer), &[disputer]);
+    assert_error(result, BetFunError::DisputeClosed);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&late, &late_creator, 0), &[late_creator]));
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let result = svm.send_instruction(dispute_arena_ix(&arena, &creator), &[creator]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    // The disputer deposits one entry fee, and the arena holds it on top of the pot
+    let before = svm.lamports(&disputer);
+    let arena_before = svm.lamports(&arena);
+    assert_ok(svm.send_instruction(dispute_arena_ix(&arena, &disputer), &[disputer]));
+    assert_eq!(svm.lamports(&disputer), before - ENTRY_FEE);
+    assert_eq!(svm.lamports(&arena), arena_before + ENTRY_FEE);
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.dispute, Dispute::Open { disputer });
+    assert_eq!(state.unclaimed_pot(), 3 * ENTRY_FEE + BOND + ENTRY_FEE);
+    let result = svm.send_instruction(dispute_arena_ix(&arena, &winner), &[winner]);
+    assert_error(result, BetFunError::DisputeClosed);
+
+    // Winners are still paid, but the bond waits on the ruling
+    svm.advance_clock(Arena::DISPUTE_WINDOW);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &winner, &creator), &[winner]));
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, BOND);
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &winner), &[winner]);
+    assert_error(result, BetFunError::NotResolved);
+
+    // Once the window closes undisputed, the resolution stands
+    let result = svm.send_instruction(dispute_arena_ix(&late, &disputer), &[disputer]);
+    assert_error(result, BetFunError::DisputeClosed);
+}
+
+#[test]
+fn only_the_admin_settles_an_open_dispute() {
+    let mut svm = setup();
+    let (creator, arena, [winner, ..], disputer) = disputed_arena(&mut svm);
+    let result = svm.send_instruction(settle_dispute_ix(&arena, &winner, &disputer, &creator, true), &[winner]);
+    assert_error(result, BetFunError::Unauthorized);
+    let result = svm.send_instruction(settle_dispute_ix(&arena, &ADMIN, &winner, &creator, true), &[ADMIN]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+    let result = svm.send_instruction(settle_dispute_ix(&arena, &ADMIN, &disputer, &disputer, true), &[ADMIN]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    assert_ok(svm.send_instruction(settle_dispute_ix(&arena, &ADMIN, &disputer, &creator, false), &[ADMIN]));
+    let result = svm.send_instruction(settle_dispute_ix(&arena, &ADMIN, &disputer, &creator, true), &[ADMIN]);
+    assert_error(result, BetFunError::NoOpenDispute);
+}
+
+#[test]
+fn upheld_dispute_slashes_the_bond_to_bettors() {
+    let mut svm = setup();
+    let (creator, arena, users, disputer) = disputed_arena(&mut svm);
+    let before = svm.lamports(&disputer);
+    assert_ok(svm.send_instruction(settle_dispute_ix(&arena, &ADMIN, &disputer, &creator, true), &[ADMIN]));
+    assert_eq!(svm.lamports(&disputer), before + ENTRY_FEE);
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!((state.dispute, state.creator_bond), (Dispute::Upheld, BOND));
+
+    // The creator never gets the bond back, however long they wait
+    svm.advance_clock(Arena::DISPUTE_WINDOW);
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &disputer), &[disputer]);
+    assert_error(result, BetFunError::NotResolved);
+    let result = svm.send_instruction(dispute_arena_ix(&arena, &disputer), &[disputer]);
+    assert_error(result, BetFunError::DisputeClosed);
+
+    // Winners keep their payout, and every bettor claims a third of the bond
+    let creator_before = svm.lamports(&creator);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &users[0], &creator), &[users[0]]));
+    assert_eq!(svm.lamports(&creator), creator_before + state.creator_fee_due());
+    for user in users {
+        let before = svm.lamports(&user);
+        assert_ok(svm.send_instruction(claim_bond_share_ix(&arena, &user, None, true), &[user]));
+        assert_eq!(svm.lamports(&user), before + BOND / 3);
+    }
+    let result = svm.send_instruction(claim_bond_share_ix(&arena, &users[2], None, true), &[users[2]]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+
+    // The last winner's claim still finds the pot intact
+    let before = svm.lamports(&users[1]);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &users[1], &creator), &[users[1]]));
+    assert_eq!(svm.lamports(&users[1]), before + state.calculate_payout(ENTRY_FEE).unwrap());
+}
+
+#[test]
+fn rejected_dispute_pays_the_deposit_to_the_creator_and_frees_the_bond() {
+    let mut svm = setup();
+    let (creator, arena, [bettor, ..], disputer) = disputed_arena(&mut svm);
+    let before = svm.lamports(&creator);
+    assert_ok(svm.send_instruction(settle_dispute_ix(&arena, &ADMIN, &disputer, &creator, false), &[ADMIN]));
+    assert_eq!(svm.lamports(&creator), before + ENTRY_FEE);
+    assert_eq!(fetch::<Arena>(&svm, &arena).dispute, Dispute::Rejected);
+
+    // A resolution is disputed once; the bond is free without waiting out the window
+    let result = svm.send_instruction(dispute_arena_ix(&arena, &bettor), &[bettor]);
+    assert_error(result, BetFunError::DisputeClosed);
+    let result = svm.send_instruction(claim_bond_share_ix(&arena, &bettor, None, true), &[bettor]);
+    assert_error(result, BetFunError::NotResolved);
+    assert_ok(svm.send_instruction(release_creator_bond_ix(&arena, &creator, &bettor), &[bettor]));
+    assert_eq!(svm.lamports(&creator), before + ENTRY_FEE + BOND);
+}
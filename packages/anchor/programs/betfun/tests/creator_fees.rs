@@ -0,0 +1,219 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{AMMPool, Arena, GlobalConfig, OrderSide, OutcomeShare, Referral};
+use betfun_testkit::Svm;
+use common::*;
+
+const CREATOR_FEE_BPS: u16 = 250;
+const TRADING_FEE_BPS: u16 = 100;
+
+fn trading_fee(sol_amount: u64) -> u64 {
+    sol_amount * TRADING_FEE_BPS as u64 / 10_000
+}
+
+/// Share market whose creator charges `TRADING_FEE_BPS` on trades
+fn market(svm: &mut Svm) -> (Pubkey, Pubkey) {
+    let mut config = ArenaConfig::new(svm);
+    config.creator_fee_bps = CREATOR_FEE_BPS;
+    config.creator_trading_fee_bps = TRADING_FEE_BPS;
+    new_share_market_with(svm, &config)
+}
+
+// ========== CREATE ARENA ==========
+
+#[test]
+fn creator_sets_both_fees() {
+    let mut svm = setup();
+    let (_, arena) = market(&mut svm);
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.creator_fee_bps, CREATOR_FEE_BPS);
+    assert_eq!(state.creator_trading_fee_bps, TRADING_FEE_BPS);
+}
+
+#[test]
+fn pot_fee_applies_to_winnings() {
+    let mut svm = setup();
+    let (creator, arena) = market(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    join(&mut svm, &arena, 1);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let pot = 2 * ENTRY_FEE;
+    let creator_fee = pot * CREATOR_FEE_BPS as u64 / 10_000;
+    let creator_before = svm.lamports(&creator);
+    let winner_before = svm.lamports(&winner);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &winner, &creator), &[winner]));
+    assert_eq!(svm.lamports(&winner), winner_before + pot - creator_fee);
+    assert_eq!(svm.lamports(&creator), creator_before + creator_fee);
+}
+
+#[test]
+fn pot_fee_is_paid_once_whichever_way_winners_claim() {
+    let mut svm = setup();
+    let (creator, arena) = market(&mut svm);
+    let winners = [join(&mut svm, &arena, 0), join(&mut svm, &arena, 0), join(&mut svm, &arena, 0)];
+    join(&mut svm, &arena, 1);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let pot = 4 * ENTRY_FEE;
+    let creator_fee = pot * CREATOR_FEE_BPS as u64 / 10_000;
+    let payout = (pot - creator_fee) / 3;
+    let creator_before = svm.lamports(&creator);
+    let arena_before = svm.lamports(&arena);
+
+    // The arena records the payment, so later claims never pay the fee again
+    for (index, winner) in winners.iter().enumerate() {
+        let before = svm.lamports(winner);
+        let claim = if index == 1 {
+            claim_many_ix(winner, &[bet_entry(&arena, winner, &creator)])
+        } else {
+            claim_winnings_ix(&arena, winner, &creator)
+        };
+        assert_ok(svm.send_instruction(claim, &[*winner]));
+        assert_eq!(svm.lamports(winner), before + payout);
+        assert_eq!(svm.lamports(&creator), creator_before + creator_fee);
+    }
+    assert!(fetch::<Arena>(&svm, &arena).creator_fee_paid);
+    assert_eq!(svm.lamports(&arena), arena_before - creator_fee - 3 * payout);
+}
+
+// ========== SHARES ==========
+
+#[test]
+fn buy_shares_pays_trading_fee_on_top() {
+    let mut svm = setup();
+    let (creator, arena) = market(&mut svm);
+    let buyer = wallet(&mut svm);
+    let cost = fetch::<OutcomeShare>(&svm, &outcome_share_pda(&arena, 0)).curve.buy_cost(0, 2 * SHARE).unwrap();
+    let fee = trading_fee(cost);
+    assert!(fee > 0);
+
+    // The slippage bound covers the fee as well as the curve cost
+    let result = svm.send_instruction(
+        buy_shares_ix(&arena, &buyer, &creator, 0, 2 * SHARE, Some(cost), None),
+        &[buyer],
+    );
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(buy_shares_ix(&arena, &buyer, &stranger, 0, 2 * SHARE, None, None), &[buyer]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let arena_before = svm.lamports(&arena);
+    let creator_before = svm.lamports(&creator);
+    assert_ok(svm.send_instruction(
+        buy_shares_ix(&arena, &buyer, &creator, 0, 2 * SHARE, Some(cost + fee), None),
+        &[buyer],
+    ));
+    assert_eq!(svm.lamports(&arena), arena_before + cost);
+    assert_eq!(svm.lamports(&creator), creator_before + fee);
+}
+
+#[test]
+fn referrer_shares_the_trading_fee() {
+    let mut svm = setup();
+    let (creator, arena) = market(&mut svm);
+    let referrer = wallet(&mut svm);
+    let buyer = wallet(&mut svm);
+    let cost = fetch::<OutcomeShare>(&svm, &outcome_share_pda(&arena, 0)).curve.buy_cost(0, SHARE).unwrap();
+    let fee = trading_fee(cost);
+    let share_bps = fetch::<GlobalConfig>(&svm, &config_pda()).referral_share_bps;
+    let reward = fee * share_bps as u64 / 10_000;
+
+    let creator_before = svm.lamports(&creator);
+    let ix = with_referrer(buy_shares_ix(&arena, &buyer, &creator, 0, SHARE, None, None), &buyer, &referrer);
+    assert_ok(svm.send_instruction(ix, &[buyer]));
+
+    assert_eq!(svm.lamports(&creator), creator_before + fee - reward);
+    assert_eq!(fetch::<Referral>(&svm, &referral_pda(&buyer)).pending_rewards, reward);
+}
+
+// ========== AMM ==========
+
+#[test]
+fn swaps_pay_trading_fee_from_sol_side() {
+    let mut svm = setup();
+    let (creator, arena) = market(&mut svm);
+    assert_ok(svm.send_instruction(initialize_pool_ix(&arena, &creator, 0, 30, 10), &[creator]));
+    let provider = shareholder(&mut svm, &arena, 0, 10 * SHARE);
+    assert_ok(svm.send_instruction(add_liquidity_ix(&arena, &provider, 0, 4 * SHARE, SOL, 0), &[provider]));
+    let pool_key = pool_pda(&arena, 0);
+    let fee_recipient = fee_recipient(&mut svm, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+    let trader = wallet(&mut svm);
+    assert_ok(svm.send_instruction(create_share_ata_ix(&trader, &trader, &arena, 0), &[trader]));
+    let trader_tokens = share_ata(&trader, &arena, 0);
+
+    // Buys pay the fee out of the SOL sent, before it reaches the pool
+    let amount_in = SOL / 10;
+    let creator_fee = trading_fee(amount_in);
+    let creator_before = svm.lamports(&creator);
+    let swap = swap_ix(&arena, &trader, &creator, &trader_tokens, &fee_recipient, 0, amount_in, 0, false);
+    assert_ok(svm.send_instruction(swap, &[trader]));
+
+    let pool: AMMPool = fetch(&svm, &pool_key);
+    let protocol_fee = (amount_in - creator_fee) * 10 / 10_000;
+    assert_eq!(svm.lamports(&creator), creator_before + creator_fee);
+    assert_eq!(pool.sol_reserve, SOL + amount_in - creator_fee - protocol_fee);
+    assert_eq!(svm.lamports(&pool_sol_vault_pda(&pool_key)), pool.sol_reserve);
+
+    // Sells pay it out of the SOL paid out, like the protocol fee
+    let shares = token_balance(&svm, &trader_tokens);
+    let creator_before = svm.lamports(&creator);
+    let trader_before = svm.lamports(&trader);
+    let swap = swap_ix(&arena, &trader, &creator, &trader_tokens, &fee_recipient, 0, shares, 0, true);
+    assert_ok(svm.send_instruction(swap, &[trader]));
+
+    let sol_reserve = pool.sol_reserve;
+    let pool: AMMPool = fetch(&svm, &pool_key);
+    let gross_out = sol_reserve - pool.sol_reserve;
+    let creator_fee = trading_fee(gross_out);
+    let protocol_fee = gross_out * 10 / 10_000;
+    assert_eq!(svm.lamports(&creator), creator_before + creator_fee);
+    assert_eq!(svm.lamports(&trader), trader_before + gross_out - protocol_fee - creator_fee);
+    assert_eq!(svm.lamports(&pool_sol_vault_pda(&pool_key)), pool.sol_reserve);
+}
+
+// ========== ORDER BOOK ==========
+
+#[test]
+fn settled_trades_pay_trading_fee_from_proceeds() {
+    const BID: u64 = 110_000;
+    const PRICE: u64 = 100_000;
+    const SIZE: u64 = 100;
+    const FEE_BPS: u16 = 30;
+
+    let mut svm = setup();
+    let (creator, arena) = market(&mut svm);
+    assert_ok(svm.send_instruction(initialize_order_book_ix(&arena, &creator, 0), &[creator]));
+    let seller = shareholder(&mut svm, &arena, 0, SHARE);
+    let buyer = wallet(&mut svm);
+    assert_ok(svm.send_instruction(create_share_ata_ix(&buyer, &buyer, &arena, 0), &[buyer]));
+    for (order_id, owner, side, price) in [(0, buyer, OrderSide::Buy, BID), (1, seller, OrderSide::Sell, PRICE)] {
+        let order = limit_order(side, price, SIZE);
+        assert_ok(svm.send_instruction(place_limit_order_ix(&arena, &owner, 0, order_id, order), &[owner]));
+    }
+
+    let accounts = MatchAccounts {
+        outcome_index: 0,
+        buy_order_id: 0,
+        sell_order_id: 1,
+        trade_id: 0,
+        buyer,
+        seller,
+        protocol_fee_recipient: fee_recipient(&mut svm, FEE_BPS),
+        creator,
+        matcher: wallet(&mut svm),
+    };
+    let value = PRICE * SIZE;
+    let fee = value * FEE_BPS as u64 / 10_000;
+    let creator_before = svm.lamports(&creator);
+    let seller_before = svm.lamports(&seller);
+    assert_ok(svm.send_instruction(settle_match_ix(&arena, &accounts, SIZE, PRICE), &[accounts.matcher]));
+
+    assert_eq!(svm.lamports(&creator), creator_before + trading_fee(value));
+    assert_eq!(svm.lamports(&seller), seller_before + value - fee - trading_fee(value));
+}
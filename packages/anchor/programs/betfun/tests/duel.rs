@@ -0,0 +1,148 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{Arena, Duel, Participant, ScalarRange, UserProfile};
+use betfun_testkit::Svm;
+use common::*;
+
+/// Duel created by a fresh challenger backing `side`; returns `(challenger, arena)`
+fn duel(svm: &mut Svm, opponent: Option<Pubkey>, side: u8) -> (Pubkey, Pubkey) {
+    let config = ArenaConfig { duel: Some(Duel { opponent, side }), ..ArenaConfig::new(svm) };
+    new_arena_with(svm, &config)
+}
+
+fn try_join(svm: &mut Svm, arena: &Pubkey, user: &Pubkey, outcome: u8) -> TxResult {
+    svm.send_instruction(join_arena_ix(arena, user, outcome), &[*user])
+}
+
+#[test]
+fn named_opponent_accepts_and_the_duel_resolves() {
+    let mut svm = setup();
+    let opponent = wallet(&mut svm);
+    let stranger = wallet(&mut svm);
+    let (challenger, arena) = duel(&mut svm, Some(opponent), 0);
+
+    // The challenger's stake is taken with the duel, and only the named opponent can match it
+    let state: Arena = fetch(&svm, &arena);
+    assert!(state.is_unaccepted_duel());
+    assert_eq!((state.pot, state.outcome_counts.clone()), (ENTRY_FEE, vec![1, 0]));
+    let stake: Participant = fetch(&svm, &participant_pda(&arena, &challenger));
+    assert_eq!((stake.outcome_chosen, stake.amount), (0, ENTRY_FEE));
+    assert_eq!(fetch::<UserProfile>(&svm, &user_profile_pda(&challenger)).total_staked, ENTRY_FEE);
+
+    assert_error(try_join(&mut svm, &arena, &stranger, 1), BetFunError::NotDuelOpponent);
+    assert_error(try_join(&mut svm, &arena, &opponent, 0), BetFunError::DuelSideTaken);
+    assert_ok(try_join(&mut svm, &arena, &opponent, 1));
+
+    let state: Arena = fetch(&svm, &arena);
+    assert!(!state.is_unaccepted_duel());
+    assert_eq!((state.pot, state.outcome_counts.clone()), (2 * ENTRY_FEE, vec![1, 1]));
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &challenger, 1), &[challenger]));
+    let state: Arena = fetch(&svm, &arena);
+    let payout = state.calculate_payout(ENTRY_FEE).unwrap();
+    let before = svm.lamports(&opponent);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &opponent, &challenger), &[opponent]));
+    assert_eq!(svm.lamports(&opponent), before + payout);
+    assert_eq!(payout, 2 * ENTRY_FEE - state.calculate_creator_fee());
+}
+
+#[test]
+fn open_challenge_goes_to_the_first_match() {
+    let mut svm = setup();
+    let (challenger, arena) = duel(&mut svm, None, 1);
+    assert_error(try_join(&mut svm, &arena, &challenger, 0), BetFunError::AlreadyJoined);
+    join(&mut svm, &arena, 0);
+
+    // Both sides are taken
+    let late = wallet(&mut svm);
+    assert_error(try_join(&mut svm, &arena, &late, 0), BetFunError::DuelSideTaken);
+    assert_error(try_join(&mut svm, &arena, &late, 1), BetFunError::DuelSideTaken);
+    assert_eq!(fetch::<Arena>(&svm, &arena).participants_count, 2);
+}
+
+#[test]
+fn unaccepted_challenge_is_refunded_once_it_locks() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(set_creator_bond_ix(&ADMIN, SOL), &[ADMIN]));
+    let (challenger, arena) = duel(&mut svm, None, 0);
+
+    let caller = wallet(&mut svm);
+    let result = svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]);
+    assert_error(result, BetFunError::ResolutionWindowOpen);
+
+    // Past the lock the challenge can't be resolved, only voided
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &challenger, 0), &[challenger]);
+    assert_error(result, BetFunError::DuelNotAccepted);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+
+    let before = svm.lamports(&challenger);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &challenger, &challenger), &[challenger]));
+    assert_eq!(svm.lamports(&challenger), before + ENTRY_FEE + SOL);
+}
+
+#[test]
+fn create_arena_rejects_bad_duels() {
+    let mut svm = setup();
+    let creator = wallet(&mut svm);
+    let base = ArenaConfig { duel: Some(Duel { opponent: None, side: 0 }), ..ArenaConfig::new(&svm) };
+    let outcomes = vec!["Red".to_string(), "Blue".to_string(), "Green".to_string()];
+    let cases = [
+        ArenaConfig { outcomes, ..base.clone() },
+        ArenaConfig { outcomes: vec![], scalar: Some(ScalarRange { lower: 0, upper: 10 }), ..base.clone() },
+        ArenaConfig { duel: Some(Duel { opponent: Some(creator), side: 0 }), ..base.clone() },
+    ];
+    for config in cases {
+        let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+        assert_error(result, BetFunError::InvalidConfiguration);
+    }
+
+    let config = ArenaConfig { duel: Some(Duel { opponent: None, side: 2 }), ..base.clone() };
+    let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+    assert_error(result, BetFunError::InvalidOutcome);
+
+    // A duel can't be created without the challenger's stake
+    let mut ix = create_arena_ix(&creator, &base);
+    ix.accounts[3].pubkey = betfun::ID;
+    ix.accounts[4].pubkey = betfun::ID;
+    assert_error(svm.send_instruction(ix, &[creator]), BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn challenger_cancels_an_unaccepted_duel() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(set_creator_bond_ix(&ADMIN, SOL), &[ADMIN]));
+    let (challenger, arena) = duel(&mut svm, None, 0);
+
+    // Only the challenger can withdraw their stake
+    let stranger = wallet(&mut svm);
+    let mut ix = cancel_duel_ix(&arena, &challenger);
+    ix.accounts[2].pubkey = stranger;
+    assert_error(svm.send_instruction(ix, &[stranger]), BetFunError::Unauthorized);
+
+    // Cancelling refunds the stake and bond at once, well before the lock
+    let before = svm.lamports(&challenger);
+    assert_ok(svm.send_instruction(cancel_duel_ix(&arena, &challenger), &[challenger]));
+    assert_eq!(svm.lamports(&challenger), before + ENTRY_FEE + SOL);
+    let state: Arena = fetch(&svm, &arena);
+    assert!(state.voided);
+    assert!(fetch::<Participant>(&svm, &participant_pda(&arena, &challenger)).claimed);
+
+    let opponent = wallet(&mut svm);
+    assert_error(try_join(&mut svm, &arena, &opponent, 1), BetFunError::ArenaEnded);
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &challenger, &challenger), &[challenger]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+}
+
+#[test]
+fn accepted_duel_cannot_be_cancelled() {
+    let mut svm = setup();
+    let (challenger, arena) = duel(&mut svm, None, 0);
+    join(&mut svm, &arena, 1);
+
+    let result = svm.send_instruction(cancel_duel_ix(&arena, &challenger), &[challenger]);
+    assert_error(result, BetFunError::DuelAlreadyAccepted);
+}
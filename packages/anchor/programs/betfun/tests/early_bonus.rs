@@ -0,0 +1,78 @@
+mod common;
+
+use betfun::error::BetFunError;
+use betfun::state::{Arena, ScalarRange, StakeWeighting};
+use betfun_math::payout;
+use common::*;
+
+const FULL_BONUS: StakeWeighting = StakeWeighting::LinearDecay { bonus_bps: 10_000 };
+
+#[test]
+fn early_bets_take_a_bigger_share_of_the_pot() {
+    let mut svm = setup();
+    let config = ArenaConfig { stake_weighting: FULL_BONUS, ..ArenaConfig::new(&svm) };
+    let (creator, arena) = new_arena_with(&mut svm, &config);
+    assert_eq!(fetch::<Arena>(&svm, &arena).outcome_weights, vec![0, 0]);
+
+    // Opening bets weigh double, halfway to the lock one and a half
+    let early = join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY / 2);
+    let late = join(&mut svm, &arena, 0);
+    join(&mut svm, &arena, 1);
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.outcome_weights, vec![2 * ENTRY_FEE + 3 * ENTRY_FEE / 2, 3 * ENTRY_FEE / 2]);
+    assert_eq!(state.outcome_pots, vec![2 * ENTRY_FEE, ENTRY_FEE]);
+
+    svm.advance_clock(DAY / 2);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let distributable = 3 * ENTRY_FEE - payout::creator_fee(3 * ENTRY_FEE, config.creator_fee_bps);
+
+    let mut paid = 0;
+    for (user, weight) in [(early, 2 * ENTRY_FEE), (late, 3 * ENTRY_FEE / 2)] {
+        let before = svm.lamports(&user);
+        assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+        let received = svm.lamports(&user) - before;
+        assert_eq!(received, distributable * weight / (7 * ENTRY_FEE / 2));
+        paid += received;
+    }
+    assert!(distributable - paid < 2);
+}
+
+#[test]
+fn flat_arenas_split_by_stake() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let early = join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY / 2);
+    let late = join(&mut svm, &arena, 0);
+    join(&mut svm, &arena, 1);
+    assert!(fetch::<Arena>(&svm, &arena).outcome_weights.is_empty());
+
+    svm.advance_clock(DAY / 2);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let state: Arena = fetch(&svm, &arena);
+    let payout = state.calculate_payout(ENTRY_FEE).unwrap();
+    for user in [early, late] {
+        let before = svm.lamports(&user);
+        assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+        assert_eq!(svm.lamports(&user), before + payout);
+    }
+}
+
+#[test]
+fn create_arena_rejects_bad_bonuses() {
+    let mut svm = setup();
+    let scalar = ScalarRange { lower: 0, upper: 100 };
+    let cases = [
+        (StakeWeighting::LinearDecay { bonus_bps: 0 }, None),
+        (StakeWeighting::LinearDecay { bonus_bps: Arena::MAX_EARLY_BONUS_BPS + 1 }, None),
+        (FULL_BONUS, Some(scalar)),
+    ];
+    for (stake_weighting, scalar) in cases {
+        let outcomes = if scalar.is_some() { vec![] } else { ArenaConfig::new(&svm).outcomes };
+        let config = ArenaConfig { stake_weighting, scalar, outcomes, ..ArenaConfig::new(&svm) };
+        let creator = wallet(&mut svm);
+        let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+        assert_error(result, BetFunError::InvalidConfiguration);
+    }
+}
@@ -0,0 +1,536 @@
+//! Property tests for the market math and random instruction sequences
+//!
+//! The sequence tests drive joins, share trades and AMM operations in random
+//! order and check after every step that the escrows stay solvent and that
+//! swaps never shrink the constant product. After resolution, every winning
+//! share must still redeem and every winning bet still claim.
+
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::associated_token::get_associated_token_address;
+use betfun::state::{
+    AMMPool, Arena, BondingCurve, CurveType, Dispute, GlobalConfig, LiquidityPosition, OutcomeShare, Participant,
+    ShareBalance, StakeWeighting,
+};
+use betfun_testkit::Svm;
+use common::*;
+use proptest::prelude::*;
+
+const FEE_BPS: u16 = 30;
+const PROTOCOL_FEE_BPS: u16 = 10;
+const TRADERS: usize = 3;
+
+fn pool_with(token_reserve: u64, sol_reserve: u64, total_lp_tokens: u64, fee_bps: u16) -> AMMPool {
+    AMMPool {
+        arena: Pubkey::default(),
+        outcome_index: 0,
+        share_mint: Pubkey::default(),
+        token_reserve,
+        sol_reserve,
+        k: token_reserve as u128 * sol_reserve as u128,
+        lp_token_mint: Pubkey::default(),
+        total_lp_tokens,
+        fee_bps,
+        protocol_fee_bps: 0,
+        fees_collected: 0,
+        volume_24h: 0,
+        swap_count: 0,
+        last_swap_at: 0,
+        last_price: 0,
+        price_24h_ago: 0,
+        bump: 0,
+        version: 1,
+    }
+}
+
+fn resolved_arena(outcome_pots: Vec<u64>, winner_outcome: u8, creator_fee_bps: u16) -> Arena {
+    Arena {
+        creator: Pubkey::default(),
+        title: String::new(),
+        description: String::new(),
+        question: String::new(),
+        outcomes: vec![String::new(); outcome_pots.len()],
+        tags: vec![],
+        entry_fee: 0,
+        pot: outcome_pots.iter().sum(),
+        participants_count: 0,
+        outcome_counts: vec![0; outcome_pots.len()],
+        outcome_pots,
+        resolved: true,
+        winner_outcome: Some(winner_outcome),
+        lock_time: 0,
+        manual_resolve: false,
+        oracle: None,
+        token_mint: None,
+        creator_fee_bps,
+        created_at: 0,
+        bump: 0,
+        creator_fee_paid: false,
+        total_paid_out: 0,
+        version: 1,
+        paused: 0,
+        referral_fees: 0,
+        creator_trading_fee_bps: 0,
+        scalar: None,
+        resolved_value: None,
+        allowlist_root: None,
+        creator_bond: 0,
+        voided: false,
+        resolve_after: 0,
+        stake_weighting: StakeWeighting::Flat,
+        outcome_weights: vec![],
+        duel: None,
+        shares_outstanding: 0,
+        parlay_approved: false,
+        bond_mint: None,
+        resolved_at: 0,
+        dispute: Dispute::Undisputed,
+    }
+}
+
+fn isqrt(value: u128) -> u128 {
+    let mut root = (value as f64).sqrt() as u128;
+    while root * root > value {
+        root -= 1;
+    }
+    while (root + 1) * (root + 1) <= value {
+        root += 1;
+    }
+    root
+}
+
+// ========== MARKET MATH ==========
+
+proptest! {
+    #[test]
+    fn amount_out_never_shrinks_k(
+        token_reserve in 1..u64::MAX / 2,
+        sol_reserve in 1..u64::MAX / 2,
+        amount_in in 1..u64::MAX / 2,
+        fee_bps in 0u16..=1_000,
+    ) {
+        let pool = pool_with(token_reserve, sol_reserve, 1, fee_bps);
+        if let Ok(amount_out) = pool.get_amount_out(amount_in, token_reserve, sol_reserve) {
+            prop_assert!(amount_out < sol_reserve);
+            let k_after = (token_reserve as u128 + amount_in as u128) * (sol_reserve - amount_out) as u128;
+            prop_assert!(k_after >= pool.k);
+        }
+    }
+
+    #[test]
+    fn first_deposit_mints_at_most_geometric_mean(
+        token_amount in 1..u64::MAX,
+        sol_amount in 1..u64::MAX,
+    ) {
+        let pool = pool_with(0, 0, 0, FEE_BPS);
+        let lp_tokens = pool.calculate_lp_tokens(token_amount, sol_amount).unwrap();
+        prop_assert_eq!(lp_tokens as u128, isqrt(token_amount as u128 * sol_amount as u128));
+    }
+
+    #[test]
+    fn deposits_mint_at_most_their_share(
+        token_reserve in 1..u64::MAX,
+        sol_reserve in 1..u64::MAX,
+        total_lp_tokens in 1..u64::MAX,
+        token_amount in 1..u64::MAX,
+        sol_amount in 1..u64::MAX,
+    ) {
+        let pool = pool_with(token_reserve, sol_reserve, total_lp_tokens, FEE_BPS);
+        if let Ok(lp_tokens) = pool.calculate_lp_tokens(token_amount, sol_amount) {
+            let lp = lp_tokens as u128;
+            let total = total_lp_tokens as u128;
+            prop_assert!(lp * token_reserve as u128 <= total * token_amount as u128);
+            prop_assert!(lp * sol_reserve as u128 <= total * sol_amount as u128);
+        }
+    }
+
+    #[test]
+    fn withdrawals_stay_within_reserves(
+        token_reserve in any::<u64>(),
+        sol_reserve in any::<u64>(),
+        total_lp_tokens in 1..u64::MAX,
+        burn_fraction in 0.0f64..=1.0,
+    ) {
+        let pool = pool_with(token_reserve, sol_reserve, total_lp_tokens, FEE_BPS);
+        let lp_tokens = ((total_lp_tokens as f64 * burn_fraction) as u64).clamp(1, total_lp_tokens);
+        let (token_amount, sol_amount) = pool.calculate_withdraw_amounts(lp_tokens).unwrap();
+        prop_assert!(token_amount <= token_reserve);
+        prop_assert!(sol_amount <= sol_reserve);
+        prop_assert!(token_amount as u128 * total_lp_tokens as u128 <= token_reserve as u128 * lp_tokens as u128);
+        prop_assert!(sol_amount as u128 * total_lp_tokens as u128 <= sol_reserve as u128 * lp_tokens as u128);
+    }
+
+    #[test]
+    fn payouts_never_exceed_pot(
+        bets in prop::collection::vec((0u8..4, 1..1_000 * SOL), 1..40),
+        winner in 0u8..4,
+        creator_fee_bps in 0u16..=1_000,
+    ) {
+        let mut outcome_pots = vec![0u64; 4];
+        for (outcome, amount) in &bets {
+            outcome_pots[*outcome as usize] += amount;
+        }
+        let arena = resolved_arena(outcome_pots, winner, creator_fee_bps);
+
+        let mut paid = arena.calculate_creator_fee() as u128;
+        for (_, amount) in bets.iter().filter(|(outcome, _)| *outcome == winner) {
+            paid += arena.calculate_payout(*amount).unwrap() as u128;
+        }
+        prop_assert!(paid <= arena.pot as u128);
+    }
+
+    #[test]
+    fn share_balance_tracks_cost_basis(
+        trades in prop::collection::vec((1..1_000 * SHARE, 1..100 * SOL), 1..20),
+    ) {
+        let mut balance = ShareBalance {
+            owner: Pubkey::default(),
+            outcome_share: Pubkey::default(),
+            balance: 0,
+            avg_cost_basis: 0,
+            total_invested: 0,
+            realized_pnl: 0,
+            bump: 0,
+            version: 1,
+        };
+        let mut bought = 0u64;
+        for (amount, price) in &trades {
+            balance.buy_shares(*amount, *price);
+            bought += amount;
+        }
+        prop_assert_eq!(balance.balance, bought);
+
+        let min_price = trades.iter().map(|(_, price)| *price).min().unwrap();
+        let max_price = trades.iter().map(|(_, price)| *price).max().unwrap();
+        prop_assert!(balance.avg_cost_basis >= min_price);
+        prop_assert!(balance.avg_cost_basis <= max_price);
+
+        balance.sell_shares(bought, balance.avg_cost_basis).unwrap();
+        prop_assert_eq!(balance.balance, 0);
+        prop_assert!(balance.realized_pnl.abs() <= trades.len() as i64);
+    }
+
+    #[test]
+    fn curve_trades_telescope(
+        linear in any::<bool>(),
+        base_price in 1_000_000..10 * SOL,
+        param in 1..100 * SOL,
+        steps in prop::collection::vec(1..50 * SHARE, 1..10),
+    ) {
+        let curve = BondingCurve {
+            curve_type: if linear { CurveType::Linear } else { CurveType::Exponential },
+            base_price,
+            param: if linear { param } else { param.max(SHARE) },
+        };
+        let mut supply = 0u64;
+        let mut paid = 0u64;
+        for amount in steps {
+            let Some(cost) = curve.buy_cost(supply, amount) else { break };
+            supply += amount;
+            paid += cost;
+        }
+        prop_assert_eq!(curve.reserve_at(supply), Some(paid));
+        prop_assert_eq!(curve.sell_proceeds(supply, supply), Some(paid));
+    }
+}
+
+// ========== INSTRUCTION SEQUENCES ==========
+
+#[derive(Clone, Debug)]
+enum Op {
+    Join { outcome: u8 },
+    Buy { trader: usize, outcome: u8, amount: u64 },
+    Sell { trader: usize, outcome: u8, fraction: f64 },
+    Swap { trader: usize, fraction: f64, is_token_to_sol: bool },
+    AddLiquidity { trader: usize, fraction: f64 },
+    RemoveLiquidity { trader: usize, fraction: f64 },
+    Warp { seconds: i64 },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let trader = 0..TRADERS;
+    prop_oneof![
+        (0u8..2).prop_map(|outcome| Op::Join { outcome }),
+        (trader.clone(), 0u8..2, SHARE / 100..20 * SHARE)
+            .prop_map(|(trader, outcome, amount)| Op::Buy { trader, outcome, amount }),
+        (trader.clone(), 0u8..2, 0.0f64..=1.0)
+            .prop_map(|(trader, outcome, fraction)| Op::Sell { trader, outcome, fraction }),
+        (trader.clone(), 0.0f64..=1.0, any::<bool>())
+            .prop_map(|(trader, fraction, is_token_to_sol)| Op::Swap { trader, fraction, is_token_to_sol }),
+        (trader.clone(), 0.0f64..=1.0).prop_map(|(trader, fraction)| Op::AddLiquidity { trader, fraction }),
+        (trader, 0.0f64..=1.0).prop_map(|(trader, fraction)| Op::RemoveLiquidity { trader, fraction }),
+        (0..DAY / 4).prop_map(|seconds| Op::Warp { seconds }),
+    ]
+}
+
+fn scale(amount: u64, fraction: f64) -> u64 {
+    (amount as f64 * fraction) as u64
+}
+
+/// Share market with a seeded AMM pool on outcome 0 and a few funded traders
+struct World {
+    svm: Svm,
+    creator: Pubkey,
+    arena: Pubkey,
+    pool: Pubkey,
+    fee_recipient: Pubkey,
+    traders: Vec<Pubkey>,
+    participants: Vec<(Pubkey, u8)>,
+    /// Winner, share reserve and winning supply when the arena resolved
+    resolution: Option<(u8, u64, u64)>,
+}
+
+impl World {
+    fn new() -> Self {
+        let mut svm = setup();
+        let (creator, arena) = new_share_market(&mut svm);
+        assert_ok(svm.send_instruction(
+            initialize_pool_ix(&arena, &creator, 0, FEE_BPS, PROTOCOL_FEE_BPS),
+            &[creator],
+        ));
+        let provider = shareholder(&mut svm, &arena, 0, 10 * SHARE);
+        assert_ok(svm.send_instruction(add_liquidity_ix(&arena, &provider, 0, 4 * SHARE, SOL, 0), &[provider]));
+
+        let traders: Vec<Pubkey> = (0..TRADERS).map(|_| wallet(&mut svm)).collect();
+        for trader in &traders {
+            for outcome_index in 0..2 {
+                assert_ok(svm.send_instruction(
+                    create_share_ata_ix(trader, trader, &arena, outcome_index),
+                    &[*trader],
+                ));
+            }
+        }
+
+        // Fee recipients must already be rent exempt to accept small transfers
+        let fee_recipient = fee_recipient(&mut svm, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+        Self {
+            svm,
+            creator,
+            arena,
+            pool: pool_pda(&arena, 0),
+            fee_recipient,
+            traders,
+            participants: vec![],
+            resolution: None,
+        }
+    }
+
+    fn pool(&self) -> AMMPool {
+        fetch(&self.svm, &self.pool)
+    }
+
+    fn shares(&self, trader: &Pubkey, outcome_index: u8) -> u64 {
+        token_balance(&self.svm, &share_ata(trader, &self.arena, outcome_index))
+    }
+
+    /// Shares `trader` can redeem: held in their account and on their balance
+    fn redeemable(&self, trader: &Pubkey, outcome_index: u8) -> u64 {
+        let balance = share_balance_pda(&outcome_share_pda(&self.arena, outcome_index), trader);
+        match self.svm.account(&balance) {
+            Some(_) => fetch::<ShareBalance>(&self.svm, &balance).balance.min(self.shares(trader, outcome_index)),
+            None => 0,
+        }
+    }
+
+    fn resolve(&mut self, winner: u8) {
+        let creator = self.creator;
+        assert_ok(self.svm.send_instruction(resolve_arena_ix(&self.arena, &creator, winner), &[creator]));
+        let supply = fetch::<OutcomeShare>(&self.svm, &outcome_share_pda(&self.arena, winner)).total_supply;
+        self.resolution = Some((winner, share_reserve(&self.svm, &self.arena), supply));
+    }
+
+    fn apply(&mut self, op: &Op) {
+        match *op {
+            Op::Join { outcome } => {
+                let user = wallet(&mut self.svm);
+                if self.svm.send_instruction(join_arena_ix(&self.arena, &user, outcome), &[user]).is_ok() {
+                    self.participants.push((user, outcome));
+                }
+            }
+            Op::Buy { trader, outcome, amount } => {
+                let trader = self.traders[trader];
+                let _ = self.svm.send_instruction(
+                    buy_shares_ix(&self.arena, &trader, &self.creator, outcome, amount, None, None),
+                    &[trader],
+                );
+            }
+            Op::Sell { trader, outcome, fraction } => {
+                let trader = self.traders[trader];
+                let amount = scale(self.shares(&trader, outcome), fraction);
+                let _ = self.svm.send_instruction(
+                    sell_shares_ix(&self.arena, &trader, outcome, amount, None, None),
+                    &[trader],
+                );
+            }
+            Op::Swap { trader, fraction, is_token_to_sol } => {
+                let trader = self.traders[trader];
+                let amount_in = if is_token_to_sol {
+                    scale(self.shares(&trader, 0), fraction)
+                } else {
+                    scale(10 * SOL, fraction)
+                };
+                let k_before = self.pool().k;
+                let result = self.svm.send_instruction(
+                    swap_ix(
+                        &self.arena,
+                        &trader,
+                        &self.creator,
+                        &share_ata(&trader, &self.arena, 0),
+                        &self.fee_recipient,
+                        0,
+                        amount_in,
+                        0,
+                        is_token_to_sol,
+                    ),
+                    &[trader],
+                );
+                if result.is_ok() {
+                    assert!(self.pool().k >= k_before, "swap shrank k: {op:?}");
+                }
+            }
+            Op::AddLiquidity { trader, fraction } => {
+                let trader = self.traders[trader];
+                let pool = self.pool();
+                let token_amount = scale(self.shares(&trader, 0), fraction);
+                let sol_amount = if pool.token_reserve == 0 {
+                    0
+                } else {
+                    (token_amount as u128 * pool.sol_reserve as u128 / pool.token_reserve as u128) as u64
+                };
+                let _ = self.svm.send_instruction(
+                    add_liquidity_ix(&self.arena, &trader, 0, token_amount, sol_amount, 0),
+                    &[trader],
+                );
+            }
+            Op::RemoveLiquidity { trader, fraction } => {
+                let trader = self.traders[trader];
+                let position = liquidity_position_pda(&self.pool, &trader);
+                if self.svm.account(&position).is_none() {
+                    return;
+                }
+                let position: LiquidityPosition = fetch(&self.svm, &position);
+                let _ = self.svm.send_instruction(
+                    remove_liquidity_ix(&self.arena, &trader, 0, scale(position.lp_tokens, fraction), 0, 0),
+                    &[trader],
+                );
+            }
+            Op::Warp { seconds } => self.svm.advance_clock(seconds),
+        }
+    }
+
+    /// The arena escrow covers the unclaimed pot plus the curve reserves until
+    /// it resolves, then every unclaimed winning bet plus the outstanding
+    /// winning shares at their value when it resolved; the pool vaults match
+    /// their reserves
+    fn check_solvency(&self) {
+        let arena: Arena = fetch(&self.svm, &self.arena);
+        let mut owed = self.svm.rent().minimum_balance(self.svm.account(&self.arena).unwrap().data.len()) as u128;
+        match self.resolution {
+            None => {
+                owed += arena.unclaimed_pot() as u128;
+                for outcome_index in 0..2 {
+                    let share: OutcomeShare = fetch(&self.svm, &outcome_share_pda(&self.arena, outcome_index));
+                    owed += share.curve.reserve_at(share.total_supply).unwrap() as u128;
+                }
+            }
+            Some((winner, reserve, supply)) => {
+                for (user, _) in self.participants.iter().filter(|(_, outcome)| *outcome == winner) {
+                    let participant: Participant = fetch(&self.svm, &participant_pda(&self.arena, user));
+                    if !participant.claimed {
+                        let weight = arena.participant_weight(&participant).unwrap();
+                        owed += arena.calculate_payout(weight).unwrap() as u128;
+                    }
+                }
+                if !arena.creator_fee_paid {
+                    owed += arena.creator_fee_due() as u128;
+                }
+                owed += arena.creator_bond as u128;
+                let share: OutcomeShare = fetch(&self.svm, &outcome_share_pda(&self.arena, winner));
+                if supply > 0 {
+                    owed += share.total_supply as u128 * reserve as u128 / supply as u128;
+                }
+            }
+        }
+        assert!(self.svm.lamports(&self.arena) as u128 >= owed, "arena escrow is short");
+
+        let pool = self.pool();
+        assert_eq!(pool.k, pool.token_reserve as u128 * pool.sol_reserve as u128);
+        assert_eq!(self.svm.lamports(&pool_sol_vault_pda(&self.pool)), pool.sol_reserve);
+        assert_eq!(token_balance(&self.svm, &pool_token_vault_pda(&self.pool)), pool.token_reserve);
+        let lp_supply: u64 = self
+            .traders
+            .iter()
+            .filter_map(|trader| {
+                let lp_account = get_associated_token_address(trader, &lp_mint_pda(&self.pool));
+                self.svm.account(&lp_account).map(|_| token_balance(&self.svm, &lp_account))
+            })
+            .sum();
+        assert!(lp_supply <= pool.total_lp_tokens);
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(48))]
+
+    #[test]
+    fn random_sequences_keep_escrows_solvent(ops in prop::collection::vec(op(), 1..40)) {
+        let mut world = World::new();
+        for op in &ops {
+            world.apply(op);
+            world.check_solvency();
+        }
+    }
+
+    #[test]
+    fn redemptions_never_starve_claims(
+        ops in prop::collection::vec(op(), 1..40),
+        winner in 0u8..2,
+    ) {
+        let mut world = World::new();
+        for op in &ops {
+            world.apply(op);
+            world.check_solvency();
+        }
+        if !world.participants.iter().any(|(_, outcome)| *outcome == winner) {
+            world.apply(&Op::Join { outcome: winner });
+        }
+        if world.participants.iter().all(|(_, outcome)| *outcome == winner) {
+            world.apply(&Op::Join { outcome: 1 - winner });
+        }
+        prop_assume!(world.participants.iter().any(|(_, outcome)| *outcome == winner));
+
+        world.svm.advance_clock(DAY);
+        world.resolve(winner);
+        world.check_solvency();
+
+        // Shareholders race the bettors to the escrow, and every redemption is paid
+        for trader in world.traders.clone() {
+            let amount = world.redeemable(&trader, winner);
+            if amount > 0 {
+                assert_ok(world.svm.send_instruction(
+                    redeem_shares_ix(&world.arena, &trader, winner, amount, None, None),
+                    &[trader],
+                ));
+            }
+            world.check_solvency();
+        }
+
+        let arena: Arena = fetch(&world.svm, &world.arena);
+        let creator = world.creator;
+        let creator_before = world.svm.lamports(&creator);
+        let mut paid = 0u64;
+        for (user, outcome) in world.participants.clone() {
+            if outcome != winner {
+                continue;
+            }
+            let before = world.svm.lamports(&user);
+            assert_ok(world.svm.send_instruction(claim_winnings_ix(&world.arena, &user, &creator), &[user]));
+            paid += world.svm.lamports(&user) - before;
+            world.check_solvency();
+        }
+        paid += world.svm.lamports(&creator) - creator_before;
+        prop_assert!(paid <= arena.pot);
+        world.check_solvency();
+    }
+}
@@ -0,0 +1,247 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::{borsh, Pubkey};
+use anchor_lang::{AccountSerialize, AnchorSerialize, Discriminator};
+use betfun::error::BetFunError;
+use betfun::state::{Arena, CurveType, GlobalConfig, OrderBook, OrderSide, OutcomeShare, Participant, Versioned};
+use betfun_testkit::Svm;
+use common::*;
+
+/// Overwrite an account's state in place, keeping its size
+fn overwrite<T: AccountSerialize>(svm: &mut Svm, key: &Pubkey, state: &T) {
+    let mut account = svm.account(key).cloned().unwrap();
+    state.try_serialize(&mut account.data.as_mut_slice()).unwrap();
+    svm.set_account(*key, account);
+}
+
+/// Rewrite an arena as if it were written at `version`
+fn set_arena_version(svm: &mut Svm, key: &Pubkey, version: u8) {
+    let mut state: Arena = fetch(svm, key);
+    state.version = version;
+    overwrite(svm, key, &state);
+}
+
+/// Rewrite an account as it was before `version` existed: one byte shorter,
+/// holding only the rent that size needs
+fn strip_version(svm: &mut Svm, key: &Pubkey) {
+    let mut account = svm.account(key).cloned().unwrap();
+    account.data.pop();
+    account.lamports = svm.rent().minimum_balance(account.data.len());
+    svm.set_account(*key, account);
+}
+
+// ========== PADDED LAYOUTS ==========
+
+#[test]
+fn handlers_reject_legacy_arena_until_migrated() {
+    let mut svm = setup();
+    for version in 0..Arena::VERSION {
+        let (_, arena) = new_arena(&mut svm);
+        set_arena_version(&mut svm, &arena, version);
+        let user = wallet(&mut svm);
+
+        let result = svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]);
+        assert_error(result, BetFunError::UnsupportedAccountVersion);
+
+        let payer = wallet(&mut svm);
+        let size = svm.account(&arena).unwrap().data.len();
+        assert_ok(svm.send_instruction(migrate_account_ix(&arena, &payer), &[payer]));
+
+        let state: Arena = fetch(&svm, &arena);
+        assert_eq!(state.version, Arena::VERSION);
+        assert_eq!(svm.account(&arena).unwrap().data.len(), size);
+        assert_ok(svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]));
+    }
+}
+
+#[test]
+fn handlers_reject_legacy_participant_until_migrated() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let user = join(&mut svm, &arena, 0);
+    let participant = participant_pda(&arena, &user);
+    let mut state: Participant = fetch(&svm, &participant);
+    state.version = 1;
+    overwrite(&mut svm, &participant, &state);
+
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]);
+    assert_error(result, BetFunError::UnsupportedAccountVersion);
+
+    let payer = wallet(&mut svm);
+    assert_ok(svm.send_instruction(migrate_account_ix(&participant, &payer), &[payer]));
+    let state: Participant = fetch(&svm, &participant);
+    assert_eq!(state.version, Participant::VERSION);
+    assert!(!state.bond_claimed);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+}
+
+#[test]
+fn migrate_account_sets_config_defaults_from_version_1() {
+    let mut svm = setup();
+    let config = config_pda();
+    let mut state: GlobalConfig = fetch(&svm, &config);
+    state.version = 1;
+    state.referral_share_bps = 0;
+    state.creator_bond = 0;
+    state.fee_recipient = Pubkey::default();
+    state.order_book_fee_bps = 0;
+    overwrite(&mut svm, &config, &state);
+
+    let creator = wallet(&mut svm);
+    let arena_config = ArenaConfig::new(&svm);
+    let result = svm.send_instruction(create_arena_ix(&creator, &arena_config), &[creator]);
+    assert_error(result, BetFunError::UnsupportedAccountVersion);
+
+    let payer = wallet(&mut svm);
+    assert_ok(svm.send_instruction(migrate_account_ix(&config, &payer), &[payer]));
+
+    let state: GlobalConfig = fetch(&svm, &config);
+    assert_eq!(state.version, GlobalConfig::VERSION);
+    assert_eq!(state.admin, ADMIN);
+    assert_eq!(state.referral_share_bps, GlobalConfig::DEFAULT_REFERRAL_SHARE_BPS);
+    assert_eq!(state.fee_recipient, ADMIN);
+    assert_eq!(state.order_book_fee_bps, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+    assert_eq!(state.bond_mint, None);
+    assert_ok(svm.send_instruction(create_arena_ix(&creator, &arena_config), &[creator]));
+}
+
+#[test]
+fn migrate_account_leaves_current_accounts_untouched() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let before = svm.account(&arena).cloned().unwrap();
+
+    let payer = wallet(&mut svm);
+    assert_ok(svm.send_instruction(migrate_account_ix(&arena, &payer), &[payer]));
+
+    assert_eq!(svm.account(&arena).cloned().unwrap(), before);
+}
+
+#[test]
+fn unknown_versions_are_rejected() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    set_arena_version(&mut svm, &arena, Arena::VERSION + 1);
+    let user = wallet(&mut svm);
+
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]);
+    assert_error(result, BetFunError::UnsupportedAccountVersion);
+
+    let result = svm.send_instruction(migrate_account_ix(&arena, &user), &[user]);
+    assert_error(result, BetFunError::UnsupportedAccountVersion);
+}
+
+// ========== UNPADDED LAYOUTS ==========
+
+/// `OutcomeShare` as the program wrote it before versioning and bonding curves
+#[derive(AnchorSerialize)]
+struct BaselineOutcomeShare {
+    arena: Pubkey,
+    outcome_index: u8,
+    token_mint: Pubkey,
+    total_supply: u64,
+    current_price: u64,
+    volume_24h: u64,
+    trade_count: u64,
+    last_trade_at: i64,
+    high_24h: u64,
+    low_24h: u64,
+    price_24h_ago: u64,
+    bump: u8,
+}
+
+#[test]
+fn migrate_account_prices_baseline_outcome_shares_on_a_flat_curve() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let outcome_share = outcome_share_pda(&arena, 0);
+    let current: OutcomeShare = fetch(&svm, &outcome_share);
+    const PRICE: u64 = 250_000_000;
+
+    let baseline = BaselineOutcomeShare {
+        arena,
+        outcome_index: 0,
+        token_mint: current.token_mint,
+        total_supply: 0,
+        current_price: PRICE,
+        volume_24h: 7 * SOL,
+        trade_count: 3,
+        last_trade_at: now(&svm) - DAY,
+        high_24h: PRICE + 1,
+        low_24h: PRICE - 1,
+        price_24h_ago: PRICE,
+        bump: current.bump,
+    };
+    let mut data = OutcomeShare::DISCRIMINATOR.to_vec();
+    baseline.serialize(&mut data).unwrap();
+    let mut account = svm.account(&outcome_share).cloned().unwrap();
+    account.lamports = svm.rent().minimum_balance(data.len());
+    account.data = data;
+    svm.set_account(outcome_share, account);
+
+    let buyer = wallet(&mut svm);
+    let buy = buy_shares_ix(&arena, &buyer, &creator, 0, SHARE, None, None);
+    let failure = svm.send_instruction(buy.clone(), &[buyer]).unwrap_err();
+    assert_eq!(failure.custom_code(), Some(u32::from(ErrorCode::AccountDidNotDeserialize)), "{failure}");
+
+    let payer = wallet(&mut svm);
+    assert_ok(svm.send_instruction(migrate_account_ix(&outcome_share, &payer), &[payer]));
+
+    let state: OutcomeShare = fetch(&svm, &outcome_share);
+    assert_eq!(svm.account(&outcome_share).unwrap().data.len(), OutcomeShare::SIZE);
+    assert_eq!(state.version, OutcomeShare::VERSION);
+    assert_eq!(state.token_mint, current.token_mint);
+    assert_eq!(state.current_price, PRICE);
+    assert_eq!(state.curve.curve_type, CurveType::Linear);
+    assert_eq!((state.curve.base_price, state.curve.param), (PRICE, 0));
+    assert_eq!((state.volume_24h, state.trade_count), (7 * SOL, 3));
+    assert_eq!((state.high_24h, state.low_24h, state.price_24h_ago), (PRICE + 1, PRICE - 1, PRICE));
+    assert_eq!(state.bump, current.bump);
+
+    // Shares keep trading at the price they were issued at
+    assert_ok(svm.send_instruction(buy, &[buyer]));
+    let state: OutcomeShare = fetch(&svm, &outcome_share);
+    assert_eq!((state.total_supply, state.current_price), (SHARE, PRICE));
+}
+
+#[test]
+fn migrate_account_grows_legacy_order_book() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let order_book = order_book_pda(&arena, 0);
+    assert_ok(svm.send_instruction(initialize_order_book_ix(&arena, &creator, 0), &[creator]));
+    strip_version(&mut svm, &order_book);
+    let seller = shareholder(&mut svm, &arena, 0, SHARE);
+
+    let place = place_limit_order_ix(&arena, &seller, 0, 0, limit_order(OrderSide::Sell, 100_000, 100));
+    let failure = svm.send_instruction(place.clone(), &[seller]).unwrap_err();
+    assert_eq!(failure.custom_code(), Some(u32::from(ErrorCode::AccountDidNotDeserialize)), "{failure}");
+
+    let payer = wallet(&mut svm);
+    let payer_before = svm.lamports(&payer);
+    let book_before = svm.lamports(&order_book);
+    assert_ok(svm.send_instruction(migrate_account_ix(&order_book, &payer), &[payer]));
+
+    let rent_due = svm.rent().minimum_balance(OrderBook::SIZE) - book_before;
+    assert_eq!(svm.account(&order_book).unwrap().data.len(), OrderBook::SIZE);
+    assert_eq!(svm.lamports(&order_book), book_before + rent_due);
+    assert_eq!(svm.lamports(&payer), payer_before - rent_due);
+
+    let state: OrderBook = fetch(&svm, &order_book);
+    assert_eq!(state.version, OrderBook::VERSION);
+    assert_eq!(state.arena, arena);
+    assert_ok(svm.send_instruction(place, &[seller]));
+}
+
+#[test]
+fn migrate_account_rejects_foreign_accounts() {
+    let mut svm = setup();
+    let payer = wallet(&mut svm);
+    let other = wallet(&mut svm);
+
+    let failure = svm.send_instruction(migrate_account_ix(&other, &payer), &[payer]).unwrap_err();
+    assert_eq!(failure.custom_code(), Some(u32::from(ErrorCode::ConstraintOwner)), "{failure}");
+}
@@ -0,0 +1,416 @@
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program;
+use anchor_spl::token::spl_token;
+use betfun::error::BetFunError;
+use betfun::state::{CurveType, GlobalConfig, LimitOrder, OrderBook, OrderSide, OrderStatus, Trade};
+use betfun_testkit::Svm;
+use common::*;
+
+const BID: u64 = 110_000;
+const ASK: u64 = 100_000;
+const SIZE: u64 = 100;
+const FEE_BPS: u16 = 30;
+
+/// Order book on outcome 0 with a seller holding shares and a buyer with an
+/// empty share account
+struct Book {
+    creator: Pubkey,
+    arena: Pubkey,
+    buyer: Pubkey,
+    seller: Pubkey,
+    matcher: Pubkey,
+    fee_recipient: Pubkey,
+}
+
+fn book(svm: &mut Svm) -> Book {
+    let (creator, arena) = new_share_market(svm);
+    assert_ok(svm.send_instruction(initialize_order_book_ix(&arena, &creator, 0), &[creator]));
+
+    let seller = shareholder(svm, &arena, 0, SHARE);
+    let buyer = wallet(svm);
+    assert_ok(svm.send_instruction(create_share_ata_ix(&buyer, &buyer, &arena, 0), &[buyer]));
+
+    Book {
+        creator,
+        arena,
+        buyer,
+        seller,
+        matcher: wallet(svm),
+        fee_recipient: fee_recipient(svm, FEE_BPS),
+    }
+}
+
+fn place(svm: &mut Svm, arena: &Pubkey, owner: &Pubkey, order_id: u64, side: OrderSide, price: u64) {
+    assert_ok(svm.send_instruction(
+        place_limit_order_ix(arena, owner, 0, order_id, limit_order(side, price, SIZE)),
+        &[*owner],
+    ));
+}
+
+fn order(svm: &Svm, arena: &Pubkey, order_id: u64) -> LimitOrder {
+    fetch(svm, &limit_order_pda(arena, 0, order_id))
+}
+
+/// Lamports a bid for `size` at `price` holds: cost, buyer fee and rent
+fn bid_reserve(svm: &Svm, price: u64, size: u64) -> u64 {
+    LimitOrder::buy_reserve(price, size, FEE_BPS).unwrap() + svm.rent().minimum_balance(0)
+}
+
+// ========== ORDER BOOK ==========
+
+#[test]
+fn initialize_order_book_starts_empty() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+
+    let order_book: OrderBook = fetch(&svm, &order_book_pda(&b.arena, 0));
+    assert_eq!(order_book.arena, b.arena);
+    assert_eq!(order_book.outcome_index, 0);
+    assert_eq!(order_book.next_order_id, 0);
+    assert_eq!(order_book.active_orders, 0);
+    assert_eq!(order_book.last_trade_at, now(&svm));
+}
+
+#[test]
+fn any_wallet_opens_each_order_book_once() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let opener = wallet(&mut svm);
+
+    // A book needs the outcome's share token, and orders need a book
+    let result = svm.send_instruction(initialize_order_book_ix(&arena, &opener, 0), &[opener]);
+    assert_eq!(result.unwrap_err().custom_code(), Some(u32::from(ErrorCode::AccountNotInitialized)));
+    assert_ok(svm.send_instruction(
+        create_share_tokens_ix(&arena, &creator, 0, BASE_PRICE, CurveType::Linear, SLOPE),
+        &[creator],
+    ));
+    let seller = shareholder(&mut svm, &arena, 0, SHARE);
+    let sell = place_limit_order_ix(&arena, &seller, 0, 0, limit_order(OrderSide::Sell, ASK, SIZE));
+    let result = svm.send_instruction(sell.clone(), &[seller]);
+    assert_eq!(result.unwrap_err().custom_code(), Some(u32::from(ErrorCode::AccountNotInitialized)));
+
+    let before = svm.lamports(&opener);
+    assert_ok(svm.send_instruction(initialize_order_book_ix(&arena, &opener, 0), &[opener]));
+    assert_eq!(svm.lamports(&opener), before - svm.lamports(&order_book_pda(&arena, 0)));
+    assert_ok(svm.send_instruction(sell, &[seller]));
+
+    // The book already exists, so a second one cannot be opened over it
+    let again = wallet(&mut svm);
+    assert!(svm.send_instruction(initialize_order_book_ix(&arena, &again, 0), &[again]).is_err());
+}
+
+#[test]
+fn initialize_order_book_rejects_resolved_arena() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let result = svm.send_instruction(initialize_order_book_ix(&arena, &creator, 0), &[creator]);
+    assert_error(result, BetFunError::AlreadyResolved);
+}
+
+// ========== PLACE ORDERS ==========
+
+#[test]
+fn place_orders_escrow_funds() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+    let bid_escrow = order_escrow_pda(&limit_order_pda(&b.arena, 0, 0));
+    assert_eq!(svm.lamports(&bid_escrow), bid_reserve(&svm, BID, SIZE));
+
+    let bid = order(&svm, &b.arena, 0);
+    assert_eq!(bid.owner, b.buyer);
+    assert_eq!(bid.side, OrderSide::Buy);
+    assert_eq!(bid.remaining_size, SIZE);
+    assert!(bid.status == OrderStatus::Open);
+
+    let seller_tokens = token_balance(&svm, &share_ata(&b.seller, &b.arena, 0));
+    place(&mut svm, &b.arena, &b.seller, 1, OrderSide::Sell, ASK);
+    let ask_escrow = order_escrow_pda(&limit_order_pda(&b.arena, 0, 1));
+    assert_eq!(token_balance(&svm, &ask_escrow), SIZE);
+    assert_eq!(token_balance(&svm, &share_ata(&b.seller, &b.arena, 0)), seller_tokens - SIZE);
+
+    let order_book: OrderBook = fetch(&svm, &order_book_pda(&b.arena, 0));
+    assert_eq!(order_book.next_order_id, 2);
+    assert_eq!(order_book.active_orders, 2);
+    assert_eq!(order_book.best_bid, BID);
+    assert_eq!(order_book.best_ask, ASK);
+    assert_eq!(order_book.mid_price, (BID + ASK) / 2);
+}
+
+#[test]
+fn sell_orders_escrow_shares_in_a_token_account_of_the_order() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    let seller_before = svm.lamports(&b.seller);
+    place(&mut svm, &b.arena, &b.seller, 0, OrderSide::Sell, ASK);
+
+    // The order creates its escrow token account, paid for by the seller
+    let ask = limit_order_pda(&b.arena, 0, 0);
+    let escrow = svm.account(&order_escrow_pda(&ask)).unwrap().clone();
+    assert_eq!(escrow.owner, spl_token::ID);
+    let escrow_state = spl_token::state::Account::unpack(&escrow.data).unwrap();
+    assert_eq!(escrow_state.mint, share_mint_pda(&b.arena, 0));
+    assert_eq!(escrow_state.owner, ask);
+    assert!(svm.lamports(&b.seller) <= seller_before - escrow.lamports);
+
+    // Shares of another outcome cannot fund the order
+    assert_ok(svm.send_instruction(create_share_ata_ix(&b.seller, &b.seller, &b.arena, 1), &[b.seller]));
+    let mut ix = place_limit_order_ix(&b.arena, &b.seller, 0, 1, limit_order(OrderSide::Sell, ASK, SIZE));
+    let own_tokens = share_ata(&b.seller, &b.arena, 0);
+    let meta = ix.accounts.iter_mut().find(|meta| meta.pubkey == own_tokens).unwrap();
+    meta.pubkey = share_ata(&b.seller, &b.arena, 1);
+    let result = svm.send_instruction(ix, &[b.seller]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn place_order_rejects_invalid_params() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    let ix = |owner: &Pubkey, params| place_limit_order_ix(&b.arena, owner, 0, 0, params);
+
+    let result = svm.send_instruction(ix(&b.buyer, limit_order(OrderSide::Buy, 0, SIZE)), &[b.buyer]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    let result = svm.send_instruction(ix(&b.buyer, limit_order(OrderSide::Buy, BID, 0)), &[b.buyer]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    let mut params = limit_order(OrderSide::Buy, BID, SIZE);
+    params.expires_at = now(&svm);
+    let result = svm.send_instruction(ix(&b.buyer, params), &[b.buyer]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let mut params = limit_order(OrderSide::Buy, BID, SIZE);
+    params.visible_size = Some(SIZE + 1);
+    let result = svm.send_instruction(ix(&b.buyer, params), &[b.buyer]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let result = svm.send_instruction(ix(&b.seller, limit_order(OrderSide::Sell, ASK, 2 * SHARE)), &[b.seller]);
+    assert_error(result, BetFunError::InsufficientFunds);
+
+    let result = svm.send_instruction(ix(&b.buyer, limit_order(OrderSide::Buy, u64::MAX, SIZE)), &[b.buyer]);
+    assert_error(result, BetFunError::ArithmeticOverflow);
+}
+
+// ========== CANCEL ORDERS ==========
+
+#[test]
+fn cancel_orders_refund_escrow() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+    place(&mut svm, &b.arena, &b.seller, 1, OrderSide::Sell, ASK);
+
+    let buyer_tokens = share_ata(&b.buyer, &b.arena, 0);
+    let buyer_before = svm.lamports(&b.buyer);
+    assert_ok(svm.send_instruction(cancel_order_ix(&b.arena, &b.buyer, &buyer_tokens, 0, 0), &[b.buyer]));
+    assert_eq!(svm.lamports(&b.buyer), buyer_before + bid_reserve(&svm, BID, SIZE));
+    assert_eq!(svm.lamports(&order_escrow_pda(&limit_order_pda(&b.arena, 0, 0))), 0);
+    assert!(order(&svm, &b.arena, 0).status == OrderStatus::Cancelled);
+
+    let seller_tokens = share_ata(&b.seller, &b.arena, 0);
+    let tokens_before = token_balance(&svm, &seller_tokens);
+    assert_ok(svm.send_instruction(cancel_order_ix(&b.arena, &b.seller, &seller_tokens, 0, 1), &[b.seller]));
+    assert_eq!(token_balance(&svm, &seller_tokens), tokens_before + SIZE);
+
+    let order_book: OrderBook = fetch(&svm, &order_book_pda(&b.arena, 0));
+    assert_eq!(order_book.active_orders, 0);
+
+    // Cancelled orders are no longer active
+    let result = svm.send_instruction(cancel_order_ix(&b.arena, &b.buyer, &buyer_tokens, 0, 0), &[b.buyer]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn cancel_order_requires_owner() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+
+    let seller_tokens = share_ata(&b.seller, &b.arena, 0);
+    let result = svm.send_instruction(cancel_order_ix(&b.arena, &b.seller, &seller_tokens, 0, 0), &[b.seller]);
+    assert_error(result, BetFunError::Unauthorized);
+}
+
+// ========== SETTLE MATCHES ==========
+
+fn match_accounts(b: &Book) -> MatchAccounts {
+    MatchAccounts {
+        outcome_index: 0,
+        buy_order_id: 0,
+        sell_order_id: 1,
+        trade_id: 0,
+        buyer: b.buyer,
+        seller: b.seller,
+        protocol_fee_recipient: b.fee_recipient,
+        creator: b.creator,
+        matcher: b.matcher,
+    }
+}
+
+#[test]
+fn settle_match_moves_shares_and_sol() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+    place(&mut svm, &b.arena, &b.seller, 1, OrderSide::Sell, ASK);
+
+    let value = ASK * SIZE;
+    let fee = value * FEE_BPS as u64 / 10_000;
+    let seller_before = svm.lamports(&b.seller);
+    let recipient_before = svm.lamports(&b.fee_recipient);
+    let buyer_before = svm.lamports(&b.buyer);
+    let escrow = order_escrow_pda(&limit_order_pda(&b.arena, 0, 0));
+
+    assert_ok(svm.send_instruction(
+        settle_match_ix(&b.arena, &match_accounts(&b), SIZE, ASK),
+        &[b.matcher],
+    ));
+
+    assert_eq!(token_balance(&svm, &share_ata(&b.buyer, &b.arena, 0)), SIZE);
+    assert_eq!(svm.lamports(&b.seller), seller_before + value - fee);
+    assert_eq!(svm.lamports(&b.fee_recipient), recipient_before + 2 * fee);
+    // The filled bid's escrow returns the price improvement, the unused fee
+    // reserve and its rent to the buyer
+    assert_eq!(svm.lamports(&escrow), 0);
+    assert_eq!(svm.lamports(&b.buyer), buyer_before + bid_reserve(&svm, BID, SIZE) - value - fee);
+
+    assert!(order(&svm, &b.arena, 0).status == OrderStatus::Filled);
+    assert!(order(&svm, &b.arena, 1).status == OrderStatus::Filled);
+
+    let trade: Trade = fetch(&svm, &trade_pda(&b.arena, 0, 0));
+    assert_eq!(trade.buyer, b.buyer);
+    assert_eq!(trade.seller, b.seller);
+    assert_eq!(trade.price, ASK);
+    assert_eq!(trade.size, SIZE);
+    assert_eq!(trade.buyer_fee, fee);
+
+    let order_book: OrderBook = fetch(&svm, &order_book_pda(&b.arena, 0));
+    assert_eq!(order_book.trade_count, 1);
+    assert_eq!(order_book.last_trade_price, ASK);
+    assert_eq!(order_book.active_orders, 0);
+
+    // Filled orders cannot be matched again
+    let mut accounts = match_accounts(&b);
+    accounts.trade_id = 1;
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &accounts, SIZE, ASK), &[b.matcher]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn bid_escrow_pays_out_while_staying_system_owned() {
+    // The bid escrow is a system account, so fills and refunds go through
+    // signed system transfers rather than direct lamport writes
+    let mut svm = setup();
+    let b = book(&mut svm);
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+    place(&mut svm, &b.arena, &b.seller, 1, OrderSide::Sell, ASK);
+    let bid_escrow = order_escrow_pda(&limit_order_pda(&b.arena, 0, 0));
+
+    assert_ok(svm.send_instruction(
+        settle_match_ix(&b.arena, &match_accounts(&b), SIZE / 2, ASK),
+        &[b.matcher],
+    ));
+    assert_eq!(svm.account(&bid_escrow).unwrap().owner, system_program::ID);
+    // A partial fill keeps only what the rest of the bid needs
+    assert_eq!(svm.lamports(&bid_escrow), bid_reserve(&svm, BID, SIZE / 2));
+
+    let left = svm.lamports(&bid_escrow);
+    let buyer_before = svm.lamports(&b.buyer);
+    let buyer_tokens = share_ata(&b.buyer, &b.arena, 0);
+    assert_ok(svm.send_instruction(cancel_order_ix(&b.arena, &b.buyer, &buyer_tokens, 0, 0), &[b.buyer]));
+    assert_eq!(svm.lamports(&b.buyer), buyer_before + left);
+    assert_eq!(svm.lamports(&bid_escrow), 0);
+}
+
+#[test]
+fn settle_match_rejects_invalid_fills() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+    place(&mut svm, &b.arena, &b.seller, 1, OrderSide::Sell, ASK);
+    let accounts = match_accounts(&b);
+
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &accounts, 0, ASK), &[b.matcher]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &accounts, SIZE + 1, ASK), &[b.matcher]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    // Outside the bid/ask range
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &accounts, SIZE, BID + 1), &[b.matcher]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &accounts, SIZE, ASK - 1), &[b.matcher]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // Swapped sides
+    let mut swapped = match_accounts(&b);
+    swapped.buy_order_id = 1;
+    swapped.sell_order_id = 0;
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &swapped, SIZE, ASK), &[b.matcher]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // Proceeds must go to the seller
+    let mut wrong_seller = match_accounts(&b);
+    wrong_seller.seller = b.matcher;
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &wrong_seller, SIZE, ASK), &[b.matcher]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    // Fees must go to the config's recipient
+    let mut wrong_recipient = match_accounts(&b);
+    wrong_recipient.protocol_fee_recipient = b.matcher;
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &wrong_recipient, SIZE, ASK), &[b.matcher]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+#[test]
+fn settle_match_charges_the_config_fee() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+    place(&mut svm, &b.arena, &b.seller, 1, OrderSide::Sell, ASK);
+
+    let result = svm.send_instruction(set_protocol_fees_ix(&b.matcher, &b.matcher, 0), &[b.matcher]);
+    assert_error(result, BetFunError::Unauthorized);
+    let too_high = GlobalConfig::MAX_ORDER_BOOK_FEE_BPS + 1;
+    let result = svm.send_instruction(set_protocol_fees_ix(&ADMIN, &b.fee_recipient, too_high), &[ADMIN]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+    assert_ok(svm.send_instruction(set_protocol_fees_ix(&ADMIN, &b.fee_recipient, 50), &[ADMIN]));
+
+    let fee = ASK * SIZE * 50 / 10_000;
+    let recipient_before = svm.lamports(&b.fee_recipient);
+    assert_ok(svm.send_instruction(settle_match_ix(&b.arena, &match_accounts(&b), SIZE, ASK), &[b.matcher]));
+    assert_eq!(svm.lamports(&b.fee_recipient), recipient_before + 2 * fee);
+    assert_eq!(fetch::<Trade>(&svm, &trade_pda(&b.arena, 0, 0)).buyer_fee, fee);
+}
+
+#[test]
+fn trading_stops_at_lock_time() {
+    let mut svm = setup();
+    let b = book(&mut svm);
+    place(&mut svm, &b.arena, &b.buyer, 0, OrderSide::Buy, BID);
+    place(&mut svm, &b.arena, &b.seller, 1, OrderSide::Sell, ASK);
+    svm.advance_clock(DAY);
+
+    let accounts = match_accounts(&b);
+    let result = svm.send_instruction(settle_match_ix(&b.arena, &accounts, SIZE, ASK), &[b.matcher]);
+    assert_error(result, BetFunError::ArenaEnded);
+    let result = svm.send_instruction(
+        place_limit_order_ix(&b.arena, &b.buyer, 0, 2, limit_order(OrderSide::Buy, BID, SIZE)),
+        &[b.buyer],
+    );
+    assert_error(result, BetFunError::ArenaEnded);
+
+    // Resting orders can still be cancelled to release their escrow
+    let buyer_tokens = share_ata(&b.buyer, &b.arena, 0);
+    assert_ok(svm.send_instruction(cancel_order_ix(&b.arena, &b.buyer, &buyer_tokens, 0, 0), &[b.buyer]));
+}
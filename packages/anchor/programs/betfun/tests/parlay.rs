@@ -0,0 +1,386 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{Arena, Parlay, ParlayStatus, ParlayVault, ScalarRange, UserProfile};
+use betfun_math::payout;
+use betfun_testkit::Svm;
+use common::*;
+
+/// Entry fee of the test markets, so three bets clear `Parlay::MIN_LEG_POT`
+const LEG_ENTRY_FEE: u64 = SOL / 2;
+
+fn approve(svm: &mut Svm, arena: &Pubkey) {
+    assert_ok(svm.send_instruction(set_parlay_approval_ix(arena, &ADMIN, true), &[ADMIN]));
+}
+
+/// Open arena backed by one bet on each of `outcomes`, approved for parlays
+fn market_with(svm: &mut Svm, config: &ArenaConfig, outcomes: &[u8]) -> (Pubkey, Pubkey) {
+    let (creator, arena) = new_arena_with(svm, config);
+    for &outcome in outcomes {
+        join(svm, &arena, outcome);
+    }
+    approve(svm, &arena);
+    (creator, arena)
+}
+
+fn market(svm: &mut Svm, title: &str, outcomes: &[u8]) -> (Pubkey, Pubkey) {
+    let config = ArenaConfig { title: title.to_string(), entry_fee: LEG_ENTRY_FEE, ..ArenaConfig::new(svm) };
+    market_with(svm, &config, outcomes)
+}
+
+/// Two approved open arenas, outcome 0 backed twice and outcome 1 once in each
+fn new_markets(svm: &mut Svm) -> [(Pubkey, Pubkey); 2] {
+    ["Grand Final", "Bronze Match"].map(|title| market(svm, title, &[0, 0, 1]))
+}
+
+/// Odds an arena's pot implies for `outcome` right now
+fn odds(svm: &Svm, arena: &Pubkey, outcome: u8) -> u64 {
+    let state: Arena = fetch(svm, arena);
+    payout::implied_odds(state.pot, state.outcome_pots[outcome as usize], state.creator_fee_bps).unwrap()
+}
+
+fn vault(svm: &Svm) -> ParlayVault {
+    fetch(svm, &parlay_vault_pda())
+}
+
+fn resolve(svm: &mut Svm, (creator, arena): (Pubkey, Pubkey), winner: u8) {
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, winner), &[creator]));
+}
+
+/// Funded vault, two markets and a 1 SOL parlay on outcome 0 in both
+fn placed_parlay(svm: &mut Svm) -> ([(Pubkey, Pubkey); 2], Pubkey) {
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, 50 * SOL), &[ADMIN]));
+    let markets = new_markets(svm);
+    let owner = wallet(svm);
+    let legs = [(markets[0].1, 0), (markets[1].1, 0)];
+    assert_ok(svm.send_instruction(place_parlay_ix(&owner, 1, &legs, SOL, 0), &[owner]));
+    (markets, owner)
+}
+
+// ========== VAULT ==========
+
+#[test]
+fn only_the_admin_funds_and_withdraws() {
+    let mut svm = setup();
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(fund_parlay_vault_ix(&stranger, SOL), &[stranger]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, SOL), &[ADMIN]));
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, SOL), &[ADMIN]));
+    let state = vault(&svm);
+    assert_eq!((state.total_liability, state.open_parlays), (0, 0));
+
+    let result = svm.send_instruction(withdraw_parlay_vault_ix(&stranger, SOL), &[stranger]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    let before = svm.lamports(&ADMIN);
+    assert_ok(svm.send_instruction(withdraw_parlay_vault_ix(&ADMIN, 2 * SOL), &[ADMIN]));
+    assert_eq!(svm.lamports(&ADMIN) - before, 2 * SOL);
+}
+
+#[test]
+fn open_parlays_reserve_vault_liquidity() {
+    let mut svm = setup();
+    placed_parlay(&mut svm);
+    let state = vault(&svm);
+    assert_eq!(state.open_parlays, 1);
+    assert_eq!(state.total_staked, SOL);
+
+    // The admin can take out everything but the payout owed
+    let free = 51 * SOL - state.total_liability;
+    let result = svm.send_instruction(withdraw_parlay_vault_ix(&ADMIN, free + 1), &[ADMIN]);
+    assert_error(result, BetFunError::InsufficientLiquidity);
+    assert_ok(svm.send_instruction(withdraw_parlay_vault_ix(&ADMIN, free), &[ADMIN]));
+
+    // With nothing free, only the new stake backs a new parlay, which is not enough
+    let [(_, first), (_, second)] = new_markets(&mut svm);
+    let owner = wallet(&mut svm);
+    let legs = [(first, 0), (second, 0)];
+    let result = svm.send_instruction(place_parlay_ix(&owner, 1, &legs, SOL, 0), &[owner]);
+    assert_error(result, BetFunError::InsufficientLiquidity);
+}
+
+// ========== PLACE ==========
+
+#[test]
+fn parlay_locks_the_product_of_leg_odds() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, 50 * SOL), &[ADMIN]));
+    let markets = new_markets(&mut svm);
+    let owner = wallet(&mut svm);
+    let legs = [(markets[0].1, 0), (markets[1].1, 1)];
+    let expected = [odds(&svm, &markets[0].1, 0), odds(&svm, &markets[1].1, 1)];
+    let payout = payout::parlay_payout(SOL, expected).unwrap();
+
+    let result = svm.send_instruction(place_parlay_ix(&owner, 7, &legs, SOL, payout + 1), &[owner]);
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    let before = svm.lamports(&parlay_vault_pda());
+    assert_ok(svm.send_instruction(place_parlay_ix(&owner, 7, &legs, SOL, payout), &[owner]));
+    assert_eq!(svm.lamports(&parlay_vault_pda()) - before, SOL);
+
+    let state: Parlay = fetch(&svm, &parlay_pda(&owner, 7));
+    assert_eq!((state.owner, state.parlay_id), (owner, 7));
+    assert_eq!((state.stake, state.payout), (SOL, payout));
+    assert_eq!(state.legs.iter().map(|leg| leg.odds).collect::<Vec<_>>(), expected);
+    assert_eq!(state.status, ParlayStatus::Open);
+    assert_eq!(vault(&svm).total_liability, payout);
+
+    let profile: UserProfile = fetch(&svm, &user_profile_pda(&owner));
+    assert_eq!(profile.total_staked, SOL);
+}
+
+#[test]
+fn place_parlay_rejects_bad_legs() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, 50 * SOL), &[ADMIN]));
+    let [(_, first), (creator, second)] = new_markets(&mut svm);
+    let owner = wallet(&mut svm);
+    let place = |svm: &mut Svm, legs: &[(Pubkey, u8)]| {
+        svm.send_instruction(place_parlay_ix(&owner, 1, legs, SOL, 0), &[owner])
+    };
+
+    assert_error(place(&mut svm, &[(first, 0)]), BetFunError::InvalidConfiguration);
+    assert_error(place(&mut svm, &[(first, 0), (first, 1)]), BetFunError::InvalidConfiguration);
+    assert_error(place(&mut svm, &[(first, 0), (second, 2)]), BetFunError::InvalidOutcome);
+
+    let (_, one_sided) = market(&mut svm, "Bonus Round", &[0, 0, 0]);
+    assert_error(place(&mut svm, &[(first, 0), (one_sided, 1)]), BetFunError::InvalidOutcome);
+
+    let config = ArenaConfig {
+        title: "Points Total".to_string(),
+        outcomes: vec![],
+        scalar: Some(ScalarRange { lower: 100, upper: 200 }),
+        ..ArenaConfig::new(&svm)
+    };
+    let (_, scalar) = new_arena_with(&mut svm, &config);
+    assert_error(place(&mut svm, &[(first, 0), (scalar, 0)]), BetFunError::WrongMarketType);
+
+    svm.advance_clock(DAY);
+    assert_error(place(&mut svm, &[(first, 0), (second, 0)]), BetFunError::ArenaEnded);
+    resolve(&mut svm, (creator, second), 0);
+    assert_error(place(&mut svm, &[(second, 0), (first, 0)]), BetFunError::AlreadyResolved);
+}
+
+#[test]
+fn only_the_admin_approves_parlay_legs() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let stranger = wallet(&mut svm);
+    for signer in [creator, stranger] {
+        let result = svm.send_instruction(set_parlay_approval_ix(&arena, &signer, true), &[signer]);
+        assert_error(result, BetFunError::Unauthorized);
+    }
+
+    approve(&mut svm, &arena);
+    assert!(fetch::<Arena>(&svm, &arena).parlay_approved);
+    assert_ok(svm.send_instruction(set_parlay_approval_ix(&arena, &ADMIN, false), &[ADMIN]));
+    assert!(!fetch::<Arena>(&svm, &arena).parlay_approved);
+}
+
+#[test]
+fn place_parlay_rejects_steerable_legs() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, 50 * SOL), &[ADMIN]));
+    let [(_, first), (creator, second)] = new_markets(&mut svm);
+    let owner = wallet(&mut svm);
+    let place = |svm: &mut Svm, owner: Pubkey, legs: &[(Pubkey, u8)]| {
+        svm.send_instruction(place_parlay_ix(&owner, 1, legs, SOL / 10, 0), &[owner])
+    };
+
+    // Arenas the admin has not approved
+    let (_, unapproved) = market(&mut svm, "Side Bet", &[0, 0, 1]);
+    assert_ok(svm.send_instruction(set_parlay_approval_ix(&unapproved, &ADMIN, false), &[ADMIN]));
+    assert_error(place(&mut svm, owner, &[(first, 0), (unapproved, 0)]), BetFunError::ParlayLegNotAllowed);
+
+    // Arenas the bettor created or resolves as oracle
+    assert_error(place(&mut svm, creator, &[(first, 0), (second, 0)]), BetFunError::ParlayLegNotAllowed);
+    let oracle = wallet(&mut svm);
+    let config = ArenaConfig {
+        title: "Oracle Final".to_string(),
+        entry_fee: LEG_ENTRY_FEE,
+        oracle: Some(oracle),
+        ..ArenaConfig::new(&svm)
+    };
+    let (_, oracled) = market_with(&mut svm, &config, &[0, 0, 1]);
+    assert_error(place(&mut svm, oracle, &[(first, 0), (oracled, 0)]), BetFunError::ParlayLegNotAllowed);
+    assert_ok(place(&mut svm, owner, &[(first, 0), (oracled, 0)]));
+
+    // Pots too small to price a leg
+    let config = ArenaConfig { title: "Warm Up".to_string(), ..ArenaConfig::new(&svm) };
+    let (_, thin) = market_with(&mut svm, &config, &[0, 0, 1]);
+    assert!(fetch::<Arena>(&svm, &thin).pot < Parlay::MIN_LEG_POT);
+    let owner = wallet(&mut svm);
+    assert_error(place(&mut svm, owner, &[(first, 0), (thin, 0)]), BetFunError::ParlayLegPotTooSmall);
+
+    // Long shots
+    let mut outcomes = vec![0; 20];
+    outcomes.push(1);
+    let (_, long_shot) = market(&mut svm, "Long Shot", &outcomes);
+    assert!(odds(&svm, &long_shot, 1) > Parlay::MAX_LEG_ODDS);
+    assert_error(place(&mut svm, owner, &[(first, 0), (long_shot, 1)]), BetFunError::ParlayLegOddsTooHigh);
+    assert_ok(place(&mut svm, owner, &[(first, 0), (long_shot, 0)]));
+}
+
+#[test]
+fn one_parlay_pays_at_most_a_tenth_of_free_liquidity() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, 50 * SOL), &[ADMIN]));
+    let [(_, first), (_, second)] = new_markets(&mut svm);
+    let legs = [(first, 1), (second, 1)];
+    let leg_odds = [odds(&svm, &first, 1), odds(&svm, &second, 1)];
+    let owner = wallet(&mut svm);
+
+    // Free liquidity includes the stake
+    let limit = |stake: u64| (50 * SOL + stake) / 10;
+    assert!(payout::parlay_payout(SOL, leg_odds).unwrap() > limit(SOL));
+    let result = svm.send_instruction(place_parlay_ix(&owner, 1, &legs, SOL, 0), &[owner]);
+    assert_error(result, BetFunError::InsufficientLiquidity);
+
+    assert!(payout::parlay_payout(SOL / 2, leg_odds).unwrap() <= limit(SOL / 2));
+    assert_ok(svm.send_instruction(place_parlay_ix(&owner, 1, &legs, SOL / 2, 0), &[owner]));
+}
+
+// ========== SETTLE ==========
+
+#[test]
+fn winning_parlay_pays_the_locked_payout() {
+    let mut svm = setup();
+    let (markets, owner) = placed_parlay(&mut svm);
+    let payout = fetch::<Parlay>(&svm, &parlay_pda(&owner, 1)).payout;
+    let arenas = markets.map(|(_, arena)| arena);
+
+    // Later bets move the pots but not the locked odds
+    join(&mut svm, &arenas[0], 0);
+    join(&mut svm, &arenas[1], 1);
+
+    svm.advance_clock(DAY);
+    let settler = wallet(&mut svm);
+    resolve(&mut svm, markets[0], 0);
+    let result = svm.send_instruction(settle_parlay_ix(&settler, &owner, 1, &arenas), &[settler]);
+    assert_error(result, BetFunError::NotResolved);
+
+    resolve(&mut svm, markets[1], 0);
+    let result = svm.send_instruction(settle_parlay_ix(&settler, &owner, 1, &[arenas[1], arenas[0]]), &[settler]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let before = svm.lamports(&owner);
+    assert_ok(svm.send_instruction(settle_parlay_ix(&settler, &owner, 1, &arenas), &[settler]));
+    assert_eq!(svm.lamports(&owner) - before, payout);
+
+    let state: Parlay = fetch(&svm, &parlay_pda(&owner, 1));
+    assert_eq!(state.status, ParlayStatus::Won);
+    assert_eq!(state.settled_at, now(&svm));
+    let vault = vault(&svm);
+    assert_eq!((vault.total_liability, vault.open_parlays, vault.total_paid_out), (0, 0, payout));
+
+    let profile: UserProfile = fetch(&svm, &user_profile_pda(&owner));
+    assert_eq!(profile.total_won, payout);
+    assert_eq!(profile.realized_pnl, (payout - SOL) as i64);
+
+    let result = svm.send_instruction(settle_parlay_ix(&settler, &owner, 1, &arenas), &[settler]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+}
+
+#[test]
+fn one_lost_leg_settles_the_parlay_early() {
+    let mut svm = setup();
+    let (markets, owner) = placed_parlay(&mut svm);
+    let arenas = markets.map(|(_, arena)| arena);
+    svm.advance_clock(DAY);
+    resolve(&mut svm, markets[1], 1);
+
+    let before = svm.lamports(&owner);
+    let settler = wallet(&mut svm);
+    assert_ok(svm.send_instruction(settle_parlay_ix(&settler, &owner, 1, &arenas), &[settler]));
+    assert_eq!(svm.lamports(&owner), before);
+
+    let state: Parlay = fetch(&svm, &parlay_pda(&owner, 1));
+    assert_eq!(state.status, ParlayStatus::Lost);
+    let profile: UserProfile = fetch(&svm, &user_profile_pda(&owner));
+    assert_eq!(profile.realized_pnl, -(SOL as i64));
+    let vault = vault(&svm);
+    assert_eq!((vault.total_liability, vault.open_parlays, vault.total_paid_out), (0, 0, 0));
+}
+
+#[test]
+fn voided_leg_pays_back_evenly() {
+    let mut svm = setup();
+    let (markets, owner) = placed_parlay(&mut svm);
+    let arenas = markets.map(|(_, arena)| arena);
+    let state: Parlay = fetch(&svm, &parlay_pda(&owner, 1));
+    let expected = payout::parlay_without_leg(state.payout, state.legs[1].odds).unwrap();
+
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    let settler = wallet(&mut svm);
+    assert_ok(svm.send_instruction(void_arena_ix(&arenas[1], &settler), &[settler]));
+    resolve(&mut svm, markets[0], 0);
+
+    let before = svm.lamports(&owner);
+    assert_ok(svm.send_instruction(settle_parlay_ix(&settler, &owner, 1, &arenas), &[settler]));
+    assert_eq!(svm.lamports(&owner) - before, expected);
+    let vault = vault(&svm);
+    assert_eq!((vault.total_liability, vault.total_paid_out), (0, expected));
+}
+
+// ========== ATTACKS ==========
+
+#[test]
+fn sock_puppet_parlays_cannot_drain_the_vault() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(fund_parlay_vault_ix(&ADMIN, 50 * SOL), &[ADMIN]));
+    let vault_before = svm.lamports(&parlay_vault_pda());
+
+    // The attacker opens arenas and has sock puppets pile onto one side, so
+    // the other side prices at long odds, then means to resolve it as won
+    let attacker = wallet(&mut svm);
+    let puppet = wallet(&mut svm);
+    let mut rigged = vec![];
+    for (title, piled_on) in [("Rigged One", 9), ("Rigged Two", 9), ("Rigged Three", 9), ("Rigged Four", 19)] {
+        let config = ArenaConfig { title: title.to_string(), ..ArenaConfig::new(&svm) };
+        assert_ok(svm.send_instruction(create_arena_ix(&attacker, &config), &[attacker]));
+        let arena = arena_pda(&attacker, title);
+        for _ in 0..piled_on {
+            join(&mut svm, &arena, 0);
+        }
+        join(&mut svm, &arena, 1);
+        rigged.push(arena);
+    }
+    let legs: Vec<_> = rigged.iter().map(|arena| (*arena, 1)).collect();
+    let place = |svm: &mut Svm, owner: Pubkey, legs: &[(Pubkey, u8)], stake: u64| {
+        svm.send_instruction(place_parlay_ix(&owner, 1, legs, stake, 0), &[owner])
+    };
+
+    // Unreviewed arenas back no parlay, whoever places it
+    for owner in [attacker, puppet] {
+        assert_error(place(&mut svm, owner, &legs, SOL), BetFunError::ParlayLegNotAllowed);
+    }
+
+    // Even once the admin approves them, the creator cannot parlay their own arenas
+    for arena in &rigged {
+        approve(&mut svm, arena);
+    }
+    assert_error(place(&mut svm, attacker, &legs, SOL), BetFunError::ParlayLegNotAllowed);
+
+    // A puppet cannot lock the most lopsided odds, nor a payout the vault cannot spare
+    assert_error(place(&mut svm, puppet, &legs, SOL), BetFunError::ParlayLegOddsTooHigh);
+    let legs = &legs[..3];
+    assert_error(place(&mut svm, puppet, legs, SOL), BetFunError::InsufficientLiquidity);
+
+    // The most the puppet can place pays a tenth of the free liquidity
+    let stake = SOL / 200;
+    assert_ok(place(&mut svm, puppet, legs, stake));
+    let parlay: Parlay = fetch(&svm, &parlay_pda(&puppet, 1));
+    assert!(parlay.payout <= (50 * SOL + stake) / 10);
+
+    svm.advance_clock(DAY);
+    for arena in &rigged[..3] {
+        assert_ok(svm.send_instruction(resolve_arena_ix(arena, &attacker, 1), &[attacker]));
+    }
+    let arenas: Vec<_> = rigged[..3].to_vec();
+    assert_ok(svm.send_instruction(settle_parlay_ix(&attacker, &puppet, 1, &arenas), &[attacker]));
+    assert_eq!(fetch::<Parlay>(&svm, &parlay_pda(&puppet, 1)).status, ParlayStatus::Won);
+    assert!(vault_before - svm.lamports(&parlay_vault_pda()) < 50 * SOL / 10);
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{pause, Arena, GlobalConfig, LiquidityPosition, OrderSide};
+use betfun_testkit::Svm;
+use common::*;
+
+fn pause_globally(svm: &mut Svm, paused: u8) {
+    assert_ok(svm.send_instruction(set_global_pause_ix(&ADMIN, paused), &[ADMIN]));
+}
+
+fn pause_arena(svm: &mut Svm, arena: &Pubkey, authority: &Pubkey, paused: u8) {
+    assert_ok(svm.send_instruction(set_arena_pause_ix(arena, authority, paused), &[*authority]));
+}
+
+// ========== CONFIG ==========
+
+#[test]
+fn initialize_config_sets_admin_once() {
+    let mut svm = setup();
+    let config: GlobalConfig = fetch(&svm, &config_pda());
+    assert_eq!(config.admin, ADMIN);
+    assert_eq!(config.paused, 0);
+
+    let other = wallet(&mut svm);
+    let result = svm.send_instruction(initialize_config_ix(&other), &[other]);
+    assert!(result.is_err());
+    assert_eq!(fetch::<GlobalConfig>(&svm, &config_pda()).admin, ADMIN);
+}
+
+#[test]
+fn initialize_config_requires_the_upgrade_authority() {
+    let mut svm = Svm::new();
+    svm.add_program(betfun::ID, betfun::entry);
+    let other = wallet(&mut svm);
+    svm.airdrop(&ADMIN, 100 * SOL);
+
+    // An immutable program has no one to initialize it
+    let result = svm.send_instruction(initialize_config_ix(&ADMIN), &[ADMIN]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    svm.set_upgrade_authority(&betfun::ID, Some(ADMIN));
+    let result = svm.send_instruction(initialize_config_ix(&other), &[other]);
+    assert_error(result, BetFunError::Unauthorized);
+    assert!(svm.account(&config_pda()).is_none());
+
+    assert_ok(svm.send_instruction(initialize_config_ix(&ADMIN), &[ADMIN]));
+    assert_eq!(fetch::<GlobalConfig>(&svm, &config_pda()).admin, ADMIN);
+}
+
+#[test]
+fn transfer_admin_hands_over_the_config() {
+    let mut svm = setup();
+    let new_admin = wallet(&mut svm);
+
+    let result = svm.send_instruction(transfer_admin_ix(&new_admin, &new_admin), &[new_admin]);
+    assert_error(result, BetFunError::Unauthorized);
+    let result = svm.send_instruction(transfer_admin_ix(&ADMIN, &Pubkey::default()), &[ADMIN]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    assert_ok(svm.send_instruction(transfer_admin_ix(&ADMIN, &new_admin), &[ADMIN]));
+    assert_eq!(fetch::<GlobalConfig>(&svm, &config_pda()).admin, new_admin);
+
+    let result = svm.send_instruction(set_global_pause_ix(&ADMIN, pause::ALL), &[ADMIN]);
+    assert_error(result, BetFunError::Unauthorized);
+    assert_ok(svm.send_instruction(set_global_pause_ix(&new_admin, pause::ALL), &[new_admin]));
+}
+
+#[test]
+fn pause_flags_require_authority() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let stranger = wallet(&mut svm);
+
+    let result = svm.send_instruction(set_global_pause_ix(&creator, pause::ALL), &[creator]);
+    assert_error(result, BetFunError::Unauthorized);
+    let result = svm.send_instruction(set_arena_pause_ix(&arena, &stranger, pause::ALL), &[stranger]);
+    assert_error(result, BetFunError::Unauthorized);
+    let result = svm.send_instruction(set_global_pause_ix(&ADMIN, 1 << 7), &[ADMIN]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // The admin can pause any arena as well as its creator
+    pause_arena(&mut svm, &arena, &ADMIN, pause::BETTING);
+    pause_arena(&mut svm, &arena, &creator, pause::BETTING | pause::CLAIMS);
+    assert_eq!(fetch::<Arena>(&svm, &arena).paused, pause::BETTING | pause::CLAIMS);
+}
+
+// ========== GLOBAL PAUSE ==========
+
+#[test]
+fn global_pause_stops_each_activity() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let user = wallet(&mut svm);
+
+    pause_globally(&mut svm, pause::BETTING);
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]);
+    assert_error(result, BetFunError::Paused);
+    let mut config = ArenaConfig::new(&svm);
+    config.title = "Second Final".to_string();
+    let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+    assert_error(result, BetFunError::Paused);
+
+    pause_globally(&mut svm, pause::SHARES);
+    assert_ok(svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]));
+    let result = svm.send_instruction(buy_shares_ix(&arena, &user, &creator, 0, SHARE, None, None), &[user]);
+    assert_error(result, BetFunError::Paused);
+
+    pause_globally(&mut svm, pause::AMM);
+    let result = svm.send_instruction(initialize_pool_ix(&arena, &creator, 0, 30, 10), &[creator]);
+    assert_error(result, BetFunError::Paused);
+
+    pause_globally(&mut svm, pause::ORDER_BOOK);
+    let result = svm.send_instruction(initialize_order_book_ix(&arena, &creator, 0), &[creator]);
+    assert_error(result, BetFunError::Paused);
+
+    pause_globally(&mut svm, pause::CLAIMS);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let result = svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]);
+    assert_error(result, BetFunError::Paused);
+
+    pause_globally(&mut svm, 0);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+}
+
+#[test]
+fn voided_refunds_work_while_claims_are_paused() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let (first, second) = (join(&mut svm, &arena, 0), join(&mut svm, &arena, 1));
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    let caller = wallet(&mut svm);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+
+    pause_globally(&mut svm, pause::CLAIMS);
+    pause_arena(&mut svm, &arena, &ADMIN, pause::CLAIMS);
+    let before = svm.lamports(&first);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &first, &creator), &[first]));
+    assert_eq!(svm.lamports(&first) - before, ENTRY_FEE);
+    let before = svm.lamports(&second);
+    assert_ok(svm.send_instruction(claim_many_ix(&second, &[bet_entry(&arena, &second, &creator)]), &[second]));
+    assert_eq!(svm.lamports(&second) - before, ENTRY_FEE);
+}
+
+#[test]
+fn cancels_and_withdrawals_work_while_paused() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    assert_ok(svm.send_instruction(initialize_order_book_ix(&arena, &creator, 0), &[creator]));
+    assert_ok(svm.send_instruction(initialize_pool_ix(&arena, &creator, 0, 30, 10), &[creator]));
+    let provider = shareholder(&mut svm, &arena, 0, 4 * SHARE);
+    assert_ok(svm.send_instruction(add_liquidity_ix(&arena, &provider, 0, 2 * SHARE, SOL, 0), &[provider]));
+    let order = limit_order(OrderSide::Sell, 100_000, SHARE);
+    assert_ok(svm.send_instruction(place_limit_order_ix(&arena, &provider, 0, 0, order), &[provider]));
+
+    pause_globally(&mut svm, pause::ALL);
+    pause_arena(&mut svm, &arena, &creator, pause::ALL);
+
+    let provider_tokens = share_ata(&provider, &arena, 0);
+    assert_ok(svm.send_instruction(cancel_order_ix(&arena, &provider, &provider_tokens, 0, 0), &[provider]));
+    let position: LiquidityPosition = fetch(&svm, &liquidity_position_pda(&pool_pda(&arena, 0), &provider));
+    let remove = remove_liquidity_ix(&arena, &provider, 0, position.lp_tokens, 0, 0);
+    assert_ok(svm.send_instruction(remove, &[provider]));
+}
+
+// ========== ARENA PAUSE ==========
+
+#[test]
+fn arena_pause_only_affects_that_arena() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let mut config = ArenaConfig::new(&svm);
+    config.title = "Bronze Match".to_string();
+    let (_, other) = new_arena_with(&mut svm, &config);
+    let user = wallet(&mut svm);
+
+    pause_arena(&mut svm, &arena, &creator, pause::BETTING);
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]);
+    assert_error(result, BetFunError::Paused);
+    assert_ok(svm.send_instruction(join_arena_ix(&other, &user, 0), &[user]));
+
+    pause_arena(&mut svm, &arena, &creator, 0);
+    assert_ok(svm.send_instruction(join_arena_ix(&arena, &user, 0), &[user]));
+}
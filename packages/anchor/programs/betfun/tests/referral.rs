@@ -0,0 +1,192 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{Arena, GlobalConfig, Referral};
+use betfun_testkit::Svm;
+use common::*;
+
+const REFERRAL_SHARE_BPS: u64 = GlobalConfig::DEFAULT_REFERRAL_SHARE_BPS as u64;
+const CREATOR_FEE_BPS: u64 = Arena::DEFAULT_CREATOR_FEE_BPS as u64;
+
+/// Referrer's cut of one entry fee at the default fee and share
+const JOIN_REWARD: u64 = ENTRY_FEE * CREATOR_FEE_BPS / 10_000 * REFERRAL_SHARE_BPS / 10_000;
+
+/// New wallet that joins `arena` on `outcome`, referred by `referrer`
+fn referred_join(svm: &mut Svm, arena: &Pubkey, outcome: u8, referrer: &Pubkey) -> Pubkey {
+    let user = wallet(svm);
+    let ix = with_referrer(join_arena_ix(arena, &user, outcome), &user, referrer);
+    assert_ok(svm.send_instruction(ix, &[user]));
+    user
+}
+
+// ========== JOIN ARENA ==========
+
+#[test]
+fn join_records_referral_and_pays_creator_fee_share() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let referrer = wallet(&mut svm);
+    let arena_before = svm.lamports(&arena);
+
+    let user = referred_join(&mut svm, &arena, 0, &referrer);
+
+    let referral: Referral = fetch(&svm, &referral_pda(&user));
+    assert_eq!(referral.user, user);
+    assert_eq!(referral.referrer, referrer);
+    assert_eq!(referral.pending_rewards, JOIN_REWARD);
+    assert_eq!(referral.total_earned, JOIN_REWARD);
+    let rent = svm.rent().minimum_balance(Referral::SIZE);
+    assert_eq!(svm.lamports(&referral_pda(&user)), rent + JOIN_REWARD);
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.pot, ENTRY_FEE);
+    assert_eq!(state.referral_fees, JOIN_REWARD);
+    assert_eq!(state.total_paid_out, JOIN_REWARD);
+    assert_eq!(svm.lamports(&arena), arena_before + ENTRY_FEE - JOIN_REWARD);
+
+    // Winners are paid in full; the referral comes out of the creator's fee
+    join(&mut svm, &arena, 1);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let creator_before = svm.lamports(&creator);
+    let user_before = svm.lamports(&user);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &user, &creator), &[user]));
+
+    let pot = 2 * ENTRY_FEE;
+    let creator_fee = pot * CREATOR_FEE_BPS / 10_000;
+    assert_eq!(svm.lamports(&user), user_before + pot - creator_fee);
+    assert_eq!(svm.lamports(&creator), creator_before + creator_fee - JOIN_REWARD);
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.total_paid_out, pot);
+    assert_eq!(svm.lamports(&arena), arena_before);
+}
+
+#[test]
+fn referrer_is_fixed_on_first_use() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let mut config = ArenaConfig::new(&svm);
+    config.title = "Bronze Match".to_string();
+    let (_, other) = new_arena_with(&mut svm, &config);
+    let referrer = wallet(&mut svm);
+    let user = referred_join(&mut svm, &arena, 0, &referrer);
+
+    // A later referrer is ignored, and the reward still accrues to the first
+    let impostor = wallet(&mut svm);
+    let ix = with_referrer(join_arena_ix(&other, &user, 0), &user, &impostor);
+    assert_ok(svm.send_instruction(ix, &[user]));
+    let referral: Referral = fetch(&svm, &referral_pda(&user));
+    assert_eq!(referral.referrer, referrer);
+    assert_eq!(referral.pending_rewards, 2 * JOIN_REWARD);
+    let result = svm.send_instruction(claim_referral_rewards_ix(&user, &impostor), &[impostor]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    // Later actions without a referrer earn nothing
+    config.title = "Final".to_string();
+    let (_, third) = new_arena_with(&mut svm, &config);
+    assert_ok(svm.send_instruction(join_arena_ix(&third, &user, 0), &[user]));
+    assert_eq!(fetch::<Referral>(&svm, &referral_pda(&user)).pending_rewards, 2 * JOIN_REWARD);
+}
+
+#[test]
+fn users_cannot_refer_themselves() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let user = wallet(&mut svm);
+
+    let ix = with_referrer(join_arena_ix(&arena, &user, 0), &user, &user);
+    assert_error(svm.send_instruction(ix, &[user]), BetFunError::InvalidReferrer);
+}
+
+// ========== SHARES AND SWAPS ==========
+
+#[test]
+fn share_purchases_record_referral_without_reward() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let referrer = wallet(&mut svm);
+    let buyer = wallet(&mut svm);
+
+    let ix = with_referrer(buy_shares_ix(&arena, &buyer, &creator, 0, SHARE, None, None), &buyer, &referrer);
+    assert_ok(svm.send_instruction(ix, &[buyer]));
+
+    let referral: Referral = fetch(&svm, &referral_pda(&buyer));
+    assert_eq!(referral.referrer, referrer);
+    assert_eq!(referral.pending_rewards, 0);
+}
+
+#[test]
+fn swaps_share_the_protocol_fee() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    assert_ok(svm.send_instruction(initialize_pool_ix(&arena, &creator, 0, 30, 10), &[creator]));
+    let provider = shareholder(&mut svm, &arena, 0, 10 * SHARE);
+    assert_ok(svm.send_instruction(add_liquidity_ix(&arena, &provider, 0, 4 * SHARE, SOL, 0), &[provider]));
+    let fee_recipient = fee_recipient(&mut svm, GlobalConfig::DEFAULT_ORDER_BOOK_FEE_BPS);
+    let referrer = wallet(&mut svm);
+    let trader = wallet(&mut svm);
+    assert_ok(svm.send_instruction(create_share_ata_ix(&trader, &trader, &arena, 0), &[trader]));
+    let trader_tokens = share_ata(&trader, &arena, 0);
+
+    let amount_in = SOL / 10;
+    let protocol_fee = amount_in * 10 / 10_000;
+    let reward = protocol_fee * REFERRAL_SHARE_BPS / 10_000;
+    let recipient_before = svm.lamports(&fee_recipient);
+    let swap = swap_ix(&arena, &trader, &creator, &trader_tokens, &fee_recipient, 0, amount_in, 0, false);
+    assert_ok(svm.send_instruction(with_referrer(swap, &trader, &referrer), &[trader]));
+
+    assert_eq!(svm.lamports(&fee_recipient), recipient_before + protocol_fee - reward);
+    let referral: Referral = fetch(&svm, &referral_pda(&trader));
+    assert_eq!(referral.referrer, referrer);
+    assert_eq!(referral.pending_rewards, reward);
+}
+
+// ========== CLAIMS ==========
+
+#[test]
+fn referrer_claims_pending_rewards() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let referrer = wallet(&mut svm);
+    let user = referred_join(&mut svm, &arena, 0, &referrer);
+
+    let result = svm.send_instruction(claim_referral_rewards_ix(&user, &user), &[user]);
+    assert_error(result, BetFunError::Unauthorized);
+
+    let referrer_before = svm.lamports(&referrer);
+    assert_ok(svm.send_instruction(claim_referral_rewards_ix(&user, &referrer), &[referrer]));
+    assert_eq!(svm.lamports(&referrer), referrer_before + JOIN_REWARD);
+
+    let referral: Referral = fetch(&svm, &referral_pda(&user));
+    assert_eq!(referral.pending_rewards, 0);
+    assert_eq!(referral.total_earned, JOIN_REWARD);
+    assert_eq!(svm.lamports(&referral_pda(&user)), svm.rent().minimum_balance(Referral::SIZE));
+
+    let result = svm.send_instruction(claim_referral_rewards_ix(&user, &referrer), &[referrer]);
+    assert_error(result, BetFunError::NoReferralRewards);
+}
+
+// ========== CONFIG ==========
+
+#[test]
+fn referral_share_is_set_by_admin() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    assert_eq!(fetch::<GlobalConfig>(&svm, &config_pda()).referral_share_bps, GlobalConfig::DEFAULT_REFERRAL_SHARE_BPS);
+
+    let result = svm.send_instruction(set_referral_share_ix(&creator, 0), &[creator]);
+    assert_error(result, BetFunError::Unauthorized);
+    let too_high = GlobalConfig::MAX_REFERRAL_SHARE_BPS + 1;
+    let result = svm.send_instruction(set_referral_share_ix(&ADMIN, too_high), &[ADMIN]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // With no share the referral is still recorded
+    assert_ok(svm.send_instruction(set_referral_share_ix(&ADMIN, 0), &[ADMIN]));
+    let referrer = wallet(&mut svm);
+    let user = referred_join(&mut svm, &arena, 0, &referrer);
+    let referral: Referral = fetch(&svm, &referral_pda(&user));
+    assert_eq!(referral.referrer, referrer);
+    assert_eq!(referral.pending_rewards, 0);
+    assert_eq!(fetch::<Arena>(&svm, &arena).referral_fees, 0);
+}
@@ -0,0 +1,297 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{Arena, CurveType, OutcomeShare, ScalarRange, ShareBalance};
+use betfun_testkit::Svm;
+use common::*;
+
+const LONG: u8 = Arena::LONG;
+const SHORT: u8 = Arena::SHORT;
+
+fn scalar_config(svm: &Svm) -> ArenaConfig {
+    ArenaConfig {
+        title: "Points Total".to_string(),
+        question: "How many points will the home side score?".to_string(),
+        outcomes: vec![],
+        scalar: Some(ScalarRange { lower: 100, upper: 200 }),
+        ..ArenaConfig::new(svm)
+    }
+}
+
+/// Scalar arena with LONG and SHORT share tokens, issued in pairs
+fn new_scalar_market(svm: &mut Svm) -> (Pubkey, Pubkey) {
+    let config = scalar_config(svm);
+    new_scalar_market_with(svm, &config)
+}
+
+fn new_scalar_market_with(svm: &mut Svm, config: &ArenaConfig) -> (Pubkey, Pubkey) {
+    let (creator, arena) = new_arena_with(svm, config);
+    for outcome_index in [LONG, SHORT] {
+        assert_ok(svm.send_instruction(
+            create_share_tokens_ix(&arena, &creator, outcome_index, SOL / 2, CurveType::Linear, 0),
+            &[creator],
+        ));
+    }
+    (creator, arena)
+}
+
+/// New wallet holding `amount` LONG/SHORT pairs
+fn pair_holder(svm: &mut Svm, arena: &Pubkey, amount: u64) -> Pubkey {
+    let holder = wallet(svm);
+    let creator = fetch::<Arena>(svm, arena).creator;
+    assert_ok(svm.send_instruction(mint_complete_sets_ix(arena, &holder, &creator, amount, u64::MAX), &[holder]));
+    holder
+}
+
+fn redeem(svm: &mut Svm, arena: &Pubkey, holder: &Pubkey, outcome_index: u8, amount: u64) -> u64 {
+    let before = svm.lamports(holder);
+    assert_ok(svm.send_instruction(redeem_shares_ix(arena, holder, outcome_index, amount, None, None), &[*holder]));
+    svm.lamports(holder) - before
+}
+
+// ========== CREATE ==========
+
+#[test]
+fn scalar_arenas_trade_long_and_short() {
+    let mut svm = setup();
+    let (_, arena) = new_scalar_market(&mut svm);
+
+    let state: Arena = fetch(&svm, &arena);
+    assert_eq!(state.outcomes, vec!["LONG".to_string(), "SHORT".to_string()]);
+    assert_eq!(state.scalar, Some(ScalarRange { lower: 100, upper: 200 }));
+    assert_eq!(state.resolved_value, None);
+    assert!(state.is_scalar());
+}
+
+#[test]
+fn scalar_arenas_reject_bad_ranges_and_outcomes() {
+    let mut svm = setup();
+    let creator = wallet(&mut svm);
+    let cases: [fn(&mut ArenaConfig); 3] = [
+        |c| c.scalar = Some(ScalarRange { lower: 200, upper: 200 }),
+        |c| c.scalar = Some(ScalarRange { lower: 200, upper: -200 }),
+        |c| c.outcomes = vec!["Over".to_string(), "Under".to_string()],
+    ];
+    for case in cases {
+        let mut config = scalar_config(&svm);
+        case(&mut config);
+        let result = svm.send_instruction(create_arena_ix(&creator, &config), &[creator]);
+        assert_error(result, BetFunError::InvalidConfiguration);
+    }
+}
+
+#[test]
+fn pot_bets_and_categorical_resolution_are_rejected() {
+    let mut svm = setup();
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let user = wallet(&mut svm);
+    let result = svm.send_instruction(join_arena_ix(&arena, &user, LONG), &[user]);
+    assert_error(result, BetFunError::WrongMarketType);
+
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(resolve_arena_ix(&arena, &creator, LONG), &[creator]);
+    assert_error(result, BetFunError::WrongMarketType);
+
+    let (creator, categorical) = new_arena(&mut svm);
+    let result = svm.send_instruction(resolve_scalar_arena_ix(&categorical, &creator, 150), &[creator]);
+    assert_error(result, BetFunError::WrongMarketType);
+}
+
+// ========== RESOLVE ==========
+
+#[test]
+fn resolve_scalar_arena_checks_resolver_and_end_time() {
+    let mut svm = setup();
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let result = svm.send_instruction(resolve_scalar_arena_ix(&arena, &creator, 150), &[creator]);
+    assert_error(result, BetFunError::ArenaNotEnded);
+
+    svm.advance_clock(DAY);
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(resolve_scalar_arena_ix(&arena, &stranger, 150), &[stranger]);
+    assert_error(result, BetFunError::UnauthorizedResolver);
+
+    // Reports outside the range settle at the nearest bound
+    assert_ok(svm.send_instruction(resolve_scalar_arena_ix(&arena, &creator, 250), &[creator]));
+    let state: Arena = fetch(&svm, &arena);
+    assert!(state.resolved);
+    assert_eq!((state.resolved_value, state.winner_outcome), (Some(200), None));
+
+    let result = svm.send_instruction(resolve_scalar_arena_ix(&arena, &creator, 150), &[creator]);
+    assert_error(result, BetFunError::AlreadyResolved);
+}
+
+// ========== COMPLETE SETS ==========
+
+#[test]
+fn complete_sets_lock_one_sol_per_pair() {
+    let mut svm = setup();
+    let config = ArenaConfig { creator_trading_fee_bps: 100, ..scalar_config(&svm) };
+    let (creator, arena) = new_scalar_market_with(&mut svm, &config);
+    let buyer = wallet(&mut svm);
+
+    let amount = 3 * SHARE / 2;
+    let fee = 3 * SOL / 200;
+    let result = svm.send_instruction(
+        mint_complete_sets_ix(&arena, &buyer, &creator, amount, 3 * SOL / 2 + fee - 1),
+        &[buyer],
+    );
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    let (escrow_before, creator_before) = (svm.lamports(&arena), svm.lamports(&creator));
+    assert_ok(svm.send_instruction(
+        mint_complete_sets_ix(&arena, &buyer, &creator, amount, 3 * SOL / 2 + fee),
+        &[buyer],
+    ));
+    assert_eq!(svm.lamports(&arena), escrow_before + 3 * SOL / 2);
+    assert_eq!(svm.lamports(&creator), creator_before + fee);
+    for side in [LONG, SHORT] {
+        assert_eq!(token_balance(&svm, &share_ata(&buyer, &arena, side)), amount);
+        let share: OutcomeShare = fetch(&svm, &outcome_share_pda(&arena, side));
+        assert_eq!(share.total_supply, amount);
+        let balance: ShareBalance = fetch(&svm, &share_balance_pda(&outcome_share_pda(&arena, side), &buyer));
+        assert_eq!((balance.balance, balance.avg_cost_basis), (amount, SOL / 2));
+    }
+    assert_eq!(fetch::<Arena>(&svm, &arena).shares_outstanding, 2 * amount);
+}
+
+#[test]
+fn scalar_shares_only_issue_in_pairs() {
+    let mut svm = setup();
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let holder = pair_holder(&mut svm, &arena, SHARE);
+
+    // A lone side bought off the curve would be unbacked
+    let result = svm.send_instruction(buy_shares_ix(&arena, &holder, &creator, LONG, SHARE, None, None), &[holder]);
+    assert_error(result, BetFunError::WrongMarketType);
+    let result = svm.send_instruction(sell_shares_ix(&arena, &holder, SHORT, SHARE, None, None), &[holder]);
+    assert_error(result, BetFunError::WrongMarketType);
+
+    let result = svm.send_instruction(mint_complete_sets_ix(&arena, &holder, &creator, 0, u64::MAX), &[holder]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+    let (categorical_creator, categorical) = new_share_market(&mut svm);
+    let result = svm.send_instruction(
+        mint_complete_sets_ix(&categorical, &holder, &categorical_creator, SHARE, u64::MAX),
+        &[holder],
+    );
+    assert_error(result, BetFunError::WrongMarketType);
+
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(mint_complete_sets_ix(&arena, &holder, &creator, SHARE, u64::MAX), &[holder]);
+    assert_error(result, BetFunError::ArenaEnded);
+}
+
+// ========== REDEEM ==========
+
+#[test]
+fn long_and_short_split_the_redemption_price() {
+    let mut svm = setup();
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let holder = pair_holder(&mut svm, &arena, 4 * SHARE);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_scalar_arena_ix(&arena, &creator, 125), &[creator]));
+
+    // 125 is a quarter of the way from 100 to 200
+    assert_eq!(redeem(&mut svm, &arena, &holder, LONG, 4 * SHARE), SOL);
+    assert_eq!(redeem(&mut svm, &arena, &holder, SHORT, 4 * SHARE), 3 * SOL);
+}
+
+#[test]
+fn shares_redeem_by_the_base_unit() {
+    // Shares have 9 decimals, so 1 SOL is the price of SHARE base units, not of one
+    let mut svm = setup();
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let holder = pair_holder(&mut svm, &arena, SHARE / 4 + 3);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_scalar_arena_ix(&arena, &creator, 200), &[creator]));
+
+    assert_eq!(redeem(&mut svm, &arena, &holder, LONG, SHARE / 4), SOL / 4);
+    assert_eq!(redeem(&mut svm, &arena, &holder, LONG, 3), 3);
+}
+
+#[test]
+fn a_side_at_its_bound_redeems_for_nothing() {
+    let mut svm = setup();
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let holder = pair_holder(&mut svm, &arena, SHARE);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_scalar_arena_ix(&arena, &creator, -40), &[creator]));
+
+    assert_eq!(redeem(&mut svm, &arena, &holder, SHORT, SHARE), SOL);
+    assert_eq!(redeem(&mut svm, &arena, &holder, LONG, SHARE), 0);
+}
+
+#[test]
+fn unbalanced_redemptions_are_all_paid() {
+    let mut svm = setup();
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let escrow_start = svm.lamports(&arena);
+    let amounts = [7 * SHARE, SHARE / 3, 25 * SHARE];
+    let holders: Vec<_> = amounts.iter().map(|amount| pair_holder(&mut svm, &arena, *amount)).collect();
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_scalar_arena_ix(&arena, &creator, 190), &[creator]));
+
+    // Every LONG redeems before most SHORTs, in uneven pieces, and the last
+    // SHORT is still paid in full
+    let mut paid = 0;
+    for (holder, amount) in holders.iter().zip(amounts) {
+        paid += redeem(&mut svm, &arena, holder, LONG, amount / 2);
+        paid += redeem(&mut svm, &arena, holder, LONG, amount - amount / 2);
+    }
+    paid += redeem(&mut svm, &arena, &holders[2], SHORT, amounts[2]);
+    paid += redeem(&mut svm, &arena, &holders[0], SHORT, amounts[0]);
+    paid += redeem(&mut svm, &arena, &holders[1], SHORT, amounts[1]);
+
+    // Rounding down leaves at most a lamport per redemption behind
+    let collateral: u64 = amounts.iter().sum();
+    assert!(paid <= collateral && collateral - paid <= 9);
+    assert_eq!(svm.lamports(&arena), escrow_start + collateral - paid);
+    assert_eq!(fetch::<Arena>(&svm, &arena).shares_outstanding, 0);
+}
+
+// ========== VOID ==========
+
+#[test]
+fn voided_scalar_arenas_refund_half_a_pair_per_share() {
+    let mut svm = setup();
+    let (_, arena) = new_scalar_market(&mut svm);
+    let caller = wallet(&mut svm);
+    let holder = pair_holder(&mut svm, &arena, 2 * SHARE);
+    let result = svm.send_instruction(redeem_shares_ix(&arena, &holder, LONG, SHARE, None, None), &[holder]);
+    assert_error(result, BetFunError::NotResolved);
+
+    // Shares alone make the arena voidable once its resolver goes missing
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+
+    assert_eq!(redeem(&mut svm, &arena, &holder, LONG, 2 * SHARE), SOL);
+    assert_eq!(redeem(&mut svm, &arena, &holder, SHORT, SHARE), SOL / 2);
+    assert_eq!(redeem(&mut svm, &arena, &holder, SHORT, SHARE), SOL / 2);
+}
+
+#[test]
+fn voided_scalar_arenas_slash_the_bond_to_share_holders() {
+    let mut svm = setup();
+    assert_ok(svm.send_instruction(set_creator_bond_ix(&ADMIN, SOL), &[ADMIN]));
+    let (creator, arena) = new_scalar_market(&mut svm);
+    let holder = pair_holder(&mut svm, &arena, 2 * SHARE);
+    let config = ArenaConfig { title: "Empty Total".to_string(), ..scalar_config(&svm) };
+    let (_, empty) = new_scalar_market_with(&mut svm, &config);
+    let caller = wallet(&mut svm);
+
+    // A scalar arena nobody traded frees its bond at the lock; one with shares keeps it
+    svm.advance_clock(DAY);
+    let empty_creator = fetch::<Arena>(&svm, &empty).creator;
+    assert_ok(svm.send_instruction(release_creator_bond_ix(&empty, &empty_creator, &caller), &[caller]));
+    let result = svm.send_instruction(release_creator_bond_ix(&arena, &creator, &caller), &[caller]);
+    assert_error(result, BetFunError::NotResolved);
+
+    // Voiding hands the bond to the holders, half a pair per share plus a quarter of the bond each
+    svm.advance_clock(Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+    assert_eq!(redeem(&mut svm, &arena, &holder, LONG, 2 * SHARE), SOL + SOL / 2);
+    assert_eq!(redeem(&mut svm, &arena, &holder, SHORT, SHARE), SOL / 2 + SOL / 4);
+    assert_eq!(redeem(&mut svm, &arena, &holder, SHORT, SHARE), SOL / 2 + SOL / 4);
+    assert_eq!(fetch::<Arena>(&svm, &arena).creator_bond, 0);
+}
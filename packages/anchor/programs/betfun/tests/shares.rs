@@ -0,0 +1,326 @@
+mod common;
+
+use betfun::error::BetFunError;
+use betfun::state::{Arena, CurveType, OutcomeShare, PriceHistory, ShareBalance};
+use betfun_math::curve::Curve;
+use common::*;
+
+fn outcome_share(svm: &betfun_testkit::Svm, arena: &anchor_lang::prelude::Pubkey, outcome_index: u8) -> OutcomeShare {
+    fetch(svm, &outcome_share_pda(arena, outcome_index))
+}
+
+// ========== CREATE SHARE TOKENS ==========
+
+#[test]
+fn create_share_tokens_initializes_market() {
+    let mut svm = setup();
+    let (_, arena) = new_share_market(&mut svm);
+
+    let share = outcome_share(&svm, &arena, 1);
+    assert_eq!(share.arena, arena);
+    assert_eq!(share.outcome_index, 1);
+    assert_eq!(share.token_mint, share_mint_pda(&arena, 1));
+    assert_eq!(share.total_supply, 0);
+    assert_eq!(share.current_price, BASE_PRICE);
+    assert_eq!(share.curve.curve_type, CurveType::Linear);
+    assert_eq!(share.curve.param, SLOPE);
+
+    let history: PriceHistory = fetch(&svm, &price_history_pda(&arena, 1));
+    assert_eq!(history.arena, arena);
+    assert!(history.latest().is_none());
+}
+
+#[test]
+fn create_share_tokens_rejects_bad_parameters() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+
+    let result = svm.send_instruction(
+        create_share_tokens_ix(&arena, &creator, 2, BASE_PRICE, CurveType::Linear, SLOPE),
+        &[creator],
+    );
+    assert_error(result, BetFunError::InvalidOutcome);
+
+    let result = svm.send_instruction(
+        create_share_tokens_ix(&arena, &creator, 0, 9_999_999, CurveType::Linear, SLOPE),
+        &[creator],
+    );
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let result = svm.send_instruction(
+        create_share_tokens_ix(&arena, &creator, 0, BASE_PRICE, CurveType::Exponential, 0),
+        &[creator],
+    );
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let stranger = wallet(&mut svm);
+    let result = svm.send_instruction(
+        create_share_tokens_ix(&arena, &stranger, 0, BASE_PRICE, CurveType::Linear, SLOPE),
+        &[stranger],
+    );
+    assert_error(result, BetFunError::UnauthorizedResolver);
+}
+
+// ========== BUY SHARES ==========
+
+#[test]
+fn buy_shares_charges_curve_cost() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let buyer = wallet(&mut svm);
+
+    let curve = outcome_share(&svm, &arena, 0).curve;
+    let cost = curve.buy_cost(0, 2 * SHARE).unwrap();
+    // 0.1 SOL * 2 + 0.01 SOL * 2^2 / 2
+    assert_eq!(cost, 220_000_000);
+
+    let arena_before = svm.lamports(&arena);
+    assert_ok(svm.send_instruction(buy_shares_ix(&arena, &buyer, &creator, 0, 2 * SHARE, Some(cost), None), &[buyer]));
+
+    assert_eq!(svm.lamports(&arena), arena_before + cost);
+    assert_eq!(token_balance(&svm, &share_ata(&buyer, &arena, 0)), 2 * SHARE);
+
+    let share = outcome_share(&svm, &arena, 0);
+    assert_eq!(share.total_supply, 2 * SHARE);
+    assert_eq!(share.current_price, curve.price_at(2 * SHARE).unwrap());
+
+    let balance: ShareBalance = fetch(&svm, &share_balance_pda(&outcome_share_pda(&arena, 0), &buyer));
+    assert_eq!(balance.owner, buyer);
+    assert_eq!(balance.balance, 2 * SHARE);
+
+    let history: PriceHistory = fetch(&svm, &price_history_pda(&arena, 0));
+    assert_eq!(history.latest().unwrap().volume, cost);
+
+    // The next buyer pays the higher price further along the curve
+    let next_cost = curve.buy_cost(2 * SHARE, 2 * SHARE).unwrap();
+    assert!(next_cost > cost);
+    let result = svm.send_instruction(
+        buy_shares_ix(&arena, &buyer, &creator, 0, 2 * SHARE, Some(cost), None),
+        &[buyer],
+    );
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+    assert_ok(svm.send_instruction(
+        buy_shares_ix(&arena, &buyer, &creator, 0, 2 * SHARE, Some(next_cost), None),
+        &[buyer],
+    ));
+}
+
+#[test]
+fn buy_shares_rejects_invalid_requests() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let buyer = wallet(&mut svm);
+
+    let result = svm.send_instruction(buy_shares_ix(&arena, &buyer, &creator, 0, 0, None, None), &[buyer]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    // A single base unit rounds down to a zero cost
+    let result = svm.send_instruction(buy_shares_ix(&arena, &buyer, &creator, 0, 1, None, None), &[buyer]);
+    assert_error(result, BetFunError::InvalidAmount);
+
+    let deadline = now(&svm) - 1;
+    let buy = buy_shares_ix(&arena, &buyer, &creator, 0, SHARE, None, Some(deadline));
+    let result = svm.send_instruction(buy, &[buyer]);
+    assert_error(result, BetFunError::DeadlineExceeded);
+
+    svm.advance_clock(DAY);
+    let result = svm.send_instruction(buy_shares_ix(&arena, &buyer, &creator, 0, SHARE, None, None), &[buyer]);
+    assert_error(result, BetFunError::ArenaEnded);
+}
+
+#[test]
+fn buy_shares_rejects_overflowing_curve() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    // Price doubles every base unit, so a whole share is far beyond u64
+    assert_ok(svm.send_instruction(
+        create_share_tokens_ix(&arena, &creator, 0, BASE_PRICE, CurveType::Exponential, 1),
+        &[creator],
+    ));
+
+    let buyer = wallet(&mut svm);
+    let result = svm.send_instruction(buy_shares_ix(&arena, &buyer, &creator, 0, SHARE, None, None), &[buyer]);
+    assert_error(result, BetFunError::ArithmeticOverflow);
+}
+
+// ========== SELL SHARES ==========
+
+#[test]
+fn sell_shares_round_trips_curve_cost() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let seller = wallet(&mut svm);
+    let curve = outcome_share(&svm, &arena, 0).curve;
+    let cost = curve.buy_cost(0, 3 * SHARE).unwrap();
+    assert_ok(svm.send_instruction(
+        buy_shares_ix(&arena, &seller, &creator, 0, 3 * SHARE, Some(cost), None),
+        &[seller],
+    ));
+
+    let proceeds = curve.sell_proceeds(3 * SHARE, 2 * SHARE).unwrap();
+    let result = svm.send_instruction(
+        sell_shares_ix(&arena, &seller, 0, 2 * SHARE, Some(proceeds + 1), None),
+        &[seller],
+    );
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    let seller_before = svm.lamports(&seller);
+    assert_ok(svm.send_instruction(sell_shares_ix(&arena, &seller, 0, 2 * SHARE, Some(proceeds), None), &[seller]));
+    assert_eq!(svm.lamports(&seller), seller_before + proceeds);
+    assert_eq!(token_balance(&svm, &share_ata(&seller, &arena, 0)), SHARE);
+
+    // Selling the rest returns exactly what was paid in
+    let rest = curve.sell_proceeds(SHARE, SHARE).unwrap();
+    assert_eq!(proceeds + rest, cost);
+    assert_ok(svm.send_instruction(sell_shares_ix(&arena, &seller, 0, SHARE, Some(rest), None), &[seller]));
+    assert_eq!(outcome_share(&svm, &arena, 0).total_supply, 0);
+}
+
+#[test]
+fn sell_shares_rejects_invalid_requests() {
+    let mut svm = setup();
+    let (_, arena) = new_share_market(&mut svm);
+    let seller = shareholder(&mut svm, &arena, 0, SHARE);
+
+    let result = svm.send_instruction(sell_shares_ix(&arena, &seller, 0, 0, None, None), &[seller]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+
+    let result = svm.send_instruction(sell_shares_ix(&arena, &seller, 0, 2 * SHARE, None, None), &[seller]);
+    assert_error(result, BetFunError::InsufficientEntryFee);
+
+    let deadline = now(&svm) - 1;
+    let result = svm.send_instruction(sell_shares_ix(&arena, &seller, 0, SHARE, None, Some(deadline)), &[seller]);
+    assert_error(result, BetFunError::DeadlineExceeded);
+}
+
+#[test]
+fn sell_shares_rejects_resolved_arena() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let seller = shareholder(&mut svm, &arena, 0, SHARE);
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let result = svm.send_instruction(sell_shares_ix(&arena, &seller, 0, SHARE, None, None), &[seller]);
+    assert_error(result, BetFunError::AlreadyResolved);
+}
+
+#[test]
+fn sell_shares_rejects_locked_arena() {
+    let mut svm = setup();
+    let (_, arena) = new_share_market(&mut svm);
+    let seller = shareholder(&mut svm, &arena, 0, SHARE);
+    svm.advance_clock(DAY);
+
+    let result = svm.send_instruction(sell_shares_ix(&arena, &seller, 0, SHARE, None, None), &[seller]);
+    assert_error(result, BetFunError::ArenaEnded);
+}
+
+// ========== REDEEM SHARES ==========
+
+#[test]
+fn redeem_shares_splits_the_reserve_pro_rata() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let winner = shareholder(&mut svm, &arena, 0, 2 * SHARE);
+    let loser = shareholder(&mut svm, &arena, 1, SHARE);
+
+    let result = svm.send_instruction(redeem_shares_ix(&arena, &winner, 0, SHARE / 10, None, None), &[winner]);
+    assert_error(result, BetFunError::NotResolved);
+
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    // The losing side's purchases back the winning shares too
+    let reserve = share_reserve(&svm, &arena);
+    let curve_reserve = |outcome_index, supply| {
+        Curve::from(outcome_share(&svm, &arena, outcome_index).curve).reserve_at(supply)
+    };
+    assert_eq!(Some(reserve), curve_reserve(0, 2 * SHARE).zip(curve_reserve(1, SHARE)).map(|(a, b)| a + b));
+    let amount = SHARE / 10;
+    let value = reserve / 20;
+    let result = svm.send_instruction(redeem_shares_ix(&arena, &winner, 0, amount, Some(value + 1), None), &[winner]);
+    assert_error(result, BetFunError::SlippageToleranceExceeded);
+
+    let winner_before = svm.lamports(&winner);
+    assert_ok(svm.send_instruction(redeem_shares_ix(&arena, &winner, 0, amount, Some(value), None), &[winner]));
+    assert_eq!(svm.lamports(&winner), winner_before + value);
+    assert_eq!(token_balance(&svm, &share_ata(&winner, &arena, 0)), 2 * SHARE - amount);
+
+    let result = svm.send_instruction(redeem_shares_ix(&arena, &loser, 1, amount, None, None), &[loser]);
+    assert_error(result, BetFunError::NotWinner);
+
+    let deadline = now(&svm) - 1;
+    let result = svm.send_instruction(redeem_shares_ix(&arena, &winner, 0, amount, None, Some(deadline)), &[winner]);
+    assert_error(result, BetFunError::DeadlineExceeded);
+
+    // Nobody redeems more than they hold
+    let result = svm.send_instruction(redeem_shares_ix(&arena, &winner, 0, 2 * SHARE, None, None), &[winner]);
+    assert_error(result, BetFunError::InsufficientEntryFee);
+}
+
+#[test]
+fn every_winning_share_redeems() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    // Enough volume to push the curve well past 1 SOL per share
+    let holders: Vec<_> = [40 * SHARE, 7 * SHARE, SHARE / 3, 95 * SHARE]
+        .into_iter()
+        .map(|amount| (shareholder(&mut svm, &arena, 0, amount), amount))
+        .collect();
+    let seller = holders[0].0;
+    assert_ok(svm.send_instruction(sell_shares_ix(&arena, &seller, 0, 15 * SHARE, None, None), &[seller]));
+    shareholder(&mut svm, &arena, 1, 30 * SHARE);
+    assert!(outcome_share(&svm, &arena, 0).current_price > SOL);
+    let bettor = join(&mut svm, &arena, 0);
+    join(&mut svm, &arena, 1);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let reserve = share_reserve(&svm, &arena);
+    let mut paid = 0;
+    for (index, (holder, _)) in holders.iter().enumerate() {
+        let amount = token_balance(&svm, &share_ata(holder, &arena, 0));
+        let before = svm.lamports(holder);
+        assert_ok(svm.send_instruction(redeem_shares_ix(&arena, holder, 0, amount, None, None), &[*holder]));
+        paid += svm.lamports(holder) - before;
+        // Winning bettors claim in between without touching the reserve
+        if index == 1 {
+            assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &bettor, &creator), &[bettor]));
+        }
+    }
+    assert_eq!(paid, reserve);
+    assert_eq!(outcome_share(&svm, &arena, 0).total_supply, 0);
+    assert_eq!(share_reserve(&svm, &arena), 0);
+}
+
+#[test]
+fn voided_arenas_refund_every_outcome_along_the_curve() {
+    let mut svm = setup();
+    let (_, arena) = new_share_market(&mut svm);
+    let early = shareholder(&mut svm, &arena, 0, 3 * SHARE);
+    let late = shareholder(&mut svm, &arena, 0, 2 * SHARE);
+    let other = shareholder(&mut svm, &arena, 1, SHARE);
+    let caller = wallet(&mut svm);
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &caller), &[caller]));
+
+    // Redeeming refunds what selling back down the curve pays, in any order
+    let reserve = share_reserve(&svm, &arena);
+    let mut refunded = 0;
+    for (holder, outcome_index, amount) in [(early, 0, 3 * SHARE), (other, 1, SHARE), (late, 0, 2 * SHARE)] {
+        let share = outcome_share(&svm, &arena, outcome_index);
+        let expected = Curve::from(share.curve).sell_proceeds(share.total_supply, amount).unwrap();
+        let before = svm.lamports(&holder);
+        assert_ok(svm.send_instruction(
+            redeem_shares_ix(&arena, &holder, outcome_index, amount, None, None),
+            &[holder],
+        ));
+        assert_eq!(svm.lamports(&holder), before + expected);
+        refunded += expected;
+    }
+    assert_eq!(refunded, reserve);
+    assert_eq!(fetch::<Arena>(&svm, &arena).shares_outstanding, 0);
+}
@@ -0,0 +1,133 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { PublicKey, LAMPORTS_PER_SOL } from "@solana/web3.js";
+import { assert } from "chai";
+import { Betfun } from "../target/types/betfun";
+
+describe("buy_shares, sell_shares and redeem_shares slippage bounds", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Betfun as Program<Betfun>;
+  const creator = provider.wallet;
+  const share = new anchor.BN(LAMPORTS_PER_SOL); // one whole share in base units
+
+  let arenaPda: PublicKey;
+
+  before(async () => {
+    // The config is program-wide, so another suite may have created it already
+    const [configPda] = PublicKey.findProgramAddressSync([Buffer.from("config")], program.programId);
+    if (!(await provider.connection.getAccountInfo(configPda))) {
+      // `anchor test` deploys with the provider wallet as upgrade authority
+      const [programData] = PublicKey.findProgramAddressSync(
+        [program.programId.toBuffer()],
+        new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+      );
+      await program.methods
+        .initializeConfig()
+        .accountsPartial({ admin: creator.publicKey, programData })
+        .rpc();
+    }
+
+    const title = `Shares ${Date.now() % 1_000_000}`;
+    [arenaPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("arena"), creator.publicKey.toBuffer(), Buffer.from(title)],
+      program.programId
+    );
+
+    const now = Math.floor(Date.now() / 1000);
+    await program.methods
+      .createArena(
+        title,
+        "Description",
+        "Will this test pass?",
+        ["Yes", "No"],
+        [],
+        new anchor.BN(100_000_000),
+        new anchor.BN(now + 86400),
+        new anchor.BN(now + 86400),
+        true,
+        null, // no oracle
+        null, // SOL entry fees
+        0,
+        0,
+        null, // categorical
+        null, // public
+        { flat: {} },
+        null // not a duel, so no challenger stake
+      )
+      .accounts({
+        creator: creator.publicKey,
+        participant: null,
+        userProfile: null,
+        bondMint: null, // SOL bond, if any
+        creatorBondAccount: null,
+        bondVault: null,
+        tokenProgram: null,
+      })
+      .rpc();
+
+    await program.methods
+      .createShareTokens(0, new anchor.BN(100_000_000), { linear: {} }, new anchor.BN(10_000_000))
+      .accounts({ arena: arenaPda, creator: creator.publicKey })
+      .rpc();
+  });
+
+  const buy = (maxTotalCost: anchor.BN | null) =>
+    program.methods
+      .buyShares(0, share, maxTotalCost, null, [])
+      .accountsPartial({
+        arena: arenaPda,
+        buyer: creator.publicKey,
+        arenaEscrow: arenaPda,
+        creator: creator.publicKey,
+        referrer: null,
+        referral: null,
+      })
+      .rpc();
+
+  const sell = (minTotalProceeds: anchor.BN | null) =>
+    program.methods
+      .sellShares(0, share, minTotalProceeds, null)
+      .accountsPartial({ arena: arenaPda, seller: creator.publicKey, arenaEscrow: arenaPda })
+      .rpc();
+
+  it("Buys with no cost bound", async () => {
+    await buy(null);
+    await buy(null);
+  });
+
+  it("Fails to buy above max_total_cost", async () => {
+    try {
+      await buy(new anchor.BN(1));
+      assert.fail("Should have failed with the cost bound exceeded");
+    } catch (err) {
+      assert.include(err.toString(), "SlippageToleranceExceeded");
+    }
+  });
+
+  it("Fails to sell below min_total_proceeds", async () => {
+    try {
+      await sell(new anchor.BN(LAMPORTS_PER_SOL * 1_000));
+      assert.fail("Should have failed with the proceeds bound missed");
+    } catch (err) {
+      assert.include(err.toString(), "SlippageToleranceExceeded");
+    }
+  });
+
+  it("Sells with no proceeds bound", async () => {
+    await sell(null);
+  });
+
+  it("Takes no proceeds bound on redemption", async () => {
+    try {
+      await program.methods
+        .redeemShares(0, share, null, null)
+        .accountsPartial({ arena: arenaPda, redeemer: creator.publicKey, arenaEscrow: arenaPda })
+        .rpc();
+      assert.fail("Should have failed before resolution");
+    } catch (err) {
+      assert.include(err.toString(), "NotResolved");
+    }
+  });
+});
@@ -0,0 +1,180 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use betfun::error::BetFunError;
+use betfun::state::{Arena, Tournament, TournamentEntry};
+use betfun_testkit::Svm;
+use common::*;
+
+const ENTRANTS: [&str; 4] = ["Ada", "Bo", "Cy", "Di"];
+
+/// Four-entrant tournament with both semi-finals open; returns `(organizer, tournament)`
+fn new_tournament(svm: &mut Svm) -> (Pubkey, Pubkey) {
+    let organizer = wallet(svm);
+    let lock_time = now(svm) + DAY;
+    assert_ok(svm.send_instruction(create_tournament_ix(&organizer, "Spring Cup", &ENTRANTS, lock_time), &[organizer]));
+    let tournament = tournament_pda(&organizer, "Spring Cup");
+    for slot in [1, 2] {
+        assert_ok(svm.send_instruction(advance_tournament_ix(&tournament, &organizer, slot), &[organizer]));
+    }
+    (organizer, tournament)
+}
+
+/// Fresh wallet entered into the tournament
+fn entrant(svm: &mut Svm, tournament: &Pubkey) -> Pubkey {
+    let user = wallet(svm);
+    assert_ok(svm.send_instruction(enter_tournament_ix(tournament, &user), &[user]));
+    user
+}
+
+/// Bet on `outcome` in the match of `slot`; returns the participant account
+fn pick(svm: &mut Svm, tournament: &Pubkey, user: &Pubkey, slot: u8, outcome: u8) -> Pubkey {
+    let arena = tournament_match_pda(tournament, slot);
+    assert_ok(svm.send_instruction(join_arena_ix(&arena, user, outcome), &[*user]));
+    participant_pda(&arena, user)
+}
+
+fn decide(svm: &mut Svm, (organizer, tournament): (Pubkey, Pubkey), slot: u8, winner: u8) {
+    assert_ok(svm.send_instruction(resolve_tournament_match_ix(&tournament, &organizer, slot, winner), &[organizer]));
+}
+
+#[test]
+fn best_picks_take_the_pot() {
+    let mut svm = setup();
+    let (organizer, tournament) = new_tournament(&mut svm);
+    let state: Arena = fetch(&svm, &tournament_match_pda(&tournament, 1));
+    assert_eq!((state.creator, state.outcomes.clone()), (tournament, vec!["Ada".to_string(), "Bo".to_string()]));
+    let result = svm.send_instruction(advance_tournament_ix(&tournament, &organizer, 0), &[organizer]);
+    assert_error(result, BetFunError::BracketNotReady);
+
+    let alice = entrant(&mut svm, &tournament);
+    let bob = entrant(&mut svm, &tournament);
+    // Carol enters but misses the scoring window
+    let carol = entrant(&mut svm, &tournament);
+    let mut alice_picks = vec![pick(&mut svm, &tournament, &alice, 1, 0), pick(&mut svm, &tournament, &alice, 2, 0)];
+    let mut bob_picks = vec![pick(&mut svm, &tournament, &bob, 1, 0), pick(&mut svm, &tournament, &bob, 2, 1)];
+
+    // Only the organizer decides matches, and not before they lock
+    let result = svm.send_instruction(resolve_tournament_match_ix(&tournament, &alice, 1, 0), &[alice]);
+    assert_error(result, BetFunError::UnauthorizedResolver);
+    let result = svm.send_instruction(resolve_tournament_match_ix(&tournament, &organizer, 1, 0), &[organizer]);
+    assert_error(result, BetFunError::ArenaNotEnded);
+    svm.advance_clock(DAY);
+    let semi_final = tournament_match_pda(&tournament, 1);
+    let result = svm.send_instruction(resolve_arena_ix(&semi_final, &organizer, 0), &[organizer]);
+    assert_error(result, BetFunError::UnauthorizedResolver);
+    decide(&mut svm, (organizer, tournament), 1, 0);
+    decide(&mut svm, (organizer, tournament), 2, 0);
+
+    // The final pits the semi-final winners against each other
+    assert_ok(svm.send_instruction(advance_tournament_ix(&tournament, &organizer, 0), &[organizer]));
+    let final_match: Arena = fetch(&svm, &tournament_match_pda(&tournament, 0));
+    assert_eq!(final_match.outcomes, vec!["Ada".to_string(), "Cy".to_string()]);
+    assert_eq!(final_match.lock_time, now(&svm) + DAY);
+    alice_picks.push(pick(&mut svm, &tournament, &alice, 0, 1));
+    bob_picks.push(pick(&mut svm, &tournament, &bob, 0, 0));
+
+    let result = svm.send_instruction(submit_tournament_score_ix(&tournament, &alice, &alice_picks), &[alice]);
+    assert_error(result, BetFunError::BracketNotReady);
+    svm.advance_clock(DAY);
+    decide(&mut svm, (organizer, tournament), 0, 1);
+    assert!(fetch::<Tournament>(&svm, &tournament).is_decided());
+
+    // Each match counts once, and only the entrant's own picks
+    let doubled = [alice_picks[0], alice_picks[0]];
+    let result = svm.send_instruction(submit_tournament_score_ix(&tournament, &alice, &doubled), &[alice]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+    let result = svm.send_instruction(submit_tournament_score_ix(&tournament, &alice, &bob_picks), &[alice]);
+    assert_error(result, BetFunError::NotParticipant);
+    assert_ok(svm.send_instruction(submit_tournament_score_ix(&tournament, &alice, &alice_picks), &[alice]));
+    assert_ok(svm.send_instruction(submit_tournament_score_ix(&tournament, &bob, &bob_picks), &[bob]));
+    assert_eq!(fetch::<TournamentEntry>(&svm, &tournament_entry_pda(&tournament, &alice)).score, 3);
+    assert_eq!(fetch::<TournamentEntry>(&svm, &tournament_entry_pda(&tournament, &bob)).score, 1);
+
+    let result = svm.send_instruction(claim_tournament_prize_ix(&tournament, &alice), &[alice]);
+    assert_error(result, BetFunError::ScoringOpen);
+    svm.advance_clock(Tournament::SCORING_WINDOW);
+    let result = svm.send_instruction(submit_tournament_score_ix(&tournament, &carol, &[]), &[carol]);
+    assert_error(result, BetFunError::ScoringClosed);
+    let late = wallet(&mut svm);
+    let result = svm.send_instruction(enter_tournament_ix(&tournament, &late), &[late]);
+    assert_error(result, BetFunError::ArenaEnded);
+
+    let before = svm.lamports(&alice);
+    assert_ok(svm.send_instruction(claim_tournament_prize_ix(&tournament, &alice), &[alice]));
+    // Alice takes all three entry fees, Carol's included
+    assert_eq!(svm.lamports(&alice), before + 3 * ENTRY_FEE);
+    let result = svm.send_instruction(claim_tournament_prize_ix(&tournament, &bob), &[bob]);
+    assert_error(result, BetFunError::NotWinner);
+
+    // Match arenas pay out like any other
+    let arena = tournament_match_pda(&tournament, 0);
+    let before = svm.lamports(&alice);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &alice, &tournament), &[alice]));
+    assert_eq!(svm.lamports(&alice), before + 2 * ENTRY_FEE);
+}
+
+#[test]
+fn unbacked_winner_voids_only_the_match() {
+    let mut svm = setup();
+    let (organizer, tournament) = new_tournament(&mut svm);
+    let bettor = wallet(&mut svm);
+    let arena = tournament_match_pda(&tournament, 1);
+    assert_ok(svm.send_instruction(join_arena_ix(&arena, &bettor, 1), &[bettor]));
+
+    // Nobody backed Ada, so Bo's backer gets their stake back
+    svm.advance_clock(DAY);
+    decide(&mut svm, (organizer, tournament), 1, 0);
+    assert!(fetch::<Arena>(&svm, &arena).voided);
+    let before = svm.lamports(&bettor);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &bettor, &tournament), &[bettor]));
+    assert_eq!(svm.lamports(&bettor), before + ENTRY_FEE);
+
+    // The result stands and the bracket moves on
+    let result = svm.send_instruction(cancel_tournament_ix(&tournament, &bettor, 1), &[bettor]);
+    assert_error(result, BetFunError::ResolutionWindowOpen);
+    decide(&mut svm, (organizer, tournament), 2, 1);
+    assert_ok(svm.send_instruction(advance_tournament_ix(&tournament, &bettor, 0), &[bettor]));
+    let final_match: Arena = fetch(&svm, &tournament_match_pda(&tournament, 0));
+    assert_eq!(final_match.outcomes, vec!["Ada".to_string(), "Di".to_string()]);
+}
+
+#[test]
+fn voided_match_cancels_and_refunds_entries() {
+    let mut svm = setup();
+    let (organizer, tournament) = new_tournament(&mut svm);
+    let alice = entrant(&mut svm, &tournament);
+    pick(&mut svm, &tournament, &alice, 1, 0);
+
+    // The organizer never decides the match, so anyone voids it and then the tournament
+    svm.advance_clock(DAY + Arena::RESOLUTION_WINDOW);
+    let arena = tournament_match_pda(&tournament, 1);
+    let result = svm.send_instruction(cancel_tournament_ix(&tournament, &alice, 1), &[alice]);
+    assert_error(result, BetFunError::ResolutionWindowOpen);
+    assert_ok(svm.send_instruction(void_arena_ix(&arena, &alice), &[alice]));
+    let result = svm.send_instruction(resolve_tournament_match_ix(&tournament, &organizer, 1, 0), &[organizer]);
+    assert_error(result, BetFunError::ArenaVoided);
+    assert_ok(svm.send_instruction(cancel_tournament_ix(&tournament, &alice, 1), &[alice]));
+    let result = svm.send_instruction(resolve_tournament_match_ix(&tournament, &organizer, 2, 0), &[organizer]);
+    assert_error(result, BetFunError::TournamentCancelled);
+
+    let before = svm.lamports(&alice);
+    assert_ok(svm.send_instruction(claim_tournament_prize_ix(&tournament, &alice), &[alice]));
+    assert_eq!(svm.lamports(&alice), before + ENTRY_FEE);
+    let result = svm.send_instruction(claim_tournament_prize_ix(&tournament, &alice), &[alice]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+}
+
+#[test]
+fn create_tournament_rejects_bad_brackets() {
+    let mut svm = setup();
+    let organizer = wallet(&mut svm);
+    let lock_time = now(&svm) + DAY;
+    let cases: [&[&str]; 3] = [&["Ada", "Bo"], &["Ada", "Bo", "Cy", "Di", "Ed", "Flo"], &["Ada", "Bo", "Cy", "ada"]];
+    for entrants in cases {
+        let ix = create_tournament_ix(&organizer, "Bad Cup", entrants, lock_time);
+        assert_error(svm.send_instruction(ix, &[organizer]), BetFunError::InvalidConfiguration);
+    }
+    let ix = create_tournament_ix(&organizer, "Bad Cup", &ENTRANTS, lock_time - DAY);
+    assert_error(svm.send_instruction(ix, &[organizer]), BetFunError::InvalidEndTime);
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::system_program;
+use betfun::error::BetFunError;
+use betfun::state::{OutcomeShare, Participant, ShareBalance, UserProfile, Versioned};
+use betfun_testkit::{StoredAccount, Svm};
+use common::*;
+
+fn profile(svm: &Svm, user: &Pubkey) -> UserProfile {
+    fetch(svm, &user_profile_pda(user))
+}
+
+fn share_balance(svm: &Svm, arena: &Pubkey, outcome_index: u8, owner: &Pubkey) -> ShareBalance {
+    fetch(svm, &share_balance_pda(&outcome_share_pda(arena, outcome_index), owner))
+}
+
+// ========== ARENAS ==========
+
+#[test]
+fn join_creates_profile_and_counts_stake() {
+    let mut svm = setup();
+    let (_, arena) = new_arena(&mut svm);
+    let mut config = ArenaConfig::new(&svm);
+    config.title = "Bronze Match".to_string();
+    let (_, other) = new_arena_with(&mut svm, &config);
+    let joined_at = now(&svm);
+    let user = join(&mut svm, &arena, 0);
+
+    let state = profile(&svm, &user);
+    assert_eq!(state.user, user);
+    assert_eq!(state.arenas_joined, 1);
+    assert_eq!(state.total_staked, ENTRY_FEE);
+    assert_eq!(state.created_at, joined_at);
+    assert_eq!(state.version, UserProfile::VERSION);
+
+    // Later joins reuse the profile
+    svm.advance_clock(60);
+    assert_ok(svm.send_instruction(join_arena_ix(&other, &user, 1), &[user]));
+    let state = profile(&svm, &user);
+    assert_eq!(state.arenas_joined, 2);
+    assert_eq!(state.total_staked, 2 * ENTRY_FEE);
+    assert_eq!(state.created_at, joined_at);
+    assert_eq!(state.last_active_at, joined_at + 60);
+}
+
+#[test]
+fn claims_and_trophies_count_wins() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    let loser = join(&mut svm, &arena, 1);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let before = svm.lamports(&winner);
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &winner, &creator), &[winner]));
+    let payout = svm.lamports(&winner) - before;
+
+    let state = profile(&svm, &winner);
+    assert_eq!(state.arenas_won, 1);
+    assert_eq!(state.total_won, payout);
+    assert_eq!(state.realized_pnl, payout as i64 - ENTRY_FEE as i64);
+    assert_eq!(state.trophies, 0);
+
+    assert_ok(svm.send_instruction(mint_trophy_ix(&arena, &winner), &[winner]));
+    assert_eq!(profile(&svm, &winner).trophies, 1);
+
+    // Losers keep their stake on record and nothing else
+    let state = profile(&svm, &loser);
+    assert_eq!((state.arenas_joined, state.arenas_won, state.total_won), (1, 0, 0));
+}
+
+#[test]
+fn claims_create_profiles_for_older_bets() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    join(&mut svm, &arena, 1);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    // As if the bet was placed before profiles existed
+    let empty = StoredAccount { lamports: 0, data: vec![], owner: system_program::ID, executable: false };
+    svm.set_account(user_profile_pda(&winner), empty);
+
+    assert_ok(svm.send_instruction(claim_winnings_ix(&arena, &winner, &creator), &[winner]));
+    let state = profile(&svm, &winner);
+    assert_eq!(state.user, winner);
+    assert_eq!((state.arenas_joined, state.arenas_won), (0, 1));
+    assert_ok(svm.send_instruction(mint_trophy_ix(&arena, &winner), &[winner]));
+    assert_eq!(profile(&svm, &winner).trophies, 1);
+}
+
+#[test]
+fn settling_books_a_losing_bet_once() {
+    let mut svm = setup();
+    let (creator, arena) = new_arena(&mut svm);
+    let winner = join(&mut svm, &arena, 0);
+    let loser = join(&mut svm, &arena, 1);
+    let caller = wallet(&mut svm);
+
+    let result = svm.send_instruction(settle_profile_ix(&arena, &loser, &caller, true, None), &[caller]);
+    assert_error(result, BetFunError::NotResolved);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    // Anyone can book the loss, and only once
+    assert_ok(svm.send_instruction(settle_profile_ix(&arena, &loser, &caller, true, None), &[caller]));
+    assert_eq!(profile(&svm, &loser).realized_pnl, -(ENTRY_FEE as i64));
+    assert!(fetch::<Participant>(&svm, &participant_pda(&arena, &loser)).claimed);
+    let result = svm.send_instruction(settle_profile_ix(&arena, &loser, &caller, true, None), &[caller]);
+    assert_error(result, BetFunError::AlreadyClaimed);
+
+    // Winners are booked by claiming
+    let result = svm.send_instruction(settle_profile_ix(&arena, &winner, &caller, true, None), &[caller]);
+    assert_error(result, BetFunError::PositionNotLost);
+    let result = svm.send_instruction(settle_profile_ix(&arena, &winner, &caller, false, None), &[caller]);
+    assert_error(result, BetFunError::InvalidConfiguration);
+}
+
+// ========== SHARES ==========
+
+#[test]
+fn share_trades_track_stake_and_pnl() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let trader = wallet(&mut svm);
+    let cost = fetch::<OutcomeShare>(&svm, &outcome_share_pda(&arena, 0)).curve.buy_cost(0, 4 * SHARE).unwrap();
+    assert_ok(svm.send_instruction(buy_shares_ix(&arena, &trader, &creator, 0, 4 * SHARE, None, None), &[trader]));
+
+    let state = profile(&svm, &trader);
+    assert_eq!(state.arenas_joined, 0);
+    assert_eq!(state.total_staked, cost);
+    assert_eq!(state.realized_pnl, 0);
+
+    // Later buyers raise the price, so the sale realizes a profit
+    shareholder(&mut svm, &arena, 0, 4 * SHARE);
+    assert_ok(svm.send_instruction(sell_shares_ix(&arena, &trader, 0, SHARE, None, None), &[trader]));
+    let pnl = share_balance(&svm, &arena, 0, &trader).realized_pnl;
+    assert!(pnl > 0);
+    assert_eq!(profile(&svm, &trader).realized_pnl, pnl);
+
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+    let before = svm.lamports(&trader);
+    assert_ok(svm.send_instruction(redeem_shares_ix(&arena, &trader, 0, SHARE / 10, None, None), &[trader]));
+
+    let state = profile(&svm, &trader);
+    assert_eq!(state.total_won, svm.lamports(&trader) - before);
+    assert_eq!(state.realized_pnl, share_balance(&svm, &arena, 0, &trader).realized_pnl);
+    assert_eq!(state.arenas_won, 0);
+}
+
+#[test]
+fn settling_books_losing_shares_at_cost() {
+    let mut svm = setup();
+    let (creator, arena) = new_share_market(&mut svm);
+    let trader = shareholder(&mut svm, &arena, 1, 2 * SHARE);
+    assert_ok(svm.send_instruction(buy_shares_ix(&arena, &trader, &creator, 0, SHARE, None, None), &[trader]));
+    join(&mut svm, &arena, 0);
+    svm.advance_clock(DAY);
+    assert_ok(svm.send_instruction(resolve_arena_ix(&arena, &creator, 0), &[creator]));
+
+    let result = svm.send_instruction(settle_profile_ix(&arena, &trader, &trader, false, Some(0)), &[trader]);
+    assert_error(result, BetFunError::PositionNotLost);
+
+    let losing = share_balance(&svm, &arena, 1, &trader);
+    let cost = (losing.balance as u128 * losing.avg_cost_basis as u128 / SHARE as u128) as i64;
+    assert_ok(svm.send_instruction(settle_profile_ix(&arena, &trader, &trader, false, Some(1)), &[trader]));
+    let losing = share_balance(&svm, &arena, 1, &trader);
+    assert_eq!((losing.balance, losing.realized_pnl), (0, -cost));
+    assert_eq!(profile(&svm, &trader).realized_pnl, -cost);
+    let result = svm.send_instruction(settle_profile_ix(&arena, &trader, &trader, false, Some(1)), &[trader]);
+    assert_error(result, BetFunError::InvalidAmount);
+}